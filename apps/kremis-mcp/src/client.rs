@@ -102,18 +102,9 @@ impl KremisClient {
         self.handle_response(resp).await
     }
 
-    /// POST /signal → ingest a signal.
-    pub async fn ingest(
-        &self,
-        entity_id: u64,
-        attribute: &str,
-        value: &str,
-    ) -> Result<Value, ClientError> {
-        let body = serde_json::json!({
-            "entity_id": entity_id,
-            "attribute": attribute,
-            "value": value,
-        });
+    /// POST /signal → ingest a signal for an entity named by id or by key,
+    /// optionally linked from another entity (generic JSON body).
+    pub async fn ingest(&self, body: Value) -> Result<Value, ClientError> {
         let req = self.request(reqwest::Method::POST, "/signal").json(&body);
         let resp = self.send(req).await?;
         self.handle_response(resp).await
@@ -126,11 +117,36 @@ impl KremisClient {
         self.handle_response(resp).await
     }
 
+    /// POST /query/text → execute a query written in the textual query language.
+    pub async fn query_text(&self, query: &str) -> Result<Value, ClientError> {
+        let body = serde_json::json!({ "query": query });
+        let req = self
+            .request(reqwest::Method::POST, "/query/text")
+            .json(&body);
+        let resp = self.send(req).await?;
+        self.handle_response(resp).await
+    }
+
+    /// POST /query/pipeline → run chained query stages against one state.
+    pub async fn pipeline(&self, request: Value) -> Result<Value, ClientError> {
+        let req = self
+            .request(reqwest::Method::POST, "/query/pipeline")
+            .json(&request);
+        let resp = self.send(req).await?;
+        self.handle_response(resp).await
+    }
+
     /// POST /signal/retract → decrement edge weight between two entities.
-    pub async fn retract(&self, from_entity: u64, to_entity: u64) -> Result<Value, ClientError> {
+    pub async fn retract(
+        &self,
+        from_entity: u64,
+        to_entity: u64,
+        relation: Option<&str>,
+    ) -> Result<Value, ClientError> {
         let body = serde_json::json!({
             "from_entity": from_entity,
             "to_entity": to_entity,
+            "relation": relation,
         });
         let req = self
             .request(reqwest::Method::POST, "/signal/retract")
@@ -139,6 +155,35 @@ impl KremisClient {
         self.handle_response(resp).await
     }
 
+    /// POST /node/delete → remove an entity's node with its edges and properties.
+    pub async fn delete_node(&self, entity_id: u64) -> Result<Value, ClientError> {
+        let body = serde_json::json!({ "entity_id": entity_id });
+        let req = self
+            .request(reqwest::Method::POST, "/node/delete")
+            .json(&body);
+        let resp = self.send(req).await?;
+        self.handle_response(resp).await
+    }
+
+    /// POST /property/retract → remove one (attribute, value) pair from an entity.
+    pub async fn retract_property(
+        &self,
+        entity_id: u64,
+        attribute: &str,
+        value: &str,
+    ) -> Result<Value, ClientError> {
+        let body = serde_json::json!({
+            "entity_id": entity_id,
+            "attribute": attribute,
+            "value": value,
+        });
+        let req = self
+            .request(reqwest::Method::POST, "/property/retract")
+            .json(&body);
+        let resp = self.send(req).await?;
+        self.handle_response(resp).await
+    }
+
     /// GET /hash → canonical BLAKE3 hash of the graph.
     pub async fn hash(&self) -> Result<Value, ClientError> {
         let req = self.request(reqwest::Method::GET, "/hash");
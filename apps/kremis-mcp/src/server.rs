@@ -1,6 +1,6 @@
 //! # Kremis MCP Server
 //!
-//! Implements `ServerHandler` with 10 MCP tools that proxy to the Kremis HTTP API.
+//! Implements `ServerHandler` with 19 MCP tools that proxy to the Kremis HTTP API.
 
 use crate::client::KremisClient;
 use rmcp::{
@@ -9,7 +9,7 @@ use rmcp::{
     model::{CallToolResult, ContentBlock as Content, ServerCapabilities, ServerInfo},
     schemars, tool, tool_handler, tool_router,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 // =============================================================================
 // MCP SERVER
@@ -29,22 +29,66 @@ pub struct KremisMcp {
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct IngestParams {
-    /// The entity ID (numeric identifier).
-    #[schemars(description = "The entity ID (numeric identifier)")]
-    pub entity_id: u64,
+    /// The entity ID (numeric identifier). Give this or `entity_key`.
+    #[schemars(description = "The entity ID (numeric identifier). Give this or entity_key")]
+    pub entity_id: Option<u64>,
+    /// A string key naming the entity (e.g. "svc:ledger"), registered on first use.
+    #[schemars(
+        description = "A string key naming the entity (e.g. 'svc:ledger'), registered on first use. Give this or entity_id"
+    )]
+    pub entity_key: Option<String>,
     /// The attribute name (e.g. "name", "type", "connected_to").
     #[schemars(description = "The attribute name (e.g. 'name', 'type', 'connected_to')")]
     pub attribute: String,
     /// The value for this attribute.
     #[schemars(description = "The value for this attribute")]
     pub value: String,
+    /// Also link to this entity from the entity with this ID.
+    #[schemars(
+        description = "Also create an edge to this entity from the entity with this ID. Give this or link_from_entity_key, or neither"
+    )]
+    pub link_from_entity_id: Option<u64>,
+    /// Also link to this entity from the entity with this key.
+    #[schemars(
+        description = "Also create an edge to this entity from the entity with this key, registered on first use"
+    )]
+    pub link_from_entity_key: Option<String>,
+    /// Label of the link edge (e.g. "depends_on").
+    #[schemars(
+        description = "Relation label of the link edge (e.g. 'depends_on'); needs link_from_entity_id or link_from_entity_key"
+    )]
+    pub relation: Option<String>,
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct LookupParams {
-    /// The entity ID to look up.
-    #[schemars(description = "The entity ID to look up")]
-    pub entity_id: u64,
+    /// The entity ID to look up. Give this or `entity_key`.
+    #[schemars(description = "The entity ID to look up. Give this or entity_key")]
+    pub entity_id: Option<u64>,
+    /// The entity key to look up. Give this or `entity_id`.
+    #[schemars(description = "The entity key to look up. Give this or entity_id")]
+    pub entity_key: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SearchParams {
+    /// Free text matched against property values (case-insensitive, prefix match).
+    #[schemars(
+        description = "Free text matched against property values (case-insensitive, prefix match)"
+    )]
+    pub query: String,
+    /// Maximum number of results (default: 10, max: 1000).
+    #[schemars(description = "Maximum number of results (default: 10, max: 1000)")]
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct TextQueryParams {
+    /// Query in the Kremis textual query language.
+    #[schemars(
+        description = "Query in the Kremis query language, e.g. \"PATH 42 -> 87 MAXHOPS 5\", \"TRAVERSE 1 DEPTH 3 MINWEIGHT 2\", \"FIND role = engineer\""
+    )]
+    pub query: String,
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -58,6 +102,19 @@ pub struct TraverseParams {
     /// Return only the K highest-weight edges (optional).
     #[schemars(description = "Return only the K highest-weight edges (optional)")]
     pub top_k: Option<u64>,
+    /// Only follow edges with this relation label (optional).
+    #[schemars(description = "Only follow edges with this relation label (optional)")]
+    pub relation: Option<String>,
+    /// Nodes plus edges per page (optional).
+    #[schemars(
+        description = "Return the result in pages of this many nodes plus edges (optional, max 1000)"
+    )]
+    pub page_size: Option<usize>,
+    /// Cursor of the next page, from a previous call (optional).
+    #[schemars(
+        description = "Next-page cursor returned by a previous call with the same arguments (optional)"
+    )]
+    pub cursor: Option<String>,
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -68,6 +125,69 @@ pub struct PathParams {
     /// Ending node ID.
     #[schemars(description = "Ending node ID")]
     pub end: u64,
+    /// Only follow edges with this relation label (optional).
+    #[schemars(description = "Only follow edges with this relation label (optional)")]
+    pub relation: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct StrongestPathParams {
+    /// Starting node ID.
+    #[schemars(description = "Starting node ID")]
+    pub start: u64,
+    /// Ending node ID.
+    #[schemars(description = "Ending node ID")]
+    pub end: u64,
+    /// Only follow edges with this relation label (optional).
+    #[schemars(description = "Only follow edges with this relation label (optional)")]
+    pub relation: Option<String>,
+    /// Node IDs the path must not visit (optional).
+    #[schemars(description = "Node IDs the path must not visit (optional)")]
+    pub avoid: Option<Vec<u64>>,
+    /// Node IDs the path must visit, in order (optional).
+    #[schemars(description = "Node IDs the path must visit, in order (optional)")]
+    pub waypoints: Option<Vec<u64>>,
+    /// Maximum number of hops (optional).
+    #[schemars(description = "Maximum number of hops (optional, max 100)")]
+    pub max_hops: Option<usize>,
+    /// Minimum weight of each hop (optional).
+    #[schemars(description = "Minimum weight of each hop (optional)")]
+    pub min_weight: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct PathsParams {
+    /// Starting node ID.
+    #[schemars(description = "Starting node ID")]
+    pub start: u64,
+    /// Ending node ID.
+    #[schemars(description = "Ending node ID")]
+    pub end: u64,
+    /// Maximum number of hops per path.
+    #[schemars(description = "Maximum number of hops per path (max 100)")]
+    pub max_hops: usize,
+    /// Maximum number of paths to return (optional).
+    #[schemars(description = "Maximum number of paths to return (default 10, max 100)")]
+    pub limit: Option<usize>,
+    /// Only follow edges with this relation label (optional).
+    #[schemars(description = "Only follow edges with this relation label (optional)")]
+    pub relation: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct KStrongestPathsParams {
+    /// Starting node ID.
+    #[schemars(description = "Starting node ID")]
+    pub start: u64,
+    /// Ending node ID.
+    #[schemars(description = "Ending node ID")]
+    pub end: u64,
+    /// Number of paths to return.
+    #[schemars(description = "Number of paths to return (max 100)")]
+    pub k: usize,
+    /// Only follow edges with this relation label (optional).
+    #[schemars(description = "Only follow edges with this relation label (optional)")]
+    pub relation: Option<String>,
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -99,6 +219,134 @@ pub struct RetractParams {
     /// Target entity ID (the edge destination).
     #[schemars(description = "Target entity ID (the edge destination)")]
     pub to_entity: u64,
+    /// Relation label of the edge (optional; omit for the unlabeled edge).
+    #[schemars(description = "Relation label of the edge (optional; omit for the unlabeled edge)")]
+    pub relation: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct DeleteParams {
+    /// The entity ID to delete.
+    #[schemars(description = "The entity ID to delete")]
+    pub entity_id: u64,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct RetractPropertyParams {
+    /// The entity ID that holds the property.
+    #[schemars(description = "The entity ID that holds the property")]
+    pub entity_id: u64,
+    /// The property attribute name.
+    #[schemars(description = "The property attribute name")]
+    pub attribute: String,
+    /// The exact value to retract.
+    #[schemars(description = "The exact value to retract")]
+    pub value: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct PatternPropertyParams {
+    /// The property attribute name.
+    #[schemars(description = "The property attribute name")]
+    pub attribute: String,
+    /// The value the property must have.
+    #[schemars(description = "The value the property must have")]
+    pub value: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct PatternVariableParams {
+    /// Variable name, referenced by edges.
+    #[schemars(description = "Variable name (letters, digits, _), referenced by edges")]
+    pub name: String,
+    /// Bind the variable to exactly this node (optional).
+    #[schemars(description = "Bind the variable to exactly this node (optional)")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_id: Option<u64>,
+    /// Properties the bound node must carry (optional).
+    #[schemars(description = "Properties the bound node must carry (optional)")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub properties: Vec<PatternPropertyParams>,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct PatternEdgeParams {
+    /// Name of the variable the edge leaves.
+    #[schemars(description = "Name of the variable the edge leaves")]
+    pub from: String,
+    /// Name of the variable the edge enters.
+    #[schemars(description = "Name of the variable the edge enters")]
+    pub to: String,
+    /// Only match edges with this relation label (optional).
+    #[schemars(description = "Only match edges with this relation label (optional)")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relation: Option<String>,
+    /// Minimum edge weight (optional).
+    #[schemars(description = "Minimum edge weight (optional)")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_weight: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct PatternParams {
+    /// Pattern variables, bound in this order.
+    #[schemars(
+        description = "Pattern variables, bound in this order; declare a pinned or property-constrained variable first"
+    )]
+    pub variables: Vec<PatternVariableParams>,
+    /// Edges required between the variables (optional).
+    #[schemars(description = "Edges required between the variables (optional)")]
+    #[serde(default)]
+    pub edges: Vec<PatternEdgeParams>,
+    /// Maximum number of bindings to return (optional).
+    #[schemars(description = "Maximum number of bindings to return (default 10, max 1000)")]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct PipelineStageParams {
+    /// Stage kind: "lookup", "traverse" or "properties".
+    #[schemars(
+        description = "Stage kind: 'lookup' (first stage only), 'traverse' or 'properties'"
+    )]
+    pub stage: String,
+    /// Entity IDs to resolve (lookup only).
+    #[schemars(description = "Entity IDs to resolve (lookup only)")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entity_ids: Vec<u64>,
+    /// Entity keys to resolve (lookup only).
+    #[schemars(description = "Entity keys to resolve (lookup only)")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entity_keys: Vec<String>,
+    /// Traversal depth (traverse only).
+    #[schemars(description = "Traversal depth (traverse only, max 100)")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth: Option<usize>,
+    /// Minimum edge weight (traverse only, optional).
+    #[schemars(description = "Minimum edge weight (traverse only, optional)")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_weight: Option<i64>,
+    /// Follow incoming edges (traverse only, optional).
+    #[schemars(
+        description = "Follow incoming edges instead of outgoing (traverse only, optional)"
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backward: Option<bool>,
+    /// Only follow edges with this relation label (traverse only, optional).
+    #[schemars(
+        description = "Only follow edges with this relation label (traverse only, optional)"
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relation: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct PipelineParams {
+    /// Stages run in order, each on the nodes produced by the previous one.
+    #[schemars(
+        description = "Stages run in order, each on the nodes produced by the previous one; the first must be a lookup"
+    )]
+    pub stages: Vec<PipelineStageParams>,
 }
 
 // =============================================================================
@@ -121,10 +369,33 @@ impl KremisMcp {
     ) -> Result<CallToolResult, McpError> {
         let IngestParams {
             entity_id,
+            entity_key,
             attribute,
             value,
+            link_from_entity_id,
+            link_from_entity_key,
+            relation,
         } = params.0;
-        let result = self.client.ingest(entity_id, &attribute, &value).await;
+        if let Some(id) = [entity_id, link_from_entity_id]
+            .into_iter()
+            .flatten()
+            .find(|&id| id >= KEYED_ENTITY_BASE)
+        {
+            return Err(McpError::invalid_params(
+                format!("entity id {id} is reserved for entity keys; name it by entity_key"),
+                None,
+            ));
+        }
+        let body = serde_json::json!({
+            "entity_id": entity_id,
+            "entity_key": entity_key,
+            "attribute": attribute,
+            "value": value,
+            "link_from_entity_id": link_from_entity_id,
+            "link_from_entity_key": link_from_entity_key,
+            "relation": relation,
+        });
+        let result = self.client.ingest(body).await;
         match result {
             Ok(resp) => {
                 let text = if let Some(node_id) = resp.get("node_id").and_then(|v| v.as_u64()) {
@@ -140,7 +411,7 @@ impl KremisMcp {
         }
     }
 
-    #[tool(description = "Look up an entity in the graph by its entity ID")]
+    #[tool(description = "Look up an entity in the graph by its entity ID or entity key")]
     async fn kremis_lookup(
         &self,
         params: Parameters<LookupParams>,
@@ -148,6 +419,7 @@ impl KremisMcp {
         let query = serde_json::json!({
             "type": "lookup",
             "entity_id": params.0.entity_id,
+            "entity_key": params.0.entity_key,
         });
         match self.client.query(query).await {
             Ok(resp) => Ok(CallToolResult::success(vec![Content::text(
@@ -157,6 +429,57 @@ impl KremisMcp {
         }
     }
 
+    #[tool(
+        description = "Search property values by text and return matching node IDs ranked by score. Use it to find a node by name before querying it"
+    )]
+    async fn kremis_search(
+        &self,
+        params: Parameters<SearchParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let query = serde_json::json!({
+            "type": "search",
+            "query": params.0.query,
+            "limit": params.0.limit.unwrap_or(10),
+        });
+        match self.client.query(query).await {
+            Ok(resp) => Ok(CallToolResult::success(vec![Content::text(
+                format_search_response(&resp),
+            )])),
+            Err(e) => Err(mcp_err(e)),
+        }
+    }
+
+    #[tool(
+        description = "Run a query written in the Kremis query language (LOOKUP, TRAVERSE, PATH, PATHS, KSTRONGEST, REACHABLE, INTERSECT, FIND, SEARCH). Syntax errors report the character position"
+    )]
+    async fn kremis_query(
+        &self,
+        params: Parameters<TextQueryParams>,
+    ) -> Result<CallToolResult, McpError> {
+        match self.client.query_text(&params.0.query).await {
+            Ok(resp) => Ok(CallToolResult::success(vec![Content::text(
+                format_text_query_response(&resp),
+            )])),
+            Err(e) => Err(mcp_err(e)),
+        }
+    }
+
+    #[tool(
+        description = "Run chained stages in one call against one consistent state: lookup entities, traverse from the nodes found, then read their properties"
+    )]
+    async fn kremis_pipeline(
+        &self,
+        params: Parameters<PipelineParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let request = serde_json::json!({ "stages": params.0.stages });
+        match self.client.pipeline(request).await {
+            Ok(resp) => Ok(CallToolResult::success(vec![Content::text(
+                format_pipeline_response(&resp),
+            )])),
+            Err(e) => Err(mcp_err(e)),
+        }
+    }
+
     #[tool(description = "Traverse the graph from a node to discover connected entities")]
     async fn kremis_traverse(
         &self,
@@ -169,6 +492,9 @@ impl KremisMcp {
             "depth": depth,
             "min_weight": 0,
             "top_k": params.0.top_k,
+            "relation": params.0.relation,
+            "page_size": params.0.page_size,
+            "cursor": params.0.cursor,
         });
         match self.client.query(query).await {
             Ok(resp) => Ok(CallToolResult::success(vec![Content::text(
@@ -178,19 +504,117 @@ impl KremisMcp {
         }
     }
 
-    #[tool(description = "Find the strongest weighted path between two nodes")]
+    #[tool(
+        description = "Find the strongest weighted path between two nodes, optionally avoiding nodes, passing through waypoints in order, within a hop limit or above a minimum hop weight"
+    )]
     async fn kremis_path(
         &self,
-        params: Parameters<PathParams>,
+        params: Parameters<StrongestPathParams>,
     ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
         let query = serde_json::json!({
             "type": "strongest_path",
+            "start": params.start,
+            "end": params.end,
+            "relation": params.relation,
+            "avoid": params.avoid.unwrap_or_default(),
+            "waypoints": params.waypoints.unwrap_or_default(),
+            "max_hops": params.max_hops,
+            "min_weight": params.min_weight,
+        });
+        match self.client.query(query).await {
+            Ok(resp) => Ok(CallToolResult::success(vec![Content::text(
+                format_query_response(&resp),
+            )])),
+            Err(e) => Err(mcp_err(e)),
+        }
+    }
+
+    #[tool(
+        description = "List every simple path between two nodes up to a hop limit, heaviest total weight first"
+    )]
+    async fn kremis_paths(
+        &self,
+        params: Parameters<PathsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let mut query = serde_json::json!({
+            "type": "paths",
             "start": params.0.start,
             "end": params.0.end,
+            "max_hops": params.0.max_hops,
+            "relation": params.0.relation,
         });
+        if let Some(limit) = params.0.limit {
+            query["limit"] = serde_json::json!(limit);
+        }
         match self.client.query(query).await {
             Ok(resp) => Ok(CallToolResult::success(vec![Content::text(
-                format_query_response(&resp),
+                format_paths_response(&resp),
+            )])),
+            Err(e) => Err(mcp_err(e)),
+        }
+    }
+
+    #[tool(description = "Find the k strongest paths between two nodes, ranked like kremis_path")]
+    async fn kremis_k_strongest_paths(
+        &self,
+        params: Parameters<KStrongestPathsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let query = serde_json::json!({
+            "type": "k_strongest_paths",
+            "start": params.0.start,
+            "end": params.0.end,
+            "k": params.0.k,
+            "relation": params.0.relation,
+        });
+        match self.client.query(query).await {
+            Ok(resp) => Ok(CallToolResult::success(vec![Content::text(
+                format_paths_response(&resp),
+            )])),
+            Err(e) => Err(mcp_err(e)),
+        }
+    }
+
+    #[tool(
+        description = "Check whether one node can reach another, directly or transitively. Explores the graph exhaustively, so a negative answer is definitive"
+    )]
+    async fn kremis_reachable(
+        &self,
+        params: Parameters<PathParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let query = serde_json::json!({
+            "type": "reachable",
+            "start": params.0.start,
+            "end": params.0.end,
+            "relation": params.0.relation,
+        });
+        match self.client.query(query).await {
+            Ok(resp) => Ok(CallToolResult::success(vec![Content::text(
+                format_reachable_response(&resp),
+            )])),
+            Err(e) => Err(mcp_err(e)),
+        }
+    }
+
+    #[tool(
+        description = "Find every binding of a subgraph pattern: named variables, optionally pinned to nodes or constrained by properties, joined by required edges"
+    )]
+    async fn kremis_pattern(
+        &self,
+        params: Parameters<PatternParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let names: Vec<String> = params.0.variables.iter().map(|v| v.name.clone()).collect();
+        let mut query = serde_json::json!({
+            "type": "pattern",
+            "variables": params.0.variables,
+            "edges": params.0.edges,
+        });
+        if let Some(limit) = params.0.limit {
+            query["limit"] = serde_json::json!(limit);
+        }
+        match self.client.query(query).await {
+            Ok(resp) => Ok(CallToolResult::success(vec![Content::text(
+                format_pattern_response(&resp, &names),
             )])),
             Err(e) => Err(mcp_err(e)),
         }
@@ -263,8 +687,13 @@ impl KremisMcp {
         let RetractParams {
             from_entity,
             to_entity,
+            relation,
         } = params.0;
-        match self.client.retract(from_entity, to_entity).await {
+        match self
+            .client
+            .retract(from_entity, to_entity, relation.as_deref())
+            .await
+        {
             Ok(resp) => Ok(CallToolResult::success(vec![Content::text(
                 format_retract_response(&resp),
             )])),
@@ -272,6 +701,41 @@ impl KremisMcp {
         }
     }
 
+    #[tool(
+        description = "Delete an entity from the graph, removing its node together with all incoming and outgoing edges and its properties"
+    )]
+    async fn kremis_delete(
+        &self,
+        params: Parameters<DeleteParams>,
+    ) -> Result<CallToolResult, McpError> {
+        match self.client.delete_node(params.0.entity_id).await {
+            Ok(resp) => Ok(CallToolResult::success(vec![Content::text(
+                format_delete_response(&resp),
+            )])),
+            Err(e) => Err(mcp_err(e)),
+        }
+    }
+
+    #[tool(
+        description = "Retract a single property from an entity. Only the exact attribute/value pair is removed"
+    )]
+    async fn kremis_retract_property(
+        &self,
+        params: Parameters<RetractPropertyParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        match self
+            .client
+            .retract_property(p.entity_id, &p.attribute, &p.value)
+            .await
+        {
+            Ok(resp) => Ok(CallToolResult::success(vec![Content::text(
+                format_retract_property_response(&resp),
+            )])),
+            Err(e) => Err(mcp_err(e)),
+        }
+    }
+
     #[tool(description = "Get the canonical BLAKE3 hash of the current graph state")]
     async fn kremis_hash(&self) -> Result<CallToolResult, McpError> {
         match self.client.hash().await {
@@ -317,9 +781,10 @@ impl ServerHandler for KremisMcp {
         let mut info = ServerInfo::default();
         info.instructions = Some(
             "Kremis knowledge graph server. Use tools to ingest entities, \
-             query relationships, traverse the graph, inspect properties, \
-             retract edges, verify graph integrity via BLAKE3 hash, and \
-             produce Verifiable Query Certificates (reproducible proofs, \
+             search property values to find nodes, query relationships, \
+             traverse the graph, inspect properties, retract edges and \
+             properties, delete entities, verify graph integrity via BLAKE3 \
+             hash, and produce Verifiable Query Certificates (reproducible proofs, \
              including proof of absence)."
                 .into(),
         );
@@ -329,6 +794,11 @@ impl ServerHandler for KremisMcp {
 }
 
 /// Map any displayable error into an MCP internal error.
+/// First id of the range owned by entity keys, as in
+/// `kremis_core::primitives::KEYED_ENTITY_BASE`. A raw id there is rejected
+/// before any request is sent.
+const KEYED_ENTITY_BASE: u64 = 1 << 63;
+
 fn mcp_err(e: impl std::fmt::Display) -> McpError {
     McpError::internal_error(format!("{e}"), None)
 }
@@ -355,6 +825,43 @@ fn format_retract_response(resp: &serde_json::Value) -> String {
     }
 }
 
+/// Format a node delete response JSON into human-readable text.
+///
+/// The HTTP `POST /node/delete` endpoint returns `{ success, node_id, error }`.
+fn format_delete_response(resp: &serde_json::Value) -> String {
+    if resp
+        .get("success")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+    {
+        match resp.get("node_id").and_then(|v| v.as_u64()) {
+            Some(node_id) => format!("Deleted node {node_id} with its edges and properties"),
+            None => "Node deleted".to_string(),
+        }
+    } else if let Some(err) = resp.get("error").and_then(|v| v.as_str()) {
+        format!("Delete failed: {err}")
+    } else {
+        format!("Delete response: {resp}")
+    }
+}
+
+/// Format a property retract response JSON into human-readable text.
+///
+/// The HTTP `POST /property/retract` endpoint returns `{ success, error }`.
+fn format_retract_property_response(resp: &serde_json::Value) -> String {
+    if resp
+        .get("success")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+    {
+        "Property retracted".to_string()
+    } else if let Some(err) = resp.get("error").and_then(|v| v.as_str()) {
+        format!("Retract property failed: {err}")
+    } else {
+        format!("Retract property response: {resp}")
+    }
+}
+
 /// Format a query response JSON into human-readable text.
 fn format_query_response(resp: &serde_json::Value) -> String {
     let success = resp
@@ -423,6 +930,12 @@ fn format_query_response(resp: &serde_json::Value) -> String {
         parts.push(format!("Grounding: {grounding}"));
     }
 
+    if let Some(cursor) = resp.get("next_cursor").and_then(|v| v.as_str()) {
+        parts.push(format!(
+            "More results: pass cursor \"{cursor}\" for the next page"
+        ));
+    }
+
     if parts.is_empty() {
         "Found (no details).".to_string()
     } else {
@@ -430,6 +943,202 @@ fn format_query_response(resp: &serde_json::Value) -> String {
     }
 }
 
+/// Format a reachable query response JSON into human-readable text.
+///
+/// A negative `reachable` answer carries the start node's closure in `path`
+/// and `edges`; only its size is shown.
+fn format_reachable_response(resp: &serde_json::Value) -> String {
+    let success = resp
+        .get("success")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+    let found = resp.get("found").and_then(|v| v.as_bool()).unwrap_or(false);
+    let diagnostic = resp.get("diagnostic").and_then(|v| v.as_str());
+    if !success || found || diagnostic == Some("start_not_found") {
+        return format_query_response(resp);
+    }
+    let count = |field: &str| {
+        resp.get(field)
+            .and_then(|v| v.as_array())
+            .map_or(0, Vec::len)
+    };
+    format!(
+        "Not reachable ({}).\nExplored every node reachable from the start: {} nodes, {} edges.",
+        diagnostic.unwrap_or("no_path"),
+        count("path"),
+        count("edges")
+    )
+}
+
+/// Format a `paths` or `k_strongest_paths` response JSON into human-readable
+/// text, one path per line.
+fn format_paths_response(resp: &serde_json::Value) -> String {
+    let Some(paths) = resp
+        .get("paths")
+        .and_then(|v| v.as_array())
+        .filter(|paths| !paths.is_empty())
+    else {
+        return format_query_response(resp);
+    };
+
+    let mut parts = vec![format!("Paths ({}):", paths.len())];
+    for path in paths {
+        let ids: Vec<String> = path
+            .get("nodes")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_u64().map(|n| n.to_string()))
+            .collect();
+        let weight = path.get("weight").and_then(|v| v.as_i64()).unwrap_or(0);
+        parts.push(format!("  [{}] (weight {weight})", ids.join(" -> ")));
+    }
+    if let Some(grounding) = resp.get("grounding").and_then(|v| v.as_str()) {
+        parts.push(format!("Grounding: {grounding}"));
+    }
+    parts.join("\n")
+}
+
+/// Format a `pattern` response JSON into human-readable text, one binding
+/// per line with each variable name next to its node.
+fn format_pattern_response(resp: &serde_json::Value, names: &[String]) -> String {
+    let Some(bindings) = resp
+        .get("bindings")
+        .and_then(|v| v.as_array())
+        .filter(|bindings| !bindings.is_empty())
+    else {
+        return format_query_response(resp);
+    };
+
+    let mut parts = vec![format!("Bindings ({}):", bindings.len())];
+    for binding in bindings {
+        let nodes = binding.get("nodes").and_then(|v| v.as_array());
+        let assigned: Vec<String> = names
+            .iter()
+            .zip(nodes.into_iter().flatten())
+            .map(|(name, node)| format!("{name}={}", node.as_u64().unwrap_or(0)))
+            .collect();
+        parts.push(format!("  {}", assigned.join(" ")));
+    }
+    if let Some(grounding) = resp.get("grounding").and_then(|v| v.as_str()) {
+        parts.push(format!("Grounding: {grounding}"));
+    }
+    parts.join("\n")
+}
+
+/// Format the response of a textual query with the formatter of the query
+/// kind it turned out to be.
+fn format_text_query_response(resp: &serde_json::Value) -> String {
+    let has = |field: &str| {
+        resp.get(field)
+            .and_then(|v| v.as_array())
+            .is_some_and(|items| !items.is_empty())
+    };
+    if has("paths") {
+        format_paths_response(resp)
+    } else if has("scores") {
+        format_search_response(resp)
+    } else {
+        format_query_response(resp)
+    }
+}
+
+/// Format a pipeline response JSON into human-readable text, one block per
+/// stage.
+fn format_pipeline_response(resp: &serde_json::Value) -> String {
+    let success = resp
+        .get("success")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    if !success && let Some(err) = resp.get("error").and_then(|v| v.as_str()) {
+        return format!("Pipeline error: {err}");
+    }
+    let Some(stages) = resp.get("stages").and_then(|v| v.as_array()) else {
+        return format!("Pipeline response: {resp}");
+    };
+
+    let mut parts = Vec::new();
+    for (index, stage) in stages.iter().enumerate() {
+        let name = stage.get("stage").and_then(|v| v.as_str()).unwrap_or("?");
+        let grounding = stage
+            .get("grounding")
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown");
+        let found = stage
+            .get("found")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if !found {
+            let reason = stage
+                .get("diagnostic")
+                .and_then(|v| v.as_str())
+                .unwrap_or("no result");
+            parts.push(format!("{}. {name}: not found ({reason})", index + 1));
+            continue;
+        }
+        let ids: Vec<String> = stage
+            .get("nodes")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_u64().map(|n| n.to_string()))
+            .collect();
+        parts.push(format!(
+            "{}. {name}: nodes [{}] ({grounding})",
+            index + 1,
+            ids.join(", ")
+        ));
+        for entry in stage
+            .get("properties")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+        {
+            let node = entry.get("node_id").and_then(|v| v.as_u64()).unwrap_or(0);
+            let props: Vec<String> = entry
+                .get("properties")
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten()
+                .map(|prop| {
+                    let attr = prop
+                        .get("attribute")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("?");
+                    let val = prop.get("value").and_then(|v| v.as_str()).unwrap_or("?");
+                    format!("{attr}: {val}")
+                })
+                .collect();
+            parts.push(format!("   node {node}: {}", props.join(", ")));
+        }
+    }
+    parts.join("\n")
+}
+
+/// Format a search query response JSON into human-readable text.
+///
+/// A `search` response lists the ranked node ids in `path` with their scores
+/// in the aligned `scores` array.
+fn format_search_response(resp: &serde_json::Value) -> String {
+    let found = resp.get("found").and_then(|v| v.as_bool()).unwrap_or(false);
+    if !found {
+        return format_query_response(resp);
+    }
+    let ids = resp.get("path").and_then(|v| v.as_array());
+    let scores = resp.get("scores").and_then(|v| v.as_array());
+    let (Some(ids), Some(scores)) = (ids, scores) else {
+        return format!("Search response: {resp}");
+    };
+
+    let mut parts = vec![format!("Matches ({}):", ids.len())];
+    for (id, score) in ids.iter().zip(scores) {
+        let id = id.as_u64().unwrap_or(0);
+        let score = score.as_u64().unwrap_or(0);
+        parts.push(format!("  node {id} (score {score})"));
+    }
+    parts.join("\n")
+}
+
 /// Format a certify response JSON into human-readable text.
 ///
 /// The HTTP `POST /certify` endpoint returns
@@ -477,7 +1186,12 @@ fn format_certify_response(resp: &serde_json::Value) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::{format_certify_response, format_query_response, format_retract_response};
+    use super::{
+        format_certify_response, format_delete_response, format_paths_response,
+        format_pattern_response, format_pipeline_response, format_query_response,
+        format_reachable_response, format_retract_property_response, format_retract_response,
+        format_search_response, format_text_query_response,
+    };
     use serde_json::json;
 
     // --- format_retract_response ---
@@ -516,6 +1230,72 @@ mod tests {
         assert!(text.starts_with("Retract response:"));
     }
 
+    // --- format_delete_response ---
+
+    #[test]
+    fn delete_success_shows_node_id() {
+        let resp = json!({ "success": true, "node_id": 4, "error": null });
+        assert_eq!(
+            format_delete_response(&resp),
+            "Deleted node 4 with its edges and properties"
+        );
+    }
+
+    #[test]
+    fn delete_error_shows_message() {
+        let resp = json!({ "success": false, "node_id": null, "error": "entity not found" });
+        assert_eq!(
+            format_delete_response(&resp),
+            "Delete failed: entity not found"
+        );
+    }
+
+    // --- format_retract_property_response ---
+
+    #[test]
+    fn retract_property_success() {
+        let resp = json!({ "success": true, "error": null });
+        assert_eq!(
+            format_retract_property_response(&resp),
+            "Property retracted"
+        );
+    }
+
+    #[test]
+    fn retract_property_error_shows_message() {
+        let resp = json!({ "success": false, "error": "property not found" });
+        assert_eq!(
+            format_retract_property_response(&resp),
+            "Retract property failed: property not found"
+        );
+    }
+
+    // --- format_search_response ---
+
+    #[test]
+    fn search_lists_nodes_with_scores() {
+        let resp = json!({
+            "success": true, "found": true, "path": [4, 1], "edges": [],
+            "scores": [4, 2], "grounding": "fact", "error": null
+        });
+        assert_eq!(
+            format_search_response(&resp),
+            "Matches (2):\n  node 4 (score 4)\n  node 1 (score 2)"
+        );
+    }
+
+    #[test]
+    fn search_no_match_shows_reason() {
+        let resp = json!({
+            "success": true, "found": false, "path": [], "edges": [],
+            "grounding": "unknown", "error": null, "diagnostic": "no_matching_nodes"
+        });
+        assert_eq!(
+            format_search_response(&resp),
+            "Not found.\nGrounding: unknown\nReason: no_matching_nodes"
+        );
+    }
+
     // --- format_query_response ---
 
     #[test]
@@ -534,6 +1314,158 @@ mod tests {
         assert!(text.contains("no such entity"));
     }
 
+    #[test]
+    fn query_page_shows_next_cursor() {
+        let resp = json!({
+            "success": true, "found": true, "path": [],
+            "edges": [{ "from": 1, "to": 2, "weight": 5 }],
+            "grounding": "inference", "next_cursor": "00ff"
+        });
+        let text = format_query_response(&resp);
+        assert!(text.contains("1 --(5)--> 2"));
+        assert!(text.ends_with("More results: pass cursor \"00ff\" for the next page"));
+    }
+
+    // --- format_reachable_response ---
+
+    #[test]
+    fn reachable_negative_summarizes_closure() {
+        let resp = json!({
+            "success": true, "found": false, "path": [0, 1], "grounding": "unknown",
+            "edges": [{"from": 0, "to": 1, "weight": 1}], "diagnostic": "no_path"
+        });
+        assert_eq!(
+            format_reachable_response(&resp),
+            "Not reachable (no_path).\nExplored every node reachable from the start: 2 nodes, 1 edges."
+        );
+    }
+
+    #[test]
+    fn reachable_positive_shows_path() {
+        let resp = json!({
+            "success": true, "found": true, "path": [0, 1], "edges": [],
+            "grounding": "inference"
+        });
+        assert!(format_reachable_response(&resp).starts_with("Path: [0 -> 1]"));
+    }
+
+    // --- format_paths_response ---
+
+    #[test]
+    fn paths_lists_each_path_with_its_weight() {
+        let resp = json!({
+            "success": true, "found": true, "path": [0, 1, 3], "edges": [],
+            "paths": [{"nodes": [0, 1, 3], "weight": 10}, {"nodes": [0, 3], "weight": 3}],
+            "grounding": "inference"
+        });
+        assert_eq!(
+            format_paths_response(&resp),
+            "Paths (2):\n  [0 -> 1 -> 3] (weight 10)\n  [0 -> 3] (weight 3)\nGrounding: inference"
+        );
+    }
+
+    #[test]
+    fn paths_not_found_falls_back() {
+        let resp = json!({
+            "success": true, "found": false, "path": [], "edges": [],
+            "grounding": "unknown", "diagnostic": "no_path"
+        });
+        assert_eq!(format_paths_response(&resp), format_query_response(&resp));
+    }
+
+    // --- format_pattern_response ---
+
+    #[test]
+    fn pattern_names_each_bound_variable() {
+        let resp = json!({
+            "success": true, "found": true, "path": [0, 1], "edges": [],
+            "bindings": [
+                {"nodes": [0, 1], "edges": [{"from": 0, "to": 1, "weight": 5}]},
+                {"nodes": [0, 2], "edges": [{"from": 0, "to": 2, "weight": 5}]}
+            ],
+            "grounding": "inference"
+        });
+        let names = ["svc".to_string(), "dep".to_string()];
+        assert_eq!(
+            format_pattern_response(&resp, &names),
+            "Bindings (2):\n  svc=0 dep=1\n  svc=0 dep=2\nGrounding: inference"
+        );
+    }
+
+    #[test]
+    fn pattern_no_match_falls_back() {
+        let resp = json!({
+            "success": true, "found": false, "path": [], "edges": [],
+            "grounding": "unknown", "diagnostic": "no_match"
+        });
+        assert_eq!(
+            format_pattern_response(&resp, &[]),
+            format_query_response(&resp)
+        );
+    }
+
+    // --- format_text_query_response ---
+
+    #[test]
+    fn text_query_picks_the_matching_formatter() {
+        let paths = json!({
+            "success": true, "found": true, "path": [0, 3], "edges": [],
+            "paths": [{"nodes": [0, 3], "weight": 3}], "grounding": "inference"
+        });
+        assert_eq!(
+            format_text_query_response(&paths),
+            format_paths_response(&paths)
+        );
+
+        let search = json!({
+            "success": true, "found": true, "path": [4], "scores": [2], "edges": [],
+            "grounding": "fact"
+        });
+        assert_eq!(
+            format_text_query_response(&search),
+            format_search_response(&search)
+        );
+
+        let lookup = json!({
+            "success": true, "found": true, "path": [7], "edges": [], "grounding": "fact"
+        });
+        assert_eq!(
+            format_text_query_response(&lookup),
+            format_query_response(&lookup)
+        );
+    }
+
+    // --- format_pipeline_response ---
+
+    #[test]
+    fn pipeline_lists_every_stage() {
+        let resp = json!({
+            "success": true,
+            "stages": [
+                {"stage": "lookup", "found": true, "nodes": [0], "grounding": "fact"},
+                {"stage": "properties", "found": true, "nodes": [0], "grounding": "fact",
+                 "properties": [{"node_id": 0, "properties": [{"attribute": "name", "value": "Alice"}]}]},
+                {"stage": "traverse", "found": false, "nodes": [], "grounding": "unknown",
+                 "diagnostic": "node_not_found"}
+            ],
+            "error": null
+        });
+        assert_eq!(
+            format_pipeline_response(&resp),
+            "1. lookup: nodes [0] (fact)\n2. properties: nodes [0] (fact)\n   node 0: name: Alice\n3. traverse: not found (node_not_found)"
+        );
+    }
+
+    #[test]
+    fn pipeline_error_shows_message() {
+        let resp =
+            json!({ "success": false, "stages": [], "error": "Pipeline failed: Invalid signal" });
+        assert_eq!(
+            format_pipeline_response(&resp),
+            "Pipeline error: Pipeline failed: Invalid signal"
+        );
+    }
+
     // --- format_certify_response ---
 
     #[test]
@@ -5,22 +5,49 @@
 use super::{
     AppState,
     types::{
-        BatchIngestRequest, BatchIngestResponse, CertifyResponse, ExportResponse, HealthResponse,
-        IngestRequest, IngestResponse, PropertyJson, QueryRequest, QueryResponse, RetractRequest,
-        RetractResponse, StageResponse, StatusResponse,
+        BatchIngestRequest, BatchIngestResponse, CentralityKind, CentralityResponse,
+        CertifyResponse, ComponentKind, ComponentsResponse, DeleteNodeRequest, DeleteNodeResponse,
+        DeltaExportRequest, DeltaExportResponse, DiffRequest, DiffResponse, EdgeJson, EntityRef,
+        ExportResponse, HealthResponse, IngestRequest, IngestResponse, NodePropertiesJson,
+        PatternEdgeJson, PatternVariableJson, PipelineRequest, PipelineResponse, PipelineStage,
+        PropertyJson, QueryEnvelope, QueryRequest, QueryResponse, RetractPropertyRequest,
+        RetractPropertyResponse, RetractRequest, RetractResponse, StageResponse, StageResultJson,
+        StatusResponse, TextQueryRequest, entity_ref,
     },
 };
-use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use axum::{
+    Json,
+    body::{Body, Bytes},
+    extract::{Query, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
 use kremis_core::{
-    Artifact, EdgeWeight, EntityId, KremisError, NodeId, QueryCertificate, Session,
-    certificate::state_hash,
-    export::{canonical_checksum, canonical_crypto_hash, export_canonical},
+    Artifact, Attribute, CentralityMetric, Cursor, EdgeWeight, EntityId, Graph, Ingestor,
+    KremisError, LabeledEdge, LinkMode, NodeId, PathConstraints, Pattern, PatternEdge,
+    PatternVariable, RankedPath, Reachability, RedbExport, Session, Signal, StorageBackend, Value,
+    VisitBudget,
+    centrality::{DEFAULT_PAGERANK_ITERATIONS, top_n},
+    cursor::DEFAULT_PAGE_SIZE,
+    delta::{GraphDelta, stream_delta},
+    diff,
+    export::{CanonicalGraph, import_canonical},
+    graph::STRONGEST_PATH_VARIANT,
+    language::parse_query,
     primitives::{
-        MAX_INTERSECT_NODES, MAX_SEQUENCE_LENGTH, MAX_TRAVERSAL_DEPTH, MIN_INTERSECT_NODES,
+        ASSOCIATION_WINDOW, MAX_ATTRIBUTE_LENGTH, MAX_CENTRALITY_RESULTS, MAX_INTERSECT_NODES,
+        MAX_PAGE_SIZE, MAX_PATH_CONSTRAINT_NODES, MAX_PATH_RESULTS, MAX_PATTERN_RESULTS,
+        MAX_PIPELINE_NODES, MAX_PIPELINE_STAGES, MAX_PROPERTY_MATCHES, MAX_SEARCH_RESULTS,
+        MAX_SEARCH_TERMS, MAX_SEQUENCE_LENGTH, MAX_TRAVERSAL_DEPTH, MIN_INTERSECT_NODES,
     },
+    search::tokenize,
+    stream::{HashingWriter, write_canonical},
     system::{GraphMetrics, Stage, StageAssessor},
 };
+use serde::Deserialize;
 use std::collections::BTreeSet;
+use std::io::Write;
+use tokio::sync::mpsc;
 
 // =============================================================================
 // HEALTH HANDLER
@@ -80,24 +107,23 @@ pub async fn ingest_handler(
     State(state): State<AppState>,
     Json(request): Json<IngestRequest>,
 ) -> impl IntoResponse {
-    // Validate and convert request to signal
-    let signal = match request.to_signal() {
-        Ok(s) => s,
-        Err(e) => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(IngestResponse::error(format!("Invalid signal: {}", e))),
-            );
-        }
-    };
+    // Validate before taking the lock, so a rejected request registers no key
+    if let Err(e) = request.validate() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(IngestResponse::error(format!("Invalid signal: {}", e))),
+        );
+    }
 
     // Get write lock and ingest
     let mut session = state.session.write().await;
-    match session.ingest(&signal) {
+    match ingest_one(&mut session, &request) {
         Ok(node_id) => (StatusCode::OK, Json(IngestResponse::success(node_id))),
         Err(e) => {
             let status = match &e {
-                KremisError::PropertyLimitExceeded(_, _) => StatusCode::BAD_REQUEST,
+                KremisError::InvalidSignal | KremisError::PropertyLimitExceeded(_, _) => {
+                    StatusCode::BAD_REQUEST
+                }
                 _ => StatusCode::INTERNAL_SERVER_ERROR,
             };
             (
@@ -108,11 +134,81 @@ pub async fn ingest_handler(
     }
 }
 
+/// Ingest a validated single-signal request, with its link if it names one.
+fn ingest_one(session: &mut Session, request: &IngestRequest) -> Result<NodeId, KremisError> {
+    let signal = resolve_signal(session, request)?;
+    let Some(from) = request.link_from()? else {
+        return session.ingest(&signal);
+    };
+    let from = resolve_entity(session, from)?;
+    let relation = parse_relation(request.relation.as_deref())?;
+    let links = LinkMode::Explicit(vec![(from, signal.entity, relation)]);
+    session
+        .ingest_sequence_with(std::slice::from_ref(&signal), &links)?
+        .first()
+        .copied()
+        .ok_or(KremisError::InvalidSignal)
+}
+
+/// Build the signal for a validated request, registering its entity key on
+/// first use.
+fn resolve_signal(session: &mut Session, request: &IngestRequest) -> Result<Signal, KremisError> {
+    let entity = resolve_entity(session, request.entity()?)?;
+    Ok(request.signal_for(entity))
+}
+
+/// Turn an entity reference into an id, registering a key on first use.
+fn resolve_entity(session: &mut Session, entity: EntityRef<'_>) -> Result<EntityId, KremisError> {
+    match entity {
+        EntityRef::Id(entity) => Ok(entity),
+        EntityRef::Key(key) => session.register_entity_key(key),
+    }
+}
+
+/// Find the node an entity reference names, without registering a key.
+fn lookup_entity_ref(
+    session: &Session,
+    entity: EntityRef<'_>,
+) -> Result<Option<NodeId>, KremisError> {
+    let entity = match entity {
+        EntityRef::Id(entity) => entity,
+        EntityRef::Key(key) => match session.resolve_entity_key(key)? {
+            Some(entity) => entity,
+            None => return Ok(None),
+        },
+    };
+    Ok(session.lookup_entity(entity))
+}
+
+/// Build the link mode of a validated batch request.
+fn resolve_links(
+    session: &mut Session,
+    request: &BatchIngestRequest,
+) -> Result<LinkMode, KremisError> {
+    match (&request.links, request.window) {
+        (Some(links), _) => links
+            .iter()
+            .map(|link| {
+                Ok((
+                    resolve_entity(session, link.from()?)?,
+                    resolve_entity(session, link.to()?)?,
+                    parse_relation(link.relation.as_deref())?,
+                ))
+            })
+            .collect::<Result<_, KremisError>>()
+            .map(LinkMode::Explicit),
+        (None, window) => Ok(LinkMode::Window(
+            window.unwrap_or(ASSOCIATION_WINDOW),
+            parse_relation(request.relation.as_deref())?,
+        )),
+    }
+}
+
 // =============================================================================
 // BATCH INGEST HANDLER
 // =============================================================================
 
-/// Ingest a sequence of signals, creating edges between adjacent entities.
+/// Ingest a sequence of signals, creating edges per the request's link mode.
 pub async fn batch_ingest_handler(
     State(state): State<AppState>,
     Json(request): Json<BatchIngestRequest>,
@@ -130,25 +226,38 @@ pub async fn batch_ingest_handler(
     }
 
     // Validate all signals before touching the session
-    let mut signals = Vec::new();
     for req in &request.signals {
-        match req.to_signal() {
-            Ok(s) => signals.push(s),
-            Err(e) => {
-                return (
-                    StatusCode::BAD_REQUEST,
-                    Json(BatchIngestResponse::error(format!("Invalid signal: {}", e))),
-                );
-            }
+        if let Err(e) = req.validate() {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(BatchIngestResponse::error(format!("Invalid signal: {}", e))),
+            );
         }
     }
+    if let Err(e) = request.validate_links() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(BatchIngestResponse::error(format!("Invalid links: {}", e))),
+        );
+    }
 
     let mut session = state.session.write().await;
-    match session.ingest_sequence(&signals) {
+    let signals: Result<Vec<Signal>, KremisError> = request
+        .signals
+        .iter()
+        .map(|req| resolve_signal(&mut session, req))
+        .collect();
+    let result = signals.and_then(|signals| {
+        let links = resolve_links(&mut session, &request)?;
+        session.ingest_sequence_with(&signals, &links)
+    });
+    match result {
         Ok(node_ids) => (StatusCode::OK, Json(BatchIngestResponse::success(node_ids))),
         Err(e) => {
             let status = match &e {
-                KremisError::PropertyLimitExceeded(_, _) => StatusCode::BAD_REQUEST,
+                KremisError::InvalidSignal | KremisError::PropertyLimitExceeded(_, _) => {
+                    StatusCode::BAD_REQUEST
+                }
                 _ => StatusCode::INTERNAL_SERVER_ERROR,
             };
             (
@@ -170,6 +279,16 @@ pub async fn retract_handler(
     State(state): State<AppState>,
     Json(request): Json<RetractRequest>,
 ) -> impl IntoResponse {
+    let relation = match parse_relation(request.relation.as_deref()) {
+        Ok(r) => r,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(RetractResponse::error(format!("Invalid relation: {}", e))),
+            );
+        }
+    };
+
     let mut session = state.session.write().await;
 
     let from_node = match session.lookup_entity(EntityId(request.from_entity)) {
@@ -191,11 +310,11 @@ pub async fn retract_handler(
         }
     };
 
-    match session.decrement_edge(from_node, to_node) {
+    match session.decrement_edge(from_node, to_node, relation.as_ref()) {
         Ok(()) => {
             // Intentional degradation: decrement already succeeded, weight read
             // is best-effort for the response body only.
-            let new_weight = match session.get_edge(from_node, to_node) {
+            let new_weight = match session.get_edge(from_node, to_node, relation.as_ref()) {
                 Ok(Some(w)) => w.value(),
                 Ok(None) | Err(_) => 0,
             };
@@ -212,21 +331,455 @@ pub async fn retract_handler(
     }
 }
 
+// =============================================================================
+// PROPERTY RETRACT HANDLER
+// =============================================================================
+
+/// Retract a single `(attribute, value)` pair from an entity.
+pub async fn retract_property_handler(
+    State(state): State<AppState>,
+    Json(request): Json<RetractPropertyRequest>,
+) -> impl IntoResponse {
+    let entity = match request.validate().and_then(|()| request.entity()) {
+        Ok(entity) => entity,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(RetractPropertyResponse::error(format!(
+                    "Invalid property: {}",
+                    e
+                ))),
+            );
+        }
+    };
+
+    let mut session = state.session.write().await;
+
+    let node_id = match lookup_entity_ref(&session, entity) {
+        Ok(Some(n)) => n,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(RetractPropertyResponse::error("entity not found")),
+            );
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(RetractPropertyResponse::error(format!(
+                    "retract failed: {}",
+                    e
+                ))),
+            );
+        }
+    };
+
+    let attribute = Attribute::new(&request.attribute);
+    let value = Value::new(&request.value);
+    match session.remove_property(node_id, &attribute, &value) {
+        Ok(true) => (StatusCode::OK, Json(RetractPropertyResponse::success())),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(RetractPropertyResponse::error("property not found")),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(RetractPropertyResponse::error(format!(
+                "retract failed: {}",
+                e
+            ))),
+        ),
+    }
+}
+
+// =============================================================================
+// NODE DELETE HANDLER
+// =============================================================================
+
+/// Delete an entity's node, cascading to its edges and properties.
+pub async fn delete_node_handler(
+    State(state): State<AppState>,
+    Json(request): Json<DeleteNodeRequest>,
+) -> impl IntoResponse {
+    let entity = match request.entity() {
+        Ok(entity) => entity,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(DeleteNodeResponse::error(format!("Invalid entity: {}", e))),
+            );
+        }
+    };
+
+    let mut session = state.session.write().await;
+
+    let node_id = match lookup_entity_ref(&session, entity) {
+        Ok(Some(n)) => n,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(DeleteNodeResponse::error("entity not found")),
+            );
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(DeleteNodeResponse::error(format!("delete failed: {}", e))),
+            );
+        }
+    };
+
+    match session.remove_node(node_id) {
+        Ok(node) => (StatusCode::OK, Json(DeleteNodeResponse::success(node.id.0))),
+        Err(KremisError::NodeNotFound(_)) => (
+            StatusCode::NOT_FOUND,
+            Json(DeleteNodeResponse::error("node not found")),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(DeleteNodeResponse::error(format!("delete failed: {}", e))),
+        ),
+    }
+}
+
 // =============================================================================
 // QUERY HANDLER
 // =============================================================================
 
-/// Execute a query.
+/// Execute a query, optionally against a past state (`as_of_seq`), under
+/// a visit budget (`timeout_ms`) or one page at a time (`page_size`, `cursor`).
+///
+/// Without `timeout_ms` the query runs under the server's default budget;
+/// a larger `timeout_ms` than the server's maximum is cut down to it.
 pub async fn query_handler(
     State(state): State<AppState>,
-    Json(request): Json<QueryRequest>,
+    Json(envelope): Json<QueryEnvelope>,
+) -> impl IntoResponse {
+    let live = state.session.read().await;
+    let mut budget = state.query_budget.grant(envelope.timeout_ms);
+    let result = session_as_of(&live, envelope.as_of_seq, &mut budget).and_then(|past| {
+        let session = past.as_ref().unwrap_or(&live);
+        let Some(page) = resolve_page(&state, &live, session, &envelope)? else {
+            return execute_query_within(session, &envelope.query, &mut budget);
+        };
+        let response =
+            execute_query_prefix(session, &envelope.query, page.node_limit(), &mut budget)?;
+        Ok(page_response(response, &page))
+    });
+    query_result(result)
+}
+
+/// One page of a traversal result, resolved against the state it reads.
+struct Page {
+    state_hash: [u8; 32],
+    offset: u64,
+    size: usize,
+}
+
+impl Page {
+    /// Nodes a traversal must list to fill this page and show whether more
+    /// items follow it.
+    fn node_limit(&self) -> usize {
+        usize::try_from(self.offset)
+            .unwrap_or(usize::MAX)
+            .saturating_add(self.size)
+            .saturating_add(1)
+    }
+}
+
+/// Resolve the `page_size` and `cursor` of a `/query` body, if either is set.
+///
+/// Only traversals page. The state hash a cursor is checked against is
+/// computed once per mutation sequence number (see [`StateHashCache`]). A
+/// malformed cursor or page size returns `KremisError::InvalidSignal`; a
+/// cursor issued at another state returns `KremisError::StaleCursor`.
+///
+/// [`StateHashCache`]: crate::api::StateHashCache
+fn resolve_page(
+    state: &AppState,
+    live: &Session,
+    session: &Session,
+    envelope: &QueryEnvelope,
+) -> Result<Option<Page>, KremisError> {
+    if envelope.page_size.is_none() && envelope.cursor.is_none() {
+        return Ok(None);
+    }
+    if !matches!(
+        envelope.query,
+        QueryRequest::Traverse { .. }
+            | QueryRequest::TraverseFiltered { .. }
+            | QueryRequest::TraverseBackward { .. }
+            | QueryRequest::Related { .. }
+    ) {
+        return Err(KremisError::InvalidSignal);
+    }
+    let size = envelope.page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+    if size == 0 || size > MAX_PAGE_SIZE {
+        return Err(KremisError::InvalidSignal);
+    }
+    let cursor = envelope.cursor.as_deref().map(Cursor::decode).transpose()?;
+    let key = match envelope.as_of_seq {
+        Some(seq) => Some((seq, true)),
+        None => live.mutation_seq()?.map(|seq| (seq, false)),
+    };
+    let state_hash = state.state_hashes.state_hash(session, key)?;
+    let offset = match cursor {
+        Some(cursor) => cursor.resume(&state_hash)?,
+        None => 0,
+    };
+    Ok(Some(Page {
+        state_hash,
+        offset,
+        size,
+    }))
+}
+
+/// Cut one page out of a traversal response.
+///
+/// The nodes of `path` followed by `edges` form one sequence of items; the
+/// page keeps `page.size` of them from `page.offset` on. `found` still
+/// describes the whole result. The response may stop at
+/// [`Page::node_limit`] nodes: the page then lies among the nodes, and the
+/// extra node shows that more follow.
+fn page_response(mut response: QueryResponse, page: &Page) -> QueryResponse {
+    let nodes = response.path.len();
+    let total = nodes + response.edges.len();
+    let start = usize::try_from(page.offset)
+        .unwrap_or(usize::MAX)
+        .min(total);
+    let end = start.saturating_add(page.size).min(total);
+    response.path = response.path[start.min(nodes)..end.min(nodes)].to_vec();
+    response.edges =
+        response.edges[start.saturating_sub(nodes)..end.saturating_sub(nodes)].to_vec();
+    if end < total {
+        response.next_cursor = Some(Cursor::new(page.state_hash, end as u64).encode());
+    }
+    response
+}
+
+/// Parse a query written in the textual query language and execute it.
+///
+/// The parsed query runs exactly like its `/query` equivalent. A syntax
+/// error returns `400 Bad Request` with the character position in `error`.
+pub async fn text_query_handler(
+    State(state): State<AppState>,
+    Json(request): Json<TextQueryRequest>,
 ) -> impl IntoResponse {
     let session = state.session.read().await;
-    match execute_query_session(&session, &request) {
+    let mut budget = state.query_budget.grant(request.timeout_ms);
+    let result = parse_query(&request.query).and_then(|query| {
+        let json = QueryRequest::try_from(&query.query_type)?;
+        let past = session_as_of(&session, request.as_of_seq, &mut budget)?;
+        execute_query_within(past.as_ref().unwrap_or(&session), &json, &mut budget)
+    });
+    query_result(result)
+}
+
+/// Run a query pipeline: every stage under one read lock, against one state.
+///
+/// A stage runs once per node of the previous stage's output, through the
+/// same executor as `/query`, so the limits of the matching query apply.
+/// All stages share one visit budget.
+pub async fn pipeline_handler(
+    State(state): State<AppState>,
+    Json(request): Json<PipelineRequest>,
+) -> impl IntoResponse {
+    let live = state.session.read().await;
+    let mut budget = state.query_budget.grant(request.timeout_ms);
+    let result = session_as_of(&live, request.as_of_seq, &mut budget).and_then(|past| {
+        let mutation_seq = match request.as_of_seq {
+            Some(seq) => Some(seq),
+            None => live.mutation_seq()?,
+        };
+        let stages =
+            execute_pipeline(past.as_ref().unwrap_or(&live), &request.stages, &mut budget)?;
+        let mut response = PipelineResponse::success(stages, mutation_seq);
+        response.truncated = budget.is_exhausted();
+        Ok(response)
+    });
+    match result {
+        Ok(response) => (StatusCode::OK, Json(response)),
+        Err(e) => {
+            let status = match &e {
+                KremisError::InvalidSignal | KremisError::HistoryReplayLimit(_) => {
+                    StatusCode::BAD_REQUEST
+                }
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (
+                status,
+                Json(PipelineResponse::error(format!("Pipeline failed: {}", e))),
+            )
+        }
+    }
+}
+
+/// Run the stages of a pipeline in order. The first stage, and only the
+/// first, must be a `lookup`.
+fn execute_pipeline(
+    session: &Session,
+    stages: &[PipelineStage],
+    budget: &mut VisitBudget,
+) -> Result<Vec<StageResultJson>, KremisError> {
+    if stages.is_empty() || stages.len() > MAX_PIPELINE_STAGES {
+        return Err(KremisError::InvalidSignal);
+    }
+    let mut input = Vec::new();
+    let mut results = Vec::with_capacity(stages.len());
+    for (index, stage) in stages.iter().enumerate() {
+        if matches!(stage, PipelineStage::Lookup { .. }) != (index == 0) {
+            return Err(KremisError::InvalidSignal);
+        }
+        let result = execute_stage(session, stage, &input, budget)?;
+        input.clone_from(&result.nodes);
+        results.push(result);
+    }
+    Ok(results)
+}
+
+/// Run one pipeline stage on the node set produced by the previous one.
+fn execute_stage(
+    session: &Session,
+    stage: &PipelineStage,
+    input: &[u64],
+    budget: &mut VisitBudget,
+) -> Result<StageResultJson, KremisError> {
+    let requests = match stage {
+        PipelineStage::Lookup {
+            entity_ids,
+            entity_keys,
+        } => {
+            let count = entity_ids.len() + entity_keys.len();
+            if count == 0 || count > MAX_PIPELINE_NODES {
+                return Err(KremisError::InvalidSignal);
+            }
+            entity_ids
+                .iter()
+                .map(|&id| QueryRequest::Lookup {
+                    entity_id: Some(id),
+                    entity_key: None,
+                })
+                .chain(entity_keys.iter().map(|key| QueryRequest::Lookup {
+                    entity_id: None,
+                    entity_key: Some(key.clone()),
+                }))
+                .collect()
+        }
+        PipelineStage::Traverse {
+            depth,
+            min_weight,
+            backward,
+            relation,
+        } => {
+            if *backward && min_weight.is_some() {
+                return Err(KremisError::InvalidSignal);
+            }
+            input
+                .iter()
+                .map(|&node_id| match (*backward, *min_weight) {
+                    (true, _) => QueryRequest::TraverseBackward {
+                        node_id,
+                        depth: *depth,
+                        relation: relation.clone(),
+                    },
+                    (false, Some(min_weight)) => QueryRequest::TraverseFiltered {
+                        node_id,
+                        depth: *depth,
+                        min_weight,
+                        top_k: None,
+                        relation: relation.clone(),
+                    },
+                    (false, None) => QueryRequest::Traverse {
+                        node_id,
+                        depth: *depth,
+                        relation: relation.clone(),
+                    },
+                })
+                .collect()
+        }
+        PipelineStage::Properties => input
+            .iter()
+            .map(|&node_id| QueryRequest::Properties { node_id })
+            .collect::<Vec<_>>(),
+    };
+
+    let mut nodes = BTreeSet::new();
+    let mut edges = BTreeSet::new();
+    let mut properties = Vec::new();
+    let mut diagnostic = None;
+    for request in &requests {
+        let response = execute_query_within(session, request, budget)?;
+        if !response.found {
+            diagnostic = diagnostic.or(response.diagnostic);
+            continue;
+        }
+        match request {
+            QueryRequest::Properties { node_id } => properties.push(NodePropertiesJson {
+                node_id: *node_id,
+                properties: response.properties,
+            }),
+            _ => {
+                nodes.extend(response.path);
+                edges.extend(
+                    response
+                        .edges
+                        .into_iter()
+                        .map(|e| (e.from, e.to, e.relation, e.weight)),
+                );
+            }
+        }
+    }
+
+    let (nodes, found) = match stage {
+        PipelineStage::Properties => (input.to_vec(), !properties.is_empty()),
+        _ => {
+            if nodes.len() > MAX_PIPELINE_NODES {
+                return Err(KremisError::InvalidSignal);
+            }
+            let found = !nodes.is_empty();
+            (nodes.into_iter().collect(), found)
+        }
+    };
+    let grounding = match (found, stage) {
+        (false, _) => "unknown",
+        (true, PipelineStage::Traverse { .. }) => "inference",
+        (true, _) => "fact",
+    };
+    let diagnostic = match (found, requests.is_empty()) {
+        (true, _) => None,
+        (false, true) => Some("empty_input".to_string()),
+        (false, false) => diagnostic,
+    };
+    Ok(StageResultJson {
+        stage: stage.name().to_string(),
+        found,
+        nodes,
+        edges: edges
+            .into_iter()
+            .map(|(from, to, relation, weight)| EdgeJson {
+                from,
+                to,
+                relation,
+                weight,
+            })
+            .collect(),
+        properties,
+        grounding: grounding.to_string(),
+        diagnostic,
+    })
+}
+
+/// Status and body of a `/query` or `/query/text` result.
+fn query_result(result: Result<QueryResponse, KremisError>) -> (StatusCode, Json<QueryResponse>) {
+    match result {
         Ok(response) => (StatusCode::OK, Json(response)),
         Err(e) => {
             let status = match &e {
-                KremisError::InvalidSignal => StatusCode::BAD_REQUEST,
+                KremisError::InvalidSignal
+                | KremisError::QueryParse { .. }
+                | KremisError::PathEnumerationLimit(_)
+                | KremisError::HistoryReplayLimit(_) => StatusCode::BAD_REQUEST,
+                KremisError::StaleCursor => StatusCode::CONFLICT,
                 _ => StatusCode::INTERNAL_SERVER_ERROR,
             };
             (
@@ -237,6 +790,35 @@ pub async fn query_handler(
     }
 }
 
+/// Rebuild the session state at `as_of_seq`, if one is given.
+///
+/// Returns `None` for the current state, and `KremisError::InvalidSignal`
+/// when the backend keeps no history or the sequence number is in the future.
+fn session_as_of(
+    session: &Session,
+    as_of_seq: Option<u64>,
+    budget: &mut VisitBudget,
+) -> Result<Option<Session>, KremisError> {
+    as_of_seq
+        .map(|seq| {
+            session
+                .snapshot_at_within(seq, budget)
+                .map(Session::with_graph)
+        })
+        .transpose()
+}
+
+/// Parse an optional relation label, applying the attribute validation rules.
+fn parse_relation(relation: Option<&str>) -> Result<Option<Attribute>, KremisError> {
+    relation
+        .map(|r| {
+            let relation = Attribute::new(r);
+            Ingestor::validate_relation(&relation)?;
+            Ok(relation)
+        })
+        .transpose()
+}
+
 /// Validate that depth is within bounds to prevent DoS.
 fn validate_depth(depth: usize) -> Result<(), KremisError> {
     if depth > MAX_TRAVERSAL_DEPTH {
@@ -245,9 +827,121 @@ fn validate_depth(depth: usize) -> Result<(), KremisError> {
     Ok(())
 }
 
+/// Validate the number of paths a path enumeration may return.
+fn validate_path_count(count: usize) -> Result<(), KremisError> {
+    if count == 0 || count > MAX_PATH_RESULTS {
+        return Err(KremisError::InvalidSignal);
+    }
+    Ok(())
+}
+
+/// Validate and convert the constraints of a `strongest_path` request.
+fn path_constraints(
+    avoid: &[u64],
+    waypoints: &[u64],
+    max_hops: Option<usize>,
+    min_weight: Option<i64>,
+) -> Result<PathConstraints, KremisError> {
+    if avoid.len() > MAX_PATH_CONSTRAINT_NODES || waypoints.len() > MAX_PATH_CONSTRAINT_NODES {
+        return Err(KremisError::InvalidSignal);
+    }
+    if let Some(max_hops) = max_hops {
+        validate_depth(max_hops)?;
+    }
+    Ok(PathConstraints {
+        avoid: avoid.iter().map(|n| NodeId(*n)).collect(),
+        waypoints: waypoints.iter().map(|n| NodeId(*n)).collect(),
+        max_hops,
+        min_weight: min_weight.map(EdgeWeight::new),
+    })
+}
+
+/// Why a path query between two nodes came back empty.
+fn path_not_found_reason(
+    session: &Session,
+    start: u64,
+    end: u64,
+) -> Result<&'static str, KremisError> {
+    Ok(if session.traverse(NodeId(start), 0, None)?.is_none() {
+        "start_not_found"
+    } else if session.traverse(NodeId(end), 0, None)?.is_none() {
+        "end_not_found"
+    } else {
+        "no_path"
+    })
+}
+
+/// Response of `paths` and `k_strongest_paths`.
+fn paths_response(
+    session: &Session,
+    start: u64,
+    end: u64,
+    paths: Option<Vec<RankedPath>>,
+) -> Result<QueryResponse, KremisError> {
+    match paths {
+        Some(paths) if !paths.is_empty() => Ok(QueryResponse::with_paths(&paths)),
+        _ => {
+            Ok(QueryResponse::not_found()
+                .with_diagnostic(path_not_found_reason(session, start, end)?))
+        }
+    }
+}
+
+/// Validate and convert the variables and edges of a `pattern` request.
+///
+/// Variable names must be unique, non-empty and made of ASCII letters,
+/// digits and `_`; edges refer to variables by name.
+fn parse_pattern(
+    variables: &[PatternVariableJson],
+    edges: &[PatternEdgeJson],
+) -> Result<Pattern, KremisError> {
+    let mut names: Vec<&str> = Vec::with_capacity(variables.len());
+    let mut pattern = Pattern::default();
+    for variable in variables {
+        let name = variable.name.as_str();
+        let well_formed = !name.is_empty()
+            && name.len() <= MAX_ATTRIBUTE_LENGTH
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !well_formed || names.contains(&name) {
+            return Err(KremisError::InvalidSignal);
+        }
+        names.push(name);
+
+        let mut properties = Vec::with_capacity(variable.properties.len());
+        for property in &variable.properties {
+            let attribute = Attribute::new(&property.attribute);
+            let value = Value::new(&property.value);
+            // Same length and emptiness rules as ingestion; the entity is unused.
+            Ingestor::validate(&Signal::new(EntityId(0), attribute.clone(), value.clone()))?;
+            properties.push((attribute, value));
+        }
+        pattern.variables.push(PatternVariable {
+            node: variable.node_id.map(NodeId),
+            properties,
+        });
+    }
+
+    let index_of = |name: &str| {
+        names
+            .iter()
+            .position(|known| *known == name)
+            .ok_or(KremisError::InvalidSignal)
+    };
+    for edge in edges {
+        pattern.edges.push(PatternEdge {
+            from: index_of(&edge.from)?,
+            to: index_of(&edge.to)?,
+            relation: parse_relation(edge.relation.as_deref())?,
+            min_weight: edge.min_weight.map(EdgeWeight::new),
+        });
+    }
+    Ok(pattern)
+}
+
 /// Apply top-K filtering to an artifact: keep only the K highest-weight edges.
 ///
-/// Ordering is deterministic: weight descending, then `from` ascending, then `to` ascending.
+/// Ordering is deterministic: weight descending, then `from` ascending, then `to` ascending,
+/// then relation ascending (unlabeled first).
 /// The path is rebuilt to include only nodes that appear in the top-K edges, plus the
 /// original start node (first element of the original path) if it was present.
 fn apply_top_k(mut artifact: Artifact, top_k: Option<usize>) -> Artifact {
@@ -260,13 +954,14 @@ fn apply_top_k(mut artifact: Artifact, top_k: Option<usize>) -> Artifact {
         Some(e) => e,
     };
     edges.sort_by(|a, b| {
-        b.2.value()
-            .cmp(&a.2.value())
+        b.3.value()
+            .cmp(&a.3.value())
             .then_with(|| a.0.cmp(&b.0))
             .then_with(|| a.1.cmp(&b.1))
+            .then_with(|| a.2.cmp(&b.2))
     });
     edges.truncate(k);
-    let in_edges: BTreeSet<NodeId> = edges.iter().flat_map(|(f, t, _)| [*f, *t]).collect();
+    let in_edges: BTreeSet<NodeId> = edges.iter().flat_map(|(f, t, _, _)| [*f, *t]).collect();
     let start = artifact.path.first().copied();
     let path: Vec<NodeId> = artifact
         .path
@@ -285,35 +980,93 @@ fn classify_grounding(request: &QueryRequest, found: bool) -> &'static str {
         return "unknown";
     }
     match request {
-        QueryRequest::Lookup { .. } | QueryRequest::Properties { .. } => "fact",
+        QueryRequest::Lookup { .. }
+        | QueryRequest::Properties { .. }
+        | QueryRequest::FindByProperty { .. }
+        | QueryRequest::Search { .. } => "fact",
         _ => "inference",
     }
 }
 
 /// Execute a query using Session methods (works with both InMemory and Persistent backends).
-fn execute_query_session(
+pub(crate) fn execute_query_session(
+    session: &Session,
+    request: &QueryRequest,
+) -> Result<QueryResponse, KremisError> {
+    execute_query_within(session, request, &mut VisitBudget::unlimited())
+}
+
+/// Execute a query under a visit budget. Traversals, `strongest_path`,
+/// `reachable`, `paths`, `k_strongest_paths` and `intersect` spend it; the
+/// response is marked `truncated` when it runs out.
+fn execute_query_within(
     session: &Session,
     request: &QueryRequest,
+    budget: &mut VisitBudget,
 ) -> Result<QueryResponse, KremisError> {
-    let mut response = execute_query_inner(session, request)?;
+    execute_query_prefix(session, request, usize::MAX, budget)
+}
+
+/// [`execute_query_within`], with a forward traversal listing only its first
+/// `max_nodes` nodes (see `GraphStore::traverse_prefix_within`). A
+/// `traverse_filtered` with `top_k` ranks every edge, so it always runs in
+/// full.
+fn execute_query_prefix(
+    session: &Session,
+    request: &QueryRequest,
+    max_nodes: usize,
+    budget: &mut VisitBudget,
+) -> Result<QueryResponse, KremisError> {
+    let mut response = execute_query_inner(session, request, max_nodes, budget)?;
     response.grounding = classify_grounding(request, response.found).to_string();
+    response.truncated = budget.is_exhausted();
     Ok(response)
 }
 
 fn execute_query_inner(
     session: &Session,
     request: &QueryRequest,
+    max_nodes: usize,
+    budget: &mut VisitBudget,
 ) -> Result<QueryResponse, KremisError> {
     match request {
-        QueryRequest::Lookup { entity_id } => match session.lookup_entity(EntityId(*entity_id)) {
-            Some(node_id) => Ok(QueryResponse::with_path(vec![node_id])),
-            None => Ok(QueryResponse::not_found().with_diagnostic("entity_not_found")),
-        },
-
-        QueryRequest::Traverse { node_id, depth } => {
-            // Validate depth to prevent DoS
+        QueryRequest::Lookup {
+            entity_id,
+            entity_key,
+        } => {
+            let entity = match entity_ref(*entity_id, entity_key.as_deref())? {
+                EntityRef::Id(entity) => entity,
+                EntityRef::Key(key) => match session.resolve_entity_key(key)? {
+                    Some(entity) => entity,
+                    None => {
+                        return Ok(
+                            QueryResponse::not_found().with_diagnostic("entity_key_not_found")
+                        );
+                    }
+                },
+            };
+            match session.lookup_entity(entity) {
+                Some(node_id) => Ok(QueryResponse::with_path(vec![node_id])),
+                None => Ok(QueryResponse::not_found().with_diagnostic("entity_not_found")),
+            }
+        }
+
+        QueryRequest::Traverse {
+            node_id,
+            depth,
+            relation,
+        } => {
+            // Validate depth to prevent DoS
             validate_depth(*depth)?;
-            match session.traverse(NodeId(*node_id), *depth)? {
+            let relation = parse_relation(relation.as_deref())?;
+            match session.traverse_prefix_within(
+                NodeId(*node_id),
+                *depth,
+                None,
+                relation.as_ref(),
+                max_nodes,
+                budget,
+            )? {
                 Some(artifact) => Ok(QueryResponse::with_artifact(&artifact)),
                 None => Ok(QueryResponse::not_found().with_diagnostic("node_not_found")),
             }
@@ -324,13 +1077,23 @@ fn execute_query_inner(
             depth,
             min_weight,
             top_k,
+            relation,
         } => {
             // Validate depth to prevent DoS
             validate_depth(*depth)?;
-            match session.traverse_filtered(
+            let relation = parse_relation(relation.as_deref())?;
+            let max_nodes = if top_k.is_some() {
+                usize::MAX
+            } else {
+                max_nodes
+            };
+            match session.traverse_prefix_within(
                 NodeId(*node_id),
                 *depth,
-                EdgeWeight::new(*min_weight),
+                Some(EdgeWeight::new(*min_weight)),
+                relation.as_ref(),
+                max_nodes,
+                budget,
             )? {
                 Some(artifact) => {
                     let artifact = apply_top_k(artifact, *top_k);
@@ -340,19 +1103,140 @@ fn execute_query_inner(
             }
         }
 
-        QueryRequest::StrongestPath { start, end } => {
-            match session.strongest_path(NodeId(*start), NodeId(*end))? {
+        QueryRequest::StrongestPath {
+            start,
+            end,
+            relation,
+            avoid,
+            waypoints,
+            max_hops,
+            min_weight,
+        } => {
+            let relation = parse_relation(relation.as_deref())?;
+            let constraints = path_constraints(avoid, waypoints, *max_hops, *min_weight)?;
+            match session.strongest_path_within(
+                NodeId(*start),
+                NodeId(*end),
+                relation.as_ref(),
+                &constraints,
+                budget,
+            )? {
                 Some(path) => Ok(QueryResponse::with_path(path)),
-                None => {
-                    let reason = if session.traverse(NodeId(*start), 0)?.is_none() {
-                        "start_not_found"
-                    } else if session.traverse(NodeId(*end), 0)?.is_none() {
+                None if budget.is_exhausted() => {
+                    Ok(QueryResponse::not_found().with_diagnostic("budget_exhausted"))
+                }
+                None => Ok(QueryResponse::not_found()
+                    .with_diagnostic(path_not_found_reason(session, *start, *end)?)),
+            }
+        }
+
+        QueryRequest::TraverseBackward {
+            node_id,
+            depth,
+            relation,
+        } => {
+            // Validate depth to prevent DoS
+            validate_depth(*depth)?;
+            let relation = parse_relation(relation.as_deref())?;
+            match session.traverse_backward(NodeId(*node_id), *depth, relation.as_ref())? {
+                Some(artifact) => Ok(QueryResponse::with_artifact(&artifact)),
+                None => Ok(QueryResponse::not_found().with_diagnostic("node_not_found")),
+            }
+        }
+
+        QueryRequest::StrongestPathBackward {
+            start,
+            end,
+            relation,
+        } => {
+            let relation = parse_relation(relation.as_deref())?;
+            match session.strongest_path_backward(
+                NodeId(*start),
+                NodeId(*end),
+                relation.as_ref(),
+            )? {
+                Some(path) => Ok(QueryResponse::with_path(path)),
+                None => Ok(QueryResponse::not_found()
+                    .with_diagnostic(path_not_found_reason(session, *start, *end)?)),
+            }
+        }
+
+        QueryRequest::Reachable {
+            start,
+            end,
+            relation,
+        } => {
+            let relation = parse_relation(relation.as_deref())?;
+            match session.reachable_within(
+                NodeId(*start),
+                NodeId(*end),
+                relation.as_ref(),
+                budget,
+            )? {
+                Some(Reachability::Reachable(path)) => Ok(QueryResponse::with_path(path)),
+                Some(Reachability::Unreachable(closure)) => {
+                    // The closure of `start` is the evidence for the negative
+                    // answer, so it is returned alongside `found: false`.
+                    let mut response = QueryResponse::with_artifact(&closure);
+                    response.found = false;
+                    let reason = if session.traverse(NodeId(*end), 0, None)?.is_none() {
                         "end_not_found"
                     } else {
                         "no_path"
                     };
-                    Ok(QueryResponse::not_found().with_diagnostic(reason))
+                    Ok(response.with_diagnostic(reason))
+                }
+                None if budget.is_exhausted() => {
+                    Ok(QueryResponse::not_found().with_diagnostic("budget_exhausted"))
+                }
+                None => Ok(QueryResponse::not_found().with_diagnostic("start_not_found")),
+            }
+        }
+
+        QueryRequest::Paths {
+            start,
+            end,
+            max_hops,
+            limit,
+            relation,
+        } => {
+            validate_depth(*max_hops)?;
+            validate_path_count(*limit)?;
+            let relation = parse_relation(relation.as_deref())?;
+            match session.simple_paths_within(
+                NodeId(*start),
+                NodeId(*end),
+                *max_hops,
+                *limit,
+                relation.as_ref(),
+                budget,
+            )? {
+                None if budget.is_exhausted() => {
+                    Ok(QueryResponse::not_found().with_diagnostic("budget_exhausted"))
                 }
+                paths => paths_response(session, *start, *end, paths),
+            }
+        }
+
+        QueryRequest::KStrongestPaths {
+            start,
+            end,
+            k,
+            relation,
+        } => {
+            validate_path_count(*k)?;
+            let relation = parse_relation(relation.as_deref())?;
+            match session.k_strongest_paths_within(
+                NodeId(*start),
+                NodeId(*end),
+                *k,
+                relation.as_ref(),
+                budget,
+            )? {
+                None if budget.is_exhausted() => {
+                    Ok(QueryResponse::not_found().with_diagnostic("budget_exhausted"))
+                }
+                paths => paths_response(session, *start, *end, paths),
             }
         }
 
@@ -362,10 +1246,12 @@ fn execute_query_inner(
                 return Err(KremisError::InvalidSignal);
             }
             let node_ids: Vec<NodeId> = nodes.iter().map(|n| NodeId(*n)).collect();
-            let result = session.intersect(&node_ids)?;
+            let result = session.intersect_within(&node_ids, budget)?;
             let is_empty = result.is_empty();
             let mut response = QueryResponse::with_path(result);
-            if is_empty {
+            if budget.is_exhausted() {
+                response = response.with_diagnostic("budget_exhausted");
+            } else if is_empty {
                 response = response.with_diagnostic("no_common_neighbors");
             }
             Ok(response)
@@ -374,8 +1260,15 @@ fn execute_query_inner(
         QueryRequest::Related { node_id, depth } => {
             // Validate depth to prevent DoS
             validate_depth(*depth)?;
-            // For Related queries, use compose which handles both backends
-            match session.compose(NodeId(*node_id), *depth)? {
+            // A related query is a forward traversal over every relation.
+            match session.traverse_prefix_within(
+                NodeId(*node_id),
+                *depth,
+                None,
+                None,
+                max_nodes,
+                budget,
+            )? {
                 Some(artifact) => Ok(QueryResponse::with_artifact(&artifact)),
                 None => Ok(QueryResponse::not_found().with_diagnostic("node_not_found")),
             }
@@ -397,6 +1290,66 @@ fn execute_query_inner(
             }
             Err(e) => Err(e),
         },
+
+        QueryRequest::FindByProperty {
+            attribute,
+            value,
+            after,
+            limit,
+        } => {
+            if *limit == 0 || *limit > MAX_PROPERTY_MATCHES {
+                return Err(KremisError::InvalidSignal);
+            }
+            let attribute = Attribute::new(attribute);
+            let value = Value::new(value);
+            // Same length and emptiness rules as ingestion; the entity is unused.
+            Ingestor::validate(&Signal::new(EntityId(0), attribute.clone(), value.clone()))?;
+
+            // Fetch one extra match to learn whether another page follows.
+            let mut nodes =
+                session.find_by_property(&attribute, &value, after.map(NodeId), limit + 1)?;
+            let next_after = if nodes.len() > *limit {
+                nodes.truncate(*limit);
+                nodes.last().map(|n| n.0)
+            } else {
+                None
+            };
+            if nodes.is_empty() {
+                return Ok(QueryResponse::not_found().with_diagnostic("no_matching_nodes"));
+            }
+            Ok(QueryResponse::with_path(nodes).with_next_after(next_after))
+        }
+
+        QueryRequest::Search { query, limit } => {
+            if *limit == 0 || *limit > MAX_SEARCH_RESULTS {
+                return Err(KremisError::InvalidSignal);
+            }
+            let terms = tokenize(query).len();
+            if terms == 0 || terms > MAX_SEARCH_TERMS {
+                return Err(KremisError::InvalidSignal);
+            }
+            let ranked = session.search(query, *limit)?;
+            if ranked.is_empty() {
+                return Ok(QueryResponse::not_found().with_diagnostic("no_matching_nodes"));
+            }
+            Ok(QueryResponse::with_ranking(ranked))
+        }
+
+        QueryRequest::Pattern {
+            variables,
+            edges,
+            limit,
+        } => {
+            if *limit == 0 || *limit > MAX_PATTERN_RESULTS {
+                return Err(KremisError::InvalidSignal);
+            }
+            let pattern = parse_pattern(variables, edges)?;
+            let matches = session.match_pattern(&pattern, *limit)?;
+            if matches.is_empty() {
+                return Ok(QueryResponse::not_found().with_diagnostic("no_match"));
+            }
+            Ok(QueryResponse::with_bindings(&matches))
+        }
     }
 }
 
@@ -404,22 +1357,45 @@ fn execute_query_inner(
 // HASH HANDLER
 // =============================================================================
 
+/// Query string of `GET /hash`.
+#[derive(Debug, Deserialize)]
+pub struct HashParams {
+    /// Hash the state right after this mutation instead of the current one.
+    pub as_of_seq: Option<u64>,
+}
+
 /// Compute BLAKE3 cryptographic hash of graph canonical export.
-pub async fn hash_handler(State(state): State<AppState>) -> impl IntoResponse {
+pub async fn hash_handler(
+    State(state): State<AppState>,
+    Query(params): Query<HashParams>,
+) -> impl IntoResponse {
     let session = state.session.read().await;
-    let graph = match session.export_graph_snapshot() {
-        Ok(g) => g,
-        Err(e) => {
+    // The current state is streamed through the hasher; a past one is
+    // rebuilt from the mutation log first.
+    let mut writer = HashingWriter::new(std::io::sink());
+    let written = match params.as_of_seq {
+        Some(seq) => session
+            .snapshot_at_within(seq, &mut state.query_budget.grant(None))
+            .and_then(|graph| write_canonical(&graph, &mut writer)),
+        None => session.write_canonical(&mut writer),
+    };
+    let header = match written {
+        Ok(header) => header,
+        Err(KremisError::InvalidSignal) => {
             return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(
-                    serde_json::json!({"success": false, "error": format!("Snapshot failed: {}", e)}),
-                ),
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "success": false,
+                    "error": "as_of_seq needs a persistent backend and a logged sequence number"
+                })),
+            );
+        }
+        Err(e @ KremisError::HistoryReplayLimit(_)) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"success": false, "error": e.to_string()})),
             );
         }
-    };
-    let hash = match canonical_crypto_hash(&graph) {
-        Ok(h) => h,
         Err(e) => {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -427,18 +1403,191 @@ pub async fn hash_handler(State(state): State<AppState>) -> impl IntoResponse {
             );
         }
     };
-    let checksum = canonical_checksum(&graph);
+    let mutation_seq = match params.as_of_seq {
+        Some(seq) => Some(seq),
+        None => match session.mutation_seq() {
+            Ok(seq) => seq,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(
+                        serde_json::json!({"success": false, "error": format!("Snapshot failed: {}", e)}),
+                    ),
+                );
+            }
+        },
+    };
+    let state_hash = writer.finalize().0;
+    if let Some(seq) = mutation_seq {
+        state
+            .state_hashes
+            .record((seq, params.as_of_seq.is_some()), state_hash);
+    }
+    let hash: String = state_hash.iter().map(|b| format!("{b:02x}")).collect();
+    let checksum = header.checksum;
     (
         StatusCode::OK,
         Json(serde_json::json!({
             "success": true,
             "hash": hash,
             "algorithm": "blake3",
-            "checksum": checksum
+            "checksum": checksum,
+            "mutation_seq": mutation_seq
         })),
     )
 }
 
+// =============================================================================
+// COMPONENTS HANDLER
+// =============================================================================
+
+/// Query parameters for the components endpoint.
+#[derive(Debug, Deserialize)]
+pub struct ComponentsParams {
+    /// Weak (direction ignored) or strong (direction followed) components.
+    #[serde(default)]
+    pub kind: ComponentKind,
+    /// Only follow edges with this relation label.
+    pub relation: Option<String>,
+    /// Analyse the state right after this mutation instead of the current one.
+    pub as_of_seq: Option<u64>,
+    /// Work budget in node and edge visits; `None` takes the server's default.
+    pub timeout_ms: Option<u64>,
+}
+
+/// Split the graph into connected components, reporting cycles for strong
+/// components.
+///
+/// Components are only meaningful for the whole graph, so a run that exceeds
+/// its visit budget returns `400 Bad Request` instead of a partial answer.
+pub async fn components_handler(
+    State(state): State<AppState>,
+    Query(params): Query<ComponentsParams>,
+) -> impl IntoResponse {
+    let session = state.session.read().await;
+    let mut budget = state.query_budget.grant(params.timeout_ms);
+    let result = session_as_of(&session, params.as_of_seq, &mut budget).and_then(|past| {
+        let session = past.as_ref().unwrap_or(&session);
+        let relation = parse_relation(params.relation.as_deref())?;
+        match params.kind {
+            ComponentKind::Weak => {
+                session.weakly_connected_components_within(relation.as_ref(), &mut budget)
+            }
+            ComponentKind::Strong => {
+                session.strongly_connected_components_within(relation.as_ref(), &mut budget)
+            }
+        }
+    });
+    match result {
+        Ok(Some(components)) => (
+            StatusCode::OK,
+            Json(ComponentsResponse::success(params.kind, &components)),
+        ),
+        Ok(None) => (
+            StatusCode::BAD_REQUEST,
+            Json(ComponentsResponse::error(
+                params.kind,
+                "Components failed: visit budget exhausted; raise timeout_ms or filter by relation",
+            )),
+        ),
+        Err(e) => {
+            let status = match &e {
+                KremisError::InvalidSignal | KremisError::HistoryReplayLimit(_) => {
+                    StatusCode::BAD_REQUEST
+                }
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (
+                status,
+                Json(ComponentsResponse::error(
+                    params.kind,
+                    format!("Components failed: {}", e),
+                )),
+            )
+        }
+    }
+}
+
+// =============================================================================
+// CENTRALITY HANDLER
+// =============================================================================
+
+/// Query parameters for the centrality endpoint.
+#[derive(Debug, Deserialize)]
+pub struct CentralityParams {
+    /// The measure to compute.
+    #[serde(default)]
+    pub metric: CentralityKind,
+    /// Number of top nodes to return.
+    #[serde(default = "default_centrality_top")]
+    pub top: usize,
+    /// PageRank iterations; ignored by the other metrics.
+    #[serde(default = "default_pagerank_iterations")]
+    pub iterations: usize,
+    /// Only follow edges with this relation label.
+    pub relation: Option<String>,
+    /// Analyse the state right after this mutation instead of the current one.
+    pub as_of_seq: Option<u64>,
+}
+
+fn default_centrality_top() -> usize {
+    10
+}
+
+fn default_pagerank_iterations() -> usize {
+    DEFAULT_PAGERANK_ITERATIONS
+}
+
+/// Rank nodes by a centrality measure and return the top ones.
+pub async fn centrality_handler(
+    State(state): State<AppState>,
+    Query(params): Query<CentralityParams>,
+) -> impl IntoResponse {
+    let metric = match params.metric {
+        CentralityKind::Degree => CentralityMetric::Degree,
+        CentralityKind::WeightedDegree => CentralityMetric::WeightedDegree,
+        CentralityKind::Pagerank => CentralityMetric::PageRank {
+            iterations: params.iterations,
+        },
+        CentralityKind::Betweenness => CentralityMetric::Betweenness,
+    };
+    let session = state.session.read().await;
+    let mut budget = state.query_budget.grant(None);
+    let result = session_as_of(&session, params.as_of_seq, &mut budget).and_then(|past| {
+        if params.top == 0 || params.top > MAX_CENTRALITY_RESULTS {
+            return Err(KremisError::InvalidSignal);
+        }
+        let session = past.as_ref().unwrap_or(&session);
+        let relation = parse_relation(params.relation.as_deref())?;
+        session.centrality(metric, relation.as_ref())
+    });
+    match result {
+        Ok(scores) => (
+            StatusCode::OK,
+            Json(CentralityResponse::success(
+                params.metric,
+                metric.scale(),
+                &top_n(&scores, params.top),
+            )),
+        ),
+        Err(e) => {
+            let status = match &e {
+                KremisError::InvalidSignal
+                | KremisError::HistoryReplayLimit(_)
+                | KremisError::BetweennessOverflow => StatusCode::BAD_REQUEST,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (
+                status,
+                Json(CentralityResponse::error(
+                    params.metric,
+                    format!("Centrality failed: {}", e),
+                )),
+            )
+        }
+    }
+}
+
 // =============================================================================
 // METRICS HANDLER
 // =============================================================================
@@ -495,45 +1644,385 @@ pub async fn metrics_handler(State(state): State<AppState>) -> impl IntoResponse
 // EXPORT HANDLER
 // =============================================================================
 
+/// Bytes of the `/export` body sent at a time.
+const EXPORT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Chunks of the `/export` body buffered ahead of a slow client.
+const EXPORT_CHUNKS_IN_FLIGHT: usize = 4;
+
 /// Export graph in canonical format.
 ///
-/// # M3 Fix
+/// The body is streamed: the export is base64-encoded into the JSON
+/// envelope chunk by chunk, so neither the export nor its encoding is
+/// buffered. A persistent graph is read from a pinned read transaction and
+/// an in-memory graph from a copy taken under the read lock, so writes go on
+/// while a client downloads. An error before the first chunk returns `500`;
+/// a later one aborts the body.
+pub async fn export_handler(State(state): State<AppState>) -> Response {
+    let source = {
+        let session = state.session.read().await;
+        match session.backend() {
+            StorageBackend::Persistent(redb) => match redb.canonical_export() {
+                Ok(pinned) => ExportSource::Pinned(pinned),
+                Err(e) => return export_error(e.to_string()),
+            },
+            StorageBackend::InMemory(graph) => ExportSource::Snapshot(Box::new(graph.clone())),
+        }
+    };
+
+    let (tx, mut rx) = mpsc::channel(EXPORT_CHUNKS_IN_FLIGHT);
+    tokio::task::spawn_blocking(move || {
+        let mut out = ChunkWriter {
+            tx,
+            buf: Vec::with_capacity(EXPORT_CHUNK_SIZE),
+        };
+        if let Err(e) = source.write_response(&mut out) {
+            let _ = out
+                .tx
+                .blocking_send(Err(std::io::Error::other(e.to_string())));
+        }
+    });
+
+    let first = match rx.recv().await {
+        Some(Ok(chunk)) => chunk,
+        Some(Err(e)) => return export_error(e.to_string()),
+        None => return export_error("the export task stopped".to_string()),
+    };
+    let chunks = futures_util::stream::unfold((Some(first), rx), |(first, mut rx)| async move {
+        match first {
+            Some(chunk) => Some((Ok(chunk), (None, rx))),
+            None => rx.recv().await.map(|chunk| (chunk, (None, rx))),
+        }
+    });
+    (
+        [(header::CONTENT_TYPE, "application/json")],
+        Body::from_stream(chunks),
+    )
+        .into_response()
+}
+
+/// What `/export` reads from: a pinned redb state, or a copy of the
+/// in-memory graph. Neither holds the session lock while the body streams.
+enum ExportSource {
+    Pinned(RedbExport),
+    Snapshot(Box<Graph>),
+}
+
+impl ExportSource {
+    /// Write the JSON body of a successful `ExportResponse` to `out`.
+    fn write_response(&self, out: &mut ChunkWriter) -> Result<(), KremisError> {
+        let io = |e: std::io::Error| KremisError::IoError(e.to_string());
+        // The checksum is only known once the export is written, but it
+        // comes after `data`, so the envelope opens the same for any.
+        let (open, _) = export_envelope(0)?;
+        out.write_all(open.as_bytes()).map_err(io)?;
+        let mut encoder = base64::write::EncoderWriter::new(
+            &mut *out,
+            &base64::engine::general_purpose::STANDARD,
+        );
+        let header = match self {
+            ExportSource::Pinned(pinned) => write_canonical(pinned, &mut encoder)?,
+            ExportSource::Snapshot(graph) => write_canonical(graph.as_ref(), &mut encoder)?,
+        };
+        let out = encoder.finish().map_err(io)?;
+        let (_, close) = export_envelope(header.checksum)?;
+        out.write_all(close.as_bytes()).map_err(io)?;
+        out.flush().map_err(io)
+    }
+}
+
+/// The JSON of a successful `ExportResponse` with `checksum`, split where
+/// its base64 `data` goes.
+fn export_envelope(checksum: u64) -> Result<(String, String), KremisError> {
+    const DATA: &str = r#""data":""#;
+    let mut json = serde_json::to_string(&ExportResponse::success(Vec::new(), checksum))
+        .map_err(|e| KremisError::SerializationError(e.to_string()))?;
+    let at = json.find(DATA).ok_or_else(|| {
+        KremisError::SerializationError("Export envelope has no data".to_string())
+    })?;
+    let close = json.split_off(at + DATA.len());
+    Ok((json, close))
+}
+
+/// A writer that sends what it is given to the `/export` body in chunks of
+/// `EXPORT_CHUNK_SIZE` bytes.
+struct ChunkWriter {
+    tx: mpsc::Sender<Result<Bytes, std::io::Error>>,
+    buf: Vec<u8>,
+}
+
+impl Write for ChunkWriter {
+    fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+        self.buf.extend_from_slice(data);
+        if self.buf.len() >= EXPORT_CHUNK_SIZE {
+            self.flush()?;
+        }
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        let chunk = Bytes::from(std::mem::replace(
+            &mut self.buf,
+            Vec::with_capacity(EXPORT_CHUNK_SIZE),
+        ));
+        // The receiver is gone once the client disconnects.
+        self.tx
+            .blocking_send(Ok(chunk))
+            .map_err(|_| std::io::Error::from(std::io::ErrorKind::BrokenPipe))
+    }
+}
+
+/// The `500` response of a failed export.
+fn export_error(error: String) -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ExportResponse::error(format!("Export failed: {}", error))),
+    )
+        .into_response()
+}
+
+/// Export the changes from a base state to the current graph.
 ///
-/// This handler now supports both in-memory and persistent backends
-/// by using `export_graph_snapshot()` which builds a graph snapshot
-/// from any backend type.
-pub async fn export_handler(State(state): State<AppState>) -> impl IntoResponse {
-    let session = state.session.read().await;
+/// A `base_hash` the server knows, as the hash of a recently hashed state or
+/// of a snapshot checkpoint, is diffed through the mutation log since that
+/// state; otherwise the uploaded `base` export is merged item by item with
+/// the current graph. Neither state is rebuilt, and the current graph is
+/// pinned as for `/export`, so writes go on while the delta is computed. A
+/// malformed `base_hash`, a base that does not decode, or an unknown
+/// `base_hash` without a `base` returns `400 Bad Request`.
+pub async fn export_delta_handler(
+    State(state): State<AppState>,
+    Json(request): Json<DeltaExportRequest>,
+) -> impl IntoResponse {
+    let failed = |e: KremisError| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(DeltaExportResponse::error(format!("Export failed: {}", e))),
+        )
+    };
+    let base_hash = match request.base_hash.as_deref().map(parse_state_hash) {
+        None => None,
+        Some(Some(hash)) => Some(hash),
+        Some(None) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(DeltaExportResponse::error(
+                    "Invalid base_hash: expected 64 hex digits",
+                )),
+            );
+        }
+    };
+    let (source, known, mutation_seq) = {
+        let session = state.session.read().await;
+        let known = match base_hash.map(|hash| known_state_seq(&state, &session, &hash)) {
+            None | Some(Ok(None)) => None,
+            Some(Ok(Some(seq))) => base_hash.map(|hash| (seq, hash)),
+            Some(Err(e)) => return failed(e),
+        };
+        let source = match session.backend() {
+            StorageBackend::Persistent(redb) => match redb.canonical_export() {
+                Ok(pinned) => ExportSource::Pinned(pinned),
+                Err(e) => return failed(e),
+            },
+            StorageBackend::InMemory(graph) => ExportSource::Snapshot(Box::new(graph.clone())),
+        };
+        match session.mutation_seq() {
+            Ok(mutation_seq) => (source, known, mutation_seq),
+            Err(e) => return failed(e),
+        }
+    };
 
-    // M3 FIX: Use export_graph_snapshot() which works with both backends
-    let graph = match session.export_graph_snapshot() {
-        Ok(g) => g,
-        Err(e) => {
+    let base = match (known, source, request.base.as_deref()) {
+        (Some((seq, hash)), ExportSource::Pinned(pinned), _) => {
+            DeltaBase::Logged { pinned, seq, hash }
+        }
+        (_, source, Some(data)) => match decode_base64(data) {
+            Ok(base) => DeltaBase::Uploaded { source, base },
+            Err(e) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(DeltaExportResponse::error(format!(
+                        "Invalid base export: {}",
+                        e
+                    ))),
+                );
+            }
+        },
+        (_, _, None) => {
+            let error = match request.base_hash {
+                Some(_) => "Unknown base_hash: upload the base export as base",
+                None => "Either base_hash or base is required",
+            };
             return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ExportResponse::error(format!(
-                    "Failed to build graph snapshot: {}",
+                StatusCode::BAD_REQUEST,
+                Json(DeltaExportResponse::error(error)),
+            );
+        }
+    };
+
+    let delta = match tokio::task::spawn_blocking(move || base.delta()).await {
+        Ok(Ok(delta)) => delta,
+        Ok(Err(DeltaError::Base(e))) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(DeltaExportResponse::error(format!(
+                    "Invalid base export: {}",
                     e
                 ))),
             );
         }
+        Ok(Err(DeltaError::Export(e))) => return failed(e),
+        Err(e) => return failed(KremisError::IoError(e.to_string())),
     };
-
-    match export_canonical(&graph) {
+    match delta.to_bytes() {
         Ok(data) => {
-            let checksum = canonical_checksum(&graph);
+            // The target becomes a base the next delta can name by hash.
+            if let Some(seq) = mutation_seq {
+                state.state_hashes.record((seq, false), delta.target_hash);
+            }
+            let hex = |hash: &[u8; 32]| hash.iter().map(|b| format!("{b:02x}")).collect();
             (
                 StatusCode::OK,
-                Json(ExportResponse::success(data, checksum)),
+                Json(DeltaExportResponse::success(
+                    data,
+                    hex(&delta.base_hash),
+                    hex(&delta.target_hash),
+                )),
             )
         }
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ExportResponse::error(format!("Export failed: {}", e))),
-        ),
+        Err(e) => failed(e),
+    }
+}
+
+/// The base of an `/export/delta` request, with the pinned current state.
+enum DeltaBase {
+    /// A logged state, diffed through the mutation log since `seq`.
+    Logged {
+        pinned: RedbExport,
+        seq: u64,
+        hash: [u8; 32],
+    },
+    /// An uploaded export, merged with `source`.
+    Uploaded { source: ExportSource, base: Vec<u8> },
+}
+
+/// Why an `/export/delta` request failed: a bad uploaded base, or anything
+/// else.
+enum DeltaError {
+    Base(KremisError),
+    Export(KremisError),
+}
+
+impl DeltaBase {
+    fn delta(self) -> Result<GraphDelta, DeltaError> {
+        match self {
+            DeltaBase::Logged { pinned, seq, hash } => {
+                pinned.delta_since(seq, hash).map_err(DeltaError::Export)
+            }
+            DeltaBase::Uploaded { source, base } => {
+                let open = || Ok(base.as_slice());
+                match &source {
+                    ExportSource::Pinned(pinned) => stream_delta(open, pinned),
+                    ExportSource::Snapshot(graph) => stream_delta(open, graph.as_ref()),
+                }
+                .map_err(|e| match e {
+                    KremisError::SerializationError(_) => DeltaError::Base(e),
+                    e => DeltaError::Export(e),
+                })
+            }
+        }
     }
 }
 
+/// The mutation sequence number of a state the server knows by its hash: a
+/// recently hashed state, or a snapshot checkpoint.
+fn known_state_seq(
+    state: &AppState,
+    session: &Session,
+    hash: &[u8; 32],
+) -> Result<Option<u64>, KremisError> {
+    match state.state_hashes.seq_of(hash) {
+        Some(seq) => Ok(Some(seq)),
+        None => session.checkpoint_with_hash(hash),
+    }
+}
+
+/// Parse a lowercase or uppercase hex state hash.
+fn parse_state_hash(hex: &str) -> Option<[u8; 32]> {
+    if hex.len() != 64 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let mut hash = [0u8; 32];
+    for (byte, pair) in hash.iter_mut().zip(hex.as_bytes().chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()?;
+    }
+    Some(hash)
+}
+
+// =============================================================================
+// DIFF HANDLER
+// =============================================================================
+
+/// Diff two canonical exports, or an export and the current graph.
+///
+/// An export that does not decode returns `400 Bad Request`.
+pub async fn diff_handler(
+    State(state): State<AppState>,
+    Json(request): Json<DiffRequest>,
+) -> impl IntoResponse {
+    let before = match decode_export(&request.before) {
+        Ok(graph) => graph,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(DiffResponse::error(format!("Invalid before export: {}", e))),
+            );
+        }
+    };
+    let after = match request.after.as_deref() {
+        Some(data) => match decode_export(data) {
+            Ok(graph) => graph,
+            Err(e) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(DiffResponse::error(format!("Invalid after export: {}", e))),
+                );
+            }
+        },
+        None => match state.session.read().await.export_graph_snapshot() {
+            Ok(graph) => CanonicalGraph::from_graph(&graph),
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(DiffResponse::error(format!(
+                        "Failed to build graph snapshot: {}",
+                        e
+                    ))),
+                );
+            }
+        },
+    };
+    (
+        StatusCode::OK,
+        Json(DiffResponse::success(diff(&before, &after))),
+    )
+}
+
+/// Decode a base64 canonical export, as returned by `/export`.
+fn decode_export(data: &str) -> Result<CanonicalGraph, KremisError> {
+    let (graph, _) = import_canonical(&decode_base64(data)?)?;
+    Ok(CanonicalGraph::from_graph(&graph))
+}
+
+/// Decode the base64 of an uploaded export.
+fn decode_base64(data: &str) -> Result<Vec<u8>, KremisError> {
+    base64::Engine::decode(&base64::engine::general_purpose::STANDARD, data)
+        .map_err(|e| KremisError::SerializationError(format!("Base64: {}", e)))
+}
+
 // =============================================================================
 // CERTIFY HANDLER
 // =============================================================================
@@ -541,18 +2030,86 @@ pub async fn export_handler(State(state): State<AppState>) -> impl IntoResponse
 /// Deterministic canonical descriptor for a query, embedded in the certificate.
 fn query_descriptor(request: &QueryRequest) -> String {
     match request {
-        QueryRequest::Lookup { entity_id } => format!("lookup:{entity_id}"),
-        QueryRequest::Traverse { node_id, depth } => format!("traverse:{node_id}:{depth}"),
+        QueryRequest::Lookup {
+            entity_key: Some(key),
+            ..
+        } => format!("lookup_key:{key}"),
+        QueryRequest::Lookup { entity_id, .. } => format!("lookup:{}", entity_id.unwrap_or(0)),
+        QueryRequest::Traverse {
+            node_id,
+            depth,
+            relation,
+        } => format!(
+            "traverse:{node_id}:{depth}{}",
+            relation_suffix(relation.as_deref())
+        ),
         QueryRequest::TraverseFiltered {
             node_id,
             depth,
             min_weight,
             top_k,
+            relation,
         } => format!(
-            "traverse_filtered:{node_id}:{depth}:{min_weight}:{}",
-            top_k.unwrap_or(0)
+            "traverse_filtered:{node_id}:{depth}:{min_weight}:{}{}",
+            top_k.unwrap_or(0),
+            relation_suffix(relation.as_deref())
+        ),
+        QueryRequest::StrongestPath {
+            start,
+            end,
+            relation,
+            avoid,
+            waypoints,
+            max_hops,
+            min_weight,
+        } => format!(
+            "strongest_path:{start}:{end}:variant={STRONGEST_PATH_VARIANT}{}{}",
+            relation_suffix(relation.as_deref()),
+            constraints_suffix(avoid, waypoints, *max_hops, *min_weight)
+        ),
+        QueryRequest::TraverseBackward {
+            node_id,
+            depth,
+            relation,
+        } => format!(
+            "traverse_backward:{node_id}:{depth}{}",
+            relation_suffix(relation.as_deref())
+        ),
+        QueryRequest::StrongestPathBackward {
+            start,
+            end,
+            relation,
+        } => format!(
+            "strongest_path_backward:{start}:{end}:variant={STRONGEST_PATH_VARIANT}{}",
+            relation_suffix(relation.as_deref())
+        ),
+        QueryRequest::Reachable {
+            start,
+            end,
+            relation,
+        } => format!(
+            "reachable:{start}:{end}{}",
+            relation_suffix(relation.as_deref())
+        ),
+        QueryRequest::Paths {
+            start,
+            end,
+            max_hops,
+            limit,
+            relation,
+        } => format!(
+            "paths:{start}:{end}:{max_hops}:{limit}{}",
+            relation_suffix(relation.as_deref())
+        ),
+        QueryRequest::KStrongestPaths {
+            start,
+            end,
+            k,
+            relation,
+        } => format!(
+            "k_strongest_paths:{start}:{end}:{k}:variant={STRONGEST_PATH_VARIANT}{}",
+            relation_suffix(relation.as_deref())
         ),
-        QueryRequest::StrongestPath { start, end } => format!("strongest_path:{start}:{end}"),
         QueryRequest::Intersect { nodes } => {
             let joined = nodes
                 .iter()
@@ -563,7 +2120,92 @@ fn query_descriptor(request: &QueryRequest) -> String {
         }
         QueryRequest::Related { node_id, depth } => format!("related:{node_id}:{depth}"),
         QueryRequest::Properties { node_id } => format!("properties:{node_id}"),
+        QueryRequest::FindByProperty {
+            attribute,
+            value,
+            after,
+            limit,
+        } => format!(
+            "find_by_property:{attribute}={value}:{}:{limit}",
+            after.map_or_else(String::new, |a| a.to_string())
+        ),
+        QueryRequest::Search { query, limit } => format!("search:{query}:{limit}"),
+        QueryRequest::Pattern {
+            variables,
+            edges,
+            limit,
+        } => format!("pattern:{limit}{}", pattern_suffix(variables, edges)),
+    }
+}
+
+/// Descriptor clauses of a pattern, in request order: the variable names,
+/// then each pinned node (`:a@5`), each required property (`:a.kind=db`) and
+/// each edge (`:a->b` with its relation and minimum weight in brackets).
+fn pattern_suffix(variables: &[PatternVariableJson], edges: &[PatternEdgeJson]) -> String {
+    let names: Vec<&str> = variables.iter().map(|v| v.name.as_str()).collect();
+    let mut suffix = format!(":vars={}", names.join(","));
+    for variable in variables {
+        if let Some(node_id) = variable.node_id {
+            suffix.push_str(&format!(":{}@{node_id}", variable.name));
+        }
+        for property in &variable.properties {
+            suffix.push_str(&format!(
+                ":{}.{}={}",
+                variable.name, property.attribute, property.value
+            ));
+        }
+    }
+    for edge in edges {
+        suffix.push_str(&format!(":{}->{}", edge.from, edge.to));
+        if let Some(relation) = &edge.relation {
+            suffix.push_str(&format!("[relation={relation}]"));
+        }
+        if let Some(min_weight) = edge.min_weight {
+            suffix.push_str(&format!("[min_weight={min_weight}]"));
+        }
+    }
+    suffix
+}
+
+/// Descriptor suffix for a relation filter. Unfiltered queries get none, so
+/// their descriptors (and certificates) are unchanged by relation support.
+fn relation_suffix(relation: Option<&str>) -> String {
+    relation.map_or_else(String::new, |r| format!(":relation={r}"))
+}
+
+/// Descriptor suffix for path constraints, in a fixed order and omitted
+/// when unset, so unconstrained descriptors are unchanged.
+fn constraints_suffix(
+    avoid: &[u64],
+    waypoints: &[u64],
+    max_hops: Option<usize>,
+    min_weight: Option<i64>,
+) -> String {
+    let ids = |nodes: &[u64]| {
+        let mut sorted = nodes.to_vec();
+        sorted.sort_unstable();
+        sorted.dedup();
+        sorted
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    let mut suffix = String::new();
+    if !avoid.is_empty() {
+        suffix.push_str(&format!(":avoid={}", ids(avoid)));
+    }
+    if !waypoints.is_empty() {
+        let via: Vec<String> = waypoints.iter().map(|n| n.to_string()).collect();
+        suffix.push_str(&format!(":via={}", via.join(",")));
+    }
+    if let Some(max_hops) = max_hops {
+        suffix.push_str(&format!(":max_hops={max_hops}"));
+    }
+    if let Some(min_weight) = min_weight {
+        suffix.push_str(&format!(":min_weight={min_weight}"));
     }
+    suffix
 }
 
 /// Execute a query and return a Verifiable Query Certificate.
@@ -572,32 +2214,64 @@ fn query_descriptor(request: &QueryRequest) -> String {
 /// deterministic, independently re-verifiable certificate. An empty result
 /// with `grounding = unknown` yields a proof of absence.
 ///
-/// `Properties` queries are rejected: the `KVQC` format carries only canonical
-/// node/edge evidence, with no field for property values, so a `Properties`
-/// certificate could only assert a verdict it cannot let a verifier re-derive.
+/// `Properties`, `FindByProperty` and `Search` queries are rejected: the
+/// `KVQC` format carries only canonical node/edge evidence, with no field for
+/// property values, so such a certificate could only assert a verdict it
+/// cannot let a verifier re-derive. A `pattern` query is certified on its
+/// nodes and edges; its property constraints are recorded in the query
+/// descriptor only.
 pub async fn certify_handler(
     State(state): State<AppState>,
-    Json(request): Json<QueryRequest>,
+    Json(envelope): Json<QueryEnvelope>,
 ) -> impl IntoResponse {
+    let request = &envelope.query;
+    // A certificate is re-derived offline without a budget, so a result cut
+    // short by one could never verify.
+    if envelope.timeout_ms.is_some() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(CertifyResponse::error(
+                "timeout_ms is not supported by /certify: a truncated result cannot be certified",
+            )),
+        );
+    }
+    if envelope.page_size.is_some() || envelope.cursor.is_some() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(CertifyResponse::error(
+                "page_size and cursor are not supported by /certify: a certificate covers the whole result",
+            )),
+        );
+    }
     // The certificate format has no property-evidence field, so a Properties
     // result cannot be honestly certified. Reject it rather than emit a hollow
     // "fact" with empty evidence. See docs/concepts/certificate-spec.mdx.
-    if matches!(request, QueryRequest::Properties { .. }) {
+    if matches!(
+        request,
+        QueryRequest::Properties { .. }
+            | QueryRequest::FindByProperty { .. }
+            | QueryRequest::Search { .. }
+    ) {
         return (
             StatusCode::BAD_REQUEST,
             Json(CertifyResponse::error(
-                "Properties queries are not certifiable: the certificate format carries no property evidence",
+                "Property queries are not certifiable: the certificate format carries no property evidence",
             )),
         );
     }
 
-    let session = state.session.read().await;
-
-    let response = match execute_query_session(&session, &request) {
-        Ok(r) => r,
+    // The largest budget the server grants, shared by the history replay and
+    // the query: a certificate needs the whole result, so running out of it
+    // is an error rather than a truncation.
+    let mut budget = state.query_budget.max();
+    let live = state.session.read().await;
+    let past = match session_as_of(&live, envelope.as_of_seq, &mut budget) {
+        Ok(past) => past,
         Err(e) => {
             let status = match &e {
-                KremisError::InvalidSignal => StatusCode::BAD_REQUEST,
+                KremisError::InvalidSignal | KremisError::HistoryReplayLimit(_) => {
+                    StatusCode::BAD_REQUEST
+                }
                 _ => StatusCode::INTERNAL_SERVER_ERROR,
             };
             return (
@@ -606,18 +2280,47 @@ pub async fn certify_handler(
             );
         }
     };
+    let session = past.as_ref().unwrap_or(&live);
+    let mutation_seq = match envelope.as_of_seq {
+        Some(seq) => Some(seq),
+        None => match live.mutation_seq() {
+            Ok(seq) => seq,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(CertifyResponse::error(format!("Snapshot failed: {}", e))),
+                );
+            }
+        },
+    };
 
-    let graph = match session.export_graph_snapshot() {
-        Ok(g) => g,
+    let response = match execute_query_within(session, request, &mut budget) {
+        Ok(r) if r.truncated => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(CertifyResponse::error(
+                    "Query failed: the query exceeds the server's visit budget and cannot be certified",
+                )),
+            );
+        }
+        Ok(r) => r,
         Err(e) => {
+            let status = match &e {
+                KremisError::InvalidSignal | KremisError::PathEnumerationLimit(_) => {
+                    StatusCode::BAD_REQUEST
+                }
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
             return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(CertifyResponse::error(format!("Snapshot failed: {}", e))),
+                status,
+                Json(CertifyResponse::error(format!("Query failed: {}", e))),
             );
         }
     };
 
-    let sh = match state_hash(&graph) {
+    // The live state is streamed through the hasher, never snapshotted.
+    let key = mutation_seq.map(|seq| (seq, envelope.as_of_seq.is_some()));
+    let sh = match state.state_hashes.state_hash(session, key) {
         Ok(h) => h,
         Err(e) => {
             return (
@@ -627,11 +2330,35 @@ pub async fn certify_handler(
         }
     };
 
-    let path: Vec<NodeId> = response.path.iter().map(|n| NodeId(*n)).collect();
-    let subgraph: Vec<(NodeId, NodeId, EdgeWeight)> = response
+    // A multi-path result is traced path after path, best first; each one
+    // starts at `start`, which a simple path never revisits. A pattern result
+    // is traced binding after binding, each one node per variable.
+    let path: Vec<NodeId> = if !response.paths.is_empty() {
+        response
+            .paths
+            .iter()
+            .flat_map(|p| p.nodes.iter().map(|n| NodeId(*n)))
+            .collect()
+    } else if !response.bindings.is_empty() {
+        response
+            .bindings
+            .iter()
+            .flat_map(|b| b.nodes.iter().map(|n| NodeId(*n)))
+            .collect()
+    } else {
+        response.path.iter().map(|n| NodeId(*n)).collect()
+    };
+    let subgraph: Vec<LabeledEdge> = response
         .edges
         .iter()
-        .map(|e| (NodeId(e.from), NodeId(e.to), EdgeWeight::new(e.weight)))
+        .map(|e| {
+            (
+                NodeId(e.from),
+                NodeId(e.to),
+                e.relation.as_deref().map(Attribute::new),
+                EdgeWeight::new(e.weight),
+            )
+        })
         .collect();
     let artifact = if subgraph.is_empty() {
         Artifact::with_path(path)
@@ -639,13 +2366,20 @@ pub async fn certify_handler(
         Artifact::with_subgraph(path, subgraph)
     };
 
-    let cert = QueryCertificate::new(
+    let cert = match session.certificate(
         sh,
-        query_descriptor(&request),
+        query_descriptor(request),
         response.grounding.clone(),
-        &graph,
         &artifact,
-    );
+    ) {
+        Ok(cert) => cert,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(CertifyResponse::error(format!("Certify failed: {}", e))),
+            );
+        }
+    };
 
     let bytes = match cert.to_canonical_bytes() {
         Ok(b) => b,
@@ -665,10 +2399,11 @@ pub async fn certify_handler(
         Json(CertifyResponse {
             success: true,
             found: response.found,
-            proof_of_absence: cert.is_proof_of_absence(),
+            proof_of_absence: cert.is_proof_of_absence() || cert.is_closure_proof(),
             grounding: response.grounding,
             state_hash: Some(state_hex),
             certificate: Some(certificate),
+            mutation_seq,
             error: None,
         }),
     )
@@ -6,13 +6,21 @@
 //!
 //! - `POST /signal` - Ingest a new signal
 //! - `POST /signals` - Ingest a sequence of signals (creates edges)
+//! - `POST /property/retract` - Retract one (attribute, value) pair from an entity
+//! - `POST /node/delete` - Delete an entity's node with its edges and properties
 //! - `POST /query` - Execute a query
+//! - `POST /query/text` - Execute a query written in the textual query language
+//! - `POST /query/pipeline` - Chain lookup, traverse and properties stages on one state
 //! - `POST /certify` - Execute a query and return a Verifiable Query Certificate
 //! - `GET /status` - Get graph status
 //! - `GET /stage` - Get current developmental stage
 //! - `POST /export` - Export graph in canonical format
+//! - `POST /export/delta` - Export the changes since a base export
+//! - `POST /diff` - Structural diff of two exports, or an export and the graph
 //! - `GET /health` - Health check
 //! - `GET /hash` - BLAKE3 cryptographic hash of graph
+//! - `GET /analytics/components` - Weakly or strongly connected components
+//! - `GET /analytics/centrality` - Top nodes by degree, PageRank or betweenness
 //! - `GET /metrics` - Prometheus metrics
 //!
 //! ## Security Configuration
@@ -26,16 +34,24 @@ mod middleware;
 mod types;
 
 // Re-export handlers and types for integration tests (via `kremis::api::*`)
+pub(crate) use handlers::execute_query_session as execute_query;
 #[allow(unused_imports)]
 pub use handlers::{
-    batch_ingest_handler, certify_handler, export_handler, hash_handler, health_handler,
-    ingest_handler, metrics_handler, query_handler, retract_handler, stage_handler, status_handler,
+    batch_ingest_handler, centrality_handler, certify_handler, components_handler,
+    delete_node_handler, diff_handler, export_delta_handler, export_handler, hash_handler,
+    health_handler, ingest_handler, metrics_handler, pipeline_handler, query_handler,
+    retract_handler, retract_property_handler, stage_handler, status_handler, text_query_handler,
 };
 #[allow(unused_imports)]
 pub use types::{
-    BatchIngestRequest, BatchIngestResponse, CertifyResponse, EdgeJson, ExportResponse,
-    HealthResponse, IngestRequest, IngestResponse, QueryRequest, QueryResponse, RetractRequest,
-    RetractResponse, StageResponse, StatusResponse,
+    BatchIngestRequest, BatchIngestResponse, BindingJson, CentralityKind, CentralityResponse,
+    CertifyResponse, ComponentKind, ComponentsResponse, DeleteNodeRequest, DeleteNodeResponse,
+    DeltaExportRequest, DeltaExportResponse, DiffRequest, DiffResponse, EdgeJson, EntityRef,
+    ExportResponse, HealthResponse, IngestRequest, IngestResponse, LinkRequest, NodeComponentJson,
+    NodePropertiesJson, NodeScoreJson, PatternEdgeJson, PatternVariableJson, PipelineRequest,
+    PipelineResponse, PipelineStage, QueryEnvelope, QueryRequest, QueryResponse,
+    RetractPropertyRequest, RetractPropertyResponse, RetractRequest, RetractResponse,
+    StageResponse, StageResultJson, StatusResponse, TextQueryRequest, entity_ref,
 };
 
 use axum::{
@@ -44,8 +60,11 @@ use axum::{
     middleware as axum_middleware,
     routing::{get, post},
 };
-use kremis_core::{KremisError, Session};
-use std::sync::Arc;
+use kremis_core::export::MAX_IMPORT_BYTES;
+use kremis_core::primitives::{DEFAULT_VISIT_BUDGET, MAX_VISIT_BUDGET};
+use kremis_core::{KremisError, Session, VisitBudget};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
 use tokio::sync::RwLock;
 use tower_http::cors::CorsLayer;
 use tower_http::trace::TraceLayer;
@@ -64,6 +83,10 @@ pub struct AppState {
     pub session: Arc<RwLock<Session>>,
     /// API key for Bearer token authentication. `None` disables auth.
     pub api_key: Option<String>,
+    /// Visit budget granted to each query.
+    pub query_budget: QueryBudget,
+    /// State hashes of recently paged states.
+    pub state_hashes: StateHashCache,
 }
 
 impl AppState {
@@ -75,6 +98,8 @@ impl AppState {
         Self {
             session: Arc::new(RwLock::new(session)),
             api_key: None,
+            query_budget: QueryBudget::default(),
+            state_hashes: StateHashCache::default(),
         }
     }
 
@@ -84,6 +109,123 @@ impl AppState {
         Self {
             session: Arc::new(RwLock::new(session)),
             api_key,
+            query_budget: QueryBudget::default(),
+            state_hashes: StateHashCache::default(),
+        }
+    }
+
+    /// Replace the visit budget granted to each query.
+    #[must_use]
+    pub fn with_query_budget(mut self, query_budget: QueryBudget) -> Self {
+        self.query_budget = query_budget;
+        self
+    }
+}
+
+/// The visit budget the server grants a query: `default_visits` when the
+/// client sets no `timeout_ms`, and never more than `max_visits`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryBudget {
+    /// Budget of a query without `timeout_ms`.
+    pub default_visits: u64,
+    /// Upper bound on any query's budget.
+    pub max_visits: u64,
+}
+
+impl QueryBudget {
+    /// Build the budget from the `[api]` configuration.
+    #[must_use]
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            default_visits: config.api.default_visit_budget,
+            max_visits: config.api.max_visit_budget,
+        }
+    }
+
+    /// The budget for a query that asked for `requested` visits.
+    #[must_use]
+    pub fn grant(&self, requested: Option<u64>) -> VisitBudget {
+        VisitBudget::new(
+            requested
+                .unwrap_or(self.default_visits)
+                .min(self.max_visits),
+        )
+    }
+
+    /// The largest budget, for queries whose result may not be cut short.
+    #[must_use]
+    pub fn max(&self) -> VisitBudget {
+        VisitBudget::new(self.max_visits)
+    }
+}
+
+/// State hashes of recent persistent states, keyed by mutation sequence
+/// number.
+///
+/// The state right after a mutation never changes, so its hash is computed
+/// once and shared by every page read at that state. In-memory sessions keep
+/// no sequence number and hash their state on each call.
+#[derive(Clone, Default)]
+pub struct StateHashCache {
+    hashes: Arc<Mutex<BTreeMap<StateKey, [u8; 32]>>>,
+}
+
+/// A persistent state: its mutation sequence number, and whether it was
+/// rebuilt from the mutation log (`as_of_seq`) rather than read live.
+type StateKey = (u64, bool);
+
+impl StateHashCache {
+    /// Number of states whose hash is kept.
+    const CAPACITY: usize = 16;
+
+    /// The state hash of `session`, which is the state `key` names.
+    pub fn state_hash(
+        &self,
+        session: &Session,
+        key: Option<StateKey>,
+    ) -> Result<[u8; 32], KremisError> {
+        let Some(key) = key else {
+            return session.state_hash();
+        };
+        if let Some(hash) = self.lock().get(&key) {
+            return Ok(*hash);
+        }
+        let hash = session.state_hash()?;
+        self.record(key, hash);
+        Ok(hash)
+    }
+
+    /// Remember `hash` as the state hash of the state `key` names.
+    pub fn record(&self, key: StateKey, hash: [u8; 32]) {
+        let mut hashes = self.lock();
+        hashes.insert(key, hash);
+        while hashes.len() > Self::CAPACITY {
+            hashes.pop_first();
+        }
+    }
+
+    /// The mutation sequence number of a kept state that hashes to `hash`.
+    pub fn seq_of(&self, hash: &[u8; 32]) -> Option<u64> {
+        self.lock()
+            .iter()
+            .find(|(_, kept)| *kept == hash)
+            .map(|(&(seq, _), _)| seq)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<StateKey, [u8; 32]>> {
+        // The map holds plain values, so a panic elsewhere cannot leave it
+        // half-updated.
+        self.hashes
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl Default for QueryBudget {
+    fn default() -> Self {
+        Self {
+            default_visits: DEFAULT_VISIT_BUDGET,
+            max_visits: MAX_VISIT_BUDGET,
         }
     }
 }
@@ -180,10 +322,17 @@ pub fn create_router(state: AppState) -> Router {
     let merged_state = AppState {
         session: state.session.clone(),
         api_key: merged_key,
+        query_budget: state.query_budget,
+        state_hashes: state.state_hashes,
     };
     create_router_with_config(merged_state, &config)
 }
 
+/// Largest `/export/delta` request body: a base export of `MAX_IMPORT_BYTES`
+/// in base64, and room for the rest of the JSON. Larger bases are named by
+/// `base_hash` instead.
+const DELTA_BODY_LIMIT: usize = MAX_IMPORT_BYTES.div_ceil(3) * 4 + 64 * 1024;
+
 /// Create the axum router using an explicit [`AppConfig`].
 pub fn create_router_with_config(state: AppState, config: &AppConfig) -> Router {
     let cors = build_cors_layer(&config.cors.origins);
@@ -216,11 +365,26 @@ pub fn create_router_with_config(state: AppState, config: &AppConfig) -> Router
         .route("/stage", get(handlers::stage_handler))
         .route("/signal", post(handlers::ingest_handler))
         .route("/signal/retract", post(handlers::retract_handler))
+        .route(
+            "/property/retract",
+            post(handlers::retract_property_handler),
+        )
         .route("/signals", post(handlers::batch_ingest_handler))
+        .route("/node/delete", post(handlers::delete_node_handler))
         .route("/query", post(handlers::query_handler))
+        .route("/query/text", post(handlers::text_query_handler))
+        .route("/query/pipeline", post(handlers::pipeline_handler))
         .route("/certify", post(handlers::certify_handler))
         .route("/export", post(handlers::export_handler))
+        .route(
+            "/export/delta",
+            post(handlers::export_delta_handler)
+                .layer(axum::extract::DefaultBodyLimit::max(DELTA_BODY_LIMIT)),
+        )
+        .route("/diff", post(handlers::diff_handler))
         .route("/hash", get(handlers::hash_handler))
+        .route("/analytics/components", get(handlers::components_handler))
+        .route("/analytics/centrality", get(handlers::centrality_handler))
         .route("/metrics", get(handlers::metrics_handler));
 
     if has_auth {
@@ -255,7 +419,8 @@ pub async fn run_server(
     session: Session,
     config: &AppConfig,
 ) -> Result<(), KremisError> {
-    let state = AppState::with_api_key(session, config.security.api_key.clone());
+    let state = AppState::with_api_key(session, config.security.api_key.clone())
+        .with_query_budget(QueryBudget::from_config(config));
     let router = create_router_with_config(state, config);
 
     let listener = tokio::net::TcpListener::bind(addr)
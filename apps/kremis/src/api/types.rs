@@ -3,8 +3,11 @@
 //! This module defines the JSON structures for the HTTP API.
 
 use kremis_core::{
-    Artifact, Attribute, EntityId, KremisError, NodeId, Signal, Value,
-    primitives::{MAX_ATTRIBUTE_LENGTH, MAX_VALUE_LENGTH},
+    Artifact, Attribute, Components, EntityId, GraphDiff, Ingestor, KremisError, LabeledEdge,
+    NodeId, PathConstraints, Pattern, PatternMatch, QueryType, RankedPath, Signal, Value,
+    primitives::{
+        MAX_ASSOCIATION_WINDOW, MAX_ATTRIBUTE_LENGTH, MAX_EXPLICIT_LINKS, MAX_VALUE_LENGTH,
+    },
 };
 use serde::{Deserialize, Serialize};
 
@@ -59,20 +62,93 @@ pub struct StageResponse {
 // INGEST REQUEST/RESPONSE
 // =============================================================================
 
+/// The entity a request addresses: a numeric id or a key from the registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntityRef<'a> {
+    Id(EntityId),
+    Key(&'a str),
+}
+
+/// Pick the entity named by an `entity_id` / `entity_key` pair.
+///
+/// Exactly one of the two must be set, and a key must pass
+/// [`Ingestor::validate_entity_key`]. Fails with `KremisError::InvalidSignal`.
+///
+/// Any id is accepted here, so an entity with a key can still be looked up,
+/// retracted from or deleted by its id. Ingestion refuses an id in the keyed
+/// range only when it would allocate a node for an entity with no key (see
+/// [`Ingestor::validate_entity`]).
+pub fn entity_ref(
+    entity_id: Option<u64>,
+    entity_key: Option<&str>,
+) -> Result<EntityRef<'_>, KremisError> {
+    match (entity_id, entity_key) {
+        (Some(id), None) => Ok(EntityRef::Id(EntityId(id))),
+        (None, Some(key)) => {
+            Ingestor::validate_entity_key(key)?;
+            Ok(EntityRef::Key(key))
+        }
+        _ => Err(KremisError::InvalidSignal),
+    }
+}
+
 /// Signal ingest request.
+///
+/// The entity is named by exactly one of `entity_id` or `entity_key`; a key
+/// is registered on first use.
+///
+/// On `/signal`, naming a `link_from_*` entity also increments the edge from
+/// it to this entity, labeled `relation` if set. Inside a batch the link
+/// fields are rejected; the batch's own `relation` and `links` apply there.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IngestRequest {
-    pub entity_id: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entity_id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entity_key: Option<String>,
     pub attribute: String,
     pub value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_from_entity_id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_from_entity_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relation: Option<String>,
 }
 
 impl IngestRequest {
-    /// Convert to a Signal, validating fields.
+    /// The entity this request names.
+    pub fn entity(&self) -> Result<EntityRef<'_>, KremisError> {
+        entity_ref(self.entity_id, self.entity_key.as_deref())
+    }
+
+    /// The entity to link from, if the request asks for a link.
+    pub fn link_from(&self) -> Result<Option<EntityRef<'_>>, KremisError> {
+        match (
+            self.link_from_entity_id,
+            self.link_from_entity_key.as_deref(),
+        ) {
+            (None, None) => Ok(None),
+            (id, key) => entity_ref(id, key).map(Some),
+        }
+    }
+
+    /// Whether any link field is set.
+    pub fn has_link(&self) -> bool {
+        self.link_from_entity_id.is_some()
+            || self.link_from_entity_key.is_some()
+            || self.relation.is_some()
+    }
+
+    /// Validate the request fields.
     ///
     /// # Validation (H2/H3 fix)
     ///
     /// This method validates:
+    /// - exactly one of `entity_id` / `entity_key` is set (see [`entity_ref`]),
+    ///   and at most one of the `link_from_*` fields
+    /// - `relation` is only set with a link, and passes
+    ///   `Ingestor::validate_relation`
     /// - `attribute` is non-empty, within `MAX_ATTRIBUTE_LENGTH` (256 bytes),
     ///   and free of control characters
     /// - `value` is non-empty, within `MAX_VALUE_LENGTH` (65536 bytes), and
@@ -81,7 +157,16 @@ impl IngestRequest {
     /// This prevents DoS attacks via oversized payloads and log/terminal
     /// escape injection at the API boundary, before data reaches the Core
     /// ingestor.
-    pub fn to_signal(&self) -> Result<Signal, KremisError> {
+    pub fn validate(&self) -> Result<(), KremisError> {
+        self.entity()?;
+        // A relation labels the link, so it needs one.
+        if self.link_from()?.is_none() && self.relation.is_some() {
+            return Err(KremisError::InvalidSignal);
+        }
+        if let Some(relation) = &self.relation {
+            Ingestor::validate_relation(&Attribute::new(relation))?;
+        }
+
         // H2 FIX: Validate attribute length
         if self.attribute.is_empty() {
             return Err(KremisError::InvalidSignal);
@@ -120,11 +205,32 @@ impl IngestRequest {
             ));
         }
 
-        Ok(Signal::new(
-            EntityId(self.entity_id),
+        Ok(())
+    }
+
+    /// Build the signal for an already validated request, addressed to `entity`.
+    pub fn signal_for(&self, entity: EntityId) -> Signal {
+        Signal::new(
+            entity,
             Attribute::new(&self.attribute),
             Value::new(&self.value),
-        ))
+        )
+    }
+
+    /// Convert to a Signal, validating fields.
+    ///
+    /// Only requests naming an `entity_id` convert directly; a request naming
+    /// an `entity_key` needs the key registry, so it fails with
+    /// `KremisError::InvalidSignal` here and is resolved by the handler.
+    ///
+    /// Used by integration tests.
+    #[allow(dead_code)]
+    pub fn to_signal(&self) -> Result<Signal, KremisError> {
+        self.validate()?;
+        match self.entity()? {
+            EntityRef::Id(entity) => Ok(self.signal_for(entity)),
+            EntityRef::Key(_) => Err(KremisError::InvalidSignal),
+        }
     }
 }
 
@@ -159,9 +265,90 @@ impl IngestResponse {
 // =============================================================================
 
 /// Batch signal ingest request.
+///
+/// By default each signal is linked from the one before it. `window` links
+/// each signal from the `window` signals before it instead, and `links`
+/// replaces order-based linking with an explicit list; at most one of the two
+/// may be set. `relation` labels the order-based edges; explicit links carry
+/// their own.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BatchIngestRequest {
     pub signals: Vec<IngestRequest>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub links: Option<Vec<LinkRequest>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relation: Option<String>,
+}
+
+impl BatchIngestRequest {
+    /// Validate the linking fields.
+    ///
+    /// Rejects `window` and `links` together, `relation` with `links`, a
+    /// window above `MAX_ASSOCIATION_WINDOW`, more than `MAX_EXPLICIT_LINKS`
+    /// links, links whose ends are not named correctly (see [`entity_ref`]),
+    /// invalid relations, and signals carrying their own link fields.
+    pub fn validate_links(&self) -> Result<(), KremisError> {
+        if self.signals.iter().any(IngestRequest::has_link) {
+            return Err(KremisError::InvalidSignal);
+        }
+        if let Some(relation) = &self.relation {
+            Ingestor::validate_relation(&Attribute::new(relation))?;
+        }
+        match (self.window, &self.links) {
+            (Some(_), Some(_)) => Err(KremisError::InvalidSignal),
+            (_, Some(_)) if self.relation.is_some() => Err(KremisError::InvalidSignal),
+            (Some(window), None) if window > MAX_ASSOCIATION_WINDOW => {
+                Err(KremisError::InvalidSignal)
+            }
+            (None, Some(links)) => {
+                if links.len() > MAX_EXPLICIT_LINKS {
+                    return Err(KremisError::InvalidSignal);
+                }
+                for link in links {
+                    link.from()?;
+                    link.to()?;
+                    if let Some(relation) = &link.relation {
+                        Ingestor::validate_relation(&Attribute::new(relation))?;
+                    }
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// An explicit association edge in a batch request.
+///
+/// Each end is named by exactly one of its `*_entity_id` or `*_entity_key`
+/// fields, and must be in the batch or already in the graph. `relation`
+/// labels the edge.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_entity_id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_entity_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_entity_id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_entity_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relation: Option<String>,
+}
+
+impl LinkRequest {
+    /// The entity the edge starts at.
+    pub fn from(&self) -> Result<EntityRef<'_>, KremisError> {
+        entity_ref(self.from_entity_id, self.from_entity_key.as_deref())
+    }
+
+    /// The entity the edge points to.
+    pub fn to(&self) -> Result<EntityRef<'_>, KremisError> {
+        entity_ref(self.to_entity_id, self.to_entity_key.as_deref())
+    }
 }
 
 /// Batch signal ingest response.
@@ -199,10 +386,15 @@ impl BatchIngestResponse {
 // =============================================================================
 
 /// Signal retraction request — decrements the weight of an existing edge.
+///
+/// `relation` selects a labeled edge; omitted, the unlabeled association
+/// edge is retracted.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RetractRequest {
     pub from_entity: u64,
     pub to_entity: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relation: Option<String>,
 }
 
 /// Signal retraction response.
@@ -231,6 +423,118 @@ impl RetractResponse {
     }
 }
 
+// =============================================================================
+// PROPERTY RETRACT REQUEST/RESPONSE
+// =============================================================================
+
+/// Property retraction request — removes one `(attribute, value)` pair from
+/// an entity, leaving its other values untouched.
+///
+/// The entity is named by exactly one of `entity_id` or `entity_key`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetractPropertyRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entity_id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entity_key: Option<String>,
+    pub attribute: String,
+    pub value: String,
+}
+
+impl RetractPropertyRequest {
+    /// The entity this request names.
+    pub fn entity(&self) -> Result<EntityRef<'_>, KremisError> {
+        entity_ref(self.entity_id, self.entity_key.as_deref())
+    }
+
+    /// Validate the request, applying the same field validation as
+    /// [`IngestRequest::validate`].
+    pub fn validate(&self) -> Result<(), KremisError> {
+        IngestRequest {
+            entity_id: self.entity_id,
+            entity_key: self.entity_key.clone(),
+            attribute: self.attribute.clone(),
+            value: self.value.clone(),
+            link_from_entity_id: None,
+            link_from_entity_key: None,
+            relation: None,
+        }
+        .validate()
+    }
+}
+
+/// Property retraction response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetractPropertyResponse {
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+impl RetractPropertyResponse {
+    pub fn success() -> Self {
+        Self {
+            success: true,
+            error: None,
+        }
+    }
+
+    pub fn error(msg: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            error: Some(msg.into()),
+        }
+    }
+}
+
+// =============================================================================
+// NODE DELETE REQUEST/RESPONSE
+// =============================================================================
+
+/// Node deletion request — removes an entity's node with its incident edges
+/// and properties.
+///
+/// The entity is named by exactly one of `entity_id` or `entity_key`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteNodeRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entity_id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entity_key: Option<String>,
+}
+
+impl DeleteNodeRequest {
+    /// The entity this request names.
+    pub fn entity(&self) -> Result<EntityRef<'_>, KremisError> {
+        entity_ref(self.entity_id, self.entity_key.as_deref())
+    }
+}
+
+/// Node deletion response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteNodeResponse {
+    pub success: bool,
+    pub node_id: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl DeleteNodeResponse {
+    pub fn success(node_id: u64) -> Self {
+        Self {
+            success: true,
+            node_id: Some(node_id),
+            error: None,
+        }
+    }
+
+    pub fn error(msg: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            node_id: None,
+            error: Some(msg.into()),
+        }
+    }
+}
+
 // =============================================================================
 // QUERY REQUEST/RESPONSE
 // =============================================================================
@@ -240,11 +544,16 @@ impl RetractResponse {
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum QueryRequest {
     Lookup {
-        entity_id: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        entity_id: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        entity_key: Option<String>,
     },
     Traverse {
         node_id: u64,
         depth: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        relation: Option<String>,
     },
     TraverseFiltered {
         node_id: u64,
@@ -252,10 +561,56 @@ pub enum QueryRequest {
         min_weight: i64,
         #[serde(default)]
         top_k: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        relation: Option<String>,
     },
     StrongestPath {
         start: u64,
         end: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        relation: Option<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        avoid: Vec<u64>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        waypoints: Vec<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_hops: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min_weight: Option<i64>,
+    },
+    TraverseBackward {
+        node_id: u64,
+        depth: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        relation: Option<String>,
+    },
+    StrongestPathBackward {
+        start: u64,
+        end: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        relation: Option<String>,
+    },
+    Reachable {
+        start: u64,
+        end: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        relation: Option<String>,
+    },
+    Paths {
+        start: u64,
+        end: u64,
+        max_hops: usize,
+        #[serde(default = "default_paths_limit")]
+        limit: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        relation: Option<String>,
+    },
+    KStrongestPaths {
+        start: u64,
+        end: u64,
+        k: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        relation: Option<String>,
     },
     Intersect {
         nodes: Vec<u64>,
@@ -267,6 +622,237 @@ pub enum QueryRequest {
     Properties {
         node_id: u64,
     },
+    FindByProperty {
+        attribute: String,
+        value: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        after: Option<u64>,
+        #[serde(default = "default_find_limit")]
+        limit: usize,
+    },
+    Search {
+        query: String,
+        #[serde(default = "default_find_limit")]
+        limit: usize,
+    },
+    Pattern {
+        variables: Vec<PatternVariableJson>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        edges: Vec<PatternEdgeJson>,
+        #[serde(default = "default_paths_limit")]
+        limit: usize,
+    },
+}
+
+fn default_find_limit() -> usize {
+    100
+}
+
+fn default_paths_limit() -> usize {
+    10
+}
+
+/// Body of `/query` and `/certify`: a query plus the state to run it against.
+///
+/// The query's own fields sit at the top level next to `as_of_seq`, so a
+/// plain [`QueryRequest`] body is accepted unchanged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryEnvelope {
+    #[serde(flatten)]
+    pub query: QueryRequest,
+    /// Evaluate against the state right after this mutation (persistent
+    /// backend only); `None` runs against the current state.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub as_of_seq: Option<u64>,
+    /// Work budget in node and edge visits (see
+    /// [`VisitBudget`](kremis_core::VisitBudget)); `None` takes the server's
+    /// default, and the server's maximum caps it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// Items per page of a traversal result; setting it or `cursor` pages
+    /// the result.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<usize>,
+    /// `next_cursor` of the previous page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+}
+
+/// Body of `POST /query/text`: a query in the textual query language.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextQueryRequest {
+    pub query: String,
+    /// Evaluate against the state right after this mutation (persistent
+    /// backend only); `None` runs against the current state.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub as_of_seq: Option<u64>,
+    /// Work budget in node and edge visits; `None` takes the server's
+    /// default, and the server's maximum caps it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+impl TryFrom<&QueryType> for QueryRequest {
+    type Error = KremisError;
+
+    /// The request a parsed query stands for. Depth-first traversal has no
+    /// request form and is rejected with `KremisError::InvalidSignal`.
+    fn try_from(query: &QueryType) -> Result<Self, Self::Error> {
+        let label =
+            |relation: &Option<Attribute>| relation.as_ref().map(|r| r.as_str().to_string());
+        let ids = |nodes: &[NodeId]| nodes.iter().map(|n| n.0).collect::<Vec<_>>();
+        Ok(match query {
+            QueryType::Lookup(entity) => QueryRequest::Lookup {
+                entity_id: Some(entity.0),
+                entity_key: None,
+            },
+            QueryType::Traverse {
+                start,
+                depth,
+                relation,
+            } => QueryRequest::Traverse {
+                node_id: start.0,
+                depth: *depth,
+                relation: label(relation),
+            },
+            QueryType::TraverseFiltered {
+                start,
+                depth,
+                min_weight,
+                relation,
+            } => QueryRequest::TraverseFiltered {
+                node_id: start.0,
+                depth: *depth,
+                min_weight: min_weight.value(),
+                top_k: None,
+                relation: label(relation),
+            },
+            QueryType::StrongestPath {
+                start,
+                end,
+                relation,
+                constraints:
+                    PathConstraints {
+                        avoid,
+                        waypoints,
+                        max_hops,
+                        min_weight,
+                    },
+            } => QueryRequest::StrongestPath {
+                start: start.0,
+                end: end.0,
+                relation: label(relation),
+                avoid: avoid.iter().map(|n| n.0).collect(),
+                waypoints: ids(waypoints),
+                max_hops: *max_hops,
+                min_weight: min_weight.map(|w| w.value()),
+            },
+            QueryType::TraverseBackward {
+                start,
+                depth,
+                relation,
+            } => QueryRequest::TraverseBackward {
+                node_id: start.0,
+                depth: *depth,
+                relation: label(relation),
+            },
+            QueryType::StrongestPathBackward {
+                start,
+                end,
+                relation,
+            } => QueryRequest::StrongestPathBackward {
+                start: start.0,
+                end: end.0,
+                relation: label(relation),
+            },
+            QueryType::Reachable {
+                start,
+                end,
+                relation,
+            } => QueryRequest::Reachable {
+                start: start.0,
+                end: end.0,
+                relation: label(relation),
+            },
+            QueryType::Paths {
+                start,
+                end,
+                max_hops,
+                limit,
+                relation,
+            } => QueryRequest::Paths {
+                start: start.0,
+                end: end.0,
+                max_hops: *max_hops,
+                limit: *limit,
+                relation: label(relation),
+            },
+            QueryType::KStrongestPaths {
+                start,
+                end,
+                k,
+                relation,
+            } => QueryRequest::KStrongestPaths {
+                start: start.0,
+                end: end.0,
+                k: *k,
+                relation: label(relation),
+            },
+            QueryType::Pattern { pattern, limit } => pattern_request(pattern, *limit),
+            QueryType::FindByProperty {
+                attribute,
+                value,
+                after,
+                limit,
+            } => QueryRequest::FindByProperty {
+                attribute: attribute.as_str().to_string(),
+                value: value.as_str().to_string(),
+                after: after.map(|n| n.0),
+                limit: *limit,
+            },
+            QueryType::Search { query, limit } => QueryRequest::Search {
+                query: query.clone(),
+                limit: *limit,
+            },
+            QueryType::Intersect(nodes) => QueryRequest::Intersect { nodes: ids(nodes) },
+            QueryType::TraverseDfs { .. } => return Err(KremisError::InvalidSignal),
+        })
+    }
+}
+
+/// A `pattern` request for a core pattern, naming its variables `v0`, `v1`, ...
+fn pattern_request(pattern: &Pattern, limit: usize) -> QueryRequest {
+    let name = |index: usize| format!("v{index}");
+    QueryRequest::Pattern {
+        variables: pattern
+            .variables
+            .iter()
+            .enumerate()
+            .map(|(index, variable)| PatternVariableJson {
+                name: name(index),
+                node_id: variable.node.map(|n| n.0),
+                properties: variable
+                    .properties
+                    .iter()
+                    .map(|(attribute, value)| PropertyJson {
+                        attribute: attribute.as_str().to_string(),
+                        value: value.as_str().to_string(),
+                    })
+                    .collect(),
+            })
+            .collect(),
+        edges: pattern
+            .edges
+            .iter()
+            .map(|edge| PatternEdgeJson {
+                from: name(edge.from),
+                to: name(edge.to),
+                relation: edge.relation.as_ref().map(|r| r.as_str().to_string()),
+                min_weight: edge.min_weight.map(|w| w.value()),
+            })
+            .collect(),
+        limit,
+    }
 }
 
 /// Property JSON representation.
@@ -276,10 +862,85 @@ pub struct PropertyJson {
     pub value: String,
 }
 
+/// A named variable of a `pattern` query.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternVariableJson {
+    pub name: String,
+    /// Bind this variable to exactly this node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node_id: Option<u64>,
+    /// Properties the bound node must carry.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub properties: Vec<PropertyJson>,
+}
+
+/// An edge between two variables of a `pattern` query, named by variable.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternEdgeJson {
+    pub from: String,
+    pub to: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relation: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_weight: Option<i64>,
+}
+
 fn default_grounding() -> String {
     "unknown".to_string()
 }
 
+/// Body of `POST /query/pipeline`: stages run in order, each one on the
+/// node set produced by the previous one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineRequest {
+    pub stages: Vec<PipelineStage>,
+    /// Evaluate against the state right after this mutation (persistent
+    /// backend only); `None` runs against the current state.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub as_of_seq: Option<u64>,
+    /// Work budget in node and edge visits, shared by all stages; `None`
+    /// takes the server's default, and the server's maximum caps it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+/// One stage of a query pipeline (tagged union).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+pub enum PipelineStage {
+    /// Resolve entities to their nodes. Only valid as the first stage.
+    Lookup {
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        entity_ids: Vec<u64>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        entity_keys: Vec<String>,
+    },
+    /// Traverse from every input node; the output is every node reached.
+    Traverse {
+        depth: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min_weight: Option<i64>,
+        /// Follow incoming edges instead of outgoing ones.
+        #[serde(default)]
+        backward: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        relation: Option<String>,
+    },
+    /// Read the properties of every input node; the node set passes through.
+    Properties,
+}
+
+impl PipelineStage {
+    /// The stage name, as used for the `stage` tag.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PipelineStage::Lookup { .. } => "lookup",
+            PipelineStage::Traverse { .. } => "traverse",
+            PipelineStage::Properties => "properties",
+        }
+    }
+}
+
 /// Query response.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueryResponse {
@@ -290,12 +951,41 @@ pub struct QueryResponse {
     #[serde(skip_serializing_if = "Vec::is_empty")]
     #[serde(default)]
     pub properties: Vec<PropertyJson>,
+    /// Search scores, aligned with `path` (only set by `search`).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub scores: Vec<u64>,
+    /// Every path found, best first (only set by `paths` and
+    /// `k_strongest_paths`; `path` holds the first one).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub paths: Vec<PathJson>,
+    /// Every binding found, in ascending order (only set by `pattern`;
+    /// `path` holds the first one).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub bindings: Vec<BindingJson>,
     #[serde(default = "default_grounding")]
     pub grounding: String,
     pub error: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub diagnostic: Option<String>,
+    /// Set on a `find_by_property` page when more matches follow; pass it
+    /// back as `after` to fetch the next page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub next_after: Option<u64>,
+    /// Set on a page of a traversal result when more items follow; pass it
+    /// back as `cursor` to fetch the next page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+    /// The query ran out of its visit budget: a traversal result is partial,
+    /// and a path, reachability or intersection answer was not found.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    #[serde(default)]
+    pub truncated: bool,
 }
 
 impl QueryResponse {
@@ -306,9 +996,15 @@ impl QueryResponse {
             path: vec![],
             edges: vec![],
             properties: vec![],
+            scores: vec![],
+            paths: vec![],
+            bindings: vec![],
             grounding: "unknown".to_string(),
             error: None,
             diagnostic: None,
+            next_after: None,
+            next_cursor: None,
+            truncated: false,
         }
     }
 
@@ -319,9 +1015,15 @@ impl QueryResponse {
             path: path.iter().map(|n| n.0).collect(),
             edges: vec![],
             properties: vec![],
+            scores: vec![],
+            paths: vec![],
+            bindings: vec![],
             grounding: "unknown".to_string(),
             error: None,
             diagnostic: None,
+            next_after: None,
+            next_cursor: None,
+            truncated: false,
         }
     }
 
@@ -329,15 +1031,7 @@ impl QueryResponse {
         let edges = artifact
             .subgraph
             .as_ref()
-            .map(|sg| {
-                sg.iter()
-                    .map(|(from, to, weight)| EdgeJson {
-                        from: from.0,
-                        to: to.0,
-                        weight: weight.value(),
-                    })
-                    .collect()
-            })
+            .map(|sg| sg.iter().cloned().map(EdgeJson::from).collect())
             .unwrap_or_default();
 
         Self {
@@ -346,9 +1040,15 @@ impl QueryResponse {
             path: artifact.path.iter().map(|n| n.0).collect(),
             edges,
             properties: vec![],
+            scores: vec![],
+            paths: vec![],
+            bindings: vec![],
             grounding: "unknown".to_string(),
             error: None,
             diagnostic: None,
+            next_after: None,
+            next_cursor: None,
+            truncated: false,
         }
     }
 
@@ -360,8 +1060,14 @@ impl QueryResponse {
             edges: vec![],
             grounding: "unknown".to_string(),
             properties,
+            scores: vec![],
+            paths: vec![],
+            bindings: vec![],
             error: None,
             diagnostic: None,
+            next_after: None,
+            next_cursor: None,
+            truncated: false,
         }
     }
 
@@ -372,9 +1078,15 @@ impl QueryResponse {
             path: vec![],
             edges: vec![],
             properties: vec![],
+            scores: vec![],
+            paths: vec![],
+            bindings: vec![],
             grounding: "unknown".to_string(),
             error: Some(msg.into()),
             diagnostic: None,
+            next_after: None,
+            next_cursor: None,
+            truncated: false,
         }
     }
 
@@ -382,16 +1094,178 @@ impl QueryResponse {
         self.diagnostic = Some(reason.to_string());
         self
     }
+
+    pub fn with_ranking(ranked: Vec<(NodeId, u64)>) -> Self {
+        let (path, scores) = ranked.into_iter().unzip();
+        Self {
+            scores,
+            ..Self::with_path(path)
+        }
+    }
+
+    /// The first path as `path`, the union of every path's hops as `edges`.
+    pub fn with_paths(ranked: &[RankedPath]) -> Self {
+        let edges: std::collections::BTreeSet<_> = ranked
+            .iter()
+            .flat_map(|path| path.edges.iter().cloned())
+            .collect();
+        Self {
+            edges: edges.into_iter().map(EdgeJson::from).collect(),
+            paths: ranked
+                .iter()
+                .map(|path| PathJson {
+                    nodes: path.nodes.iter().map(|n| n.0).collect(),
+                    weight: path.weight,
+                })
+                .collect(),
+            ..Self::with_path(
+                ranked
+                    .first()
+                    .map(|path| path.nodes.clone())
+                    .unwrap_or_default(),
+            )
+        }
+    }
+
+    /// The first binding as `path`, the union of every binding's edges as
+    /// `edges`.
+    pub fn with_bindings(matches: &[PatternMatch]) -> Self {
+        let edges: std::collections::BTreeSet<_> = matches
+            .iter()
+            .flat_map(|binding| binding.edges.iter().cloned())
+            .collect();
+        Self {
+            edges: edges.into_iter().map(EdgeJson::from).collect(),
+            bindings: matches
+                .iter()
+                .map(|binding| BindingJson {
+                    nodes: binding.nodes.iter().map(|n| n.0).collect(),
+                    edges: binding.edges.iter().cloned().map(EdgeJson::from).collect(),
+                })
+                .collect(),
+            ..Self::with_path(
+                matches
+                    .first()
+                    .map(|binding| binding.nodes.clone())
+                    .unwrap_or_default(),
+            )
+        }
+    }
+
+    pub fn with_next_after(mut self, next_after: Option<u64>) -> Self {
+        self.next_after = next_after;
+        self
+    }
 }
 
 /// Edge JSON representation.
+///
+/// `relation` is the edge's label; it is omitted for unlabeled edges.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EdgeJson {
     pub from: u64,
     pub to: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relation: Option<String>,
     pub weight: i64,
 }
 
+impl From<LabeledEdge> for EdgeJson {
+    fn from((from, to, relation, weight): LabeledEdge) -> Self {
+        Self {
+            from: from.0,
+            to: to.0,
+            relation: relation.map(|r| r.0),
+            weight: weight.value(),
+        }
+    }
+}
+
+/// One binding of a `pattern` result: the node bound to each variable, in
+/// declaration order, and the edge matched by each pattern edge.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BindingJson {
+    pub nodes: Vec<u64>,
+    pub edges: Vec<EdgeJson>,
+}
+
+/// One path of a `paths` or `k_strongest_paths` result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathJson {
+    pub nodes: Vec<u64>,
+    /// Sum of the hop weights, negative weights counted as zero.
+    pub weight: i64,
+}
+
+/// Query pipeline response: one result per stage, in request order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineResponse {
+    pub success: bool,
+    pub stages: Vec<StageResultJson>,
+    /// Mutation sequence number every stage was evaluated at (persistent
+    /// backend only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub mutation_seq: Option<u64>,
+    /// The pipeline ran out of its visit budget: the stage that ran out, and
+    /// every stage after it, saw only part of the graph.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    #[serde(default)]
+    pub truncated: bool,
+    pub error: Option<String>,
+}
+
+impl PipelineResponse {
+    pub fn success(stages: Vec<StageResultJson>, mutation_seq: Option<u64>) -> Self {
+        Self {
+            success: true,
+            stages,
+            mutation_seq,
+            truncated: false,
+            error: None,
+        }
+    }
+
+    pub fn error(msg: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            stages: Vec::new(),
+            mutation_seq: None,
+            truncated: false,
+            error: Some(msg.into()),
+        }
+    }
+}
+
+/// The result of one pipeline stage.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageResultJson {
+    pub stage: String,
+    pub found: bool,
+    /// Node set passed to the next stage, in ascending order.
+    pub nodes: Vec<u64>,
+    /// Edges walked by a `traverse` stage.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub edges: Vec<EdgeJson>,
+    /// Properties read by a `properties` stage, one entry per node found.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub properties: Vec<NodePropertiesJson>,
+    #[serde(default = "default_grounding")]
+    pub grounding: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub diagnostic: Option<String>,
+}
+
+/// The properties of one node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodePropertiesJson {
+    pub node_id: u64,
+    pub properties: Vec<PropertyJson>,
+}
+
 // =============================================================================
 // CERTIFY RESPONSE
 // =============================================================================
@@ -410,6 +1284,11 @@ pub struct CertifyResponse {
     pub proof_of_absence: bool,
     pub state_hash: Option<String>,
     pub certificate: Option<String>,
+    /// Mutation sequence number the certificate was issued at (persistent
+    /// backend only). Pass it as `as_of_seq` to re-run the query later.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub mutation_seq: Option<u64>,
     pub error: Option<String>,
 }
 
@@ -422,6 +1301,237 @@ impl CertifyResponse {
             proof_of_absence: false,
             state_hash: None,
             certificate: None,
+            mutation_seq: None,
+            error: Some(msg.into()),
+        }
+    }
+}
+
+// =============================================================================
+// COMPONENTS RESPONSE
+// =============================================================================
+
+/// Which kind of connected components to compute.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentKind {
+    /// Edge direction is ignored.
+    #[default]
+    Weak,
+    /// Edges are followed forward only; cycles are reported.
+    Strong,
+}
+
+/// Body of `POST /diff`: two canonical exports, base64-encoded like the
+/// `data` of `/export`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffRequest {
+    /// Export of the earlier state.
+    pub before: String,
+    /// Export of the later state; `None` diffs against the current graph.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+}
+
+/// Structural diff response; empty lists on error.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffResponse {
+    pub success: bool,
+    #[serde(flatten)]
+    pub diff: GraphDiff,
+    pub error: Option<String>,
+}
+
+impl DiffResponse {
+    pub fn success(diff: GraphDiff) -> Self {
+        Self {
+            success: true,
+            diff,
+            error: None,
+        }
+    }
+
+    pub fn error(msg: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            diff: GraphDiff::default(),
+            error: Some(msg.into()),
+        }
+    }
+}
+
+/// Body of `POST /export/delta`: the base state, named by its state hash or
+/// uploaded as a canonical export.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeltaExportRequest {
+    /// Lowercase hex state hash of the base, as returned by `/hash`,
+    /// `/certify` or the `target_hash` of an earlier delta.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_hash: Option<String>,
+    /// Export of the base, base64-encoded like the `data` of `/export`;
+    /// only read when the server does not know `base_hash`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+}
+
+/// Delta export response.
+///
+/// `data` is the base64-encoded delta (`KRXD`); the hashes are the lowercase
+/// hex BLAKE3 state hashes of the base and of the current graph.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeltaExportResponse {
+    pub success: bool,
+    pub data: Option<String>,
+    pub base_hash: Option<String>,
+    pub target_hash: Option<String>,
+    pub error: Option<String>,
+}
+
+impl DeltaExportResponse {
+    pub fn success(data: Vec<u8>, base_hash: String, target_hash: String) -> Self {
+        Self {
+            success: true,
+            data: Some(base64::Engine::encode(
+                &base64::engine::general_purpose::STANDARD,
+                &data,
+            )),
+            base_hash: Some(base_hash),
+            target_hash: Some(target_hash),
+            error: None,
+        }
+    }
+
+    pub fn error(msg: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            data: None,
+            base_hash: None,
+            target_hash: None,
+            error: Some(msg.into()),
+        }
+    }
+}
+
+/// Connected components response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentsResponse {
+    pub success: bool,
+    pub kind: ComponentKind,
+    pub count: usize,
+    /// Component id of every node, in ascending node order.
+    pub nodes: Vec<NodeComponentJson>,
+    /// Number of nodes in each component, indexed by component id.
+    pub sizes: Vec<usize>,
+    /// Strongly connected components with more than one node.
+    pub cycles: Vec<Vec<u64>>,
+    pub error: Option<String>,
+}
+
+/// The component a node belongs to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeComponentJson {
+    pub node: u64,
+    pub component: usize,
+}
+
+impl ComponentsResponse {
+    pub fn success(kind: ComponentKind, components: &Components) -> Self {
+        Self {
+            success: true,
+            kind,
+            count: components.count(),
+            nodes: components
+                .membership
+                .iter()
+                .map(|(node, &component)| NodeComponentJson {
+                    node: node.0,
+                    component,
+                })
+                .collect(),
+            sizes: components.sizes.clone(),
+            cycles: components
+                .cycles
+                .iter()
+                .map(|cycle| cycle.iter().map(|node| node.0).collect())
+                .collect(),
+            error: None,
+        }
+    }
+
+    pub fn error(kind: ComponentKind, msg: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            kind,
+            count: 0,
+            nodes: Vec::new(),
+            sizes: Vec::new(),
+            cycles: Vec::new(),
+            error: Some(msg.into()),
+        }
+    }
+}
+
+// =============================================================================
+// CENTRALITY RESPONSE
+// =============================================================================
+
+/// Which centrality measure to compute.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CentralityKind {
+    /// Number of incident edges.
+    #[default]
+    Degree,
+    /// Sum of incident edge weights.
+    WeightedDegree,
+    /// PageRank after a fixed number of iterations.
+    Pagerank,
+    /// Exact shortest-path betweenness.
+    Betweenness,
+}
+
+/// Centrality response: the highest-scoring nodes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CentralityResponse {
+    pub success: bool,
+    pub metric: CentralityKind,
+    /// Unit of `score`: 1 for counts, 1 000 000 000 for fixed-point scores.
+    pub scale: u64,
+    /// Top nodes, by score descending, then node id ascending.
+    pub nodes: Vec<NodeScoreJson>,
+    pub error: Option<String>,
+}
+
+/// A node and its centrality score.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeScoreJson {
+    pub node: u64,
+    pub score: u64,
+}
+
+impl CentralityResponse {
+    pub fn success(metric: CentralityKind, scale: u64, ranked: &[(NodeId, u64)]) -> Self {
+        Self {
+            success: true,
+            metric,
+            scale,
+            nodes: ranked
+                .iter()
+                .map(|&(node, score)| NodeScoreJson {
+                    node: node.0,
+                    score,
+                })
+                .collect(),
+            error: None,
+        }
+    }
+
+    pub fn error(metric: CentralityKind, msg: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            metric,
+            scale: 0,
+            nodes: Vec::new(),
             error: Some(msg.into()),
         }
     }
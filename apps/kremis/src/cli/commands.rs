@@ -5,8 +5,11 @@
 use crate::api;
 use crate::config::AppConfig;
 use kremis_core::{
-    Graph, KremisError, NodeId, Session,
-    export::{canonical_checksum, export_canonical, import_canonical},
+    Graph, GraphDelta, GraphStore, KremisError, MergePolicy, NodeId, RedbGraph, Session,
+    delta::apply_delta,
+    diff,
+    export::{CanonicalGraph, export_canonical, import_canonical},
+    graph::LoadDiagnostics,
     primitives::MAX_SEQUENCE_LENGTH,
     system::{GraphMetrics, StageAssessor},
 };
@@ -118,7 +121,8 @@ pub async fn cmd_server(
     port: u16,
     config: AppConfig,
 ) -> Result<(), KremisError> {
-    let (session, _) = load_or_create_session(db_path, backend)?;
+    let (mut session, _) = load_or_create_session(db_path, backend)?;
+    session.set_checkpoint_policy(config.storage.checkpoint_policy());
 
     println!("Kremis Honest AGI Server Starting...");
     println!();
@@ -259,6 +263,10 @@ pub fn cmd_stage(
 // =============================================================================
 
 /// Ingest signals from a file or stdin.
+///
+/// Signals are linked per `window` (default `ASSOCIATION_WINDOW`) unless the
+/// input lists explicit links, which replace order-based linking.
+#[allow(clippy::too_many_arguments)]
 pub fn cmd_ingest(
     db_path: &PathBuf,
     backend: &str,
@@ -267,11 +275,17 @@ pub fn cmd_ingest(
     format: &str,
     from_stdin: bool,
     strict: bool,
+    window: Option<usize>,
 ) -> Result<(), KremisError> {
-    use kremis_core::{Attribute, EntityId, Signal, Value};
+    use kremis_core::{Attribute, EntityId, Ingestor, LinkMode, Signal, Value};
 
     let (mut session, _) = load_or_create_session(db_path, backend)?;
 
+    // Explicit links, a window and a relation given inside the input itself.
+    let mut links: Vec<(EntityId, EntityId, Option<Attribute>)> = Vec::new();
+    let mut input_window: Option<usize> = None;
+    let mut input_relation: Option<Attribute> = None;
+
     let signals = if from_stdin {
         if file.is_some() {
             return Err(KremisError::SerializationError(
@@ -293,6 +307,10 @@ pub fn cmd_ingest(
             let val: serde_json::Value = serde_json::from_str(&line).map_err(|e| {
                 KremisError::SerializationError(format!("Invalid JSON on stdin: {}", e))
             })?;
+            if val.get("from_entity_id").is_some() {
+                links.push(parse_json_link(&val)?);
+                continue;
+            }
             let entity_id = val["entity_id"].as_u64().ok_or_else(|| {
                 KremisError::SerializationError(
                     "Missing or invalid 'entity_id' (expected unsigned integer). Note: the field is 'entity_id', not 'entity'.".to_string(),
@@ -356,13 +374,59 @@ pub fn cmd_ingest(
         // Parse signals based on format
         match format {
             "json" => {
-                let json_values: Vec<serde_json::Value> =
+                let document: serde_json::Value =
                     serde_json::from_slice(&contents).map_err(|e| {
                         KremisError::SerializationError(format!(
                             "Invalid JSON in file: {}. Expected array of {{\"entity_id\": <u64>, \"attribute\": \"...\", \"value\": \"...\"}}",
                             e
                         ))
                     })?;
+                // Either a bare array of signals, or an object carrying the
+                // signals next to their linking: {"signals", "window", "links"}.
+                let json_values = match document {
+                    serde_json::Value::Array(values) => values,
+                    serde_json::Value::Object(mut object) => {
+                        if let Some(window) = object.get("window") {
+                            let window = window.as_u64().ok_or_else(|| {
+                                KremisError::SerializationError(
+                                    "Invalid 'window' (expected unsigned integer).".to_string(),
+                                )
+                            })?;
+                            input_window = Some(usize::try_from(window).unwrap_or(usize::MAX));
+                        }
+                        if let Some(relation) = object.get("relation") {
+                            let relation = relation.as_str().ok_or_else(|| {
+                                KremisError::SerializationError(
+                                    "Invalid 'relation' (expected string).".to_string(),
+                                )
+                            })?;
+                            input_relation = Some(Attribute::new(relation));
+                        }
+                        if let Some(link_values) = object.get("links") {
+                            let link_values = link_values.as_array().ok_or_else(|| {
+                                KremisError::SerializationError(
+                                    "Invalid 'links' (expected array).".to_string(),
+                                )
+                            })?;
+                            for link in link_values {
+                                links.push(parse_json_link(link)?);
+                            }
+                        }
+                        match object.remove("signals") {
+                            Some(serde_json::Value::Array(values)) => values,
+                            _ => {
+                                return Err(KremisError::SerializationError(
+                                    "Missing or invalid 'signals' (expected array).".to_string(),
+                                ));
+                            }
+                        }
+                    }
+                    _ => {
+                        return Err(KremisError::SerializationError(
+                            "Expected an array of signals or an object with 'signals'.".to_string(),
+                        ));
+                    }
+                };
 
                 // Validate signal count to prevent DoS
                 if json_values.len() > MAX_SEQUENCE_LENGTH {
@@ -411,6 +475,27 @@ pub fn cmd_ingest(
                 let mut skipped: usize = 0;
 
                 for (line_num, line) in text.lines().enumerate() {
+                    // `<from_entity> -> <to_entity>` declares an explicit link,
+                    // `<from_entity> -[<relation>]-> <to_entity>` a labeled one.
+                    if !line.contains(':')
+                        && let Some((from, to)) = line.split_once("->")
+                    {
+                        let (from, relation) = match from.split_once("-[") {
+                            Some((from, label)) => {
+                                let label =
+                                    label.strip_suffix(']').ok_or(KremisError::InvalidSignal)?;
+                                (from, Some(Attribute::new(label)))
+                            }
+                            None => (from, None),
+                        };
+                        let from: u64 = from
+                            .trim()
+                            .parse()
+                            .map_err(|_| KremisError::InvalidSignal)?;
+                        let to: u64 = to.trim().parse().map_err(|_| KremisError::InvalidSignal)?;
+                        links.push((EntityId(from), EntityId(to), relation));
+                        continue;
+                    }
                     let parts: Vec<&str> = line.split(':').collect();
                     if parts.len() >= 3 {
                         let entity_id: u64 = parts[0]
@@ -470,9 +555,51 @@ pub fn cmd_ingest(
         )));
     }
 
+    // Ids from KEYED_ENTITY_BASE up belong to entity keys, which this input
+    // cannot name.
+    let entities = signals.iter().map(|s| s.entity);
+    let link_ends = links.iter().flat_map(|(from, to, _)| [*from, *to]);
+    if let Some(entity) = entities
+        .chain(link_ends)
+        .find(|&entity| Ingestor::validate_raw_entity_id(entity).is_err())
+    {
+        return Err(KremisError::SerializationError(format!(
+            "Entity id {} is reserved for entity keys (ids from {} up)",
+            entity.0,
+            kremis_core::primitives::KEYED_ENTITY_BASE
+        )));
+    }
+
+    let window = window.or(input_window);
+    let link_mode = if links.is_empty() {
+        LinkMode::Window(
+            window.unwrap_or(kremis_core::primitives::ASSOCIATION_WINDOW),
+            input_relation,
+        )
+    } else if window.is_some() {
+        return Err(KremisError::SerializationError(
+            "use a window or explicit links, not both".to_string(),
+        ));
+    } else if input_relation.is_some() {
+        return Err(KremisError::SerializationError(
+            "explicit links carry their own 'relation'".to_string(),
+        ));
+    } else {
+        LinkMode::Explicit(links)
+    };
+    if let LinkMode::Window(window, _) = link_mode
+        && window > kremis_core::primitives::MAX_ASSOCIATION_WINDOW
+    {
+        return Err(KremisError::SerializationError(format!(
+            "Window {} exceeds maximum {}",
+            window,
+            kremis_core::primitives::MAX_ASSOCIATION_WINDOW
+        )));
+    }
+
     // Ingest signals
     let count = signals.len();
-    session.ingest_sequence(&signals)?;
+    session.ingest_sequence_with(&signals, &link_mode)?;
 
     // Save graph
     save_session(&session, db_path)?;
@@ -496,6 +623,133 @@ pub fn cmd_ingest(
     Ok(())
 }
 
+/// Parse a `{"from_entity_id": <u64>, "to_entity_id": <u64>}` link, with an
+/// optional `"relation"` label.
+fn parse_json_link(
+    val: &serde_json::Value,
+) -> Result<
+    (
+        kremis_core::EntityId,
+        kremis_core::EntityId,
+        Option<kremis_core::Attribute>,
+    ),
+    KremisError,
+> {
+    let end = |field: &str| {
+        val[field]
+            .as_u64()
+            .map(kremis_core::EntityId)
+            .ok_or_else(|| {
+                KremisError::SerializationError(format!(
+                    "Missing or invalid '{field}' in link (expected unsigned integer)."
+                ))
+            })
+    };
+    let relation = match &val["relation"] {
+        serde_json::Value::Null => None,
+        relation => Some(kremis_core::Attribute::new(relation.as_str().ok_or_else(
+            || {
+                KremisError::SerializationError(
+                    "Invalid 'relation' in link (expected string).".to_string(),
+                )
+            },
+        )?)),
+    };
+    Ok((end("from_entity_id")?, end("to_entity_id")?, relation))
+}
+
+// =============================================================================
+// RETRACT PROPERTY COMMAND
+// =============================================================================
+
+/// Retract one `(attribute, value)` pair from an entity.
+pub fn cmd_retract_property(
+    db_path: &PathBuf,
+    backend: &str,
+    json_mode: bool,
+    entity: u64,
+    attribute: &str,
+    value: &str,
+) -> Result<(), KremisError> {
+    use kremis_core::{Attribute, EntityId, Ingestor, Signal, Value};
+
+    let signal = Signal::new(
+        EntityId(entity),
+        Attribute::new(attribute),
+        Value::new(value),
+    );
+    Ingestor::validate(&signal)?;
+
+    let (mut session, _) = load_or_create_session(db_path, backend)?;
+
+    let node_id = session
+        .lookup_entity(signal.entity)
+        .ok_or_else(|| KremisError::SerializationError(format!("Entity {} not found", entity)))?;
+    if !session.remove_property(node_id, &signal.attribute, &signal.value)? {
+        return Err(KremisError::SerializationError(format!(
+            "Entity {} has no property {}={}",
+            entity, attribute, value
+        )));
+    }
+
+    save_session(&session, db_path)?;
+
+    if json_mode {
+        let output = serde_json::json!({
+            "entity_id": entity,
+            "node_id": node_id.0,
+            "attribute": attribute,
+            "value": value
+        });
+        println!("{}", json_pretty(&output)?);
+    } else {
+        println!("Retracted {}={} from entity {}", attribute, value, entity);
+    }
+
+    Ok(())
+}
+
+// =============================================================================
+// DELETE COMMAND
+// =============================================================================
+
+/// Delete an entity's node together with its edges and properties.
+pub fn cmd_delete(
+    db_path: &PathBuf,
+    backend: &str,
+    json_mode: bool,
+    entity: u64,
+) -> Result<(), KremisError> {
+    use kremis_core::EntityId;
+
+    let (mut session, _) = load_or_create_session(db_path, backend)?;
+
+    let node_id = session
+        .lookup_entity(EntityId(entity))
+        .ok_or_else(|| KremisError::SerializationError(format!("Entity {} not found", entity)))?;
+    let edges_before = session.edge_count()?;
+    session.remove_node(node_id)?;
+    let edges_removed = edges_before.saturating_sub(session.edge_count()?);
+
+    save_session(&session, db_path)?;
+
+    if json_mode {
+        let output = serde_json::json!({
+            "entity_id": entity,
+            "node_id": node_id.0,
+            "edges_removed": edges_removed
+        });
+        println!("{}", json_pretty(&output)?);
+    } else {
+        println!(
+            "Deleted entity {} (node {}) and {} edge(s)",
+            entity, node_id.0, edges_removed
+        );
+    }
+
+    Ok(())
+}
+
 // =============================================================================
 // QUERY COMMAND
 // =============================================================================
@@ -513,12 +767,21 @@ pub fn cmd_query(
     entity: Option<u64>,
     nodes: Option<String>,
     min_weight: Option<i64>,
+    relation: Option<String>,
+    limit: Option<usize>,
+    avoid: Option<String>,
+    via: Option<String>,
+    max_hops: Option<usize>,
 ) -> Result<(), KremisError> {
-    use kremis_core::{EdgeWeight, EntityId};
+    use kremis_core::{Attribute, EdgeWeight, EntityId, Ingestor, PathConstraints, Reachability};
 
     if depth > kremis_core::primitives::MAX_TRAVERSAL_DEPTH {
         return Err(KremisError::InvalidSignal);
     }
+    let relation = relation.map(Attribute::new);
+    if let Some(ref r) = relation {
+        Ingestor::validate_relation(r)?;
+    }
     let (session, _) = load_or_create_session(db_path, backend)?;
 
     match query_type {
@@ -545,9 +808,14 @@ pub fn cmd_query(
             let start_id = start.ok_or(KremisError::InvalidSignal)?;
 
             let artifact = if let Some(min_w) = min_weight {
-                session.traverse_filtered(NodeId(start_id), depth, EdgeWeight::new(min_w))?
+                session.traverse_filtered(
+                    NodeId(start_id),
+                    depth,
+                    EdgeWeight::new(min_w),
+                    relation.as_ref(),
+                )?
             } else {
-                session.traverse(NodeId(start_id), depth)?
+                session.traverse(NodeId(start_id), depth, relation.as_ref())?
             };
 
             if json_mode {
@@ -559,9 +827,7 @@ pub fn cmd_query(
                         "found": true,
                         "path": a.path.iter().map(|n| n.0).collect::<Vec<_>>(),
                         "edges": a.subgraph.as_ref().map(|sg| {
-                            sg.iter().map(|(f, t, w)| serde_json::json!({
-                                "from": f.0, "to": t.0, "weight": w.value()
-                            })).collect::<Vec<_>>()
+                            sg.iter().cloned().map(api::EdgeJson::from).collect::<Vec<_>>()
                         })
                     }),
                     None => serde_json::json!({
@@ -582,8 +848,22 @@ pub fn cmd_query(
                         );
                         if let Some(ref sg) = a.subgraph {
                             println!("  Edges: {}", sg.len());
-                            for (from, to, weight) in sg.iter().take(10) {
-                                println!("    {} -> {} (weight: {})", from.0, to.0, weight.value());
+                            for (from, to, relation, weight) in sg.iter().take(10) {
+                                match relation {
+                                    Some(relation) => println!(
+                                        "    {} -[{}]-> {} (weight: {})",
+                                        from.0,
+                                        relation.as_str(),
+                                        to.0,
+                                        weight.value()
+                                    ),
+                                    None => println!(
+                                        "    {} -> {} (weight: {})",
+                                        from.0,
+                                        to.0,
+                                        weight.value()
+                                    ),
+                                }
                             }
                             if sg.len() > 10 {
                                 println!("    ... and {} more", sg.len() - 10);
@@ -599,7 +879,27 @@ pub fn cmd_query(
             let start_id = start.ok_or(KremisError::InvalidSignal)?;
             let end_id = end.ok_or(KremisError::InvalidSignal)?;
 
-            let result = session.strongest_path(NodeId(start_id), NodeId(end_id))?;
+            if max_hops.is_some_and(|h| h > kremis_core::primitives::MAX_TRAVERSAL_DEPTH) {
+                return Err(KremisError::InvalidSignal);
+            }
+            let constraints = PathConstraints {
+                avoid: match avoid {
+                    Some(list) => parse_node_ids(&list)?.into_iter().collect(),
+                    None => Default::default(),
+                },
+                waypoints: match via {
+                    Some(list) => parse_node_ids(&list)?,
+                    None => Vec::new(),
+                },
+                max_hops,
+                min_weight: min_weight.map(EdgeWeight::new),
+            };
+            let result = session.strongest_path_with(
+                NodeId(start_id),
+                NodeId(end_id),
+                relation.as_ref(),
+                &constraints,
+            )?;
 
             if json_mode {
                 let output = match &result {
@@ -629,18 +929,109 @@ pub fn cmd_query(
             }
         }
 
+        "paths" | "k-strongest" => {
+            let start_id = start.ok_or(KremisError::InvalidSignal)?;
+            let end_id = end.ok_or(KremisError::InvalidSignal)?;
+            let limit = limit.unwrap_or(10);
+            if limit == 0 || limit > kremis_core::primitives::MAX_PATH_RESULTS {
+                return Err(KremisError::InvalidSignal);
+            }
+
+            let result = if query_type == "paths" {
+                session.simple_paths(
+                    NodeId(start_id),
+                    NodeId(end_id),
+                    depth,
+                    limit,
+                    relation.as_ref(),
+                )?
+            } else {
+                session.k_strongest_paths(
+                    NodeId(start_id),
+                    NodeId(end_id),
+                    limit,
+                    relation.as_ref(),
+                )?
+            };
+            let paths = result.unwrap_or_default();
+
+            if json_mode {
+                let output = serde_json::json!({
+                    "query_type": query_type,
+                    "start": start_id,
+                    "end": end_id,
+                    "found": !paths.is_empty(),
+                    "paths": paths.iter().map(|p| serde_json::json!({
+                        "nodes": p.nodes.iter().map(|n| n.0).collect::<Vec<_>>(),
+                        "weight": p.weight
+                    })).collect::<Vec<_>>()
+                });
+                println!("{}", json_pretty(&output)?);
+            } else if paths.is_empty() {
+                println!("No path found from {} to {}", start_id, end_id);
+            } else {
+                println!("{} paths {} -> {}:", paths.len(), start_id, end_id);
+                for p in &paths {
+                    println!(
+                        "  {:?} (weight: {})",
+                        p.nodes.iter().map(|n| n.0).collect::<Vec<_>>(),
+                        p.weight
+                    );
+                }
+            }
+        }
+
+        "reachable" => {
+            let start_id = start.ok_or(KremisError::InvalidSignal)?;
+            let end_id = end.ok_or(KremisError::InvalidSignal)?;
+
+            let result = session.reachable(NodeId(start_id), NodeId(end_id), relation.as_ref())?;
+
+            if json_mode {
+                let output = match &result {
+                    Some(Reachability::Reachable(path)) => serde_json::json!({
+                        "query_type": "reachable",
+                        "start": start_id,
+                        "end": end_id,
+                        "found": true,
+                        "path": path.iter().map(|n| n.0).collect::<Vec<_>>()
+                    }),
+                    Some(Reachability::Unreachable(closure)) => serde_json::json!({
+                        "query_type": "reachable",
+                        "start": start_id,
+                        "end": end_id,
+                        "found": false,
+                        "closure": closure.path.iter().map(|n| n.0).collect::<Vec<_>>()
+                    }),
+                    None => serde_json::json!({
+                        "query_type": "reachable",
+                        "start": start_id,
+                        "end": end_id,
+                        "found": false
+                    }),
+                };
+                println!("{}", json_pretty(&output)?);
+            } else {
+                match result {
+                    Some(Reachability::Reachable(path)) => {
+                        println!("Node {} reaches node {}:", start_id, end_id);
+                        println!("  {:?}", path.iter().map(|n| n.0).collect::<Vec<_>>());
+                    }
+                    Some(Reachability::Unreachable(closure)) => println!(
+                        "Node {} does not reach node {} ({} nodes reachable, all explored)",
+                        start_id,
+                        end_id,
+                        closure.path.len()
+                    ),
+                    None => println!("Node {} not found", start_id),
+                }
+            }
+        }
+
         "intersect" => {
             let nodes_str = nodes.ok_or(KremisError::InvalidSignal)?;
 
-            let node_ids: Vec<NodeId> = nodes_str
-                .split(',')
-                .map(|s: &str| {
-                    s.trim()
-                        .parse::<u64>()
-                        .map(NodeId)
-                        .map_err(|_| KremisError::InvalidSignal)
-                })
-                .collect::<Result<Vec<_>, _>>()?;
+            let node_ids = parse_node_ids(&nodes_str)?;
 
             if node_ids.len() < kremis_core::primitives::MIN_INTERSECT_NODES
                 || node_ids.len() > kremis_core::primitives::MAX_INTERSECT_NODES
@@ -692,9 +1083,7 @@ pub fn cmd_query(
                         "found": true,
                         "path": a.path.iter().map(|n| n.0).collect::<Vec<_>>(),
                         "edges": a.subgraph.as_ref().map(|sg| {
-                            sg.iter().map(|(f, t, w)| serde_json::json!({
-                                "from": f.0, "to": t.0, "weight": w.value()
-                            })).collect::<Vec<_>>()
+                            sg.iter().cloned().map(api::EdgeJson::from).collect::<Vec<_>>()
                         })
                     }),
                     None => serde_json::json!({
@@ -715,8 +1104,22 @@ pub fn cmd_query(
                         );
                         if let Some(ref sg) = a.subgraph {
                             println!("  Edges: {}", sg.len());
-                            for (from, to, weight) in sg.iter().take(10) {
-                                println!("    {} -> {} (weight: {})", from.0, to.0, weight.value());
+                            for (from, to, relation, weight) in sg.iter().take(10) {
+                                match relation {
+                                    Some(relation) => println!(
+                                        "    {} -[{}]-> {} (weight: {})",
+                                        from.0,
+                                        relation.as_str(),
+                                        to.0,
+                                        weight.value()
+                                    ),
+                                    None => println!(
+                                        "    {} -> {} (weight: {})",
+                                        from.0,
+                                        to.0,
+                                        weight.value()
+                                    ),
+                                }
                             }
                             if sg.len() > 10 {
                                 println!("    ... and {} more", sg.len() - 10);
@@ -789,17 +1192,225 @@ pub fn cmd_query(
     Ok(())
 }
 
+/// Parse a comma-separated list of node ids.
+fn parse_node_ids(list: &str) -> Result<Vec<NodeId>, KremisError> {
+    list.split(',')
+        .map(|s| {
+            s.trim()
+                .parse::<u64>()
+                .map(NodeId)
+                .map_err(|_| KremisError::InvalidSignal)
+        })
+        .collect()
+}
+
+/// Execute a query written in the textual query language.
+///
+/// The query runs through the same validation and execution as `POST /query`;
+/// `--json-mode` prints the same response body. A syntax error is reported
+/// with a caret under the offending position.
+pub fn cmd_query_text(
+    db_path: &PathBuf,
+    backend: &str,
+    json_mode: bool,
+    expression: &str,
+) -> Result<(), KremisError> {
+    let query = kremis_core::parse_query(expression).inspect_err(|e| {
+        if let KremisError::QueryParse { position, .. } = e {
+            eprintln!("  {expression}\n  {}^", " ".repeat(*position));
+        }
+    })?;
+    let request = api::QueryRequest::try_from(&query.query_type)?;
+    let (session, _) = load_or_create_session(db_path, backend)?;
+    let response = api::execute_query(&session, &request)?;
+
+    if json_mode {
+        let output = serde_json::to_value(&response)
+            .map_err(|e| KremisError::SerializationError(e.to_string()))?;
+        println!("{}", json_pretty(&output)?);
+        return Ok(());
+    }
+
+    if !response.found {
+        match response.diagnostic {
+            Some(reason) => println!("No result ({reason})"),
+            None => println!("No result"),
+        }
+        return Ok(());
+    }
+    if !response.paths.is_empty() {
+        println!("{} paths:", response.paths.len());
+        for path in &response.paths {
+            println!("  {:?} (weight: {})", path.nodes, path.weight);
+        }
+    } else if !response.bindings.is_empty() {
+        println!("{} bindings:", response.bindings.len());
+        for binding in &response.bindings {
+            println!("  {:?}", binding.nodes);
+        }
+    } else if !response.scores.is_empty() {
+        println!("{} matches:", response.path.len());
+        for (node, score) in response.path.iter().zip(&response.scores) {
+            println!("  node {node} (score {score})");
+        }
+    } else {
+        println!("Nodes: {:?}", response.path);
+    }
+    if !response.edges.is_empty() {
+        println!("Edges: {}", response.edges.len());
+        for edge in response.edges.iter().take(10) {
+            println!("  {} -> {} (weight: {})", edge.from, edge.to, edge.weight);
+        }
+        if response.edges.len() > 10 {
+            println!("  ... and {} more", response.edges.len() - 10);
+        }
+    }
+    println!("Grounding: {}", response.grounding);
+    Ok(())
+}
+
+// =============================================================================
+// COMPONENTS COMMAND
+// =============================================================================
+
+/// Split the graph into weakly or strongly connected components.
+pub fn cmd_components(
+    db_path: &PathBuf,
+    backend: &str,
+    json_mode: bool,
+    strong: bool,
+    relation: Option<String>,
+) -> Result<(), KremisError> {
+    use kremis_core::{Attribute, Ingestor};
+
+    let relation = relation.map(Attribute::new);
+    if let Some(ref r) = relation {
+        Ingestor::validate_relation(r)?;
+    }
+    let (session, _) = load_or_create_session(db_path, backend)?;
+    let components = if strong {
+        session.strongly_connected_components(relation.as_ref())?
+    } else {
+        session.weakly_connected_components(relation.as_ref())?
+    };
+    let kind = if strong { "strong" } else { "weak" };
+    let cycles: Vec<Vec<u64>> = components
+        .cycles
+        .iter()
+        .map(|cycle| cycle.iter().map(|n| n.0).collect())
+        .collect();
+
+    if json_mode {
+        let output = serde_json::json!({
+            "kind": kind,
+            "count": components.count(),
+            "nodes": components.membership.iter().map(|(node, component)| serde_json::json!({
+                "node": node.0, "component": component
+            })).collect::<Vec<_>>(),
+            "sizes": components.sizes,
+            "cycles": cycles
+        });
+        println!("{}", json_pretty(&output)?);
+        return Ok(());
+    }
+
+    println!("{} {} components", components.count(), kind);
+    for (id, size) in components.sizes.iter().enumerate().take(10) {
+        println!("  Component {}: {} nodes", id, size);
+    }
+    if components.count() > 10 {
+        println!("  ... and {} more", components.count() - 10);
+    }
+    if strong {
+        println!("Cycles: {}", cycles.len());
+        for cycle in &cycles {
+            println!("  {:?}", cycle);
+        }
+    }
+
+    Ok(())
+}
+
+// =============================================================================
+// CENTRALITY COMMAND
+// =============================================================================
+
+/// Rank nodes by a centrality measure and show the top ones.
+pub fn cmd_centrality(
+    db_path: &PathBuf,
+    backend: &str,
+    json_mode: bool,
+    metric: &str,
+    top: usize,
+    iterations: usize,
+    relation: Option<String>,
+) -> Result<(), KremisError> {
+    use kremis_core::centrality::top_n;
+    use kremis_core::primitives::MAX_CENTRALITY_RESULTS;
+    use kremis_core::{Attribute, CentralityMetric, Ingestor};
+
+    let centrality_metric = match metric {
+        "degree" => CentralityMetric::Degree,
+        "weighted-degree" => CentralityMetric::WeightedDegree,
+        "pagerank" => CentralityMetric::PageRank { iterations },
+        "betweenness" => CentralityMetric::Betweenness,
+        _ => {
+            return Err(KremisError::SerializationError(format!(
+                "Unknown metric: {}. Use: degree, weighted-degree, pagerank, betweenness",
+                metric
+            )));
+        }
+    };
+    if top == 0 || top > MAX_CENTRALITY_RESULTS {
+        return Err(KremisError::InvalidSignal);
+    }
+    let relation = relation.map(Attribute::new);
+    if let Some(ref r) = relation {
+        Ingestor::validate_relation(r)?;
+    }
+    let (session, _) = load_or_create_session(db_path, backend)?;
+    let scores = session.centrality(centrality_metric, relation.as_ref())?;
+    let ranked = top_n(&scores, top);
+    let scale = centrality_metric.scale();
+
+    if json_mode {
+        let output = serde_json::json!({
+            "metric": metric,
+            "scale": scale,
+            "nodes": ranked.iter().map(|(node, score)| serde_json::json!({
+                "node": node.0, "score": score
+            })).collect::<Vec<_>>()
+        });
+        println!("{}", json_pretty(&output)?);
+        return Ok(());
+    }
+
+    if scale == 1 {
+        println!("Top {} nodes by {}:", ranked.len(), metric);
+    } else {
+        println!(
+            "Top {} nodes by {} (scores in units of 1/{}):",
+            ranked.len(),
+            metric,
+            scale
+        );
+    }
+    for (node, score) in &ranked {
+        println!("  Node {}: {}", node.0, score);
+    }
+
+    Ok(())
+}
+
 // =============================================================================
 // EXPORT COMMAND
 // =============================================================================
 
 /// Export graph.
 ///
-/// # M3 Fix
-///
-/// This function now supports both in-memory and persistent (redb) backends.
-/// For persistent backends, it builds a graph snapshot by iterating all
-/// nodes and edges from the database.
+/// Supports both in-memory and persistent (redb) backends. The canonical
+/// format is streamed into the file, so a persistent graph is never held in
+/// memory as a whole; JSON export builds a graph snapshot first.
 pub fn cmd_export(
     db_path: &PathBuf,
     backend: &str,
@@ -811,20 +1422,24 @@ pub fn cmd_export(
 
     let (session, _) = load_or_create_session(db_path, backend)?;
 
-    // M3 FIX: Use export_graph_snapshot() which works with both backends
-    let graph = session.export_graph_snapshot()?;
-
-    let data = match format {
+    let written = match format {
         "canonical" => {
-            let data = export_canonical(&graph)?;
-            let checksum = canonical_checksum(&graph);
-            println!("Checksum: {}", checksum);
-            data
+            let file = std::fs::File::create(&validated_output)
+                .map_err(|e| KremisError::SerializationError(format!("Write file: {}", e)))?;
+            let header = session.write_canonical(std::io::BufWriter::new(file))?;
+            println!("Checksum: {}", header.checksum);
+            std::fs::metadata(&validated_output)
+                .map_err(|e| KremisError::SerializationError(format!("Write file: {}", e)))?
+                .len()
         }
         "json" => {
+            let graph = session.export_graph_snapshot()?;
             let serializable = kremis_core::SerializableGraph::from(&graph);
-            serde_json::to_vec_pretty(&serializable)
-                .map_err(|e| KremisError::SerializationError(e.to_string()))?
+            let data = serde_json::to_vec_pretty(&serializable)
+                .map_err(|e| KremisError::SerializationError(e.to_string()))?;
+            std::fs::write(&validated_output, &data)
+                .map_err(|e| KremisError::SerializationError(format!("Write file: {}", e)))?;
+            data.len() as u64
         }
         _ => {
             return Err(KremisError::SerializationError(format!(
@@ -834,14 +1449,103 @@ pub fn cmd_export(
         }
     };
 
+    println!("Exported {} bytes to {:?}", written, validated_output);
+
+    Ok(())
+}
+
+/// Export the changes from a base canonical export to the current graph.
+///
+/// The base file is streamed past the current graph, reopened for each
+/// pass, so neither is held in memory and the base is not bound by
+/// `MAX_IMPORT_FILE_SIZE`.
+pub fn cmd_export_delta(
+    db_path: &PathBuf,
+    backend: &str,
+    output: &std::path::Path,
+    base: &std::path::Path,
+) -> Result<(), KremisError> {
+    let validated_output = validate_output_path(output)?;
+    let validated_base = validate_file_path(base)?;
+
+    let (session, _) = load_or_create_session(db_path, backend)?;
+    let delta = session.delta_from(|| {
+        std::fs::File::open(&validated_base)
+            .map(std::io::BufReader::new)
+            .map_err(|e| KremisError::SerializationError(format!("Read file: {}", e)))
+    })?;
+    let data = delta.to_bytes()?;
+
     std::fs::write(&validated_output, &data)
         .map_err(|e| KremisError::SerializationError(format!("Write file: {}", e)))?;
 
+    let changes = &delta.changes;
+    println!("Base:   {}", hex(&delta.base_hash));
+    println!("Target: {}", hex(&delta.target_hash));
+    println!(
+        "Delta: {} node(s), {} edge(s), {} propert(ies) changed",
+        changes.nodes_added.len() + changes.nodes_removed.len(),
+        changes.edges_added.len() + changes.edges_removed.len() + changes.edges_reweighted.len(),
+        changes.properties_added.len() + changes.properties_removed.len()
+    );
     println!("Exported {} bytes to {:?}", data.len(), validated_output);
 
     Ok(())
 }
 
+// =============================================================================
+// APPLY DELTA COMMAND
+// =============================================================================
+
+/// Apply a delta export to its base export, verify the target state hash and
+/// write the result as a canonical export.
+pub fn cmd_apply_delta(
+    json_mode: bool,
+    base: &std::path::Path,
+    delta: &std::path::Path,
+    output: &std::path::Path,
+) -> Result<(), KremisError> {
+    let validated_output = validate_output_path(output)?;
+    let base = read_canonical_file(base)?;
+
+    let validated_delta = validate_file_path(delta)?;
+    validate_file_size(&validated_delta, MAX_IMPORT_FILE_SIZE)?;
+    let data = std::fs::read(&validated_delta)
+        .map_err(|e| KremisError::SerializationError(format!("Read file: {}", e)))?;
+    let delta = GraphDelta::from_bytes(&data)?;
+
+    let graph = apply_delta(&base, &delta)?;
+    let data = export_canonical(&graph)?;
+    std::fs::write(&validated_output, &data)
+        .map_err(|e| KremisError::SerializationError(format!("Write file: {}", e)))?;
+
+    if json_mode {
+        let output = serde_json::json!({
+            "verified": true,
+            "base_hash": hex(&delta.base_hash),
+            "target_hash": hex(&delta.target_hash),
+            "node_count": graph.node_count()?,
+            "edge_count": graph.edge_count()?
+        });
+        println!("{}", json_pretty(&output)?);
+    } else {
+        println!("Verified target hash {}", hex(&delta.target_hash));
+        println!(
+            "Wrote {} nodes, {} edges to {:?}",
+            graph.node_count()?,
+            graph.edge_count()?,
+            validated_output
+        );
+    }
+
+    Ok(())
+}
+
+/// Lowercase hex of a state hash.
+fn hex(hash: &[u8; 32]) -> String {
+    hash.iter().map(|b| format!("{b:02x}")).collect()
+}
+
 // =============================================================================
 // IMPORT COMMAND
 // =============================================================================
@@ -852,6 +1556,42 @@ pub fn cmd_import(
     backend: &str,
     input: &std::path::Path,
 ) -> Result<(), KremisError> {
+    let session = if backend == "redb" {
+        import_redb(db_path, input)?
+    } else {
+        let session = Session::with_graph(read_canonical_file(input)?);
+        save_session(&session, db_path)?;
+        session
+    };
+
+    println!(
+        "Imported graph: {} nodes, {} edges",
+        session.node_count()?,
+        session.edge_count()?
+    );
+
+    Ok(())
+}
+
+/// Stream a canonical export from disk into an empty redb database.
+///
+/// The file is never read whole, so the import is bound by the export's node
+/// and edge limits rather than `MAX_IMPORT_FILE_SIZE`. The graph is rebuilt
+/// in memory once, at the end, for the import's snapshot checkpoint, and the
+/// opened database keeps its entity -> node index in memory as usual.
+fn import_redb(db_path: &PathBuf, input: &std::path::Path) -> Result<Session, KremisError> {
+    let validated_path = validate_file_path(input)?;
+    let file = std::fs::File::open(&validated_path)
+        .map_err(|e| KremisError::SerializationError(format!("Read file: {}", e)))?;
+
+    let mut graph = RedbGraph::open(db_path)?;
+    let diag = graph.import_canonical(std::io::BufReader::new(file))?;
+    warn_dangling(&diag);
+    Ok(Session::with_redb_graph(graph))
+}
+
+/// Read a canonical export from disk, warning about discarded dangling data.
+fn read_canonical_file(input: &std::path::Path) -> Result<Graph, KremisError> {
     // L1 FIX: Validate file path for security (prevents path traversal)
     let validated_path = validate_file_path(input)?;
 
@@ -862,27 +1602,176 @@ pub fn cmd_import(
         .map_err(|e| KremisError::SerializationError(format!("Read file: {}", e)))?;
 
     let (graph, diag) = import_canonical(&data)?;
+    warn_dangling(&diag);
+    Ok(graph)
+}
+
+/// Warn about the edges and properties an import discarded.
+fn warn_dangling(diag: &LoadDiagnostics) {
     if diag.dangling_edges > 0 || diag.dangling_properties > 0 {
         eprintln!(
             "warning: discarded {} dangling edge(s) and {} dangling propert(ies) referencing non-existent nodes",
             diag.dangling_edges, diag.dangling_properties
         );
     }
-    let session = Session::with_graph(graph);
+}
 
-    if backend == "redb" {
-        return Err(KremisError::SerializationError(
-            "Import to redb not yet supported. Use file backend.".to_string(),
-        ));
-    }
+// =============================================================================
+// MERGE COMMAND
+// =============================================================================
 
+/// Merge a canonical export into the graph, reconciling nodes by entity.
+pub fn cmd_merge(
+    db_path: &PathBuf,
+    backend: &str,
+    json_mode: bool,
+    input: &std::path::Path,
+    policy: &str,
+) -> Result<(), KremisError> {
+    let policy = match policy {
+        "sum" => MergePolicy::Sum,
+        "max" => MergePolicy::Max,
+        "keep-existing" => MergePolicy::KeepExisting,
+        _ => {
+            return Err(KremisError::SerializationError(format!(
+                "Unknown policy: {}. Use: sum, max, keep-existing",
+                policy
+            )));
+        }
+    };
+    let incoming = CanonicalGraph::from_graph(&read_canonical_file(input)?);
+
+    let (mut session, _) = load_or_create_session(db_path, backend)?;
+    let diag = session.merge_canonical(&incoming, policy)?;
     save_session(&session, db_path)?;
 
+    if json_mode {
+        let output = serde_json::json!({
+            "nodes_added": diag.nodes_added,
+            "nodes_reconciled": diag.nodes_reconciled,
+            "nodes_remapped": diag.nodes_remapped,
+            "edges_added": diag.edges_added,
+            "edges_combined": diag.edges_combined,
+            "properties_added": diag.properties_added,
+            "keys_bound": diag.keys_bound,
+            "key_conflicts": diag.key_conflicts,
+            "node_count": session.node_count()?,
+            "edge_count": session.edge_count()?
+        });
+        println!("{}", json_pretty(&output)?);
+    } else {
+        println!(
+            "Merged: {} node(s) added ({} remapped), {} reconciled",
+            diag.nodes_added, diag.nodes_remapped, diag.nodes_reconciled
+        );
+        println!(
+            "Edges: {} added, {} combined; properties: {} added",
+            diag.edges_added, diag.edges_combined, diag.properties_added
+        );
+        if diag.key_conflicts > 0 {
+            eprintln!(
+                "warning: skipped {} entity key binding(s) that conflict with existing keys",
+                diag.key_conflicts
+            );
+        }
+        println!(
+            "Graph: {} nodes, {} edges",
+            session.node_count()?,
+            session.edge_count()?
+        );
+    }
+
+    Ok(())
+}
+
+// =============================================================================
+// DIFF COMMAND
+// =============================================================================
+
+/// Show what changed between two canonical exports, or between an export
+/// and the database when `after` is omitted.
+pub fn cmd_diff(
+    db_path: &PathBuf,
+    backend: &str,
+    json_mode: bool,
+    before: &std::path::Path,
+    after: Option<&std::path::Path>,
+) -> Result<(), KremisError> {
+    let before = CanonicalGraph::from_graph(&read_canonical_file(before)?);
+    let after = match after {
+        Some(path) => read_canonical_file(path)?,
+        None => load_or_create_session(db_path, backend)?
+            .0
+            .export_graph_snapshot()?,
+    };
+    let delta = diff(&before, &CanonicalGraph::from_graph(&after));
+
+    if json_mode {
+        let output = serde_json::to_value(&delta)
+            .map_err(|e| KremisError::SerializationError(e.to_string()))?;
+        println!("{}", json_pretty(&output)?);
+        return Ok(());
+    }
+
     println!(
-        "Imported graph: {} nodes, {} edges",
-        session.node_count()?,
-        session.edge_count()?
+        "Nodes: +{} -{}",
+        delta.nodes_added.len(),
+        delta.nodes_removed.len()
     );
+    println!(
+        "Edges: +{} -{} ~{}",
+        delta.edges_added.len(),
+        delta.edges_removed.len(),
+        delta.edges_reweighted.len()
+    );
+    println!(
+        "Properties: +{} -{}",
+        delta.properties_added.len(),
+        delta.properties_removed.len()
+    );
+    let relation = |r: &Option<String>| r.as_ref().map(|r| format!(" [{r}]")).unwrap_or_default();
+    for node in &delta.nodes_added {
+        println!("+ node {} (entity {})", node.id, node.entity);
+    }
+    for node in &delta.nodes_removed {
+        println!("- node {} (entity {})", node.id, node.entity);
+    }
+    for edge in &delta.edges_added {
+        let label = relation(&edge.relation);
+        println!(
+            "+ edge {} -> {}{label} weight {}",
+            edge.from, edge.to, edge.weight
+        );
+    }
+    for edge in &delta.edges_removed {
+        let label = relation(&edge.relation);
+        println!(
+            "- edge {} -> {}{label} weight {}",
+            edge.from, edge.to, edge.weight
+        );
+    }
+    for edge in &delta.edges_reweighted {
+        let label = relation(&edge.relation);
+        println!(
+            "~ edge {} -> {}{label} weight {} -> {}",
+            edge.from,
+            edge.to,
+            edge.old_weight.value(),
+            edge.new_weight.value()
+        );
+    }
+    for prop in &delta.properties_added {
+        println!(
+            "+ property {} {} = {}",
+            prop.node_id, prop.attribute, prop.value
+        );
+    }
+    for prop in &delta.properties_removed {
+        println!(
+            "- property {} {} = {}",
+            prop.node_id, prop.attribute, prop.value
+        );
+    }
 
     Ok(())
 }
@@ -925,11 +1814,11 @@ pub fn cmd_init(db_path: &PathBuf, backend: &str, force: bool) -> Result<(), Kre
 
 /// Compute BLAKE3 cryptographic hash of the graph.
 pub fn cmd_hash(db_path: &PathBuf, backend: &str, json_mode: bool) -> Result<(), KremisError> {
-    use kremis_core::export::canonical_crypto_hash;
+    use kremis_core::stream::HashingWriter;
     let (session, _) = load_or_create_session(db_path, backend)?;
-    let graph = session.export_graph_snapshot()?;
-    let hash = canonical_crypto_hash(&graph)?;
-    let checksum = canonical_checksum(&graph);
+    let mut writer = HashingWriter::new(std::io::sink());
+    let checksum = session.write_canonical(&mut writer)?.checksum;
+    let hash = hex(&writer.finalize().0);
     if json_mode {
         let output = serde_json::json!({
             "hash": hash, "algorithm": "blake3", "checksum": checksum
@@ -942,6 +1831,74 @@ pub fn cmd_hash(db_path: &PathBuf, backend: &str, json_mode: bool) -> Result<(),
     Ok(())
 }
 
+// =============================================================================
+// REPLAY COMMAND
+// =============================================================================
+
+/// Rebuild the graph from the signal journal and compare canonical hashes.
+///
+/// Fails when the rebuilt graph does not hash like the stored one. With
+/// `output`, the rebuilt graph is also written in canonical format, which
+/// `import` can load into a file-backend database.
+pub fn cmd_replay(
+    db_path: &PathBuf,
+    backend: &str,
+    json_mode: bool,
+    output: Option<&std::path::Path>,
+) -> Result<(), KremisError> {
+    use kremis_core::export::canonical_crypto_hash;
+
+    let validated_output = output.map(validate_output_path).transpose()?;
+
+    let (session, resolved) = load_or_create_session(db_path, backend)?;
+    if resolved != "redb" {
+        return Err(KremisError::SerializationError(
+            "Replay needs the redb backend: only redb databases keep a journal.".to_string(),
+        ));
+    }
+    let journal = session.journal()?.ok_or_else(|| {
+        KremisError::SerializationError(
+            "The journal does not cover this database: it already held data when the journal was introduced."
+                .to_string(),
+        )
+    })?;
+
+    let replayed = kremis_core::journal::replay(&journal)?;
+    let replayed_hash = canonical_crypto_hash(&replayed)?;
+    let stored_hash = canonical_crypto_hash(&session.export_graph_snapshot()?)?;
+    let matches = replayed_hash == stored_hash;
+
+    if let Some(path) = &validated_output {
+        let data = export_canonical(&replayed)?;
+        std::fs::write(path, &data)
+            .map_err(|e| KremisError::SerializationError(format!("Write file: {}", e)))?;
+    }
+
+    if json_mode {
+        let output = serde_json::json!({
+            "entries": journal.len(),
+            "replayed_hash": replayed_hash,
+            "stored_hash": stored_hash,
+            "matches": matches
+        });
+        println!("{}", json_pretty(&output)?);
+    } else {
+        println!("Replayed {} journal entries", journal.len());
+        println!("Replayed: {}", replayed_hash);
+        println!("Stored:   {}", stored_hash);
+        if let Some(path) = &validated_output {
+            println!("Wrote rebuilt graph to {:?}", path);
+        }
+    }
+
+    if !matches {
+        return Err(KremisError::StorageCorruption(
+            "replayed graph does not match the stored graph".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 // =============================================================================
 // HELPER FUNCTIONS
 // =============================================================================
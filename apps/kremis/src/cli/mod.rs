@@ -8,11 +8,19 @@
 //! - `status` - Show graph status
 //! - `stage` - Show developmental stage
 //! - `ingest` - Ingest signals from a file
-//! - `query` - Execute a query on the graph
-//! - `export` - Export graph to file
+//! - `retract-property` - Retract one (attribute, value) pair from an entity
+//! - `delete` - Delete an entity's node with its edges and properties
+//! - `query` - Execute a query on the graph, from flags or a textual query
+//! - `components` - Find weakly or strongly connected components
+//! - `centrality` - Rank nodes by degree, PageRank or betweenness
+//! - `export` - Export graph to file, in full or as a delta against a base export
+//! - `apply-delta` - Apply a delta export to its base and verify the result
 //! - `import` - Import graph from file
+//! - `merge` - Merge a canonical export into the graph, reconciling by entity
+//! - `diff` - Show what changed between two exports, or an export and the database
 //! - `init` - Initialize new database
 //! - `hash` - Compute BLAKE3 cryptographic hash of graph
+//! - `replay` - Rebuild the graph from its signal journal and compare hashes
 
 mod commands;
 
@@ -102,23 +110,56 @@ pub enum Commands {
         /// Fail with exit code 1 if any lines are skipped (text format only)
         #[arg(long)]
         strict: bool,
+
+        /// Link each signal to the N signals before it (default 1). Not
+        /// allowed when the input lists explicit links.
+        #[arg(long)]
+        window: Option<usize>,
+    },
+
+    /// Retract one (attribute, value) pair from an entity
+    RetractProperty {
+        /// Entity ID the property belongs to
+        #[arg(long)]
+        entity: u64,
+
+        /// Attribute name
+        #[arg(long)]
+        attribute: String,
+
+        /// Value to retract
+        #[arg(long)]
+        value: String,
+    },
+
+    /// Delete an entity's node with its edges and properties
+    Delete {
+        /// Entity ID to delete
+        #[arg(long)]
+        entity: u64,
     },
 
     /// Execute a query on the graph
     Query {
-        /// Query type (lookup, traverse, path, intersect, related, properties)
-        #[arg(short = 't', long)]
-        query_type: String,
+        /// Query in the textual query language, e.g. "PATH 42 -> 87 MAXHOPS 5".
+        /// Replaces --query-type and its options.
+        #[arg(conflicts_with = "query_type")]
+        expression: Option<String>,
+
+        /// Query type (lookup, traverse, path, paths, k-strongest, reachable, intersect,
+        /// related, properties)
+        #[arg(short = 't', long, required_unless_present = "expression")]
+        query_type: Option<String>,
 
         /// Start node ID
         #[arg(short, long)]
         start: Option<u64>,
 
-        /// End node ID (for path queries)
+        /// End node ID (for path, paths, k-strongest and reachable queries)
         #[arg(short, long)]
         end: Option<u64>,
 
-        /// Traversal depth
+        /// Traversal depth (maximum hops per path for paths)
         #[arg(short, long, default_value = "3")]
         depth: usize,
 
@@ -130,9 +171,60 @@ pub enum Commands {
         #[arg(long)]
         nodes: Option<String>,
 
-        /// Minimum edge weight filter
+        /// Minimum edge weight filter (traverse, path)
         #[arg(long)]
         min_weight: Option<i64>,
+
+        /// Only follow edges with this relation label (traverse, path, paths,
+        /// k-strongest, reachable)
+        #[arg(long)]
+        relation: Option<String>,
+
+        /// Number of paths to return (paths, k-strongest; default 10)
+        #[arg(long)]
+        limit: Option<usize>,
+
+        /// Comma-separated node IDs the path must not visit (path)
+        #[arg(long)]
+        avoid: Option<String>,
+
+        /// Comma-separated node IDs the path must visit, in order (path)
+        #[arg(long)]
+        via: Option<String>,
+
+        /// Maximum number of hops (path)
+        #[arg(long)]
+        max_hops: Option<usize>,
+    },
+
+    /// Find weakly or strongly connected components
+    Components {
+        /// Compute strongly connected components and report cycles
+        #[arg(long)]
+        strong: bool,
+
+        /// Only follow edges with this relation label
+        #[arg(long)]
+        relation: Option<String>,
+    },
+
+    /// Rank nodes by a centrality measure
+    Centrality {
+        /// Measure (degree, weighted-degree, pagerank, betweenness)
+        #[arg(short, long, default_value = "degree")]
+        metric: String,
+
+        /// Number of top nodes to show
+        #[arg(long, default_value = "10")]
+        top: usize,
+
+        /// PageRank iterations
+        #[arg(long, default_value = "20")]
+        iterations: usize,
+
+        /// Only follow edges with this relation label
+        #[arg(long)]
+        relation: Option<String>,
     },
 
     /// Export graph in canonical format
@@ -144,15 +236,55 @@ pub enum Commands {
         /// Export format (canonical, json)
         #[arg(short = 't', long, default_value = "canonical")]
         format: String,
+
+        /// Write a delta against this canonical export instead of the full graph
+        #[arg(long, conflicts_with = "format")]
+        base: Option<PathBuf>,
     },
 
-    /// Import graph from canonical format (file backend only)
+    /// Apply a delta export to its base export and verify the target hash
+    ApplyDelta {
+        /// Canonical export the delta was computed against
+        #[arg(long)]
+        base: PathBuf,
+
+        /// Delta export to apply
+        #[arg(long)]
+        delta: PathBuf,
+
+        /// Where to write the resulting canonical export
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+
+    /// Import graph from canonical format (replaces a file graph; redb must be empty)
     Import {
         /// Input file path
         #[arg(short, long)]
         input: PathBuf,
     },
 
+    /// Merge a canonical export into the graph, reconciling nodes by entity
+    Merge {
+        /// Input file path
+        #[arg(short, long)]
+        input: PathBuf,
+
+        /// How to combine edges present on both sides (sum, max, keep-existing)
+        #[arg(short, long, default_value = "sum")]
+        policy: String,
+    },
+
+    /// Show what changed between two canonical exports, or between an export
+    /// and the database
+    Diff {
+        /// Canonical export of the earlier state
+        before: PathBuf,
+
+        /// Canonical export of the later state (default: the database)
+        after: Option<PathBuf>,
+    },
+
     /// Initialize a new empty database
     Init {
         /// Force initialization even if database exists
@@ -162,6 +294,13 @@ pub enum Commands {
 
     /// Compute BLAKE3 cryptographic hash of graph
     Hash,
+
+    /// Rebuild the graph from its signal journal and compare hashes (redb only)
+    Replay {
+        /// Also write the rebuilt graph to this file (canonical format)
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
 }
 
 // =============================================================================
@@ -186,6 +325,7 @@ pub async fn execute(cli: Cli, config: AppConfig) -> Result<(), KremisError> {
             format,
             from_stdin,
             strict,
+            window,
         }) => cmd_ingest(
             &cli.database,
             backend,
@@ -194,8 +334,27 @@ pub async fn execute(cli: Cli, config: AppConfig) -> Result<(), KremisError> {
             &format,
             from_stdin,
             strict,
+            window,
         ),
+        Some(Commands::RetractProperty {
+            entity,
+            attribute,
+            value,
+        }) => cmd_retract_property(
+            &cli.database,
+            backend,
+            json_mode,
+            entity,
+            &attribute,
+            &value,
+        ),
+        Some(Commands::Delete { entity }) => cmd_delete(&cli.database, backend, json_mode, entity),
+        Some(Commands::Query {
+            expression: Some(expression),
+            ..
+        }) => cmd_query_text(&cli.database, backend, json_mode, &expression),
         Some(Commands::Query {
+            expression: None,
             query_type,
             start,
             end,
@@ -203,24 +362,72 @@ pub async fn execute(cli: Cli, config: AppConfig) -> Result<(), KremisError> {
             entity,
             nodes,
             min_weight,
+            relation,
+            limit,
+            avoid,
+            via,
+            max_hops,
         }) => cmd_query(
             &cli.database,
             backend,
             json_mode,
-            &query_type,
+            query_type.as_deref().unwrap_or_default(),
             start,
             end,
             depth,
             entity,
             nodes,
             min_weight,
+            relation,
+            limit,
+            avoid,
+            via,
+            max_hops,
         ),
-        Some(Commands::Export { output, format }) => {
-            cmd_export(&cli.database, backend, &output, &format)
+        Some(Commands::Components { strong, relation }) => {
+            cmd_components(&cli.database, backend, json_mode, strong, relation)
         }
+        Some(Commands::Centrality {
+            metric,
+            top,
+            iterations,
+            relation,
+        }) => cmd_centrality(
+            &cli.database,
+            backend,
+            json_mode,
+            &metric,
+            top,
+            iterations,
+            relation,
+        ),
+        Some(Commands::Export {
+            output,
+            base: Some(base),
+            ..
+        }) => cmd_export_delta(&cli.database, backend, &output, &base),
+        Some(Commands::Export {
+            output,
+            format,
+            base: None,
+        }) => cmd_export(&cli.database, backend, &output, &format),
+        Some(Commands::ApplyDelta {
+            base,
+            delta,
+            output,
+        }) => cmd_apply_delta(json_mode, &base, &delta, &output),
         Some(Commands::Import { input }) => cmd_import(&cli.database, backend, &input),
+        Some(Commands::Merge { input, policy }) => {
+            cmd_merge(&cli.database, backend, json_mode, &input, &policy)
+        }
+        Some(Commands::Diff { before, after }) => {
+            cmd_diff(&cli.database, backend, json_mode, &before, after.as_deref())
+        }
         Some(Commands::Init { force }) => cmd_init(&cli.database, backend, force),
         Some(Commands::Hash) => cmd_hash(&cli.database, backend, json_mode),
+        Some(Commands::Replay { output }) => {
+            cmd_replay(&cli.database, backend, json_mode, output.as_deref())
+        }
         None => {
             // No subcommand - show status by default
             cmd_status(&cli.database, backend, json_mode)
@@ -14,10 +14,18 @@
 //! | `KREMIS_LOG_FORMAT` | `[logging] format`      |
 //! | `RUST_LOG`          | `[logging] level`       |
 //! | `KREMIS_RATE_LIMIT` | `[api] rate_limit`      |
+//! | `KREMIS_VISIT_BUDGET` | `[api] default_visit_budget` |
+//! | `KREMIS_MAX_VISIT_BUDGET` | `[api] max_visit_budget` |
+//! | `KREMIS_CHECKPOINT_INTERVAL` | `[storage] checkpoint_interval` |
+//! | `KREMIS_MAX_CHECKPOINTS` | `[storage] max_checkpoints` |
 //! | `KREMIS_API_KEY`    | `[security] api_key`    |
 //! | `KREMIS_CORS_ORIGINS` | `[cors] origins`      |
 //! | `KREMIS_URL`        | `[mcp] url`             |
 
+use kremis_core::CheckpointPolicy;
+use kremis_core::primitives::{
+    DEFAULT_VISIT_BUDGET, MAX_SNAPSHOT_CHECKPOINTS, MAX_VISIT_BUDGET, SNAPSHOT_CHECKPOINT_INTERVAL,
+};
 use serde::Deserialize;
 
 // =============================================================================
@@ -60,18 +68,79 @@ pub struct ApiConfig {
     /// Requests per second. `0` disables rate limiting.
     #[serde(default = "ApiConfig::default_rate_limit")]
     pub rate_limit: u32,
+
+    /// Visit budget of a query that sets no `timeout_ms`.
+    #[serde(default = "ApiConfig::default_visit_budget")]
+    pub default_visit_budget: u64,
+
+    /// Largest visit budget a query may ask for with `timeout_ms`.
+    #[serde(default = "ApiConfig::default_max_visit_budget")]
+    pub max_visit_budget: u64,
 }
 
 impl ApiConfig {
     fn default_rate_limit() -> u32 {
         100
     }
+    fn default_visit_budget() -> u64 {
+        DEFAULT_VISIT_BUDGET
+    }
+    fn default_max_visit_budget() -> u64 {
+        MAX_VISIT_BUDGET
+    }
 }
 
 impl Default for ApiConfig {
     fn default() -> Self {
         Self {
             rate_limit: Self::default_rate_limit(),
+            default_visit_budget: Self::default_visit_budget(),
+            max_visit_budget: Self::default_max_visit_budget(),
+        }
+    }
+}
+
+/// Persistent storage configuration.
+///
+/// Every `checkpoint_interval` logged writes, the write that reaches the
+/// interval also rebuilds, serializes and hashes the whole graph as a snapshot
+/// checkpoint, and each kept checkpoint stores a copy of the graph. Raise the
+/// interval to make those writes rarer, at the cost of longer point-in-time
+/// replays.
+#[derive(Debug, Clone, Deserialize)]
+pub struct StorageConfig {
+    /// Logged writes between two snapshot checkpoints. `0` takes none.
+    #[serde(default = "StorageConfig::default_checkpoint_interval")]
+    pub checkpoint_interval: u64,
+
+    /// Snapshot checkpoints kept before older ones are thinned out.
+    #[serde(default = "StorageConfig::default_max_checkpoints")]
+    pub max_checkpoints: usize,
+}
+
+impl StorageConfig {
+    fn default_checkpoint_interval() -> u64 {
+        SNAPSHOT_CHECKPOINT_INTERVAL
+    }
+    fn default_max_checkpoints() -> usize {
+        MAX_SNAPSHOT_CHECKPOINTS
+    }
+
+    /// The checkpoint policy of a persistent database.
+    #[must_use]
+    pub fn checkpoint_policy(&self) -> CheckpointPolicy {
+        CheckpointPolicy {
+            interval: self.checkpoint_interval,
+            max_kept: self.max_checkpoints,
+        }
+    }
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            checkpoint_interval: Self::default_checkpoint_interval(),
+            max_checkpoints: Self::default_max_checkpoints(),
         }
     }
 }
@@ -148,6 +217,10 @@ pub struct AppConfig {
     #[serde(default)]
     pub api: ApiConfig,
 
+    /// Persistent storage settings.
+    #[serde(default)]
+    pub storage: StorageConfig,
+
     /// Security settings.
     #[serde(default)]
     pub security: SecurityConfig,
@@ -210,6 +283,30 @@ impl AppConfig {
             config.api.rate_limit = n;
             report.env_overrides.push("KREMIS_RATE_LIMIT");
         }
+        if let Ok(v) = std::env::var("KREMIS_VISIT_BUDGET")
+            && let Ok(n) = v.parse::<u64>()
+        {
+            config.api.default_visit_budget = n;
+            report.env_overrides.push("KREMIS_VISIT_BUDGET");
+        }
+        if let Ok(v) = std::env::var("KREMIS_MAX_VISIT_BUDGET")
+            && let Ok(n) = v.parse::<u64>()
+        {
+            config.api.max_visit_budget = n;
+            report.env_overrides.push("KREMIS_MAX_VISIT_BUDGET");
+        }
+        if let Ok(v) = std::env::var("KREMIS_CHECKPOINT_INTERVAL")
+            && let Ok(n) = v.parse::<u64>()
+        {
+            config.storage.checkpoint_interval = n;
+            report.env_overrides.push("KREMIS_CHECKPOINT_INTERVAL");
+        }
+        if let Ok(v) = std::env::var("KREMIS_MAX_CHECKPOINTS")
+            && let Ok(n) = v.parse::<usize>()
+        {
+            config.storage.max_checkpoints = n;
+            report.env_overrides.push("KREMIS_MAX_CHECKPOINTS");
+        }
         if let Ok(v) = std::env::var("KREMIS_API_KEY") {
             if !v.is_empty() {
                 config.security.api_key = Some(v);
@@ -254,6 +351,9 @@ mod tests {
         assert_eq!(cfg.logging.format, "text");
         assert_eq!(cfg.logging.level, "kremis=info,tower_http=debug");
         assert_eq!(cfg.api.rate_limit, 100);
+        assert_eq!(cfg.api.default_visit_budget, DEFAULT_VISIT_BUDGET);
+        assert_eq!(cfg.api.max_visit_budget, MAX_VISIT_BUDGET);
+        assert_eq!(cfg.storage.checkpoint_policy(), CheckpointPolicy::default());
         assert!(cfg.security.api_key.is_none());
         assert!(cfg.cors.origins.is_empty());
         assert_eq!(cfg.mcp.url, "http://localhost:8080");
@@ -302,6 +402,12 @@ level  = "debug"
 
 [api]
 rate_limit = 50
+default_visit_budget = 5000
+max_visit_budget = 20000
+
+[storage]
+checkpoint_interval = 4096
+max_checkpoints = 8
 
 [security]
 api_key = "secret"
@@ -316,6 +422,10 @@ url = "http://kremis:9090"
         assert_eq!(cfg.logging.format, "json");
         assert_eq!(cfg.logging.level, "debug");
         assert_eq!(cfg.api.rate_limit, 50);
+        assert_eq!(cfg.api.default_visit_budget, 5000);
+        assert_eq!(cfg.api.max_visit_budget, 20000);
+        assert_eq!(cfg.storage.checkpoint_interval, 4096);
+        assert_eq!(cfg.storage.max_checkpoints, 8);
         assert_eq!(cfg.security.api_key.as_deref(), Some("secret"));
         assert_eq!(cfg.cors.origins, vec!["https://example.com"]);
         assert_eq!(cfg.mcp.url, "http://kremis:9090");
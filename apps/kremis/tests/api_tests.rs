@@ -15,9 +15,11 @@
 use axum::http::HeaderValue;
 use axum_test::TestServer;
 use kremis::api::{
-    AppState, BatchIngestRequest, BatchIngestResponse, CertifyResponse, ExportResponse,
-    HealthResponse, IngestRequest, IngestResponse, QueryRequest, QueryResponse, RetractRequest,
-    RetractResponse, StageResponse, StatusResponse, create_router,
+    AppState, BatchIngestRequest, BatchIngestResponse, CentralityResponse, CertifyResponse,
+    ComponentKind, ComponentsResponse, DeleteNodeRequest, DeleteNodeResponse, DeltaExportResponse,
+    DiffResponse, ExportResponse, HealthResponse, IngestRequest, IngestResponse, PipelineResponse,
+    QueryBudget, QueryRequest, QueryResponse, RetractPropertyRequest, RetractPropertyResponse,
+    RetractRequest, RetractResponse, StageResponse, StatusResponse, create_router,
 };
 use kremis_core::Session;
 use serde_json::json;
@@ -79,6 +81,19 @@ fn create_populated_test_server() -> (TestServer, TestGuard) {
     (TestServer::new(router), TestGuard { _guard: guard })
 }
 
+/// Create a test server over a fresh redb-backed session.
+/// The returned directory holds the database and must outlive the server.
+fn create_persistent_test_server() -> (TestServer, TestGuard, tempfile::TempDir) {
+    let guard = AUTH_TEST_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
+    // SAFETY: Tests run sequentially under AUTH_TEST_MUTEX, so no concurrent env access.
+    unsafe { std::env::remove_var("KREMIS_API_KEY") };
+    let dir = tempfile::tempdir().unwrap();
+    let session = Session::with_redb(dir.path().join("api.redb")).unwrap();
+    let state = AppState::new(session);
+    let router = create_router(state);
+    (TestServer::new(router), TestGuard { _guard: guard }, dir)
+}
+
 // =============================================================================
 // HEALTH ENDPOINT TESTS
 // =============================================================================
@@ -177,9 +192,13 @@ async fn test_ingest_valid_signal() {
     let (server, _guard) = create_test_server();
 
     let request = IngestRequest {
-        entity_id: 1,
+        entity_id: Some(1),
+        entity_key: None,
         attribute: "name".to_string(),
         value: "Alice".to_string(),
+        link_from_entity_id: None,
+        link_from_entity_key: None,
+        relation: None,
     };
 
     let response = server.post("/signal").json(&request).await;
@@ -226,15 +245,45 @@ async fn test_ingest_empty_value() {
     assert!(!result.success);
 }
 
+#[tokio::test]
+async fn test_ingest_rejects_raw_ids_in_the_keyed_range() {
+    let (server, _guard) = create_test_server();
+    let keyed = kremis_core::primitives::KEYED_ENTITY_BASE;
+
+    for body in [
+        json!({"entity_id": keyed, "attribute": "name", "value": "Alice"}),
+        json!({"entity_id": 1, "attribute": "name", "value": "Alice", "link_from_entity_id": u64::MAX}),
+        json!({"signals": [{"entity_id": keyed + 7, "attribute": "name", "value": "Alice"}]}),
+    ] {
+        let path = if body.get("signals").is_some() {
+            "/signals"
+        } else {
+            "/signal"
+        };
+        server
+            .post(path)
+            .json(&body)
+            .await
+            .assert_status_bad_request();
+    }
+
+    let status: StatusResponse = server.get("/status").await.json();
+    assert_eq!(status.node_count, 0);
+}
+
 #[tokio::test]
 async fn test_ingest_multiple_signals() {
     let (server, _guard) = create_test_server();
 
     // Ingest first signal
     let request1 = IngestRequest {
-        entity_id: 1,
+        entity_id: Some(1),
+        entity_key: None,
         attribute: "name".to_string(),
         value: "Alice".to_string(),
+        link_from_entity_id: None,
+        link_from_entity_key: None,
+        relation: None,
     };
     let response1 = server.post("/signal").json(&request1).await;
     let result1: IngestResponse = response1.json();
@@ -242,9 +291,13 @@ async fn test_ingest_multiple_signals() {
 
     // Ingest second signal
     let request2 = IngestRequest {
-        entity_id: 2,
+        entity_id: Some(2),
+        entity_key: None,
         attribute: "name".to_string(),
         value: "Bob".to_string(),
+        link_from_entity_id: None,
+        link_from_entity_key: None,
+        relation: None,
     };
     let response2 = server.post("/signal").json(&request2).await;
     let result2: IngestResponse = response2.json();
@@ -264,7 +317,10 @@ async fn test_ingest_multiple_signals() {
 async fn test_query_lookup_not_found() {
     let (server, _guard) = create_test_server();
 
-    let request = QueryRequest::Lookup { entity_id: 999 };
+    let request = QueryRequest::Lookup {
+        entity_id: Some(999),
+        entity_key: None,
+    };
     let response = server.post("/query").json(&request).await;
 
     response.assert_status_ok();
@@ -279,7 +335,10 @@ async fn test_query_lookup_not_found() {
 async fn test_query_lookup_found() {
     let (server, _guard) = create_populated_test_server();
 
-    let request = QueryRequest::Lookup { entity_id: 1 };
+    let request = QueryRequest::Lookup {
+        entity_id: Some(1),
+        entity_key: None,
+    };
     let response = server.post("/query").json(&request).await;
 
     response.assert_status_ok();
@@ -295,7 +354,10 @@ async fn test_query_traverse() {
     let (server, _guard) = create_populated_test_server();
 
     // First lookup to get a node ID
-    let lookup = QueryRequest::Lookup { entity_id: 1 };
+    let lookup = QueryRequest::Lookup {
+        entity_id: Some(1),
+        entity_key: None,
+    };
     let lookup_response = server.post("/query").json(&lookup).await;
     let lookup_result: QueryResponse = lookup_response.json();
 
@@ -310,7 +372,11 @@ async fn test_query_traverse() {
 
     let node_id = lookup_result.path[0];
 
-    let request = QueryRequest::Traverse { node_id, depth: 2 };
+    let request = QueryRequest::Traverse {
+        node_id,
+        depth: 2,
+        relation: None,
+    };
     let response = server.post("/query").json(&request).await;
 
     response.assert_status_ok();
@@ -337,7 +403,10 @@ async fn test_query_traverse_filtered() {
     let (server, _guard) = create_populated_test_server();
 
     // First verify node 1 exists
-    let lookup = QueryRequest::Lookup { entity_id: 1 };
+    let lookup = QueryRequest::Lookup {
+        entity_id: Some(1),
+        entity_key: None,
+    };
     let lookup_response = server.post("/query").json(&lookup).await;
     let lookup_result: QueryResponse = lookup_response.json();
     assert!(lookup_result.found, "Entity 1 should exist");
@@ -349,6 +418,7 @@ async fn test_query_traverse_filtered() {
         depth: 2,
         min_weight: 0,
         top_k: None,
+        relation: None,
     };
     let response = server.post("/query").json(&request).await;
 
@@ -371,6 +441,7 @@ async fn test_query_traverse_filtered() {
         depth: 2,
         min_weight: 1000,
         top_k: None,
+        relation: None,
     };
     let high_response = server.post("/query").json(&high_filter).await;
     let high_result: QueryResponse = high_response.json();
@@ -387,8 +458,14 @@ async fn test_query_strongest_path() {
     let (server, _guard) = create_populated_test_server();
 
     // First verify both nodes exist
-    let lookup1 = QueryRequest::Lookup { entity_id: 1 };
-    let lookup2 = QueryRequest::Lookup { entity_id: 2 };
+    let lookup1 = QueryRequest::Lookup {
+        entity_id: Some(1),
+        entity_key: None,
+    };
+    let lookup2 = QueryRequest::Lookup {
+        entity_id: Some(2),
+        entity_key: None,
+    };
     let resp1 = server.post("/query").json(&lookup1).await;
     let resp2 = server.post("/query").json(&lookup2).await;
     let result1: QueryResponse = resp1.json();
@@ -402,6 +479,11 @@ async fn test_query_strongest_path() {
     let request = QueryRequest::StrongestPath {
         start: node1,
         end: node2,
+        relation: None,
+        avoid: vec![],
+        waypoints: vec![],
+        max_hops: None,
+        min_weight: None,
     };
     let response = server.post("/query").json(&request).await;
 
@@ -430,8 +512,14 @@ async fn test_query_intersect() {
     let (server, _guard) = create_populated_test_server();
 
     // Get actual node IDs from lookups
-    let lookup1 = QueryRequest::Lookup { entity_id: 1 };
-    let lookup2 = QueryRequest::Lookup { entity_id: 2 };
+    let lookup1 = QueryRequest::Lookup {
+        entity_id: Some(1),
+        entity_key: None,
+    };
+    let lookup2 = QueryRequest::Lookup {
+        entity_id: Some(2),
+        entity_key: None,
+    };
     let resp1 = server.post("/query").json(&lookup1).await;
     let resp2 = server.post("/query").json(&lookup2).await;
     let result1: QueryResponse = resp1.json();
@@ -487,7 +575,10 @@ async fn test_query_related() {
     let (server, _guard) = create_populated_test_server();
 
     // First get actual node ID
-    let lookup = QueryRequest::Lookup { entity_id: 1 };
+    let lookup = QueryRequest::Lookup {
+        entity_id: Some(1),
+        entity_key: None,
+    };
     let lookup_response = server.post("/query").json(&lookup).await;
     let lookup_result: QueryResponse = lookup_response.json();
     assert!(lookup_result.found, "Entity 1 should exist");
@@ -579,7 +670,10 @@ async fn test_traverse_filtered_top_k_limits_result_count() {
     let (server, _guard) = create_star_graph_server();
 
     // Lookup hub node
-    let lookup = QueryRequest::Lookup { entity_id: 1 };
+    let lookup = QueryRequest::Lookup {
+        entity_id: Some(1),
+        entity_key: None,
+    };
     let resp = server.post("/query").json(&lookup).await;
     let result: QueryResponse = resp.json();
     assert!(result.found, "Hub entity 1 must exist");
@@ -591,6 +685,7 @@ async fn test_traverse_filtered_top_k_limits_result_count() {
         depth: 2,
         min_weight: 0,
         top_k: Some(top_k_val),
+        relation: None,
     };
     let response = server.post("/query").json(&request).await;
     response.assert_status_ok();
@@ -607,7 +702,10 @@ async fn test_traverse_filtered_top_k_limits_result_count() {
 async fn test_traverse_filtered_top_k_returns_highest_weights() {
     let (server, _guard) = create_star_graph_server();
 
-    let lookup = QueryRequest::Lookup { entity_id: 1 };
+    let lookup = QueryRequest::Lookup {
+        entity_id: Some(1),
+        entity_key: None,
+    };
     let resp = server.post("/query").json(&lookup).await;
     let result: QueryResponse = resp.json();
     assert!(result.found);
@@ -619,6 +717,7 @@ async fn test_traverse_filtered_top_k_returns_highest_weights() {
         depth: 2,
         min_weight: 0,
         top_k: Some(top_k_val),
+        relation: None,
     };
     let response = server.post("/query").json(&request).await;
     response.assert_status_ok();
@@ -642,7 +741,10 @@ async fn test_traverse_filtered_top_k_returns_highest_weights() {
 async fn test_traverse_filtered_top_k_none_returns_all() {
     let (server, _guard) = create_star_graph_server();
 
-    let lookup = QueryRequest::Lookup { entity_id: 1 };
+    let lookup = QueryRequest::Lookup {
+        entity_id: Some(1),
+        entity_key: None,
+    };
     let resp = server.post("/query").json(&lookup).await;
     let result: QueryResponse = resp.json();
     assert!(result.found);
@@ -654,6 +756,7 @@ async fn test_traverse_filtered_top_k_none_returns_all() {
         depth: 2,
         min_weight: 0,
         top_k: None,
+        relation: None,
     };
     let resp_no_limit: QueryResponse = server.post("/query").json(&req_no_limit).await.json();
     assert!(resp_no_limit.success);
@@ -665,6 +768,7 @@ async fn test_traverse_filtered_top_k_none_returns_all() {
         depth: 2,
         min_weight: 0,
         top_k: Some(0),
+        relation: None,
     };
     let resp_zero: QueryResponse = server.post("/query").json(&req_zero).await.json();
     assert_eq!(
@@ -710,7 +814,10 @@ fn create_isolated_pair_server() -> (TestServer, TestGuard) {
 async fn test_query_lookup_missing_has_diagnostic() {
     let (server, _guard) = create_test_server();
 
-    let request = QueryRequest::Lookup { entity_id: 99999 };
+    let request = QueryRequest::Lookup {
+        entity_id: Some(99999),
+        entity_key: None,
+    };
     let response = server.post("/query").json(&request).await;
 
     response.assert_status_ok();
@@ -727,6 +834,7 @@ async fn test_query_traverse_missing_node_has_diagnostic() {
     let request = QueryRequest::Traverse {
         node_id: 99999,
         depth: 2,
+        relation: None,
     };
     let response = server.post("/query").json(&request).await;
 
@@ -742,13 +850,20 @@ async fn test_query_traverse_found_no_diagnostic() {
     let (server, _guard) = create_populated_test_server();
 
     // Lookup to get a valid node ID
-    let lookup = QueryRequest::Lookup { entity_id: 1 };
+    let lookup = QueryRequest::Lookup {
+        entity_id: Some(1),
+        entity_key: None,
+    };
     let lookup_resp = server.post("/query").json(&lookup).await;
     let lookup_result: QueryResponse = lookup_resp.json();
     assert!(lookup_result.found, "Entity 1 should exist");
     let node_id = lookup_result.path[0];
 
-    let request = QueryRequest::Traverse { node_id, depth: 2 };
+    let request = QueryRequest::Traverse {
+        node_id,
+        depth: 2,
+        relation: None,
+    };
     let response = server.post("/query").json(&request).await;
 
     response.assert_status_ok();
@@ -768,6 +883,11 @@ async fn test_query_path_start_not_found_has_diagnostic() {
     let request = QueryRequest::StrongestPath {
         start: 99999,
         end: 88888,
+        relation: None,
+        avoid: vec![],
+        waypoints: vec![],
+        max_hops: None,
+        min_weight: None,
     };
     let response = server.post("/query").json(&request).await;
 
@@ -783,7 +903,10 @@ async fn test_query_path_end_not_found_has_diagnostic() {
     let (server, _guard) = create_populated_test_server();
 
     // Lookup entity 1 to get a real start node
-    let lookup = QueryRequest::Lookup { entity_id: 1 };
+    let lookup = QueryRequest::Lookup {
+        entity_id: Some(1),
+        entity_key: None,
+    };
     let lookup_resp = server.post("/query").json(&lookup).await;
     let lookup_result: QueryResponse = lookup_resp.json();
     assert!(lookup_result.found, "Entity 1 should exist");
@@ -791,7 +914,12 @@ async fn test_query_path_end_not_found_has_diagnostic() {
 
     let request = QueryRequest::StrongestPath {
         start: start_node,
-        end: 99999, // non-existent
+        end: 99999, // non-existent,
+        relation: None,
+        avoid: vec![],
+        waypoints: vec![],
+        max_hops: None,
+        min_weight: None,
     };
     let response = server.post("/query").json(&request).await;
 
@@ -807,8 +935,14 @@ async fn test_query_path_no_path_has_diagnostic() {
     let (server, _guard) = create_isolated_pair_server();
 
     // Lookup both isolated entities to get their node IDs
-    let lookup1 = QueryRequest::Lookup { entity_id: 100 };
-    let lookup2 = QueryRequest::Lookup { entity_id: 200 };
+    let lookup1 = QueryRequest::Lookup {
+        entity_id: Some(100),
+        entity_key: None,
+    };
+    let lookup2 = QueryRequest::Lookup {
+        entity_id: Some(200),
+        entity_key: None,
+    };
     let resp1 = server.post("/query").json(&lookup1).await;
     let resp2 = server.post("/query").json(&lookup2).await;
     let result1: QueryResponse = resp1.json();
@@ -822,6 +956,11 @@ async fn test_query_path_no_path_has_diagnostic() {
     let request = QueryRequest::StrongestPath {
         start: node1,
         end: node2,
+        relation: None,
+        avoid: vec![],
+        waypoints: vec![],
+        max_hops: None,
+        min_weight: None,
     };
     let response = server.post("/query").json(&request).await;
 
@@ -934,6 +1073,125 @@ async fn test_export_populated_graph() {
     assert!(decoded.is_ok());
 }
 
+#[tokio::test]
+async fn test_export_streams_the_same_body_across_chunks() {
+    use kremis_core::{EdgeWeight, EntityId, GraphStore, export::export_canonical};
+
+    let guard = AUTH_TEST_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
+    unsafe { std::env::remove_var("KREMIS_API_KEY") };
+    let mut session = Session::new();
+    let graph = session.graph_mut().expect("in-memory graph");
+    let mut prev = graph.insert_node(EntityId(0)).unwrap();
+    // Large enough for several body chunks.
+    for entity in 1..20_000 {
+        let node = graph.insert_node(EntityId(entity)).unwrap();
+        graph
+            .insert_edge(prev, node, None, EdgeWeight::new(1))
+            .unwrap();
+        prev = node;
+    }
+    let graph = session.graph_opt().expect("in-memory graph");
+    let data = export_canonical(graph).unwrap();
+    let checksum = kremis_core::export::CanonicalGraph::from_graph(graph).checksum();
+    let router = create_router(AppState::new(session));
+    let server = TestServer::new(router);
+    let _guard = TestGuard { _guard: guard };
+
+    let response = server.post("/export").await;
+    response.assert_status_ok();
+    assert!(response.as_bytes().len() > 3 * 64 * 1024);
+    assert_eq!(
+        response.as_bytes().as_ref(),
+        serde_json::to_vec(&ExportResponse::success(data, checksum))
+            .unwrap()
+            .as_slice()
+    );
+}
+
+#[tokio::test]
+async fn test_export_in_flight_does_not_block_writes() {
+    use axum::body::{Body, to_bytes};
+    use axum::http::Request;
+    use kremis_core::{EdgeWeight, EntityId, GraphStore, export::export_canonical};
+    use tower::ServiceExt;
+
+    let guard = AUTH_TEST_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
+    unsafe { std::env::remove_var("KREMIS_API_KEY") };
+    let mut session = Session::new();
+    let graph = session.graph_mut().expect("in-memory graph");
+    let mut prev = graph.insert_node(EntityId(0)).unwrap();
+    // More chunks than the export buffers ahead, so it stalls mid-body.
+    for entity in 1..20_000 {
+        let node = graph.insert_node(EntityId(entity)).unwrap();
+        graph
+            .insert_edge(prev, node, None, EdgeWeight::new(1))
+            .unwrap();
+        prev = node;
+    }
+    let graph = session.graph_opt().expect("in-memory graph");
+    let data = export_canonical(graph).unwrap();
+    let checksum = kremis_core::export::CanonicalGraph::from_graph(graph).checksum();
+    let router = create_router(AppState::new(session));
+    let _guard = TestGuard { _guard: guard };
+
+    let export = router
+        .clone()
+        .oneshot(Request::post("/export").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(export.status(), 200);
+
+    // The export body is not read yet: a write still goes through.
+    let write = router.oneshot(
+        Request::post("/signal")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                r#"{"entity_id": 99999, "attribute": "name", "value": "late"}"#,
+            ))
+            .unwrap(),
+    );
+    let write = tokio::time::timeout(std::time::Duration::from_secs(10), write)
+        .await
+        .expect("write blocked by an in-flight export")
+        .unwrap();
+    assert_eq!(write.status(), 200);
+
+    // The export still reflects the state it started from.
+    let body = to_bytes(export.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(
+        body.as_ref(),
+        serde_json::to_vec(&ExportResponse::success(data, checksum))
+            .unwrap()
+            .as_slice()
+    );
+}
+
+#[tokio::test]
+async fn test_export_streams_a_persistent_graph() {
+    use kremis_core::{GraphStore, export::import_canonical};
+
+    let (server, _guard, _dir) = create_persistent_test_server();
+    server
+        .post("/signal")
+        .json(&json!({"entity_id": 1, "attribute": "name", "value": "Alice"}))
+        .await
+        .assert_status_ok();
+
+    let result: ExportResponse = server.post("/export").await.json();
+    assert!(result.success);
+    let data = base64::Engine::decode(
+        &base64::engine::general_purpose::STANDARD,
+        result.data.unwrap(),
+    )
+    .unwrap();
+    let (graph, _) = import_canonical(&data).unwrap();
+    assert_eq!(graph.node_count().unwrap(), 1);
+    assert_eq!(
+        result.checksum,
+        Some(kremis_core::export::CanonicalGraph::from_graph(&graph).checksum())
+    );
+}
+
 // =============================================================================
 // CORS TESTS
 // =============================================================================
@@ -1284,6 +1542,7 @@ async fn test_retract_reduces_edge_weight() {
     let request = RetractRequest {
         from_entity: 1,
         to_entity: 2,
+        relation: None,
     };
     let response = server.post("/signal/retract").json(&request).await;
 
@@ -1301,6 +1560,7 @@ async fn test_retract_from_entity_not_found_returns_404() {
     let request = RetractRequest {
         from_entity: 99999,
         to_entity: 1,
+        relation: None,
     };
     let response = server.post("/signal/retract").json(&request).await;
 
@@ -1317,6 +1577,7 @@ async fn test_retract_to_entity_not_found_returns_404() {
     let request = RetractRequest {
         from_entity: 1,
         to_entity: 99999,
+        relation: None,
     };
     let response = server.post("/signal/retract").json(&request).await;
 
@@ -1350,6 +1611,7 @@ async fn test_retract_edge_not_found_returns_404() {
     let request = RetractRequest {
         from_entity: 2,
         to_entity: 1,
+        relation: None,
     };
     let response = server.post("/signal/retract").json(&request).await;
 
@@ -1382,6 +1644,7 @@ async fn test_retract_multiple_times_floors_at_zero() {
     let request = RetractRequest {
         from_entity: 10,
         to_entity: 11,
+        relation: None,
     };
 
     // First retract: 1 → 0
@@ -1398,153 +1661,2238 @@ async fn test_retract_multiple_times_floors_at_zero() {
 }
 
 // =============================================================================
-// BATCH INGEST ENDPOINT TESTS
+// RELATION TESTS
 // =============================================================================
 
+/// Three entities with an unlabeled edge 1 -> 2 (weight 5), a `depends_on`
+/// edge 1 -> 2 (weight 2), and an unlabeled edge 2 -> 3.
+fn create_relation_test_server() -> (TestServer, TestGuard) {
+    use kremis_core::{Attribute, EdgeWeight, EntityId, GraphStore};
+
+    let guard = AUTH_TEST_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
+    unsafe { std::env::remove_var("KREMIS_API_KEY") };
+
+    let mut session = Session::new();
+    let graph = session.graph_mut().expect("in-memory graph");
+    let a = graph.insert_node(EntityId(1)).unwrap();
+    let b = graph.insert_node(EntityId(2)).unwrap();
+    let c = graph.insert_node(EntityId(3)).unwrap();
+    graph.insert_edge(a, b, None, EdgeWeight::new(5)).unwrap();
+    graph
+        .insert_edge(
+            a,
+            b,
+            Some(&Attribute::new("depends_on")),
+            EdgeWeight::new(2),
+        )
+        .unwrap();
+    graph.insert_edge(b, c, None, EdgeWeight::new(1)).unwrap();
+
+    let state = AppState::new(session);
+    let router = create_router(state);
+    (TestServer::new(router), TestGuard { _guard: guard })
+}
+
 #[tokio::test]
-async fn test_batch_ingest_creates_edges() {
-    let (server, _guard) = create_test_server();
+async fn test_traverse_with_relation_follows_only_labeled_edges() {
+    let (server, _guard) = create_relation_test_server();
 
-    let request = BatchIngestRequest {
-        signals: vec![
-            IngestRequest {
-                entity_id: 1,
-                attribute: "name".to_string(),
-                value: "Alice".to_string(),
-            },
-            IngestRequest {
-                entity_id: 2,
-                attribute: "name".to_string(),
-                value: "Bob".to_string(),
-            },
-        ],
+    let request = QueryRequest::Traverse {
+        node_id: 0,
+        depth: 3,
+        relation: Some("depends_on".to_string()),
     };
+    let response = server.post("/query").json(&request).await;
 
-    let response = server.post("/signals").json(&request).await;
     response.assert_status_ok();
-    let result: BatchIngestResponse = response.json();
+    let result: QueryResponse = response.json();
+    assert!(result.found);
+    assert_eq!(result.path, vec![0, 1]);
+    assert_eq!(result.edges.len(), 1);
+    assert_eq!(result.edges[0].weight, 2);
+}
+
+#[tokio::test]
+async fn test_strongest_path_with_relation_without_route_is_not_found() {
+    let (server, _guard) = create_relation_test_server();
+
+    let request = QueryRequest::StrongestPath {
+        start: 0,
+        end: 2,
+        relation: Some("depends_on".to_string()),
+        avoid: vec![],
+        waypoints: vec![],
+        max_hops: None,
+        min_weight: None,
+    };
+    let result: QueryResponse = server.post("/query").json(&request).await.json();
+    assert!(!result.found);
+    assert_eq!(result.diagnostic.as_deref(), Some("no_path"));
+}
+
+#[tokio::test]
+async fn test_query_with_empty_relation_returns_400() {
+    let (server, _guard) = create_relation_test_server();
+
+    let request = QueryRequest::Traverse {
+        node_id: 0,
+        depth: 1,
+        relation: Some(String::new()),
+    };
+    let response = server.post("/query").json(&request).await;
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_retract_with_relation_touches_only_that_edge() {
+    let (server, _guard) = create_relation_test_server();
+
+    let request = RetractRequest {
+        from_entity: 1,
+        to_entity: 2,
+        relation: Some("depends_on".to_string()),
+    };
+    let result: RetractResponse = server.post("/signal/retract").json(&request).await.json();
     assert!(result.success);
-    assert_eq!(result.ingested, 2);
-    assert_eq!(result.node_ids.len(), 2);
-    assert!(result.error.is_none());
+    assert_eq!(result.new_weight, Some(1));
 
-    // Edge must have been created between the two entities
-    let status_response = server.get("/status").await;
-    status_response.assert_status_ok();
-    let status: StatusResponse = status_response.json();
-    assert!(status.edge_count > 0, "Batch ingest must create edges");
+    let missing = RetractRequest {
+        from_entity: 2,
+        to_entity: 3,
+        relation: Some("depends_on".to_string()),
+    };
+    let response = server.post("/signal/retract").json(&missing).await;
+    response.assert_status(axum::http::StatusCode::NOT_FOUND);
 }
 
+// =============================================================================
+// BACKWARD QUERY TESTS
+// =============================================================================
+
 #[tokio::test]
-async fn test_batch_ingest_invalid_signal_returns_400() {
-    let (server, _guard) = create_test_server();
+async fn test_traverse_backward_follows_incoming_edges() {
+    let (server, _guard) = create_relation_test_server();
 
-    let request = BatchIngestRequest {
-        signals: vec![
-            IngestRequest {
-                entity_id: 1,
-                attribute: "name".to_string(),
-                value: "Alice".to_string(),
-            },
-            IngestRequest {
-                entity_id: 2,
-                attribute: "".to_string(), // empty attribute — invalid
-                value: "Bob".to_string(),
-            },
-        ],
+    let request = QueryRequest::TraverseBackward {
+        node_id: 2,
+        depth: 3,
+        relation: None,
     };
+    let response = server.post("/query").json(&request).await;
 
-    let response = server.post("/signals").json(&request).await;
-    assert_eq!(
-        response.status_code().as_u16(),
-        400,
-        "Invalid signal in batch should return 400"
-    );
-    let result: BatchIngestResponse = response.json();
-    assert!(!result.success);
-    assert!(result.error.is_some());
+    response.assert_status_ok();
+    let result: QueryResponse = response.json();
+    assert!(result.found);
+    assert_eq!(result.grounding, "inference");
+    assert_eq!(result.path, vec![2, 1, 0]);
+    // Both parallel edges 0 -> 1 are reported, in their stored direction.
+    assert_eq!(result.edges.len(), 3);
+    assert!(result.edges.iter().all(|e| e.to != 0));
 }
 
 #[tokio::test]
-async fn test_batch_ingest_enables_strongest_path() {
-    let (server, _guard) = create_test_server();
+async fn test_traverse_backward_with_relation_and_missing_node() {
+    let (server, _guard) = create_relation_test_server();
 
-    // Ingest Alice and Bob as a sequence — creates an edge
-    let request = BatchIngestRequest {
-        signals: vec![
-            IngestRequest {
-                entity_id: 10,
-                attribute: "role".to_string(),
-                value: "analyst".to_string(),
-            },
-            IngestRequest {
-                entity_id: 20,
-                attribute: "role".to_string(),
-                value: "analyst".to_string(),
-            },
-        ],
+    let request = QueryRequest::TraverseBackward {
+        node_id: 1,
+        depth: 2,
+        relation: Some("depends_on".to_string()),
     };
-    let ingest_response: BatchIngestResponse = server.post("/signals").json(&request).await.json();
-    assert!(ingest_response.success);
+    let result: QueryResponse = server.post("/query").json(&request).await.json();
+    assert_eq!(result.path, vec![1, 0]);
+    assert_eq!(result.edges.len(), 1);
+    assert_eq!(result.edges[0].weight, 2);
 
-    // Resolve actual node IDs via lookup
-    let lookup10: QueryResponse = server
-        .post("/query")
-        .json(&QueryRequest::Lookup { entity_id: 10 })
-        .await
-        .json();
-    let lookup20: QueryResponse = server
-        .post("/query")
-        .json(&QueryRequest::Lookup { entity_id: 20 })
-        .await
-        .json();
-    assert!(lookup10.found, "Entity 10 should exist after batch ingest");
-    assert!(lookup20.found, "Entity 20 should exist after batch ingest");
+    let missing = QueryRequest::TraverseBackward {
+        node_id: 99,
+        depth: 2,
+        relation: None,
+    };
+    let result: QueryResponse = server.post("/query").json(&missing).await.json();
+    assert!(!result.found);
+    assert_eq!(result.diagnostic.as_deref(), Some("node_not_found"));
+}
 
-    let node10 = lookup10.path[0];
-    let node20 = lookup20.path[0];
+#[tokio::test]
+async fn test_strongest_path_backward_walks_edges_in_reverse() {
+    let (server, _guard) = create_relation_test_server();
 
-    // strongest_path must succeed because the edge was created
-    let path_response: QueryResponse = server
-        .post("/query")
-        .json(&QueryRequest::StrongestPath {
-            start: node10,
-            end: node20,
-        })
-        .await
-        .json();
-    assert!(path_response.success, "strongest_path query should succeed");
-    assert!(
-        path_response.found,
-        "strongest_path must find a path after batch ingest creates edges"
-    );
+    let request = QueryRequest::StrongestPathBackward {
+        start: 2,
+        end: 0,
+        relation: None,
+    };
+    let result: QueryResponse = server.post("/query").json(&request).await.json();
+    assert!(result.found);
+    assert_eq!(result.path, vec![2, 1, 0]);
+
+    // The forward direction has no incoming route from 0 to 2.
+    let reversed = QueryRequest::StrongestPathBackward {
+        start: 0,
+        end: 2,
+        relation: None,
+    };
+    let result: QueryResponse = server.post("/query").json(&reversed).await.json();
+    assert!(!result.found);
+    assert_eq!(result.diagnostic.as_deref(), Some("no_path"));
 }
 
 // =============================================================================
-// CERTIFY ENDPOINT TESTS
+// REACHABILITY TESTS
 // =============================================================================
 
-fn decode_cert(b64: &str) -> kremis_core::QueryCertificate {
-    use base64::Engine;
-    let bytes = base64::engine::general_purpose::STANDARD
-        .decode(b64)
-        .expect("base64 decode");
-    kremis_core::QueryCertificate::from_canonical_bytes(&bytes).expect("decode cert")
+#[tokio::test]
+async fn test_reachable_returns_path_or_closure() {
+    let (server, _guard) = create_relation_test_server();
+
+    let request = QueryRequest::Reachable {
+        start: 0,
+        end: 2,
+        relation: None,
+    };
+    let result: QueryResponse = server.post("/query").json(&request).await.json();
+    assert!(result.found);
+    assert_eq!(result.grounding, "inference");
+    assert_eq!(result.path, vec![0, 1, 2]);
+
+    // Only the depends_on edge 0 -> 1 is followed, so 2 is out of reach and
+    // the closure {0, 1} comes back as evidence.
+    let request = QueryRequest::Reachable {
+        start: 0,
+        end: 2,
+        relation: Some("depends_on".to_string()),
+    };
+    let result: QueryResponse = server.post("/query").json(&request).await.json();
+    assert!(!result.found);
+    assert_eq!(result.grounding, "unknown");
+    assert_eq!(result.diagnostic.as_deref(), Some("no_path"));
+    assert_eq!(result.path, vec![0, 1]);
+    assert_eq!(result.edges.len(), 1);
+
+    let missing = QueryRequest::Reachable {
+        start: 99,
+        end: 2,
+        relation: None,
+    };
+    let result: QueryResponse = server.post("/query").json(&missing).await.json();
+    assert!(!result.found);
+    assert!(result.path.is_empty());
+    assert_eq!(result.diagnostic.as_deref(), Some("start_not_found"));
 }
 
 #[tokio::test]
-async fn test_certify_lookup_carries_node_evidence() {
-    let (server, _guard) = create_populated_test_server();
+async fn test_certify_unreachable_carries_closure_proof() {
+    let (server, _guard) = create_relation_test_server();
 
     let resp: CertifyResponse = server
         .post("/certify")
-        .json(&QueryRequest::Lookup { entity_id: 1 })
+        .json(&QueryRequest::Reachable {
+            start: 1,
+            end: 0,
+            relation: None,
+        })
         .await
         .json();
 
     assert!(resp.success);
-    assert!(resp.found);
-    assert_eq!(resp.grounding, "fact");
+    assert!(!resp.found);
+    assert!(resp.proof_of_absence);
+    let cert = decode_cert(resp.certificate.as_ref().expect("certificate present"));
+    assert_eq!(cert.query, "reachable:1:0");
+    assert!(cert.is_closure_proof());
+    assert!(!cert.is_proof_of_absence());
+    let ids: Vec<u64> = cert.evidence_nodes.iter().map(|n| n.id).collect();
+    assert_eq!(ids, vec![1, 2]);
+    assert_eq!(cert.traversal_trace, vec![1, 2]);
+}
+
+// =============================================================================
+// PATH ENUMERATION TESTS
+// =============================================================================
+
+/// Nodes 0..=3 with two routes of weight 5 + 5 (via 1 and via 2) and a
+/// direct hop 0 -> 3 of weight 3.
+fn create_diamond_test_server() -> (TestServer, TestGuard) {
+    create_diamond_test_server_with(QueryBudget::default())
+}
+
+/// The diamond graph served under the given per-query visit budget.
+fn create_diamond_test_server_with(query_budget: QueryBudget) -> (TestServer, TestGuard) {
+    use kremis_core::{EdgeWeight, EntityId, GraphStore};
+
+    let guard = AUTH_TEST_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
+    unsafe { std::env::remove_var("KREMIS_API_KEY") };
+
+    let mut session = Session::new();
+    let graph = session.graph_mut().expect("in-memory graph");
+    let [a, b, c, d] = [1, 2, 3, 4].map(|e| graph.insert_node(EntityId(e)).unwrap());
+    for (from, to, weight) in [(a, b, 5), (b, d, 5), (a, c, 5), (c, d, 5), (a, d, 3)] {
+        graph
+            .insert_edge(from, to, None, EdgeWeight::new(weight))
+            .unwrap();
+    }
+
+    let state = AppState::new(session).with_query_budget(query_budget);
+    let router = create_router(state);
+    (TestServer::new(router), TestGuard { _guard: guard })
+}
+
+#[tokio::test]
+async fn test_paths_and_k_strongest_paths_rank_every_route() {
+    let (server, _guard) = create_diamond_test_server();
+
+    let request = QueryRequest::Paths {
+        start: 0,
+        end: 3,
+        max_hops: 2,
+        limit: 10,
+        relation: None,
+    };
+    let result: QueryResponse = server.post("/query").json(&request).await.json();
+    assert!(result.found);
+    assert_eq!(result.grounding, "inference");
+    let listed: Vec<(Vec<u64>, i64)> = result
+        .paths
+        .iter()
+        .map(|p| (p.nodes.clone(), p.weight))
+        .collect();
+    assert_eq!(
+        listed,
+        vec![(vec![0, 1, 3], 10), (vec![0, 2, 3], 10), (vec![0, 3], 3)]
+    );
+    assert_eq!(result.path, vec![0, 1, 3]);
+    assert_eq!(result.edges.len(), 5);
+
+    // Ranked like strongest_path: the direct hop has the weakest bottleneck.
+    let request = QueryRequest::KStrongestPaths {
+        start: 0,
+        end: 3,
+        k: 2,
+        relation: None,
+    };
+    let result: QueryResponse = server.post("/query").json(&request).await.json();
+    let nodes: Vec<Vec<u64>> = result.paths.into_iter().map(|p| p.nodes).collect();
+    assert_eq!(nodes, vec![vec![0, 1, 3], vec![0, 2, 3]]);
+
+    let missing = QueryRequest::KStrongestPaths {
+        start: 99,
+        end: 3,
+        k: 2,
+        relation: None,
+    };
+    let result: QueryResponse = server.post("/query").json(&missing).await.json();
+    assert!(!result.found);
+    assert_eq!(result.diagnostic.as_deref(), Some("start_not_found"));
+
+    let zero = QueryRequest::Paths {
+        start: 0,
+        end: 3,
+        max_hops: 2,
+        limit: 0,
+        relation: None,
+    };
+    let response = server.post("/query").json(&zero).await;
+    response.assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_paths_past_the_enumeration_limit_name_the_limit() {
+    use kremis_core::{EdgeWeight, EntityId, GraphStore};
+
+    let guard = AUTH_TEST_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
+    unsafe { std::env::remove_var("KREMIS_API_KEY") };
+
+    // A complete graph on 12 nodes has far too many simple paths to rank.
+    let mut session = Session::new();
+    let graph = session.graph_mut().expect("in-memory graph");
+    let nodes: Vec<_> = (0..12)
+        .map(|i| graph.insert_node(EntityId(i)).unwrap())
+        .collect();
+    for &from in &nodes {
+        for &to in &nodes {
+            if from != to {
+                graph
+                    .insert_edge(from, to, None, EdgeWeight::new(1))
+                    .unwrap();
+            }
+        }
+    }
+    let router = create_router(AppState::new(session));
+    let server = TestServer::new(router);
+    let _guard = TestGuard { _guard: guard };
+
+    let request = QueryRequest::Paths {
+        start: 0,
+        end: 1,
+        max_hops: 11,
+        limit: 10,
+        relation: None,
+    };
+    let response = server.post("/query").json(&request).await;
+    response.assert_status_bad_request();
+    let result: QueryResponse = response.json();
+    assert!(
+        result
+            .error
+            .unwrap_or_default()
+            .contains("enumeration limit exceeded")
+    );
+}
+
+#[tokio::test]
+async fn test_certify_paths_uses_the_union_of_path_edges() {
+    let (server, _guard) = create_diamond_test_server();
+
+    let resp: CertifyResponse = server
+        .post("/certify")
+        .json(&QueryRequest::Paths {
+            start: 0,
+            end: 3,
+            max_hops: 2,
+            limit: 2,
+            relation: None,
+        })
+        .await
+        .json();
+
+    assert!(resp.success);
+    assert!(resp.found);
+    let cert = decode_cert(resp.certificate.as_ref().expect("certificate present"));
+    assert_eq!(cert.query, "paths:0:3:2:2");
+    assert_eq!(cert.traversal_trace, vec![0, 1, 3, 0, 2, 3]);
+    let edges: Vec<(u64, u64)> = cert.evidence_edges.iter().map(|e| (e.from, e.to)).collect();
+    assert_eq!(edges, vec![(0, 1), (0, 2), (1, 3), (2, 3)]);
+}
+
+// =============================================================================
+// TEXT QUERY TESTS
+// =============================================================================
+
+#[tokio::test]
+async fn test_text_query_matches_its_json_form() {
+    let (server, _guard) = create_diamond_test_server();
+
+    let text: QueryResponse = server
+        .post("/query/text")
+        .json(&json!({ "query": "kstrongest 0 -> 3 LIMIT 2" }))
+        .await
+        .json();
+    let structured: QueryResponse = server
+        .post("/query")
+        .json(&QueryRequest::KStrongestPaths {
+            start: 0,
+            end: 3,
+            k: 2,
+            relation: None,
+        })
+        .await
+        .json();
+    assert!(text.found);
+    assert_eq!(
+        serde_json::to_value(&text).unwrap(),
+        serde_json::to_value(&structured).unwrap()
+    );
+
+    let result: QueryResponse = server
+        .post("/query/text")
+        .json(&json!({ "query": "PATH 0 -> 3 AVOID 1 MAXHOPS 2" }))
+        .await
+        .json();
+    assert_eq!(result.path, vec![0, 2, 3]);
+}
+
+#[tokio::test]
+async fn test_text_query_reports_parse_position() {
+    let (server, _guard) = create_diamond_test_server();
+
+    let response = server
+        .post("/query/text")
+        .json(&json!({ "query": "PATH 0 3" }))
+        .await;
+    response.assert_status_bad_request();
+    let result: QueryResponse = response.json();
+    let error = result.error.expect("error message");
+    assert!(error.contains("position 7"), "{error}");
+
+    // Well-formed but out of bounds: rejected by the same checks as /query.
+    let response = server
+        .post("/query/text")
+        .json(&json!({ "query": "TRAVERSE 0 DEPTH 1000" }))
+        .await;
+    response.assert_status_bad_request();
+}
+
+// =============================================================================
+// VISIT BUDGET TESTS
+// =============================================================================
+
+#[tokio::test]
+async fn test_timeout_budget_truncates_at_the_same_point() {
+    let (server, _guard) = create_diamond_test_server();
+
+    // Node 0 and two of its three edges fit in three visits.
+    let body = json!({ "type": "traverse", "node_id": 0, "depth": 2, "timeout_ms": 3 });
+    let first: QueryResponse = server.post("/query").json(&body).await.json();
+    assert!(first.found);
+    assert!(first.truncated);
+    assert_eq!(first.path, vec![0]);
+    assert_eq!(first.edges.len(), 2);
+    let again: QueryResponse = server.post("/query").json(&body).await.json();
+    assert_eq!(
+        serde_json::to_value(&first).unwrap(),
+        serde_json::to_value(&again).unwrap()
+    );
+
+    let full: serde_json::Value = server
+        .post("/query")
+        .json(&json!({ "type": "traverse", "node_id": 0, "depth": 2, "timeout_ms": 1000 }))
+        .await
+        .json();
+    assert_eq!(full["path"], json!([0, 1, 2, 3]));
+    assert!(full.get("truncated").is_none());
+
+    let path: QueryResponse = server
+        .post("/query")
+        .json(&json!({ "type": "strongest_path", "start": 0, "end": 3, "timeout_ms": 2 }))
+        .await
+        .json();
+    assert!(!path.found);
+    assert!(path.truncated);
+    assert_eq!(path.diagnostic.as_deref(), Some("budget_exhausted"));
+
+    let intersect: QueryResponse = server
+        .post("/query/text")
+        .json(&json!({ "query": "INTERSECT 1, 2", "timeout_ms": 1 }))
+        .await
+        .json();
+    assert!(intersect.truncated);
+    assert_eq!(intersect.diagnostic.as_deref(), Some("budget_exhausted"));
+}
+
+#[tokio::test]
+async fn test_server_budget_applies_without_timeout_and_caps_it() {
+    let (server, _guard) = create_diamond_test_server_with(QueryBudget {
+        default_visits: 2,
+        max_visits: 3,
+    });
+
+    // No timeout_ms: the default of two visits runs out.
+    let reachable: QueryResponse = server
+        .post("/query")
+        .json(&json!({ "type": "reachable", "start": 0, "end": 3 }))
+        .await
+        .json();
+    assert!(!reachable.found);
+    assert!(reachable.truncated);
+    assert_eq!(reachable.diagnostic.as_deref(), Some("budget_exhausted"));
+
+    // A larger timeout_ms is cut down to the maximum of three visits.
+    for body in [
+        json!({ "type": "traverse", "node_id": 0, "depth": 2, "timeout_ms": 1000 }),
+        json!({ "type": "paths", "start": 0, "end": 3, "max_hops": 3, "limit": 10, "timeout_ms": 1000 }),
+        json!({ "type": "k_strongest_paths", "start": 0, "end": 3, "k": 3, "timeout_ms": 1000 }),
+    ] {
+        let response: QueryResponse = server.post("/query").json(&body).await.json();
+        assert!(response.truncated, "{body}");
+    }
+
+    let pipeline: PipelineResponse = server
+        .post("/query/pipeline")
+        .json(&json!({ "stages": [
+            { "stage": "lookup", "entity_ids": [1] },
+            { "stage": "traverse", "depth": 2 }
+        ] }))
+        .await
+        .json();
+    assert!(pipeline.success);
+    assert!(pipeline.truncated);
+
+    let components = server
+        .get("/analytics/components")
+        .add_query_param("timeout_ms", 1000)
+        .await;
+    components.assert_status_bad_request();
+
+    let certify = server
+        .post("/certify")
+        .json(&json!({ "type": "traverse", "node_id": 0, "depth": 2 }))
+        .await;
+    certify.assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_certify_rejects_a_timeout_budget() {
+    let (server, _guard) = create_diamond_test_server();
+
+    let response = server
+        .post("/certify")
+        .json(&json!({ "type": "traverse", "node_id": 0, "depth": 2, "timeout_ms": 3 }))
+        .await;
+    response.assert_status_bad_request();
+}
+
+// =============================================================================
+// DIFF TESTS
+// =============================================================================
+
+#[tokio::test]
+async fn test_diff_against_the_live_graph() {
+    let (server, _guard) = create_populated_test_server();
+
+    let export: ExportResponse = server.post("/export").await.json();
+    let before = export.data.unwrap();
+    let unchanged: DiffResponse = server
+        .post("/diff")
+        .json(&json!({ "before": before, "after": before }))
+        .await
+        .json();
+    assert!(unchanged.success);
+    assert!(unchanged.diff.is_empty());
+
+    server
+        .post("/signal")
+        .json(&json!({ "entity_id": 99, "attribute": "name", "value": "Zed" }))
+        .await
+        .assert_status_ok();
+    let response = server
+        .post("/diff")
+        .json(&json!({ "before": before }))
+        .await;
+    response.assert_status_ok();
+    let changed: DiffResponse = response.json();
+    assert_eq!(changed.diff.nodes_added.len(), 1);
+    assert_eq!(changed.diff.nodes_added[0].entity, 99);
+    assert_eq!(changed.diff.properties_added.len(), 1);
+    assert_eq!(changed.diff.properties_added[0].value, "Zed");
+    assert!(changed.diff.nodes_removed.is_empty());
+
+    server
+        .post("/diff")
+        .json(&json!({ "before": "not base64!" }))
+        .await
+        .assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_delta_export_rebuilds_the_live_graph() {
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use kremis_core::{GraphDelta, delta::apply_delta, export::import_canonical};
+
+    let (server, _guard) = create_populated_test_server();
+    let export: ExportResponse = server.post("/export").await.json();
+    let base = export.data.unwrap();
+
+    server
+        .post("/signal")
+        .json(&json!({ "entity_id": 99, "attribute": "name", "value": "Zed" }))
+        .await
+        .assert_status_ok();
+    let response = server
+        .post("/export/delta")
+        .json(&json!({ "base": base }))
+        .await;
+    response.assert_status_ok();
+    let delta: DeltaExportResponse = response.json();
+    assert!(delta.success);
+
+    let hash: serde_json::Value = server.get("/hash").await.json();
+    assert_eq!(delta.target_hash.as_deref(), hash["hash"].as_str());
+
+    let (base_graph, _) = import_canonical(&STANDARD.decode(&base).unwrap()).unwrap();
+    let bytes = STANDARD.decode(delta.data.unwrap()).unwrap();
+    let rebuilt = apply_delta(&base_graph, &GraphDelta::from_bytes(&bytes).unwrap()).unwrap();
+    assert_eq!(
+        kremis_core::export::canonical_crypto_hash(&rebuilt).unwrap(),
+        hash["hash"].as_str().unwrap()
+    );
+
+    // The route takes bases past the default body limit; only the export
+    // at the start of the upload is read.
+    let mut padded = STANDARD.decode(&base).unwrap();
+    padded.resize(3 * 1024 * 1024, 0);
+    let response = server
+        .post("/export/delta")
+        .json(&json!({ "base": STANDARD.encode(&padded) }))
+        .await;
+    response.assert_status_ok();
+    assert_eq!(
+        response.json::<DeltaExportResponse>().target_hash,
+        delta.target_hash
+    );
+
+    server
+        .post("/export/delta")
+        .json(&json!({ "base": "not base64!" }))
+        .await
+        .assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_delta_export_rebuilds_a_base_known_by_hash() {
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use kremis_core::{GraphDelta, delta::apply_delta, export::import_canonical};
+
+    let (server, _guard, _dir) = create_persistent_test_server();
+    server
+        .post("/signal")
+        .json(&json!({ "entity_id": 1, "attribute": "name", "value": "Alice" }))
+        .await
+        .assert_status_ok();
+    let export: ExportResponse = server.post("/export").await.json();
+    let base = export.data.unwrap();
+    let then: serde_json::Value = server.get("/hash").await.json();
+
+    server
+        .post("/signal")
+        .json(&json!({ "entity_id": 2, "attribute": "name", "value": "Bob" }))
+        .await
+        .assert_status_ok();
+    // No export is uploaded: the server rebuilds the base from its log.
+    let response = server
+        .post("/export/delta")
+        .json(&json!({ "base_hash": then["hash"] }))
+        .await;
+    response.assert_status_ok();
+    let delta: DeltaExportResponse = response.json();
+    assert_eq!(delta.base_hash.as_deref(), then["hash"].as_str());
+
+    let (base_graph, _) = import_canonical(&STANDARD.decode(&base).unwrap()).unwrap();
+    let bytes = STANDARD.decode(delta.data.unwrap()).unwrap();
+    let rebuilt = apply_delta(&base_graph, &GraphDelta::from_bytes(&bytes).unwrap()).unwrap();
+    assert_eq!(
+        kremis_core::export::canonical_crypto_hash(&rebuilt).unwrap(),
+        delta.target_hash.clone().unwrap()
+    );
+
+    // The target of one delta is the base of the next.
+    server
+        .post("/signal")
+        .json(&json!({ "entity_id": 3, "attribute": "name", "value": "Carol" }))
+        .await
+        .assert_status_ok();
+    let next: DeltaExportResponse = server
+        .post("/export/delta")
+        .json(&json!({ "base_hash": delta.target_hash }))
+        .await
+        .json();
+    assert!(next.success);
+    assert_eq!(next.base_hash, delta.target_hash);
+
+    // An unknown hash falls back to the uploaded base, and needs one.
+    let unknown = "00".repeat(32);
+    let response = server
+        .post("/export/delta")
+        .json(&json!({ "base_hash": unknown, "base": base }))
+        .await;
+    response.assert_status_ok();
+    assert_eq!(
+        response.json::<DeltaExportResponse>().base_hash.as_deref(),
+        then["hash"].as_str()
+    );
+    server
+        .post("/export/delta")
+        .json(&json!({ "base_hash": unknown }))
+        .await
+        .assert_status_bad_request();
+    server
+        .post("/export/delta")
+        .json(&json!({ "base_hash": "not hex" }))
+        .await
+        .assert_status_bad_request();
+    server
+        .post("/export/delta")
+        .json(&json!({}))
+        .await
+        .assert_status_bad_request();
+}
+
+// =============================================================================
+// CURSOR TESTS
+// =============================================================================
+
+#[tokio::test]
+async fn test_traverse_pages_concatenate_to_the_full_result() {
+    let (server, _guard) = create_diamond_test_server();
+
+    let full: QueryResponse = server
+        .post("/query")
+        .json(&json!({ "type": "traverse", "node_id": 0, "depth": 2 }))
+        .await
+        .json();
+    assert!(full.next_cursor.is_none());
+
+    // Four nodes and five edges, four items a page.
+    let mut path = Vec::new();
+    let mut edges = Vec::new();
+    let mut cursor: Option<String> = None;
+    let mut pages = 0;
+    loop {
+        let mut body = json!({ "type": "traverse", "node_id": 0, "depth": 2, "page_size": 4 });
+        if let Some(cursor) = &cursor {
+            body["cursor"] = json!(cursor);
+        }
+        let page: QueryResponse = server.post("/query").json(&body).await.json();
+        assert!(page.found);
+        assert!(page.path.len() + page.edges.len() <= 4);
+        path.extend(page.path);
+        edges.extend(page.edges.into_iter().map(|e| (e.from, e.to, e.weight)));
+        pages += 1;
+        match page.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    assert_eq!(pages, 3);
+    assert_eq!(path, full.path);
+    let full_edges: Vec<_> = full
+        .edges
+        .iter()
+        .map(|e| (e.from, e.to, e.weight))
+        .collect();
+    assert_eq!(edges, full_edges);
+}
+
+#[tokio::test]
+async fn test_cursor_is_rejected_after_the_graph_changes() {
+    let (server, _guard) = create_diamond_test_server();
+    let body = json!({ "type": "related", "node_id": 0, "depth": 2, "page_size": 2 });
+
+    let first: QueryResponse = server.post("/query").json(&body).await.json();
+    let cursor = first.next_cursor.expect("more pages");
+    // The same page at the same state carries the same cursor.
+    let again: QueryResponse = server.post("/query").json(&body).await.json();
+    assert_eq!(again.next_cursor.as_deref(), Some(cursor.as_str()));
+
+    server
+        .post("/signal")
+        .json(&json!({ "entity_id": 99, "attribute": "name", "value": "Zed" }))
+        .await
+        .assert_status_ok();
+    let mut next = body.clone();
+    next["cursor"] = json!(cursor);
+    let response = server.post("/query").json(&next).await;
+    response.assert_status(axum::http::StatusCode::CONFLICT);
+    let result: QueryResponse = response.json();
+    assert!(result.error.unwrap().contains("Stale cursor"));
+
+    next["cursor"] = json!("not-a-cursor");
+    server
+        .post("/query")
+        .json(&next)
+        .await
+        .assert_status_bad_request();
+    server
+        .post("/query")
+        .json(&json!({ "type": "lookup", "entity_id": 1, "page_size": 2 }))
+        .await
+        .assert_status_bad_request();
+    server
+        .post("/certify")
+        .json(&body)
+        .await
+        .assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_persistent_cursor_follows_the_mutation_sequence() {
+    let (server, _guard, _dir) = create_persistent_test_server();
+    server
+        .post("/signals")
+        .json(&json!({ "signals": [
+            { "entity_id": 1, "attribute": "name", "value": "A" },
+            { "entity_id": 2, "attribute": "name", "value": "B" },
+            { "entity_id": 3, "attribute": "name", "value": "C" },
+            { "entity_id": 4, "attribute": "name", "value": "D" }
+        ] }))
+        .await
+        .assert_status_ok();
+
+    let full: QueryResponse = server
+        .post("/query")
+        .json(&json!({ "type": "traverse", "node_id": 0, "depth": 5 }))
+        .await
+        .json();
+    assert_eq!(full.path.len(), 4);
+
+    // The first page stops after the nodes it needs.
+    let body = json!({ "type": "traverse", "node_id": 0, "depth": 5, "page_size": 2 });
+    let first: QueryResponse = server.post("/query").json(&body).await.json();
+    assert_eq!(first.path, full.path[..2]);
+    assert!(first.edges.is_empty());
+    let cursor = first.next_cursor.expect("more pages");
+
+    let mut next = body.clone();
+    next["cursor"] = json!(cursor);
+    let second: QueryResponse = server.post("/query").json(&next).await.json();
+    assert_eq!(second.path, full.path[2..]);
+
+    // A mutation moves the sequence number, so the cached hash is not reused.
+    server
+        .post("/signal")
+        .json(&json!({ "entity_id": 9, "attribute": "name", "value": "Z" }))
+        .await
+        .assert_status_ok();
+    server
+        .post("/query")
+        .json(&next)
+        .await
+        .assert_status(axum::http::StatusCode::CONFLICT);
+}
+
+// =============================================================================
+// PIPELINE TESTS
+// =============================================================================
+
+#[tokio::test]
+async fn test_pipeline_chains_lookup_traverse_and_properties() {
+    let (server, _guard) = create_populated_test_server();
+
+    let response = server
+        .post("/query/pipeline")
+        .json(&json!({
+            "stages": [
+                { "stage": "lookup", "entity_ids": [1] },
+                { "stage": "traverse", "depth": 1 },
+                { "stage": "properties" }
+            ]
+        }))
+        .await;
+    response.assert_status_ok();
+    let result: PipelineResponse = response.json();
+    assert!(result.success);
+    assert_eq!(result.stages.len(), 3);
+
+    let lookup = &result.stages[0];
+    assert_eq!(lookup.stage, "lookup");
+    assert_eq!(lookup.grounding, "fact");
+    assert_eq!(lookup.nodes.len(), 1);
+    let alice = lookup.nodes[0];
+
+    // Each stage answers what the matching single query would.
+    let traverse = &result.stages[1];
+    let single: QueryResponse = server
+        .post("/query")
+        .json(&QueryRequest::Traverse {
+            node_id: alice,
+            depth: 1,
+            relation: None,
+        })
+        .await
+        .json();
+    let mut expected = single.path.clone();
+    expected.sort_unstable();
+    assert_eq!(traverse.grounding, "inference");
+    assert_eq!(traverse.nodes, expected);
+    assert_eq!(traverse.edges.len(), single.edges.len());
+
+    let properties = &result.stages[2];
+    assert_eq!(properties.grounding, "fact");
+    assert_eq!(properties.nodes, traverse.nodes);
+    assert_eq!(properties.properties.len(), traverse.nodes.len());
+    let alice_props = properties
+        .properties
+        .iter()
+        .find(|p| p.node_id == alice)
+        .expect("alice properties");
+    assert!(
+        alice_props
+            .properties
+            .iter()
+            .any(|p| p.attribute == "name" && p.value == "Alice")
+    );
+}
+
+#[tokio::test]
+async fn test_pipeline_reports_empty_stages_and_rejects_bad_chains() {
+    let (server, _guard) = create_populated_test_server();
+
+    let result: PipelineResponse = server
+        .post("/query/pipeline")
+        .json(&json!({
+            "stages": [
+                { "stage": "lookup", "entity_ids": [999] },
+                { "stage": "properties" }
+            ]
+        }))
+        .await
+        .json();
+    assert!(result.success);
+    assert!(!result.stages[0].found);
+    assert_eq!(
+        result.stages[0].diagnostic.as_deref(),
+        Some("entity_not_found")
+    );
+    assert!(!result.stages[1].found);
+    assert_eq!(result.stages[1].diagnostic.as_deref(), Some("empty_input"));
+
+    let invalid = [
+        json!({ "stages": [] }),
+        json!({ "stages": [{ "stage": "traverse", "depth": 1 }] }),
+        json!({ "stages": [
+            { "stage": "lookup", "entity_ids": [1] },
+            { "stage": "lookup", "entity_ids": [2] }
+        ] }),
+        json!({ "stages": [
+            { "stage": "lookup", "entity_ids": [1] },
+            { "stage": "traverse", "depth": 1, "backward": true, "min_weight": 1 }
+        ] }),
+        json!({ "stages": [
+            { "stage": "lookup", "entity_ids": [1] },
+            { "stage": "traverse", "depth": 1000 }
+        ] }),
+    ];
+    for body in invalid {
+        let response = server.post("/query/pipeline").json(&body).await;
+        response.assert_status_bad_request();
+        let result: PipelineResponse = response.json();
+        assert!(!result.success, "{body}");
+    }
+}
+
+// =============================================================================
+// PATH CONSTRAINT TESTS
+// =============================================================================
+
+fn constrained_path(
+    avoid: Vec<u64>,
+    waypoints: Vec<u64>,
+    max_hops: Option<usize>,
+    min_weight: Option<i64>,
+) -> QueryRequest {
+    QueryRequest::StrongestPath {
+        start: 0,
+        end: 3,
+        relation: None,
+        avoid,
+        waypoints,
+        max_hops,
+        min_weight,
+    }
+}
+
+#[tokio::test]
+async fn test_strongest_path_honours_constraints() {
+    let (server, _guard) = create_diamond_test_server();
+
+    let cases = [
+        (constrained_path(vec![], vec![], None, None), vec![0, 1, 3]),
+        (constrained_path(vec![1], vec![], None, None), vec![0, 2, 3]),
+        (constrained_path(vec![], vec![2], None, None), vec![0, 2, 3]),
+        (constrained_path(vec![], vec![], Some(1), None), vec![0, 3]),
+    ];
+    for (request, expected) in cases {
+        let result: QueryResponse = server.post("/query").json(&request).await.json();
+        assert!(result.found);
+        assert_eq!(result.path, expected);
+    }
+
+    // The only path short enough is lighter than the floor.
+    let request = constrained_path(vec![], vec![], Some(1), Some(4));
+    let result: QueryResponse = server.post("/query").json(&request).await.json();
+    assert!(!result.found);
+    assert_eq!(result.diagnostic.as_deref(), Some("no_path"));
+
+    let request = constrained_path(vec![], vec![], Some(101), None);
+    let response = server.post("/query").json(&request).await;
+    response.assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_certify_constrained_path_records_its_constraints() {
+    let (server, _guard) = create_diamond_test_server();
+
+    let resp: CertifyResponse = server
+        .post("/certify")
+        .json(&constrained_path(vec![1], vec![], Some(2), None))
+        .await
+        .json();
+
+    assert!(resp.found);
+    let cert = decode_cert(resp.certificate.as_ref().expect("certificate present"));
+    assert_eq!(
+        cert.query,
+        "strongest_path:0:3:variant=widest:avoid=1:max_hops=2"
+    );
+    assert_eq!(cert.traversal_trace, vec![0, 2, 3]);
+}
+
+// =============================================================================
+// COMPONENTS TESTS
+// =============================================================================
+
+/// Two services calling each other, a third depending on one of them, and an
+/// isolated fourth.
+fn create_cycle_test_server() -> (TestServer, TestGuard) {
+    use kremis_core::{Attribute, EdgeWeight, EntityId, GraphStore};
+
+    let guard = AUTH_TEST_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
+    unsafe { std::env::remove_var("KREMIS_API_KEY") };
+
+    let mut session = Session::new();
+    let graph = session.graph_mut().expect("in-memory graph");
+    let [a, b, c, _] = [1, 2, 3, 4].map(|e| graph.insert_node(EntityId(e)).unwrap());
+    let depends_on = Attribute::new("depends_on");
+    for (from, to) in [(a, b), (b, a), (c, a)] {
+        graph
+            .insert_edge(from, to, Some(&depends_on), EdgeWeight::new(1))
+            .unwrap();
+    }
+
+    let state = AppState::new(session);
+    let router = create_router(state);
+    (TestServer::new(router), TestGuard { _guard: guard })
+}
+
+#[tokio::test]
+async fn test_components_weak_and_strong() {
+    let (server, _guard) = create_cycle_test_server();
+
+    let weak: ComponentsResponse = server.get("/analytics/components").await.json();
+    assert!(weak.success);
+    assert_eq!(weak.kind, ComponentKind::Weak);
+    assert_eq!(weak.count, 2);
+    assert_eq!(weak.sizes, vec![3, 1]);
+    assert!(weak.cycles.is_empty());
+
+    let strong: ComponentsResponse = server
+        .get("/analytics/components")
+        .add_query_param("kind", "strong")
+        .add_query_param("relation", "depends_on")
+        .await
+        .json();
+    assert!(strong.success);
+    assert_eq!(strong.count, 3);
+    assert_eq!(strong.sizes, vec![2, 1, 1]);
+    assert_eq!(strong.cycles, vec![vec![0, 1]]);
+    let assigned: Vec<(u64, usize)> = strong.nodes.iter().map(|n| (n.node, n.component)).collect();
+    assert_eq!(assigned, vec![(0, 0), (1, 0), (2, 1), (3, 2)]);
+}
+
+#[tokio::test]
+async fn test_components_rejects_invalid_relation() {
+    let (server, _guard) = create_cycle_test_server();
+
+    let response = server
+        .get("/analytics/components")
+        .add_query_param("relation", "")
+        .await;
+    response.assert_status_bad_request();
+}
+
+// =============================================================================
+// CENTRALITY TESTS
+// =============================================================================
+
+#[tokio::test]
+async fn test_centrality_ranks_top_nodes() {
+    let (server, _guard) = create_diamond_test_server();
+
+    let degree: CentralityResponse = server
+        .get("/analytics/centrality")
+        .add_query_param("top", 2)
+        .await
+        .json();
+    assert!(degree.success);
+    assert_eq!(degree.scale, 1);
+    let ranked: Vec<(u64, u64)> = degree.nodes.iter().map(|n| (n.node, n.score)).collect();
+    assert_eq!(ranked, vec![(0, 3), (3, 3)]);
+
+    // Only the two middle nodes sit on a shortest path (0 -> 3 is direct).
+    let betweenness: CentralityResponse = server
+        .get("/analytics/centrality")
+        .add_query_param("metric", "betweenness")
+        .await
+        .json();
+    assert_eq!(betweenness.scale, 1_000_000_000);
+    assert!(betweenness.nodes.iter().all(|n| n.score == 0));
+
+    // The sink collects the most rank.
+    let pagerank: CentralityResponse = server
+        .get("/analytics/centrality")
+        .add_query_param("metric", "pagerank")
+        .add_query_param("top", 1)
+        .await
+        .json();
+    assert_eq!(pagerank.nodes[0].node, 3);
+}
+
+#[tokio::test]
+async fn test_centrality_rejects_out_of_range_parameters() {
+    let (server, _guard) = create_diamond_test_server();
+
+    for (name, value) in [("top", 0), ("top", 1001), ("iterations", 101)] {
+        let response = server
+            .get("/analytics/centrality")
+            .add_query_param("metric", "pagerank")
+            .add_query_param(name, value)
+            .await;
+        response.assert_status_bad_request();
+    }
+}
+
+// =============================================================================
+// ENTITY KEY TESTS
+// =============================================================================
+
+#[tokio::test]
+async fn test_ingest_and_lookup_by_entity_key() {
+    let (server, _guard) = create_test_server();
+
+    let request = json!({"entity_key": "svc:ledger", "attribute": "name", "value": "Ledger"});
+    let first: IngestResponse = server.post("/signal").json(&request).await.json();
+    assert!(first.success);
+
+    // The same key resolves to the same entity, so no second node appears.
+    let request = json!({"entity_key": "svc:ledger", "attribute": "tier", "value": "gold"});
+    let second: IngestResponse = server.post("/signal").json(&request).await.json();
+    assert_eq!(second.node_id, first.node_id);
+
+    let request = json!({"type": "lookup", "entity_key": "svc:ledger"});
+    let result: QueryResponse = server.post("/query").json(&request).await.json();
+    assert!(result.found);
+    assert_eq!(result.path, vec![first.node_id.unwrap()]);
+
+    let request = json!({"type": "lookup", "entity_key": "svc:unknown"});
+    let result: QueryResponse = server.post("/query").json(&request).await.json();
+    assert!(!result.found);
+    assert_eq!(result.diagnostic.as_deref(), Some("entity_key_not_found"));
+}
+
+#[tokio::test]
+async fn test_entity_reference_must_be_exactly_one() {
+    let (server, _guard) = create_test_server();
+
+    let both = json!({"entity_id": 1, "entity_key": "a", "attribute": "name", "value": "A"});
+    let response = server.post("/signal").json(&both).await;
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+
+    let neither = json!({"attribute": "name", "value": "A"});
+    let response = server.post("/signal").json(&neither).await;
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+
+    let lookup = json!({"type": "lookup"});
+    let response = server.post("/query").json(&lookup).await;
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+
+    // Nothing was registered or ingested by the rejected requests.
+    let status: StatusResponse = server.get("/status").await.json();
+    assert_eq!(status.node_count, 0);
+}
+
+#[tokio::test]
+async fn test_batch_ingest_links_keyed_entities() {
+    let (server, _guard) = create_test_server();
+
+    let request = json!({"signals": [
+        {"entity_key": "svc:api", "attribute": "name", "value": "API"},
+        {"entity_key": "svc:ledger", "attribute": "name", "value": "Ledger"},
+        {"entity_key": "svc:api", "attribute": "tier", "value": "gold"}
+    ]});
+    let result: BatchIngestResponse = server.post("/signals").json(&request).await.json();
+    assert!(result.success);
+    assert_eq!(result.node_ids[0], result.node_ids[2]);
+    assert_ne!(result.node_ids[0], result.node_ids[1]);
+}
+
+// =============================================================================
+// AS-OF QUERY TESTS
+// =============================================================================
+
+#[tokio::test]
+async fn test_query_as_of_seq_sees_past_state() {
+    let (server, _guard, _dir) = create_persistent_test_server();
+
+    server
+        .post("/signal")
+        .json(&json!({"entity_id": 1, "attribute": "name", "value": "Alice"}))
+        .await
+        .assert_status_ok();
+    let then: serde_json::Value = server.get("/hash").await.json();
+    let seq = then["mutation_seq"].as_u64().unwrap();
+
+    let lookup: QueryResponse = server
+        .post("/query")
+        .json(&QueryRequest::Lookup {
+            entity_id: Some(1),
+            entity_key: None,
+        })
+        .await
+        .json();
+    server
+        .post("/node/delete")
+        .json(&DeleteNodeRequest {
+            entity_id: Some(1),
+            entity_key: None,
+        })
+        .await
+        .assert_status_ok();
+
+    let now: QueryResponse = server
+        .post("/query")
+        .json(&json!({"type": "lookup", "entity_id": 1}))
+        .await
+        .json();
+    assert!(!now.found);
+
+    let past: QueryResponse = server
+        .post("/query")
+        .json(&json!({"type": "lookup", "entity_id": 1, "as_of_seq": seq}))
+        .await
+        .json();
+    assert!(past.found);
+    assert_eq!(past.path, lookup.path);
+
+    // The state hash of the past sequence is reconstructable.
+    let replayed: serde_json::Value = server
+        .get("/hash")
+        .add_query_param("as_of_seq", seq)
+        .await
+        .json();
+    assert_eq!(replayed["hash"], then["hash"]);
+    assert_ne!(
+        server.get("/hash").await.json::<serde_json::Value>()["hash"],
+        then["hash"]
+    );
+}
+
+#[tokio::test]
+async fn test_certify_as_of_seq_reissues_the_certificate() {
+    let (server, _guard, _dir) = create_persistent_test_server();
+
+    server
+        .post("/signal")
+        .json(&json!({"entity_id": 1, "attribute": "name", "value": "Alice"}))
+        .await
+        .assert_status_ok();
+    let issued: CertifyResponse = server
+        .post("/certify")
+        .json(&json!({"type": "lookup", "entity_id": 1}))
+        .await
+        .json();
+    let seq = issued.mutation_seq.unwrap();
+
+    server
+        .post("/signal")
+        .json(&json!({"entity_id": 2, "attribute": "name", "value": "Bob"}))
+        .await
+        .assert_status_ok();
+
+    let reissued: CertifyResponse = server
+        .post("/certify")
+        .json(&json!({"type": "lookup", "entity_id": 1, "as_of_seq": seq}))
+        .await
+        .json();
+    assert!(reissued.success);
+    assert_eq!(reissued.mutation_seq, Some(seq));
+    assert_eq!(reissued.state_hash, issued.state_hash);
+    assert_eq!(reissued.certificate, issued.certificate);
+}
+
+#[tokio::test]
+async fn test_as_of_seq_is_rejected_without_history() {
+    let (server, _guard) = create_populated_test_server();
+
+    let response = server
+        .post("/query")
+        .json(&json!({"type": "lookup", "entity_id": 1, "as_of_seq": 1}))
+        .await;
+    response.assert_status_bad_request();
+
+    let response = server.get("/hash").add_query_param("as_of_seq", 1).await;
+    response.assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_as_of_seq_in_the_future_is_rejected() {
+    let (server, _guard, _dir) = create_persistent_test_server();
+
+    let response = server
+        .post("/certify")
+        .json(&json!({"type": "lookup", "entity_id": 1, "as_of_seq": 5}))
+        .await;
+    response.assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_as_of_seq_replay_counts_against_the_visit_budget() {
+    let (server, _guard, _dir) = create_persistent_test_server();
+
+    for (entity_id, value) in [(1, "Alice"), (2, "Bob"), (3, "Carol")] {
+        server
+            .post("/signal")
+            .json(&json!({"entity_id": entity_id, "attribute": "name", "value": value}))
+            .await
+            .assert_status_ok();
+    }
+    let seq = server.get("/hash").await.json::<serde_json::Value>()["mutation_seq"]
+        .as_u64()
+        .unwrap();
+
+    // Rebuilding the state replays every mutation up to `seq`, one visit each.
+    let response = server
+        .post("/query")
+        .json(&json!({"type": "lookup", "entity_id": 1, "as_of_seq": seq, "timeout_ms": 1}))
+        .await;
+    response.assert_status_bad_request();
+    let body: QueryResponse = response.json();
+    assert!(body.error.unwrap().contains("History replay limit"));
+
+    let response = server
+        .post("/query")
+        .json(&json!({"type": "lookup", "entity_id": 1, "as_of_seq": seq}))
+        .await;
+    response.assert_status_ok();
+    assert!(response.json::<QueryResponse>().found);
+}
+
+// =============================================================================
+// FIND BY PROPERTY TESTS
+// =============================================================================
+
+#[tokio::test]
+async fn test_find_by_property_pages_through_matches() {
+    let (server, _guard) = create_test_server();
+
+    for entity_id in 1..=3 {
+        let request = IngestRequest {
+            entity_id: Some(entity_id),
+            entity_key: None,
+            attribute: "role".to_string(),
+            value: "engineer".to_string(),
+            link_from_entity_id: None,
+            link_from_entity_key: None,
+            relation: None,
+        };
+        server
+            .post("/signal")
+            .json(&request)
+            .await
+            .assert_status_ok();
+    }
+
+    let request = QueryRequest::FindByProperty {
+        attribute: "role".to_string(),
+        value: "engineer".to_string(),
+        after: None,
+        limit: 2,
+    };
+    let response = server.post("/query").json(&request).await;
+    response.assert_status_ok();
+    let first: QueryResponse = response.json();
+    assert!(first.found);
+    assert_eq!(first.grounding, "fact");
+    assert_eq!(first.path, vec![0, 1]);
+    assert_eq!(first.next_after, Some(1));
+
+    let request = QueryRequest::FindByProperty {
+        attribute: "role".to_string(),
+        value: "engineer".to_string(),
+        after: first.next_after,
+        limit: 2,
+    };
+    let last: QueryResponse = server.post("/query").json(&request).await.json();
+    assert_eq!(last.path, vec![2]);
+    assert_eq!(last.next_after, None);
+}
+
+#[tokio::test]
+async fn test_find_by_property_no_match_and_bad_limit() {
+    let (server, _guard) = create_populated_test_server();
+
+    let request = json!({
+        "type": "find_by_property",
+        "attribute": "role",
+        "value": "astronaut"
+    });
+    let result: QueryResponse = server.post("/query").json(&request).await.json();
+    assert!(!result.found);
+    assert_eq!(result.diagnostic.as_deref(), Some("no_matching_nodes"));
+
+    let request = QueryRequest::FindByProperty {
+        attribute: "role".to_string(),
+        value: "engineer".to_string(),
+        after: None,
+        limit: 0,
+    };
+    let response = server.post("/query").json(&request).await;
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_certify_rejects_find_by_property() {
+    let (server, _guard) = create_populated_test_server();
+
+    let request = json!({
+        "type": "find_by_property",
+        "attribute": "name",
+        "value": "Alice"
+    });
+    let response = server.post("/certify").json(&request).await;
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+}
+
+// =============================================================================
+// SEARCH TESTS
+// =============================================================================
+
+#[tokio::test]
+async fn test_search_ranks_nodes_by_score() {
+    let (server, _guard) = create_test_server();
+
+    for (entity_id, name) in [
+        (1, "Payment Ledger"),
+        (2, "ledgers archive"),
+        (3, "Ledger API"),
+    ] {
+        let request = IngestRequest {
+            entity_id: Some(entity_id),
+            entity_key: None,
+            attribute: "name".to_string(),
+            value: name.to_string(),
+            link_from_entity_id: None,
+            link_from_entity_key: None,
+            relation: None,
+        };
+        server
+            .post("/signal")
+            .json(&request)
+            .await
+            .assert_status_ok();
+    }
+
+    let request = QueryRequest::Search {
+        query: "LEDGER api".to_string(),
+        limit: 10,
+    };
+    let response = server.post("/query").json(&request).await;
+    response.assert_status_ok();
+    let result: QueryResponse = response.json();
+    assert!(result.found);
+    assert_eq!(result.grounding, "fact");
+    assert_eq!(result.path, vec![2, 0, 1]);
+    assert_eq!(result.scores, vec![4, 2, 1]);
+
+    let request = QueryRequest::Search {
+        query: "ledger".to_string(),
+        limit: 1,
+    };
+    let result: QueryResponse = server.post("/query").json(&request).await.json();
+    assert_eq!(result.path, vec![0]);
+}
+
+#[tokio::test]
+async fn test_search_rejects_empty_query_and_reports_no_match() {
+    let (server, _guard) = create_populated_test_server();
+
+    let request = json!({ "type": "search", "query": "zeppelin" });
+    let result: QueryResponse = server.post("/query").json(&request).await.json();
+    assert!(!result.found);
+    assert_eq!(result.diagnostic.as_deref(), Some("no_matching_nodes"));
+
+    let request = json!({ "type": "search", "query": " -- " });
+    let response = server.post("/query").json(&request).await;
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+}
+
+// =============================================================================
+// PATTERN TESTS
+// =============================================================================
+
+/// A triangle a -> b -> c with a shortcut a -> c, anchored at node 0.
+fn triangle_pattern(limit: usize) -> serde_json::Value {
+    json!({
+        "type": "pattern",
+        "variables": [{ "name": "a", "node_id": 0 }, { "name": "b" }, { "name": "c" }],
+        "edges": [
+            { "from": "a", "to": "b" },
+            { "from": "b", "to": "c" },
+            { "from": "a", "to": "c", "min_weight": 3 }
+        ],
+        "limit": limit
+    })
+}
+
+#[tokio::test]
+async fn test_pattern_returns_bindings_in_order() {
+    let (server, _guard) = create_diamond_test_server();
+
+    let response = server.post("/query").json(&triangle_pattern(10)).await;
+    response.assert_status_ok();
+    let result: QueryResponse = response.json();
+    assert!(result.found);
+    assert_eq!(result.grounding, "inference");
+    let bindings: Vec<Vec<u64>> = result.bindings.iter().map(|b| b.nodes.clone()).collect();
+    assert_eq!(bindings, vec![vec![0, 1, 3], vec![0, 2, 3]]);
+    assert_eq!(result.path, vec![0, 1, 3]);
+    assert_eq!(result.edges.len(), 5);
+
+    let result: QueryResponse = server
+        .post("/query")
+        .json(&triangle_pattern(1))
+        .await
+        .json();
+    assert_eq!(result.bindings.len(), 1);
+
+    // Only node 3 carries the property.
+    server
+        .post("/signal")
+        .json(&json!({ "entity_id": 4, "attribute": "kind", "value": "sink" }))
+        .await
+        .assert_status_ok();
+    let request = json!({
+        "type": "pattern",
+        "variables": [
+            { "name": "hub", "properties": [{ "attribute": "kind", "value": "sink" }] },
+            { "name": "feeder" }
+        ],
+        "edges": [{ "from": "feeder", "to": "hub", "min_weight": 5 }]
+    });
+    let result: QueryResponse = server.post("/query").json(&request).await.json();
+    let bindings: Vec<Vec<u64>> = result.bindings.iter().map(|b| b.nodes.clone()).collect();
+    assert_eq!(bindings, vec![vec![3, 1], vec![3, 2]]);
+}
+
+#[tokio::test]
+async fn test_pattern_rejects_malformed_patterns() {
+    let (server, _guard) = create_diamond_test_server();
+
+    let no_match = json!({
+        "type": "pattern",
+        "variables": [{ "name": "a", "node_id": 3 }, { "name": "b" }],
+        "edges": [{ "from": "a", "to": "b" }]
+    });
+    let result: QueryResponse = server.post("/query").json(&no_match).await.json();
+    assert!(!result.found);
+    assert_eq!(result.diagnostic.as_deref(), Some("no_match"));
+
+    let unknown_variable = json!({
+        "type": "pattern",
+        "variables": [{ "name": "a" }],
+        "edges": [{ "from": "a", "to": "z" }]
+    });
+    let duplicate_name = json!({
+        "type": "pattern",
+        "variables": [{ "name": "a" }, { "name": "a" }]
+    });
+    let bad_name = json!({ "type": "pattern", "variables": [{ "name": "a:b" }] });
+    let empty = json!({ "type": "pattern", "variables": [] });
+    for request in [
+        unknown_variable,
+        duplicate_name,
+        bad_name,
+        empty,
+        triangle_pattern(0),
+    ] {
+        let response = server.post("/query").json(&request).await;
+        response.assert_status_bad_request();
+    }
+}
+
+#[tokio::test]
+async fn test_certify_pattern_traces_every_binding() {
+    let (server, _guard) = create_diamond_test_server();
+
+    let resp: CertifyResponse = server
+        .post("/certify")
+        .json(&triangle_pattern(10))
+        .await
+        .json();
+    assert!(resp.success);
+    assert!(resp.found);
+    let cert = decode_cert(resp.certificate.as_ref().expect("certificate present"));
+    assert_eq!(
+        cert.query,
+        "pattern:10:vars=a,b,c:a@0:a->b:b->c:a->c[min_weight=3]"
+    );
+    assert_eq!(cert.traversal_trace, vec![0, 1, 3, 0, 2, 3]);
+    assert_eq!(cert.evidence_edges.len(), 5);
+}
+
+// =============================================================================
+// PROPERTY RETRACT TESTS
+// =============================================================================
+
+#[tokio::test]
+async fn test_retract_property_removes_only_that_pair() {
+    let (server, _guard) = create_test_server();
+
+    for value in ["Bob", "Carol"] {
+        let request = IngestRequest {
+            entity_id: Some(1),
+            entity_key: None,
+            attribute: "knows".to_string(),
+            value: value.to_string(),
+            link_from_entity_id: None,
+            link_from_entity_key: None,
+            relation: None,
+        };
+        server
+            .post("/signal")
+            .json(&request)
+            .await
+            .assert_status_ok();
+    }
+
+    let request = RetractPropertyRequest {
+        entity_id: Some(1),
+        entity_key: None,
+        attribute: "knows".to_string(),
+        value: "Bob".to_string(),
+    };
+    let response = server.post("/property/retract").json(&request).await;
+    response.assert_status_ok();
+    let result: RetractPropertyResponse = response.json();
+    assert!(result.success);
+
+    let props: QueryResponse = server
+        .post("/query")
+        .json(&QueryRequest::Properties { node_id: 0 })
+        .await
+        .json();
+    assert_eq!(props.properties.len(), 1);
+    assert_eq!(props.properties[0].value, "Carol");
+
+    // Retracting it again finds nothing.
+    let response = server.post("/property/retract").json(&request).await;
+    response.assert_status(axum::http::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_retract_property_rejects_unknown_entity_and_bad_input() {
+    let (server, _guard) = create_test_server();
+
+    let unknown = RetractPropertyRequest {
+        entity_id: Some(42),
+        entity_key: None,
+        attribute: "name".to_string(),
+        value: "Alice".to_string(),
+    };
+    let response = server.post("/property/retract").json(&unknown).await;
+    response.assert_status(axum::http::StatusCode::NOT_FOUND);
+    let result: RetractPropertyResponse = response.json();
+    assert_eq!(result.error.as_deref(), Some("entity not found"));
+
+    let invalid = RetractPropertyRequest {
+        entity_id: Some(42),
+        entity_key: None,
+        attribute: String::new(),
+        value: "Alice".to_string(),
+    };
+    let response = server.post("/property/retract").json(&invalid).await;
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_keyed_entities_are_addressable_by_id_and_key() {
+    use kremis_core::GraphStore;
+
+    let (server, _guard) = create_test_server();
+    // Keyed ids are derived from the key, so any graph gives the same one.
+    let keyed = kremis_core::Graph::new()
+        .register_entity_key("svc:ledger")
+        .expect("register")
+        .0;
+
+    for value in ["Ledger", "Books"] {
+        let request = json!({"entity_key": "svc:ledger", "attribute": "name", "value": value});
+        server
+            .post("/signal")
+            .json(&request)
+            .await
+            .assert_status_ok();
+    }
+
+    // Lookups and retractions accept the keyed id as a raw id.
+    let request = json!({"type": "lookup", "entity_id": keyed});
+    let lookup: QueryResponse = server.post("/query").json(&request).await.json();
+    assert!(lookup.found);
+    let request = json!({"entity_id": keyed, "attribute": "name", "value": "Ledger"});
+    server
+        .post("/property/retract")
+        .json(&request)
+        .await
+        .assert_status_ok();
+    let request = json!({"entity_key": "svc:ledger", "attribute": "name", "value": "Books"});
+    server
+        .post("/property/retract")
+        .json(&request)
+        .await
+        .assert_status_ok();
+
+    let request = json!({"entity_key": "svc:unknown", "attribute": "name", "value": "Books"});
+    let response = server.post("/property/retract").json(&request).await;
+    response.assert_status(axum::http::StatusCode::NOT_FOUND);
+
+    // An unbound keyed-range id still cannot allocate a node.
+    let request = json!({"entity_id": keyed ^ 1, "attribute": "name", "value": "Ghost"});
+    server
+        .post("/signal")
+        .json(&request)
+        .await
+        .assert_status_bad_request();
+
+    let request = json!({"entity_key": "svc:ledger"});
+    let response = server.post("/node/delete").json(&request).await;
+    response.assert_status_ok();
+    let status: StatusResponse = server.get("/status").await.json();
+    assert_eq!(status.node_count, 0);
+
+    let request = json!({"entity_id": keyed, "entity_key": "svc:ledger"});
+    let response = server.post("/node/delete").json(&request).await;
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+}
+
+// =============================================================================
+// NODE DELETE TESTS
+// =============================================================================
+
+#[tokio::test]
+async fn test_delete_node_cascades_to_edges() {
+    let (server, _guard) = create_relation_test_server();
+
+    let response = server
+        .post("/node/delete")
+        .json(&DeleteNodeRequest {
+            entity_id: Some(2),
+            entity_key: None,
+        })
+        .await;
+    response.assert_status_ok();
+    let result: DeleteNodeResponse = response.json();
+    assert!(result.success);
+    assert_eq!(result.node_id, Some(1));
+
+    let status: StatusResponse = server.get("/status").await.json();
+    assert_eq!(status.node_count, 2);
+    assert_eq!(status.edge_count, 0);
+
+    let lookup: QueryResponse = server
+        .post("/query")
+        .json(&QueryRequest::Lookup {
+            entity_id: Some(2),
+            entity_key: None,
+        })
+        .await
+        .json();
+    assert!(!lookup.found);
+}
+
+#[tokio::test]
+async fn test_delete_unknown_node_returns_404() {
+    let (server, _guard) = create_relation_test_server();
+
+    let response = server
+        .post("/node/delete")
+        .json(&DeleteNodeRequest {
+            entity_id: Some(99),
+            entity_key: None,
+        })
+        .await;
+    response.assert_status(axum::http::StatusCode::NOT_FOUND);
+    let result: DeleteNodeResponse = response.json();
+    assert!(!result.success);
+    assert!(result.node_id.is_none());
+}
+
+// =============================================================================
+// BATCH INGEST ENDPOINT TESTS
+// =============================================================================
+
+#[tokio::test]
+async fn test_batch_ingest_creates_edges() {
+    let (server, _guard) = create_test_server();
+
+    let request = BatchIngestRequest {
+        signals: vec![
+            IngestRequest {
+                entity_id: Some(1),
+                entity_key: None,
+                attribute: "name".to_string(),
+                value: "Alice".to_string(),
+                link_from_entity_id: None,
+                link_from_entity_key: None,
+                relation: None,
+            },
+            IngestRequest {
+                entity_id: Some(2),
+                entity_key: None,
+                attribute: "name".to_string(),
+                value: "Bob".to_string(),
+                link_from_entity_id: None,
+                link_from_entity_key: None,
+                relation: None,
+            },
+        ],
+        window: None,
+        links: None,
+        relation: None,
+    };
+
+    let response = server.post("/signals").json(&request).await;
+    response.assert_status_ok();
+    let result: BatchIngestResponse = response.json();
+    assert!(result.success);
+    assert_eq!(result.ingested, 2);
+    assert_eq!(result.node_ids.len(), 2);
+    assert!(result.error.is_none());
+
+    // Edge must have been created between the two entities
+    let status_response = server.get("/status").await;
+    status_response.assert_status_ok();
+    let status: StatusResponse = status_response.json();
+    assert!(status.edge_count > 0, "Batch ingest must create edges");
+}
+
+#[tokio::test]
+async fn test_batch_ingest_invalid_signal_returns_400() {
+    let (server, _guard) = create_test_server();
+
+    let request = BatchIngestRequest {
+        signals: vec![
+            IngestRequest {
+                entity_id: Some(1),
+                entity_key: None,
+                attribute: "name".to_string(),
+                value: "Alice".to_string(),
+                link_from_entity_id: None,
+                link_from_entity_key: None,
+                relation: None,
+            },
+            IngestRequest {
+                entity_id: Some(2),
+                entity_key: None,
+                attribute: "".to_string(), // empty attribute — invalid
+                value: "Bob".to_string(),
+                link_from_entity_id: None,
+                link_from_entity_key: None,
+                relation: None,
+            },
+        ],
+        window: None,
+        links: None,
+        relation: None,
+    };
+
+    let response = server.post("/signals").json(&request).await;
+    assert_eq!(
+        response.status_code().as_u16(),
+        400,
+        "Invalid signal in batch should return 400"
+    );
+    let result: BatchIngestResponse = response.json();
+    assert!(!result.success);
+    assert!(result.error.is_some());
+}
+
+#[tokio::test]
+async fn test_batch_ingest_enables_strongest_path() {
+    let (server, _guard) = create_test_server();
+
+    // Ingest Alice and Bob as a sequence — creates an edge
+    let request = BatchIngestRequest {
+        signals: vec![
+            IngestRequest {
+                entity_id: Some(10),
+                entity_key: None,
+                attribute: "role".to_string(),
+                value: "analyst".to_string(),
+                link_from_entity_id: None,
+                link_from_entity_key: None,
+                relation: None,
+            },
+            IngestRequest {
+                entity_id: Some(20),
+                entity_key: None,
+                attribute: "role".to_string(),
+                value: "analyst".to_string(),
+                link_from_entity_id: None,
+                link_from_entity_key: None,
+                relation: None,
+            },
+        ],
+        window: None,
+        links: None,
+        relation: None,
+    };
+    let ingest_response: BatchIngestResponse = server.post("/signals").json(&request).await.json();
+    assert!(ingest_response.success);
+
+    // Resolve actual node IDs via lookup
+    let lookup10: QueryResponse = server
+        .post("/query")
+        .json(&QueryRequest::Lookup {
+            entity_id: Some(10),
+            entity_key: None,
+        })
+        .await
+        .json();
+    let lookup20: QueryResponse = server
+        .post("/query")
+        .json(&QueryRequest::Lookup {
+            entity_id: Some(20),
+            entity_key: None,
+        })
+        .await
+        .json();
+    assert!(lookup10.found, "Entity 10 should exist after batch ingest");
+    assert!(lookup20.found, "Entity 20 should exist after batch ingest");
+
+    let node10 = lookup10.path[0];
+    let node20 = lookup20.path[0];
+
+    // strongest_path must succeed because the edge was created
+    let path_response: QueryResponse = server
+        .post("/query")
+        .json(&QueryRequest::StrongestPath {
+            start: node10,
+            end: node20,
+            relation: None,
+            avoid: vec![],
+            waypoints: vec![],
+            max_hops: None,
+            min_weight: None,
+        })
+        .await
+        .json();
+    assert!(path_response.success, "strongest_path query should succeed");
+    assert!(
+        path_response.found,
+        "strongest_path must find a path after batch ingest creates edges"
+    );
+}
+
+#[tokio::test]
+async fn test_batch_ingest_window_links_further_back() {
+    let (server, _guard) = create_test_server();
+
+    let body = json!({
+        "signals": [
+            {"entity_id": 1, "attribute": "n", "value": "a"},
+            {"entity_id": 2, "attribute": "n", "value": "b"},
+            {"entity_id": 3, "attribute": "n", "value": "c"}
+        ],
+        "window": 2
+    });
+    let result: BatchIngestResponse = server.post("/signals").json(&body).await.json();
+    assert!(result.success);
+
+    let status: StatusResponse = server.get("/status").await.json();
+    assert_eq!(status.edge_count, 3);
+}
+
+#[tokio::test]
+async fn test_batch_ingest_explicit_links_ignore_order() {
+    let (server, _guard) = create_test_server();
+
+    let body = json!({
+        "signals": [
+            {"entity_key": "svc:api", "attribute": "kind", "value": "service"},
+            {"entity_key": "svc:db", "attribute": "kind", "value": "database"},
+            {"entity_key": "svc:web", "attribute": "kind", "value": "service"}
+        ],
+        "links": [
+            {"from_entity_key": "svc:web", "to_entity_key": "svc:db"}
+        ]
+    });
+    let result: BatchIngestResponse = server.post("/signals").json(&body).await.json();
+    assert!(result.success);
+    let (api, db, web) = (result.node_ids[0], result.node_ids[1], result.node_ids[2]);
+
+    let status: StatusResponse = server.get("/status").await.json();
+    assert_eq!(status.edge_count, 1);
+    let path: QueryResponse = server
+        .post("/query")
+        .json(&QueryRequest::StrongestPath {
+            start: web,
+            end: db,
+            relation: None,
+            avoid: vec![],
+            waypoints: vec![],
+            max_hops: None,
+            min_weight: None,
+        })
+        .await
+        .json();
+    assert!(path.found);
+    let path: QueryResponse = server
+        .post("/query")
+        .json(&QueryRequest::StrongestPath {
+            start: api,
+            end: db,
+            relation: None,
+            avoid: vec![],
+            waypoints: vec![],
+            max_hops: None,
+            min_weight: None,
+        })
+        .await
+        .json();
+    assert!(!path.found);
+}
+
+#[tokio::test]
+async fn test_batch_ingest_labels_edges_with_relation() {
+    let (server, _guard) = create_test_server();
+
+    let body = json!({
+        "signals": [
+            {"entity_key": "svc:api", "attribute": "kind", "value": "service"},
+            {"entity_key": "svc:db", "attribute": "kind", "value": "database"},
+            {"entity_key": "svc:web", "attribute": "kind", "value": "service"}
+        ],
+        "links": [
+            {"from_entity_key": "svc:api", "to_entity_key": "svc:db", "relation": "depends_on"},
+            {"from_entity_key": "svc:web", "to_entity_key": "svc:api"}
+        ]
+    });
+    let result: BatchIngestResponse = server.post("/signals").json(&body).await.json();
+    assert!(result.success);
+    let (api, web) = (result.node_ids[0], result.node_ids[2]);
+
+    let labeled: QueryResponse = server
+        .post("/query")
+        .json(&QueryRequest::Traverse {
+            node_id: api,
+            depth: 2,
+            relation: Some("depends_on".to_string()),
+        })
+        .await
+        .json();
+    assert_eq!(labeled.edges.len(), 1);
+    let unlabeled: QueryResponse = server
+        .post("/query")
+        .json(&QueryRequest::Traverse {
+            node_id: web,
+            depth: 2,
+            relation: Some("depends_on".to_string()),
+        })
+        .await
+        .json();
+    assert!(unlabeled.edges.is_empty());
+
+    // A batch-level relation labels the window edges.
+    let body = json!({
+        "signals": [
+            {"entity_id": 7, "attribute": "n", "value": "a"},
+            {"entity_id": 8, "attribute": "n", "value": "b"}
+        ],
+        "relation": "next"
+    });
+    let result: BatchIngestResponse = server.post("/signals").json(&body).await.json();
+    assert!(result.success);
+    let next: QueryResponse = server
+        .post("/query")
+        .json(&QueryRequest::Traverse {
+            node_id: result.node_ids[0],
+            depth: 1,
+            relation: Some("next".to_string()),
+        })
+        .await
+        .json();
+    assert_eq!(next.edges.len(), 1);
+}
+
+#[tokio::test]
+async fn test_ingest_with_link_and_relation_creates_labeled_edge() {
+    let (server, _guard) = create_test_server();
+
+    let first: IngestResponse = server
+        .post("/signal")
+        .json(&json!({"entity_key": "svc:api", "attribute": "kind", "value": "service"}))
+        .await
+        .json();
+    let second: IngestResponse = server
+        .post("/signal")
+        .json(&json!({
+            "entity_key": "svc:db",
+            "attribute": "kind",
+            "value": "database",
+            "link_from_entity_key": "svc:api",
+            "relation": "depends_on"
+        }))
+        .await
+        .json();
+    assert!(second.success);
+
+    let path: QueryResponse = server
+        .post("/query")
+        .json(&QueryRequest::StrongestPath {
+            start: first.node_id.unwrap(),
+            end: second.node_id.unwrap(),
+            relation: Some("depends_on".to_string()),
+            avoid: vec![],
+            waypoints: vec![],
+            max_hops: None,
+            min_weight: None,
+        })
+        .await
+        .json();
+    assert!(path.found);
+
+    for body in [
+        json!({"entity_id": 1, "attribute": "n", "value": "a", "relation": "x"}),
+        json!({"entity_id": 1, "attribute": "n", "value": "a", "link_from_entity_id": 2, "relation": ""}),
+    ] {
+        server
+            .post("/signal")
+            .json(&body)
+            .await
+            .assert_status_bad_request();
+    }
+}
+
+#[tokio::test]
+async fn test_batch_ingest_rejects_bad_links() {
+    let (server, _guard) = create_test_server();
+    let signals = json!([{"entity_id": 1, "attribute": "n", "value": "a"}]);
+
+    for body in [
+        json!({"signals": signals, "window": 1, "links": []}),
+        json!({"signals": signals, "window": 1000}),
+        json!({"signals": signals, "links": [{"from_entity_id": 1}]}),
+        json!({"signals": signals, "links": [{"from_entity_id": 1, "to_entity_id": 99}]}),
+        json!({"signals": signals, "relation": "next", "links": []}),
+        json!({"signals": signals, "links": [{"from_entity_id": 1, "to_entity_id": 1, "relation": ""}]}),
+    ] {
+        let response = server.post("/signals").json(&body).await;
+        response.assert_status_bad_request();
+    }
+
+    let status: StatusResponse = server.get("/status").await.json();
+    assert_eq!(status.node_count, 0);
+}
+
+// =============================================================================
+// CERTIFY ENDPOINT TESTS
+// =============================================================================
+
+fn decode_cert(b64: &str) -> kremis_core::QueryCertificate {
+    use base64::Engine;
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(b64)
+        .expect("base64 decode");
+    kremis_core::QueryCertificate::from_canonical_bytes(&bytes).expect("decode cert")
+}
+
+#[tokio::test]
+async fn test_certify_lookup_carries_node_evidence() {
+    let (server, _guard) = create_populated_test_server();
+
+    let resp: CertifyResponse = server
+        .post("/certify")
+        .json(&QueryRequest::Lookup {
+            entity_id: Some(1),
+            entity_key: None,
+        })
+        .await
+        .json();
+
+    assert!(resp.success);
+    assert!(resp.found);
+    assert_eq!(resp.grounding, "fact");
 
     // A certified fact must carry the evidence needed to re-derive it.
     let cert = decode_cert(resp.certificate.as_ref().expect("certificate present"));
@@ -1561,7 +3909,10 @@ async fn test_certify_lookup_absent_is_proof_of_absence() {
 
     let resp: CertifyResponse = server
         .post("/certify")
-        .json(&QueryRequest::Lookup { entity_id: 99999 })
+        .json(&QueryRequest::Lookup {
+            entity_id: Some(99999),
+            entity_key: None,
+        })
         .await
         .json();
 
@@ -1572,6 +3923,30 @@ async fn test_certify_lookup_absent_is_proof_of_absence() {
     assert!(cert.is_proof_of_absence());
 }
 
+#[tokio::test]
+async fn test_certify_strongest_path_names_its_variant() {
+    let (server, _guard) = create_relation_test_server();
+
+    let resp: CertifyResponse = server
+        .post("/certify")
+        .json(&QueryRequest::StrongestPath {
+            start: 0,
+            end: 2,
+            relation: None,
+            avoid: vec![],
+            waypoints: vec![],
+            max_hops: None,
+            min_weight: None,
+        })
+        .await
+        .json();
+
+    assert!(resp.found);
+    let cert = decode_cert(resp.certificate.as_ref().expect("certificate present"));
+    assert_eq!(cert.query, "strongest_path:0:2:variant=widest");
+    assert_eq!(cert.traversal_trace, vec![0, 1, 2]);
+}
+
 #[tokio::test]
 async fn test_certify_rejects_properties_query() {
     let (server, _guard) = create_populated_test_server();
@@ -1581,7 +3956,10 @@ async fn test_certify_rejects_properties_query() {
     // evidence. Regression guard for the P1 finding.
     let lookup: QueryResponse = server
         .post("/query")
-        .json(&QueryRequest::Lookup { entity_id: 1 })
+        .json(&QueryRequest::Lookup {
+            entity_id: Some(1),
+            entity_key: None,
+        })
         .await
         .json();
     let node_id = lookup.path[0];
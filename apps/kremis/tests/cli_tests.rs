@@ -6,8 +6,9 @@
 #![allow(clippy::unwrap_used, clippy::expect_used, clippy::panic)]
 
 use kremis::cli::{
-    cmd_export, cmd_import, cmd_ingest, cmd_init, cmd_query, cmd_stage, cmd_status,
-    load_or_create_session, save_session,
+    cmd_apply_delta, cmd_centrality, cmd_components, cmd_delete, cmd_diff, cmd_export,
+    cmd_export_delta, cmd_import, cmd_ingest, cmd_init, cmd_merge, cmd_query, cmd_query_text,
+    cmd_replay, cmd_retract_property, cmd_stage, cmd_status, load_or_create_session, save_session,
 };
 use kremis_core::{Attribute, EntityId, Session, Signal, Value};
 use std::path::PathBuf;
@@ -248,6 +249,7 @@ fn test_ingest_json_format() {
         "json",
         false,
         false,
+        None,
     );
     assert!(result.is_ok());
 
@@ -271,6 +273,7 @@ fn test_ingest_text_format() {
         "text",
         false,
         false,
+        None,
     );
     assert!(result.is_ok());
 
@@ -294,6 +297,7 @@ fn test_ingest_invalid_format() {
         "unknown",
         false,
         false,
+        None,
     );
     assert!(result.is_err());
 }
@@ -314,6 +318,7 @@ fn test_ingest_invalid_json() {
         "json",
         false,
         false,
+        None,
     );
     assert!(result.is_err());
 }
@@ -338,6 +343,7 @@ fn test_ingest_json_with_bom() {
         "json",
         false,
         false,
+        None,
     );
     assert!(result.is_ok(), "ingest should accept JSON with UTF-8 BOM");
 
@@ -361,6 +367,7 @@ fn ingest_text_strict_fails_on_malformed_lines() {
         "text",
         false,
         true,
+        None,
     );
     assert!(result.is_err());
 }
@@ -380,10 +387,309 @@ fn ingest_text_strict_ok_on_all_valid_lines() {
         "text",
         false,
         true,
+        None,
     );
     assert!(result.is_ok());
 }
 
+#[test]
+fn test_ingest_json_object_with_explicit_links() {
+    let temp = create_temp_dir();
+    let db_path = temp.path().join("test.db");
+    let signals_file = temp.path().join("linked.json");
+    std::fs::write(
+        &signals_file,
+        r#"{
+            "signals": [
+                {"entity_id": 1, "attribute": "name", "value": "Alice"},
+                {"entity_id": 2, "attribute": "name", "value": "Bob"},
+                {"entity_id": 3, "attribute": "name", "value": "Carol"}
+            ],
+            "links": [{"from_entity_id": 3, "to_entity_id": 1}]
+        }"#,
+    )
+    .unwrap();
+
+    cmd_init(&db_path, "file", false).unwrap();
+    cmd_ingest(
+        &db_path,
+        "file",
+        false,
+        Some(&signals_file),
+        "json",
+        false,
+        false,
+        None,
+    )
+    .unwrap();
+
+    let (session, _) = load_or_create_session(&db_path, "file").unwrap();
+    let alice = session.lookup_entity(EntityId(1)).unwrap();
+    let bob = session.lookup_entity(EntityId(2)).unwrap();
+    let carol = session.lookup_entity(EntityId(3)).unwrap();
+    assert!(session.get_edge(carol, alice, None).unwrap().is_some());
+    assert!(session.get_edge(alice, bob, None).unwrap().is_none());
+}
+
+#[test]
+fn test_ingest_text_link_lines_and_window_conflict() {
+    let temp = create_temp_dir();
+    let db_path = temp.path().join("test.db");
+    let signals_file = temp.path().join("linked.txt");
+    std::fs::write(&signals_file, "1:name:Alice\n2:name:Bob\n2 -> 1\n").unwrap();
+
+    cmd_init(&db_path, "file", false).unwrap();
+    let ingest = |window| {
+        cmd_ingest(
+            &db_path,
+            "file",
+            false,
+            Some(&signals_file),
+            "text",
+            false,
+            true,
+            window,
+        )
+    };
+    assert!(ingest(Some(2)).is_err());
+    ingest(None).unwrap();
+
+    let (session, _) = load_or_create_session(&db_path, "file").unwrap();
+    let alice = session.lookup_entity(EntityId(1)).unwrap();
+    let bob = session.lookup_entity(EntityId(2)).unwrap();
+    assert!(session.get_edge(bob, alice, None).unwrap().is_some());
+    assert!(session.get_edge(alice, bob, None).unwrap().is_none());
+}
+
+#[test]
+fn test_ingest_link_relations_label_edges() {
+    let temp = create_temp_dir();
+    let db_path = temp.path().join("test.db");
+    let text_file = temp.path().join("linked.txt");
+    std::fs::write(
+        &text_file,
+        "1:name:Alice\n2:name:Bob\n2 -[owns]-> 1\n1 -> 2\n",
+    )
+    .unwrap();
+    let json_file = temp.path().join("linked.json");
+    std::fs::write(
+        &json_file,
+        r#"{
+            "signals": [{"entity_id": 3, "attribute": "name", "value": "Carol"}],
+            "links": [{"from_entity_id": 3, "to_entity_id": 1, "relation": "knows"}]
+        }"#,
+    )
+    .unwrap();
+
+    cmd_init(&db_path, "file", false).unwrap();
+    for (file, format) in [(&text_file, "text"), (&json_file, "json")] {
+        cmd_ingest(
+            &db_path,
+            "file",
+            false,
+            Some(file),
+            format,
+            false,
+            false,
+            None,
+        )
+        .unwrap();
+    }
+
+    let (session, _) = load_or_create_session(&db_path, "file").unwrap();
+    let alice = session.lookup_entity(EntityId(1)).unwrap();
+    let bob = session.lookup_entity(EntityId(2)).unwrap();
+    let carol = session.lookup_entity(EntityId(3)).unwrap();
+    let owns = Attribute::new("owns");
+    let knows = Attribute::new("knows");
+    assert!(session.get_edge(bob, alice, Some(&owns)).unwrap().is_some());
+    assert!(session.get_edge(bob, alice, None).unwrap().is_none());
+    assert!(session.get_edge(alice, bob, None).unwrap().is_some());
+    assert!(
+        session
+            .get_edge(carol, alice, Some(&knows))
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[test]
+fn test_ingest_rejects_raw_ids_in_the_keyed_range() {
+    let temp = create_temp_dir();
+    let db_path = temp.path().join("test.db");
+    let signals_file = temp.path().join("keyed.txt");
+    std::fs::write(
+        &signals_file,
+        "1:name:Alice\n9223372036854775808:name:Bob\n",
+    )
+    .unwrap();
+
+    cmd_init(&db_path, "file", false).unwrap();
+    let result = cmd_ingest(
+        &db_path,
+        "file",
+        false,
+        Some(&signals_file),
+        "text",
+        false,
+        false,
+        None,
+    );
+    assert!(result.is_err());
+
+    let (session, _) = load_or_create_session(&db_path, "file").unwrap();
+    assert!(session.lookup_entity(EntityId(1)).is_none());
+}
+
+#[test]
+fn test_ingest_window_flag_links_further_back() {
+    let temp = create_temp_dir();
+    let db_path = temp.path().join("test.db");
+    let signals_file = create_signals_text(&temp);
+
+    cmd_init(&db_path, "file", false).unwrap();
+    let ingest = |window| {
+        cmd_ingest(
+            &db_path,
+            "file",
+            false,
+            Some(&signals_file),
+            "text",
+            false,
+            false,
+            Some(window),
+        )
+    };
+    assert!(ingest(kremis_core::primitives::MAX_ASSOCIATION_WINDOW + 1).is_err());
+    ingest(2).unwrap();
+
+    // Signals 1:name:Alice and 1:knows:Bob are two apart; entity 1 links to itself.
+    let (session, _) = load_or_create_session(&db_path, "file").unwrap();
+    let alice = session.lookup_entity(EntityId(1)).unwrap();
+    assert!(session.get_edge(alice, alice, None).unwrap().is_some());
+}
+
+// =============================================================================
+// RETRACT PROPERTY COMMAND TESTS
+// =============================================================================
+
+#[test]
+fn test_retract_property_removes_pair() {
+    let temp = create_temp_dir();
+    let db_path = temp.path().join("test.db");
+    let signals_file = create_signals_json(&temp);
+
+    cmd_init(&db_path, "file", false).unwrap();
+    cmd_ingest(
+        &db_path,
+        "file",
+        false,
+        Some(&signals_file),
+        "json",
+        false,
+        false,
+        None,
+    )
+    .unwrap();
+
+    cmd_retract_property(&db_path, "file", false, 1, "knows", "Bob").unwrap();
+
+    let (session, _) = load_or_create_session(&db_path, "file").unwrap();
+    let alice = session.lookup_entity(EntityId(1)).unwrap();
+    assert_eq!(
+        session.get_properties(alice).unwrap(),
+        vec![(Attribute::new("name"), Value::new("Alice"))]
+    );
+
+    // The pair is gone, so a second retraction fails.
+    assert!(cmd_retract_property(&db_path, "file", false, 1, "knows", "Bob").is_err());
+}
+
+// =============================================================================
+// DELETE COMMAND TESTS
+// =============================================================================
+
+#[test]
+fn test_delete_removes_entity_and_edges() {
+    let temp = create_temp_dir();
+    let db_path = temp.path().join("test.db");
+    let signals_file = create_signals_json(&temp);
+
+    cmd_init(&db_path, "file", false).unwrap();
+    cmd_ingest(
+        &db_path,
+        "file",
+        false,
+        Some(&signals_file),
+        "json",
+        false,
+        false,
+        None,
+    )
+    .unwrap();
+
+    cmd_delete(&db_path, "file", false, 2).unwrap();
+
+    let (session, _) = load_or_create_session(&db_path, "file").unwrap();
+    assert_eq!(session.lookup_entity(EntityId(2)), None);
+    assert!(session.lookup_entity(EntityId(1)).is_some());
+    assert_eq!(session.edge_count().expect("edge_count"), 0);
+}
+
+#[test]
+fn test_delete_unknown_entity_fails() {
+    let temp = create_temp_dir();
+    let db_path = temp.path().join("test.redb");
+
+    cmd_init(&db_path, "redb", false).unwrap();
+    assert!(cmd_delete(&db_path, "redb", true, 42).is_err());
+}
+
+// =============================================================================
+// REPLAY COMMAND TESTS
+// =============================================================================
+
+#[test]
+fn test_replay_rebuilds_the_redb_graph() {
+    let temp = create_temp_dir();
+    let db_path = temp.path().join("test.redb");
+    let signals_file = create_signals_json(&temp);
+    let output_path = temp.path().join("replayed.krex");
+
+    cmd_init(&db_path, "redb", false).unwrap();
+    cmd_ingest(
+        &db_path,
+        "redb",
+        false,
+        Some(&signals_file),
+        "json",
+        false,
+        false,
+        None,
+    )
+    .unwrap();
+    cmd_delete(&db_path, "redb", false, 2).unwrap();
+
+    cmd_replay(&db_path, "redb", true, Some(&output_path)).unwrap();
+
+    let (stored, _) = load_or_create_session(&db_path, "redb").unwrap();
+    let data = std::fs::read(&output_path).unwrap();
+    let (replayed, _) = kremis_core::export::import_canonical(&data).unwrap();
+    assert_eq!(
+        kremis_core::export::export_canonical(&replayed).unwrap(),
+        kremis_core::export::export_canonical(&stored.export_graph_snapshot().unwrap()).unwrap()
+    );
+}
+
+#[test]
+fn test_replay_requires_the_redb_backend() {
+    let temp = create_temp_dir();
+    let db_path = temp.path().join("test.db");
+
+    cmd_init(&db_path, "file", false).unwrap();
+    assert!(cmd_replay(&db_path, "file", false, None).is_err());
+}
+
 // =============================================================================
 // QUERY COMMAND TESTS
 // =============================================================================
@@ -406,6 +712,11 @@ fn test_query_lookup_not_found() {
         Some(999),
         None,
         None,
+        None,
+        None,
+        None,
+        None,
+        None,
     );
     assert!(result.is_ok());
 }
@@ -425,6 +736,7 @@ fn test_query_lookup_found() {
         "json",
         false,
         false,
+        None,
     )
     .unwrap();
 
@@ -439,6 +751,11 @@ fn test_query_lookup_found() {
         Some(1),
         None,
         None,
+        None,
+        None,
+        None,
+        None,
+        None,
     );
     assert!(result.is_ok());
 }
@@ -458,6 +775,7 @@ fn test_query_traverse() {
         "json",
         false,
         false,
+        None,
     )
     .unwrap();
 
@@ -472,6 +790,11 @@ fn test_query_traverse() {
         None,
         None,
         None,
+        None,
+        None,
+        None,
+        None,
+        None,
     );
     assert!(result.is_ok());
 }
@@ -491,6 +814,7 @@ fn test_query_traverse_filtered() {
         "json",
         false,
         false,
+        None,
     )
     .unwrap();
 
@@ -505,6 +829,11 @@ fn test_query_traverse_filtered() {
         None,
         None,
         Some(10),
+        None,
+        None,
+        None,
+        None,
+        None,
     );
     assert!(result.is_ok());
 }
@@ -524,6 +853,7 @@ fn test_query_path() {
         "json",
         false,
         false,
+        None,
     )
     .unwrap();
 
@@ -538,10 +868,263 @@ fn test_query_path() {
         None,
         None,
         None,
+        None,
+        None,
+        None,
+        None,
+        None,
     );
     assert!(result.is_ok());
 }
 
+#[test]
+fn test_query_path_with_constraints() {
+    let temp = create_temp_dir();
+    let db_path = temp.path().join("test.db");
+    let signals_file = create_signals_json(&temp);
+
+    cmd_init(&db_path, "file", false).unwrap();
+    cmd_ingest(
+        &db_path,
+        "file",
+        false,
+        Some(&signals_file),
+        "json",
+        false,
+        false,
+        None,
+    )
+    .unwrap();
+
+    let query = |avoid: &str| {
+        cmd_query(
+            &db_path,
+            "file",
+            true,
+            "path",
+            Some(0),
+            Some(1),
+            3,
+            None,
+            None,
+            Some(1),
+            None,
+            None,
+            Some(avoid.to_string()),
+            Some("1".to_string()),
+            Some(2),
+        )
+    };
+    assert!(query("7, 8").is_ok());
+    assert!(query("seven").is_err());
+}
+
+#[test]
+fn test_query_reachable() {
+    let temp = create_temp_dir();
+    let db_path = temp.path().join("test.db");
+    let signals_file = create_signals_json(&temp);
+
+    cmd_init(&db_path, "file", false).unwrap();
+    cmd_ingest(
+        &db_path,
+        "file",
+        false,
+        Some(&signals_file),
+        "json",
+        false,
+        false,
+        None,
+    )
+    .unwrap();
+
+    // Reachable, unreachable and unknown start all succeed.
+    for (start, end, json_mode) in [(0, 1, false), (1, 0, true), (1, 0, false), (9, 0, true)] {
+        let result = cmd_query(
+            &db_path,
+            "file",
+            json_mode,
+            "reachable",
+            Some(start),
+            Some(end),
+            3,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        assert!(result.is_ok());
+    }
+    let missing_end = cmd_query(
+        &db_path,
+        "file",
+        false,
+        "reachable",
+        Some(0),
+        None,
+        3,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    assert!(missing_end.is_err());
+}
+
+#[test]
+fn test_query_paths_and_k_strongest() {
+    let temp = create_temp_dir();
+    let db_path = temp.path().join("test.db");
+    let signals_file = create_signals_json(&temp);
+
+    cmd_init(&db_path, "file", false).unwrap();
+    cmd_ingest(
+        &db_path,
+        "file",
+        false,
+        Some(&signals_file),
+        "json",
+        false,
+        false,
+        None,
+    )
+    .unwrap();
+
+    for (query_type, json_mode) in [("paths", false), ("paths", true), ("k-strongest", true)] {
+        let result = cmd_query(
+            &db_path,
+            "file",
+            json_mode,
+            query_type,
+            Some(0),
+            Some(1),
+            3,
+            None,
+            None,
+            None,
+            None,
+            Some(5),
+            None,
+            None,
+            None,
+        );
+        assert!(result.is_ok());
+    }
+    let zero_limit = cmd_query(
+        &db_path,
+        "file",
+        false,
+        "k-strongest",
+        Some(0),
+        Some(1),
+        3,
+        None,
+        None,
+        None,
+        None,
+        Some(0),
+        None,
+        None,
+        None,
+    );
+    assert!(zero_limit.is_err());
+}
+
+#[test]
+fn test_query_text_runs_and_rejects_bad_syntax() {
+    let temp = create_temp_dir();
+    let db_path = temp.path().join("test.db");
+    let signals_file = create_signals_json(&temp);
+
+    cmd_init(&db_path, "file", false).unwrap();
+    cmd_ingest(
+        &db_path,
+        "file",
+        false,
+        Some(&signals_file),
+        "json",
+        false,
+        false,
+        None,
+    )
+    .unwrap();
+
+    for json_mode in [false, true] {
+        assert!(cmd_query_text(&db_path, "file", json_mode, "TRAVERSE 0 DEPTH 2").is_ok());
+        assert!(cmd_query_text(&db_path, "file", json_mode, "SEARCH alice").is_ok());
+        assert!(cmd_query_text(&db_path, "file", json_mode, "REACHABLE 1 -> 0").is_ok());
+    }
+    let result = cmd_query_text(&db_path, "file", false, "TRAVERSE zero");
+    assert!(matches!(
+        result,
+        Err(kremis_core::KremisError::QueryParse { position: 9, .. })
+    ));
+}
+
+#[test]
+fn test_components_weak_and_strong() {
+    let temp = create_temp_dir();
+    let db_path = temp.path().join("test.db");
+    let signals_file = create_signals_json(&temp);
+
+    cmd_init(&db_path, "file", false).unwrap();
+    cmd_ingest(
+        &db_path,
+        "file",
+        false,
+        Some(&signals_file),
+        "json",
+        false,
+        false,
+        None,
+    )
+    .unwrap();
+
+    for (strong, json_mode) in [(false, false), (false, true), (true, false), (true, true)] {
+        let result = cmd_components(&db_path, "file", json_mode, strong, None);
+        assert!(result.is_ok());
+    }
+    let invalid = cmd_components(&db_path, "file", false, true, Some(String::new()));
+    assert!(invalid.is_err());
+}
+
+#[test]
+fn test_centrality_metrics() {
+    let temp = create_temp_dir();
+    let db_path = temp.path().join("test.db");
+    let signals_file = create_signals_json(&temp);
+
+    cmd_init(&db_path, "file", false).unwrap();
+    cmd_ingest(
+        &db_path,
+        "file",
+        false,
+        Some(&signals_file),
+        "json",
+        false,
+        false,
+        None,
+    )
+    .unwrap();
+
+    for metric in ["degree", "weighted-degree", "pagerank", "betweenness"] {
+        for json_mode in [false, true] {
+            let result = cmd_centrality(&db_path, "file", json_mode, metric, 5, 20, None);
+            assert!(result.is_ok());
+        }
+    }
+    assert!(cmd_centrality(&db_path, "file", false, "closeness", 5, 20, None).is_err());
+    assert!(cmd_centrality(&db_path, "file", false, "degree", 0, 20, None).is_err());
+}
+
 #[test]
 fn test_query_intersect() {
     let temp = create_temp_dir();
@@ -557,6 +1140,7 @@ fn test_query_intersect() {
         "json",
         false,
         false,
+        None,
     )
     .unwrap();
 
@@ -571,6 +1155,11 @@ fn test_query_intersect() {
         None,
         Some("1,2".to_string()),
         None,
+        None,
+        None,
+        None,
+        None,
+        None,
     );
     assert!(result.is_ok());
 }
@@ -593,6 +1182,11 @@ fn test_query_unknown_type() {
         None,
         None,
         None,
+        None,
+        None,
+        None,
+        None,
+        None,
     );
     assert!(result.is_err());
 }
@@ -616,6 +1210,7 @@ fn test_query_lookup_json_mode() {
         "json",
         false,
         false,
+        None,
     )
     .unwrap();
 
@@ -630,6 +1225,11 @@ fn test_query_lookup_json_mode() {
         Some(1),
         None,
         None,
+        None,
+        None,
+        None,
+        None,
+        None,
     );
     assert!(result.is_ok());
 }
@@ -649,6 +1249,7 @@ fn test_query_traverse_json_mode() {
         "json",
         false,
         false,
+        None,
     )
     .unwrap();
 
@@ -663,6 +1264,11 @@ fn test_query_traverse_json_mode() {
         None,
         None,
         None,
+        None,
+        None,
+        None,
+        None,
+        None,
     );
     assert!(result.is_ok());
 }
@@ -682,6 +1288,7 @@ fn test_query_intersect_json_mode() {
         "json",
         false,
         false,
+        None,
     )
     .unwrap();
 
@@ -696,6 +1303,11 @@ fn test_query_intersect_json_mode() {
         None,
         Some("1,2".to_string()),
         None,
+        None,
+        None,
+        None,
+        None,
+        None,
     );
     assert!(result.is_ok());
 }
@@ -715,6 +1327,7 @@ fn test_query_properties_json_mode() {
         "json",
         false,
         false,
+        None,
     )
     .unwrap();
 
@@ -729,6 +1342,11 @@ fn test_query_properties_json_mode() {
         None,
         None,
         None,
+        None,
+        None,
+        None,
+        None,
+        None,
     );
     assert!(result.is_ok());
 }
@@ -753,6 +1371,7 @@ fn test_export_canonical_format() {
         "json",
         false,
         false,
+        None,
     )
     .unwrap();
 
@@ -761,6 +1380,34 @@ fn test_export_canonical_format() {
     assert!(output_path.exists());
 }
 
+#[test]
+fn test_export_canonical_streams_a_redb_database() {
+    let temp = create_temp_dir();
+    let db_path = temp.path().join("test.redb");
+    let signals_file = create_signals_json(&temp);
+    let output_path = temp.path().join("export.bin");
+
+    cmd_init(&db_path, "redb", false).unwrap();
+    cmd_ingest(
+        &db_path,
+        "redb",
+        false,
+        Some(&signals_file),
+        "json",
+        false,
+        false,
+        None,
+    )
+    .unwrap();
+
+    cmd_export(&db_path, "redb", &output_path, "canonical").unwrap();
+
+    let (session, _) = load_or_create_session(&db_path, "redb").unwrap();
+    let expected =
+        kremis_core::export_canonical(&session.export_graph_snapshot().unwrap()).unwrap();
+    assert_eq!(std::fs::read(&output_path).unwrap(), expected);
+}
+
 #[test]
 fn test_export_json_format() {
     let temp = create_temp_dir();
@@ -777,6 +1424,7 @@ fn test_export_json_format() {
         "json",
         false,
         false,
+        None,
     )
     .unwrap();
 
@@ -823,6 +1471,7 @@ fn test_import_canonical() {
         "json",
         false,
         false,
+        None,
     )
     .unwrap();
     cmd_export(&db_path, "file", &export_path, "canonical").unwrap();
@@ -845,22 +1494,205 @@ fn test_import_canonical() {
 }
 
 #[test]
-fn test_import_to_redb_fails() {
+fn test_import_to_redb_streams_the_export() {
     let temp = create_temp_dir();
+    let db_path = temp.path().join("test.db");
     let export_path = temp.path().join("export.bin");
     let import_db_path = temp.path().join("imported.redb");
 
-    // Create a minimal canonical export
-    let session = Session::new();
-    let graph = session
-        .graph_opt()
-        .expect("in-memory session should have graph");
-    let data = kremis_core::export::export_canonical(graph).unwrap();
-    std::fs::write(&export_path, &data).unwrap();
+    let (mut session, _) = load_or_create_session(&db_path, "file").unwrap();
+    session
+        .ingest_sequence(&[
+            Signal::new(EntityId(1), Attribute::new("name"), Value::new("Alice")),
+            Signal::new(EntityId(2), Attribute::new("name"), Value::new("Bob")),
+        ])
+        .unwrap();
+    save_session(&session, &db_path).unwrap();
+    cmd_export(&db_path, "file", &export_path, "canonical").unwrap();
 
-    // Import to redb should fail (not supported)
-    let result = cmd_import(&import_db_path, "redb", &export_path);
-    assert!(result.is_err());
+    cmd_import(&import_db_path, "redb", &export_path).unwrap();
+    let (imported, backend) = load_or_create_session(&import_db_path, "redb").unwrap();
+    assert_eq!(backend, "redb");
+    assert_eq!(
+        kremis_core::export::export_canonical(&imported.export_graph_snapshot().unwrap()).unwrap(),
+        kremis_core::export::export_canonical(&session.export_graph_snapshot().unwrap()).unwrap()
+    );
+    drop(imported);
+
+    // Import never overwrites a populated database.
+    assert!(cmd_import(&import_db_path, "redb", &export_path).is_err());
+}
+
+// =============================================================================
+// DIFF COMMAND TESTS
+// =============================================================================
+
+#[test]
+fn test_diff_between_exports_and_the_database() {
+    let temp = create_temp_dir();
+    let db_path = temp.path().join("test.db");
+    let signals_file = create_signals_json(&temp);
+    let before_path = temp.path().join("before.bin");
+    let after_path = temp.path().join("after.bin");
+
+    cmd_init(&db_path, "file", false).unwrap();
+    cmd_export(&db_path, "file", &before_path, "canonical").unwrap();
+    cmd_ingest(
+        &db_path,
+        "file",
+        false,
+        Some(&signals_file),
+        "json",
+        false,
+        false,
+        None,
+    )
+    .unwrap();
+    cmd_export(&db_path, "file", &after_path, "canonical").unwrap();
+
+    assert!(cmd_diff(&db_path, "file", false, &before_path, Some(&after_path)).is_ok());
+    assert!(cmd_diff(&db_path, "file", true, &before_path, None).is_ok());
+    assert!(
+        cmd_diff(
+            &db_path,
+            "file",
+            false,
+            &temp.path().join("missing.bin"),
+            None
+        )
+        .is_err()
+    );
+}
+
+// =============================================================================
+// DELTA EXPORT TESTS
+// =============================================================================
+
+#[test]
+fn test_delta_export_applies_to_its_base() {
+    let temp = create_temp_dir();
+    let db_path = temp.path().join("test.db");
+    let signals_file = create_signals_json(&temp);
+    let base_path = temp.path().join("base.krex");
+    let delta_path = temp.path().join("nightly.krexd");
+    let target_path = temp.path().join("target.krex");
+    let rebuilt_path = temp.path().join("rebuilt.krex");
+
+    cmd_init(&db_path, "file", false).unwrap();
+    cmd_export(&db_path, "file", &base_path, "canonical").unwrap();
+    cmd_ingest(
+        &db_path,
+        "file",
+        false,
+        Some(&signals_file),
+        "json",
+        false,
+        false,
+        None,
+    )
+    .unwrap();
+    cmd_export(&db_path, "file", &target_path, "canonical").unwrap();
+    cmd_export_delta(&db_path, "file", &delta_path, &base_path).unwrap();
+
+    cmd_apply_delta(true, &base_path, &delta_path, &rebuilt_path).unwrap();
+    assert_eq!(
+        std::fs::read(&rebuilt_path).unwrap(),
+        std::fs::read(&target_path).unwrap()
+    );
+
+    // The target is not the base the delta was computed against.
+    assert!(cmd_apply_delta(false, &target_path, &delta_path, &rebuilt_path).is_err());
+    // A full export is not a delta.
+    assert!(cmd_apply_delta(false, &base_path, &target_path, &rebuilt_path).is_err());
+}
+
+#[test]
+fn test_delta_export_streams_a_base_past_the_import_size_limit() {
+    let temp = create_temp_dir();
+    let db_path = temp.path().join("test.redb");
+    let signals_file = create_signals_json(&temp);
+    let base_path = temp.path().join("base.krex");
+    let padded_path = temp.path().join("padded.krex");
+    let delta_path = temp.path().join("nightly.krexd");
+    let target_path = temp.path().join("target.krex");
+    let rebuilt_path = temp.path().join("rebuilt.krex");
+
+    cmd_init(&db_path, "redb", false).unwrap();
+    cmd_export(&db_path, "redb", &base_path, "canonical").unwrap();
+    // A sparse file larger than the 500 MB import limit; only the export at
+    // its start is read.
+    std::fs::copy(&base_path, &padded_path).unwrap();
+    std::fs::OpenOptions::new()
+        .write(true)
+        .open(&padded_path)
+        .unwrap()
+        .set_len(600 * 1024 * 1024)
+        .unwrap();
+
+    cmd_ingest(
+        &db_path,
+        "redb",
+        false,
+        Some(&signals_file),
+        "json",
+        false,
+        false,
+        None,
+    )
+    .unwrap();
+    cmd_export(&db_path, "redb", &target_path, "canonical").unwrap();
+    cmd_export_delta(&db_path, "redb", &delta_path, &padded_path).unwrap();
+
+    cmd_apply_delta(true, &base_path, &delta_path, &rebuilt_path).unwrap();
+    assert_eq!(
+        std::fs::read(&rebuilt_path).unwrap(),
+        std::fs::read(&target_path).unwrap()
+    );
+}
+
+// =============================================================================
+// MERGE COMMAND TESTS
+// =============================================================================
+
+#[test]
+fn test_merge_into_a_redb_database_reconciles_entities() {
+    let temp = create_temp_dir();
+    let theirs_path = temp.path().join("theirs.db");
+    let export_path = temp.path().join("theirs.bin");
+    let db_path = temp.path().join("ours.redb");
+
+    let mut theirs = Session::new();
+    theirs
+        .ingest_sequence(&[
+            Signal::new(EntityId(3), Attribute::new("name"), Value::new("Carol")),
+            Signal::new(EntityId(1), Attribute::new("name"), Value::new("Alicia")),
+        ])
+        .unwrap();
+    save_session(&theirs, &theirs_path).unwrap();
+    cmd_export(&theirs_path, "file", &export_path, "canonical").unwrap();
+
+    cmd_init(&db_path, "redb", false).unwrap();
+    {
+        let (mut ours, _) = load_or_create_session(&db_path, "redb").unwrap();
+        ours.ingest_sequence(&[
+            Signal::new(EntityId(1), Attribute::new("name"), Value::new("Alice")),
+            Signal::new(EntityId(2), Attribute::new("name"), Value::new("Bob")),
+        ])
+        .unwrap();
+    }
+
+    assert!(cmd_merge(&db_path, "redb", false, &export_path, "bogus").is_err());
+    cmd_merge(&db_path, "redb", true, &export_path, "keep-existing").unwrap();
+
+    let (merged, _) = load_or_create_session(&db_path, "redb").unwrap();
+    assert_eq!(merged.node_count().unwrap(), 3);
+    let alice = merged.lookup_entity(EntityId(1)).unwrap();
+    assert_eq!(
+        merged.get_properties(alice).unwrap(),
+        vec![(Attribute::new("name"), Value::new("Alice"))]
+    );
+    let carol = merged.lookup_entity(EntityId(3)).unwrap();
+    assert!(merged.get_edge(carol, alice, None).unwrap().is_some());
 }
 
 // =============================================================================
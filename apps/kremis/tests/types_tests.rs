@@ -4,9 +4,11 @@
 #![allow(clippy::unwrap_used, clippy::panic)]
 
 use kremis::api::{
-    EdgeJson, ExportResponse, HealthResponse, IngestRequest, IngestResponse, QueryRequest,
-    QueryResponse, StageResponse, StatusResponse,
+    BatchIngestRequest, CentralityKind, CentralityResponse, ComponentKind, ComponentsResponse,
+    EdgeJson, ExportResponse, HealthResponse, IngestRequest, IngestResponse, PipelineRequest,
+    PipelineStage, QueryEnvelope, QueryRequest, QueryResponse, StageResponse, StatusResponse,
 };
+use kremis_core::{Attribute, EdgeWeight, NodeId};
 
 // =============================================================================
 // HEALTH RESPONSE TESTS
@@ -112,7 +114,7 @@ fn test_ingest_request_deserialization() {
     let json = r#"{"entity_id":1,"attribute":"name","value":"Alice"}"#;
     let request: IngestRequest = serde_json::from_str(json).unwrap();
 
-    assert_eq!(request.entity_id, 1);
+    assert_eq!(request.entity_id, Some(1));
     assert_eq!(request.attribute, "name");
     assert_eq!(request.value, "Alice");
 }
@@ -120,9 +122,13 @@ fn test_ingest_request_deserialization() {
 #[test]
 fn test_ingest_request_to_signal_valid() {
     let request = IngestRequest {
-        entity_id: 1,
+        entity_id: Some(1),
+        entity_key: None,
         attribute: "name".to_string(),
         value: "Alice".to_string(),
+        link_from_entity_id: None,
+        link_from_entity_key: None,
+        relation: None,
     };
 
     let result = request.to_signal();
@@ -132,9 +138,13 @@ fn test_ingest_request_to_signal_valid() {
 #[test]
 fn test_ingest_request_to_signal_empty_attribute() {
     let request = IngestRequest {
-        entity_id: 1,
+        entity_id: Some(1),
+        entity_key: None,
         attribute: "".to_string(),
         value: "Alice".to_string(),
+        link_from_entity_id: None,
+        link_from_entity_key: None,
+        relation: None,
     };
 
     let result = request.to_signal();
@@ -144,15 +154,99 @@ fn test_ingest_request_to_signal_empty_attribute() {
 #[test]
 fn test_ingest_request_to_signal_empty_value() {
     let request = IngestRequest {
-        entity_id: 1,
+        entity_id: Some(1),
+        entity_key: None,
         attribute: "name".to_string(),
         value: "".to_string(),
+        link_from_entity_id: None,
+        link_from_entity_key: None,
+        relation: None,
     };
 
     let result = request.to_signal();
     assert!(result.is_err());
 }
 
+#[test]
+fn test_ingest_request_needs_exactly_one_entity_reference() {
+    let json = r#"{"entity_key":"svc:ledger","attribute":"name","value":"Ledger"}"#;
+    let request: IngestRequest = serde_json::from_str(json).unwrap();
+    assert_eq!(request.entity_id, None);
+    assert!(request.validate().is_ok());
+    // A key needs the registry, so it cannot become a signal on its own.
+    assert!(request.to_signal().is_err());
+    assert!(
+        !serde_json::to_string(&request)
+            .unwrap()
+            .contains("entity_id")
+    );
+
+    let both = IngestRequest {
+        entity_id: Some(1),
+        ..request.clone()
+    };
+    assert!(both.validate().is_err());
+
+    let neither = IngestRequest {
+        entity_key: None,
+        ..request
+    };
+    assert!(neither.validate().is_err());
+}
+
+#[test]
+fn test_batch_ingest_request_linking_fields() {
+    let plain: BatchIngestRequest = serde_json::from_str(r#"{"signals":[]}"#).unwrap();
+    assert_eq!(plain.window, None);
+    assert!(plain.links.is_none());
+    assert!(plain.validate_links().is_ok());
+
+    let json = r#"{"signals":[],"links":[{"from_entity_key":"a","to_entity_id":2}]}"#;
+    let linked: BatchIngestRequest = serde_json::from_str(json).unwrap();
+    assert!(linked.validate_links().is_ok());
+
+    let both = BatchIngestRequest {
+        window: Some(2),
+        ..linked.clone()
+    };
+    assert!(both.validate_links().is_err());
+
+    let too_wide: BatchIngestRequest =
+        serde_json::from_str(r#"{"signals":[],"window":1000}"#).unwrap();
+    assert!(too_wide.validate_links().is_err());
+
+    let json =
+        r#"{"signals":[],"links":[{"from_entity_id":1,"from_entity_key":"a","to_entity_id":2}]}"#;
+    let ambiguous: BatchIngestRequest = serde_json::from_str(json).unwrap();
+    assert!(ambiguous.validate_links().is_err());
+}
+
+#[test]
+fn test_ingest_request_relation_needs_a_link() {
+    let json = r#"{"entity_id":2,"attribute":"n","value":"b","relation":"depends_on"}"#;
+    let unlinked: IngestRequest = serde_json::from_str(json).unwrap();
+    assert!(unlinked.validate().is_err());
+
+    let linked = IngestRequest {
+        link_from_entity_id: Some(1),
+        ..unlinked.clone()
+    };
+    assert!(linked.has_link());
+    assert!(linked.validate().is_ok());
+
+    let bad_label = IngestRequest {
+        relation: Some(String::new()),
+        ..linked.clone()
+    };
+    assert!(bad_label.validate().is_err());
+
+    let ambiguous = IngestRequest {
+        link_from_entity_key: Some("a".to_string()),
+        ..linked
+    };
+    assert!(ambiguous.validate().is_err());
+}
+
 // =============================================================================
 // INGEST RESPONSE TESTS
 // =============================================================================
@@ -194,7 +288,10 @@ fn test_ingest_response_serialization() {
 
 #[test]
 fn test_query_request_lookup_serialization() {
-    let request = QueryRequest::Lookup { entity_id: 42 };
+    let request = QueryRequest::Lookup {
+        entity_id: Some(42),
+        entity_key: None,
+    };
     let json = serde_json::to_string(&request).unwrap();
 
     assert!(json.contains("\"type\":\"lookup\""));
@@ -206,6 +303,7 @@ fn test_query_request_traverse_serialization() {
     let request = QueryRequest::Traverse {
         node_id: 1,
         depth: 3,
+        relation: None,
     };
     let json = serde_json::to_string(&request).unwrap();
 
@@ -221,6 +319,7 @@ fn test_query_request_traverse_filtered_serialization() {
         depth: 2,
         min_weight: 50,
         top_k: None,
+        relation: None,
     };
     let json = serde_json::to_string(&request).unwrap();
 
@@ -236,6 +335,7 @@ fn test_query_request_traverse_filtered_top_k_serialization() {
         depth: 3,
         min_weight: 0,
         top_k: Some(5),
+        relation: None,
     };
     let json = serde_json::to_string(&request).unwrap();
     assert!(json.contains("\"top_k\":5"));
@@ -253,7 +353,15 @@ fn test_query_request_traverse_filtered_top_k_serialization() {
 
 #[test]
 fn test_query_request_strongest_path_serialization() {
-    let request = QueryRequest::StrongestPath { start: 1, end: 10 };
+    let request = QueryRequest::StrongestPath {
+        start: 1,
+        end: 10,
+        relation: None,
+        avoid: vec![],
+        waypoints: vec![],
+        max_hops: None,
+        min_weight: None,
+    };
     let json = serde_json::to_string(&request).unwrap();
 
     assert!(json.contains("\"type\":\"strongest_path\""));
@@ -261,6 +369,170 @@ fn test_query_request_strongest_path_serialization() {
     assert!(json.contains("\"end\":10"));
 }
 
+#[test]
+fn test_query_request_backward_serialization() {
+    let request = QueryRequest::TraverseBackward {
+        node_id: 3,
+        depth: 2,
+        relation: Some("calls".to_string()),
+    };
+    let json = serde_json::to_string(&request).unwrap();
+    assert!(json.contains("\"type\":\"traverse_backward\""));
+    assert!(json.contains("\"relation\":\"calls\""));
+
+    let request = QueryRequest::StrongestPathBackward {
+        start: 10,
+        end: 1,
+        relation: None,
+    };
+    let json = serde_json::to_string(&request).unwrap();
+    assert!(json.contains("\"type\":\"strongest_path_backward\""));
+    assert!(!json.contains("relation"));
+}
+
+#[test]
+fn test_query_request_reachable_roundtrip() {
+    let json = r#"{"type":"reachable","start":4,"end":9,"relation":"depends_on"}"#;
+    let request: QueryRequest = serde_json::from_str(json).unwrap();
+    match request {
+        QueryRequest::Reachable {
+            start,
+            end,
+            ref relation,
+        } => {
+            assert_eq!((start, end), (4, 9));
+            assert_eq!(relation.as_deref(), Some("depends_on"));
+        }
+        other => panic!("Expected Reachable, got {other:?}"),
+    }
+    assert_eq!(serde_json::to_string(&request).unwrap(), json);
+}
+
+#[test]
+fn test_query_request_strongest_path_constraints() {
+    let json = r#"{"type":"strongest_path","start":1,"end":2,"avoid":[5],"waypoints":[3,4],"max_hops":6,"min_weight":2}"#;
+    let request: QueryRequest = serde_json::from_str(json).unwrap();
+    match request {
+        QueryRequest::StrongestPath {
+            ref avoid,
+            ref waypoints,
+            max_hops,
+            min_weight,
+            ..
+        } => {
+            assert_eq!(avoid, &vec![5]);
+            assert_eq!(waypoints, &vec![3, 4]);
+            assert_eq!((max_hops, min_weight), (Some(6), Some(2)));
+        }
+        other => panic!("Expected StrongestPath, got {other:?}"),
+    }
+    assert_eq!(serde_json::to_string(&request).unwrap(), json);
+}
+
+#[test]
+fn test_query_request_paths_defaults() {
+    let json = r#"{"type":"paths","start":1,"end":2,"max_hops":4}"#;
+    let request: QueryRequest = serde_json::from_str(json).unwrap();
+    match request {
+        QueryRequest::Paths {
+            max_hops, limit, ..
+        } => assert_eq!((max_hops, limit), (4, 10)),
+        other => panic!("Expected Paths, got {other:?}"),
+    }
+
+    let json = r#"{"type":"k_strongest_paths","start":1,"end":2,"k":3}"#;
+    let request: QueryRequest = serde_json::from_str(json).unwrap();
+    assert!(matches!(
+        request,
+        QueryRequest::KStrongestPaths { k: 3, .. }
+    ));
+    assert_eq!(serde_json::to_string(&request).unwrap(), json);
+}
+
+#[test]
+fn test_query_request_pattern_defaults() {
+    let json = r#"{"type":"pattern","variables":[{"name":"a"},{"name":"b","node_id":4}]}"#;
+    let request: QueryRequest = serde_json::from_str(json).unwrap();
+    match &request {
+        QueryRequest::Pattern {
+            variables,
+            edges,
+            limit,
+        } => {
+            assert_eq!(variables.len(), 2);
+            assert_eq!(variables[1].node_id, Some(4));
+            assert!(variables[0].properties.is_empty());
+            assert!(edges.is_empty());
+            assert_eq!(*limit, 10);
+        }
+        other => panic!("Expected Pattern, got {other:?}"),
+    }
+    let json = serde_json::to_string(&request).unwrap();
+    assert!(!json.contains("\"edges\""));
+    assert!(!json.contains("properties"));
+}
+
+#[test]
+fn test_pipeline_request_stages() {
+    let json = r#"{"stages":[{"stage":"lookup","entity_keys":["alice"]},{"stage":"traverse","depth":2},{"stage":"properties"}]}"#;
+    let request: PipelineRequest = serde_json::from_str(json).unwrap();
+    assert_eq!(request.stages.len(), 3);
+    match &request.stages[0] {
+        PipelineStage::Lookup {
+            entity_ids,
+            entity_keys,
+        } => {
+            assert!(entity_ids.is_empty());
+            assert_eq!(entity_keys, &["alice".to_string()]);
+        }
+        other => panic!("Expected Lookup, got {other:?}"),
+    }
+    match &request.stages[1] {
+        PipelineStage::Traverse {
+            depth,
+            min_weight,
+            backward,
+            relation,
+        } => {
+            assert_eq!(*depth, 2);
+            assert!(min_weight.is_none() && !backward && relation.is_none());
+        }
+        other => panic!("Expected Traverse, got {other:?}"),
+    }
+    assert!(matches!(request.stages[2], PipelineStage::Properties));
+    assert!(request.as_of_seq.is_none());
+}
+
+#[test]
+fn test_query_request_from_parsed_text() {
+    let query = kremis_core::parse_query("TRAVERSE 2 DEPTH 4 MINWEIGHT 3 RELATION calls").unwrap();
+    let request = QueryRequest::try_from(&query.query_type).unwrap();
+    assert_eq!(
+        serde_json::to_value(&request).unwrap(),
+        serde_json::json!({
+            "type": "traverse_filtered",
+            "node_id": 2,
+            "depth": 4,
+            "min_weight": 3,
+            "top_k": null,
+            "relation": "calls"
+        })
+    );
+}
+
+#[test]
+fn test_query_request_find_by_property_defaults() {
+    let json = r#"{"type":"find_by_property","attribute":"role","value":"engineer"}"#;
+    let request: QueryRequest = serde_json::from_str(json).unwrap();
+    match request {
+        QueryRequest::FindByProperty { after, limit, .. } => {
+            assert_eq!(after, None);
+            assert_eq!(limit, 100);
+        }
+        _ => panic!("Expected FindByProperty variant"),
+    }
+}
+
 #[test]
 fn test_query_request_intersect_serialization() {
     let request = QueryRequest::Intersect {
@@ -289,7 +561,7 @@ fn test_query_request_deserialization() {
     let request: QueryRequest = serde_json::from_str(json).unwrap();
 
     match request {
-        QueryRequest::Lookup { entity_id } => assert_eq!(entity_id, 42),
+        QueryRequest::Lookup { entity_id, .. } => assert_eq!(entity_id, Some(42)),
         _ => panic!("Expected Lookup variant"),
     }
 }
@@ -401,6 +673,7 @@ fn test_edge_json_serialization() {
     let edge = EdgeJson {
         from: 1,
         to: 2,
+        relation: None,
         weight: 100,
     };
 
@@ -408,6 +681,25 @@ fn test_edge_json_serialization() {
     assert!(json.contains("\"from\":1"));
     assert!(json.contains("\"to\":2"));
     assert!(json.contains("\"weight\":100"));
+    assert!(!json.contains("relation"));
+}
+
+#[test]
+fn test_edge_json_carries_the_relation_label() {
+    let edge = EdgeJson::from((
+        NodeId(1),
+        NodeId(2),
+        Some(Attribute::new("depends_on")),
+        EdgeWeight::new(3),
+    ));
+    let json = serde_json::to_string(&edge).unwrap();
+    assert_eq!(
+        json,
+        r#"{"from":1,"to":2,"relation":"depends_on","weight":3}"#
+    );
+
+    let edge: EdgeJson = serde_json::from_str(&json).unwrap();
+    assert_eq!(edge.relation.as_deref(), Some("depends_on"));
 }
 
 #[test]
@@ -415,6 +707,7 @@ fn test_edge_json_deserialization() {
     let json = r#"{"from":5,"to":10,"weight":50}"#;
     let edge: EdgeJson = serde_json::from_str(json).unwrap();
 
+    assert_eq!(edge.relation, None);
     assert_eq!(edge.from, 5);
     assert_eq!(edge.to, 10);
     assert_eq!(edge.weight, 50);
@@ -458,6 +751,62 @@ fn test_export_response_data_is_base64() {
     assert_eq!(decoded, data);
 }
 
+// =============================================================================
+// COMPONENTS RESPONSE TESTS
+// =============================================================================
+
+#[test]
+fn test_components_response_lists_nodes_sizes_and_cycles() {
+    use kremis_core::{EdgeWeight, EntityId, Graph, GraphStore, components};
+
+    let mut graph = Graph::new();
+    let [a, b, c] = [1, 2, 3].map(|e| graph.insert_node(EntityId(e)).unwrap());
+    for (from, to) in [(a, b), (b, a), (b, c)] {
+        graph
+            .insert_edge(from, to, None, EdgeWeight::new(1))
+            .unwrap();
+    }
+    let scc = components::strongly_connected_components(&graph, None).unwrap();
+    let response = ComponentsResponse::success(ComponentKind::Strong, &scc);
+
+    let json = serde_json::to_value(&response).unwrap();
+    assert_eq!(json["kind"], "strong");
+    assert_eq!(json["count"], 2);
+    assert_eq!(
+        json["nodes"][2],
+        serde_json::json!({"node": 2, "component": 1})
+    );
+    assert_eq!(json["sizes"], serde_json::json!([2, 1]));
+    assert_eq!(json["cycles"], serde_json::json!([[0, 1]]));
+}
+
+#[test]
+fn test_component_kind_defaults_to_weak() {
+    assert_eq!(ComponentKind::default(), ComponentKind::Weak);
+    let kind: ComponentKind = serde_json::from_str("\"strong\"").unwrap();
+    assert_eq!(kind, ComponentKind::Strong);
+}
+
+// =============================================================================
+// CENTRALITY RESPONSE TESTS
+// =============================================================================
+
+#[test]
+fn test_centrality_response_serialization() {
+    use kremis_core::NodeId;
+
+    let response =
+        CentralityResponse::success(CentralityKind::WeightedDegree, 1, &[(NodeId(4), 17)]);
+    let json = serde_json::to_value(&response).unwrap();
+    assert_eq!(json["metric"], "weighted_degree");
+    assert_eq!(json["scale"], 1);
+    assert_eq!(json["nodes"], serde_json::json!([{"node": 4, "score": 17}]));
+
+    let error = CentralityResponse::error(CentralityKind::Pagerank, "bad");
+    assert!(!error.success);
+    assert!(error.nodes.is_empty());
+}
+
 // =============================================================================
 // ROUNDTRIP TESTS
 // =============================================================================
@@ -465,9 +814,13 @@ fn test_export_response_data_is_base64() {
 #[test]
 fn test_ingest_request_roundtrip() {
     let original = IngestRequest {
-        entity_id: 42,
+        entity_id: Some(42),
+        entity_key: None,
         attribute: "test_attr".to_string(),
         value: "test_value".to_string(),
+        link_from_entity_id: None,
+        link_from_entity_key: None,
+        relation: None,
     };
 
     let json = serde_json::to_string(&original).unwrap();
@@ -481,18 +834,31 @@ fn test_ingest_request_roundtrip() {
 #[test]
 fn test_query_request_all_variants_roundtrip() {
     let variants = vec![
-        QueryRequest::Lookup { entity_id: 1 },
+        QueryRequest::Lookup {
+            entity_id: Some(1),
+            entity_key: None,
+        },
         QueryRequest::Traverse {
             node_id: 2,
             depth: 3,
+            relation: None,
         },
         QueryRequest::TraverseFiltered {
             node_id: 4,
             depth: 5,
             min_weight: 10,
             top_k: None,
+            relation: None,
+        },
+        QueryRequest::StrongestPath {
+            start: 6,
+            end: 7,
+            relation: None,
+            avoid: vec![],
+            waypoints: vec![],
+            max_hops: None,
+            min_weight: None,
         },
-        QueryRequest::StrongestPath { start: 6, end: 7 },
         QueryRequest::Intersect {
             nodes: vec![8, 9, 10],
         },
@@ -500,6 +866,26 @@ fn test_query_request_all_variants_roundtrip() {
             node_id: 11,
             depth: 2,
         },
+        QueryRequest::TraverseBackward {
+            node_id: 12,
+            depth: 1,
+            relation: None,
+        },
+        QueryRequest::StrongestPathBackward {
+            start: 13,
+            end: 14,
+            relation: Some("calls".to_string()),
+        },
+        QueryRequest::FindByProperty {
+            attribute: "role".to_string(),
+            value: "engineer".to_string(),
+            after: Some(15),
+            limit: 50,
+        },
+        QueryRequest::Search {
+            query: "ledger api".to_string(),
+            limit: 5,
+        },
     ];
 
     for original in variants {
@@ -551,3 +937,21 @@ fn test_query_response_diagnostic_deserializes_old_format() {
     let response: QueryResponse = serde_json::from_str(json).unwrap();
     assert!(response.diagnostic.is_none());
 }
+
+#[test]
+fn test_query_envelope_flattens_the_query() {
+    let plain: QueryEnvelope =
+        serde_json::from_str(r#"{"type":"properties","node_id":3}"#).unwrap();
+    assert!(matches!(
+        plain.query,
+        QueryRequest::Properties { node_id: 3 }
+    ));
+    assert_eq!(plain.as_of_seq, None);
+
+    let past: QueryEnvelope =
+        serde_json::from_str(r#"{"type":"properties","node_id":3,"as_of_seq":7}"#).unwrap();
+    assert_eq!(past.as_of_seq, Some(7));
+    let json = serde_json::to_string(&past).unwrap();
+    assert!(json.contains("\"type\":\"properties\""));
+    assert!(json.contains("\"as_of_seq\":7"));
+}
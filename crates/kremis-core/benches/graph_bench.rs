@@ -28,7 +28,7 @@ fn create_linear_graph(size: usize) -> Graph {
         let node = graph.insert_node(EntityId(i as u64)).expect("insert");
         if let Some(prev) = prev_node {
             graph
-                .insert_edge(prev, node, EdgeWeight::new(10))
+                .insert_edge(prev, node, None, EdgeWeight::new(10))
                 .expect("edge");
         }
         prev_node = Some(node);
@@ -45,7 +45,7 @@ fn create_star_graph(size: usize) -> Graph {
     for i in 1..size {
         let spoke = graph.insert_node(EntityId(i as u64)).expect("insert");
         graph
-            .insert_edge(hub, spoke, EdgeWeight::new(10))
+            .insert_edge(hub, spoke, None, EdgeWeight::new(10))
             .expect("edge");
     }
 
@@ -66,7 +66,7 @@ fn create_dense_graph(size: usize) -> Graph {
         for j in 1..=5 {
             if i + j < size {
                 graph
-                    .insert_edge(nodes[i], nodes[i + j], EdgeWeight::new(10))
+                    .insert_edge(nodes[i], nodes[i + j], None, EdgeWeight::new(10))
                     .expect("edge");
             }
         }
@@ -155,7 +155,7 @@ fn bench_traverse(c: &mut Criterion) {
             BenchmarkId::new("depth_10", size),
             &(start, 10),
             |b, &(start, depth)| {
-                b.iter(|| black_box(graph.traverse(start, depth)));
+                b.iter(|| black_box(graph.traverse(start, depth, None)));
             },
         );
 
@@ -163,7 +163,7 @@ fn bench_traverse(c: &mut Criterion) {
             BenchmarkId::new("depth_50", size),
             &(start, 50),
             |b, &(start, depth)| {
-                b.iter(|| black_box(graph.traverse(start, depth)));
+                b.iter(|| black_box(graph.traverse(start, depth, None)));
             },
         );
     }
@@ -187,7 +187,7 @@ fn bench_strongest_path(c: &mut Criterion) {
             BenchmarkId::from_parameter(size),
             &(start, end),
             |b, &(start, end)| {
-                b.iter(|| black_box(graph.strongest_path(start, end)));
+                b.iter(|| black_box(graph.strongest_path(start, end, None)));
             },
         );
     }
@@ -390,7 +390,7 @@ fn bench_increment_edge(c: &mut Criterion) {
                 b.iter(|| {
                     let mut graph = create_linear_graph(*size);
                     for &(from, to) in pairs {
-                        let _ = graph.increment_edge(from, to);
+                        let _ = graph.increment_edge(from, to, None);
                     }
                     black_box(graph)
                 });
@@ -444,7 +444,7 @@ fn bench_redb_operations(c: &mut Criterion) {
                 for i in 0..size {
                     let node = redb.insert_node(EntityId(i as u64)).expect("insert");
                     if let Some(p) = prev {
-                        let _ = redb.insert_edge(p, node, EdgeWeight::new(10));
+                        let _ = redb.insert_edge(p, node, None, EdgeWeight::new(10));
                     }
                     prev = Some(node);
                 }
@@ -477,7 +477,7 @@ fn bench_redb_operations(c: &mut Criterion) {
         for i in 0..*size {
             let node = redb.insert_node(EntityId(i as u64)).expect("insert");
             if let Some(p) = prev {
-                redb.insert_edge(p, node, EdgeWeight::new(10))
+                redb.insert_edge(p, node, None, EdgeWeight::new(10))
                     .expect("edge");
             }
             prev = Some(node);
@@ -490,7 +490,7 @@ fn bench_redb_operations(c: &mut Criterion) {
             BenchmarkId::new("traverse_depth10", size),
             &start,
             |b, &start| {
-                b.iter(|| black_box(redb.traverse(start, 10)));
+                b.iter(|| black_box(redb.traverse(start, 10, None)));
             },
         );
     }
@@ -511,7 +511,7 @@ fn bench_dense_graph_traverse(c: &mut Criterion) {
             BenchmarkId::new("depth_5", size),
             &(start, 5),
             |b, &(start, depth)| {
-                b.iter(|| black_box(graph.traverse(start, depth)));
+                b.iter(|| black_box(graph.traverse(start, depth, None)));
             },
         );
 
@@ -519,7 +519,7 @@ fn bench_dense_graph_traverse(c: &mut Criterion) {
             BenchmarkId::new("depth_10", size),
             &(start, 10),
             |b, &(start, depth)| {
-                b.iter(|| black_box(graph.traverse(start, depth)));
+                b.iter(|| black_box(graph.traverse(start, depth, None)));
             },
         );
     }
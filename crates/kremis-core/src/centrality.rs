@@ -0,0 +1,402 @@
+//! # Centrality Module
+//!
+//! Deterministic node centrality over any [`GraphStore`], in integer
+//! arithmetic only.
+//!
+//! - **Degree** counts the edges touching a node, in both directions, each
+//!   relation separately
+//! - **Weighted degree** sums the weights of those edges, negative weights
+//!   counted as zero
+//! - **PageRank** runs a fixed number of power iterations with damping
+//!   [`PAGERANK_DAMPING`]; dangling nodes spread their rank over every node
+//! - **Betweenness** is exact (Brandes' algorithm over every source, no
+//!   sampling): the number of shortest directed paths between other nodes
+//!   that pass through a node, each pair's paths sharing one unit
+//!
+//! Degree scores are plain counts. PageRank and betweenness are fixed-point
+//! numbers in units of `1 / CENTRALITY_SCALE`, rounded down at every step;
+//! betweenness counts shortest paths exactly in 128 bits and fails rather
+//! than saturate on graphs with more (chains of about a hundred diamonds);
+//! the PageRank scores of a graph sum to about `CENTRALITY_SCALE`. PageRank
+//! and betweenness follow distinct successors, so parallel edges under
+//! several relations count once.
+
+use crate::graph::GraphStore;
+use crate::primitives::{MAX_BETWEENNESS_NODES, MAX_PAGERANK_ITERATIONS};
+use crate::{Attribute, KremisError, NodeId};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+/// Fixed-point unit of PageRank and betweenness scores.
+pub const CENTRALITY_SCALE: u64 = 1_000_000_000;
+
+/// PageRank damping factor, as `(numerator, denominator)`.
+pub const PAGERANK_DAMPING: (u64, u64) = (85, 100);
+
+/// Number of PageRank iterations used when the caller does not choose.
+pub const DEFAULT_PAGERANK_ITERATIONS: usize = 20;
+
+/// A centrality measure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CentralityMetric {
+    /// Number of incident edges.
+    Degree,
+    /// Sum of incident edge weights.
+    WeightedDegree,
+    /// PageRank after a fixed number of iterations.
+    PageRank {
+        /// Number of power iterations, at most `MAX_PAGERANK_ITERATIONS`.
+        iterations: usize,
+    },
+    /// Exact shortest-path betweenness.
+    Betweenness,
+}
+
+impl CentralityMetric {
+    /// The unit a score of this metric is expressed in: 1 for counts,
+    /// [`CENTRALITY_SCALE`] for fixed-point scores.
+    #[must_use]
+    pub fn scale(&self) -> u64 {
+        match self {
+            Self::Degree | Self::WeightedDegree => 1,
+            Self::PageRank { .. } | Self::Betweenness => CENTRALITY_SCALE,
+        }
+    }
+}
+
+/// Score every node of the graph under `metric`, following only edges that
+/// match `relation` (`None`: all edges).
+///
+/// Returns `KremisError::InvalidSignal` for PageRank past
+/// `MAX_PAGERANK_ITERATIONS` iterations, and for betweenness on graphs of
+/// more than `MAX_BETWEENNESS_NODES` nodes, whose cost grows with nodes
+/// times edges. Returns `KremisError::BetweennessOverflow` when shortest
+/// paths are too many to count exactly.
+pub fn centrality<G: GraphStore + ?Sized>(
+    graph: &G,
+    metric: CentralityMetric,
+    relation: Option<&Attribute>,
+) -> Result<BTreeMap<NodeId, u64>, KremisError> {
+    if let CentralityMetric::PageRank { iterations } = metric
+        && iterations > MAX_PAGERANK_ITERATIONS
+    {
+        return Err(KremisError::InvalidSignal);
+    }
+    if metric == CentralityMetric::Betweenness && graph.node_count()? > MAX_BETWEENNESS_NODES {
+        return Err(KremisError::InvalidSignal);
+    }
+
+    let adjacency = Adjacency::load(graph, relation)?;
+    let scores = match metric {
+        CentralityMetric::Degree => adjacency.degree(),
+        CentralityMetric::WeightedDegree => adjacency.weighted_degree(),
+        CentralityMetric::PageRank { iterations } => adjacency.pagerank(iterations),
+        CentralityMetric::Betweenness => adjacency.betweenness()?,
+    };
+    Ok(adjacency.ids.into_iter().zip(scores).collect())
+}
+
+/// The `n` highest scores, ordered by score descending, then node id
+/// ascending.
+#[must_use]
+pub fn top_n(scores: &BTreeMap<NodeId, u64>, n: usize) -> Vec<(NodeId, u64)> {
+    let mut ranked: Vec<(NodeId, u64)> = scores.iter().map(|(&id, &s)| (id, s)).collect();
+    ranked.sort_by_key(|&(id, score)| (Reverse(score), id));
+    ranked.truncate(n);
+    ranked
+}
+
+/// The graph read once into dense indices, so the algorithms run without
+/// further storage access.
+struct Adjacency {
+    /// Node ids, ascending; a node's index is its position here.
+    ids: Vec<NodeId>,
+    /// Every matching outgoing edge as `(target index, weight)`.
+    edges: Vec<Vec<(usize, i64)>>,
+    /// Distinct successor indices, ascending.
+    successors: Vec<Vec<usize>>,
+}
+
+impl Adjacency {
+    fn load<G: GraphStore + ?Sized>(
+        graph: &G,
+        relation: Option<&Attribute>,
+    ) -> Result<Self, KremisError> {
+        let ids = graph.node_ids()?;
+        let index: BTreeMap<NodeId, usize> =
+            ids.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+
+        let mut edges = Vec::with_capacity(ids.len());
+        let mut successors = Vec::with_capacity(ids.len());
+        for &id in &ids {
+            let out: Vec<(usize, i64)> = graph
+                .neighbors(id, relation)?
+                .into_iter()
+                .filter_map(|(to, weight)| index.get(&to).map(|&i| (i, weight.value())))
+                .collect();
+            let distinct: BTreeSet<usize> = out.iter().map(|&(to, _)| to).collect();
+            successors.push(distinct.into_iter().collect());
+            edges.push(out);
+        }
+        Ok(Self {
+            ids,
+            edges,
+            successors,
+        })
+    }
+
+    fn degree(&self) -> Vec<u64> {
+        let mut degree = vec![0u64; self.ids.len()];
+        for (from, out) in self.edges.iter().enumerate() {
+            for &(to, _) in out {
+                degree[from] += 1;
+                degree[to] += 1;
+            }
+        }
+        degree
+    }
+
+    fn weighted_degree(&self) -> Vec<u64> {
+        let mut degree = vec![0u64; self.ids.len()];
+        for (from, out) in self.edges.iter().enumerate() {
+            for &(to, weight) in out {
+                let weight = weight.max(0) as u64;
+                degree[from] = degree[from].saturating_add(weight);
+                degree[to] = degree[to].saturating_add(weight);
+            }
+        }
+        degree
+    }
+
+    fn pagerank(&self, iterations: usize) -> Vec<u64> {
+        let n = self.ids.len() as u128;
+        if n == 0 {
+            return Vec::new();
+        }
+        let scale = u128::from(CENTRALITY_SCALE);
+        let (num, den) = (
+            u128::from(PAGERANK_DAMPING.0),
+            u128::from(PAGERANK_DAMPING.1),
+        );
+        let teleport = scale * (den - num) / (den * n);
+
+        let mut rank = vec![scale / n; self.ids.len()];
+        for _ in 0..iterations {
+            let dangling: u128 = self
+                .successors
+                .iter()
+                .zip(&rank)
+                .filter(|(out, _)| out.is_empty())
+                .map(|(_, &r)| r)
+                .sum();
+            let base = teleport + dangling * num / (den * n);
+
+            let mut next = vec![base; self.ids.len()];
+            for (out, &r) in self.successors.iter().zip(&rank) {
+                if out.is_empty() {
+                    continue;
+                }
+                let share = r * num / (den * out.len() as u128);
+                for &to in out {
+                    next[to] += share;
+                }
+            }
+            rank = next;
+        }
+        rank.into_iter().map(clamp_u64).collect()
+    }
+
+    fn betweenness(&self) -> Result<Vec<u64>, KremisError> {
+        let n = self.ids.len();
+        let scale = u128::from(CENTRALITY_SCALE);
+        let mut score = vec![0u128; n];
+
+        for source in 0..n {
+            // Forward BFS counting shortest paths (`sigma`) and recording
+            // each node's predecessors on them.
+            let mut order = Vec::with_capacity(n);
+            let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+            let mut sigma = vec![0u128; n];
+            let mut distance: Vec<Option<usize>> = vec![None; n];
+            sigma[source] = 1;
+            distance[source] = Some(0);
+            let mut queue = VecDeque::from([source]);
+            while let Some(v) = queue.pop_front() {
+                order.push(v);
+                let next_distance = distance[v].map(|d| d + 1);
+                for &w in &self.successors[v] {
+                    if distance[w].is_none() {
+                        distance[w] = next_distance;
+                        queue.push_back(w);
+                    }
+                    if distance[w] == next_distance {
+                        sigma[w] = sigma[w]
+                            .checked_add(sigma[v])
+                            .ok_or(KremisError::BetweennessOverflow)?;
+                        predecessors[w].push(v);
+                    }
+                }
+            }
+
+            // Backward accumulation of each node's dependency on `source`,
+            // in fixed point.
+            let mut delta = vec![0u128; n];
+            while let Some(w) = order.pop() {
+                for &v in &predecessors[w] {
+                    let share = scale
+                        .checked_add(delta[w])
+                        .and_then(|d| sigma[v].checked_mul(d))
+                        .ok_or(KremisError::BetweennessOverflow)?
+                        / sigma[w];
+                    delta[v] = delta[v]
+                        .checked_add(share)
+                        .ok_or(KremisError::BetweennessOverflow)?;
+                }
+                if w != source {
+                    score[w] = score[w].saturating_add(delta[w]);
+                }
+            }
+        }
+        Ok(score.into_iter().map(clamp_u64).collect())
+    }
+}
+
+fn clamp_u64(value: u128) -> u64 {
+    u64::try_from(value).unwrap_or(u64::MAX)
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Graph;
+    use crate::{EdgeWeight, EntityId};
+
+    fn graph_with(nodes: u64, edges: &[(u64, u64, i64)]) -> Graph {
+        let mut graph = Graph::new();
+        for entity in 0..nodes {
+            graph.insert_node(EntityId(entity)).expect("insert");
+        }
+        for &(from, to, weight) in edges {
+            graph
+                .insert_edge(NodeId(from), NodeId(to), None, EdgeWeight::new(weight))
+                .expect("edge");
+        }
+        graph
+    }
+
+    fn scores(graph: &Graph, metric: CentralityMetric) -> Vec<u64> {
+        centrality(graph, metric, None)
+            .expect("centrality")
+            .into_values()
+            .collect()
+    }
+
+    #[test]
+    fn degree_counts_both_directions() {
+        // A star around 0, plus 1 -> 2 with a negative weight
+        let graph = graph_with(4, &[(0, 1, 3), (0, 2, 4), (3, 0, 5), (1, 2, -2)]);
+
+        assert_eq!(scores(&graph, CentralityMetric::Degree), vec![3, 2, 2, 1]);
+        assert_eq!(
+            scores(&graph, CentralityMetric::WeightedDegree),
+            vec![12, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn pagerank_of_a_cycle_is_uniform() {
+        let graph = graph_with(4, &[(0, 1, 1), (1, 2, 1), (2, 3, 1), (3, 0, 1)]);
+        let rank = scores(&graph, CentralityMetric::PageRank { iterations: 30 });
+        assert!(rank.iter().all(|&r| r == CENTRALITY_SCALE / 4));
+    }
+
+    #[test]
+    fn pagerank_favours_the_hub_and_conserves_rank() {
+        // Every leaf points at 0; 0 points back at 1 only.
+        let graph = graph_with(4, &[(1, 0, 1), (2, 0, 1), (3, 0, 1), (0, 1, 1)]);
+        let rank = scores(&graph, CentralityMetric::PageRank { iterations: 20 });
+
+        assert!(rank[0] > rank[1] && rank[1] > rank[2]);
+        assert_eq!(rank[2], rank[3]);
+        let total: u64 = rank.iter().sum();
+        // Rounding only ever drops fractions of a unit per node and step.
+        assert!(total <= CENTRALITY_SCALE && total > CENTRALITY_SCALE - 1000);
+    }
+
+    #[test]
+    fn pagerank_spreads_dangling_rank() {
+        // 0 -> 1 and 1 has no successors: its rank flows back to everyone.
+        let graph = graph_with(2, &[(0, 1, 1)]);
+        let rank = scores(&graph, CentralityMetric::PageRank { iterations: 50 });
+        assert!(rank[1] > rank[0]);
+        assert!(rank[0] + rank[1] > CENTRALITY_SCALE - 100);
+    }
+
+    #[test]
+    fn betweenness_matches_shortest_path_counts() {
+        // 0 -> 1 -> 3 and 0 -> 2 -> 3 share the single pair (0, 3); 3 -> 4
+        // adds pairs (0, 4), (1, 4) and (2, 4) through 3.
+        let graph = graph_with(5, &[(0, 1, 1), (1, 3, 1), (0, 2, 1), (2, 3, 1), (3, 4, 1)]);
+        let between = scores(&graph, CentralityMetric::Betweenness);
+
+        let half = CENTRALITY_SCALE / 2;
+        // 1 and 2 each carry half of (0, 3) and half of (0, 4).
+        assert_eq!(between[1], 2 * half);
+        assert_eq!(between[2], 2 * half);
+        // 3 carries (0, 4), (1, 4) and (2, 4) in full.
+        assert_eq!(between[3], 3 * CENTRALITY_SCALE);
+        assert_eq!(between[0], 0);
+        assert_eq!(between[4], 0);
+    }
+
+    #[test]
+    fn betweenness_rejects_uncountable_shortest_paths() {
+        // A chain of `k` diamonds has 2^k shortest paths end to end.
+        let diamonds = |k: u64| {
+            let mut edges = Vec::new();
+            for i in 0..k {
+                let (a, b, c, d) = (3 * i, 3 * i + 1, 3 * i + 2, 3 * i + 3);
+                edges.extend([(a, b, 1), (a, c, 1), (b, d, 1), (c, d, 1)]);
+            }
+            graph_with(3 * k + 1, &edges)
+        };
+
+        // 2^60 paths still fit alongside the fixed-point scale. The middle
+        // junction is a cut node between the 90 nodes before it and the 90
+        // after; rounding only trims fractions of a unit.
+        let between = scores(&diamonds(60), CentralityMetric::Betweenness);
+        let expected = 90 * 90 * CENTRALITY_SCALE;
+        assert!(between[90] <= expected && between[90] > expected - expected / 1000);
+
+        assert!(matches!(
+            centrality(&diamonds(128), CentralityMetric::Betweenness, None),
+            Err(KremisError::BetweennessOverflow)
+        ));
+    }
+
+    #[test]
+    fn top_n_breaks_ties_by_node_id() {
+        let graph = graph_with(4, &[(0, 1, 1), (2, 3, 1), (3, 2, 1)]);
+        let all = centrality(&graph, CentralityMetric::Degree, None).expect("degree");
+        assert_eq!(
+            top_n(&all, 3),
+            vec![(NodeId(2), 2), (NodeId(3), 2), (NodeId(0), 1)]
+        );
+    }
+
+    #[test]
+    fn rejects_unbounded_work() {
+        let graph = graph_with(1, &[]);
+        let too_many = CentralityMetric::PageRank {
+            iterations: MAX_PAGERANK_ITERATIONS + 1,
+        };
+        assert!(matches!(
+            centrality(&graph, too_many, None),
+            Err(KremisError::InvalidSignal)
+        ));
+    }
+}
@@ -14,8 +14,8 @@
 //! Specification: `docs/concepts/certificate-spec.mdx`.
 
 use crate::export::{CanonicalEdge, CanonicalNode};
-use crate::graph::Graph;
-use crate::{Artifact, KremisError, NodeId};
+use crate::graph::{Graph, GraphStore};
+use crate::{Artifact, KremisError, LabeledEdge, NodeId};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeSet;
 
@@ -48,6 +48,7 @@ pub struct CertBody {
     /// Minimal evidence nodes, sorted by NodeId.
     pub evidence_nodes: Vec<CanonicalNode>,
     /// Minimal evidence edges, sorted by (from, to).
+    #[serde(with = "evidence_edge_wire")]
     pub evidence_edges: Vec<CanonicalEdge>,
     /// Ordered node trace of the traversal.
     pub traversal_trace: Vec<u64>,
@@ -69,6 +70,7 @@ pub struct QueryCertificate {
     /// Minimal evidence nodes, sorted.
     pub evidence_nodes: Vec<CanonicalNode>,
     /// Minimal evidence edges, sorted.
+    #[serde(with = "evidence_edge_wire")]
     pub evidence_edges: Vec<CanonicalEdge>,
     /// Ordered traversal trace (node ids).
     pub traversal_trace: Vec<u64>,
@@ -91,14 +93,7 @@ impl QueryCertificate {
         graph: &Graph,
         artifact: &Artifact,
     ) -> Self {
-        // Evidence node-id set: traversal path plus any subgraph endpoints.
-        let mut id_set: BTreeSet<u64> = artifact.path.iter().map(|n| n.0).collect();
-        if let Some(sub) = &artifact.subgraph {
-            for (from, to, _w) in sub {
-                id_set.insert(from.0);
-                id_set.insert(to.0);
-            }
-        }
+        let id_set = evidence_ids(artifact);
 
         // Evidence is a *local* projection: it is built by direct lookup of the
         // ids the result touches, never by materializing the whole graph. The
@@ -115,21 +110,23 @@ impl QueryCertificate {
             .collect();
 
         let evidence_edges: Vec<CanonicalEdge> = match &artifact.subgraph {
-            Some(sub) => {
-                let mut edges: Vec<CanonicalEdge> = sub
-                    .iter()
-                    .map(|(from, to, w)| CanonicalEdge::new(*from, *to, *w))
-                    .collect();
-                edges.sort();
-                edges
-            }
+            Some(sub) => subgraph_edges(sub),
+            // Parallel relations between two evidence nodes collapse to the
+            // strongest one: that is the weight the path pays for the hop.
             None => id_set
                 .iter()
                 .flat_map(|&from| {
-                    graph
+                    let mut targets: Vec<NodeId> = graph
                         .neighbors_internal(NodeId(from))
-                        .filter(|(to, _)| id_set.contains(&to.0))
-                        .map(move |(to, weight)| CanonicalEdge::new(NodeId(from), to, weight))
+                        .map(|(to, _)| to)
+                        .filter(|to| id_set.contains(&to.0))
+                        .collect();
+                    targets.dedup();
+                    targets.into_iter().filter_map(move |to| {
+                        graph
+                            .strongest_edge_internal(NodeId(from), to)
+                            .map(|weight| CanonicalEdge::new(NodeId(from), to, weight))
+                    })
                 })
                 .collect(),
         };
@@ -146,6 +143,67 @@ impl QueryCertificate {
         }
     }
 
+    /// Create a certificate like [`new`](Self::new), reading the evidence
+    /// from any [`GraphStore`] instead of an in-memory graph.
+    ///
+    /// A persistent graph answers the same direct lookups from its tables,
+    /// so certifying against it never builds a snapshot. The certificate is
+    /// byte-identical to the one `new` builds from that store's snapshot.
+    ///
+    /// # Errors
+    ///
+    /// Returns the store's error if a lookup fails.
+    pub fn from_store(
+        state_hash: [u8; 32],
+        query: impl Into<String>,
+        grounding: impl Into<String>,
+        store: &(impl GraphStore + ?Sized),
+        artifact: &Artifact,
+    ) -> Result<Self, KremisError> {
+        let id_set = evidence_ids(artifact);
+
+        let mut evidence_nodes = Vec::new();
+        for &id in &id_set {
+            if let Some(node) = store.lookup(NodeId(id))? {
+                evidence_nodes.push(CanonicalNode {
+                    id,
+                    entity: node.entity.0,
+                });
+            }
+        }
+
+        let evidence_edges = match &artifact.subgraph {
+            Some(sub) => subgraph_edges(sub),
+            None => {
+                let mut edges = Vec::new();
+                for &from in &id_set {
+                    let mut targets: Vec<NodeId> = store
+                        .neighbors(NodeId(from), None)?
+                        .into_iter()
+                        .map(|(to, _)| to)
+                        .filter(|to| id_set.contains(&to.0))
+                        .collect();
+                    targets.dedup();
+                    for to in targets {
+                        if let Some(weight) = store.strongest_edge(NodeId(from), to, None)? {
+                            edges.push(CanonicalEdge::new(NodeId(from), to, weight));
+                        }
+                    }
+                }
+                edges
+            }
+        };
+
+        Ok(Self {
+            state_hash,
+            query: query.into(),
+            evidence_nodes,
+            evidence_edges,
+            traversal_trace: artifact.path.iter().map(|n| n.0).collect(),
+            grounding: grounding.into(),
+        })
+    }
+
     /// Serialize to the canonical certificate format.
     ///
     /// Layout: `[cert_len: u32 LE] [CertHeader: postcard] [CertBody: postcard]`.
@@ -235,6 +293,92 @@ impl QueryCertificate {
             && self.evidence_nodes.is_empty()
             && self.evidence_edges.is_empty()
     }
+
+    /// Whether this certificate proves absence by closure: a certified
+    /// `unknown` whose evidence edges never leave its evidence nodes.
+    ///
+    /// `reachable` certifies a negative answer this way, with the forward
+    /// closure of its start node as evidence. A verifier holding the graph at
+    /// `state_hash` confirms that every edge leaving an evidence node is
+    /// listed and that the target is not an evidence node.
+    #[must_use]
+    pub fn is_closure_proof(&self) -> bool {
+        let ids: BTreeSet<u64> = self.evidence_nodes.iter().map(|n| n.id).collect();
+        self.grounding == "unknown"
+            && !ids.is_empty()
+            && self
+                .evidence_edges
+                .iter()
+                .all(|e| ids.contains(&e.from) && ids.contains(&e.to))
+    }
+}
+
+/// Evidence node ids of `artifact`: its path plus any subgraph endpoints.
+fn evidence_ids(artifact: &Artifact) -> BTreeSet<u64> {
+    let mut id_set: BTreeSet<u64> = artifact.path.iter().map(|n| n.0).collect();
+    if let Some(sub) = &artifact.subgraph {
+        for (from, to, _, _) in sub {
+            id_set.insert(from.0);
+            id_set.insert(to.0);
+        }
+    }
+    id_set
+}
+
+/// Evidence edges of a subgraph artifact, in canonical order. Relation
+/// labels are dropped: evidence is encoded unlabeled (see
+/// `evidence_edge_wire`).
+fn subgraph_edges(sub: &[LabeledEdge]) -> Vec<CanonicalEdge> {
+    let mut edges: Vec<CanonicalEdge> = sub
+        .iter()
+        .map(|(from, to, _, w)| CanonicalEdge::new(*from, *to, *w))
+        .collect();
+    edges.sort();
+    edges
+}
+
+/// Wire shape of certificate evidence edges.
+///
+/// Certificates predate relation labels and their bytes are frozen by the
+/// test vectors, so evidence edges are encoded as `(from, to, weight)` only.
+/// Evidence is always built unlabeled; the relation a query was restricted to
+/// is recorded in its descriptor instead.
+mod evidence_edge_wire {
+    use crate::export::CanonicalEdge;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct WireEdge {
+        from: u64,
+        to: u64,
+        weight: i64,
+    }
+
+    pub(super) fn serialize<S: Serializer>(
+        edges: &[CanonicalEdge],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(edges.iter().map(|e| WireEdge {
+            from: e.from,
+            to: e.to,
+            weight: e.weight,
+        }))
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<CanonicalEdge>, D::Error> {
+        let wire = Vec::<WireEdge>::deserialize(deserializer)?;
+        Ok(wire
+            .into_iter()
+            .map(|e| CanonicalEdge {
+                from: e.from,
+                to: e.to,
+                relation: None,
+                weight: e.weight,
+            })
+            .collect())
+    }
 }
 
 /// Derive the BLAKE3 state hash from a graph's canonical export.
@@ -277,7 +421,8 @@ mod tests {
             let b = *ids
                 .entry(*to_e)
                 .or_insert_with(|| g.insert_node(EntityId(*to_e)).expect("insert"));
-            g.insert_edge(a, b, EdgeWeight::new(*w)).expect("edge");
+            g.insert_edge(a, b, None, EdgeWeight::new(*w))
+                .expect("edge");
         }
         g
     }
@@ -293,6 +438,48 @@ mod tests {
         assert!(back.is_proof_of_absence());
     }
 
+    #[test]
+    fn closure_proof_requires_closed_evidence() {
+        let g = graph_from(&[(1, 2, 10), (2, 1, 5), (3, 1, 1)]);
+        let closure = |path: Vec<u64>| {
+            let path: Vec<NodeId> = path.into_iter().map(NodeId).collect();
+            let edges = path
+                .iter()
+                .flat_map(|&n| {
+                    g.labeled_neighbors(n, None)
+                        .unwrap()
+                        .into_iter()
+                        .map(move |(to, label, w)| (n, to, label, w))
+                })
+                .collect();
+            Artifact::with_subgraph(path, edges)
+        };
+
+        let closed = QueryCertificate::new(
+            [0u8; 32],
+            "reachable:0:2",
+            "unknown",
+            &g,
+            &closure(vec![0, 1]),
+        );
+        assert!(closed.is_closure_proof());
+        assert!(!closed.is_proof_of_absence());
+
+        // Dropping a closure node leaves edges pointing outside the evidence.
+        let mut open = closed.clone();
+        open.evidence_nodes.retain(|n| n.id != 1);
+        assert!(!open.is_closure_proof());
+
+        let found = QueryCertificate::new(
+            [0u8; 32],
+            "reachable:0:1",
+            "inference",
+            &g,
+            &closure(vec![0, 1]),
+        );
+        assert!(!found.is_closure_proof());
+    }
+
     #[test]
     fn bad_magic_is_rejected() {
         let mut bytes =
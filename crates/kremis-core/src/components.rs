@@ -0,0 +1,312 @@
+//! # Components Module
+//!
+//! Deterministic connected-component analysis over any [`GraphStore`].
+//!
+//! - Weakly connected components ignore edge direction: two nodes share a
+//!   component when a chain of edges, followed either way, links them
+//! - Strongly connected components follow edges forward only: two nodes
+//!   share a component when each reaches the other
+//! - Components are numbered from 0 in ascending order of their smallest
+//!   node id, so the numbering depends only on the graph
+//! - A strongly connected component with more than one node is a cycle;
+//!   cycles are listed with their nodes in ascending order
+//!
+//! Both computations are iterative and linear in nodes plus edges. The
+//! `_within` variants spend a [`VisitBudget`] as they go and return nothing
+//! once it runs out, since a partial split would misnumber components.
+
+use crate::graph::{GraphStore, VisitBudget};
+use crate::{Attribute, KremisError, NodeId};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+/// The components a graph splits into.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Components {
+    /// Component id of every node, keyed by node id.
+    pub membership: BTreeMap<NodeId, usize>,
+    /// Number of nodes in each component, indexed by component id.
+    pub sizes: Vec<usize>,
+    /// The components with more than one node, each listed in ascending node
+    /// order, in ascending component id order. Only strongly connected
+    /// components fill this in; weak components carry no direction, so they
+    /// leave it empty.
+    pub cycles: Vec<Vec<NodeId>>,
+}
+
+impl Components {
+    /// Number the given groups canonically and collect their sizes.
+    fn from_groups(mut groups: Vec<Vec<NodeId>>, with_cycles: bool) -> Self {
+        for group in &mut groups {
+            group.sort_unstable();
+        }
+        groups.sort_unstable_by_key(|group| group.first().copied());
+
+        let mut components = Self::default();
+        for (id, group) in groups.into_iter().enumerate() {
+            components.sizes.push(group.len());
+            for &node in &group {
+                components.membership.insert(node, id);
+            }
+            if with_cycles && group.len() > 1 {
+                components.cycles.push(group);
+            }
+        }
+        components
+    }
+
+    /// Number of components.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.sizes.len()
+    }
+}
+
+/// Split the graph into weakly connected components, following only edges
+/// that match `relation` (`None`: all edges).
+///
+/// A node with no matching edge is a component of its own.
+pub fn weakly_connected_components<G: GraphStore + ?Sized>(
+    graph: &G,
+    relation: Option<&Attribute>,
+) -> Result<Components, KremisError> {
+    weakly_connected_components_within(graph, relation, &mut VisitBudget::unlimited())
+        .map(Option::unwrap_or_default)
+}
+
+/// [`weakly_connected_components`] under a visit budget; `Ok(None)` once
+/// `budget` runs out.
+pub fn weakly_connected_components_within<G: GraphStore + ?Sized>(
+    graph: &G,
+    relation: Option<&Attribute>,
+    budget: &mut VisitBudget,
+) -> Result<Option<Components>, KremisError> {
+    let mut seen: BTreeSet<NodeId> = BTreeSet::new();
+    let mut groups = Vec::new();
+
+    for root in graph.node_ids()? {
+        if !seen.insert(root) {
+            continue;
+        }
+        let mut group = vec![root];
+        let mut queue = VecDeque::from([root]);
+        while let Some(node) = queue.pop_front() {
+            let outgoing = graph.neighbors(node, relation)?;
+            let incoming = graph.predecessors(node, relation)?;
+            if !budget.spend(1 + (outgoing.len() + incoming.len()) as u64) {
+                return Ok(None);
+            }
+            for (next, _) in outgoing.into_iter().chain(incoming) {
+                if seen.insert(next) {
+                    group.push(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+        groups.push(group);
+    }
+
+    Ok(Some(Components::from_groups(groups, false)))
+}
+
+/// Split the graph into strongly connected components, following only edges
+/// that match `relation` (`None`: all edges).
+///
+/// Uses Tarjan's algorithm with an explicit stack, so deep chains do not
+/// grow the call stack.
+pub fn strongly_connected_components<G: GraphStore + ?Sized>(
+    graph: &G,
+    relation: Option<&Attribute>,
+) -> Result<Components, KremisError> {
+    strongly_connected_components_within(graph, relation, &mut VisitBudget::unlimited())
+        .map(Option::unwrap_or_default)
+}
+
+/// [`strongly_connected_components`] under a visit budget; `Ok(None)` once
+/// `budget` runs out.
+pub fn strongly_connected_components_within<G: GraphStore + ?Sized>(
+    graph: &G,
+    relation: Option<&Attribute>,
+    budget: &mut VisitBudget,
+) -> Result<Option<Components>, KremisError> {
+    let mut index: BTreeMap<NodeId, usize> = BTreeMap::new();
+    let mut low: BTreeMap<NodeId, usize> = BTreeMap::new();
+    let mut on_stack: BTreeSet<NodeId> = BTreeSet::new();
+    let mut stack: Vec<NodeId> = Vec::new();
+    let mut groups = Vec::new();
+
+    for root in graph.node_ids()? {
+        if index.contains_key(&root) {
+            continue;
+        }
+
+        // Each frame holds a node, its distinct successors and the position
+        // of the next successor to visit.
+        let mut frames: Vec<(NodeId, Vec<NodeId>, usize)> = Vec::new();
+        let mut next = Some(root);
+
+        loop {
+            if let Some(node) = next.take() {
+                let order = index.len();
+                index.insert(node, order);
+                low.insert(node, order);
+                stack.push(node);
+                on_stack.insert(node);
+                let successors: BTreeSet<NodeId> = graph
+                    .neighbors(node, relation)?
+                    .into_iter()
+                    .map(|(to, _)| to)
+                    .collect();
+                if !budget.spend(1 + successors.len() as u64) {
+                    return Ok(None);
+                }
+                frames.push((node, successors.into_iter().collect(), 0));
+            }
+
+            let Some((node, successors, position)) = frames.last_mut() else {
+                break;
+            };
+            let node = *node;
+
+            if let Some(&successor) = successors.get(*position) {
+                *position += 1;
+                match index.get(&successor) {
+                    None => next = Some(successor),
+                    Some(&order) if on_stack.contains(&successor) => {
+                        lower(&mut low, node, order);
+                    }
+                    Some(_) => {}
+                }
+                continue;
+            }
+
+            frames.pop();
+            let node_low = low.get(&node).copied().unwrap_or_default();
+            if let Some((parent, _, _)) = frames.last() {
+                lower(&mut low, *parent, node_low);
+            }
+            if index.get(&node) == Some(&node_low) {
+                let mut group = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack.remove(&member);
+                    group.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                groups.push(group);
+            }
+        }
+    }
+
+    Ok(Some(Components::from_groups(groups, true)))
+}
+
+/// Lower the low-link of `node` to `value` if that is smaller.
+fn lower(low: &mut BTreeMap<NodeId, usize>, node: NodeId, value: usize) {
+    if let Some(current) = low.get_mut(&node)
+        && value < *current
+    {
+        *current = value;
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Graph;
+    use crate::{EdgeWeight, EntityId};
+
+    fn graph_with(nodes: u64, edges: &[(u64, u64)]) -> Graph {
+        let mut graph = Graph::new();
+        for entity in 0..nodes {
+            graph.insert_node(EntityId(entity)).expect("insert");
+        }
+        for &(from, to) in edges {
+            graph
+                .insert_edge(NodeId(from), NodeId(to), None, EdgeWeight::new(1))
+                .expect("edge");
+        }
+        graph
+    }
+
+    fn ids(values: &[u64]) -> Vec<NodeId> {
+        values.iter().copied().map(NodeId).collect()
+    }
+
+    #[test]
+    fn weak_components_ignore_direction() {
+        // 0 -> 1 <- 2, 3 -> 4, 5 alone
+        let graph = graph_with(6, &[(0, 1), (2, 1), (3, 4)]);
+        let components = weakly_connected_components(&graph, None).expect("wcc");
+
+        assert_eq!(components.count(), 3);
+        assert_eq!(components.sizes, vec![3, 2, 1]);
+        assert_eq!(components.membership.get(&NodeId(2)), Some(&0));
+        assert_eq!(components.membership.get(&NodeId(4)), Some(&1));
+        assert_eq!(components.membership.get(&NodeId(5)), Some(&2));
+        assert!(components.cycles.is_empty());
+    }
+
+    #[test]
+    fn strong_components_report_cycles_in_canonical_order() {
+        // 4 -> 5 -> 4 and 0 -> 1 -> 2 -> 0, joined one way by 2 -> 4; 3 alone
+        let graph = graph_with(6, &[(4, 5), (5, 4), (1, 2), (2, 0), (0, 1), (2, 4), (3, 3)]);
+        let components = strongly_connected_components(&graph, None).expect("scc");
+
+        assert_eq!(components.sizes, vec![3, 1, 2]);
+        assert_eq!(components.cycles, vec![ids(&[0, 1, 2]), ids(&[4, 5])]);
+        assert_eq!(components.membership.get(&NodeId(3)), Some(&1));
+        assert_eq!(components.membership.get(&NodeId(5)), Some(&2));
+    }
+
+    #[test]
+    fn budgeted_components_give_up_when_the_budget_runs_out() {
+        let graph = graph_with(6, &[(0, 1), (2, 1), (3, 4)]);
+        let full = weakly_connected_components(&graph, None).expect("wcc");
+        let mut budget = VisitBudget::new(1_000);
+        assert_eq!(
+            weakly_connected_components_within(&graph, None, &mut budget).expect("wcc"),
+            Some(full)
+        );
+
+        let mut budget = VisitBudget::new(3);
+        assert_eq!(
+            strongly_connected_components_within(&graph, None, &mut budget).expect("scc"),
+            None
+        );
+        assert!(budget.is_exhausted());
+    }
+
+    #[test]
+    fn strong_components_follow_relation_filter() {
+        let mut graph = graph_with(2, &[(0, 1)]);
+        let depends_on = Attribute::new("depends_on");
+        graph
+            .insert_edge(NodeId(1), NodeId(0), Some(&depends_on), EdgeWeight::new(1))
+            .expect("edge");
+
+        let all = strongly_connected_components(&graph, None).expect("scc");
+        assert_eq!(all.cycles, vec![ids(&[0, 1])]);
+
+        let labeled = strongly_connected_components(&graph, Some(&depends_on)).expect("scc");
+        assert!(labeled.cycles.is_empty());
+        assert_eq!(labeled.sizes, vec![1, 1]);
+    }
+
+    #[test]
+    fn strong_components_handle_long_chains() {
+        // A single cycle through 10 000 nodes would overflow a recursive
+        // Tarjan on a small stack.
+        let n = 10_000;
+        let edges: Vec<(u64, u64)> = (0..n).map(|i| (i, (i + 1) % n)).collect();
+        let graph = graph_with(n, &edges);
+        let components = strongly_connected_components(&graph, None).expect("scc");
+
+        assert_eq!(components.sizes, vec![n as usize]);
+        assert_eq!(components.cycles.len(), 1);
+    }
+}
@@ -28,7 +28,7 @@ impl Compositor {
         start: NodeId,
         depth: usize,
     ) -> Result<Option<Artifact>, KremisError> {
-        graph.traverse(start, depth)
+        graph.traverse(start, depth, None)
     }
 
     /// Compose an artifact with weight filtering.
@@ -40,7 +40,7 @@ impl Compositor {
         depth: usize,
         min_weight: EdgeWeight,
     ) -> Result<Option<Artifact>, KremisError> {
-        graph.traverse_filtered(start, depth, min_weight)
+        graph.traverse_filtered(start, depth, min_weight, None)
     }
 
     /// Extract a path between two nodes.
@@ -51,7 +51,7 @@ impl Compositor {
         start: NodeId,
         end: NodeId,
     ) -> Result<Option<Artifact>, KremisError> {
-        let path = match graph.strongest_path(start, end)? {
+        let path = match graph.strongest_path(start, end, None)? {
             Some(p) => p,
             None => return Ok(None),
         };
@@ -61,8 +61,8 @@ impl Compositor {
         for window in path.windows(2) {
             let from = window[0];
             let to = window[1];
-            if let Some(weight) = graph.get_edge(from, to)? {
-                subgraph.push((from, to, weight));
+            if let Some((label, weight)) = graph.strongest_labeled_edge(from, to, None)? {
+                subgraph.push((from, to, label, weight));
             }
         }
 
@@ -86,7 +86,7 @@ impl Compositor {
         start: NodeId,
         depth: usize,
     ) -> Result<Option<Artifact>, KremisError> {
-        graph.traverse(start, depth)
+        graph.traverse(start, depth, None)
     }
 }
 
@@ -125,10 +125,10 @@ mod tests {
         let c = graph.insert_node(EntityId(3)).expect("insert");
 
         graph
-            .insert_edge(a, b, EdgeWeight::new(10))
+            .insert_edge(a, b, None, EdgeWeight::new(10))
             .expect("insert");
         graph
-            .insert_edge(b, c, EdgeWeight::new(10))
+            .insert_edge(b, c, None, EdgeWeight::new(10))
             .expect("insert");
 
         let artifact = Compositor::extract_path(&graph, a, c).expect("extract");
@@ -146,10 +146,10 @@ mod tests {
         let common = graph.insert_node(EntityId(100)).expect("insert");
 
         graph
-            .insert_edge(a, common, EdgeWeight::new(1))
+            .insert_edge(a, common, None, EdgeWeight::new(1))
             .expect("insert");
         graph
-            .insert_edge(b, common, EdgeWeight::new(1))
+            .insert_edge(b, common, None, EdgeWeight::new(1))
             .expect("insert");
 
         let artifact = Compositor::find_intersection(&graph, &[a, b]).expect("intersect");
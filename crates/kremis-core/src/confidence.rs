@@ -117,7 +117,7 @@ pub fn compute_path_confidence(path: &[crate::NodeId], graph: &Graph) -> Confide
     let mut edge_count: usize = 0;
 
     for window in path.windows(2) {
-        if let Some(weight) = graph.get_edge_internal(window[0], window[1]) {
+        if let Some(weight) = graph.strongest_edge_internal(window[0], window[1]) {
             total_weight = total_weight.saturating_add(weight.value());
             edge_count = edge_count.saturating_add(1);
         }
@@ -245,7 +245,7 @@ mod tests {
         let n1 = graph.insert_node(EntityId(1)).expect("insert");
         let n2 = graph.insert_node(EntityId(2)).expect("insert");
         graph
-            .insert_edge(n1, n2, EdgeWeight::new(5))
+            .insert_edge(n1, n2, None, EdgeWeight::new(5))
             .expect("insert");
 
         let path = vec![n1, n2];
@@ -270,7 +270,7 @@ mod tests {
         // Create edges between consecutive nodes with weight 5
         for i in 0..9 {
             graph
-                .insert_edge(nodes[i], nodes[i + 1], EdgeWeight::new(5))
+                .insert_edge(nodes[i], nodes[i + 1], None, EdgeWeight::new(5))
                 .expect("insert");
         }
 
@@ -293,10 +293,10 @@ mod tests {
 
         // High weight edges (10 is max for scoring)
         graph
-            .insert_edge(n1, n2, EdgeWeight::new(10))
+            .insert_edge(n1, n2, None, EdgeWeight::new(10))
             .expect("insert");
         graph
-            .insert_edge(n2, n3, EdgeWeight::new(10))
+            .insert_edge(n2, n3, None, EdgeWeight::new(10))
             .expect("insert");
 
         let path = vec![n1, n2, n3];
@@ -319,10 +319,10 @@ mod tests {
 
         // Low weight edges
         graph
-            .insert_edge(n1, n2, EdgeWeight::new(1))
+            .insert_edge(n1, n2, None, EdgeWeight::new(1))
             .expect("insert");
         graph
-            .insert_edge(n2, n3, EdgeWeight::new(1))
+            .insert_edge(n2, n3, None, EdgeWeight::new(1))
             .expect("insert");
 
         let path = vec![n1, n2, n3];
@@ -346,13 +346,13 @@ mod tests {
 
         // Mixed weights: 2, 8, 5 -> average = 5
         graph
-            .insert_edge(n1, n2, EdgeWeight::new(2))
+            .insert_edge(n1, n2, None, EdgeWeight::new(2))
             .expect("insert");
         graph
-            .insert_edge(n2, n3, EdgeWeight::new(8))
+            .insert_edge(n2, n3, None, EdgeWeight::new(8))
             .expect("insert");
         graph
-            .insert_edge(n3, n4, EdgeWeight::new(5))
+            .insert_edge(n3, n4, None, EdgeWeight::new(5))
             .expect("insert");
 
         let path = vec![n1, n2, n3, n4];
@@ -376,7 +376,7 @@ mod tests {
 
         // Very high weight (above 10, should be capped)
         graph
-            .insert_edge(n1, n2, EdgeWeight::new(1000))
+            .insert_edge(n1, n2, None, EdgeWeight::new(1000))
             .expect("insert");
 
         let path = vec![n1, n2];
@@ -397,7 +397,7 @@ mod tests {
 
         // Negative weight
         graph
-            .insert_edge(n1, n2, EdgeWeight::new(-5))
+            .insert_edge(n1, n2, None, EdgeWeight::new(-5))
             .expect("insert");
 
         let path = vec![n1, n2];
@@ -418,7 +418,7 @@ mod tests {
 
         // Zero weight
         graph
-            .insert_edge(n1, n2, EdgeWeight::new(0))
+            .insert_edge(n1, n2, None, EdgeWeight::new(0))
             .expect("insert");
 
         let path = vec![n1, n2];
@@ -441,10 +441,10 @@ mod tests {
 
         // Only some edges exist: n1->n2 and n3->n4, but NOT n2->n3
         graph
-            .insert_edge(n1, n2, EdgeWeight::new(10))
+            .insert_edge(n1, n2, None, EdgeWeight::new(10))
             .expect("insert");
         graph
-            .insert_edge(n3, n4, EdgeWeight::new(10))
+            .insert_edge(n3, n4, None, EdgeWeight::new(10))
             .expect("insert");
 
         let path = vec![n1, n2, n3, n4];
@@ -466,10 +466,10 @@ mod tests {
         let n3 = graph.insert_node(EntityId(3)).expect("insert");
 
         graph
-            .insert_edge(n1, n2, EdgeWeight::new(7))
+            .insert_edge(n1, n2, None, EdgeWeight::new(7))
             .expect("insert");
         graph
-            .insert_edge(n2, n3, EdgeWeight::new(3))
+            .insert_edge(n2, n3, None, EdgeWeight::new(3))
             .expect("insert");
 
         let path = vec![n1, n2, n3];
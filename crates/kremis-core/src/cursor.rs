@@ -0,0 +1,135 @@
+//! # Cursor Module
+//!
+//! Opaque page tokens for results too large to return at once.
+//!
+//! A [`Cursor`] names a position in a result at one graph state: the BLAKE3
+//! hash of the canonical export (`certificate::state_hash`) and
+//! the offset of the next item. The token is the lowercase hex of the hash
+//! followed by the big-endian offset, so the same position at the same state
+//! always encodes to the same string.
+//!
+//! A cursor carries no result data: the next page is recomputed from the
+//! query. Resuming checks the state hash first, so a page is never cut from
+//! a graph that changed since the cursor was issued.
+
+use crate::KremisError;
+
+/// Number of items in a page when the client does not choose one.
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Length of an encoded cursor: 32 hash bytes and 8 offset bytes, in hex.
+const ENCODED_LEN: usize = 2 * (32 + 8);
+
+/// A position in a paged result, bound to the graph state it was issued at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    /// State hash of the graph the result was computed from.
+    pub state_hash: [u8; 32],
+    /// Index of the first item of the next page.
+    pub offset: u64,
+}
+
+impl Cursor {
+    /// Create a cursor at `offset` for the state hashed as `state_hash`.
+    #[must_use]
+    pub fn new(state_hash: [u8; 32], offset: u64) -> Self {
+        Self { state_hash, offset }
+    }
+
+    /// Encode the cursor as an opaque token.
+    #[must_use]
+    pub fn encode(&self) -> String {
+        self.state_hash
+            .iter()
+            .chain(self.offset.to_be_bytes().iter())
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+
+    /// Decode a token produced by [`Cursor::encode`].
+    ///
+    /// Only the canonical encoding is accepted: anything else, including
+    /// uppercase hex, returns `KremisError::InvalidSignal`.
+    pub fn decode(token: &str) -> Result<Self, KremisError> {
+        if token.len() != ENCODED_LEN {
+            return Err(KremisError::InvalidSignal);
+        }
+        let mut bytes = [0u8; ENCODED_LEN / 2];
+        for (byte, pair) in bytes.iter_mut().zip(token.as_bytes().chunks_exact(2)) {
+            *byte = (hex_digit(pair[0])? << 4) | hex_digit(pair[1])?;
+        }
+        let mut state_hash = [0u8; 32];
+        state_hash.copy_from_slice(&bytes[..32]);
+        let mut offset = [0u8; 8];
+        offset.copy_from_slice(&bytes[32..]);
+        Ok(Self::new(state_hash, u64::from_be_bytes(offset)))
+    }
+
+    /// The offset to resume from, if the cursor was issued at `state_hash`.
+    ///
+    /// Returns `KremisError::StaleCursor` when the graph has changed since.
+    pub fn resume(&self, state_hash: &[u8; 32]) -> Result<u64, KremisError> {
+        if &self.state_hash == state_hash {
+            Ok(self.offset)
+        } else {
+            Err(KremisError::StaleCursor)
+        }
+    }
+}
+
+/// Value of one lowercase hex digit.
+fn hex_digit(c: u8) -> Result<u8, KremisError> {
+    match c {
+        b'0'..=b'9' => Ok(c - b'0'),
+        b'a'..=b'f' => Ok(c - b'a' + 10),
+        _ => Err(KremisError::InvalidSignal),
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_round_trips_through_its_token() {
+        let cursor = Cursor::new([0xab; 32], 258);
+        let token = cursor.encode();
+        assert_eq!(token.len(), ENCODED_LEN);
+        assert!(token.ends_with("0000000000000102"));
+        assert_eq!(Cursor::decode(&token).unwrap(), cursor);
+        // Same position, same state: same token.
+        assert_eq!(Cursor::new([0xab; 32], 258).encode(), token);
+    }
+
+    #[test]
+    fn malformed_tokens_are_rejected() {
+        let token = Cursor::new([0xab; 32], 5).encode();
+        for bad in [
+            String::new(),
+            token[1..].to_string(),
+            format!("{token}0"),
+            token.to_uppercase(),
+            token.replacen('0', "g", 1),
+        ] {
+            assert!(matches!(
+                Cursor::decode(&bad),
+                Err(KremisError::InvalidSignal)
+            ));
+        }
+    }
+
+    #[test]
+    fn resume_rejects_a_different_state() {
+        let cursor = Cursor::new([1; 32], 40);
+        assert_eq!(cursor.resume(&[1; 32]).unwrap(), 40);
+        assert!(matches!(
+            cursor.resume(&[2; 32]),
+            Err(KremisError::StaleCursor)
+        ));
+    }
+}
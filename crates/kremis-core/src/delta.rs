@@ -0,0 +1,552 @@
+//! # Delta Module
+//!
+//! Incremental exports: the changes from one graph state to another.
+//!
+//! A [`GraphDelta`] names its base and target states by their BLAKE3 state
+//! hashes and carries the [`GraphDiff`] between them, with the node id
+//! counter and entity key changes the diff leaves out. Applying it to the
+//! base rebuilds the target exactly, which the receiver checks by hashing
+//! the result.
+//!
+//! [`delta_between`] diffs two graphs in memory; [`stream_delta`] merges a
+//! base export with any [`CanonicalSource`] item by item, for states too
+//! large to load.
+//!
+//! ## Format
+//!
+//! A delta file follows the layout of a canonical export under its own magic:
+//!
+//! ```text
+//! [header_len: u32 LE] [DeltaHeader (postcard)] [GraphDelta changes (postcard)]
+//! ```
+
+use crate::KremisError;
+use crate::diff::GraphDiff;
+use crate::export::{CanonicalEntityKey, MAX_IMPORT_BYTES};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "crypto-hash")]
+use crate::EdgeWeight;
+#[cfg(feature = "crypto-hash")]
+use crate::diff::EdgeReweight;
+#[cfg(feature = "crypto-hash")]
+use crate::diff::diff;
+#[cfg(feature = "crypto-hash")]
+use crate::export::{CanonicalEdge, CanonicalGraph};
+#[cfg(feature = "crypto-hash")]
+use crate::graph::Graph;
+#[cfg(feature = "crypto-hash")]
+use crate::stream::{
+    CanonicalItem, CanonicalReader, CanonicalSource, ExportReader, ReadLimits, stream_state_hash,
+};
+#[cfg(feature = "crypto-hash")]
+use std::cmp::Ordering;
+#[cfg(feature = "crypto-hash")]
+use std::collections::{BTreeMap, BTreeSet};
+#[cfg(feature = "crypto-hash")]
+use std::io::Read;
+
+/// Magic bytes for delta files.
+pub const DELTA_MAGIC: [u8; 4] = *b"KRXD"; // Kremis Export Delta
+
+/// Current delta format version.
+pub const DELTA_VERSION: u8 = 1;
+
+/// Header for delta files.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DeltaHeader {
+    /// Magic bytes to identify the format.
+    pub magic: [u8; 4],
+
+    /// Format version for compatibility.
+    pub version: u8,
+
+    /// State hash of the graph the delta applies to.
+    pub base_hash: [u8; 32],
+
+    /// State hash of the graph the delta produces.
+    pub target_hash: [u8; 32],
+}
+
+/// The changes from a base state to a target state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphDelta {
+    /// State hash of the graph the delta applies to.
+    pub base_hash: [u8; 32],
+    /// State hash of the graph the delta produces.
+    pub target_hash: [u8; 32],
+    /// Node, edge and property changes.
+    pub changes: GraphDiff,
+    /// Next node id of the target.
+    pub next_node_id: u64,
+    /// Entity key bindings only in the target, sorted by key.
+    pub entity_keys_added: Vec<CanonicalEntityKey>,
+    /// Entity key bindings only in the base, sorted by key.
+    pub entity_keys_removed: Vec<CanonicalEntityKey>,
+}
+
+/// The part of a delta file after the header.
+#[derive(Serialize, Deserialize)]
+struct DeltaPayload {
+    changes: GraphDiff,
+    next_node_id: u64,
+    entity_keys_added: Vec<CanonicalEntityKey>,
+    entity_keys_removed: Vec<CanonicalEntityKey>,
+}
+
+impl GraphDelta {
+    /// Serialize the delta to the delta file format.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KremisError::SerializationError` if serialization fails.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, KremisError> {
+        let header = DeltaHeader {
+            magic: DELTA_MAGIC,
+            version: DELTA_VERSION,
+            base_hash: self.base_hash,
+            target_hash: self.target_hash,
+        };
+        let header_bytes = postcard::to_allocvec(&header)
+            .map_err(|e| KremisError::SerializationError(format!("Header: {}", e)))?;
+        let data_bytes = postcard::to_allocvec(&DeltaPayload {
+            changes: self.changes.clone(),
+            next_node_id: self.next_node_id,
+            entity_keys_added: self.entity_keys_added.clone(),
+            entity_keys_removed: self.entity_keys_removed.clone(),
+        })
+        .map_err(|e| KremisError::SerializationError(format!("Data: {}", e)))?;
+
+        let mut result = Vec::with_capacity(4 + header_bytes.len() + data_bytes.len());
+        result.extend_from_slice(&(header_bytes.len() as u32).to_le_bytes());
+        result.extend_from_slice(&header_bytes);
+        result.extend_from_slice(&data_bytes);
+        Ok(result)
+    }
+
+    /// Read a delta written by [`GraphDelta::to_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `KremisError::SerializationError` if the data is not a delta
+    /// file of a supported version, or is larger than `MAX_IMPORT_BYTES`.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, KremisError> {
+        if data.len() < 4 {
+            return Err(KremisError::SerializationError(
+                "Data too short".to_string(),
+            ));
+        }
+        let header_len = u32::from_le_bytes([data[0], data[1], data[2], data[3]]) as usize;
+        if data.len() < 4 + header_len {
+            return Err(KremisError::SerializationError(
+                "Data too short for header".to_string(),
+            ));
+        }
+        let header: DeltaHeader = postcard::from_bytes(&data[4..4 + header_len])
+            .map_err(|e| KremisError::SerializationError(format!("Header: {}", e)))?;
+        if header.magic != DELTA_MAGIC {
+            return Err(KremisError::SerializationError(
+                "Invalid file format".to_string(),
+            ));
+        }
+        if header.version == 0 || header.version > DELTA_VERSION {
+            return Err(KremisError::SerializationError(
+                "Unsupported file version".to_string(),
+            ));
+        }
+
+        let payload = &data[4 + header_len..];
+        if payload.len() > MAX_IMPORT_BYTES {
+            return Err(KremisError::SerializationError(format!(
+                "Payload size {} exceeds maximum allowed {}",
+                payload.len(),
+                MAX_IMPORT_BYTES
+            )));
+        }
+        let payload: DeltaPayload = postcard::from_bytes(payload)
+            .map_err(|e| KremisError::SerializationError(format!("Data: {}", e)))?;
+
+        Ok(Self {
+            base_hash: header.base_hash,
+            target_hash: header.target_hash,
+            changes: payload.changes,
+            next_node_id: payload.next_node_id,
+            entity_keys_added: payload.entity_keys_added,
+            entity_keys_removed: payload.entity_keys_removed,
+        })
+    }
+}
+
+/// Compute the delta that turns `base` into `target`.
+///
+/// # Errors
+///
+/// Returns `KremisError::SerializationError` if either canonical export fails.
+///
+/// # Requires
+///
+/// Only available with the `crypto-hash` feature.
+#[cfg(feature = "crypto-hash")]
+pub fn delta_between(base: &Graph, target: &Graph) -> Result<GraphDelta, KremisError> {
+    use crate::certificate::state_hash;
+
+    let before = CanonicalGraph::from_graph(base);
+    let after = CanonicalGraph::from_graph(target);
+    let old_keys: BTreeSet<&CanonicalEntityKey> = before.entity_keys.iter().collect();
+    let new_keys: BTreeSet<&CanonicalEntityKey> = after.entity_keys.iter().collect();
+
+    Ok(GraphDelta {
+        base_hash: state_hash(base)?,
+        target_hash: state_hash(target)?,
+        changes: diff(&before, &after),
+        next_node_id: after.next_node_id,
+        entity_keys_added: new_keys.difference(&old_keys).map(|&k| k.clone()).collect(),
+        entity_keys_removed: old_keys.difference(&new_keys).map(|&k| k.clone()).collect(),
+    })
+}
+
+/// Compute the delta that turns the canonical export read by `open_base`
+/// into `target`, without loading either state.
+///
+/// `open_base` is called once per pass over the export and must return the
+/// same bytes each time; bytes after the export are ignored. The two states
+/// are merged item by item in canonical order, so memory holds the delta
+/// and one item of each side, whatever the size of the export.
+///
+/// # Errors
+///
+/// Returns `KremisError::SerializationError` if the base is not a valid
+/// canonical export in canonical order, and any error `open_base` or
+/// `target` returns.
+///
+/// # Requires
+///
+/// Only available with the `crypto-hash` feature.
+#[cfg(feature = "crypto-hash")]
+pub fn stream_delta<R: Read>(
+    open_base: impl Fn() -> Result<R, KremisError>,
+    target: &(impl CanonicalSource + ?Sized),
+) -> Result<GraphDelta, KremisError> {
+    let base_hash = stream_state_hash(&ExportReader(&open_base))?;
+    let target_hash = stream_state_hash(target)?;
+
+    let mut merge = Merge {
+        base: CanonicalReader::new(open_base()?, ReadLimits::Streaming)?,
+        pending: None,
+        changes: GraphDiff::default(),
+        entity_keys_added: Vec::new(),
+        entity_keys_removed: Vec::new(),
+    };
+    merge.advance()?;
+    let next_node_id = target.visit_canonical(&mut |item| merge.target(item))?;
+    while let Some(item) = merge.advance()? {
+        merge.removed(item);
+    }
+    merge.base.finish()?;
+
+    Ok(GraphDelta {
+        base_hash,
+        target_hash,
+        changes: merge.changes,
+        next_node_id,
+        entity_keys_added: merge.entity_keys_added,
+        entity_keys_removed: merge.entity_keys_removed,
+    })
+}
+
+/// The state of [`stream_delta`]: a base reader one item ahead, and the
+/// changes found so far.
+#[cfg(feature = "crypto-hash")]
+struct Merge<R> {
+    base: CanonicalReader<R>,
+    pending: Option<CanonicalItem>,
+    changes: GraphDiff,
+    entity_keys_added: Vec<CanonicalEntityKey>,
+    entity_keys_removed: Vec<CanonicalEntityKey>,
+}
+
+#[cfg(feature = "crypto-hash")]
+impl<R: Read> Merge<R> {
+    /// Take the pending base item and read the next one.
+    fn advance(&mut self) -> Result<Option<CanonicalItem>, KremisError> {
+        let next = self.base.next_item()?;
+        if let (Some(current), Some(next)) = (&self.pending, &next)
+            && canonical_cmp(current, next) != Ordering::Less
+        {
+            return Err(KremisError::SerializationError(
+                "Base export is not in canonical order".to_string(),
+            ));
+        }
+        Ok(std::mem::replace(&mut self.pending, next))
+    }
+
+    /// Match the next target item against the base.
+    fn target(&mut self, item: CanonicalItem) -> Result<(), KremisError> {
+        loop {
+            match self.pending.as_ref().map(|base| canonical_cmp(base, &item)) {
+                Some(Ordering::Less) => {
+                    if let Some(base) = self.advance()? {
+                        self.removed(base);
+                    }
+                }
+                Some(Ordering::Equal) => {
+                    if let Some(base) = self.advance()? {
+                        self.changed(base, item);
+                    }
+                    return Ok(());
+                }
+                Some(Ordering::Greater) | None => {
+                    self.added(item);
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// An item under the same key on both sides.
+    fn changed(&mut self, base: CanonicalItem, target: CanonicalItem) {
+        match (base, target) {
+            (CanonicalItem::Edge(old), CanonicalItem::Edge(new)) if old.weight != new.weight => {
+                self.changes.edges_reweighted.push(EdgeReweight {
+                    from: new.from,
+                    to: new.to,
+                    relation: new.relation,
+                    old_weight: EdgeWeight::new(old.weight),
+                    new_weight: EdgeWeight::new(new.weight),
+                });
+            }
+            (base, target) if base != target => {
+                self.removed(base);
+                self.added(target);
+            }
+            _ => {}
+        }
+    }
+
+    fn added(&mut self, item: CanonicalItem) {
+        match item {
+            CanonicalItem::Node(node) => self.changes.nodes_added.push(node),
+            CanonicalItem::Edge(edge) => self.changes.edges_added.push(edge),
+            CanonicalItem::Property(prop) => self.changes.properties_added.push(prop),
+            CanonicalItem::EntityKey(key) => self.entity_keys_added.push(key),
+        }
+    }
+
+    fn removed(&mut self, item: CanonicalItem) {
+        match item {
+            CanonicalItem::Node(node) => self.changes.nodes_removed.push(node),
+            CanonicalItem::Edge(edge) => self.changes.edges_removed.push(edge),
+            CanonicalItem::Property(prop) => self.changes.properties_removed.push(prop),
+            CanonicalItem::EntityKey(key) => self.entity_keys_removed.push(key),
+        }
+    }
+}
+
+/// Canonical export order, comparing only what identifies an item: a node's
+/// id, an edge's `(from, to, relation)` and an entity key's key.
+#[cfg(feature = "crypto-hash")]
+fn canonical_cmp(a: &CanonicalItem, b: &CanonicalItem) -> Ordering {
+    fn section(item: &CanonicalItem) -> u8 {
+        match item {
+            CanonicalItem::Node(_) => 0,
+            CanonicalItem::Edge(_) => 1,
+            CanonicalItem::Property(_) => 2,
+            CanonicalItem::EntityKey(_) => 3,
+        }
+    }
+    match (a, b) {
+        (CanonicalItem::Node(a), CanonicalItem::Node(b)) => a.id.cmp(&b.id),
+        (CanonicalItem::Edge(a), CanonicalItem::Edge(b)) => {
+            (a.from, a.to, &a.relation).cmp(&(b.from, b.to, &b.relation))
+        }
+        (CanonicalItem::Property(a), CanonicalItem::Property(b)) => a.cmp(b),
+        (CanonicalItem::EntityKey(a), CanonicalItem::EntityKey(b)) => a.key.cmp(&b.key),
+        _ => section(a).cmp(&section(b)),
+    }
+}
+
+/// Apply `delta` to `base` and verify the result.
+///
+/// # Errors
+///
+/// Returns `KremisError::SerializationError` if `base` is not the state the
+/// delta was computed from, or if the result does not hash to the delta's
+/// target hash. No graph is returned in either case.
+///
+/// # Requires
+///
+/// Only available with the `crypto-hash` feature.
+#[cfg(feature = "crypto-hash")]
+pub fn apply_delta(base: &Graph, delta: &GraphDelta) -> Result<Graph, KremisError> {
+    use crate::certificate::state_hash;
+
+    if state_hash(base)? != delta.base_hash {
+        return Err(KremisError::SerializationError(
+            "Delta does not apply: base state hash mismatch".to_string(),
+        ));
+    }
+
+    let canonical = CanonicalGraph::from_graph(base);
+    let changes = &delta.changes;
+
+    let mut nodes: BTreeSet<_> = canonical.nodes.into_iter().collect();
+    for node in &changes.nodes_removed {
+        nodes.remove(node);
+    }
+    nodes.extend(changes.nodes_added.iter().cloned());
+
+    let mut edges: BTreeMap<(u64, u64, Option<String>), i64> = canonical
+        .edges
+        .into_iter()
+        .map(|e| ((e.from, e.to, e.relation), e.weight))
+        .collect();
+    for edge in &changes.edges_removed {
+        edges.remove(&(edge.from, edge.to, edge.relation.clone()));
+    }
+    for edge in &changes.edges_added {
+        edges.insert((edge.from, edge.to, edge.relation.clone()), edge.weight);
+    }
+    for edge in &changes.edges_reweighted {
+        edges.insert(
+            (edge.from, edge.to, edge.relation.clone()),
+            edge.new_weight.value(),
+        );
+    }
+
+    let mut properties: BTreeSet<_> = canonical.properties.into_iter().collect();
+    for property in &changes.properties_removed {
+        properties.remove(property);
+    }
+    properties.extend(changes.properties_added.iter().cloned());
+
+    let mut entity_keys: BTreeSet<_> = canonical.entity_keys.into_iter().collect();
+    for key in &delta.entity_keys_removed {
+        entity_keys.remove(key);
+    }
+    entity_keys.extend(delta.entity_keys_added.iter().cloned());
+
+    let target = CanonicalGraph {
+        nodes: nodes.into_iter().collect(),
+        edges: edges
+            .into_iter()
+            .map(|((from, to, relation), weight)| CanonicalEdge {
+                from,
+                to,
+                relation,
+                weight,
+            })
+            .collect(),
+        next_node_id: delta.next_node_id,
+        properties: properties.into_iter().collect(),
+        entity_keys: entity_keys.into_iter().collect(),
+    }
+    .to_graph();
+
+    if state_hash(&target)? != delta.target_hash {
+        return Err(KremisError::SerializationError(
+            "Delta does not apply: target state hash mismatch".to_string(),
+        ));
+    }
+    Ok(target)
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(all(test, feature = "crypto-hash"))]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::graph::GraphStore;
+    use crate::{Attribute, EdgeWeight, EntityId, Value};
+
+    fn states() -> (Graph, Graph) {
+        let mut base = Graph::new();
+        let a = base.insert_node(EntityId(1)).unwrap();
+        let b = base.insert_node(EntityId(2)).unwrap();
+        base.insert_edge(a, b, None, EdgeWeight::new(1)).unwrap();
+        base.store_property(b, Attribute::new("name"), Value::new("Bob"))
+            .unwrap();
+
+        let mut target = base.clone();
+        target.insert_edge(a, b, None, EdgeWeight::new(2)).unwrap();
+        target.remove_node(b).unwrap();
+        let c = target.insert_node(EntityId(3)).unwrap();
+        target.insert_edge(c, a, None, EdgeWeight::new(1)).unwrap();
+        target.register_entity_key("svc:ledger").unwrap();
+        (base, target)
+    }
+
+    #[test]
+    fn delta_round_trips_and_rebuilds_the_target() {
+        let (base, target) = states();
+        let delta = delta_between(&base, &target).unwrap();
+        let read = GraphDelta::from_bytes(&delta.to_bytes().unwrap()).unwrap();
+        assert_eq!(read, delta);
+
+        let rebuilt = apply_delta(&base, &read).unwrap();
+        assert_eq!(
+            CanonicalGraph::from_graph(&rebuilt),
+            CanonicalGraph::from_graph(&target)
+        );
+    }
+
+    #[test]
+    fn delta_is_rejected_on_the_wrong_base_or_a_tampered_payload() {
+        let (base, target) = states();
+        let delta = delta_between(&base, &target).unwrap();
+        assert!(matches!(
+            apply_delta(&target, &delta),
+            Err(KremisError::SerializationError(_))
+        ));
+
+        let mut tampered = delta;
+        tampered.next_node_id += 1;
+        assert!(matches!(
+            apply_delta(&base, &tampered),
+            Err(KremisError::SerializationError(_))
+        ));
+    }
+
+    #[test]
+    fn streamed_delta_matches_the_in_memory_delta() {
+        let (base, target) = states();
+        let data = crate::export::export_canonical(&base).unwrap();
+        let streamed = stream_delta(|| Ok(data.as_slice()), &target).unwrap();
+        assert_eq!(streamed, delta_between(&base, &target).unwrap());
+
+        // A node rebound to another entity is removed and added.
+        let mut rebound = CanonicalGraph::from_graph(&target);
+        rebound.nodes[0].entity = 99;
+        let expected = delta_between(&base, &rebound.to_graph()).unwrap();
+        assert_eq!(
+            stream_delta(|| Ok(data.as_slice()), &rebound).unwrap(),
+            expected
+        );
+
+        let reversed = stream_delta(|| Ok(data.as_slice()), &base).unwrap();
+        assert!(reversed.changes.is_empty());
+        assert_eq!(reversed.base_hash, reversed.target_hash);
+    }
+
+    #[test]
+    fn streamed_delta_rejects_a_base_out_of_canonical_order() {
+        let (base, target) = states();
+        let mut canonical = CanonicalGraph::from_graph(&base);
+        canonical.nodes.reverse();
+        let mut data = Vec::new();
+        crate::stream::write_canonical(&canonical, &mut data).unwrap();
+        assert!(matches!(
+            stream_delta(|| Ok(data.as_slice()), &target),
+            Err(KremisError::SerializationError(_))
+        ));
+    }
+
+    #[test]
+    fn canonical_exports_are_not_deltas() {
+        let (base, _) = states();
+        let data = crate::export::export_canonical(&base).unwrap();
+        assert!(GraphDelta::from_bytes(&data).is_err());
+    }
+}
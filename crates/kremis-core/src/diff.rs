@@ -0,0 +1,229 @@
+//! # Diff Module
+//!
+//! Deterministic structural diff between two [`CanonicalGraph`]s.
+//!
+//! - A node is identified by its id and entity: a node id rebound to another
+//!   entity is listed as removed and added
+//! - An edge is identified by `(from, to, relation)`; the same edge with a
+//!   different weight is reweighted, not removed and added
+//! - A property is identified by its whole `(node_id, attribute, value)`
+//!
+//! Every list is in canonical order, so the same two states always produce
+//! the same diff.
+
+use crate::EdgeWeight;
+use crate::export::{CanonicalEdge, CanonicalGraph, CanonicalNode, CanonicalProperty};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// An edge present in both states with a different weight.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct EdgeReweight {
+    /// Source node ID.
+    pub from: u64,
+    /// Target node ID.
+    pub to: u64,
+    /// Relation label, `None` for an unlabeled association edge.
+    pub relation: Option<String>,
+    /// Weight in the first state.
+    pub old_weight: EdgeWeight,
+    /// Weight in the second state.
+    pub new_weight: EdgeWeight,
+}
+
+/// What changed from one graph state to another.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphDiff {
+    /// Nodes only in the second state, sorted by id.
+    pub nodes_added: Vec<CanonicalNode>,
+    /// Nodes only in the first state, sorted by id.
+    pub nodes_removed: Vec<CanonicalNode>,
+    /// Edges only in the second state, sorted by `(from, to, relation)`.
+    pub edges_added: Vec<CanonicalEdge>,
+    /// Edges only in the first state, sorted by `(from, to, relation)`.
+    pub edges_removed: Vec<CanonicalEdge>,
+    /// Edges in both states whose weight changed, sorted by
+    /// `(from, to, relation)`.
+    pub edges_reweighted: Vec<EdgeReweight>,
+    /// Properties only in the second state, sorted by
+    /// `(node_id, attribute, value)`.
+    pub properties_added: Vec<CanonicalProperty>,
+    /// Properties only in the first state, sorted by
+    /// `(node_id, attribute, value)`.
+    pub properties_removed: Vec<CanonicalProperty>,
+}
+
+impl GraphDiff {
+    /// Whether the two states hold the same nodes, edges and properties.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.nodes_added.is_empty()
+            && self.nodes_removed.is_empty()
+            && self.edges_added.is_empty()
+            && self.edges_removed.is_empty()
+            && self.edges_reweighted.is_empty()
+            && self.properties_added.is_empty()
+            && self.properties_removed.is_empty()
+    }
+}
+
+/// Compute the structural diff from `before` to `after`.
+///
+/// Entity key bindings and the node id counter are not compared.
+#[must_use]
+pub fn diff(before: &CanonicalGraph, after: &CanonicalGraph) -> GraphDiff {
+    let (nodes_added, nodes_removed) = set_difference(&before.nodes, &after.nodes);
+    let (properties_added, properties_removed) =
+        set_difference(&before.properties, &after.properties);
+
+    let edge_map = |graph: &CanonicalGraph| -> BTreeMap<(u64, u64, Option<String>), i64> {
+        graph
+            .edges
+            .iter()
+            .map(|e| ((e.from, e.to, e.relation.clone()), e.weight))
+            .collect()
+    };
+    let old_edges = edge_map(before);
+    let new_edges = edge_map(after);
+
+    let mut diff = GraphDiff {
+        nodes_added,
+        nodes_removed,
+        properties_added,
+        properties_removed,
+        ..GraphDiff::default()
+    };
+    for ((from, to, relation), &weight) in &new_edges {
+        let key = (*from, *to, relation.clone());
+        match old_edges.get(&key) {
+            None => diff.edges_added.push(CanonicalEdge {
+                from: *from,
+                to: *to,
+                relation: relation.clone(),
+                weight,
+            }),
+            Some(&old) if old != weight => diff.edges_reweighted.push(EdgeReweight {
+                from: *from,
+                to: *to,
+                relation: relation.clone(),
+                old_weight: EdgeWeight::new(old),
+                new_weight: EdgeWeight::new(weight),
+            }),
+            Some(_) => {}
+        }
+    }
+    for ((from, to, relation), &weight) in &old_edges {
+        if !new_edges.contains_key(&(*from, *to, relation.clone())) {
+            diff.edges_removed.push(CanonicalEdge {
+                from: *from,
+                to: *to,
+                relation: relation.clone(),
+                weight,
+            });
+        }
+    }
+    diff
+}
+
+/// Items only in `after` and items only in `before`, both sorted.
+fn set_difference<T: Ord + Clone>(before: &[T], after: &[T]) -> (Vec<T>, Vec<T>) {
+    let before: BTreeSet<&T> = before.iter().collect();
+    let after: BTreeSet<&T> = after.iter().collect();
+    (
+        after.difference(&before).map(|&t| t.clone()).collect(),
+        before.difference(&after).map(|&t| t.clone()).collect(),
+    )
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::graph::{Graph, GraphStore};
+    use crate::{Attribute, EntityId, Value};
+
+    fn canonical(graph: &Graph) -> CanonicalGraph {
+        CanonicalGraph::from_graph(graph)
+    }
+
+    #[test]
+    fn identical_states_have_an_empty_diff() {
+        let mut graph = Graph::new();
+        let a = graph.insert_node(EntityId(1)).unwrap();
+        let b = graph.insert_node(EntityId(2)).unwrap();
+        graph.insert_edge(a, b, None, EdgeWeight::new(3)).unwrap();
+        assert!(diff(&canonical(&graph), &canonical(&graph)).is_empty());
+    }
+
+    #[test]
+    fn diff_lists_every_change_in_canonical_order() {
+        let mut before = Graph::new();
+        let a = before.insert_node(EntityId(1)).unwrap();
+        let b = before.insert_node(EntityId(2)).unwrap();
+        let c = before.insert_node(EntityId(3)).unwrap();
+        before.insert_edge(a, b, None, EdgeWeight::new(1)).unwrap();
+        before.insert_edge(b, c, None, EdgeWeight::new(2)).unwrap();
+        before
+            .store_property(c, Attribute::new("name"), Value::new("Carol"))
+            .unwrap();
+
+        let mut after = before.clone();
+        after.insert_edge(a, b, None, EdgeWeight::new(4)).unwrap();
+        after.remove_node(c).unwrap();
+        let d = after.insert_node(EntityId(4)).unwrap();
+        let rel = Attribute::new("knows");
+        after
+            .insert_edge(d, a, Some(&rel), EdgeWeight::new(1))
+            .unwrap();
+        after
+            .store_property(a, Attribute::new("name"), Value::new("Alice"))
+            .unwrap();
+
+        let delta = diff(&canonical(&before), &canonical(&after));
+        assert_eq!(
+            delta.nodes_added,
+            vec![CanonicalNode { id: d.0, entity: 4 }]
+        );
+        assert_eq!(
+            delta.nodes_removed,
+            vec![CanonicalNode { id: c.0, entity: 3 }]
+        );
+        assert_eq!(
+            delta.edges_added,
+            vec![CanonicalEdge::with_relation(
+                d,
+                a,
+                Some(&rel),
+                EdgeWeight::new(1)
+            )]
+        );
+        assert_eq!(
+            delta.edges_removed,
+            vec![CanonicalEdge::new(b, c, EdgeWeight::new(2))]
+        );
+        assert_eq!(
+            delta.edges_reweighted,
+            vec![EdgeReweight {
+                from: a.0,
+                to: b.0,
+                relation: None,
+                old_weight: EdgeWeight::new(1),
+                new_weight: EdgeWeight::new(4),
+            }]
+        );
+        assert_eq!(delta.properties_added.len(), 1);
+        assert_eq!(delta.properties_added[0].value, "Alice");
+        assert_eq!(delta.properties_removed.len(), 1);
+        assert_eq!(delta.properties_removed[0].node_id, c.0);
+
+        // Reversing the arguments swaps every side.
+        let back = diff(&canonical(&after), &canonical(&before));
+        assert_eq!(back.nodes_added, delta.nodes_removed);
+        assert_eq!(back.edges_removed, delta.edges_added);
+        assert_eq!(back.edges_reweighted[0].new_weight, EdgeWeight::new(1));
+    }
+}
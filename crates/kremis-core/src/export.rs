@@ -9,7 +9,8 @@
 //! This module provides deterministic, bit-exact serialization for graph verification.
 
 use crate::graph::{Graph, GraphStore, LoadDiagnostics};
-use crate::{EdgeWeight, EntityId, KremisError, Node, NodeId};
+use crate::storage::stable_attr_hash;
+use crate::{Attribute, EdgeWeight, EntityId, KremisError, Node, NodeId};
 use serde::{Deserialize, Serialize};
 
 // =============================================================================
@@ -20,7 +21,12 @@ use serde::{Deserialize, Serialize};
 pub const CANONICAL_MAGIC: [u8; 4] = *b"KREX"; // Kremis Export
 
 /// Current canonical format version.
-pub const CANONICAL_VERSION: u8 = 2;
+///
+/// Version 3 adds relation labels to edges and version 4 the entity key
+/// registry. Each graph is written in the oldest version that can hold it, so
+/// exports of graphs without labeled edges or keys (and every hash or
+/// certificate derived from them) are unchanged.
+pub const CANONICAL_VERSION: u8 = 4;
 
 /// Maximum allowed node count in canonical imports.
 ///
@@ -85,7 +91,7 @@ impl CanonicalHeader {
                 "Invalid file format".to_string(),
             ));
         }
-        if self.version != 1 && self.version != CANONICAL_VERSION {
+        if self.version == 0 || self.version > CANONICAL_VERSION {
             return Err(KremisError::SerializationError(
                 "Unsupported file version".to_string(),
             ));
@@ -127,7 +133,8 @@ impl From<CanonicalNode> for Node {
 
 /// An edge in canonical format.
 ///
-/// Sorted by (from, to) for deterministic ordering.
+/// Sorted by (from, to, relation) for deterministic ordering; the unlabeled
+/// edge sorts before labeled edges between the same nodes.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub struct CanonicalEdge {
     /// Source node ID.
@@ -136,22 +143,56 @@ pub struct CanonicalEdge {
     /// Target node ID.
     pub to: u64,
 
+    /// Relation label, `None` for an unlabeled association edge.
+    pub relation: Option<String>,
+
     /// Edge weight.
     pub weight: i64,
 }
 
 impl CanonicalEdge {
-    /// Create a new canonical edge.
+    /// Create a new unlabeled canonical edge.
     #[must_use]
     pub fn new(from: NodeId, to: NodeId, weight: EdgeWeight) -> Self {
+        Self::with_relation(from, to, None, weight)
+    }
+
+    /// Create a new canonical edge carrying an optional relation label.
+    #[must_use]
+    pub fn with_relation(
+        from: NodeId,
+        to: NodeId,
+        relation: Option<&Attribute>,
+        weight: EdgeWeight,
+    ) -> Self {
         Self {
             from: from.0,
             to: to.0,
+            relation: relation.map(|r| r.as_str().to_string()),
             weight: weight.value(),
         }
     }
 }
 
+/// Edge layout of format versions 1 and 2, which predate relation labels.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+struct CanonicalEdgeV2 {
+    from: u64,
+    to: u64,
+    weight: i64,
+}
+
+impl CanonicalEdgeV2 {
+    fn into_edge(self) -> CanonicalEdge {
+        CanonicalEdge {
+            from: self.from,
+            to: self.to,
+            relation: None,
+            weight: self.weight,
+        }
+    }
+}
+
 /// A property in canonical format.
 ///
 /// Sorted by (node_id, attribute, value) for deterministic ordering.
@@ -167,6 +208,18 @@ pub struct CanonicalProperty {
     pub value: String,
 }
 
+/// An entity key binding in canonical format.
+///
+/// Sorted by key for deterministic ordering.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CanonicalEntityKey {
+    /// The external key (sort key).
+    pub key: String,
+
+    /// The entity ID the key is bound to.
+    pub entity: u64,
+}
+
 // =============================================================================
 // CANONICAL GRAPH (Sorted, Deterministic)
 // =============================================================================
@@ -174,9 +227,29 @@ pub struct CanonicalProperty {
 /// V1 canonical graph format (without properties) for backward compatibility.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 struct CanonicalGraphV1 {
+    nodes: Vec<CanonicalNode>,
+    edges: Vec<CanonicalEdgeV2>,
+    next_node_id: u64,
+}
+
+/// V2 canonical graph format (properties, no relation labels).
+///
+/// Still written for graphs without labeled edges so their bytes stay stable.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+struct CanonicalGraphV2 {
+    nodes: Vec<CanonicalNode>,
+    edges: Vec<CanonicalEdgeV2>,
+    next_node_id: u64,
+    properties: Vec<CanonicalProperty>,
+}
+
+/// V3 canonical graph format (relation labels, no entity keys).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+struct CanonicalGraphV3 {
     nodes: Vec<CanonicalNode>,
     edges: Vec<CanonicalEdge>,
     next_node_id: u64,
+    properties: Vec<CanonicalProperty>,
 }
 
 /// A graph in canonical format for bit-exact serialization.
@@ -188,7 +261,7 @@ pub struct CanonicalGraph {
     /// Nodes sorted by NodeId.
     pub nodes: Vec<CanonicalNode>,
 
-    /// Edges sorted by (from, to).
+    /// Edges sorted by (from, to, relation).
     pub edges: Vec<CanonicalEdge>,
 
     /// Next node ID counter.
@@ -196,6 +269,9 @@ pub struct CanonicalGraph {
 
     /// Properties sorted by (node_id, attribute, value).
     pub properties: Vec<CanonicalProperty>,
+
+    /// Entity key bindings sorted by key.
+    pub entity_keys: Vec<CanonicalEntityKey>,
 }
 
 impl CanonicalGraph {
@@ -210,8 +286,10 @@ impl CanonicalGraph {
 
         // Collect and sort edges
         let mut edges: Vec<CanonicalEdge> = graph
-            .edges()
-            .map(|(from, to, weight)| CanonicalEdge::new(from, to, weight))
+            .edges_with_relations()
+            .map(|(from, to, relation, weight)| {
+                CanonicalEdge::with_relation(from, to, relation, weight)
+            })
             .collect();
         edges.sort();
 
@@ -230,11 +308,35 @@ impl CanonicalGraph {
         }
         properties.sort();
 
+        // Registry iteration is already ordered by key
+        let entity_keys = graph
+            .entity_keys()
+            .map(|(key, entity)| CanonicalEntityKey {
+                key: key.to_string(),
+                entity: entity.0,
+            })
+            .collect();
+
         Self {
             nodes,
             edges,
             next_node_id: graph.next_node_id(),
             properties,
+            entity_keys,
+        }
+    }
+
+    /// The format version this graph is written as: [`CANONICAL_VERSION`]
+    /// when it has entity keys, 3 when some edge carries a relation label,
+    /// 2 otherwise.
+    #[must_use]
+    pub fn format_version(&self) -> u8 {
+        if !self.entity_keys.is_empty() {
+            CANONICAL_VERSION
+        } else if self.edges.iter().any(|e| e.relation.is_some()) {
+            3
+        } else {
+            2
         }
     }
 
@@ -278,6 +380,10 @@ impl CanonicalGraph {
             hash ^= edge.from.rotate_left(17);
             hash ^= edge.to.rotate_left(11);
             hash ^= (edge.weight as u64).rotate_left(5);
+            // Unlabeled edges contribute nothing here, keeping v2 checksums intact.
+            if let Some(relation) = &edge.relation {
+                hash ^= relation_checksum(relation);
+            }
         }
 
         // Hash properties
@@ -291,6 +397,12 @@ impl CanonicalGraph {
             }
         }
 
+        // Hash entity keys (none before v4, keeping older checksums intact)
+        for entry in &self.entity_keys {
+            hash ^= entity_key_checksum(&entry.key);
+            hash ^= entry.entity.rotate_left(41);
+        }
+
         // Hash metadata
         hash ^= self.next_node_id.rotate_left(3);
 
@@ -298,6 +410,24 @@ impl CanonicalGraph {
     }
 }
 
+/// Checksum term of an edge relation label.
+///
+/// Unlike the per-byte terms above, the label is folded with FNV-1a (see
+/// [`stable_attr_hash`]) so byte order matters: `"ab"` and `"ba"` differ. The
+/// length is mixed in as well.
+pub(crate) fn relation_checksum(relation: &str) -> u64 {
+    stable_attr_hash(relation).rotate_left(31) ^ (relation.len() as u64).rotate_left(43)
+}
+
+/// Checksum term of an entity key.
+///
+/// Folded like [`relation_checksum`], so reordered or corrupted key bytes
+/// change the checksum, then turned so a key and a relation label with the
+/// same text contribute different terms.
+pub(crate) fn entity_key_checksum(key: &str) -> u64 {
+    relation_checksum(key).rotate_left(6)
+}
+
 // =============================================================================
 // EXPORT FUNCTIONS
 // =============================================================================
@@ -311,6 +441,9 @@ impl CanonicalGraph {
 /// [CanonicalHeader (postcard)] [CanonicalGraph (postcard)]
 /// ```
 ///
+/// The version is chosen by [`CanonicalGraph::format_version`]: a graph with
+/// no labeled edges and no entity keys is written in the version 2 layout.
+///
 /// # Errors
 ///
 /// Returns `KremisError::SerializationError` if serialization fails.
@@ -318,19 +451,49 @@ pub fn export_canonical(graph: &Graph) -> Result<Vec<u8>, KremisError> {
     let canonical = CanonicalGraph::from_graph(graph);
     let checksum = canonical.checksum();
 
-    let header = CanonicalHeader::new(
-        canonical.nodes.len() as u64,
-        canonical.edges.len() as u64,
-        checksum,
-    );
+    let version = canonical.format_version();
+
+    let header = CanonicalHeader {
+        version,
+        ..CanonicalHeader::new(
+            canonical.nodes.len() as u64,
+            canonical.edges.len() as u64,
+            checksum,
+        )
+    };
 
     // Serialize header
     let header_bytes = postcard::to_allocvec(&header)
         .map_err(|e| KremisError::SerializationError(format!("Header: {}", e)))?;
 
     // Serialize data
-    let data_bytes = postcard::to_allocvec(&canonical)
-        .map_err(|e| KremisError::SerializationError(format!("Data: {}", e)))?;
+    let data_bytes = match version {
+        2 => {
+            let v2 = CanonicalGraphV2 {
+                nodes: canonical.nodes,
+                edges: canonical
+                    .edges
+                    .into_iter()
+                    .map(|e| CanonicalEdgeV2 {
+                        from: e.from,
+                        to: e.to,
+                        weight: e.weight,
+                    })
+                    .collect(),
+                next_node_id: canonical.next_node_id,
+                properties: canonical.properties,
+            };
+            postcard::to_allocvec(&v2)
+        }
+        3 => postcard::to_allocvec(&CanonicalGraphV3 {
+            nodes: canonical.nodes,
+            edges: canonical.edges,
+            next_node_id: canonical.next_node_id,
+            properties: canonical.properties,
+        }),
+        _ => postcard::to_allocvec(&canonical),
+    }
+    .map_err(|e| KremisError::SerializationError(format!("Data: {}", e)))?;
 
     // Combine: [header_len: u32] [header] [data]
     let mut result = Vec::with_capacity(4 + header_bytes.len() + data_bytes.len());
@@ -395,19 +558,53 @@ pub fn import_canonical(data: &[u8]) -> Result<(Graph, LoadDiagnostics), KremisE
     }
 
     // Deserialize data based on version
-    let canonical: CanonicalGraph = if header.version == 1 {
-        // V1 format: no properties field
-        let v1: CanonicalGraphV1 = postcard::from_bytes(payload)
-            .map_err(|e| KremisError::SerializationError(format!("Data: {}", e)))?;
-        CanonicalGraph {
-            nodes: v1.nodes,
-            edges: v1.edges,
-            next_node_id: v1.next_node_id,
-            properties: Vec::new(),
+    let canonical: CanonicalGraph = match header.version {
+        1 => {
+            // V1 format: no properties field
+            let v1: CanonicalGraphV1 = postcard::from_bytes(payload)
+                .map_err(|e| KremisError::SerializationError(format!("Data: {}", e)))?;
+            CanonicalGraph {
+                nodes: v1.nodes,
+                edges: v1
+                    .edges
+                    .into_iter()
+                    .map(CanonicalEdgeV2::into_edge)
+                    .collect(),
+                next_node_id: v1.next_node_id,
+                properties: Vec::new(),
+                entity_keys: Vec::new(),
+            }
         }
-    } else {
-        postcard::from_bytes(payload)
-            .map_err(|e| KremisError::SerializationError(format!("Data: {}", e)))?
+        2 => {
+            // V2 format: no relation labels
+            let v2: CanonicalGraphV2 = postcard::from_bytes(payload)
+                .map_err(|e| KremisError::SerializationError(format!("Data: {}", e)))?;
+            CanonicalGraph {
+                nodes: v2.nodes,
+                edges: v2
+                    .edges
+                    .into_iter()
+                    .map(CanonicalEdgeV2::into_edge)
+                    .collect(),
+                next_node_id: v2.next_node_id,
+                properties: v2.properties,
+                entity_keys: Vec::new(),
+            }
+        }
+        3 => {
+            // V3 format: no entity keys
+            let v3: CanonicalGraphV3 = postcard::from_bytes(payload)
+                .map_err(|e| KremisError::SerializationError(format!("Data: {}", e)))?;
+            CanonicalGraph {
+                nodes: v3.nodes,
+                edges: v3.edges,
+                next_node_id: v3.next_node_id,
+                properties: v3.properties,
+                entity_keys: Vec::new(),
+            }
+        }
+        _ => postcard::from_bytes(payload)
+            .map_err(|e| KremisError::SerializationError(format!("Data: {}", e)))?,
     };
 
     // Verify checksum: for v1 imports, recompute using v1's checksum logic (no properties)
@@ -418,6 +615,7 @@ pub fn import_canonical(data: &[u8]) -> Result<(Graph, LoadDiagnostics), KremisE
             edges: canonical.edges.clone(),
             next_node_id: canonical.next_node_id,
             properties: Vec::new(),
+            entity_keys: Vec::new(),
         };
         v1_canonical.checksum()
     } else {
@@ -552,12 +750,14 @@ mod tests {
         let c = graph.insert_node(EntityId(3)).expect("insert");
 
         graph
-            .insert_edge(a, b, EdgeWeight::new(10))
+            .insert_edge(a, b, None, EdgeWeight::new(10))
             .expect("insert");
         graph
-            .insert_edge(b, c, EdgeWeight::new(20))
+            .insert_edge(b, c, None, EdgeWeight::new(20))
+            .expect("insert");
+        graph
+            .insert_edge(a, c, None, EdgeWeight::new(5))
             .expect("insert");
-        graph.insert_edge(a, c, EdgeWeight::new(5)).expect("insert");
 
         graph
     }
@@ -647,9 +847,15 @@ mod tests {
         let c = graph.insert_node(EntityId(3)).expect("insert");
 
         // Insert edges in non-sorted order
-        graph.insert_edge(c, a, EdgeWeight::new(1)).expect("insert");
-        graph.insert_edge(a, b, EdgeWeight::new(2)).expect("insert");
-        graph.insert_edge(b, c, EdgeWeight::new(3)).expect("insert");
+        graph
+            .insert_edge(c, a, None, EdgeWeight::new(1))
+            .expect("insert");
+        graph
+            .insert_edge(a, b, None, EdgeWeight::new(2))
+            .expect("insert");
+        graph
+            .insert_edge(b, c, None, EdgeWeight::new(3))
+            .expect("insert");
 
         let canonical = CanonicalGraph::from_graph(&graph);
 
@@ -1046,7 +1252,7 @@ mod tests {
         let a = graph2.insert_node(EntityId(100)).expect("insert");
         let b = graph2.insert_node(EntityId(200)).expect("insert");
         graph2
-            .insert_edge(a, b, EdgeWeight::new(999))
+            .insert_edge(a, b, None, EdgeWeight::new(999))
             .expect("insert");
 
         let exported1 = export_canonical(&graph1).expect("export");
@@ -1062,14 +1268,14 @@ mod tests {
         let a = graph1.insert_node(EntityId(1)).expect("insert");
         let b = graph1.insert_node(EntityId(2)).expect("insert");
         graph1
-            .insert_edge(a, b, EdgeWeight::new(10))
+            .insert_edge(a, b, None, EdgeWeight::new(10))
             .expect("insert");
 
         let mut graph2 = Graph::new();
         let c = graph2.insert_node(EntityId(1)).expect("insert");
         let d = graph2.insert_node(EntityId(2)).expect("insert");
         graph2
-            .insert_edge(c, d, EdgeWeight::new(20))
+            .insert_edge(c, d, None, EdgeWeight::new(20))
             .expect("insert"); // Different weight
 
         let checksum1 = canonical_checksum(&graph1);
@@ -1146,9 +1352,13 @@ mod tests {
                 nodes
             },
             edges: {
-                let mut edges: Vec<CanonicalEdge> = graph
+                let mut edges: Vec<CanonicalEdgeV2> = graph
                     .edges()
-                    .map(|(from, to, weight)| CanonicalEdge::new(from, to, weight))
+                    .map(|(from, to, weight)| CanonicalEdgeV2 {
+                        from: from.0,
+                        to: to.0,
+                        weight: weight.value(),
+                    })
                     .collect();
                 edges.sort();
                 edges
@@ -1159,9 +1369,15 @@ mod tests {
         // Compute v1 checksum (same algorithm, no properties)
         let v1_as_canonical = CanonicalGraph {
             nodes: v1.nodes.clone(),
-            edges: v1.edges.clone(),
+            edges: v1
+                .edges
+                .iter()
+                .cloned()
+                .map(CanonicalEdgeV2::into_edge)
+                .collect(),
             next_node_id: v1.next_node_id,
             properties: Vec::new(),
+            entity_keys: Vec::new(),
         };
         let checksum = v1_as_canonical.checksum();
 
@@ -1212,4 +1428,149 @@ mod tests {
             "Properties should affect the checksum"
         );
     }
+
+    // =========================================================================
+    // Relation label tests
+    // =========================================================================
+
+    #[test]
+    fn unlabeled_graph_exports_as_v2() {
+        let graph = create_test_graph();
+        let exported = export_canonical(&graph).expect("export");
+
+        // Version byte follows the 4-byte length prefix and the 4 magic bytes.
+        assert_eq!(exported[4 + 4], 2);
+        assert_eq!(CanonicalGraph::from_graph(&graph).format_version(), 2);
+    }
+
+    #[test]
+    fn labeled_edges_roundtrip_as_v3() {
+        let mut graph = create_test_graph();
+        let depends_on = Attribute::new("depends_on");
+        graph
+            .insert_edge(NodeId(0), NodeId(1), Some(&depends_on), EdgeWeight::new(7))
+            .expect("insert labeled");
+
+        let exported = export_canonical(&graph).expect("export");
+        assert_eq!(exported[4 + 4], 3);
+
+        let (imported, diag) = import_canonical(&exported).expect("import");
+        assert_eq!(diag, LoadDiagnostics::default());
+        assert_eq!(
+            imported.get_edge_internal(NodeId(0), NodeId(1), Some(&depends_on)),
+            Some(EdgeWeight::new(7))
+        );
+        assert_eq!(
+            imported.get_edge_internal(NodeId(0), NodeId(1), None),
+            graph.get_edge_internal(NodeId(0), NodeId(1), None)
+        );
+        assert_eq!(
+            CanonicalGraph::from_graph(&graph),
+            CanonicalGraph::from_graph(&imported)
+        );
+    }
+
+    #[test]
+    fn relation_label_affects_checksum() {
+        let mut graph1 = Graph::new();
+        let mut graph2 = Graph::new();
+        for graph in [&mut graph1, &mut graph2] {
+            graph.insert_node(EntityId(1)).expect("insert");
+            graph.insert_node(EntityId(2)).expect("insert");
+        }
+        graph1
+            .insert_edge(
+                NodeId(0),
+                NodeId(1),
+                Some(&Attribute::new("owns")),
+                EdgeWeight::new(1),
+            )
+            .expect("insert");
+        graph2
+            .insert_edge(
+                NodeId(0),
+                NodeId(1),
+                Some(&Attribute::new("uses")),
+                EdgeWeight::new(1),
+            )
+            .expect("insert");
+
+        assert_ne!(canonical_checksum(&graph1), canonical_checksum(&graph2));
+    }
+
+    #[test]
+    fn relation_checksum_depends_on_byte_order() {
+        assert_ne!(relation_checksum("ab"), relation_checksum("ba"));
+        assert_ne!(relation_checksum("owns"), relation_checksum("snwo"));
+        assert_ne!(relation_checksum("a"), relation_checksum("aaa"));
+    }
+
+    // =========================================================================
+    // Entity key tests
+    // =========================================================================
+
+    #[test]
+    fn entity_keys_roundtrip_as_v4() {
+        let mut graph = create_test_graph();
+        let entity = graph.register_entity_key("svc:ledger").expect("register");
+        graph.insert_node(entity).expect("insert");
+
+        let exported = export_canonical(&graph).expect("export");
+        assert_eq!(exported[4 + 4], CANONICAL_VERSION);
+
+        let (imported, diag) = import_canonical(&exported).expect("import");
+        assert_eq!(diag, LoadDiagnostics::default());
+        assert_eq!(
+            imported.entity_for_key("svc:ledger").expect("resolve"),
+            Some(entity)
+        );
+        assert_eq!(
+            imported.key_for_entity(entity).expect("reverse"),
+            Some("svc:ledger".to_string())
+        );
+        assert_eq!(
+            CanonicalGraph::from_graph(&graph),
+            CanonicalGraph::from_graph(&imported)
+        );
+    }
+
+    #[test]
+    fn entity_key_affects_checksum() {
+        let mut graph1 = create_test_graph();
+        let mut graph2 = create_test_graph();
+        graph1.register_entity_key("a").expect("register");
+        graph2.register_entity_key("b").expect("register");
+
+        assert_ne!(canonical_checksum(&graph1), canonical_checksum(&graph2));
+    }
+
+    #[test]
+    fn corrupted_entity_key_fails_the_checksum() {
+        let mut graph = create_test_graph();
+        let entity = graph.register_entity_key("svc:ledger").expect("register");
+        graph.insert_node(entity).expect("insert");
+        let exported = export_canonical(&graph).expect("export");
+        let at = exported
+            .windows(b"svc:ledger".len())
+            .position(|w| w == b"svc:ledger")
+            .expect("key bytes in export");
+
+        // One byte changed, and two bytes swapped: a per-byte XOR fold
+        // cannot see the swap.
+        let mut flipped = exported.clone();
+        flipped[at + 4] = b'L';
+        let mut swapped = exported;
+        swapped.swap(at, at + 1);
+
+        for corrupted in [flipped, swapped] {
+            let err = import_canonical(&corrupted).expect_err("corrupted key");
+            assert!(err.to_string().contains("Checksum mismatch"), "{err}");
+        }
+    }
+
+    #[test]
+    fn entity_key_checksum_depends_on_byte_order() {
+        assert_ne!(entity_key_checksum("ab"), entity_key_checksum("ba"));
+        assert_ne!(entity_key_checksum("owns"), relation_checksum("owns"));
+    }
 }
@@ -195,7 +195,7 @@ mod tests {
         let a = graph.insert_node(EntityId(1)).expect("insert");
         let b = graph.insert_node(EntityId(2)).expect("insert");
         graph
-            .insert_edge(a, b, EdgeWeight::new(10))
+            .insert_edge(a, b, None, EdgeWeight::new(10))
             .expect("insert");
 
         // First serialization
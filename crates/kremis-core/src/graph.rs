@@ -4,14 +4,184 @@
 //!
 //! This module implements the `GraphStore` trait.
 //! All data structures use `BTreeMap` for deterministic ordering.
-
-use crate::{Artifact, Attribute, EdgeWeight, EntityId, KremisError, Node, NodeId, Value};
-use std::collections::{BTreeMap, BTreeSet, VecDeque};
+//!
+//! ## Relations
+//!
+//! An edge is identified by `(from, to, relation)`. The relation is an
+//! optional [`Attribute`] label: association edges created by ingestion are
+//! unlabeled (`None`), while edges such as `depends_on` or `owned_by` carry
+//! their label, so two differently labeled edges between the same pair of
+//! nodes are distinct and weighted independently.
+//!
+//! Methods that address a single edge (`insert_edge`, `increment_edge`,
+//! `decrement_edge`, `get_edge`) take the relation as part of the edge
+//! identity. Methods that walk the graph (`neighbors`, `traverse`,
+//! `traverse_filtered`, `strongest_path`) take it as a filter, where `None`
+//! follows every edge regardless of label.
+//!
+//! ## Reverse Index
+//!
+//! Every store keeps incoming adjacency alongside outgoing adjacency, so
+//! `predecessors`, `traverse_backward` and `strongest_path_backward` answer
+//! "what points here" without scanning every node.
+//!
+//! ## Strongest Path
+//!
+//! `strongest_path` is a widest-path search ([`STRONGEST_PATH_VARIANT`]): it
+//! returns the path whose weakest hop is heaviest, then the one with the
+//! fewest hops, then the one with the largest total weight, then the
+//! lexicographically smallest node sequence. Each hop weighs as much as the
+//! heaviest matching edge between its two nodes, with negative weights
+//! counted as zero. The search is complete and polynomial, so it never
+//! depends on how much of the graph it had time to visit.
+//!
+//! `strongest_path_with` adds [`PathConstraints`]: nodes to avoid, waypoints
+//! to visit in order, a hop limit and a minimum hop weight. Under a hop
+//! limit the weakest hop is the heaviest one that still fits, found by
+//! binary search over the hop weights.
+//!
+//! ## Path Enumeration
+//!
+//! `simple_paths` lists every loop-free route of up to `max_hops` hops,
+//! heaviest total weight first. `k_strongest_paths` ranks routes the way
+//! `strongest_path` does and returns the best `k`, found with Yen's
+//! algorithm: each spur search is a widest-path search whose weakest hop is
+//! capped at the weakest hop of the fixed prefix, which keeps the ranking of
+//! prefix plus spur consistent with the ranking of the spur alone.
+//!
+//! ## Visit Budgets
+//!
+//! `traverse_within`, `traverse_filtered_within`, `traverse_prefix_within`,
+//! `intersect_within`,
+//! `strongest_path_within`, `reachable_within`, `simple_paths_within` and
+//! `k_strongest_paths_within` run their query under a [`VisitBudget`]: a cap on
+//! the nodes expanded plus the edges followed from them. The count depends
+//! only on the graph and the query, never on the clock, so a budgeted query
+//! stops at the same point every time. A traversal that runs out returns
+//! what it reached; an intersection, reachability or path search returns
+//! nothing, since its answer is only right once the search is complete.
+//!
+//! ## Reachability
+//!
+//! `reachable` answers whether any path exists. It walks the whole forward
+//! closure of its start node and returns that closure with a negative
+//! answer, as the evidence the answer rests on.
+//!
+//! ## Property Index
+//!
+//! Stores also keep an inverted `(attribute, value) -> nodes` index, so
+//! `find_by_property` reaches nodes by what they carry instead of by id.
+//! Alongside it, a posting list maps every value token (see
+//! [`crate::search::tokenize`]) to the nodes whose values contain it, which
+//! backs the ranked `search`.
+
+use crate::{
+    Artifact, Attribute, EdgeWeight, EntityId, KremisError, LabeledEdge, Node, NodeId, Value,
+};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, VecDeque};
+use std::ops::Bound;
 
 // =============================================================================
 // GRAPHSTORE TRAIT
 // =============================================================================
 
+/// Name of the path semantics `strongest_path` implements, recorded in the
+/// descriptors of certified path queries.
+pub const STRONGEST_PATH_VARIANT: &str = "widest";
+
+/// Answer of [`GraphStore::reachable`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reachability {
+    /// `end` is reachable; the path with the fewest hops, preferring lower
+    /// node ids on ties.
+    Reachable(Vec<NodeId>),
+    /// `end` is not reachable. The artifact is the forward closure of
+    /// `start`: its path lists every reachable node in breadth-first order,
+    /// its subgraph every edge leaving those nodes.
+    Unreachable(Artifact),
+}
+
+/// One route found by [`GraphStore::simple_paths`] or
+/// [`GraphStore::k_strongest_paths`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RankedPath {
+    /// The nodes of the route, from `start` to `end`.
+    pub nodes: Vec<NodeId>,
+    /// The heaviest matching edge behind each hop, as `(from, to, relation,
+    /// weight)`, in route order.
+    pub edges: Vec<LabeledEdge>,
+    /// Sum of the hop weights, with negative weights counted as zero.
+    pub weight: i64,
+}
+
+/// Optional restrictions on [`GraphStore::strongest_path_with`]. The default
+/// restricts nothing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathConstraints {
+    /// Nodes the path may not visit, `start` and `end` included.
+    pub avoid: BTreeSet<NodeId>,
+    /// Nodes the path must visit, in this order, between `start` and `end`.
+    pub waypoints: Vec<NodeId>,
+    /// Maximum number of hops.
+    pub max_hops: Option<usize>,
+    /// Hops whose heaviest matching edge weighs less than this are not taken.
+    pub min_weight: Option<EdgeWeight>,
+}
+
+/// A deterministic work budget, counted in visits: one per node expanded
+/// and one per edge followed from it.
+///
+/// Once a query asks for more visits than are left, the budget is exhausted
+/// and stays exhausted; the query stops there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VisitBudget {
+    remaining: Option<u64>,
+    exhausted: bool,
+}
+
+impl VisitBudget {
+    /// A budget of `visits` visits.
+    #[must_use]
+    pub fn new(visits: u64) -> Self {
+        Self {
+            remaining: Some(visits),
+            exhausted: false,
+        }
+    }
+
+    /// A budget that never runs out.
+    #[must_use]
+    pub fn unlimited() -> Self {
+        Self {
+            remaining: None,
+            exhausted: false,
+        }
+    }
+
+    /// Spend `visits` visits. Returns `false`, and exhausts the budget, when
+    /// fewer are left.
+    pub fn spend(&mut self, visits: u64) -> bool {
+        match self.remaining {
+            None => true,
+            Some(left) if !self.exhausted && left >= visits => {
+                self.remaining = Some(left - visits);
+                true
+            }
+            Some(_) => {
+                self.exhausted = true;
+                false
+            }
+        }
+    }
+
+    /// Whether a query ran out of this budget.
+    #[must_use]
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted
+    }
+}
+
 /// The GraphStore trait defines the core graph operations.
 ///
 /// All queries must be computationally bounded.
@@ -26,6 +196,9 @@ pub trait GraphStore {
     /// Insert or update an edge with the given weight.
     /// If the edge exists, the weight is updated (not added).
     ///
+    /// `relation` is part of the edge identity: `None` addresses the
+    /// unlabeled association edge, `Some(label)` the edge carrying that label.
+    ///
     /// ## Missing-node semantics
     ///
     /// If either `from` or `to` does not exist in the graph, the call is a
@@ -36,24 +209,46 @@ pub trait GraphStore {
         &mut self,
         from: NodeId,
         to: NodeId,
+        relation: Option<&Attribute>,
         weight: EdgeWeight,
     ) -> Result<(), KremisError>;
 
     /// Increment the weight of an edge by 1 using saturating arithmetic.
     /// Creates the edge with weight 1 if it doesn't exist.
     ///
+    /// Only the edge with the given `relation` is touched; differently
+    /// labeled edges between the same nodes keep their weights.
+    ///
     /// ## Missing-node semantics
     ///
     /// Consistent with [`insert_edge`](GraphStore::insert_edge): if either
     /// `from` or `to` does not exist in the graph, the call is a **silent
     /// no-op** (returns `Ok(())`). No phantom edge is created.
-    fn increment_edge(&mut self, from: NodeId, to: NodeId) -> Result<(), KremisError>;
+    fn increment_edge(
+        &mut self,
+        from: NodeId,
+        to: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<(), KremisError>;
 
     /// Decrement the weight of an existing edge by 1, floored at 0.
     ///
     /// Returns `KremisError::EdgeNotFound` if the edge does not exist.
     /// Weight is clamped at 0 — negative weights break `strongest_path`.
-    fn decrement_edge(&mut self, from: NodeId, to: NodeId) -> Result<(), KremisError>;
+    fn decrement_edge(
+        &mut self,
+        from: NodeId,
+        to: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<(), KremisError>;
+
+    /// Remove a node and everything attached to it: its outgoing and incoming
+    /// edges (under every relation), its properties and its entity mapping.
+    ///
+    /// Returns the removed node, or `KremisError::NodeNotFound` if it does not
+    /// exist. The node's id is not reused; a later `insert_node` for the same
+    /// entity gets a fresh id.
+    fn remove_node(&mut self, id: NodeId) -> Result<Node, KremisError>;
 
     /// Lookup a node by its NodeId. Returns owned Node for storage compatibility.
     fn lookup(&self, id: NodeId) -> Result<Option<Node>, KremisError>;
@@ -61,17 +256,99 @@ pub trait GraphStore {
     /// Get a node by its EntityId. This is infallible (uses in-memory cache).
     fn get_node_by_entity(&self, entity: EntityId) -> Option<NodeId>;
 
-    /// Get the weight of an edge.
-    fn get_edge(&self, from: NodeId, to: NodeId) -> Result<Option<EdgeWeight>, KremisError>;
+    /// Get the weight of the edge `(from, to, relation)`.
+    fn get_edge(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<Option<EdgeWeight>, KremisError>;
+
+    /// Get the outgoing edges of a node, ordered by `(to, relation)` with the
+    /// unlabeled edge first.
+    ///
+    /// With `relation = Some(label)` only edges carrying that label are
+    /// returned; with `None` every outgoing edge is, so a target reached by
+    /// several relations appears once per relation.
+    fn neighbors(
+        &self,
+        node: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<Vec<(NodeId, EdgeWeight)>, KremisError>;
+
+    /// Get the incoming edges of a node as `(from, weight)` pairs, ordered by
+    /// `(from, relation)` with the unlabeled edge first.
+    ///
+    /// `relation` filters exactly as in [`neighbors`](Self::neighbors).
+    /// Implementations answer this from a maintained reverse index rather
+    /// than by scanning every node's adjacency.
+    fn predecessors(
+        &self,
+        node: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<Vec<(NodeId, EdgeWeight)>, KremisError>;
+
+    /// Get the outgoing edges of a node with their relation labels, as
+    /// `(to, relation, weight)`, in the order of [`neighbors`](Self::neighbors).
+    fn labeled_neighbors(
+        &self,
+        node: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<Vec<(NodeId, Option<Attribute>, EdgeWeight)>, KremisError>;
 
-    /// Get all neighbors of a node (outgoing edges).
-    fn neighbors(&self, node: NodeId) -> Result<Vec<(NodeId, EdgeWeight)>, KremisError>;
+    /// Get the incoming edges of a node with their relation labels, as
+    /// `(from, relation, weight)`, in the order of
+    /// [`predecessors`](Self::predecessors).
+    fn labeled_predecessors(
+        &self,
+        node: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<Vec<(NodeId, Option<Attribute>, EdgeWeight)>, KremisError>;
+
+    /// Get the heaviest edge from `from` to `to` among those matching
+    /// `relation`, with its label. Of equally heavy edges the first in
+    /// [`neighbors`](Self::neighbors) order wins: the unlabeled edge, then
+    /// labels ascending.
+    fn strongest_labeled_edge(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<Option<(Option<Attribute>, EdgeWeight)>, KremisError> {
+        let edges = self.labeled_neighbors(from, relation)?;
+        Ok(heaviest_by_target(edges).remove(&to))
+    }
+
+    /// Get the heaviest edge from `from` to `to` among those matching
+    /// `relation` (`None`: any label).
+    ///
+    /// This is the weight a path algorithm pays for the hop, so it is what
+    /// path artifacts report for each step.
+    fn strongest_edge(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<Option<EdgeWeight>, KremisError> {
+        Ok(self
+            .neighbors(from, relation)?
+            .into_iter()
+            .filter(|(target, _)| *target == to)
+            .map(|(_, weight)| weight)
+            .max())
+    }
 
     /// Check if a node exists in the graph.
     fn contains_node(&self, id: NodeId) -> Result<bool, KremisError>;
 
-    /// Traverse the graph from a starting node up to a depth limit.
-    fn traverse(&self, start: NodeId, depth: usize) -> Result<Option<Artifact>, KremisError> {
+    /// Traverse the graph from a starting node up to a depth limit,
+    /// following only edges that match `relation` (`None`: all edges).
+    fn traverse(
+        &self,
+        start: NodeId,
+        depth: usize,
+        relation: Option<&Attribute>,
+    ) -> Result<Option<Artifact>, KremisError> {
         let depth = depth.min(crate::primitives::MAX_TRAVERSAL_DEPTH);
         if !self.contains_node(start)? {
             return Ok(None);
@@ -92,8 +369,8 @@ pub trait GraphStore {
                 continue;
             }
 
-            for (neighbor, weight) in self.neighbors(current)? {
-                subgraph_edges.push((current, neighbor, weight));
+            for (neighbor, label, weight) in self.labeled_neighbors(current, relation)? {
+                subgraph_edges.push((current, neighbor, label, weight));
 
                 if !visited.contains(&neighbor) {
                     visited.insert(neighbor);
@@ -111,6 +388,7 @@ pub trait GraphStore {
         start: NodeId,
         depth: usize,
         min_weight: EdgeWeight,
+        relation: Option<&Attribute>,
     ) -> Result<Option<Artifact>, KremisError> {
         let depth = depth.min(crate::primitives::MAX_TRAVERSAL_DEPTH);
         if !self.contains_node(start)? {
@@ -132,9 +410,9 @@ pub trait GraphStore {
                 continue;
             }
 
-            for (neighbor, weight) in self.neighbors(current)? {
+            for (neighbor, label, weight) in self.labeled_neighbors(current, relation)? {
                 if weight.value() >= min_weight.value() {
-                    subgraph_edges.push((current, neighbor, weight));
+                    subgraph_edges.push((current, neighbor, label, weight));
 
                     if !visited.contains(&neighbor) {
                         visited.insert(neighbor);
@@ -147,6 +425,51 @@ pub trait GraphStore {
         Ok(Some(Artifact::with_subgraph(path, subgraph_edges)))
     }
 
+    /// Traverse incoming edges from a starting node up to a depth limit,
+    /// following only edges that match `relation` (`None`: all edges).
+    ///
+    /// The artifact path lists nodes in the order they were reached walking
+    /// backwards, while subgraph edges keep their stored direction
+    /// (`predecessor -> current`).
+    fn traverse_backward(
+        &self,
+        start: NodeId,
+        depth: usize,
+        relation: Option<&Attribute>,
+    ) -> Result<Option<Artifact>, KremisError> {
+        let depth = depth.min(crate::primitives::MAX_TRAVERSAL_DEPTH);
+        if !self.contains_node(start)? {
+            return Ok(None);
+        }
+
+        let mut visited = BTreeSet::new();
+        let mut queue = VecDeque::new();
+        let mut path = Vec::new();
+        let mut subgraph_edges = Vec::new();
+
+        queue.push_back((start, 0usize));
+        visited.insert(start);
+
+        while let Some((current, current_depth)) = queue.pop_front() {
+            path.push(current);
+
+            if current_depth >= depth {
+                continue;
+            }
+
+            for (predecessor, label, weight) in self.labeled_predecessors(current, relation)? {
+                subgraph_edges.push((predecessor, current, label, weight));
+
+                if !visited.contains(&predecessor) {
+                    visited.insert(predecessor);
+                    queue.push_back((predecessor, current_depth.saturating_add(1)));
+                }
+            }
+        }
+
+        Ok(Some(Artifact::with_subgraph(path, subgraph_edges)))
+    }
+
     /// Find nodes connected to ALL input nodes (intersection).
     fn intersect(&self, nodes: &[NodeId]) -> Result<Vec<NodeId>, KremisError> {
         if nodes.is_empty() {
@@ -154,7 +477,7 @@ pub trait GraphStore {
         }
 
         let first_neighbors: BTreeSet<_> = self
-            .neighbors(nodes[0])?
+            .neighbors(nodes[0], None)?
             .into_iter()
             .map(|(n, _)| n)
             .collect();
@@ -165,166 +488,1144 @@ pub trait GraphStore {
 
         let mut result = first_neighbors;
         for &node in &nodes[1..] {
-            let neighbors: BTreeSet<_> =
-                self.neighbors(node)?.into_iter().map(|(n, _)| n).collect();
+            let neighbors: BTreeSet<_> = self
+                .neighbors(node, None)?
+                .into_iter()
+                .map(|(n, _)| n)
+                .collect();
             result = result.intersection(&neighbors).copied().collect();
         }
 
         Ok(result.into_iter().collect())
     }
 
-    /// Find the simple path with maximum total weight between two nodes.
-    /// Uses DFS with backtracking (correct for all graph topologies).
+    /// Find the strongest path between two nodes, using only edges that
+    /// match `relation` (`None`: all edges).
     ///
-    /// The search is bounded by [`MAX_TRAVERSAL_DEPTH`] and [`MAX_VISIT_COUNT`].
-    /// When either bound is reached the search stops and returns the strongest
-    /// path found within those bounds, which on dense graphs may not be the
-    /// strongest path overall.
-    ///
-    /// [`MAX_TRAVERSAL_DEPTH`]: crate::primitives::MAX_TRAVERSAL_DEPTH
-    /// [`MAX_VISIT_COUNT`]: crate::primitives::MAX_VISIT_COUNT
+    /// The path maximizes its weakest hop, then minimizes its hop count, then
+    /// maximizes its total weight; remaining ties go to the lexicographically
+    /// smallest node sequence. See the [module docs](self#strongest-path).
     fn strongest_path(
         &self,
         start: NodeId,
         end: NodeId,
+        relation: Option<&Attribute>,
     ) -> Result<Option<Vec<NodeId>>, KremisError> {
-        if !self.contains_node(start)? || !self.contains_node(end)? {
-            return Ok(None);
-        }
+        widest_path(
+            self,
+            start,
+            end,
+            relation,
+            Direction::Outgoing,
+            &PathLimits::none(),
+            &mut VisitBudget::unlimited(),
+        )
+    }
+
+    /// Find the strongest path between two nodes that satisfies
+    /// `constraints`, using only edges that match `relation` (`None`: all
+    /// edges).
+    ///
+    /// Among the paths that qualify, the choice is made as in
+    /// [`strongest_path`](Self::strongest_path). With waypoints the path is
+    /// the concatenation of one leg per consecutive pair of stops, and may
+    /// pass a node in more than one leg. See the
+    /// [module docs](self#strongest-path).
+    fn strongest_path_with(
+        &self,
+        start: NodeId,
+        end: NodeId,
+        relation: Option<&Attribute>,
+        constraints: &PathConstraints,
+    ) -> Result<Option<Vec<NodeId>>, KremisError> {
+        widest_path(
+            self,
+            start,
+            end,
+            relation,
+            Direction::Outgoing,
+            &PathLimits::from_constraints(constraints),
+            &mut VisitBudget::unlimited(),
+        )
+    }
+
+    /// [`traverse`](Self::traverse) under a visit budget. Once `budget` runs
+    /// out the traversal stops, and the artifact holds what it had reached.
+    fn traverse_within(
+        &self,
+        start: NodeId,
+        depth: usize,
+        relation: Option<&Attribute>,
+        budget: &mut VisitBudget,
+    ) -> Result<Option<Artifact>, KremisError> {
+        budgeted_traverse(self, start, depth, None, relation, usize::MAX, budget)
+    }
+
+    /// [`traverse_filtered`](Self::traverse_filtered) under a visit budget,
+    /// stopping like [`traverse_within`](Self::traverse_within).
+    fn traverse_filtered_within(
+        &self,
+        start: NodeId,
+        depth: usize,
+        min_weight: EdgeWeight,
+        relation: Option<&Attribute>,
+        budget: &mut VisitBudget,
+    ) -> Result<Option<Artifact>, KremisError> {
+        budgeted_traverse(
+            self,
+            start,
+            depth,
+            Some(min_weight),
+            relation,
+            usize::MAX,
+            budget,
+        )
+    }
 
-        if start == end {
-            return Ok(Some(vec![start]));
+    /// The first `max_nodes` nodes [`traverse_within`](Self::traverse_within)
+    /// (`min_weight` unset) or
+    /// [`traverse_filtered_within`](Self::traverse_filtered_within) would
+    /// list, in the same order, with the edges read while expanding them.
+    ///
+    /// Both lists are prefixes of the full result, so a page of a traversal
+    /// stops once it has the nodes it needs.
+    fn traverse_prefix_within(
+        &self,
+        start: NodeId,
+        depth: usize,
+        min_weight: Option<EdgeWeight>,
+        relation: Option<&Attribute>,
+        max_nodes: usize,
+        budget: &mut VisitBudget,
+    ) -> Result<Option<Artifact>, KremisError> {
+        budgeted_traverse(self, start, depth, min_weight, relation, max_nodes, budget)
+    }
+
+    /// [`intersect`](Self::intersect) under a visit budget. An intersection
+    /// cut short would list nodes the remaining inputs exclude, so it returns
+    /// no nodes once `budget` runs out.
+    fn intersect_within(
+        &self,
+        nodes: &[NodeId],
+        budget: &mut VisitBudget,
+    ) -> Result<Vec<NodeId>, KremisError> {
+        let mut result: Option<BTreeSet<NodeId>> = None;
+        for &node in nodes {
+            let neighbors = self.neighbors(node, None)?;
+            if !budget.spend(1 + neighbors.len() as u64) {
+                return Ok(Vec::new());
+            }
+            let neighbors: BTreeSet<_> = neighbors.into_iter().map(|(n, _)| n).collect();
+            let narrowed = match result {
+                Some(known) => known.intersection(&neighbors).copied().collect(),
+                None => neighbors,
+            };
+            if narrowed.is_empty() {
+                return Ok(Vec::new());
+            }
+            result = Some(narrowed);
         }
+        Ok(result.into_iter().flatten().collect())
+    }
 
-        let mut best_path: Option<Vec<NodeId>> = None;
-        let mut best_weight: i64 = i64::MIN;
-        let mut visited = BTreeSet::new();
-        let mut current_path = vec![start];
-        let mut visit_budget = crate::primitives::MAX_VISIT_COUNT;
-        visited.insert(start);
+    /// [`strongest_path_with`](Self::strongest_path_with) under a visit
+    /// budget. A search cut short cannot rank the paths it never saw, so it
+    /// returns `None` once `budget` runs out.
+    fn strongest_path_within(
+        &self,
+        start: NodeId,
+        end: NodeId,
+        relation: Option<&Attribute>,
+        constraints: &PathConstraints,
+        budget: &mut VisitBudget,
+    ) -> Result<Option<Vec<NodeId>>, KremisError> {
+        widest_path(
+            self,
+            start,
+            end,
+            relation,
+            Direction::Outgoing,
+            &PathLimits::from_constraints(constraints),
+            budget,
+        )
+    }
 
-        dfs_strongest_path_default(
+    /// Find the strongest path that reaches `start` from `end` by walking
+    /// incoming edges, using only edges that match `relation` (`None`: all
+    /// edges).
+    ///
+    /// The returned path is ordered from `start` back to `end`, i.e. each
+    /// consecutive pair `(a, b)` is backed by a stored edge `b -> a`. The path
+    /// is chosen as in [`strongest_path`](Self::strongest_path).
+    fn strongest_path_backward(
+        &self,
+        start: NodeId,
+        end: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<Option<Vec<NodeId>>, KremisError> {
+        widest_path(
             self,
             start,
             end,
-            0,
-            &mut visited,
-            &mut current_path,
-            0,
-            &mut best_path,
-            &mut best_weight,
-            &mut visit_budget,
-        )?;
+            relation,
+            Direction::Incoming,
+            &PathLimits::none(),
+            &mut VisitBudget::unlimited(),
+        )
+    }
+
+    /// Decide whether `end` can be reached from `start` over edges matching
+    /// `relation` (`None`: all edges).
+    ///
+    /// The search is breadth-first and ignores `MAX_TRAVERSAL_DEPTH`: it only
+    /// stops at `end` or once the closure of `start` is exhausted, so it is
+    /// bounded by the size of the graph.
+    ///
+    /// Returns `Ok(None)` when `start` does not exist.
+    fn reachable(
+        &self,
+        start: NodeId,
+        end: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<Option<Reachability>, KremisError> {
+        self.reachable_within(start, end, relation, &mut VisitBudget::unlimited())
+    }
+
+    /// [`reachable`](Self::reachable) under a visit budget. A negative answer
+    /// rests on the whole closure of `start`, so the search returns `None`
+    /// once `budget` runs out.
+    fn reachable_within(
+        &self,
+        start: NodeId,
+        end: NodeId,
+        relation: Option<&Attribute>,
+        budget: &mut VisitBudget,
+    ) -> Result<Option<Reachability>, KremisError> {
+        if !self.contains_node(start)? {
+            return Ok(None);
+        }
+
+        let mut parents: BTreeMap<NodeId, NodeId> = BTreeMap::new();
+        let mut visited = BTreeSet::new();
+        let mut queue = VecDeque::new();
+        let mut closure = Vec::new();
+        let mut closure_edges = Vec::new();
+
+        queue.push_back(start);
+        visited.insert(start);
+
+        while let Some(current) = queue.pop_front() {
+            if current == end {
+                let mut path = vec![end];
+                let mut node = end;
+                while let Some(&parent) = parents.get(&node) {
+                    path.push(parent);
+                    node = parent;
+                }
+                path.reverse();
+                return Ok(Some(Reachability::Reachable(path)));
+            }
+
+            closure.push(current);
+            let neighbors = self.labeled_neighbors(current, relation)?;
+            if !budget.spend(1 + neighbors.len() as u64) {
+                return Ok(None);
+            }
+            for (neighbor, label, weight) in neighbors {
+                closure_edges.push((current, neighbor, label, weight));
+                if visited.insert(neighbor) {
+                    parents.insert(neighbor, current);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        Ok(Some(Reachability::Unreachable(Artifact::with_subgraph(
+            closure,
+            closure_edges,
+        ))))
+    }
+
+    /// List the simple paths from `start` to `end` with at most `max_hops`
+    /// hops, using only edges that match `relation` (`None`: all edges).
+    ///
+    /// Parallel edges count as one hop, weighing as much as the heaviest of
+    /// them. Paths are ordered by total weight descending, then by node
+    /// sequence ascending, and the first `limit` are returned.
+    ///
+    /// Returns `Ok(None)` when `start` or `end` does not exist, and
+    /// `KremisError::PathEnumerationLimit` when the search would extend more
+    /// than `MAX_PATH_ENUMERATION` partial paths: the full set is needed to order
+    /// it, so a narrower query is required rather than a truncated answer.
+    fn simple_paths(
+        &self,
+        start: NodeId,
+        end: NodeId,
+        max_hops: usize,
+        limit: usize,
+        relation: Option<&Attribute>,
+    ) -> Result<Option<Vec<RankedPath>>, KremisError> {
+        let mut budget = VisitBudget::unlimited();
+        enumerate_simple_paths(self, start, end, max_hops, limit, relation, &mut budget)
+    }
+
+    /// [`simple_paths`](Self::simple_paths) under a visit budget. Paths can
+    /// only be ordered once all are known, so the search returns `None` once
+    /// `budget` runs out.
+    fn simple_paths_within(
+        &self,
+        start: NodeId,
+        end: NodeId,
+        max_hops: usize,
+        limit: usize,
+        relation: Option<&Attribute>,
+        budget: &mut VisitBudget,
+    ) -> Result<Option<Vec<RankedPath>>, KremisError> {
+        enumerate_simple_paths(self, start, end, max_hops, limit, relation, budget)
+    }
+
+    /// Find the `k` strongest simple paths from `start` to `end`, using only
+    /// edges that match `relation` (`None`: all edges).
+    ///
+    /// Paths are ranked as in [`strongest_path`](Self::strongest_path), whose
+    /// answer is always the first one. See the
+    /// [module docs](self#path-enumeration).
+    ///
+    /// Returns `Ok(None)` when `start` or `end` does not exist.
+    fn k_strongest_paths(
+        &self,
+        start: NodeId,
+        end: NodeId,
+        k: usize,
+        relation: Option<&Attribute>,
+    ) -> Result<Option<Vec<RankedPath>>, KremisError> {
+        yen_strongest_paths(self, start, end, k, relation, &mut VisitBudget::unlimited())
+    }
+
+    /// [`k_strongest_paths`](Self::k_strongest_paths) under a visit budget,
+    /// shared by all spur searches. A ranking cut short could miss a
+    /// stronger path, so the search returns `None` once `budget` runs out.
+    fn k_strongest_paths_within(
+        &self,
+        start: NodeId,
+        end: NodeId,
+        k: usize,
+        relation: Option<&Attribute>,
+        budget: &mut VisitBudget,
+    ) -> Result<Option<Vec<RankedPath>>, KremisError> {
+        yen_strongest_paths(self, start, end, k, relation, budget)
+    }
+
+    /// Get the total number of nodes.
+    fn node_count(&self) -> Result<usize, KremisError>;
+
+    /// Get the id of every node, in ascending order.
+    fn node_ids(&self) -> Result<Vec<NodeId>, KremisError>;
+
+    /// Get the total number of edges, counting each relation separately.
+    fn edge_count(&self) -> Result<usize, KremisError>;
+
+    /// Store a property (attribute, value) for a node.
+    ///
+    /// This persists the full signal data beyond just the entity.
+    ///
+    /// ## Property semantics
+    ///
+    /// Properties follow **set semantics** at the `(attribute, value)` level:
+    /// - Storing the same `(attribute, value)` pair multiple times is idempotent.
+    /// - Different values for the same attribute are accumulated as separate entries
+    ///   (e.g. `("knows", "Bob")` and `("knows", "Charlie")` both persist).
+    ///
+    /// This ensures that repeated ingestion of the same signal does not grow the
+    /// property list unboundedly.
+    fn store_property(
+        &mut self,
+        node: NodeId,
+        attribute: Attribute,
+        value: Value,
+    ) -> Result<(), KremisError>;
+
+    /// Retract one `(attribute, value)` pair from a node.
+    ///
+    /// The counterpart of [`store_property`](Self::store_property): other
+    /// values of the same attribute are kept, and the freed slot counts
+    /// against `MAX_PROPERTIES_PER_NODE` again. Returns `Ok(false)` if the
+    /// node does not carry that pair, and `KremisError::NodeNotFound` if the
+    /// node does not exist.
+    fn remove_property(
+        &mut self,
+        node: NodeId,
+        attribute: &Attribute,
+        value: &Value,
+    ) -> Result<bool, KremisError>;
+
+    /// Get all properties for a node.
+    ///
+    /// Returns a list of (Attribute, Value) pairs associated with this node.
+    fn get_properties(&self, node: NodeId) -> Result<Vec<(Attribute, Value)>, KremisError>;
+
+    /// Find the nodes that carry the exact `(attribute, value)` pair.
+    ///
+    /// Returns at most `limit` node ids in ascending order. When `after` is
+    /// given, only ids strictly greater than it are returned, so a caller pages
+    /// through a large match set by passing the last id of the previous page.
+    fn find_by_property(
+        &self,
+        attribute: &Attribute,
+        value: &Value,
+        after: Option<NodeId>,
+        limit: usize,
+    ) -> Result<Vec<NodeId>, KremisError>;
+
+    /// Find the nodes whose property values contain a token starting with
+    /// `prefix`.
+    ///
+    /// `prefix` must already be case-folded. Each node appears once, mapped to
+    /// `true` when one of its tokens equals `prefix` exactly.
+    fn token_prefix_matches(&self, prefix: &str) -> Result<BTreeMap<NodeId, bool>, KremisError>;
+
+    /// Rank nodes by how well their property values match a text query.
+    ///
+    /// The query is tokenized like stored values. Each query token adds
+    /// `EXACT_TOKEN_SCORE` to a node with an equal token, or
+    /// `PREFIX_TOKEN_SCORE` to a node with a longer token it prefixes.
+    /// Returns at most `limit` `(node, score)` pairs, by score descending and
+    /// then `NodeId` ascending.
+    fn search(&self, query: &str, limit: usize) -> Result<Vec<(NodeId, u64)>, KremisError> {
+        use crate::search::{EXACT_TOKEN_SCORE, PREFIX_TOKEN_SCORE, tokenize};
+
+        let mut scores: BTreeMap<NodeId, u64> = BTreeMap::new();
+        for token in tokenize(query) {
+            for (node, exact) in self.token_prefix_matches(&token)? {
+                let gain = if exact {
+                    EXACT_TOKEN_SCORE
+                } else {
+                    PREFIX_TOKEN_SCORE
+                };
+                let score = scores.entry(node).or_insert(0);
+                *score = score.saturating_add(gain);
+            }
+        }
+
+        let mut ranked: Vec<(NodeId, u64)> = scores.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked.truncate(limit);
+        Ok(ranked)
+    }
+
+    /// Resolve an entity key to the entity it names.
+    fn entity_for_key(&self, key: &str) -> Result<Option<EntityId>, KremisError>;
+
+    /// Return the key bound to `entity`, if any.
+    fn key_for_entity(&self, entity: EntityId) -> Result<Option<String>, KremisError>;
+
+    /// Bind `key` to `entity` in the key registry.
+    ///
+    /// The registry is independent of the nodes: a binding outlives
+    /// `remove_node`, so re-ingesting under the same key reuses the entity.
+    /// Callers allocate through [`register_entity_key`](Self::register_entity_key),
+    /// which never binds a key or an entity twice.
+    fn bind_entity_key(&mut self, key: &str, entity: EntityId) -> Result<(), KremisError>;
+
+    /// Return the entity bound to `key`, allocating one on first use.
+    ///
+    /// A new key gets `KEYED_ENTITY_BASE` plus the low 63 bits of its FNV-1a
+    /// hash, so allocation depends only on the key and on which ids are
+    /// already taken. If that id is bound to another key or already names an
+    /// unkeyed node, the next id in the keyed range is tried, up to
+    /// `MAX_ENTITY_KEY_PROBES` times.
+    ///
+    /// Returns `KremisError::InvalidSignal` if the key is invalid or no free
+    /// id was found.
+    fn register_entity_key(&mut self, key: &str) -> Result<EntityId, KremisError> {
+        use crate::primitives::{KEYED_ENTITY_BASE, MAX_ENTITY_KEY_PROBES};
+
+        crate::ingestor::Ingestor::validate_entity_key(key)?;
+        if let Some(entity) = self.entity_for_key(key)? {
+            return Ok(entity);
+        }
+
+        let offset = crate::storage::stable_attr_hash(key) & (KEYED_ENTITY_BASE - 1);
+        for probe in 0..MAX_ENTITY_KEY_PROBES {
+            let entity = EntityId(
+                KEYED_ENTITY_BASE | (offset.wrapping_add(probe) & (KEYED_ENTITY_BASE - 1)),
+            );
+            if self.key_for_entity(entity)?.is_none() && self.get_node_by_entity(entity).is_none() {
+                self.bind_entity_key(key, entity)?;
+                return Ok(entity);
+            }
+        }
+        Err(KremisError::InvalidSignal)
+    }
+}
+
+/// Which way a path search follows edges.
+#[derive(Debug, Clone, Copy)]
+enum Direction {
+    /// Follow edges from source to target (`neighbors`).
+    Outgoing,
+    /// Follow edges from target to source (`predecessors`).
+    Incoming,
+}
+
+/// Restrictions on a [`widest_path`] search: the caller's
+/// [`PathConstraints`], plus the cap and avoided hops of a spur search in
+/// [`yen_strongest_paths`].
+struct PathLimits {
+    /// Upper bound on the weakest hop, as if `start` were reached over a hop
+    /// of this weight.
+    cap: i64,
+    /// Nodes the path may not visit.
+    avoid_nodes: BTreeSet<NodeId>,
+    /// Hops the path may not take.
+    avoid_hops: BTreeSet<(NodeId, NodeId)>,
+    /// Nodes the path must visit, in order.
+    waypoints: Vec<NodeId>,
+    /// Maximum number of hops.
+    max_hops: Option<usize>,
+    /// Hops whose heaviest matching edge is lighter than this are not taken.
+    min_weight: Option<EdgeWeight>,
+}
+
+impl PathLimits {
+    /// No restriction at all.
+    fn none() -> Self {
+        Self {
+            cap: i64::MAX,
+            avoid_nodes: BTreeSet::new(),
+            avoid_hops: BTreeSet::new(),
+            waypoints: Vec::new(),
+            max_hops: None,
+            min_weight: None,
+        }
+    }
+
+    /// Exactly the caller's constraints.
+    fn from_constraints(constraints: &PathConstraints) -> Self {
+        Self {
+            avoid_nodes: constraints.avoid.clone(),
+            waypoints: constraints.waypoints.clone(),
+            max_hops: constraints.max_hops,
+            min_weight: constraints.min_weight,
+            ..Self::none()
+        }
+    }
+}
+
+/// Breadth-first traversal behind `traverse_within`,
+/// `traverse_filtered_within` and `traverse_prefix_within`: `traverse` and
+/// `traverse_filtered`, spending one visit per node dequeued and one per edge
+/// read, and stopping before the node after the first `max_nodes`.
+fn budgeted_traverse<G: GraphStore + ?Sized>(
+    store: &G,
+    start: NodeId,
+    depth: usize,
+    min_weight: Option<EdgeWeight>,
+    relation: Option<&Attribute>,
+    max_nodes: usize,
+    budget: &mut VisitBudget,
+) -> Result<Option<Artifact>, KremisError> {
+    let depth = depth.min(crate::primitives::MAX_TRAVERSAL_DEPTH);
+    if !store.contains_node(start)? {
+        return Ok(None);
+    }
+
+    let mut visited = BTreeSet::from([start]);
+    let mut queue = VecDeque::from([(start, 0usize)]);
+    let mut path = Vec::new();
+    let mut subgraph_edges = Vec::new();
+
+    'search: while let Some((current, current_depth)) = queue.pop_front() {
+        if path.len() >= max_nodes || !budget.spend(1) {
+            break;
+        }
+        path.push(current);
+
+        if current_depth >= depth {
+            continue;
+        }
+
+        for (neighbor, label, weight) in store.labeled_neighbors(current, relation)? {
+            if !budget.spend(1) {
+                break 'search;
+            }
+            if min_weight.is_some_and(|min| weight.value() < min.value()) {
+                continue;
+            }
+            subgraph_edges.push((current, neighbor, label, weight));
+            if visited.insert(neighbor) {
+                queue.push_back((neighbor, current_depth.saturating_add(1)));
+            }
+        }
+    }
+
+    Ok(Some(Artifact::with_subgraph(path, subgraph_edges)))
+}
+
+/// The hops leaving `node`, each weighing as much as its heaviest matching
+/// edge.
+fn collapsed_hops<G: GraphStore + ?Sized>(
+    store: &G,
+    node: NodeId,
+    relation: Option<&Attribute>,
+    direction: Direction,
+) -> Result<BTreeMap<NodeId, EdgeWeight>, KremisError> {
+    let adjacent = match direction {
+        Direction::Outgoing => store.neighbors(node, relation)?,
+        Direction::Incoming => store.predecessors(node, relation)?,
+    };
+    let mut hops: BTreeMap<NodeId, EdgeWeight> = BTreeMap::new();
+    for (next, weight) in adjacent {
+        let entry = hops.entry(next).or_insert(weight);
+        *entry = (*entry).max(weight);
+    }
+    Ok(hops)
+}
+
+/// The heaviest of `edges` to each target, with its label. Of equally heavy
+/// edges the one listed first is kept.
+fn heaviest_by_target(
+    edges: Vec<(NodeId, Option<Attribute>, EdgeWeight)>,
+) -> BTreeMap<NodeId, (Option<Attribute>, EdgeWeight)> {
+    let mut heaviest: BTreeMap<NodeId, (Option<Attribute>, EdgeWeight)> = BTreeMap::new();
+    for (target, label, weight) in edges {
+        match heaviest.get(&target) {
+            Some((_, best)) if *best >= weight => {}
+            _ => {
+                heaviest.insert(target, (label, weight));
+            }
+        }
+    }
+    heaviest
+}
+
+/// Nodes by hop distance from the start of a leg, up to the layer that
+/// holds its end.
+type Layers = Vec<Vec<NodeId>>;
+
+/// The hops a [`widest_path`] search may take, fetched once per node, with
+/// negative weights counted as zero.
+///
+/// Fetching a node's hops spends one visit for the node and one per hop.
+/// Once the budget runs out every further node looks like a dead end, and
+/// the search discards its answer.
+struct HopCache<'a, G: GraphStore + ?Sized> {
+    store: &'a G,
+    relation: Option<&'a Attribute>,
+    direction: Direction,
+    limits: &'a PathLimits,
+    budget: &'a mut VisitBudget,
+    hops: BTreeMap<NodeId, BTreeMap<NodeId, i64>>,
+}
+
+impl<G: GraphStore + ?Sized> HopCache<'_, G> {
+    /// The allowed hops leaving `node`.
+    fn hops(&mut self, node: NodeId) -> Result<&BTreeMap<NodeId, i64>, KremisError> {
+        if !self.hops.contains_key(&node) {
+            let limits = self.limits;
+            let collapsed = collapsed_hops(self.store, node, self.relation, self.direction)?;
+            if !self.budget.spend(1 + collapsed.len() as u64) {
+                return Ok(self.hops.entry(node).or_default());
+            }
+            let targets = collapsed
+                .into_iter()
+                .filter(|&(next, weight)| {
+                    !limits.avoid_nodes.contains(&next)
+                        && !limits.avoid_hops.contains(&(node, next))
+                        && limits.min_weight.is_none_or(|min| weight >= min)
+                })
+                .map(|(next, weight)| (next, weight.value().max(0)))
+                .collect();
+            self.hops.insert(node, targets);
+        }
+        Ok(self.hops.entry(node).or_default())
+    }
+
+    /// The heaviest weakest hop over which `from` reaches `to`, found with a
+    /// max-bottleneck Dijkstra that settles every node reachable from `from`.
+    fn max_bottleneck(&mut self, from: NodeId, to: NodeId) -> Result<Option<i64>, KremisError> {
+        let cap = self.limits.cap;
+        let mut width: BTreeMap<NodeId, i64> = BTreeMap::from([(from, cap)]);
+        let mut settled = BTreeSet::new();
+        let mut heap = BinaryHeap::from([(cap, Reverse(from))]);
+
+        while let Some((node_width, Reverse(node))) = heap.pop() {
+            if !settled.insert(node) {
+                continue;
+            }
+            for (&next, &weight) in self.hops(node)? {
+                let through = node_width.min(weight);
+                if width.get(&next).is_none_or(|&known| through > known) {
+                    width.insert(next, through);
+                    heap.push((through, Reverse(next)));
+                }
+            }
+        }
+        Ok(width.get(&to).copied())
+    }
+
+    /// Breadth-first layers from `from` over hops of at least `threshold`,
+    /// or `None` if `to` is not reached within `max_hops`.
+    fn layers(
+        &mut self,
+        from: NodeId,
+        to: NodeId,
+        threshold: i64,
+        max_hops: Option<usize>,
+    ) -> Result<Option<Layers>, KremisError> {
+        let mut layers: Layers = vec![vec![from]];
+        let mut seen = BTreeSet::from([from]);
+        while !seen.contains(&to) {
+            if max_hops.is_some_and(|max| layers.len() > max) {
+                return Ok(None);
+            }
+            let mut next_layer = Vec::new();
+            for &node in layers.last().into_iter().flatten() {
+                for (&next, &weight) in self.hops(node)? {
+                    if weight >= threshold && seen.insert(next) {
+                        next_layer.push(next);
+                    }
+                }
+            }
+            if next_layer.is_empty() {
+                return Ok(None);
+            }
+            layers.push(next_layer);
+        }
+        Ok(Some(layers))
+    }
+
+    /// Layers for every leg between consecutive `stops`, sharing one hop
+    /// budget, or `None` if some leg does not fit.
+    fn leg_layers(
+        &mut self,
+        stops: &[NodeId],
+        threshold: i64,
+    ) -> Result<Option<Vec<Layers>>, KremisError> {
+        let mut budget = self.limits.max_hops;
+        let mut legs = Vec::with_capacity(stops.len().saturating_sub(1));
+        for leg in stops.windows(2) {
+            let Some(layers) = self.layers(leg[0], leg[1], threshold, budget)? else {
+                return Ok(None);
+            };
+            budget = budget.map(|left| left.saturating_sub(layers.len() - 1));
+            legs.push(layers);
+        }
+        Ok(Some(legs))
+    }
+
+    /// Walking back from `to` through `layers`, the heaviest continuation
+    /// from every node on a shortest leg, preferring the lower node id; then
+    /// the leg it spells out from the first layer.
+    fn heaviest_leg(
+        &mut self,
+        layers: &Layers,
+        to: NodeId,
+        threshold: i64,
+    ) -> Result<Option<Vec<NodeId>>, KremisError> {
+        let last = layers.len() - 1;
+        let mut next_hop: BTreeMap<NodeId, NodeId> = BTreeMap::new();
+        let mut totals: BTreeMap<NodeId, i64> = BTreeMap::from([(to, 0)]);
+        for layer in layers[..last].iter().rev() {
+            let mut layer_totals = BTreeMap::new();
+            for &node in layer {
+                let mut choice: Option<(i64, NodeId)> = None;
+                for (&next, &weight) in self.hops(node)? {
+                    if weight < threshold {
+                        continue;
+                    }
+                    let Some(&rest) = totals.get(&next) else {
+                        continue;
+                    };
+                    let total = weight.saturating_add(rest);
+                    if choice.is_none_or(|(known, _)| total > known) {
+                        choice = Some((total, next));
+                    }
+                }
+                if let Some((total, next)) = choice {
+                    layer_totals.insert(node, total);
+                    next_hop.insert(node, next);
+                }
+            }
+            totals = layer_totals;
+        }
+
+        let Some(&from) = layers.first().and_then(|layer| layer.first()) else {
+            return Ok(None);
+        };
+        let mut leg = vec![from];
+        let mut node = from;
+        while let Some(&next) = next_hop.get(&node) {
+            leg.push(next);
+            node = next;
+        }
+        Ok((node == to).then_some(leg))
+    }
+}
+
+/// Search behind `strongest_path`, `strongest_path_with` and
+/// `strongest_path_backward`.
+///
+/// The path runs from `start` through each waypoint to `end`, one leg per
+/// consecutive pair of stops:
+///
+/// 1. A max-bottleneck Dijkstra per leg finds the heaviest weakest hop all
+///    legs allow. Under a hop limit, a binary search over the hop weights
+///    seen lowers it to the heaviest one whose shortest legs fit the limit
+/// 2. A breadth-first search per leg over hops at least that heavy gives
+///    each node its hop count
+/// 3. Walking each leg's layers back from its end picks, per node, the next
+///    hop with the largest remaining total, preferring the lower node id
+///
+/// Legs are independent once the weakest hop is fixed, so their
+/// concatenation is the best path through the waypoints; it may pass a node
+/// in more than one leg. Works with any `GraphStore` implementor, in
+/// O(E log V) per leg without a hop limit.
+fn widest_path<G: GraphStore + ?Sized>(
+    store: &G,
+    start: NodeId,
+    end: NodeId,
+    relation: Option<&Attribute>,
+    direction: Direction,
+    limits: &PathLimits,
+    budget: &mut VisitBudget,
+) -> Result<Option<Vec<NodeId>>, KremisError> {
+    let mut stops = Vec::with_capacity(limits.waypoints.len() + 2);
+    stops.push(start);
+    stops.extend(limits.waypoints.iter().copied());
+    stops.push(end);
+    for &stop in &stops {
+        if !store.contains_node(stop)? || limits.avoid_nodes.contains(&stop) {
+            return Ok(None);
+        }
+    }
+
+    let mut cache = HopCache {
+        store,
+        relation,
+        direction,
+        limits,
+        budget,
+        hops: BTreeMap::new(),
+    };
+
+    // Phase 1: the widest weakest hop every leg allows.
+    let mut bottleneck = limits.cap;
+    for leg in stops.windows(2) {
+        match cache.max_bottleneck(leg[0], leg[1])? {
+            Some(width) => bottleneck = bottleneck.min(width),
+            None => return Ok(None),
+        }
+    }
+
+    // Phase 2: hop counts over hops no weaker than the bottleneck. Only a hop
+    // limit can rule the bottleneck out; lighter thresholds admit more hops,
+    // so the heaviest one that fits is found by binary search.
+    let mut legs = cache.leg_layers(&stops, bottleneck)?;
+    if legs.is_none() {
+        let mut weights: Vec<i64> = cache
+            .hops
+            .values()
+            .flat_map(|targets| targets.values().copied())
+            .filter(|&weight| weight < bottleneck)
+            .collect();
+        weights.sort_unstable();
+        weights.dedup();
+
+        let (mut low, mut high) = (0, weights.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match cache.leg_layers(&stops, weights[mid])? {
+                Some(found) => {
+                    bottleneck = weights[mid];
+                    legs = Some(found);
+                    low = mid + 1;
+                }
+                None => high = mid,
+            }
+        }
+    }
+    let Some(legs) = legs else {
+        return Ok(None);
+    };
+
+    // Phase 3: the heaviest shortest route through each leg.
+    let mut path = vec![start];
+    for (leg, layers) in stops.windows(2).zip(&legs) {
+        let Some(part) = cache.heaviest_leg(layers, leg[1], bottleneck)? else {
+            return Ok(None);
+        };
+        path.extend(part.into_iter().skip(1));
+    }
+    Ok((!cache.budget.is_exhausted()).then_some(path))
+}
+
+/// Build the [`RankedPath`] for a node sequence taken from the graph.
+fn ranked_path<G: GraphStore + ?Sized>(
+    store: &G,
+    nodes: Vec<NodeId>,
+    relation: Option<&Attribute>,
+) -> Result<RankedPath, KremisError> {
+    let mut edges = Vec::with_capacity(nodes.len().saturating_sub(1));
+    let mut weight: i64 = 0;
+    for pair in nodes.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        let (label, edge) = store
+            .strongest_labeled_edge(from, to, relation)?
+            .ok_or(KremisError::EdgeNotFound(from, to))?;
+        weight = weight.saturating_add(edge.value().max(0));
+        edges.push((from, to, label, edge));
+    }
+    Ok(RankedPath {
+        nodes,
+        edges,
+        weight,
+    })
+}
+
+/// Sort key ranking paths the way `strongest_path` does: heaviest weakest
+/// hop, fewest hops, heaviest total, smallest node sequence.
+type StrengthKey = (Reverse<i64>, usize, Reverse<i64>, Vec<NodeId>);
+
+/// The [`StrengthKey`] of a path.
+fn strength_key(path: &RankedPath) -> StrengthKey {
+    let bottleneck = path
+        .edges
+        .iter()
+        .map(|(_, _, _, weight)| weight.value().max(0))
+        .min()
+        .unwrap_or(i64::MAX);
+    (
+        Reverse(bottleneck),
+        path.edges.len(),
+        Reverse(path.weight),
+        path.nodes.clone(),
+    )
+}
 
-        Ok(best_path)
+/// Search behind `k_strongest_paths`: Yen's algorithm over [`widest_path`].
+///
+/// Every spur search starts at a node of the last accepted path, avoids the
+/// prefix before it and the next hops of accepted paths sharing that
+/// prefix, and caps its weakest hop at the weakest hop of the prefix.
+fn yen_strongest_paths<G: GraphStore + ?Sized>(
+    store: &G,
+    start: NodeId,
+    end: NodeId,
+    k: usize,
+    relation: Option<&Attribute>,
+    budget: &mut VisitBudget,
+) -> Result<Option<Vec<RankedPath>>, KremisError> {
+    if !store.contains_node(start)? || !store.contains_node(end)? {
+        return Ok(None);
+    }
+    if k == 0 {
+        return Ok(Some(Vec::new()));
+    }
+    let first = widest_path(
+        store,
+        start,
+        end,
+        relation,
+        Direction::Outgoing,
+        &PathLimits::none(),
+        budget,
+    )?;
+    if budget.is_exhausted() {
+        return Ok(None);
     }
+    let Some(first) = first else {
+        return Ok(Some(Vec::new()));
+    };
 
-    /// Get the total number of nodes.
-    fn node_count(&self) -> Result<usize, KremisError>;
+    let mut accepted = vec![ranked_path(store, first, relation)?];
+    let mut candidates: BTreeMap<StrengthKey, RankedPath> = BTreeMap::new();
 
-    /// Get the total number of edges.
-    fn edge_count(&self) -> Result<usize, KremisError>;
+    while accepted.len() < k {
+        let Some(last) = accepted.last().cloned() else {
+            break;
+        };
+        for spur_index in 0..last.edges.len() {
+            let root = &last.nodes[..=spur_index];
+            let mut limits = PathLimits::none();
+            limits.cap = last.edges[..spur_index]
+                .iter()
+                .map(|(_, _, _, weight)| weight.value().max(0))
+                .min()
+                .unwrap_or(i64::MAX);
+            limits.avoid_nodes = root[..spur_index].iter().copied().collect();
+            for path in &accepted {
+                if path.nodes.len() > spur_index + 1 && path.nodes[..=spur_index] == *root {
+                    limits
+                        .avoid_hops
+                        .insert((root[spur_index], path.nodes[spur_index + 1]));
+                }
+            }
 
-    /// Store a property (attribute, value) for a node.
-    ///
-    /// This persists the full signal data beyond just the entity.
-    ///
-    /// ## Property semantics
-    ///
-    /// Properties follow **set semantics** at the `(attribute, value)` level:
-    /// - Storing the same `(attribute, value)` pair multiple times is idempotent.
-    /// - Different values for the same attribute are accumulated as separate entries
-    ///   (e.g. `("knows", "Bob")` and `("knows", "Charlie")` both persist).
-    ///
-    /// This ensures that repeated ingestion of the same signal does not grow the
-    /// property list unboundedly.
-    fn store_property(
-        &mut self,
-        node: NodeId,
-        attribute: Attribute,
-        value: Value,
-    ) -> Result<(), KremisError>;
+            let spur = widest_path(
+                store,
+                root[spur_index],
+                end,
+                relation,
+                Direction::Outgoing,
+                &limits,
+                budget,
+            )?;
+            if budget.is_exhausted() {
+                return Ok(None);
+            }
+            let Some(spur) = spur else {
+                continue;
+            };
+            let mut nodes = root[..spur_index].to_vec();
+            nodes.extend(spur);
+            if accepted.iter().any(|path| path.nodes == nodes) {
+                continue;
+            }
+            let candidate = ranked_path(store, nodes, relation)?;
+            candidates.insert(strength_key(&candidate), candidate);
+        }
 
-    /// Get all properties for a node.
-    ///
-    /// Returns a list of (Attribute, Value) pairs associated with this node.
-    fn get_properties(&self, node: NodeId) -> Result<Vec<(Attribute, Value)>, KremisError>;
+        let Some((_, best)) = candidates.pop_first() else {
+            break;
+        };
+        accepted.push(best);
+    }
+
+    Ok(Some(accepted))
 }
 
-/// DFS helper for the default `strongest_path` implementation.
-///
-/// Explores simple paths from `current` to `end`, tracking the one with
-/// maximum total weight. Works with any `GraphStore` implementor.
-///
-/// Exploration stops at `MAX_TRAVERSAL_DEPTH` or when `visit_budget` reaches
-/// zero, so on dense graphs the paths explored are a subset of all simple paths.
-#[allow(clippy::too_many_arguments)]
-fn dfs_strongest_path_default<G: GraphStore + ?Sized>(
+/// Search behind `simple_paths`: a depth-first enumeration that only
+/// extends a partial path while `end` is still within `max_hops`.
+fn enumerate_simple_paths<G: GraphStore + ?Sized>(
     store: &G,
-    current: NodeId,
+    start: NodeId,
     end: NodeId,
-    depth: usize,
-    visited: &mut BTreeSet<NodeId>,
-    current_path: &mut Vec<NodeId>,
-    current_weight: i64,
-    best_path: &mut Option<Vec<NodeId>>,
-    best_weight: &mut i64,
-    visit_budget: &mut usize,
-) -> Result<(), KremisError> {
-    use crate::primitives::MAX_TRAVERSAL_DEPTH;
-
-    if depth >= MAX_TRAVERSAL_DEPTH || *visit_budget == 0 {
-        return Ok(());
-    }
-
-    for (neighbor, weight) in store.neighbors(current)? {
-        *visit_budget = visit_budget.saturating_sub(1);
-        if *visit_budget == 0 {
-            return Ok(());
+    max_hops: usize,
+    limit: usize,
+    relation: Option<&Attribute>,
+    budget: &mut VisitBudget,
+) -> Result<Option<Vec<RankedPath>>, KremisError> {
+    use crate::primitives::MAX_PATH_ENUMERATION;
+
+    if !store.contains_node(start)? || !store.contains_node(end)? {
+        return Ok(None);
+    }
+
+    // Hops from each node to `end`, found walking incoming edges back from it.
+    let mut to_end: BTreeMap<NodeId, usize> = BTreeMap::from([(end, 0)]);
+    let mut frontier = vec![end];
+    for distance in 1..=max_hops {
+        let mut next_frontier = Vec::new();
+        for node in frontier {
+            let predecessors = store.predecessors(node, relation)?;
+            if !budget.spend(1 + predecessors.len() as u64) {
+                return Ok(None);
+            }
+            for (previous, _) in predecessors {
+                if let std::collections::btree_map::Entry::Vacant(entry) = to_end.entry(previous) {
+                    entry.insert(distance);
+                    next_frontier.push(previous);
+                }
+            }
         }
+        frontier = next_frontier;
+    }
+    if !to_end.contains_key(&start) {
+        return Ok(Some(Vec::new()));
+    }
 
-        let w = weight.value().max(0);
-        let new_weight = current_weight.saturating_add(w);
+    let mut found = Vec::new();
+    if start == end {
+        found.push(RankedPath {
+            nodes: vec![start],
+            edges: Vec::new(),
+            weight: 0,
+        });
+    }
 
-        if neighbor == end {
-            if new_weight > *best_weight {
-                let mut path = current_path.clone();
-                path.push(end);
-                *best_path = Some(path);
-                *best_weight = new_weight;
+    type Hop = (NodeId, Option<Attribute>, EdgeWeight);
+    let hops_of = |node: NodeId| -> Result<Vec<Hop>, KremisError> {
+        Ok(heaviest_by_target(store.labeled_neighbors(node, relation)?)
+            .into_iter()
+            .filter(|(next, _)| to_end.contains_key(next))
+            .map(|(next, (label, weight))| (next, label, weight))
+            .collect())
+    };
+
+    let mut nodes = vec![start];
+    let mut edges: Vec<LabeledEdge> = Vec::new();
+    let mut on_path = BTreeSet::from([start]);
+    let first_hops = hops_of(start)?;
+    if !budget.spend(1 + first_hops.len() as u64) {
+        return Ok(None);
+    }
+    let mut frames = vec![(first_hops, 0usize)];
+    let mut extended = 0usize;
+
+    while let Some((hops, index)) = frames.last_mut() {
+        let Some((next, label, weight)) = hops.get(*index).cloned() else {
+            frames.pop();
+            if let Some(node) = nodes.pop() {
+                on_path.remove(&node);
             }
+            edges.pop();
+            continue;
+        };
+        *index += 1;
+
+        let within_reach = to_end
+            .get(&next)
+            .is_some_and(|&rest| edges.len() + 1 + rest <= max_hops);
+        if on_path.contains(&next) || !within_reach {
             continue;
         }
+        extended += 1;
+        if extended > MAX_PATH_ENUMERATION {
+            return Err(KremisError::PathEnumerationLimit(MAX_PATH_ENUMERATION));
+        }
 
-        if visited.contains(&neighbor) {
+        let from = nodes[nodes.len() - 1];
+        if next == end {
+            let mut path_nodes = nodes.clone();
+            path_nodes.push(end);
+            let mut path_edges = edges.clone();
+            path_edges.push((from, end, label, weight));
+            let total = path_edges.iter().fold(0i64, |sum, (_, _, _, w)| {
+                sum.saturating_add(w.value().max(0))
+            });
+            found.push(RankedPath {
+                nodes: path_nodes,
+                edges: path_edges,
+                weight: total,
+            });
             continue;
         }
 
-        visited.insert(neighbor);
-        current_path.push(neighbor);
-        dfs_strongest_path_default(
-            store,
-            neighbor,
-            end,
-            depth.saturating_add(1),
-            visited,
-            current_path,
-            new_weight,
-            best_path,
-            best_weight,
-            visit_budget,
-        )?;
-        current_path.pop();
-        visited.remove(&neighbor);
-    }
-
-    Ok(())
+        let hops = hops_of(next)?;
+        if !budget.spend(1 + hops.len() as u64) {
+            return Ok(None);
+        }
+        nodes.push(next);
+        edges.push((from, next, label, weight));
+        on_path.insert(next);
+        frames.push((hops, 0));
+    }
+
+    found.sort_by(|a, b| b.weight.cmp(&a.weight).then_with(|| a.nodes.cmp(&b.nodes)));
+    found.truncate(limit);
+    Ok(Some(found))
 }
 
 // =============================================================================
 // GRAPH IMPLEMENTATION
 // =============================================================================
 
+/// Key of an outgoing edge inside a node's adjacency map: the target node and
+/// the optional relation label. `None` sorts first, so the unlabeled edge to a
+/// target precedes its labeled siblings.
+type EdgeKey = (NodeId, Option<Attribute>);
+
 /// The main Graph structure.
 ///
 /// Uses `BTreeMap` exclusively for deterministic ordering.
@@ -334,8 +1635,12 @@ pub struct Graph {
     /// Node storage: NodeId -> Node
     nodes: BTreeMap<NodeId, Node>,
 
-    /// Adjacency list: from_node -> (to_node -> weight)
-    edges: BTreeMap<NodeId, BTreeMap<NodeId, EdgeWeight>>,
+    /// Adjacency list: from_node -> ((to_node, relation) -> weight)
+    edges: BTreeMap<NodeId, BTreeMap<EdgeKey, EdgeWeight>>,
+
+    /// Reverse adjacency: to_node -> {(from_node, relation)}
+    /// Weights live only in `edges`; this index answers "who points here".
+    incoming: BTreeMap<NodeId, BTreeSet<EdgeKey>>,
 
     /// Reverse lookup: EntityId -> NodeId
     entity_index: BTreeMap<EntityId, NodeId>,
@@ -344,6 +1649,21 @@ pub struct Graph {
     /// Stores the full signal data (attribute, value) for each node.
     properties: BTreeMap<NodeId, BTreeMap<Attribute, Vec<Value>>>,
 
+    /// Inverted property index: (Attribute, Value) -> {NodeId}
+    /// Mirrors `properties`; an entry exists only while some node carries it.
+    property_index: BTreeMap<Attribute, BTreeMap<Value, BTreeSet<NodeId>>>,
+
+    /// Token posting lists: token -> (NodeId -> number of the node's values
+    /// containing the token). The count lets one value be retracted while
+    /// another value of the same node still carries the token.
+    token_index: BTreeMap<String, BTreeMap<NodeId, u64>>,
+
+    /// Entity key registry: key -> EntityId
+    entity_keys: BTreeMap<String, EntityId>,
+
+    /// Inverse of `entity_keys`: EntityId -> key
+    key_of_entity: BTreeMap<EntityId, String>,
+
     /// Next available NodeId
     next_node_id: u64,
 }
@@ -394,11 +1714,13 @@ impl Graph {
             let from = NodeId(ce.from);
             let to = NodeId(ce.to);
             if graph.nodes.contains_key(&from) && graph.nodes.contains_key(&to) {
+                let relation = ce.relation.as_deref().map(Attribute::new);
+                graph.link(from, to, relation.as_ref());
                 graph
                     .edges
                     .entry(from)
                     .or_default()
-                    .insert(to, EdgeWeight::new(ce.weight));
+                    .insert((to, relation), EdgeWeight::new(ce.weight));
             } else {
                 diag.dangling_edges += 1;
             }
@@ -417,6 +1739,10 @@ impl Graph {
             }
         }
 
+        for ck in &canonical.entity_keys {
+            graph.restore_entity_key(&ck.key, EntityId(ck.entity));
+        }
+
         (graph, diag)
     }
 
@@ -426,11 +1752,24 @@ impl Graph {
     }
 
     /// Get all edges in deterministic order.
+    ///
+    /// Relation labels are not reported; an edge pair joined by several
+    /// relations yields one triple per relation. Use
+    /// [`edges_with_relations`](Self::edges_with_relations) to see the labels.
     pub fn edges(&self) -> impl Iterator<Item = (NodeId, NodeId, EdgeWeight)> + '_ {
+        self.edges_with_relations()
+            .map(|(from, to, _relation, weight)| (from, to, weight))
+    }
+
+    /// Get all edges with their relation labels, ordered by
+    /// `(from, to, relation)` with the unlabeled edge first.
+    pub fn edges_with_relations(
+        &self,
+    ) -> impl Iterator<Item = (NodeId, NodeId, Option<&Attribute>, EdgeWeight)> + '_ {
         self.edges.iter().flat_map(|(from, targets)| {
             targets
                 .iter()
-                .map(move |(to, weight)| (*from, *to, *weight))
+                .map(move |((to, relation), weight)| (*from, *to, relation.as_ref(), *weight))
         })
     }
 
@@ -455,27 +1794,42 @@ impl Graph {
             .map(|(entity, node)| (*entity, *node))
     }
 
+    /// Get all entity key bindings, ordered by key.
+    pub fn entity_keys(&self) -> impl Iterator<Item = (&str, EntityId)> + '_ {
+        self.entity_keys
+            .iter()
+            .map(|(key, entity)| (key.as_str(), *entity))
+    }
+
     /// Get the next node ID that would be assigned.
     #[must_use]
     pub fn next_node_id(&self) -> u64 {
         self.next_node_id
     }
 
+    /// Raise the next node ID to `next` if it is currently lower.
+    ///
+    /// Stores never hand out an ID twice, so a deleted node's ID stays
+    /// consumed; rebuilt graphs use this to carry that counter over.
+    pub(crate) fn advance_next_node_id(&mut self, next: u64) {
+        self.next_node_id = self.next_node_id.max(next);
+    }
+
     /// Check if the graph contains a node (internal, non-Result version).
     #[must_use]
     pub fn contains_node_internal(&self, id: NodeId) -> bool {
         self.nodes.contains_key(&id)
     }
 
-    /// Check if the graph contains an edge.
+    /// Check if the graph contains the edge `(from, to, relation)`.
     #[must_use]
-    pub fn contains_edge(&self, from: NodeId, to: NodeId) -> bool {
-        self.edges
-            .get(&from)
-            .is_some_and(|targets| targets.contains_key(&to))
+    pub fn contains_edge(&self, from: NodeId, to: NodeId, relation: Option<&Attribute>) -> bool {
+        self.get_edge_internal(from, to, relation).is_some()
     }
 
     /// Get neighbors (internal, iterator version for efficiency in algorithms).
+    ///
+    /// Follows every outgoing edge regardless of relation.
     pub fn neighbors_internal(
         &self,
         node: NodeId,
@@ -483,13 +1837,85 @@ impl Graph {
         self.edges
             .get(&node)
             .into_iter()
-            .flat_map(|targets| targets.iter().map(|(k, v)| (*k, *v)))
+            .flat_map(|targets| targets.iter().map(|((k, _), v)| (*k, *v)))
+    }
+
+    /// Get the neighbors reached through edges matching `relation`
+    /// (`None`: every edge), in the same order as [`GraphStore::neighbors`].
+    pub fn neighbors_by_relation<'a>(
+        &'a self,
+        node: NodeId,
+        relation: Option<&'a Attribute>,
+    ) -> impl Iterator<Item = (NodeId, EdgeWeight)> + 'a {
+        self.labeled_neighbors_by_relation(node, relation)
+            .map(|(to, _, weight)| (to, weight))
+    }
+
+    /// [`neighbors_by_relation`](Self::neighbors_by_relation) with the
+    /// label of each edge.
+    pub fn labeled_neighbors_by_relation<'a>(
+        &'a self,
+        node: NodeId,
+        relation: Option<&'a Attribute>,
+    ) -> impl Iterator<Item = (NodeId, &'a Option<Attribute>, EdgeWeight)> + 'a {
+        self.edges.get(&node).into_iter().flat_map(move |targets| {
+            targets
+                .iter()
+                .filter(move |((_, label), _)| {
+                    relation.is_none_or(|wanted| label.as_ref() == Some(wanted))
+                })
+                .map(|((k, label), v)| (*k, label, *v))
+        })
+    }
+
+    /// Get the predecessors reached through incoming edges matching
+    /// `relation` (`None`: every edge), in the same order as
+    /// [`GraphStore::predecessors`].
+    pub fn predecessors_by_relation<'a>(
+        &'a self,
+        node: NodeId,
+        relation: Option<&'a Attribute>,
+    ) -> impl Iterator<Item = (NodeId, EdgeWeight)> + 'a {
+        self.incoming
+            .get(&node)
+            .into_iter()
+            .flat_map(move |sources| {
+                sources
+                    .iter()
+                    .filter(move |(_, label)| {
+                        relation.is_none_or(|wanted| label.as_ref() == Some(wanted))
+                    })
+                    .filter_map(move |(from, label)| {
+                        self.get_edge_internal(*from, node, label.as_ref())
+                            .map(|weight| (*from, weight))
+                    })
+            })
     }
 
     /// Get edge weight (internal, non-Result version).
     #[must_use]
-    pub fn get_edge_internal(&self, from: NodeId, to: NodeId) -> Option<EdgeWeight> {
-        self.edges.get(&from)?.get(&to).copied()
+    pub fn get_edge_internal(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Option<EdgeWeight> {
+        self.edges
+            .get(&from)?
+            .get(&(to, relation.cloned()))
+            .copied()
+    }
+
+    /// Get the heaviest edge from `from` to `to` over any relation
+    /// (internal, non-Result version).
+    #[must_use]
+    pub fn strongest_edge_internal(&self, from: NodeId, to: NodeId) -> Option<EdgeWeight> {
+        self.edges
+            .get(&from)?
+            .range((to, None)..)
+            .take_while(|((target, _), _)| *target == to)
+            .map(|(_, weight)| *weight)
+            .max()
     }
 
     /// Import a node with its original NodeId (for export/import operations).
@@ -507,6 +1933,74 @@ impl Graph {
         self.entity_index.insert(node.entity, node.id);
         self.nodes.insert(node.id, node);
     }
+
+    /// Restore a key binding read from a serialized graph.
+    ///
+    /// Bindings with an invalid key, or whose key or entity is already bound,
+    /// are skipped so the registry stays one-to-one even for crafted input.
+    fn restore_entity_key(&mut self, key: &str, entity: EntityId) {
+        if crate::ingestor::Ingestor::validate_entity_key(key).is_err()
+            || self.entity_keys.contains_key(key)
+            || self.key_of_entity.contains_key(&entity)
+        {
+            return;
+        }
+        self.entity_keys.insert(key.to_string(), entity);
+        self.key_of_entity.insert(entity, key.to_string());
+    }
+
+    /// Record `from -> to` under `relation` in the reverse index.
+    fn link(&mut self, from: NodeId, to: NodeId, relation: Option<&Attribute>) {
+        self.incoming
+            .entry(to)
+            .or_default()
+            .insert((from, relation.cloned()));
+    }
+
+    /// Add `value`'s tokens to the posting lists of `node`.
+    fn index_tokens(&mut self, node: NodeId, value: &Value) {
+        for token in crate::search::tokenize(value.as_str()) {
+            let count = self
+                .token_index
+                .entry(token)
+                .or_default()
+                .entry(node)
+                .or_insert(0);
+            *count = count.saturating_add(1);
+        }
+    }
+
+    /// Undo [`index_tokens`](Self::index_tokens) for one value of `node`.
+    fn unindex_tokens(&mut self, node: NodeId, value: &Value) {
+        for token in crate::search::tokenize(value.as_str()) {
+            if let Some(postings) = self.token_index.get_mut(&token) {
+                if let Some(count) = postings.get_mut(&node) {
+                    *count = count.saturating_sub(1);
+                    if *count == 0 {
+                        postings.remove(&node);
+                    }
+                }
+                if postings.is_empty() {
+                    self.token_index.remove(&token);
+                }
+            }
+        }
+    }
+
+    /// Drop `node` from the property index entry for `(attribute, value)`.
+    fn unindex_property(&mut self, node: NodeId, attribute: &Attribute, value: &Value) {
+        if let Some(by_value) = self.property_index.get_mut(attribute) {
+            if let Some(nodes) = by_value.get_mut(value) {
+                nodes.remove(&node);
+                if nodes.is_empty() {
+                    by_value.remove(value);
+                }
+            }
+            if by_value.is_empty() {
+                self.property_index.remove(attribute);
+            }
+        }
+    }
 }
 
 impl GraphStore for Graph {
@@ -531,35 +2025,94 @@ impl GraphStore for Graph {
         &mut self,
         from: NodeId,
         to: NodeId,
+        relation: Option<&Attribute>,
         weight: EdgeWeight,
     ) -> Result<(), KremisError> {
         if !self.nodes.contains_key(&from) || !self.nodes.contains_key(&to) {
             return Ok(());
         }
-        self.edges.entry(from).or_default().insert(to, weight);
+        self.link(from, to, relation);
+        self.edges
+            .entry(from)
+            .or_default()
+            .insert((to, relation.cloned()), weight);
         Ok(())
     }
 
-    fn increment_edge(&mut self, from: NodeId, to: NodeId) -> Result<(), KremisError> {
+    fn increment_edge(
+        &mut self,
+        from: NodeId,
+        to: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<(), KremisError> {
         // Silent no-op when either node is missing — consistent with insert_edge semantics.
         if !self.nodes.contains_key(&from) || !self.nodes.contains_key(&to) {
             return Ok(());
         }
-        let targets = self.edges.entry(from).or_default();
-        let current = targets.get(&to).copied().unwrap_or(EdgeWeight::new(0));
-        targets.insert(to, current.increment());
+        self.link(from, to, relation);
+        let weight = self
+            .edges
+            .entry(from)
+            .or_default()
+            .entry((to, relation.cloned()))
+            .or_insert(EdgeWeight::new(0));
+        *weight = weight.increment();
         Ok(())
     }
 
-    fn decrement_edge(&mut self, from: NodeId, to: NodeId) -> Result<(), KremisError> {
-        let current = self
-            .get_edge(from, to)?
+    fn decrement_edge(
+        &mut self,
+        from: NodeId,
+        to: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<(), KremisError> {
+        let weight = self
+            .edges
+            .get_mut(&from)
+            .and_then(|targets| targets.get_mut(&(to, relation.cloned())))
             .ok_or(KremisError::EdgeNotFound(from, to))?;
-        let new_weight = current.decrement();
-        self.edges.entry(from).or_default().insert(to, new_weight);
+        *weight = weight.decrement();
         Ok(())
     }
 
+    fn remove_node(&mut self, id: NodeId) -> Result<Node, KremisError> {
+        let node = self
+            .nodes
+            .remove(&id)
+            .ok_or(KremisError::NodeNotFound(id))?;
+        self.entity_index.remove(&node.entity);
+        for (attribute, values) in self.properties.remove(&id).into_iter().flatten() {
+            for value in &values {
+                self.unindex_property(id, &attribute, value);
+                self.unindex_tokens(id, value);
+            }
+        }
+
+        for (to, relation) in self
+            .edges
+            .remove(&id)
+            .into_iter()
+            .flat_map(BTreeMap::into_keys)
+        {
+            if let Some(sources) = self.incoming.get_mut(&to) {
+                sources.remove(&(id, relation));
+                if sources.is_empty() {
+                    self.incoming.remove(&to);
+                }
+            }
+        }
+        for (from, relation) in self.incoming.remove(&id).into_iter().flatten() {
+            if let Some(targets) = self.edges.get_mut(&from) {
+                targets.remove(&(id, relation));
+                if targets.is_empty() {
+                    self.edges.remove(&from);
+                }
+            }
+        }
+
+        Ok(node)
+    }
+
     fn lookup(&self, id: NodeId) -> Result<Option<Node>, KremisError> {
         Ok(self.nodes.get(&id).cloned())
     }
@@ -568,27 +2121,106 @@ impl GraphStore for Graph {
         self.entity_index.get(&entity).copied()
     }
 
-    fn get_edge(&self, from: NodeId, to: NodeId) -> Result<Option<EdgeWeight>, KremisError> {
+    fn get_edge(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<Option<EdgeWeight>, KremisError> {
+        Ok(self.get_edge_internal(from, to, relation))
+    }
+
+    fn neighbors(
+        &self,
+        node: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<Vec<(NodeId, EdgeWeight)>, KremisError> {
+        Ok(self.neighbors_by_relation(node, relation).collect())
+    }
+
+    fn predecessors(
+        &self,
+        node: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<Vec<(NodeId, EdgeWeight)>, KremisError> {
+        Ok(self.predecessors_by_relation(node, relation).collect())
+    }
+
+    fn labeled_neighbors(
+        &self,
+        node: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<Vec<(NodeId, Option<Attribute>, EdgeWeight)>, KremisError> {
         Ok(self
-            .edges
-            .get(&from)
-            .and_then(|targets| targets.get(&to).copied()))
+            .labeled_neighbors_by_relation(node, relation)
+            .map(|(to, label, weight)| (to, label.clone(), weight))
+            .collect())
     }
 
-    fn neighbors(&self, node: NodeId) -> Result<Vec<(NodeId, EdgeWeight)>, KremisError> {
+    fn labeled_predecessors(
+        &self,
+        node: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<Vec<(NodeId, Option<Attribute>, EdgeWeight)>, KremisError> {
         Ok(self
-            .edges
+            .incoming
             .get(&node)
             .into_iter()
-            .flat_map(|targets| targets.iter().map(|(k, v)| (*k, *v)))
+            .flatten()
+            .filter(|(_, label)| relation.is_none_or(|wanted| label.as_ref() == Some(wanted)))
+            .filter_map(|(from, label)| {
+                self.get_edge_internal(*from, node, label.as_ref())
+                    .map(|weight| (*from, label.clone(), weight))
+            })
             .collect())
     }
 
+    fn strongest_labeled_edge(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<Option<(Option<Attribute>, EdgeWeight)>, KremisError> {
+        let mut heaviest: Option<(Option<Attribute>, EdgeWeight)> = None;
+        for ((_, label), weight) in self
+            .edges
+            .get(&from)
+            .into_iter()
+            .flat_map(|targets| targets.range((to, None)..))
+            .take_while(|((target, _), _)| *target == to)
+        {
+            if relation.is_some_and(|wanted| label.as_ref() != Some(wanted)) {
+                continue;
+            }
+            if heaviest.as_ref().is_none_or(|(_, best)| weight > best) {
+                heaviest = Some((label.clone(), *weight));
+            }
+        }
+        Ok(heaviest)
+    }
+
+    fn strongest_edge(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<Option<EdgeWeight>, KremisError> {
+        Ok(match relation {
+            Some(_) => self.get_edge_internal(from, to, relation),
+            None => self.strongest_edge_internal(from, to),
+        })
+    }
+
     fn contains_node(&self, id: NodeId) -> Result<bool, KremisError> {
         Ok(self.nodes.contains_key(&id))
     }
 
-    fn traverse(&self, start: NodeId, depth: usize) -> Result<Option<Artifact>, KremisError> {
+    fn traverse(
+        &self,
+        start: NodeId,
+        depth: usize,
+        relation: Option<&Attribute>,
+    ) -> Result<Option<Artifact>, KremisError> {
         let depth = depth.min(crate::primitives::MAX_TRAVERSAL_DEPTH);
         if !self.contains_node_internal(start) {
             return Ok(None);
@@ -609,8 +2241,8 @@ impl GraphStore for Graph {
                 continue;
             }
 
-            for (neighbor, weight) in self.neighbors_internal(current) {
-                subgraph_edges.push((current, neighbor, weight));
+            for (neighbor, label, weight) in self.labeled_neighbors_by_relation(current, relation) {
+                subgraph_edges.push((current, neighbor, label.clone(), weight));
 
                 if !visited.contains(&neighbor) {
                     visited.insert(neighbor);
@@ -627,6 +2259,7 @@ impl GraphStore for Graph {
         start: NodeId,
         depth: usize,
         min_weight: EdgeWeight,
+        relation: Option<&Attribute>,
     ) -> Result<Option<Artifact>, KremisError> {
         let depth = depth.min(crate::primitives::MAX_TRAVERSAL_DEPTH);
         if !self.contains_node_internal(start) {
@@ -648,10 +2281,10 @@ impl GraphStore for Graph {
                 continue;
             }
 
-            for (neighbor, weight) in self.neighbors_internal(current) {
+            for (neighbor, label, weight) in self.labeled_neighbors_by_relation(current, relation) {
                 // Filter by minimum weight
                 if weight.value() >= min_weight.value() {
-                    subgraph_edges.push((current, neighbor, weight));
+                    subgraph_edges.push((current, neighbor, label.clone(), weight));
 
                     if !visited.contains(&neighbor) {
                         visited.insert(neighbor);
@@ -687,49 +2320,14 @@ impl GraphStore for Graph {
         Ok(result.into_iter().collect())
     }
 
-    fn strongest_path(
-        &self,
-        start: NodeId,
-        end: NodeId,
-    ) -> Result<Option<Vec<NodeId>>, KremisError> {
-        if !self.contains_node_internal(start) || !self.contains_node_internal(end) {
-            return Ok(None);
-        }
-
-        if start == end {
-            return Ok(Some(vec![start]));
-        }
-
-        // DFS with backtracking: explore all simple paths, keep the one with
-        // maximum total weight.  Correct for all graph topologies (including
-        // cycles).  Bounded by MAX_TRAVERSAL_DEPTH and MAX_VISIT_COUNT to
-        // prevent DoS.
-        let mut best_path: Option<Vec<NodeId>> = None;
-        let mut best_weight: i64 = i64::MIN;
-        let mut visited = BTreeSet::new();
-        let mut current_path = vec![start];
-        let mut visit_budget = crate::primitives::MAX_VISIT_COUNT;
-        visited.insert(start);
-
-        self.dfs_strongest_path(
-            start,
-            end,
-            0,
-            &mut visited,
-            &mut current_path,
-            0,
-            &mut best_path,
-            &mut best_weight,
-            &mut visit_budget,
-        );
-
-        Ok(best_path)
-    }
-
     fn node_count(&self) -> Result<usize, KremisError> {
         Ok(self.nodes.len())
     }
 
+    fn node_ids(&self) -> Result<Vec<NodeId>, KremisError> {
+        Ok(self.nodes.keys().copied().collect())
+    }
+
     fn edge_count(&self) -> Result<usize, KremisError> {
         Ok(self.edges.values().map(BTreeMap::len).sum())
     }
@@ -770,6 +2368,13 @@ impl GraphStore for Graph {
             ));
         }
 
+        self.property_index
+            .entry(attribute.clone())
+            .or_default()
+            .entry(value.clone())
+            .or_default()
+            .insert(node);
+        self.index_tokens(node, &value);
         self.properties
             .entry(node)
             .or_default()
@@ -779,6 +2384,40 @@ impl GraphStore for Graph {
         Ok(())
     }
 
+    fn remove_property(
+        &mut self,
+        node: NodeId,
+        attribute: &Attribute,
+        value: &Value,
+    ) -> Result<bool, KremisError> {
+        if !self.nodes.contains_key(&node) {
+            return Err(KremisError::NodeNotFound(node));
+        }
+
+        let Some(attrs) = self.properties.get_mut(&node) else {
+            return Ok(false);
+        };
+        let Some(values) = attrs.get_mut(attribute) else {
+            return Ok(false);
+        };
+        let Some(position) = values.iter().position(|v| v == value) else {
+            return Ok(false);
+        };
+        values.remove(position);
+
+        // Drop emptied containers so a retracted node exports exactly like one
+        // that never had the property.
+        if values.is_empty() {
+            attrs.remove(attribute);
+        }
+        if attrs.is_empty() {
+            self.properties.remove(&node);
+        }
+        self.unindex_property(node, attribute, value);
+        self.unindex_tokens(node, value);
+        Ok(true)
+    }
+
     fn get_properties(&self, node: NodeId) -> Result<Vec<(Attribute, Value)>, KremisError> {
         if !self.nodes.contains_key(&node) {
             return Err(KremisError::NodeNotFound(node));
@@ -791,7 +2430,57 @@ impl GraphStore for Graph {
                 }
             }
         }
-        Ok(result)
+        Ok(result)
+    }
+
+    fn find_by_property(
+        &self,
+        attribute: &Attribute,
+        value: &Value,
+        after: Option<NodeId>,
+        limit: usize,
+    ) -> Result<Vec<NodeId>, KremisError> {
+        let Some(nodes) = self
+            .property_index
+            .get(attribute)
+            .and_then(|by_value| by_value.get(value))
+        else {
+            return Ok(Vec::new());
+        };
+        let lower = after.map_or(Bound::Unbounded, Bound::Excluded);
+        Ok(nodes
+            .range((lower, Bound::Unbounded))
+            .take(limit)
+            .copied()
+            .collect())
+    }
+
+    fn token_prefix_matches(&self, prefix: &str) -> Result<BTreeMap<NodeId, bool>, KremisError> {
+        let mut matches = BTreeMap::new();
+        for (token, postings) in self
+            .token_index
+            .range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+            .take_while(|(token, _)| token.starts_with(prefix))
+        {
+            let exact = token == prefix;
+            for &node in postings.keys() {
+                *matches.entry(node).or_insert(false) |= exact;
+            }
+        }
+        Ok(matches)
+    }
+
+    fn entity_for_key(&self, key: &str) -> Result<Option<EntityId>, KremisError> {
+        Ok(self.entity_keys.get(key).copied())
+    }
+
+    fn key_for_entity(&self, entity: EntityId) -> Result<Option<String>, KremisError> {
+        Ok(self.key_of_entity.get(&entity).cloned())
+    }
+
+    fn bind_entity_key(&mut self, key: &str, entity: EntityId) -> Result<(), KremisError> {
+        self.restore_entity_key(key, entity);
+        Ok(())
     }
 }
 
@@ -838,7 +2527,7 @@ impl Graph {
         max_depth: usize,
         visited: &mut BTreeSet<NodeId>,
         path: &mut Vec<NodeId>,
-        subgraph_edges: &mut Vec<(NodeId, NodeId, EdgeWeight)>,
+        subgraph_edges: &mut Vec<LabeledEdge>,
     ) {
         if visited.contains(&current) || current_depth > max_depth {
             return;
@@ -848,8 +2537,8 @@ impl Graph {
         path.push(current);
 
         if current_depth < max_depth {
-            for (neighbor, weight) in self.neighbors_internal(current) {
-                subgraph_edges.push((current, neighbor, weight));
+            for (neighbor, label, weight) in self.labeled_neighbors_by_relation(current, None) {
+                subgraph_edges.push((current, neighbor, label.clone(), weight));
 
                 if !visited.contains(&neighbor) {
                     self.dfs_recursive(
@@ -864,69 +2553,6 @@ impl Graph {
             }
         }
     }
-
-    /// DFS helper for strongest_path: explores simple paths from `current` to
-    /// `end`, tracking the one with maximum total weight. Exploration stops at
-    /// `MAX_TRAVERSAL_DEPTH` or when `visit_budget` reaches zero.
-    #[allow(clippy::too_many_arguments)]
-    fn dfs_strongest_path(
-        &self,
-        current: NodeId,
-        end: NodeId,
-        depth: usize,
-        visited: &mut BTreeSet<NodeId>,
-        current_path: &mut Vec<NodeId>,
-        current_weight: i64,
-        best_path: &mut Option<Vec<NodeId>>,
-        best_weight: &mut i64,
-        visit_budget: &mut usize,
-    ) {
-        use crate::primitives::MAX_TRAVERSAL_DEPTH;
-
-        if depth >= MAX_TRAVERSAL_DEPTH || *visit_budget == 0 {
-            return;
-        }
-
-        for (neighbor, weight) in self.neighbors_internal(current) {
-            *visit_budget = visit_budget.saturating_sub(1);
-            if *visit_budget == 0 {
-                return;
-            }
-
-            let w = weight.value().max(0);
-            let new_weight = current_weight.saturating_add(w);
-
-            if neighbor == end {
-                if new_weight > *best_weight {
-                    let mut path = current_path.clone();
-                    path.push(end);
-                    *best_path = Some(path);
-                    *best_weight = new_weight;
-                }
-                continue;
-            }
-
-            if visited.contains(&neighbor) {
-                continue;
-            }
-
-            visited.insert(neighbor);
-            current_path.push(neighbor);
-            self.dfs_strongest_path(
-                neighbor,
-                end,
-                depth.saturating_add(1),
-                visited,
-                current_path,
-                new_weight,
-                best_path,
-                best_weight,
-                visit_budget,
-            );
-            current_path.pop();
-            visited.remove(&neighbor);
-        }
-    }
 }
 
 // =============================================================================
@@ -950,7 +2576,7 @@ pub struct LoadDiagnostics {
 pub struct SerializableGraph {
     /// Every node in the graph, in deterministic order.
     pub nodes: Vec<Node>,
-    /// Edges as `(source, target, weight)` triples.
+    /// Unlabeled edges as `(source, target, weight)` triples.
     pub edges: Vec<(NodeId, NodeId, EdgeWeight)>,
     /// The identifier the next inserted node will receive.
     pub next_node_id: u64,
@@ -958,6 +2584,14 @@ pub struct SerializableGraph {
     /// which deserialise to an empty vector rather than failing.
     #[serde(default)]
     pub properties: Vec<(u64, String, String)>,
+    /// Labeled edges as `(source, target, relation, weight)`. Absent in older
+    /// payloads, which predate relation labels.
+    #[serde(default)]
+    pub labeled_edges: Vec<(NodeId, NodeId, Attribute, EdgeWeight)>,
+    /// Entity key bindings as `(key, entity)`, ordered by key. Absent in
+    /// older payloads, which predate the key registry.
+    #[serde(default)]
+    pub entity_keys: Vec<(String, EntityId)>,
 }
 
 impl From<&Graph> for SerializableGraph {
@@ -974,11 +2608,24 @@ impl From<&Graph> for SerializableGraph {
                 }
             }
         }
+        let mut edges = Vec::new();
+        let mut labeled_edges = Vec::new();
+        for (from, to, relation, weight) in graph.edges_with_relations() {
+            match relation {
+                Some(relation) => labeled_edges.push((from, to, relation.clone(), weight)),
+                None => edges.push((from, to, weight)),
+            }
+        }
         Self {
             nodes: graph.nodes.values().cloned().collect(),
-            edges: graph.edges().collect(),
+            edges,
             next_node_id: graph.next_node_id,
             properties,
+            labeled_edges,
+            entity_keys: graph
+                .entity_keys()
+                .map(|(key, entity)| (key.to_string(), entity))
+                .collect(),
         }
     }
 }
@@ -1009,7 +2656,15 @@ impl Graph {
             if !graph.nodes.contains_key(&from) || !graph.nodes.contains_key(&to) {
                 diag.dangling_edges += 1;
             } else {
-                let _ = graph.insert_edge(from, to, weight);
+                let _ = graph.insert_edge(from, to, None, weight);
+            }
+        }
+
+        for (from, to, relation, weight) in sg.labeled_edges {
+            if !graph.nodes.contains_key(&from) || !graph.nodes.contains_key(&to) {
+                diag.dangling_edges += 1;
+            } else {
+                let _ = graph.insert_edge(from, to, Some(&relation), weight);
             }
         }
 
@@ -1022,6 +2677,10 @@ impl Graph {
             }
         }
 
+        for (key, entity) in sg.entity_keys {
+            graph.restore_entity_key(&key, entity);
+        }
+
         (graph, diag)
     }
 }
@@ -1112,12 +2771,18 @@ mod tests {
         let b = graph.insert_node(EntityId(2)).expect("insert");
 
         // First increment creates edge with weight 1
-        graph.increment_edge(a, b).expect("increment");
-        assert_eq!(graph.get_edge(a, b).expect("get"), Some(EdgeWeight::new(1)));
+        graph.increment_edge(a, b, None).expect("increment");
+        assert_eq!(
+            graph.get_edge(a, b, None).expect("get"),
+            Some(EdgeWeight::new(1))
+        );
 
         // Second increment increases to 2
-        graph.increment_edge(a, b).expect("increment");
-        assert_eq!(graph.get_edge(a, b).expect("get"), Some(EdgeWeight::new(2)));
+        graph.increment_edge(a, b, None).expect("increment");
+        assert_eq!(
+            graph.get_edge(a, b, None).expect("get"),
+            Some(EdgeWeight::new(2))
+        );
     }
 
     #[test]
@@ -1126,9 +2791,14 @@ mod tests {
         let a = graph.insert_node(EntityId(1)).expect("insert");
         let b = graph.insert_node(EntityId(2)).expect("insert");
 
-        graph.insert_edge(a, b, EdgeWeight::new(5)).expect("insert");
-        graph.decrement_edge(a, b).expect("decrement");
-        assert_eq!(graph.get_edge(a, b).expect("get"), Some(EdgeWeight::new(4)));
+        graph
+            .insert_edge(a, b, None, EdgeWeight::new(5))
+            .expect("insert");
+        graph.decrement_edge(a, b, None).expect("decrement");
+        assert_eq!(
+            graph.get_edge(a, b, None).expect("get"),
+            Some(EdgeWeight::new(4))
+        );
     }
 
     #[test]
@@ -1137,13 +2807,21 @@ mod tests {
         let a = graph.insert_node(EntityId(1)).expect("insert");
         let b = graph.insert_node(EntityId(2)).expect("insert");
 
-        graph.insert_edge(a, b, EdgeWeight::new(1)).expect("insert");
-        graph.decrement_edge(a, b).expect("decrement to 0");
-        assert_eq!(graph.get_edge(a, b).expect("get"), Some(EdgeWeight::new(0)));
+        graph
+            .insert_edge(a, b, None, EdgeWeight::new(1))
+            .expect("insert");
+        graph.decrement_edge(a, b, None).expect("decrement to 0");
+        assert_eq!(
+            graph.get_edge(a, b, None).expect("get"),
+            Some(EdgeWeight::new(0))
+        );
 
         // Further decrements stay at 0
-        graph.decrement_edge(a, b).expect("decrement at 0");
-        assert_eq!(graph.get_edge(a, b).expect("get"), Some(EdgeWeight::new(0)));
+        graph.decrement_edge(a, b, None).expect("decrement at 0");
+        assert_eq!(
+            graph.get_edge(a, b, None).expect("get"),
+            Some(EdgeWeight::new(0))
+        );
     }
 
     #[test]
@@ -1152,7 +2830,7 @@ mod tests {
         let a = graph.insert_node(EntityId(1)).expect("insert");
         let b = graph.insert_node(EntityId(2)).expect("insert");
 
-        let result = graph.decrement_edge(a, b);
+        let result = graph.decrement_edge(a, b, None);
         assert!(matches!(result, Err(KremisError::EdgeNotFound(_, _))));
     }
 
@@ -1162,10 +2840,15 @@ mod tests {
         let a = graph.insert_node(EntityId(1)).expect("insert");
         let b = graph.insert_node(EntityId(2)).expect("insert");
 
-        graph.insert_edge(a, b, EdgeWeight::new(3)).expect("insert");
-        graph.decrement_edge(a, b).expect("decrement");
-        graph.increment_edge(a, b).expect("increment");
-        assert_eq!(graph.get_edge(a, b).expect("get"), Some(EdgeWeight::new(3)));
+        graph
+            .insert_edge(a, b, None, EdgeWeight::new(3))
+            .expect("insert");
+        graph.decrement_edge(a, b, None).expect("decrement");
+        graph.increment_edge(a, b, None).expect("increment");
+        assert_eq!(
+            graph.get_edge(a, b, None).expect("get"),
+            Some(EdgeWeight::new(3))
+        );
     }
 
     #[test]
@@ -1176,11 +2859,15 @@ mod tests {
         let c = graph.insert_node(EntityId(3)).expect("insert");
 
         // Insert edges in non-sorted order
-        graph.insert_edge(a, c, EdgeWeight::new(1)).expect("insert");
-        graph.insert_edge(a, b, EdgeWeight::new(2)).expect("insert");
+        graph
+            .insert_edge(a, c, None, EdgeWeight::new(1))
+            .expect("insert");
+        graph
+            .insert_edge(a, b, None, EdgeWeight::new(2))
+            .expect("insert");
 
         let neighbors: Vec<_> = graph
-            .neighbors(a)
+            .neighbors(a, None)
             .expect("neighbors")
             .into_iter()
             .map(|(n, _)| n)
@@ -1197,11 +2884,15 @@ mod tests {
         let b = graph.insert_node(EntityId(2)).expect("insert");
         let c = graph.insert_node(EntityId(3)).expect("insert");
 
-        graph.insert_edge(a, b, EdgeWeight::new(1)).expect("insert");
-        graph.insert_edge(b, c, EdgeWeight::new(1)).expect("insert");
+        graph
+            .insert_edge(a, b, None, EdgeWeight::new(1))
+            .expect("insert");
+        graph
+            .insert_edge(b, c, None, EdgeWeight::new(1))
+            .expect("insert");
 
         // Depth 1: should reach a and b
-        let artifact = graph.traverse(a, 1).expect("traverse");
+        let artifact = graph.traverse(a, 1, None).expect("traverse");
         assert!(artifact.is_some());
 
         let path = artifact.as_ref().map(|a| &a.path);
@@ -1213,7 +2904,7 @@ mod tests {
     #[test]
     fn traverse_missing_node_returns_none() {
         let graph = Graph::new();
-        let result = graph.traverse(NodeId(999), 5).expect("traverse");
+        let result = graph.traverse(NodeId(999), 5, None).expect("traverse");
         assert!(result.is_none());
     }
 
@@ -1225,13 +2916,13 @@ mod tests {
         let c = graph.insert_node(EntityId(3)).expect("insert");
 
         graph
-            .insert_edge(a, b, EdgeWeight::new(10))
+            .insert_edge(a, b, None, EdgeWeight::new(10))
             .expect("insert");
         graph
-            .insert_edge(b, c, EdgeWeight::new(10))
+            .insert_edge(b, c, None, EdgeWeight::new(10))
             .expect("insert");
 
-        let path = graph.strongest_path(a, c).expect("path");
+        let path = graph.strongest_path(a, c, None).expect("path");
         assert_eq!(path, Some(vec![a, b, c]));
     }
 
@@ -1243,15 +2934,17 @@ mod tests {
         let n4 = graph.insert_node(EntityId(4)).expect("insert");
         let n5 = graph.insert_node(EntityId(5)).expect("insert");
 
-        graph.insert_edge(n3, n4, EdgeWeight::new(1)).expect("edge");
         graph
-            .insert_edge(n3, n5, EdgeWeight::new(50))
+            .insert_edge(n3, n4, None, EdgeWeight::new(1))
             .expect("edge");
         graph
-            .insert_edge(n5, n4, EdgeWeight::new(50))
+            .insert_edge(n3, n5, None, EdgeWeight::new(50))
+            .expect("edge");
+        graph
+            .insert_edge(n5, n4, None, EdgeWeight::new(50))
             .expect("edge");
 
-        let path = graph.strongest_path(n3, n4).expect("path");
+        let path = graph.strongest_path(n3, n4, None).expect("path");
         assert_eq!(path, Some(vec![n3, n5, n4]));
     }
 
@@ -1265,21 +2958,27 @@ mod tests {
         let n4 = graph.insert_node(EntityId(4)).expect("insert");
         let n5 = graph.insert_node(EntityId(5)).expect("insert");
 
-        graph.insert_edge(n2, n3, EdgeWeight::new(2)).expect("edge");
-        graph.insert_edge(n2, n4, EdgeWeight::new(1)).expect("edge");
-        graph.insert_edge(n3, n5, EdgeWeight::new(1)).expect("edge");
         graph
-            .insert_edge(n4, n5, EdgeWeight::new(10))
+            .insert_edge(n2, n3, None, EdgeWeight::new(2))
+            .expect("edge");
+        graph
+            .insert_edge(n2, n4, None, EdgeWeight::new(1))
+            .expect("edge");
+        graph
+            .insert_edge(n3, n5, None, EdgeWeight::new(1))
+            .expect("edge");
+        graph
+            .insert_edge(n4, n5, None, EdgeWeight::new(10))
             .expect("edge");
 
-        let path = graph.strongest_path(n2, n5).expect("path");
+        let path = graph.strongest_path(n2, n5, None).expect("path");
         assert_eq!(path, Some(vec![n2, n4, n5]));
     }
 
     #[test]
     fn strongest_path_dense_graph_completes() {
-        // K_20 complete graph: without a visit budget this would explore ~20! paths.
-        // With the budget it returns a best-effort path almost instantly.
+        // K_20 complete graph has ~20! simple paths; the widest-path search
+        // never enumerates them.
         let mut graph = Graph::new();
         let mut nodes = Vec::new();
         for i in 0..20 {
@@ -1287,17 +2986,345 @@ mod tests {
         }
         for (i, &a) in nodes.iter().enumerate() {
             for &b in &nodes[i + 1..] {
-                graph.insert_edge(a, b, EdgeWeight::new(1)).expect("edge");
+                graph
+                    .insert_edge(a, b, None, EdgeWeight::new(1))
+                    .expect("edge");
             }
         }
         let start = *nodes.first().expect("non-empty");
         let end = *nodes.last().expect("non-empty");
-        let path = graph.strongest_path(start, end).expect("should not error");
+        let path = graph
+            .strongest_path(start, end, None)
+            .expect("should not error");
         let p = path.expect("should find a path");
         assert_eq!(*p.first().expect("non-empty"), start);
         assert_eq!(*p.last().expect("non-empty"), end);
     }
 
+    #[test]
+    fn strongest_path_is_complete_on_dense_graphs() {
+        // The only strong route leaves from the highest-numbered node of a
+        // complete digraph, which a depth-first search reaches last.
+        let mut graph = Graph::new();
+        let nodes: Vec<NodeId> = (0..40)
+            .map(|i| graph.insert_node(EntityId(i)).expect("insert"))
+            .collect();
+        for &from in &nodes {
+            for &to in &nodes {
+                if from != to {
+                    graph.increment_edge(from, to, None).expect("inc");
+                }
+            }
+        }
+        let end = graph.insert_node(EntityId(100)).expect("insert");
+        graph
+            .insert_edge(nodes[1], end, None, EdgeWeight::new(1))
+            .expect("edge");
+        graph
+            .insert_edge(nodes[0], nodes[39], None, EdgeWeight::new(9))
+            .expect("edge");
+        graph
+            .insert_edge(nodes[39], end, None, EdgeWeight::new(9))
+            .expect("edge");
+
+        assert_eq!(
+            graph.strongest_path(nodes[0], end, None).expect("path"),
+            Some(vec![nodes[0], nodes[39], end])
+        );
+    }
+
+    #[test]
+    fn strongest_path_breaks_ties_by_hops_then_total_then_ids() {
+        let mut graph = Graph::new();
+        let [a, b, c, d] = [1, 2, 3, 4].map(|e| graph.insert_node(EntityId(e)).expect("insert"));
+        for (from, to) in [(a, b), (b, d), (a, c), (c, d)] {
+            graph
+                .insert_edge(from, to, None, EdgeWeight::new(5))
+                .expect("edge");
+        }
+        // Equal weakest hop, hop count and total: the lower node ids win.
+        assert_eq!(
+            graph.strongest_path(a, d, None).expect("path"),
+            Some(vec![a, b, d])
+        );
+
+        // Heavier total breaks the tie.
+        graph
+            .insert_edge(c, d, None, EdgeWeight::new(6))
+            .expect("edge");
+        assert_eq!(
+            graph.strongest_path(a, d, None).expect("path"),
+            Some(vec![a, c, d])
+        );
+
+        // A direct hop as strong as the weakest one is shorter, so it wins.
+        graph
+            .insert_edge(a, d, None, EdgeWeight::new(5))
+            .expect("edge");
+        assert_eq!(
+            graph.strongest_path(a, d, None).expect("path"),
+            Some(vec![a, d])
+        );
+        assert_eq!(
+            graph.strongest_path_backward(d, a, None).expect("path"),
+            Some(vec![d, a])
+        );
+    }
+
+    #[test]
+    fn strongest_path_with_honours_constraints() {
+        let mut graph = Graph::new();
+        let [a, b, c, d, g] =
+            [1, 2, 3, 4, 5].map(|e| graph.insert_node(EntityId(e)).expect("insert"));
+        for (from, to, weight) in [
+            (a, g, 10),
+            (g, d, 10),
+            (a, b, 3),
+            (b, c, 3),
+            (c, d, 3),
+            (a, d, 1),
+        ] {
+            graph
+                .insert_edge(from, to, None, EdgeWeight::new(weight))
+                .expect("edge");
+        }
+        let path = |constraints: PathConstraints| {
+            graph
+                .strongest_path_with(a, d, None, &constraints)
+                .expect("path")
+        };
+
+        assert_eq!(path(PathConstraints::default()), Some(vec![a, g, d]));
+        let avoid_gateway = PathConstraints {
+            avoid: BTreeSet::from([g]),
+            ..PathConstraints::default()
+        };
+        assert_eq!(path(avoid_gateway.clone()), Some(vec![a, b, c, d]));
+        // Three hops no longer fit, so the weakest hop drops to the direct one.
+        assert_eq!(
+            path(PathConstraints {
+                max_hops: Some(2),
+                ..avoid_gateway.clone()
+            }),
+            Some(vec![a, d])
+        );
+        assert_eq!(
+            path(PathConstraints {
+                min_weight: Some(EdgeWeight::new(5)),
+                ..avoid_gateway.clone()
+            }),
+            None
+        );
+        assert_eq!(
+            path(PathConstraints {
+                waypoints: vec![c],
+                ..PathConstraints::default()
+            }),
+            Some(vec![a, b, c, d])
+        );
+        assert_eq!(
+            path(PathConstraints {
+                waypoints: vec![b, g],
+                ..PathConstraints::default()
+            }),
+            None
+        );
+        assert_eq!(
+            path(PathConstraints {
+                avoid: BTreeSet::from([a]),
+                ..PathConstraints::default()
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn strongest_path_with_hop_limit_matches_a_full_ranking() {
+        let mut seed: u64 = 0x9E37_79B9_7F4A_7C15;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+
+        for _ in 0..5 {
+            let mut graph = Graph::new();
+            let nodes: Vec<NodeId> = (0..7)
+                .map(|e| graph.insert_node(EntityId(e)).expect("insert"))
+                .collect();
+            for &from in &nodes {
+                for &to in &nodes {
+                    if from != to && next() % 3 == 0 {
+                        let weight = (next() % 6) as i64;
+                        graph
+                            .insert_edge(from, to, None, EdgeWeight::new(weight))
+                            .expect("edge");
+                    }
+                }
+            }
+
+            for &start in &nodes {
+                for &end in &nodes {
+                    for max_hops in 1..=3 {
+                        let best = graph
+                            .simple_paths(start, end, max_hops, usize::MAX, None)
+                            .expect("paths")
+                            .expect("nodes exist")
+                            .into_iter()
+                            .min_by_key(strength_key)
+                            .map(|path| path.nodes);
+                        let constraints = PathConstraints {
+                            max_hops: Some(max_hops),
+                            ..PathConstraints::default()
+                        };
+                        assert_eq!(
+                            graph
+                                .strongest_path_with(start, end, None, &constraints)
+                                .expect("path"),
+                            best,
+                            "{start:?} -> {end:?} in {max_hops} hops"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn simple_paths_order_by_weight_then_ids() {
+        let mut graph = Graph::new();
+        let [a, b, c, d] = [1, 2, 3, 4].map(|e| graph.insert_node(EntityId(e)).expect("insert"));
+        for (from, to, weight) in [
+            (a, b, 5),
+            (b, d, 5),
+            (a, c, 5),
+            (c, d, 5),
+            (a, d, 3),
+            (b, c, 1),
+        ] {
+            graph
+                .insert_edge(from, to, None, EdgeWeight::new(weight))
+                .expect("edge");
+        }
+
+        let paths = graph
+            .simple_paths(a, d, 3, 10, None)
+            .expect("paths")
+            .expect("nodes exist");
+        let listed: Vec<(Vec<NodeId>, i64)> = paths
+            .iter()
+            .map(|path| (path.nodes.clone(), path.weight))
+            .collect();
+        assert_eq!(
+            listed,
+            vec![
+                (vec![a, b, c, d], 11),
+                (vec![a, b, d], 10),
+                (vec![a, c, d], 10),
+                (vec![a, d], 3),
+            ]
+        );
+        assert_eq!(
+            paths[0].edges,
+            vec![
+                (a, b, None, EdgeWeight::new(5)),
+                (b, c, None, EdgeWeight::new(1)),
+                (c, d, None, EdgeWeight::new(5)),
+            ]
+        );
+
+        // The hop limit drops the long route, the limit cuts the tail.
+        let short = graph
+            .simple_paths(a, d, 2, 2, None)
+            .expect("paths")
+            .expect("nodes exist");
+        let nodes: Vec<Vec<NodeId>> = short.into_iter().map(|path| path.nodes).collect();
+        assert_eq!(nodes, vec![vec![a, b, d], vec![a, c, d]]);
+
+        assert_eq!(
+            graph
+                .simple_paths(a, NodeId(99), 3, 10, None)
+                .expect("paths"),
+            None
+        );
+        assert_eq!(
+            graph.simple_paths(d, a, 3, 10, None).expect("paths"),
+            Some(vec![])
+        );
+    }
+
+    #[test]
+    fn simple_paths_reject_enumeration_blowup() {
+        let mut graph = Graph::new();
+        let nodes: Vec<NodeId> = (0..12)
+            .map(|e| graph.insert_node(EntityId(e)).expect("insert"))
+            .collect();
+        for &from in &nodes {
+            for &to in &nodes {
+                if from != to {
+                    graph
+                        .insert_edge(from, to, None, EdgeWeight::new(1))
+                        .expect("edge");
+                }
+            }
+        }
+
+        assert!(matches!(
+            graph.simple_paths(nodes[0], nodes[1], 11, 10, None),
+            Err(KremisError::PathEnumerationLimit(_))
+        ));
+    }
+
+    #[test]
+    fn k_strongest_paths_match_a_full_ranking() {
+        let mut seed: u64 = 0x2545_F491_4F6C_DD1D;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+
+        for _ in 0..5 {
+            let mut graph = Graph::new();
+            let nodes: Vec<NodeId> = (0..7)
+                .map(|e| graph.insert_node(EntityId(e)).expect("insert"))
+                .collect();
+            for &from in &nodes {
+                for &to in &nodes {
+                    if from != to && next() % 2 == 0 {
+                        let weight = (next() % 6) as i64;
+                        graph
+                            .insert_edge(from, to, None, EdgeWeight::new(weight))
+                            .expect("edge");
+                    }
+                }
+            }
+
+            for &start in &nodes {
+                for &end in &nodes {
+                    let mut ranking = graph
+                        .simple_paths(start, end, nodes.len(), usize::MAX, None)
+                        .expect("paths")
+                        .expect("nodes exist");
+                    ranking.sort_by_key(strength_key);
+                    ranking.truncate(6);
+
+                    let best = graph
+                        .k_strongest_paths(start, end, 6, None)
+                        .expect("paths")
+                        .expect("nodes exist");
+                    assert_eq!(best, ranking, "{start:?} -> {end:?}");
+                    assert_eq!(
+                        graph.strongest_path(start, end, None).expect("path"),
+                        best.first().map(|path| path.nodes.clone())
+                    );
+                }
+            }
+        }
+    }
+
     #[test]
     fn intersect_finds_common_neighbors() {
         let mut graph = Graph::new();
@@ -1306,14 +3333,187 @@ mod tests {
         let common = graph.insert_node(EntityId(100)).expect("insert");
 
         graph
-            .insert_edge(a, common, EdgeWeight::new(1))
+            .insert_edge(a, common, None, EdgeWeight::new(1))
             .expect("insert");
         graph
-            .insert_edge(b, common, EdgeWeight::new(1))
+            .insert_edge(b, common, None, EdgeWeight::new(1))
             .expect("insert");
 
-        let result = graph.intersect(&[a, b]).expect("intersect");
-        assert_eq!(result, vec![common]);
+        let result = graph.intersect(&[a, b]).expect("intersect");
+        assert_eq!(result, vec![common]);
+    }
+
+    #[test]
+    fn budgeted_queries_match_unbudgeted_ones_within_budget() {
+        let mut graph = Graph::new();
+        let nodes: Vec<NodeId> = (0..6)
+            .map(|i| graph.insert_node(EntityId(i)).expect("insert"))
+            .collect();
+        for (i, &from) in nodes.iter().enumerate() {
+            for (j, &to) in nodes.iter().enumerate() {
+                if from != to {
+                    let weight = EdgeWeight::new(((i * 7 + j * 3) % 5) as i64);
+                    graph.insert_edge(from, to, None, weight).expect("edge");
+                }
+            }
+        }
+        let constraints = PathConstraints {
+            avoid: BTreeSet::from([nodes[2]]),
+            ..PathConstraints::default()
+        };
+
+        for mut budget in [VisitBudget::unlimited(), VisitBudget::new(10_000)] {
+            assert_eq!(
+                graph
+                    .traverse_within(nodes[0], 3, None, &mut budget)
+                    .expect("traverse"),
+                graph.traverse(nodes[0], 3, None).expect("traverse")
+            );
+            assert_eq!(
+                graph
+                    .traverse_filtered_within(nodes[0], 3, EdgeWeight::new(2), None, &mut budget)
+                    .expect("filtered"),
+                graph
+                    .traverse_filtered(nodes[0], 3, EdgeWeight::new(2), None)
+                    .expect("filtered")
+            );
+            assert_eq!(
+                graph
+                    .intersect_within(&nodes[..3], &mut budget)
+                    .expect("intersect"),
+                graph.intersect(&nodes[..3]).expect("intersect")
+            );
+            assert_eq!(
+                graph
+                    .strongest_path_within(nodes[0], nodes[5], None, &constraints, &mut budget)
+                    .expect("path"),
+                graph
+                    .strongest_path_with(nodes[0], nodes[5], None, &constraints)
+                    .expect("path")
+            );
+            assert_eq!(
+                graph
+                    .reachable_within(nodes[0], nodes[5], None, &mut budget)
+                    .expect("reachable"),
+                graph
+                    .reachable(nodes[0], nodes[5], None)
+                    .expect("reachable")
+            );
+            assert_eq!(
+                graph
+                    .simple_paths_within(nodes[0], nodes[5], 3, 5, None, &mut budget)
+                    .expect("paths"),
+                graph
+                    .simple_paths(nodes[0], nodes[5], 3, 5, None)
+                    .expect("paths")
+            );
+            assert_eq!(
+                graph
+                    .k_strongest_paths_within(nodes[0], nodes[5], 3, None, &mut budget)
+                    .expect("k paths"),
+                graph
+                    .k_strongest_paths(nodes[0], nodes[5], 3, None)
+                    .expect("k paths")
+            );
+            assert!(!budget.is_exhausted());
+        }
+    }
+
+    #[test]
+    fn exhausted_budget_stops_at_the_same_point() {
+        let mut graph = Graph::new();
+        let [a, b, c, d] = [1, 2, 3, 4].map(|e| graph.insert_node(EntityId(e)).expect("insert"));
+        for (from, to) in [(a, b), (b, c), (c, d)] {
+            graph
+                .insert_edge(from, to, None, EdgeWeight::new(5))
+                .expect("edge");
+        }
+
+        // a, a->b, b: the fourth visit (b->c) is over budget.
+        for _ in 0..2 {
+            let mut budget = VisitBudget::new(3);
+            let artifact = graph
+                .traverse_within(a, 10, None, &mut budget)
+                .expect("traverse")
+                .expect("start exists");
+            assert!(budget.is_exhausted());
+            assert_eq!(artifact.path, vec![a, b]);
+            assert_eq!(
+                artifact.subgraph,
+                Some(vec![(a, b, None, EdgeWeight::new(5))])
+            );
+        }
+
+        let mut budget = VisitBudget::new(3);
+        assert_eq!(
+            graph
+                .strongest_path_within(a, d, None, &PathConstraints::default(), &mut budget)
+                .expect("path"),
+            None
+        );
+        assert!(budget.is_exhausted());
+
+        let mut budget = VisitBudget::new(1);
+        assert!(
+            graph
+                .intersect_within(&[a, b], &mut budget)
+                .expect("intersect")
+                .is_empty()
+        );
+        assert!(budget.is_exhausted());
+
+        let mut budget = VisitBudget::new(2);
+        assert_eq!(
+            graph
+                .reachable_within(a, d, None, &mut budget)
+                .expect("reachable"),
+            None
+        );
+        assert!(budget.is_exhausted());
+
+        let mut budget = VisitBudget::new(2);
+        assert_eq!(
+            graph
+                .simple_paths_within(a, d, 5, 5, None, &mut budget)
+                .expect("paths"),
+            None
+        );
+        assert!(budget.is_exhausted());
+    }
+
+    #[test]
+    fn traversal_prefix_is_a_prefix_of_the_full_traversal() {
+        let mut graph = Graph::new();
+        let nodes: Vec<NodeId> = (0..6)
+            .map(|i| graph.insert_node(EntityId(i)).expect("insert"))
+            .collect();
+        for (from, to) in [(0, 1), (0, 2), (1, 3), (2, 4), (4, 5)] {
+            graph
+                .insert_edge(nodes[from], nodes[to], None, EdgeWeight::new(2))
+                .expect("edge");
+        }
+        let full = graph
+            .traverse(nodes[0], 5, None)
+            .expect("traverse")
+            .expect("start exists");
+        let full_edges = full.subgraph.clone().unwrap_or_default();
+
+        for max_nodes in 0..=full.path.len() + 1 {
+            let prefix = graph
+                .traverse_prefix_within(
+                    nodes[0],
+                    5,
+                    None,
+                    None,
+                    max_nodes,
+                    &mut VisitBudget::unlimited(),
+                )
+                .expect("prefix")
+                .expect("start exists");
+            let edges = prefix.subgraph.unwrap_or_default();
+            assert_eq!(prefix.path, full.path[..max_nodes.min(full.path.len())]);
+            assert_eq!(edges, full_edges[..edges.len()]);
+        }
     }
 
     #[test]
@@ -1321,7 +3521,9 @@ mod tests {
         let mut graph = Graph::new();
         let a = graph.insert_node(EntityId(1)).expect("insert");
         let b = graph.insert_node(EntityId(2)).expect("insert");
-        graph.insert_edge(a, b, EdgeWeight::new(5)).expect("insert");
+        graph
+            .insert_edge(a, b, None, EdgeWeight::new(5))
+            .expect("insert");
 
         let serializable = SerializableGraph::from(&graph);
         let restored = Graph::from(serializable);
@@ -1335,7 +3537,7 @@ mod tests {
             restored.edge_count().expect("count")
         );
         assert_eq!(
-            restored.get_edge(a, b).expect("get"),
+            restored.get_edge(a, b, None).expect("get"),
             Some(EdgeWeight::new(5))
         );
     }
@@ -1432,6 +3634,173 @@ mod tests {
         assert!(props.is_empty());
     }
 
+    #[test]
+    fn remove_property_retracts_a_single_pair() {
+        let mut graph = Graph::new();
+        let node = graph.insert_node(EntityId(1)).expect("insert");
+        let knows = Attribute::new("knows");
+        graph
+            .store_property(node, knows.clone(), Value::new("Bob"))
+            .expect("store");
+        graph
+            .store_property(node, knows.clone(), Value::new("Carol"))
+            .expect("store");
+
+        assert!(
+            graph
+                .remove_property(node, &knows, &Value::new("Bob"))
+                .expect("remove")
+        );
+        assert_eq!(
+            graph.get_properties(node).expect("get"),
+            vec![(knows.clone(), Value::new("Carol"))]
+        );
+
+        // Absent pairs are reported, not treated as errors.
+        assert!(
+            !graph
+                .remove_property(node, &knows, &Value::new("Bob"))
+                .expect("remove")
+        );
+        assert!(matches!(
+            graph.remove_property(NodeId(999), &knows, &Value::new("Bob")),
+            Err(KremisError::NodeNotFound(_))
+        ));
+
+        // Retracting the last pair leaves no trace in the serialized form.
+        assert!(
+            graph
+                .remove_property(node, &knows, &Value::new("Carol"))
+                .expect("remove")
+        );
+        let mut fresh = Graph::new();
+        fresh.insert_node(EntityId(1)).expect("insert");
+        assert_eq!(
+            crate::export::export_canonical(&graph).expect("export"),
+            crate::export::export_canonical(&fresh).expect("export")
+        );
+    }
+
+    #[test]
+    fn search_ranks_exact_over_prefix_matches() {
+        let mut graph = Graph::new();
+        let name = Attribute::new("name");
+        let a = graph.insert_node(EntityId(1)).expect("insert");
+        let b = graph.insert_node(EntityId(2)).expect("insert");
+        let c = graph.insert_node(EntityId(3)).expect("insert");
+        graph
+            .store_property(a, name.clone(), Value::new("Ledgers archive"))
+            .expect("store");
+        graph
+            .store_property(b, name.clone(), Value::new("Payment LEDGER"))
+            .expect("store");
+        graph
+            .store_property(c, name.clone(), Value::new("ledger-api"))
+            .expect("store");
+
+        // b and c hold "ledger" exactly; a only by prefix. c also has "api".
+        assert_eq!(
+            graph.search("ledger api", 10).expect("search"),
+            vec![(c, 4), (b, 2), (a, 1)]
+        );
+        assert_eq!(
+            graph.search("LEDG", 2).expect("search"),
+            vec![(a, 1), (b, 1)]
+        );
+        assert!(graph.search("--", 10).expect("search").is_empty());
+
+        // A token stays searchable while any value of the node still holds it.
+        graph
+            .store_property(b, Attribute::new("alias"), Value::new("ledger"))
+            .expect("store");
+        graph
+            .remove_property(b, &name, &Value::new("Payment LEDGER"))
+            .expect("remove");
+        assert_eq!(graph.search("payment", 10).expect("search"), vec![]);
+        assert_eq!(graph.search("ledger", 10).expect("search")[0], (b, 2));
+        graph.remove_node(c).expect("remove");
+        assert_eq!(
+            graph.search("ledger", 10).expect("search"),
+            vec![(b, 2), (a, 1)]
+        );
+    }
+
+    #[test]
+    fn register_entity_key_is_stable_and_probes_past_taken_ids() {
+        use crate::primitives::KEYED_ENTITY_BASE;
+
+        let mut first = Graph::new();
+        let mut second = Graph::new();
+        let ledger = first.register_entity_key("svc:ledger").expect("register");
+        assert!(ledger.0 >= KEYED_ENTITY_BASE);
+        assert_eq!(
+            first.register_entity_key("svc:ledger").expect("again"),
+            ledger
+        );
+
+        // An unkeyed node already holding the derived id pushes the key along.
+        second.insert_node(ledger).expect("insert");
+        let probed = second.register_entity_key("svc:ledger").expect("register");
+        assert_ne!(probed, ledger);
+        assert!(probed.0 >= KEYED_ENTITY_BASE);
+
+        // Bindings outlive the node and survive serialization.
+        let node = first.insert_node(ledger).expect("insert");
+        first.remove_node(node).expect("remove");
+        let restored = Graph::from(SerializableGraph::from(&first));
+        assert_eq!(
+            restored.entity_for_key("svc:ledger").expect("resolve"),
+            Some(ledger)
+        );
+        assert!(first.register_entity_key("").is_err());
+    }
+
+    #[test]
+    fn find_by_property_pages_in_node_order() {
+        let mut graph = Graph::new();
+        let role = Attribute::new("role");
+        let engineer = Value::new("engineer");
+        let nodes: Vec<NodeId> = (1..=5)
+            .map(|e| graph.insert_node(EntityId(e)).expect("insert"))
+            .collect();
+        // Insert out of order; results still come back sorted by NodeId.
+        for &node in nodes.iter().rev() {
+            graph
+                .store_property(node, role.clone(), engineer.clone())
+                .expect("store");
+        }
+        graph
+            .store_property(nodes[0], role.clone(), Value::new("manager"))
+            .expect("store");
+
+        let first = graph
+            .find_by_property(&role, &engineer, None, 2)
+            .expect("find");
+        assert_eq!(first, vec![nodes[0], nodes[1]]);
+        let rest = graph
+            .find_by_property(&role, &engineer, first.last().copied(), 10)
+            .expect("find");
+        assert_eq!(rest, vec![nodes[2], nodes[3], nodes[4]]);
+
+        // The index follows retraction and node deletion.
+        graph
+            .remove_property(nodes[1], &role, &engineer)
+            .expect("remove");
+        graph.remove_node(nodes[2]).expect("remove");
+        assert_eq!(
+            graph
+                .find_by_property(&role, &engineer, None, 10)
+                .expect("find"),
+            vec![nodes[0], nodes[3], nodes[4]]
+        );
+        assert!(
+            graph
+                .find_by_property(&role, &Value::new("intern"), None, 10)
+                .expect("find")
+                .is_empty()
+        );
+    }
+
     #[test]
     fn insert_edge_ignores_dangling_nodes() {
         let mut graph = Graph::new();
@@ -1440,13 +3809,13 @@ mod tests {
 
         // Edge from existing to non-existing: silently ignored
         graph
-            .insert_edge(node1, dangling, EdgeWeight::new(5))
+            .insert_edge(node1, dangling, None, EdgeWeight::new(5))
             .expect("insert edge");
         assert_eq!(graph.edge_count().expect("count"), 0);
 
         // Edge from non-existing to existing: silently ignored
         graph
-            .insert_edge(dangling, node1, EdgeWeight::new(5))
+            .insert_edge(dangling, node1, None, EdgeWeight::new(5))
             .expect("insert edge");
         assert_eq!(graph.edge_count().expect("count"), 0);
     }
@@ -1459,19 +3828,19 @@ mod tests {
 
         // Increment from existing to non-existing: silently ignored, no phantom edge
         graph
-            .increment_edge(node1, dangling)
+            .increment_edge(node1, dangling, None)
             .expect("increment edge");
         assert_eq!(graph.edge_count().expect("count"), 0);
 
         // Increment from non-existing to existing: silently ignored, no phantom edge
         graph
-            .increment_edge(dangling, node1)
+            .increment_edge(dangling, node1, None)
             .expect("increment edge");
         assert_eq!(graph.edge_count().expect("count"), 0);
 
         // Increment between two non-existing nodes: silently ignored
         graph
-            .increment_edge(NodeId(998), dangling)
+            .increment_edge(NodeId(998), dangling, None)
             .expect("increment edge");
         assert_eq!(graph.edge_count().expect("count"), 0);
     }
@@ -1481,7 +3850,9 @@ mod tests {
         let mut graph = Graph::new();
         let a = graph.insert_node(EntityId(1)).expect("insert");
         let b = graph.insert_node(EntityId(2)).expect("insert");
-        graph.insert_edge(a, b, EdgeWeight::new(5)).expect("insert");
+        graph
+            .insert_edge(a, b, None, EdgeWeight::new(5))
+            .expect("insert");
 
         graph
             .store_property(a, Attribute::new("name"), Value::new("Alice"))
@@ -1530,6 +3901,7 @@ mod tests {
             edges: vec![],
             next_node_id: 0, // tampered: below all existing node IDs
             properties: vec![],
+            entity_keys: vec![],
         };
 
         let mut graph = Graph::from_canonical(&canonical);
@@ -1562,6 +3934,8 @@ mod tests {
             edges: vec![],
             next_node_id: 0, // tampered: below all existing node IDs
             properties: vec![],
+            labeled_edges: vec![],
+            entity_keys: vec![],
         };
 
         let mut graph = Graph::from(sg);
@@ -1593,11 +3967,26 @@ mod tests {
             ],
             next_node_id: 2,
             properties: vec![],
+            labeled_edges: vec![
+                (
+                    NodeId(1),
+                    NodeId(0),
+                    Attribute::new("owns"),
+                    EdgeWeight::new(2),
+                ), // valid
+                (
+                    NodeId(7),
+                    NodeId(0),
+                    Attribute::new("owns"),
+                    EdgeWeight::new(2),
+                ), // dangling
+            ],
+            entity_keys: vec![],
         };
 
         let (graph, diag) = Graph::from_serializable(sg);
-        assert_eq!(graph.edge_count().expect("count"), 1);
-        assert_eq!(diag.dangling_edges, 1);
+        assert_eq!(graph.edge_count().expect("count"), 2);
+        assert_eq!(diag.dangling_edges, 2);
         assert_eq!(diag.dangling_properties, 0);
     }
 
@@ -1611,6 +4000,8 @@ mod tests {
                 (0, "name".to_string(), "Alice".to_string()),   // valid
                 (999, "role".to_string(), "admin".to_string()), // dangling
             ],
+            labeled_edges: vec![],
+            entity_keys: vec![],
         };
 
         let (graph, diag) = Graph::from_serializable(sg);
@@ -1631,11 +4022,13 @@ mod tests {
                 crate::export::CanonicalEdge {
                     from: 0,
                     to: 1,
+                    relation: None,
                     weight: 5,
                 },
                 crate::export::CanonicalEdge {
                     from: 0,
                     to: 999,
+                    relation: None,
                     weight: 3,
                 },
             ],
@@ -1645,6 +4038,7 @@ mod tests {
                 attribute: "x".to_string(),
                 value: "y".to_string(),
             }],
+            entity_keys: vec![],
         };
 
         let (graph, diag) = Graph::from_canonical_validated(&canonical);
@@ -1652,4 +4046,391 @@ mod tests {
         assert_eq!(diag.dangling_edges, 1);
         assert_eq!(diag.dangling_properties, 1);
     }
+
+    // =========================================================================
+    // Relation label tests
+    // =========================================================================
+
+    fn labeled_pair() -> (Graph, NodeId, NodeId) {
+        let mut graph = Graph::new();
+        let a = graph.insert_node(EntityId(1)).expect("insert");
+        let b = graph.insert_node(EntityId(2)).expect("insert");
+        (graph, a, b)
+    }
+
+    #[test]
+    fn relations_are_part_of_edge_identity() {
+        let (mut graph, a, b) = labeled_pair();
+        let owns = Attribute::new("owns");
+        let uses = Attribute::new("uses");
+
+        graph.increment_edge(a, b, None).expect("inc");
+        graph
+            .insert_edge(a, b, Some(&owns), EdgeWeight::new(5))
+            .expect("insert");
+        graph.increment_edge(a, b, Some(&uses)).expect("inc");
+        graph.increment_edge(a, b, Some(&uses)).expect("inc");
+
+        assert_eq!(graph.edge_count().expect("count"), 3);
+        assert_eq!(
+            graph.get_edge_internal(a, b, None),
+            Some(EdgeWeight::new(1))
+        );
+        assert_eq!(
+            graph.get_edge_internal(a, b, Some(&owns)),
+            Some(EdgeWeight::new(5))
+        );
+        assert_eq!(
+            graph.get_edge_internal(a, b, Some(&uses)),
+            Some(EdgeWeight::new(2))
+        );
+        assert!(!graph.contains_edge(b, a, Some(&owns)));
+
+        graph.decrement_edge(a, b, Some(&owns)).expect("dec");
+        assert_eq!(
+            graph.get_edge_internal(a, b, Some(&owns)),
+            Some(EdgeWeight::new(4))
+        );
+        assert_eq!(
+            graph.get_edge_internal(a, b, None),
+            Some(EdgeWeight::new(1))
+        );
+        assert!(matches!(
+            graph.decrement_edge(b, a, Some(&owns)),
+            Err(KremisError::EdgeNotFound(_, _))
+        ));
+    }
+
+    #[test]
+    fn neighbors_filter_by_relation() {
+        let (mut graph, a, b) = labeled_pair();
+        let c = graph.insert_node(EntityId(3)).expect("insert");
+        let owns = Attribute::new("owns");
+
+        graph
+            .insert_edge(a, b, None, EdgeWeight::new(1))
+            .expect("e");
+        graph
+            .insert_edge(a, b, Some(&owns), EdgeWeight::new(3))
+            .expect("e");
+        graph
+            .insert_edge(a, c, Some(&Attribute::new("uses")), EdgeWeight::new(2))
+            .expect("e");
+
+        assert_eq!(
+            graph.neighbors(a, None).expect("n"),
+            vec![
+                (b, EdgeWeight::new(1)),
+                (b, EdgeWeight::new(3)),
+                (c, EdgeWeight::new(2))
+            ]
+        );
+        assert_eq!(
+            graph.neighbors(a, Some(&owns)).expect("n"),
+            vec![(b, EdgeWeight::new(3))]
+        );
+        assert_eq!(
+            graph.strongest_edge(a, b, None).expect("s"),
+            Some(EdgeWeight::new(3))
+        );
+        assert_eq!(
+            graph.labeled_neighbors(a, None).expect("n"),
+            vec![
+                (b, None, EdgeWeight::new(1)),
+                (b, Some(owns.clone()), EdgeWeight::new(3)),
+                (c, Some(Attribute::new("uses")), EdgeWeight::new(2))
+            ]
+        );
+        assert_eq!(
+            graph.labeled_predecessors(b, None).expect("p"),
+            vec![
+                (a, None, EdgeWeight::new(1)),
+                (a, Some(owns.clone()), EdgeWeight::new(3))
+            ]
+        );
+        assert_eq!(
+            graph.strongest_labeled_edge(a, b, None).expect("s"),
+            Some((Some(owns.clone()), EdgeWeight::new(3)))
+        );
+        assert_eq!(graph.edges().count(), 3);
+
+        // Of equally heavy edges the unlabeled one is reported.
+        graph
+            .insert_edge(a, b, None, EdgeWeight::new(3))
+            .expect("e");
+        assert_eq!(
+            graph.strongest_labeled_edge(a, b, None).expect("s"),
+            Some((None, EdgeWeight::new(3)))
+        );
+    }
+
+    #[test]
+    fn traversal_and_paths_respect_relation() {
+        let (mut graph, a, b) = labeled_pair();
+        let c = graph.insert_node(EntityId(3)).expect("insert");
+        let depends_on = Attribute::new("depends_on");
+
+        graph
+            .insert_edge(a, b, Some(&depends_on), EdgeWeight::new(1))
+            .expect("e");
+        graph
+            .insert_edge(b, c, None, EdgeWeight::new(9))
+            .expect("e");
+
+        let all = graph.traverse(a, 3, None).expect("t").expect("artifact");
+        assert_eq!(all.path, vec![a, b, c]);
+        assert_eq!(
+            all.subgraph.expect("subgraph"),
+            vec![
+                (a, b, Some(depends_on.clone()), EdgeWeight::new(1)),
+                (b, c, None, EdgeWeight::new(9))
+            ]
+        );
+
+        let only = graph
+            .traverse(a, 3, Some(&depends_on))
+            .expect("t")
+            .expect("artifact");
+        assert_eq!(only.path, vec![a, b]);
+
+        assert_eq!(
+            graph.strongest_path(a, c, None).expect("p"),
+            Some(vec![a, b, c])
+        );
+        assert_eq!(
+            graph.strongest_path(a, c, Some(&depends_on)).expect("p"),
+            None
+        );
+    }
+
+    #[test]
+    fn reachable_explores_dense_graphs_exhaustively() {
+        // A complete digraph plus a tail that is only reachable from its last
+        // node.
+        let mut graph = Graph::new();
+        let nodes: Vec<NodeId> = (0..40)
+            .map(|i| graph.insert_node(EntityId(i)).expect("insert"))
+            .collect();
+        for &from in &nodes {
+            for &to in &nodes {
+                if from != to {
+                    graph.increment_edge(from, to, None).expect("inc");
+                }
+            }
+        }
+        let tail = graph.insert_node(EntityId(100)).expect("insert");
+        let last = nodes[nodes.len() - 1];
+        graph.increment_edge(last, tail, None).expect("inc");
+        let island = graph.insert_node(EntityId(200)).expect("insert");
+
+        assert_eq!(
+            graph.reachable(nodes[0], tail, None).expect("r"),
+            Some(Reachability::Reachable(vec![nodes[0], last, tail]))
+        );
+
+        let Some(Reachability::Unreachable(closure)) =
+            graph.reachable(nodes[0], island, None).expect("r")
+        else {
+            panic!("island must be unreachable");
+        };
+        assert_eq!(closure.path.len(), nodes.len() + 1);
+        assert!(!closure.path.contains(&island));
+        // Every edge leaving the closure stays inside it.
+        let sub = closure.subgraph.expect("subgraph");
+        assert_eq!(sub.len(), 40 * 39 + 1);
+        assert!(sub.iter().all(|(_, to, _, _)| closure.path.contains(to)));
+
+        assert_eq!(graph.reachable(NodeId(999), tail, None).expect("r"), None);
+    }
+
+    #[test]
+    fn reachable_respects_relation() {
+        let (mut graph, a, b) = labeled_pair();
+        let depends_on = Attribute::new("depends_on");
+        graph
+            .insert_edge(a, b, Some(&depends_on), EdgeWeight::new(1))
+            .expect("e");
+
+        assert_eq!(
+            graph.reachable(a, b, Some(&depends_on)).expect("r"),
+            Some(Reachability::Reachable(vec![a, b]))
+        );
+        assert_eq!(
+            graph.reachable(a, a, None).expect("r"),
+            Some(Reachability::Reachable(vec![a]))
+        );
+        let Some(Reachability::Unreachable(closure)) =
+            graph.reachable(b, a, Some(&depends_on)).expect("r")
+        else {
+            panic!("a must be unreachable from b");
+        };
+        assert_eq!(closure.path, vec![b]);
+    }
+
+    // =========================================================================
+    // REVERSE INDEX TESTS
+    // =========================================================================
+
+    #[test]
+    fn predecessors_mirror_outgoing_edges() {
+        let (mut graph, a, b) = labeled_pair();
+        let c = graph.insert_node(EntityId(3)).expect("insert");
+        let owns = Attribute::new("owns");
+
+        graph.increment_edge(b, c, None).expect("inc");
+        graph
+            .insert_edge(a, c, Some(&owns), EdgeWeight::new(4))
+            .expect("insert");
+        graph
+            .insert_edge(a, c, None, EdgeWeight::new(2))
+            .expect("insert");
+
+        assert_eq!(
+            graph.predecessors(c, None).expect("p"),
+            vec![
+                (a, EdgeWeight::new(2)),
+                (a, EdgeWeight::new(4)),
+                (b, EdgeWeight::new(1))
+            ]
+        );
+        assert_eq!(
+            graph.predecessors(c, Some(&owns)).expect("p"),
+            vec![(a, EdgeWeight::new(4))]
+        );
+        assert!(graph.predecessors(a, None).expect("p").is_empty());
+
+        // The index is rebuilt when loading from either persisted form.
+        let (restored, _) = Graph::from_serializable(SerializableGraph::from(&graph));
+        assert_eq!(
+            restored.predecessors(c, None).expect("p"),
+            graph.predecessors(c, None).expect("p")
+        );
+        let canonical = crate::export::CanonicalGraph::from_graph(&graph);
+        assert_eq!(
+            Graph::from_canonical(&canonical)
+                .predecessors(c, None)
+                .expect("p"),
+            graph.predecessors(c, None).expect("p")
+        );
+    }
+
+    #[test]
+    fn backward_traversal_and_paths_follow_incoming_edges() {
+        let (mut graph, a, b) = labeled_pair();
+        let c = graph.insert_node(EntityId(3)).expect("insert");
+        let calls = Attribute::new("calls");
+
+        graph
+            .insert_edge(a, b, Some(&calls), EdgeWeight::new(3))
+            .expect("e");
+        graph
+            .insert_edge(b, c, None, EdgeWeight::new(5))
+            .expect("e");
+
+        let all = graph
+            .traverse_backward(c, 3, None)
+            .expect("t")
+            .expect("artifact");
+        assert_eq!(all.path, vec![c, b, a]);
+        // Edges keep their stored direction and label.
+        assert_eq!(
+            all.subgraph.expect("subgraph"),
+            vec![
+                (b, c, None, EdgeWeight::new(5)),
+                (a, b, Some(calls.clone()), EdgeWeight::new(3))
+            ]
+        );
+
+        let only = graph
+            .traverse_backward(c, 3, Some(&calls))
+            .expect("t")
+            .expect("artifact");
+        assert_eq!(only.path, vec![c]);
+
+        assert_eq!(
+            graph.strongest_path_backward(c, a, None).expect("p"),
+            Some(vec![c, b, a])
+        );
+        assert_eq!(graph.strongest_path_backward(a, c, None).expect("p"), None);
+        assert!(
+            graph
+                .traverse_backward(NodeId(99), 2, None)
+                .expect("t")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn remove_node_cascades_to_incident_edges_and_properties() {
+        let (mut graph, a, b) = labeled_pair();
+        let c = graph.insert_node(EntityId(3)).expect("insert");
+        let owns = Attribute::new("owns");
+
+        graph.increment_edge(a, b, None).expect("e");
+        graph
+            .insert_edge(b, c, Some(&owns), EdgeWeight::new(2))
+            .expect("e");
+        graph.increment_edge(c, b, None).expect("e");
+        graph.increment_edge(b, b, None).expect("e");
+        graph.increment_edge(a, c, None).expect("e");
+        graph
+            .store_property(b, Attribute::new("name"), Value::new("Bob"))
+            .expect("prop");
+
+        let removed = graph.remove_node(b).expect("remove");
+        assert_eq!(removed.entity, EntityId(2));
+
+        assert!(!graph.contains_node_internal(b));
+        assert_eq!(graph.get_node_by_entity(EntityId(2)), None);
+        assert_eq!(graph.entity_of(b), None);
+        assert!(matches!(
+            graph.get_properties(b),
+            Err(KremisError::NodeNotFound(_))
+        ));
+        assert_eq!(graph.edge_count().expect("count"), 1);
+        assert_eq!(
+            graph.neighbors(a, None).expect("n"),
+            vec![(c, EdgeWeight::new(1))]
+        );
+        assert!(graph.neighbors(c, None).expect("n").is_empty());
+        assert_eq!(
+            graph.predecessors(c, None).expect("p"),
+            vec![(a, EdgeWeight::new(1))]
+        );
+        assert!(graph.predecessors(a, None).expect("p").is_empty());
+
+        assert!(matches!(
+            graph.remove_node(b),
+            Err(KremisError::NodeNotFound(id)) if id == b
+        ));
+
+        // Ids are not reused: the entity comes back under a fresh node.
+        let again = graph.insert_node(EntityId(2)).expect("insert");
+        assert_ne!(again, b);
+        assert!(graph.neighbors(again, None).expect("n").is_empty());
+    }
+
+    #[test]
+    fn serializable_graph_roundtrips_labeled_edges() {
+        let (mut graph, a, b) = labeled_pair();
+        let owns = Attribute::new("owns");
+        graph
+            .insert_edge(a, b, None, EdgeWeight::new(1))
+            .expect("e");
+        graph
+            .insert_edge(a, b, Some(&owns), EdgeWeight::new(4))
+            .expect("e");
+
+        let sg = SerializableGraph::from(&graph);
+        assert_eq!(sg.edges.len(), 1);
+        assert_eq!(sg.labeled_edges.len(), 1);
+
+        let (restored, diag) = Graph::from_serializable(sg);
+        assert_eq!(diag, LoadDiagnostics::default());
+        assert_eq!(
+            restored.get_edge_internal(a, b, Some(&owns)),
+            Some(EdgeWeight::new(4))
+        );
+        assert_eq!(restored.edge_count().expect("count"), 2);
+    }
 }
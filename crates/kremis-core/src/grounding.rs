@@ -8,9 +8,11 @@
 //! - Unverifiable claims explicitly marked
 
 use crate::confidence::{ConfidenceScore, compute_confidence, compute_path_confidence};
-use crate::graph::{Graph, GraphStore};
+use crate::graph::{Graph, GraphStore, RankedPath, Reachability};
+use crate::pattern::{PatternMatch, match_pattern};
 use crate::query::{Query, QueryType};
 use crate::{Artifact, NodeId};
+use std::collections::BTreeSet;
 
 /// Result of hypothesis verification.
 ///
@@ -26,6 +28,9 @@ pub struct GroundedResult {
     pub verified: bool,
     /// The evidence path supporting the result.
     pub evidence_path: Vec<NodeId>,
+    /// The query ran out of its visit budget: a traversal artifact is
+    /// partial, and a path or intersection was not found.
+    pub truncated: bool,
 }
 
 impl GroundedResult {
@@ -37,6 +42,7 @@ impl GroundedResult {
             confidence: ConfidenceScore::zero(),
             verified: false,
             evidence_path: Vec::new(),
+            truncated: false,
         }
     }
 
@@ -50,6 +56,7 @@ impl GroundedResult {
             confidence,
             verified,
             evidence_path,
+            truncated: false,
         }
     }
 }
@@ -59,9 +66,14 @@ impl GroundedResult {
 /// This is the main entry point for Core verification.
 /// It executes the query, computes confidence, and annotates
 /// the result with verification status.
+///
+/// `Traverse`, `TraverseFiltered`, `StrongestPath` and `Intersect` run under
+/// the query's visit budget ([`Query::budget`]) and set `truncated` when it
+/// runs out.
 #[must_use]
 pub fn verify_hypothesis(graph: &Graph, query: Query) -> GroundedResult {
-    match query.query_type {
+    let mut budget = query.budget();
+    let mut result = match query.query_type {
         QueryType::Lookup(entity) => {
             if let Some(node_id) = graph.get_node_by_entity(entity) {
                 let artifact = Artifact::with_path(vec![node_id]);
@@ -72,7 +84,11 @@ pub fn verify_hypothesis(graph: &Graph, query: Query) -> GroundedResult {
             }
         }
 
-        QueryType::Traverse { start, depth } => match graph.traverse(start, depth) {
+        QueryType::Traverse {
+            start,
+            depth,
+            ref relation,
+        } => match graph.traverse_within(start, depth, relation.as_ref(), &mut budget) {
             Ok(Some(artifact)) => {
                 let confidence = compute_confidence(&artifact, graph);
                 GroundedResult::with_artifact(artifact, confidence)
@@ -84,7 +100,46 @@ pub fn verify_hypothesis(graph: &Graph, query: Query) -> GroundedResult {
             start,
             depth,
             min_weight,
-        } => match graph.traverse_filtered(start, depth, min_weight) {
+            ref relation,
+        } => match graph.traverse_filtered_within(
+            start,
+            depth,
+            min_weight,
+            relation.as_ref(),
+            &mut budget,
+        ) {
+            Ok(Some(artifact)) => {
+                let confidence = compute_confidence(&artifact, graph);
+                GroundedResult::with_artifact(artifact, confidence)
+            }
+            _ => GroundedResult::unverified(),
+        },
+
+        QueryType::StrongestPath {
+            start,
+            end,
+            ref relation,
+            ref constraints,
+        } => match graph.strongest_path_within(
+            start,
+            end,
+            relation.as_ref(),
+            constraints,
+            &mut budget,
+        ) {
+            Ok(Some(path)) => {
+                let confidence = compute_path_confidence(&path, graph);
+                let artifact = Artifact::with_path(path);
+                GroundedResult::with_artifact(artifact, confidence)
+            }
+            _ => GroundedResult::unverified(),
+        },
+
+        QueryType::TraverseBackward {
+            start,
+            depth,
+            ref relation,
+        } => match graph.traverse_backward(start, depth, relation.as_ref()) {
             Ok(Some(artifact)) => {
                 let confidence = compute_confidence(&artifact, graph);
                 GroundedResult::with_artifact(artifact, confidence)
@@ -92,8 +147,27 @@ pub fn verify_hypothesis(graph: &Graph, query: Query) -> GroundedResult {
             _ => GroundedResult::unverified(),
         },
 
-        QueryType::StrongestPath { start, end } => match graph.strongest_path(start, end) {
+        QueryType::StrongestPathBackward {
+            start,
+            end,
+            ref relation,
+        } => match graph.strongest_path_backward(start, end, relation.as_ref()) {
             Ok(Some(path)) => {
+                // Score the hops in their stored direction.
+                let forward: Vec<_> = path.iter().rev().copied().collect();
+                let confidence = compute_path_confidence(&forward, graph);
+                let artifact = Artifact::with_path(path);
+                GroundedResult::with_artifact(artifact, confidence)
+            }
+            _ => GroundedResult::unverified(),
+        },
+
+        QueryType::Reachable {
+            start,
+            end,
+            ref relation,
+        } => match graph.reachable(start, end, relation.as_ref()) {
+            Ok(Some(Reachability::Reachable(path))) => {
                 let confidence = compute_path_confidence(&path, graph);
                 let artifact = Artifact::with_path(path);
                 GroundedResult::with_artifact(artifact, confidence)
@@ -101,7 +175,57 @@ pub fn verify_hypothesis(graph: &Graph, query: Query) -> GroundedResult {
             _ => GroundedResult::unverified(),
         },
 
-        QueryType::Intersect(ref nodes) => match graph.intersect(nodes) {
+        QueryType::Paths {
+            start,
+            end,
+            max_hops,
+            limit,
+            ref relation,
+        } => match graph.simple_paths(start, end, max_hops, limit, relation.as_ref()) {
+            Ok(Some(paths)) => ground_paths(&paths, graph),
+            _ => GroundedResult::unverified(),
+        },
+
+        QueryType::KStrongestPaths {
+            start,
+            end,
+            k,
+            ref relation,
+        } => match graph.k_strongest_paths(start, end, k, relation.as_ref()) {
+            Ok(Some(paths)) => ground_paths(&paths, graph),
+            _ => GroundedResult::unverified(),
+        },
+
+        QueryType::Pattern { ref pattern, limit } => match match_pattern(graph, pattern, limit) {
+            Ok(matches) => ground_pattern(&matches, graph),
+            Err(_) => GroundedResult::unverified(),
+        },
+
+        QueryType::FindByProperty {
+            ref attribute,
+            ref value,
+            after,
+            limit,
+        } => match graph.find_by_property(attribute, value, after, limit) {
+            Ok(nodes) if !nodes.is_empty() => {
+                // Matches are stored properties, as certain as a lookup.
+                let confidence = ConfidenceScore::new(100, 0, nodes.len());
+                GroundedResult::with_artifact(Artifact::with_path(nodes), confidence)
+            }
+            _ => GroundedResult::unverified(),
+        },
+
+        QueryType::Search { ref query, limit } => match graph.search(query, limit) {
+            Ok(ranked) if !ranked.is_empty() => {
+                // Every hit carries a stored value containing a query token.
+                let path: Vec<NodeId> = ranked.into_iter().map(|(node, _)| node).collect();
+                let confidence = ConfidenceScore::new(100, 0, path.len());
+                GroundedResult::with_artifact(Artifact::with_path(path), confidence)
+            }
+            _ => GroundedResult::unverified(),
+        },
+
+        QueryType::Intersect(ref nodes) => match graph.intersect_within(nodes, &mut budget) {
             Ok(common) if !common.is_empty() => {
                 let artifact = Artifact::with_path(common);
                 let confidence = compute_confidence(&artifact, graph);
@@ -118,7 +242,39 @@ pub fn verify_hypothesis(graph: &Graph, query: Query) -> GroundedResult {
                 GroundedResult::unverified()
             }
         }
-    }
+    };
+    result.truncated = budget.is_exhausted();
+    result
+}
+
+/// Ground a list of paths: the first one is the trace and sets the
+/// confidence, the union of all their hops is the subgraph.
+fn ground_paths(paths: &[RankedPath], graph: &Graph) -> GroundedResult {
+    let Some(best) = paths.first() else {
+        return GroundedResult::unverified();
+    };
+    let confidence = compute_path_confidence(&best.nodes, graph);
+    let edges: BTreeSet<_> = paths
+        .iter()
+        .flat_map(|path| path.edges.iter().cloned())
+        .collect();
+    let artifact = Artifact::with_subgraph(best.nodes.clone(), edges.into_iter().collect());
+    GroundedResult::with_artifact(artifact, confidence)
+}
+
+/// Ground pattern bindings: the first binding is the trace, the union of
+/// every binding's edges is the subgraph.
+fn ground_pattern(matches: &[PatternMatch], graph: &Graph) -> GroundedResult {
+    let Some(first) = matches.first() else {
+        return GroundedResult::unverified();
+    };
+    let edges: BTreeSet<_> = matches
+        .iter()
+        .flat_map(|binding| binding.edges.iter().cloned())
+        .collect();
+    let artifact = Artifact::with_subgraph(first.nodes.clone(), edges.into_iter().collect());
+    let confidence = compute_confidence(&artifact, graph);
+    GroundedResult::with_artifact(artifact, confidence)
 }
 
 // =============================================================================
@@ -128,7 +284,7 @@ pub fn verify_hypothesis(graph: &Graph, query: Query) -> GroundedResult {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{EdgeWeight, EntityId};
+    use crate::{Attribute, EdgeWeight, EntityId, Value};
 
     #[test]
     fn verify_lookup_existing() {
@@ -155,18 +311,70 @@ mod tests {
         assert_eq!(result.confidence.score, 0);
     }
 
+    #[test]
+    fn verify_find_by_property() {
+        let mut graph = Graph::new();
+        let a = graph.insert_node(EntityId(1)).expect("insert");
+        graph
+            .store_property(a, Attribute::new("role"), Value::new("engineer"))
+            .expect("store");
+
+        let query = Query::new(QueryType::FindByProperty {
+            attribute: Attribute::new("role"),
+            value: Value::new("engineer"),
+            after: None,
+            limit: 10,
+        });
+        let result = verify_hypothesis(&graph, query);
+
+        assert!(result.verified);
+        assert_eq!(result.evidence_path, vec![a]);
+        assert_eq!(result.confidence.score, 100);
+    }
+
     #[test]
     fn verify_traverse() {
         let mut graph = Graph::new();
         let a = graph.insert_node(EntityId(1)).expect("insert");
         let b = graph.insert_node(EntityId(2)).expect("insert");
-        graph.insert_edge(a, b, EdgeWeight::new(5)).expect("insert");
+        graph
+            .insert_edge(a, b, None, EdgeWeight::new(5))
+            .expect("insert");
 
         let query = Query::traverse(a, 2);
         let result = verify_hypothesis(&graph, query);
 
         assert!(result.artifact.is_some());
         assert!(result.confidence.score >= 50);
+        assert!(!result.truncated);
+    }
+
+    #[test]
+    fn verify_traverse_honours_the_visit_budget() {
+        let mut graph = Graph::new();
+        let a = graph.insert_node(EntityId(1)).expect("insert");
+        let b = graph.insert_node(EntityId(2)).expect("insert");
+        let c = graph.insert_node(EntityId(3)).expect("insert");
+        for (from, to) in [(a, b), (b, c)] {
+            graph
+                .insert_edge(from, to, None, EdgeWeight::new(5))
+                .expect("insert");
+        }
+
+        let query = Query::with_timeout(
+            QueryType::Traverse {
+                start: a,
+                depth: 5,
+                relation: None,
+            },
+            3,
+        );
+        let result = verify_hypothesis(&graph, query);
+        assert!(result.truncated);
+        assert_eq!(result.evidence_path, vec![a, b]);
+
+        let query = Query::with_timeout(QueryType::Intersect(vec![a, b]), 1);
+        assert!(verify_hypothesis(&graph, query).truncated);
     }
 
     #[test]
@@ -176,10 +384,10 @@ mod tests {
         let b = graph.insert_node(EntityId(2)).expect("insert");
         let c = graph.insert_node(EntityId(3)).expect("insert");
         graph
-            .insert_edge(a, b, EdgeWeight::new(10))
+            .insert_edge(a, b, None, EdgeWeight::new(10))
             .expect("insert");
         graph
-            .insert_edge(b, c, EdgeWeight::new(10))
+            .insert_edge(b, c, None, EdgeWeight::new(10))
             .expect("insert");
 
         let query = Query::strongest_path(a, c);
@@ -190,6 +398,58 @@ mod tests {
         assert!(!result.evidence_path.is_empty());
     }
 
+    #[test]
+    fn verify_strongest_path_backward() {
+        let mut graph = Graph::new();
+        let a = graph.insert_node(EntityId(1)).expect("insert");
+        let b = graph.insert_node(EntityId(2)).expect("insert");
+        graph
+            .insert_edge(a, b, None, EdgeWeight::new(10))
+            .expect("insert");
+
+        let query = Query::new(QueryType::StrongestPathBackward {
+            start: b,
+            end: a,
+            relation: None,
+        });
+        let result = verify_hypothesis(&graph, query);
+
+        assert!(result.verified);
+        assert_eq!(result.evidence_path, vec![b, a]);
+        assert_eq!(result.confidence.evidence_count, 1);
+    }
+
+    #[test]
+    fn verify_pattern() {
+        use crate::pattern::{Pattern, PatternEdge, PatternVariable};
+
+        let mut graph = Graph::new();
+        let a = graph.insert_node(EntityId(1)).expect("insert");
+        let b = graph.insert_node(EntityId(2)).expect("insert");
+        graph
+            .insert_edge(a, b, None, EdgeWeight::new(4))
+            .expect("insert");
+
+        let pattern = Pattern {
+            variables: vec![PatternVariable::default(); 2],
+            edges: vec![PatternEdge {
+                from: 0,
+                to: 1,
+                relation: None,
+                min_weight: None,
+            }],
+        };
+        let query = Query::new(QueryType::Pattern { pattern, limit: 10 });
+        let result = verify_hypothesis(&graph, query);
+
+        assert_eq!(result.evidence_path, vec![a, b]);
+        let artifact = result.artifact.expect("artifact");
+        assert_eq!(
+            artifact.subgraph,
+            Some(vec![(a, b, None, EdgeWeight::new(4))])
+        );
+    }
+
     #[test]
     fn verify_intersect() {
         let mut graph = Graph::new();
@@ -197,10 +457,10 @@ mod tests {
         let b = graph.insert_node(EntityId(2)).expect("insert");
         let common = graph.insert_node(EntityId(100)).expect("insert");
         graph
-            .insert_edge(a, common, EdgeWeight::new(1))
+            .insert_edge(a, common, None, EdgeWeight::new(1))
             .expect("insert");
         graph
-            .insert_edge(b, common, EdgeWeight::new(1))
+            .insert_edge(b, common, None, EdgeWeight::new(1))
             .expect("insert");
 
         let query = Query::intersect(vec![a, b]);
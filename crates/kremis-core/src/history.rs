@@ -0,0 +1,458 @@
+//! # History Module
+//!
+//! Mutation log entries for point-in-time ("as of") reads.
+//!
+//! - Every committed write transaction of a `RedbGraph` is numbered with the
+//!   next mutation sequence number and logged as one [`MutationRecord`]
+//! - Records hold absolute results (the edge weight after the change), so
+//!   replaying them in order onto an empty `Graph` rebuilds the state at any
+//!   logged sequence number
+//! - Replay goes through the ordinary `GraphStore` methods, so the rebuilt
+//!   graph exports, hashes and answers queries exactly like a live one
+//! - A `LogWindow` collects what a stretch of records touched and
+//!   `BaseValues` walks the earlier records back to find what those values
+//!   were before it, which is all a delta needs; neither state is rebuilt
+
+use crate::graph::{Graph, GraphStore};
+use crate::{Attribute, EdgeWeight, EntityId, KremisError, Node, NodeId, Value};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "crypto-hash")]
+use crate::export::CanonicalProperty;
+#[cfg(feature = "crypto-hash")]
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A single logged change to the graph.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Mutation {
+    /// A node was created for `entity`.
+    InsertNode {
+        /// The new node.
+        node: NodeId,
+        /// The entity it represents.
+        entity: EntityId,
+    },
+    /// An edge was created or its weight changed.
+    SetEdge {
+        /// Source node.
+        from: NodeId,
+        /// Target node.
+        to: NodeId,
+        /// Relation label, `None` for an association edge.
+        relation: Option<Attribute>,
+        /// Weight after the change.
+        weight: EdgeWeight,
+    },
+    /// A property pair was added to a node.
+    StoreProperty {
+        /// The node carrying the property.
+        node: NodeId,
+        /// Property attribute.
+        attribute: Attribute,
+        /// Property value.
+        value: Value,
+    },
+    /// A property pair was retracted from a node.
+    RemoveProperty {
+        /// The node that carried the property.
+        node: NodeId,
+        /// Property attribute.
+        attribute: Attribute,
+        /// Property value.
+        value: Value,
+    },
+    /// A node was deleted with its incident edges and properties.
+    RemoveNode {
+        /// The deleted node.
+        node: NodeId,
+    },
+    /// An entity key was bound in the key registry.
+    BindEntityKey {
+        /// The external key.
+        key: String,
+        /// The entity it names.
+        entity: EntityId,
+    },
+}
+
+/// Everything one write transaction changed, in the order it happened.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MutationRecord {
+    /// The store's next node id once the transaction committed.
+    pub next_node_id: u64,
+    /// The changes made by the transaction.
+    pub mutations: Vec<Mutation>,
+}
+
+impl MutationRecord {
+    /// Apply the record to `graph`.
+    ///
+    /// Returns `KremisError::StorageCorruption` if the record does not fit
+    /// the graph, which means the log was replayed out of order or damaged.
+    pub fn apply(&self, graph: &mut Graph) -> Result<(), KremisError> {
+        for mutation in &self.mutations {
+            match mutation {
+                Mutation::InsertNode { node, entity } => {
+                    graph.import_node(Node::new(*node, *entity));
+                }
+                Mutation::SetEdge {
+                    from,
+                    to,
+                    relation,
+                    weight,
+                } => graph.insert_edge(*from, *to, relation.as_ref(), *weight)?,
+                Mutation::StoreProperty {
+                    node,
+                    attribute,
+                    value,
+                } => graph
+                    .store_property(*node, attribute.clone(), value.clone())
+                    .map_err(|e| replay_error(&e))?,
+                Mutation::RemoveProperty {
+                    node,
+                    attribute,
+                    value,
+                } => {
+                    graph
+                        .remove_property(*node, attribute, value)
+                        .map_err(|e| replay_error(&e))?;
+                }
+                Mutation::RemoveNode { node } => {
+                    graph.remove_node(*node).map_err(|e| replay_error(&e))?;
+                }
+                Mutation::BindEntityKey { key, entity } => graph.bind_entity_key(key, *entity)?,
+            }
+        }
+        graph.advance_next_node_id(self.next_node_id);
+        Ok(())
+    }
+}
+
+/// An edge as the log names it: `(from, to, relation)`.
+#[cfg(feature = "crypto-hash")]
+pub(crate) type EdgeKey = (u64, u64, Option<String>);
+
+/// Everything a stretch of consecutive log records touched.
+#[cfg(feature = "crypto-hash")]
+#[derive(Debug, Default)]
+pub(crate) struct LogWindow {
+    /// Nodes inserted or removed.
+    pub(crate) nodes: BTreeSet<u64>,
+    /// Edges whose weight was set.
+    pub(crate) edges: BTreeSet<EdgeKey>,
+    /// Property pairs stored or retracted.
+    pub(crate) properties: BTreeSet<CanonicalProperty>,
+    /// Entity keys bound.
+    pub(crate) entity_keys: BTreeSet<String>,
+}
+
+#[cfg(feature = "crypto-hash")]
+impl LogWindow {
+    /// Add the next record of the window.
+    pub(crate) fn add(&mut self, record: &MutationRecord) {
+        for mutation in &record.mutations {
+            match mutation {
+                Mutation::InsertNode { node, .. } | Mutation::RemoveNode { node } => {
+                    self.nodes.insert(node.0);
+                }
+                Mutation::SetEdge {
+                    from, to, relation, ..
+                } => {
+                    self.edges.insert(edge_key(*from, *to, relation.as_ref()));
+                }
+                Mutation::StoreProperty {
+                    node,
+                    attribute,
+                    value,
+                }
+                | Mutation::RemoveProperty {
+                    node,
+                    attribute,
+                    value,
+                } => {
+                    self.properties
+                        .insert(property_key(*node, attribute, value));
+                }
+                Mutation::BindEntityKey { key, .. } => {
+                    self.entity_keys.insert(key.clone());
+                }
+            }
+        }
+    }
+}
+
+/// The values a [`LogWindow`] touched, as they were right before it.
+///
+/// Fed the records before the window newest first, the first mutation found
+/// for a value decides it: an edge weight or property set before the window
+/// stands unless a later insert or removal of its node came in between. A
+/// node touched in the window also gets every edge and property it had, so
+/// a node the window removed takes them out of the delta with it.
+///
+/// Entity keys are bound once and never unbound, so a key the window bound
+/// was unbound before it and needs no lookup.
+#[cfg(feature = "crypto-hash")]
+#[derive(Debug)]
+pub(crate) struct BaseValues {
+    /// Touched nodes, with their entity if they existed.
+    nodes: BTreeMap<u64, Option<u64>>,
+    /// Touched nodes not resolved yet.
+    open_nodes: BTreeSet<u64>,
+    /// Nodes inserted or removed in the records walked so far, or not
+    /// allocated yet; older mutations on them no longer matter.
+    settled: BTreeSet<u64>,
+    /// Edge weights found, `None` for an edge that did not exist.
+    edges: BTreeMap<EdgeKey, Option<i64>>,
+    /// Touched edges not resolved yet.
+    open_edges: BTreeSet<EdgeKey>,
+    /// Touched edges not resolved yet, by endpoint.
+    edges_by_node: BTreeMap<u64, Vec<EdgeKey>>,
+    /// Property pairs found, with whether they were held.
+    properties: BTreeMap<CanonicalProperty, bool>,
+    /// Touched property pairs not resolved yet.
+    open_properties: BTreeSet<CanonicalProperty>,
+}
+
+#[cfg(feature = "crypto-hash")]
+impl BaseValues {
+    /// Start resolving `window`, which follows the record whose next node id
+    /// was `next_node_id`.
+    pub(crate) fn new(window: &LogWindow, next_node_id: u64) -> Self {
+        let mut base = Self {
+            nodes: BTreeMap::new(),
+            open_nodes: BTreeSet::new(),
+            settled: BTreeSet::new(),
+            edges: BTreeMap::new(),
+            open_edges: BTreeSet::new(),
+            edges_by_node: BTreeMap::new(),
+            properties: BTreeMap::new(),
+            open_properties: BTreeSet::new(),
+        };
+        for &node in &window.nodes {
+            if node < next_node_id {
+                base.open_nodes.insert(node);
+            } else {
+                base.nodes.insert(node, None);
+            }
+        }
+        for key in &window.edges {
+            base.open_edges.insert(key.clone());
+            base.edges_by_node
+                .entry(key.0)
+                .or_default()
+                .push(key.clone());
+            if key.1 != key.0 {
+                base.edges_by_node
+                    .entry(key.1)
+                    .or_default()
+                    .push(key.clone());
+            }
+        }
+        base.open_properties = window.properties.clone();
+        for node in window.nodes.range(next_node_id..) {
+            base.settle(*node);
+        }
+        base
+    }
+
+    /// Whether every touched value is resolved, so older records can be
+    /// skipped.
+    pub(crate) fn is_resolved(&self) -> bool {
+        self.open_nodes.is_empty() && self.open_edges.is_empty() && self.open_properties.is_empty()
+    }
+
+    /// Walk the next older record. Returns [`is_resolved`](Self::is_resolved).
+    pub(crate) fn rewind(&mut self, record: &MutationRecord) -> bool {
+        for mutation in record.mutations.iter().rev() {
+            match mutation {
+                Mutation::InsertNode { node, entity } => {
+                    if self.open_nodes.remove(&node.0) {
+                        self.nodes.insert(node.0, Some(entity.0));
+                    }
+                    self.settle(node.0);
+                }
+                Mutation::RemoveNode { node } => {
+                    if self.open_nodes.remove(&node.0) {
+                        self.nodes.insert(node.0, None);
+                    }
+                    self.settle(node.0);
+                }
+                Mutation::SetEdge {
+                    from,
+                    to,
+                    relation,
+                    weight,
+                } => {
+                    if self.settled.contains(&from.0) || self.settled.contains(&to.0) {
+                        continue;
+                    }
+                    let key = edge_key(*from, *to, relation.as_ref());
+                    let wanted = self.open_edges.remove(&key)
+                        || self.open_nodes.contains(&from.0)
+                        || self.open_nodes.contains(&to.0);
+                    if wanted {
+                        self.edges.entry(key).or_insert(Some(weight.value()));
+                    }
+                }
+                Mutation::StoreProperty {
+                    node,
+                    attribute,
+                    value,
+                } => self.property(*node, attribute, value, true),
+                Mutation::RemoveProperty {
+                    node,
+                    attribute,
+                    value,
+                } => self.property(*node, attribute, value, false),
+                Mutation::BindEntityKey { .. } => {}
+            }
+        }
+        self.is_resolved()
+    }
+
+    /// The entity of a touched node, or `None` if it did not exist.
+    pub(crate) fn node(&self, id: u64) -> Option<u64> {
+        self.nodes.get(&id).copied().flatten()
+    }
+
+    /// The weight of an edge found, or `None` if it did not exist.
+    ///
+    /// Only meaningful for touched edges and edges of touched nodes, and only
+    /// if both endpoints existed.
+    pub(crate) fn edge(&self, key: &EdgeKey) -> Option<i64> {
+        self.edges.get(key).copied().flatten()
+    }
+
+    /// Whether a property pair was held, with the same caveats as
+    /// [`edge`](Self::edge).
+    pub(crate) fn has_property(&self, property: &CanonicalProperty) -> bool {
+        self.properties.get(property) == Some(&true)
+    }
+
+    /// Every edge resolved: touched ones and those found on touched nodes.
+    pub(crate) fn edge_keys(&self) -> impl Iterator<Item = &EdgeKey> {
+        self.edges.keys()
+    }
+
+    /// Every property pair resolved, like [`edge_keys`](Self::edge_keys).
+    pub(crate) fn property_keys(&self) -> impl Iterator<Item = &CanonicalProperty> {
+        self.properties.keys()
+    }
+
+    fn property(&mut self, node: NodeId, attribute: &Attribute, value: &Value, held: bool) {
+        if self.settled.contains(&node.0) {
+            return;
+        }
+        let key = property_key(node, attribute, value);
+        if self.open_properties.remove(&key) || self.open_nodes.contains(&node.0) {
+            self.properties.entry(key).or_insert(held);
+        }
+    }
+
+    /// Mark `node` inserted or removed: what older records did to it and its
+    /// edges and properties no longer matters.
+    fn settle(&mut self, node: u64) {
+        if !self.settled.insert(node) {
+            return;
+        }
+        for key in self.edges_by_node.remove(&node).unwrap_or_default() {
+            if self.open_edges.remove(&key) {
+                self.edges.insert(key, None);
+            }
+        }
+        let first = CanonicalProperty {
+            node_id: node,
+            attribute: String::new(),
+            value: String::new(),
+        };
+        let done: Vec<CanonicalProperty> = self
+            .open_properties
+            .range(first..)
+            .take_while(|p| p.node_id == node)
+            .cloned()
+            .collect();
+        for key in done {
+            self.open_properties.remove(&key);
+            self.properties.insert(key, false);
+        }
+    }
+}
+
+#[cfg(feature = "crypto-hash")]
+fn edge_key(from: NodeId, to: NodeId, relation: Option<&Attribute>) -> EdgeKey {
+    (from.0, to.0, relation.map(|r| r.as_str().to_string()))
+}
+
+#[cfg(feature = "crypto-hash")]
+fn property_key(node: NodeId, attribute: &Attribute, value: &Value) -> CanonicalProperty {
+    CanonicalProperty {
+        node_id: node.0,
+        attribute: attribute.as_str().to_string(),
+        value: value.as_str().to_string(),
+    }
+}
+
+/// Report a mutation the replayed graph rejected.
+fn replay_error(error: &KremisError) -> KremisError {
+    KremisError::StorageCorruption(format!("mutation log does not replay: {error}"))
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replay_rebuilds_nodes_edges_and_counter() {
+        let records = [
+            MutationRecord {
+                next_node_id: 2,
+                mutations: vec![
+                    Mutation::InsertNode {
+                        node: NodeId(0),
+                        entity: EntityId(10),
+                    },
+                    Mutation::InsertNode {
+                        node: NodeId(1),
+                        entity: EntityId(11),
+                    },
+                    Mutation::SetEdge {
+                        from: NodeId(0),
+                        to: NodeId(1),
+                        relation: None,
+                        weight: EdgeWeight::new(3),
+                    },
+                ],
+            },
+            MutationRecord {
+                next_node_id: 2,
+                mutations: vec![Mutation::RemoveNode { node: NodeId(1) }],
+            },
+        ];
+
+        let mut graph = Graph::new();
+        for record in &records {
+            record.apply(&mut graph).expect("apply");
+        }
+
+        assert_eq!(graph.node_count().expect("count"), 1);
+        assert_eq!(graph.edge_count().expect("count"), 0);
+        // The counter is not rewound by the deletion.
+        assert_eq!(graph.next_node_id(), 2);
+    }
+
+    #[test]
+    fn replay_out_of_order_is_reported() {
+        let record = MutationRecord {
+            next_node_id: 0,
+            mutations: vec![Mutation::RemoveNode { node: NodeId(5) }],
+        };
+        assert!(matches!(
+            record.apply(&mut Graph::new()),
+            Err(KremisError::StorageCorruption(_))
+        ));
+    }
+}
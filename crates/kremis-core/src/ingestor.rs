@@ -9,9 +9,75 @@
 
 use crate::graph::GraphStore;
 use crate::primitives::{
-    ASSOCIATION_WINDOW, MAX_ATTRIBUTE_LENGTH, MAX_SEQUENCE_LENGTH, MAX_VALUE_LENGTH,
+    ASSOCIATION_WINDOW, KEYED_ENTITY_BASE, MAX_ASSOCIATION_WINDOW, MAX_ATTRIBUTE_LENGTH,
+    MAX_ENTITY_KEY_LENGTH, MAX_EXPLICIT_LINKS, MAX_SEQUENCE_LENGTH, MAX_VALUE_LENGTH,
 };
-use crate::{KremisError, NodeId, Signal};
+use crate::{Attribute, EntityId, KremisError, NodeId, Signal};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// How a batch of signals is turned into association edges.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LinkMode {
+    /// Link each signal from the `N` signals before it in the batch, with
+    /// every edge labeled by the relation if one is given.
+    ///
+    /// `Window(0, _)` creates no edges.
+    Window(usize, Option<Attribute>),
+    /// Link exactly these `(from, to, relation)` entities, ignoring batch
+    /// order.
+    ///
+    /// Each entity must appear in the batch or already have a node.
+    Explicit(Vec<(EntityId, EntityId, Option<Attribute>)>),
+}
+
+impl Default for LinkMode {
+    fn default() -> Self {
+        Self::Window(ASSOCIATION_WINDOW, None)
+    }
+}
+
+impl LinkMode {
+    /// Check the mode against the linking limits.
+    ///
+    /// Returns `KremisError::InvalidSignal` for a window above
+    /// `MAX_ASSOCIATION_WINDOW`, more than `MAX_EXPLICIT_LINKS` links, or a
+    /// relation that fails [`Ingestor::validate_relation`].
+    pub fn validate(&self) -> Result<(), KremisError> {
+        let within_limits = match self {
+            Self::Window(window, _) => *window <= MAX_ASSOCIATION_WINDOW,
+            Self::Explicit(links) => links.len() <= MAX_EXPLICIT_LINKS,
+        };
+        if !within_limits {
+            return Err(KremisError::InvalidSignal);
+        }
+        match self {
+            Self::Window(_, relation) => relation.iter().try_for_each(Ingestor::validate_relation),
+            Self::Explicit(links) => links
+                .iter()
+                .filter_map(|(_, _, relation)| relation.as_ref())
+                .try_for_each(Ingestor::validate_relation),
+        }
+    }
+
+    /// Get the `(from, to, relation)` links to create for `signals`, in the
+    /// order their edges are incremented.
+    #[must_use]
+    pub fn pairs(&self, signals: &[Signal]) -> Vec<(EntityId, EntityId, Option<Attribute>)> {
+        match self {
+            Self::Window(window, relation) => signals
+                .iter()
+                .enumerate()
+                .flat_map(|(i, current)| {
+                    signals[i.saturating_sub(*window)..i]
+                        .iter()
+                        .map(|prev| (prev.entity, current.entity, relation.clone()))
+                })
+                .collect(),
+            Self::Explicit(links) => links.clone(),
+        }
+    }
+}
 
 /// The Ingestor handles signal validation and graph ingestion.
 ///
@@ -36,23 +102,9 @@ impl Ingestor {
     ///
     /// Returns `KremisError::InvalidSignal` if validation fails.
     pub fn validate(signal: &Signal) -> Result<(), KremisError> {
-        let attr = signal.attribute.as_str();
         let val = signal.value.as_str();
 
-        // Attribute must be non-empty
-        if attr.is_empty() {
-            return Err(KremisError::InvalidSignal);
-        }
-
-        // Attribute length check
-        if attr.len() > MAX_ATTRIBUTE_LENGTH {
-            return Err(KremisError::InvalidSignal);
-        }
-
-        // Attributes are identifiers: no control characters at all
-        if attr.chars().any(char::is_control) {
-            return Err(KremisError::InvalidSignal);
-        }
+        Self::validate_identifier(signal.attribute.as_str())?;
 
         // Value must be non-empty
         if val.is_empty() {
@@ -75,6 +127,88 @@ impl Ingestor {
         Ok(())
     }
 
+    /// Validate an edge relation label.
+    ///
+    /// Relations follow the attribute rules: non-empty, at most
+    /// `MAX_ATTRIBUTE_LENGTH` bytes, no control characters.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KremisError::InvalidSignal` if validation fails.
+    pub fn validate_relation(relation: &Attribute) -> Result<(), KremisError> {
+        Self::validate_identifier(relation.as_str())
+    }
+
+    /// Validate an entity id given by a client as a raw number.
+    ///
+    /// Ids from `KEYED_ENTITY_BASE` up belong to entity keys, so a raw id
+    /// there is rejected wherever it would allocate a node; see
+    /// [`Ingestor::validate_entity`] for the check against a store.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KremisError::InvalidSignal` if the id is in the keyed range.
+    pub fn validate_raw_entity_id(entity: EntityId) -> Result<(), KremisError> {
+        if entity.0 >= KEYED_ENTITY_BASE {
+            return Err(KremisError::InvalidSignal);
+        }
+        Ok(())
+    }
+
+    /// Validate the entity of a signal against the store it is ingested into.
+    ///
+    /// An id in the keyed range is only accepted once a key is bound to it,
+    /// so ingestion never creates a keyed-range entity without its key.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KremisError::InvalidSignal` for an unbound keyed-range id.
+    pub fn validate_entity<G: GraphStore>(graph: &G, entity: EntityId) -> Result<(), KremisError> {
+        if Self::validate_raw_entity_id(entity).is_err() && graph.key_for_entity(entity)?.is_none()
+        {
+            return Err(KremisError::InvalidSignal);
+        }
+        Ok(())
+    }
+
+    /// Validate an entity key.
+    ///
+    /// Keys are non-empty, at most `MAX_ENTITY_KEY_LENGTH` bytes, and free of
+    /// control characters.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KremisError::InvalidSignal` if validation fails.
+    pub fn validate_entity_key(key: &str) -> Result<(), KremisError> {
+        if key.is_empty() || key.len() > MAX_ENTITY_KEY_LENGTH {
+            return Err(KremisError::InvalidSignal);
+        }
+        if key.chars().any(char::is_control) {
+            return Err(KremisError::InvalidSignal);
+        }
+        Ok(())
+    }
+
+    /// Shared rules for attributes and relation labels, which are identifiers.
+    fn validate_identifier(ident: &str) -> Result<(), KremisError> {
+        // Must be non-empty
+        if ident.is_empty() {
+            return Err(KremisError::InvalidSignal);
+        }
+
+        // Length check
+        if ident.len() > MAX_ATTRIBUTE_LENGTH {
+            return Err(KremisError::InvalidSignal);
+        }
+
+        // Identifiers: no control characters at all
+        if ident.chars().any(char::is_control) {
+            return Err(KremisError::InvalidSignal);
+        }
+
+        Ok(())
+    }
+
     /// Ingest a single signal into any graph store.
     ///
     /// Works with both in-memory Graph and persistent RedbGraph.
@@ -93,6 +227,7 @@ impl Ingestor {
         signal: &Signal,
     ) -> Result<NodeId, KremisError> {
         Self::validate(signal)?;
+        Self::validate_entity(graph, signal.entity)?;
 
         // Get or create node for the entity
         let node_id = graph.insert_node(signal.entity)?;
@@ -118,6 +253,26 @@ impl Ingestor {
     pub fn ingest_sequence<G: GraphStore>(
         graph: &mut G,
         signals: &[Signal],
+    ) -> Result<Vec<NodeId>, KremisError> {
+        Self::ingest_sequence_with(graph, signals, &LinkMode::default())
+    }
+
+    /// Ingest a sequence of signals, creating the edges `links` asks for.
+    ///
+    /// All signals are ingested first, then every pair from
+    /// [`LinkMode::pairs`] increments its association edge by 1.
+    ///
+    /// # Errors
+    /// Returns `KremisError::InvalidSignal`, before touching the graph, if:
+    /// - The sequence exceeds `MAX_SEQUENCE_LENGTH`
+    /// - Any signal in the sequence is invalid
+    /// - `links` fails [`LinkMode::validate`]
+    /// - An explicit link names an entity that is neither in the batch nor
+    ///   already in the graph
+    pub fn ingest_sequence_with<G: GraphStore>(
+        graph: &mut G,
+        signals: &[Signal],
+        links: &LinkMode,
     ) -> Result<Vec<NodeId>, KremisError> {
         if signals.is_empty() {
             return Ok(Vec::new());
@@ -128,27 +283,49 @@ impl Ingestor {
             return Err(KremisError::InvalidSignal);
         }
 
+        for signal in signals {
+            Self::validate(signal)?;
+            Self::validate_entity(graph, signal.entity)?;
+        }
+        links.validate()?;
+        let pairs = links.pairs(signals);
+        Self::check_link_endpoints(&pairs, signals, |entity| {
+            graph.get_node_by_entity(entity).is_some()
+        })?;
+
         let mut node_ids = Vec::with_capacity(signals.len());
+        for signal in signals {
+            node_ids.push(Self::ingest_signal(graph, signal)?);
+        }
 
-        // Ingest first signal
-        let first_node = Self::ingest_signal(graph, &signals[0])?;
-        node_ids.push(first_node);
+        for (from, to, relation) in pairs {
+            if let (Some(from_node), Some(to_node)) =
+                (graph.get_node_by_entity(from), graph.get_node_by_entity(to))
+            {
+                graph.increment_edge(from_node, to_node, relation.as_ref())?;
+            }
+        }
 
-        // Ingest remaining signals with edge creation
-        for window in signals.windows(ASSOCIATION_WINDOW + 1) {
-            let current_signal = &window[window.len() - 1];
-            let current_node = Self::ingest_signal(graph, current_signal)?;
-            node_ids.push(current_node);
+        Ok(node_ids)
+    }
 
-            // Create edges from all previous signals in window to current
-            for prev_signal in window.iter().take(window.len() - 1) {
-                if let Some(prev_node) = graph.get_node_by_entity(prev_signal.entity) {
-                    graph.increment_edge(prev_node, current_node)?;
+    /// Check that every linked entity is in the batch or already has a node.
+    ///
+    /// `known` reports whether the store already holds a node for an entity.
+    pub(crate) fn check_link_endpoints(
+        pairs: &[(EntityId, EntityId, Option<Attribute>)],
+        signals: &[Signal],
+        known: impl Fn(EntityId) -> bool,
+    ) -> Result<(), KremisError> {
+        let in_batch: BTreeSet<EntityId> = signals.iter().map(|s| s.entity).collect();
+        for &(from, to, _) in pairs {
+            for entity in [from, to] {
+                if !in_batch.contains(&entity) && !known(entity) {
+                    return Err(KremisError::InvalidSignal);
                 }
             }
         }
-
-        Ok(node_ids)
+        Ok(())
     }
 
     /// Check if a signal would be a duplicate.
@@ -222,6 +399,24 @@ mod tests {
         assert!(Ingestor::validate(&signal).is_ok());
     }
 
+    #[test]
+    fn validate_relation_follows_attribute_rules() {
+        assert!(Ingestor::validate_relation(&Attribute::new("depends_on")).is_ok());
+        assert!(Ingestor::validate_relation(&Attribute::new("")).is_err());
+        assert!(Ingestor::validate_relation(&Attribute::new("a\nb")).is_err());
+        let too_long = "r".repeat(MAX_ATTRIBUTE_LENGTH + 1);
+        assert!(Ingestor::validate_relation(&Attribute::new(too_long)).is_err());
+    }
+
+    #[test]
+    fn validate_entity_key_rejects_empty_long_and_control() {
+        assert!(Ingestor::validate_entity_key("svc:ledger-api").is_ok());
+        assert!(Ingestor::validate_entity_key("").is_err());
+        assert!(Ingestor::validate_entity_key("a\tb").is_err());
+        let too_long = "k".repeat(MAX_ENTITY_KEY_LENGTH + 1);
+        assert!(Ingestor::validate_entity_key(&too_long).is_err());
+    }
+
     #[test]
     fn ingest_signal_creates_node() {
         let mut graph = Graph::new();
@@ -246,9 +441,19 @@ mod tests {
 
         assert_eq!(nodes.len(), 3);
         // Edge from node 0 to node 1
-        assert!(graph.get_edge(nodes[0], nodes[1]).expect("get").is_some());
+        assert!(
+            graph
+                .get_edge(nodes[0], nodes[1], None)
+                .expect("get")
+                .is_some()
+        );
         // Edge from node 1 to node 2
-        assert!(graph.get_edge(nodes[1], nodes[2]).expect("get").is_some());
+        assert!(
+            graph
+                .get_edge(nodes[1], nodes[2], None)
+                .expect("get")
+                .is_some()
+        );
     }
 
     #[test]
@@ -327,4 +532,129 @@ mod tests {
         assert_eq!(props2[0].0.as_str(), "name");
         assert_eq!(props2[0].1.as_str(), "Bob");
     }
+
+    #[test]
+    fn window_links_each_signal_to_the_ones_before_it() {
+        let signals = vec![
+            make_signal(1, "type", "word"),
+            make_signal(2, "type", "word"),
+            make_signal(3, "type", "word"),
+        ];
+        assert_eq!(
+            LinkMode::Window(2, None).pairs(&signals),
+            vec![
+                (EntityId(1), EntityId(2), None),
+                (EntityId(1), EntityId(3), None),
+                (EntityId(2), EntityId(3), None),
+            ]
+        );
+        assert!(LinkMode::Window(0, None).pairs(&signals).is_empty());
+        assert!(
+            LinkMode::Window(MAX_ASSOCIATION_WINDOW + 1, None)
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn explicit_links_ignore_batch_order() {
+        let mut graph = Graph::new();
+        let existing =
+            Ingestor::ingest_signal(&mut graph, &make_signal(9, "name", "db")).expect("ingest");
+        let signals = vec![make_signal(1, "name", "api"), make_signal(2, "name", "web")];
+        let links = LinkMode::Explicit(vec![
+            (EntityId(2), EntityId(1), None),
+            (EntityId(1), EntityId(9), None),
+        ]);
+
+        let nodes = Ingestor::ingest_sequence_with(&mut graph, &signals, &links).expect("ingest");
+
+        assert_eq!(graph.get_edge(nodes[0], nodes[1], None).expect("get"), None);
+        assert!(
+            graph
+                .get_edge(nodes[1], nodes[0], None)
+                .expect("get")
+                .is_some()
+        );
+        assert!(
+            graph
+                .get_edge(nodes[0], existing, None)
+                .expect("get")
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn explicit_link_to_unknown_entity_rejects_the_batch() {
+        let mut graph = Graph::new();
+        let signals = vec![make_signal(1, "name", "api")];
+        let links = LinkMode::Explicit(vec![(EntityId(1), EntityId(42), None)]);
+
+        assert!(matches!(
+            Ingestor::ingest_sequence_with(&mut graph, &signals, &links),
+            Err(KremisError::InvalidSignal)
+        ));
+        assert_eq!(graph.node_count().expect("count"), 0);
+    }
+
+    #[test]
+    fn links_carry_their_relation_onto_the_edge() {
+        let mut graph = Graph::new();
+        let signals = vec![make_signal(1, "name", "api"), make_signal(2, "name", "db")];
+        let owns = Attribute::new("owns");
+
+        let nodes = Ingestor::ingest_sequence_with(
+            &mut graph,
+            &signals,
+            &LinkMode::Window(1, Some(Attribute::new("calls"))),
+        )
+        .expect("ingest");
+        Ingestor::ingest_sequence_with(
+            &mut graph,
+            &signals,
+            &LinkMode::Explicit(vec![(EntityId(2), EntityId(1), Some(owns.clone()))]),
+        )
+        .expect("ingest");
+
+        assert_eq!(graph.get_edge(nodes[0], nodes[1], None).expect("get"), None);
+        assert!(
+            graph
+                .get_edge(nodes[0], nodes[1], Some(&Attribute::new("calls")))
+                .expect("get")
+                .is_some()
+        );
+        assert!(
+            graph
+                .get_edge(nodes[1], nodes[0], Some(&owns))
+                .expect("get")
+                .is_some()
+        );
+        assert!(
+            LinkMode::Explicit(vec![(EntityId(1), EntityId(2), Some(Attribute::new("")))])
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn raw_ids_in_the_keyed_range_are_rejected() {
+        use crate::primitives::KEYED_ENTITY_BASE;
+
+        let mut graph = Graph::new();
+        let raw = Signal::new(
+            EntityId(KEYED_ENTITY_BASE),
+            Attribute::new("name"),
+            Value::new("x"),
+        );
+        assert!(Ingestor::validate_raw_entity_id(raw.entity).is_err());
+        assert!(Ingestor::validate_raw_entity_id(EntityId(KEYED_ENTITY_BASE - 1)).is_ok());
+        assert!(Ingestor::ingest_signal(&mut graph, &raw).is_err());
+        assert!(Ingestor::ingest_sequence(&mut graph, std::slice::from_ref(&raw)).is_err());
+        assert_eq!(graph.node_count().expect("count"), 0);
+
+        // The same range is fine once a key owns the id.
+        let keyed = graph.register_entity_key("svc:ledger").expect("register");
+        let signal = Signal::new(keyed, Attribute::new("name"), Value::new("Ledger"));
+        assert!(Ingestor::ingest_signal(&mut graph, &signal).is_ok());
+    }
 }
@@ -0,0 +1,246 @@
+//! # Journal Module
+//!
+//! The append-only journal of accepted inputs.
+//!
+//! - A `RedbGraph` appends one [`JournalEntry`] per accepted session call, and
+//!   per direct `GraphStore` mutation, in the same transaction as the change
+//!   itself
+//! - Entries hold the inputs (signals, edge and node ids), not their effects;
+//!   the mutation log in [`crate::history`] holds the effects
+//! - [`replay`] feeds the entries back through an in-memory `Session`, so a
+//!   matching canonical hash shows the stored graph follows from its inputs
+
+use crate::export::CanonicalGraph;
+use crate::graph::{Graph, GraphStore};
+use crate::ingestor::LinkMode;
+use crate::merge::MergePolicy;
+use crate::session::Session;
+use crate::{Attribute, EdgeWeight, EntityId, KremisError, NodeId, Signal, Value};
+use serde::{Deserialize, Serialize};
+
+/// One accepted session call, or one direct `GraphStore` mutation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JournalEntry {
+    /// One `ingest` or `ingest_sequence` call; the signals form one batch.
+    Ingest(Vec<Signal>),
+    /// One `ingest_sequence_with` call that did not use the default linking.
+    IngestLinked {
+        /// The batch.
+        signals: Vec<Signal>,
+        /// How the batch was linked.
+        links: LinkMode,
+    },
+    /// A `decrement_edge` call.
+    DecrementEdge {
+        /// Source node.
+        from: NodeId,
+        /// Target node.
+        to: NodeId,
+        /// Relation label, `None` for an association edge.
+        relation: Option<Attribute>,
+    },
+    /// A `remove_node` call.
+    RemoveNode(NodeId),
+    /// A `remove_property` call that retracted a pair.
+    RemoveProperty {
+        /// The node that carried the property.
+        node: NodeId,
+        /// Property attribute.
+        attribute: Attribute,
+        /// Property value.
+        value: Value,
+    },
+    /// An entity key bound by `register_entity_key`.
+    RegisterEntityKey {
+        /// The external key.
+        key: String,
+        /// The entity the key was bound to.
+        entity: EntityId,
+    },
+    /// A `merge_canonical` call.
+    Merge {
+        /// The merged graph.
+        graph: CanonicalGraph,
+        /// How edge weights and properties were combined.
+        policy: MergePolicy,
+    },
+    /// A direct `insert_node` call.
+    InsertNode(EntityId),
+    /// A direct `insert_edge` call.
+    InsertEdge {
+        /// Source node.
+        from: NodeId,
+        /// Target node.
+        to: NodeId,
+        /// Relation label, `None` for an association edge.
+        relation: Option<Attribute>,
+        /// The weight the edge was set to.
+        weight: EdgeWeight,
+    },
+    /// A direct `increment_edge` call.
+    IncrementEdge {
+        /// Source node.
+        from: NodeId,
+        /// Target node.
+        to: NodeId,
+        /// Relation label, `None` for an association edge.
+        relation: Option<Attribute>,
+    },
+    /// A direct `store_property` call that stored a new pair.
+    StoreProperty {
+        /// The node that received the property.
+        node: NodeId,
+        /// Property attribute.
+        attribute: Attribute,
+        /// Property value.
+        value: Value,
+    },
+}
+
+impl JournalEntry {
+    /// Repeat the call on `session`.
+    ///
+    /// Returns `KremisError::StorageCorruption` if the session rejects the
+    /// call or a key resolves to another entity than it did originally.
+    pub fn apply(&self, session: &mut Session) -> Result<(), KremisError> {
+        match self {
+            Self::Ingest(signals) => {
+                session
+                    .ingest_sequence(signals)
+                    .map_err(|e| replay_error(&e))?;
+            }
+            Self::IngestLinked { signals, links } => {
+                session
+                    .ingest_sequence_with(signals, links)
+                    .map_err(|e| replay_error(&e))?;
+            }
+            Self::DecrementEdge { from, to, relation } => session
+                .decrement_edge(*from, *to, relation.as_ref())
+                .map_err(|e| replay_error(&e))?,
+            Self::RemoveNode(node) => {
+                session.remove_node(*node).map_err(|e| replay_error(&e))?;
+            }
+            Self::RemoveProperty {
+                node,
+                attribute,
+                value,
+            } => {
+                session
+                    .remove_property(*node, attribute, value)
+                    .map_err(|e| replay_error(&e))?;
+            }
+            Self::RegisterEntityKey { key, entity } => {
+                let replayed = session
+                    .register_entity_key(key)
+                    .map_err(|e| replay_error(&e))?;
+                if replayed != *entity {
+                    return Err(KremisError::StorageCorruption(format!(
+                        "journal does not replay: key {key:?} resolved to {} instead of {}",
+                        replayed.0, entity.0
+                    )));
+                }
+            }
+            Self::Merge { graph, policy } => {
+                session
+                    .merge_canonical(graph, *policy)
+                    .map_err(|e| replay_error(&e))?;
+            }
+            Self::InsertNode(entity) => {
+                replay_graph(session)?
+                    .insert_node(*entity)
+                    .map_err(|e| replay_error(&e))?;
+            }
+            Self::InsertEdge {
+                from,
+                to,
+                relation,
+                weight,
+            } => replay_graph(session)?
+                .insert_edge(*from, *to, relation.as_ref(), *weight)
+                .map_err(|e| replay_error(&e))?,
+            Self::IncrementEdge { from, to, relation } => replay_graph(session)?
+                .increment_edge(*from, *to, relation.as_ref())
+                .map_err(|e| replay_error(&e))?,
+            Self::StoreProperty {
+                node,
+                attribute,
+                value,
+            } => replay_graph(session)?
+                .store_property(*node, attribute.clone(), value.clone())
+                .map_err(|e| replay_error(&e))?,
+        }
+        Ok(())
+    }
+}
+
+/// The in-memory graph behind a replaying session, for the entries that
+/// record raw `GraphStore` calls rather than session calls.
+fn replay_graph(session: &mut Session) -> Result<&mut Graph, KremisError> {
+    session.graph_mut().ok_or_else(|| {
+        KremisError::StorageCorruption(
+            "journal does not replay: direct store calls need an in-memory session".to_string(),
+        )
+    })
+}
+
+/// Rebuild a graph by applying `entries` in order to an empty session.
+pub fn replay<'a>(
+    entries: impl IntoIterator<Item = &'a JournalEntry>,
+) -> Result<Graph, KremisError> {
+    let mut session = Session::new();
+    for entry in entries {
+        entry.apply(&mut session)?;
+    }
+    session.export_graph_snapshot()
+}
+
+/// Report a call the replaying session rejected.
+fn replay_error(error: &KremisError) -> KremisError {
+    KremisError::StorageCorruption(format!("journal does not replay: {error}"))
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signal(entity: u64, attribute: &str, value: &str) -> Signal {
+        Signal::new(
+            EntityId(entity),
+            Attribute::new(attribute),
+            Value::new(value),
+        )
+    }
+
+    #[test]
+    fn replay_repeats_batches_and_decrements() {
+        let entries = [
+            JournalEntry::Ingest(vec![signal(1, "name", "a"), signal(2, "name", "b")]),
+            JournalEntry::Ingest(vec![signal(1, "name", "a"), signal(2, "name", "b")]),
+            JournalEntry::DecrementEdge {
+                from: NodeId(0),
+                to: NodeId(1),
+                relation: None,
+            },
+        ];
+
+        let graph = replay(&entries).expect("replay");
+        assert_eq!(graph.node_count().expect("count"), 2);
+        assert_eq!(
+            graph.get_edge(NodeId(0), NodeId(1), None).expect("edge"),
+            Some(crate::EdgeWeight::new(1))
+        );
+    }
+
+    #[test]
+    fn replay_rejects_a_call_that_no_longer_fits() {
+        let entries = [JournalEntry::RemoveNode(NodeId(3))];
+        assert!(matches!(
+            replay(&entries),
+            Err(KremisError::StorageCorruption(_))
+        ));
+    }
+}
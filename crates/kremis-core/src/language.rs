@@ -0,0 +1,720 @@
+//! # Query Language Module
+//!
+//! A small textual syntax for [`Query`], parsed deterministically.
+//!
+//! One statement per query; keywords are case-insensitive, and the clauses
+//! after the head may come in any order, each at most once:
+//!
+//! ```text
+//! LOOKUP <entity>
+//! TRAVERSE <node> [DEPTH n] [MINWEIGHT w] [BACKWARD] [RELATION label]
+//! PATH <a> -> <b> [AVOID ids] [VIA ids] [MAXHOPS n] [MINWEIGHT w] [RELATION label]
+//! PATH <a> <- <b> [RELATION label]
+//! PATHS <a> -> <b> MAXHOPS n [LIMIT k] [RELATION label]
+//! KSTRONGEST <a> -> <b> [LIMIT k] [RELATION label]
+//! REACHABLE <a> -> <b> [RELATION label]
+//! INTERSECT <id>, <id>, ...
+//! FIND <attribute> = <value> [AFTER node] [LIMIT k]
+//! SEARCH <text> [LIMIT k]
+//! ```
+//!
+//! - `DEPTH` defaults to 3, `LIMIT` to 10 for paths and 100 for `FIND` and
+//!   `SEARCH`
+//! - `ids` is a comma-separated list of node ids
+//! - A label, attribute, value or search text is a bare word or a
+//!   double-quoted string (`\"` and `\\` escape inside quotes)
+//! - `PATH <a> <- <b>` walks incoming edges from `a` back to `b`
+//!
+//! The parser checks syntax only; bounds such as the maximum depth are
+//! enforced by whoever executes the query, exactly as for a structured one.
+//! A syntax error is a [`KremisError::QueryParse`] carrying the character
+//! offset of the offending token.
+
+use crate::primitives::MAX_QUERY_TEXT_LENGTH;
+use crate::query::{Query, QueryType};
+use crate::{Attribute, EdgeWeight, EntityId, KremisError, NodeId, PathConstraints, Value};
+
+/// Depth of a `TRAVERSE` without a `DEPTH` clause.
+pub const DEFAULT_TEXT_DEPTH: usize = 3;
+
+/// Number of paths returned by `PATHS` and `KSTRONGEST` without `LIMIT`.
+pub const DEFAULT_TEXT_PATH_LIMIT: usize = 10;
+
+/// Number of nodes returned by `FIND` and `SEARCH` without `LIMIT`.
+pub const DEFAULT_TEXT_MATCH_LIMIT: usize = 100;
+
+/// Parse one textual query.
+///
+/// # Errors
+///
+/// Returns `KremisError::QueryParse` with the position of the first token
+/// that does not fit the grammar, or of the end of the input when it stops
+/// early. Input longer than `MAX_QUERY_TEXT_LENGTH` bytes is rejected at
+/// position 0.
+pub fn parse_query(text: &str) -> Result<Query, KremisError> {
+    if text.len() > MAX_QUERY_TEXT_LENGTH {
+        return Err(parse_error(
+            0,
+            format!("query is longer than {MAX_QUERY_TEXT_LENGTH} bytes"),
+        ));
+    }
+    let mut parser = Parser::new(tokenize(text)?, text.chars().count());
+    let query_type = parser.statement()?;
+    if let Some(token) = parser.peek() {
+        return Err(parse_error(
+            token.position,
+            format!("unexpected {} after the query", token.describe()),
+        ));
+    }
+    Ok(Query::new(query_type))
+}
+
+fn parse_error(position: usize, message: impl Into<String>) -> KremisError {
+    KremisError::QueryParse {
+        position,
+        message: message.into(),
+    }
+}
+
+// =============================================================================
+// TOKENS
+// =============================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TokenKind {
+    /// A bare word: keyword, number, label or value.
+    Word(String),
+    /// A double-quoted string, unescaped.
+    Quoted(String),
+    /// `->`
+    Arrow,
+    /// `<-`
+    BackArrow,
+    /// `,`
+    Comma,
+    /// `=`
+    Equals,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    /// Character offset of the token's first character.
+    position: usize,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match &self.kind {
+            TokenKind::Word(word) => format!("'{word}'"),
+            TokenKind::Quoted(text) => format!("\"{text}\""),
+            TokenKind::Arrow => "'->'".to_string(),
+            TokenKind::BackArrow => "'<-'".to_string(),
+            TokenKind::Comma => "','".to_string(),
+            TokenKind::Equals => "'='".to_string(),
+        }
+    }
+}
+
+/// Split the input into tokens. A word ends at whitespace, `,`, `=`, `"`,
+/// `->` or `<-`, so `42->87` reads as three tokens.
+fn tokenize(text: &str) -> Result<Vec<Token>, KremisError> {
+    let chars: Vec<char> = text.chars().collect();
+    let arrow_at = |i: usize| match (chars.get(i), chars.get(i + 1)) {
+        (Some('-'), Some('>')) => Some(TokenKind::Arrow),
+        (Some('<'), Some('-')) => Some(TokenKind::BackArrow),
+        _ => None,
+    };
+
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while let Some(&c) = chars.get(i) {
+        let position = i;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let kind = if let Some(arrow) = arrow_at(i) {
+            i += 2;
+            arrow
+        } else if c == ',' {
+            i += 1;
+            TokenKind::Comma
+        } else if c == '=' {
+            i += 1;
+            TokenKind::Equals
+        } else if c == '"' {
+            i += 1;
+            let mut text = String::new();
+            loop {
+                match chars.get(i) {
+                    None => return Err(parse_error(position, "unterminated string")),
+                    Some('"') => {
+                        i += 1;
+                        break;
+                    }
+                    Some('\\') => match chars.get(i + 1) {
+                        Some(&escaped @ ('"' | '\\')) => {
+                            text.push(escaped);
+                            i += 2;
+                        }
+                        _ => {
+                            return Err(parse_error(
+                                i,
+                                "only \\\" and \\\\ may be escaped in a string",
+                            ));
+                        }
+                    },
+                    Some(&other) => {
+                        text.push(other);
+                        i += 1;
+                    }
+                }
+            }
+            TokenKind::Quoted(text)
+        } else {
+            let mut word = String::new();
+            while let Some(&next) = chars.get(i) {
+                if next.is_whitespace() || matches!(next, ',' | '=' | '"') || arrow_at(i).is_some()
+                {
+                    break;
+                }
+                word.push(next);
+                i += 1;
+            }
+            TokenKind::Word(word)
+        };
+        tokens.push(Token { kind, position });
+    }
+    Ok(tokens)
+}
+
+// =============================================================================
+// PARSER
+// =============================================================================
+
+/// Clauses that may follow a statement head.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Clause {
+    Depth,
+    MinWeight,
+    Backward,
+    Relation,
+    Avoid,
+    Via,
+    MaxHops,
+    Limit,
+    After,
+}
+
+impl Clause {
+    const ALL: [(&'static str, Clause); 9] = [
+        ("DEPTH", Clause::Depth),
+        ("MINWEIGHT", Clause::MinWeight),
+        ("BACKWARD", Clause::Backward),
+        ("RELATION", Clause::Relation),
+        ("AVOID", Clause::Avoid),
+        ("VIA", Clause::Via),
+        ("MAXHOPS", Clause::MaxHops),
+        ("LIMIT", Clause::Limit),
+        ("AFTER", Clause::After),
+    ];
+
+    fn from_keyword(word: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|(keyword, _)| word.eq_ignore_ascii_case(keyword))
+            .map(|(_, clause)| *clause)
+    }
+
+    fn keyword(self) -> &'static str {
+        Self::ALL
+            .iter()
+            .find(|(_, clause)| *clause == self)
+            .map_or("", |(keyword, _)| keyword)
+    }
+}
+
+/// The clause values read after a statement head; each is set at most once.
+#[derive(Debug, Default)]
+struct Clauses {
+    depth: Option<usize>,
+    min_weight: Option<EdgeWeight>,
+    backward: bool,
+    relation: Option<Attribute>,
+    avoid: Option<Vec<NodeId>>,
+    via: Option<Vec<NodeId>>,
+    max_hops: Option<usize>,
+    limit: Option<usize>,
+    after: Option<NodeId>,
+    /// Position of each clause keyword, for errors about combinations.
+    positions: Vec<(Clause, usize)>,
+}
+
+impl Clauses {
+    fn position_of(&self, clause: Clause) -> Option<usize> {
+        self.positions
+            .iter()
+            .find(|(seen, _)| *seen == clause)
+            .map(|(_, position)| *position)
+    }
+}
+
+const STATEMENTS: &str =
+    "LOOKUP, TRAVERSE, PATH, PATHS, KSTRONGEST, REACHABLE, INTERSECT, FIND, SEARCH";
+
+struct Parser {
+    tokens: Vec<Token>,
+    next: usize,
+    /// Character length of the input, the position reported at its end.
+    end: usize,
+}
+
+impl Parser {
+    fn new(tokens: Vec<Token>, end: usize) -> Self {
+        Self {
+            tokens,
+            next: 0,
+            end,
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next)
+    }
+
+    /// Error at the next token (or the end of input): expected `what`.
+    fn expected(&self, what: &str) -> KremisError {
+        match self.peek() {
+            Some(token) => parse_error(
+                token.position,
+                format!("expected {what}, found {}", token.describe()),
+            ),
+            None => parse_error(self.end, format!("expected {what}, found end of input")),
+        }
+    }
+
+    fn statement(&mut self) -> Result<QueryType, KremisError> {
+        let Some(Token {
+            kind: TokenKind::Word(head),
+            position,
+        }) = self.peek().cloned()
+        else {
+            return Err(self.expected(&format!("a query ({STATEMENTS})")));
+        };
+        self.next += 1;
+
+        match head.to_ascii_uppercase().as_str() {
+            "LOOKUP" => Ok(QueryType::Lookup(EntityId(self.number("an entity id")?))),
+            "TRAVERSE" => self.traverse(),
+            "PATH" => self.path(),
+            "PATHS" => {
+                let (start, end) = self.endpoints()?;
+                let clauses = self.clauses(&[Clause::MaxHops, Clause::Limit, Clause::Relation])?;
+                let max_hops = clauses.max_hops.ok_or_else(|| {
+                    parse_error(self.end, "PATHS needs a MAXHOPS clause, found end of input")
+                })?;
+                Ok(QueryType::Paths {
+                    start,
+                    end,
+                    max_hops,
+                    limit: clauses.limit.unwrap_or(DEFAULT_TEXT_PATH_LIMIT),
+                    relation: clauses.relation,
+                })
+            }
+            "KSTRONGEST" => {
+                let (start, end) = self.endpoints()?;
+                let clauses = self.clauses(&[Clause::Limit, Clause::Relation])?;
+                Ok(QueryType::KStrongestPaths {
+                    start,
+                    end,
+                    k: clauses.limit.unwrap_or(DEFAULT_TEXT_PATH_LIMIT),
+                    relation: clauses.relation,
+                })
+            }
+            "REACHABLE" => {
+                let (start, end) = self.endpoints()?;
+                let clauses = self.clauses(&[Clause::Relation])?;
+                Ok(QueryType::Reachable {
+                    start,
+                    end,
+                    relation: clauses.relation,
+                })
+            }
+            "INTERSECT" => Ok(QueryType::Intersect(self.node_list()?)),
+            "FIND" => {
+                let attribute = Attribute::new(self.text("an attribute")?);
+                if self.peek().map(|t| &t.kind) != Some(&TokenKind::Equals) {
+                    return Err(self.expected("'='"));
+                }
+                self.next += 1;
+                let value = Value::new(self.text("a value")?);
+                let clauses = self.clauses(&[Clause::After, Clause::Limit])?;
+                Ok(QueryType::FindByProperty {
+                    attribute,
+                    value,
+                    after: clauses.after,
+                    limit: clauses.limit.unwrap_or(DEFAULT_TEXT_MATCH_LIMIT),
+                })
+            }
+            "SEARCH" => {
+                let query = self.text("search text")?;
+                let clauses = self.clauses(&[Clause::Limit])?;
+                Ok(QueryType::Search {
+                    query,
+                    limit: clauses.limit.unwrap_or(DEFAULT_TEXT_MATCH_LIMIT),
+                })
+            }
+            _ => Err(parse_error(
+                position,
+                format!("unknown query '{head}', expected one of {STATEMENTS}"),
+            )),
+        }
+    }
+
+    fn traverse(&mut self) -> Result<QueryType, KremisError> {
+        let start = NodeId(self.number("a node id")?);
+        let clauses = self.clauses(&[
+            Clause::Depth,
+            Clause::MinWeight,
+            Clause::Backward,
+            Clause::Relation,
+        ])?;
+        let depth = clauses.depth.unwrap_or(DEFAULT_TEXT_DEPTH);
+        match (clauses.backward, clauses.min_weight) {
+            (true, Some(_)) => Err(parse_error(
+                later_of(&clauses, Clause::Backward, Clause::MinWeight),
+                "BACKWARD cannot be combined with MINWEIGHT",
+            )),
+            (true, None) => Ok(QueryType::TraverseBackward {
+                start,
+                depth,
+                relation: clauses.relation,
+            }),
+            (false, Some(min_weight)) => Ok(QueryType::TraverseFiltered {
+                start,
+                depth,
+                min_weight,
+                relation: clauses.relation,
+            }),
+            (false, None) => Ok(QueryType::Traverse {
+                start,
+                depth,
+                relation: clauses.relation,
+            }),
+        }
+    }
+
+    fn path(&mut self) -> Result<QueryType, KremisError> {
+        let (start, end, backward) = {
+            let start = NodeId(self.number("a node id")?);
+            let backward = match self.peek().map(|t| &t.kind) {
+                Some(TokenKind::Arrow) => false,
+                Some(TokenKind::BackArrow) => true,
+                _ => return Err(self.expected("'->' or '<-'")),
+            };
+            self.next += 1;
+            (start, NodeId(self.number("a node id")?), backward)
+        };
+
+        if backward {
+            let clauses = self.clauses(&[Clause::Relation])?;
+            return Ok(QueryType::StrongestPathBackward {
+                start,
+                end,
+                relation: clauses.relation,
+            });
+        }
+        let clauses = self.clauses(&[
+            Clause::Avoid,
+            Clause::Via,
+            Clause::MaxHops,
+            Clause::MinWeight,
+            Clause::Relation,
+        ])?;
+        Ok(QueryType::StrongestPath {
+            start,
+            end,
+            relation: clauses.relation,
+            constraints: PathConstraints {
+                avoid: clauses.avoid.unwrap_or_default().into_iter().collect(),
+                waypoints: clauses.via.unwrap_or_default(),
+                max_hops: clauses.max_hops,
+                min_weight: clauses.min_weight,
+            },
+        })
+    }
+
+    /// `<a> -> <b>`.
+    fn endpoints(&mut self) -> Result<(NodeId, NodeId), KremisError> {
+        let start = NodeId(self.number("a node id")?);
+        if self.peek().map(|t| &t.kind) != Some(&TokenKind::Arrow) {
+            return Err(self.expected("'->'"));
+        }
+        self.next += 1;
+        Ok((start, NodeId(self.number("a node id")?)))
+    }
+
+    /// Read clauses until the end of input, accepting only `allowed` ones.
+    fn clauses(&mut self, allowed: &[Clause]) -> Result<Clauses, KremisError> {
+        let mut clauses = Clauses::default();
+        while let Some(token) = self.peek().cloned() {
+            let clause = match &token.kind {
+                TokenKind::Word(word) => Clause::from_keyword(word),
+                _ => None,
+            };
+            let Some(clause) = clause.filter(|c| allowed.contains(c)) else {
+                let names: Vec<&str> = allowed.iter().map(|c| c.keyword()).collect();
+                return Err(parse_error(
+                    token.position,
+                    format!(
+                        "expected one of {}, found {}",
+                        names.join(", "),
+                        token.describe()
+                    ),
+                ));
+            };
+            if clauses.position_of(clause).is_some() {
+                return Err(parse_error(
+                    token.position,
+                    format!("{} is given twice", clause.keyword()),
+                ));
+            }
+            clauses.positions.push((clause, token.position));
+            self.next += 1;
+
+            match clause {
+                Clause::Depth => clauses.depth = Some(self.count("a depth")?),
+                Clause::MinWeight => {
+                    clauses.min_weight = Some(EdgeWeight::new(self.weight()?));
+                }
+                Clause::Backward => clauses.backward = true,
+                Clause::Relation => {
+                    clauses.relation = Some(Attribute::new(self.text("a relation label")?));
+                }
+                Clause::Avoid => clauses.avoid = Some(self.node_list()?),
+                Clause::Via => clauses.via = Some(self.node_list()?),
+                Clause::MaxHops => clauses.max_hops = Some(self.count("a hop count")?),
+                Clause::Limit => clauses.limit = Some(self.count("a limit")?),
+                Clause::After => clauses.after = Some(NodeId(self.number("a node id")?)),
+            }
+        }
+        Ok(clauses)
+    }
+
+    /// A bare word holding an unsigned 64-bit integer.
+    fn number(&mut self, what: &str) -> Result<u64, KremisError> {
+        match self.peek().map(|t| &t.kind) {
+            Some(TokenKind::Word(word)) if is_digits(word) => match word.parse() {
+                Ok(value) => {
+                    self.next += 1;
+                    Ok(value)
+                }
+                Err(_) => Err(self.out_of_range(what)),
+            },
+            _ => Err(self.expected(what)),
+        }
+    }
+
+    fn count(&mut self, what: &str) -> Result<usize, KremisError> {
+        let position = self.peek().map_or(self.end, |t| t.position);
+        let value = self.number(what)?;
+        usize::try_from(value).map_err(|_| parse_error(position, format!("{what} is out of range")))
+    }
+
+    /// A bare word holding a signed 64-bit integer.
+    fn weight(&mut self) -> Result<i64, KremisError> {
+        match self.peek().map(|t| &t.kind) {
+            Some(TokenKind::Word(word)) if is_digits(word.strip_prefix('-').unwrap_or(word)) => {
+                match word.parse() {
+                    Ok(value) => {
+                        self.next += 1;
+                        Ok(value)
+                    }
+                    Err(_) => Err(self.out_of_range("a weight")),
+                }
+            }
+            _ => Err(self.expected("a weight")),
+        }
+    }
+
+    /// A bare word or a quoted string.
+    fn text(&mut self, what: &str) -> Result<String, KremisError> {
+        match self.peek().map(|t| &t.kind) {
+            Some(TokenKind::Word(text) | TokenKind::Quoted(text)) => {
+                let text = text.clone();
+                self.next += 1;
+                Ok(text)
+            }
+            _ => Err(self.expected(what)),
+        }
+    }
+
+    /// Node ids separated by commas.
+    fn node_list(&mut self) -> Result<Vec<NodeId>, KremisError> {
+        let mut nodes = vec![NodeId(self.number("a node id")?)];
+        while self.peek().map(|t| &t.kind) == Some(&TokenKind::Comma) {
+            self.next += 1;
+            nodes.push(NodeId(self.number("a node id")?));
+        }
+        Ok(nodes)
+    }
+
+    fn out_of_range(&self, what: &str) -> KremisError {
+        let position = self.peek().map_or(self.end, |t| t.position);
+        parse_error(position, format!("{what} is out of range"))
+    }
+}
+
+fn is_digits(word: &str) -> bool {
+    !word.is_empty() && word.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Position of whichever of two given clauses came last.
+fn later_of(clauses: &Clauses, a: Clause, b: Clause) -> usize {
+    clauses
+        .position_of(a)
+        .max(clauses.position_of(b))
+        .unwrap_or_default()
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> QueryType {
+        parse_query(text).expect("parse").query_type
+    }
+
+    fn error_at(text: &str) -> (usize, String) {
+        match parse_query(text) {
+            Err(KremisError::QueryParse { position, message }) => (position, message),
+            other => panic!("expected a parse error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_every_statement() {
+        assert_eq!(parse("lookup 7"), QueryType::Lookup(EntityId(7)));
+        assert_eq!(
+            parse("TRAVERSE 1 DEPTH 3 MINWEIGHT 2"),
+            QueryType::TraverseFiltered {
+                start: NodeId(1),
+                depth: 3,
+                min_weight: EdgeWeight::new(2),
+                relation: None,
+            }
+        );
+        assert_eq!(
+            parse("TRAVERSE 4 BACKWARD RELATION \"depends on\""),
+            QueryType::TraverseBackward {
+                start: NodeId(4),
+                depth: DEFAULT_TEXT_DEPTH,
+                relation: Some(Attribute::new("depends on")),
+            }
+        );
+        assert_eq!(
+            parse("PATHS 1 -> 2 MAXHOPS 4"),
+            QueryType::Paths {
+                start: NodeId(1),
+                end: NodeId(2),
+                max_hops: 4,
+                limit: DEFAULT_TEXT_PATH_LIMIT,
+                relation: None,
+            }
+        );
+        assert_eq!(
+            parse("KSTRONGEST 1->2 LIMIT 3"),
+            QueryType::KStrongestPaths {
+                start: NodeId(1),
+                end: NodeId(2),
+                k: 3,
+                relation: None,
+            }
+        );
+        assert_eq!(
+            parse("REACHABLE 0 -> 9 RELATION calls"),
+            QueryType::Reachable {
+                start: NodeId(0),
+                end: NodeId(9),
+                relation: Some(Attribute::new("calls")),
+            }
+        );
+        assert_eq!(
+            parse("INTERSECT 1, 2,3"),
+            QueryType::Intersect(vec![NodeId(1), NodeId(2), NodeId(3)])
+        );
+        assert_eq!(
+            parse("FIND role = \"staff \\\"lead\\\"\" AFTER 5 LIMIT 2"),
+            QueryType::FindByProperty {
+                attribute: Attribute::new("role"),
+                value: Value::new("staff \"lead\""),
+                after: Some(NodeId(5)),
+                limit: 2,
+            }
+        );
+        assert_eq!(
+            parse("search ledger"),
+            QueryType::Search {
+                query: "ledger".to_string(),
+                limit: DEFAULT_TEXT_MATCH_LIMIT,
+            }
+        );
+    }
+
+    #[test]
+    fn path_clauses_become_constraints() {
+        let QueryType::StrongestPath {
+            start,
+            end,
+            constraints,
+            ..
+        } = parse("PATH 42 -> 87 MAXHOPS 5 AVOID 3,4 VIA 9 MINWEIGHT -1")
+        else {
+            panic!("expected a strongest path");
+        };
+        assert_eq!((start, end), (NodeId(42), NodeId(87)));
+        assert_eq!(constraints.max_hops, Some(5));
+        assert_eq!(constraints.avoid.len(), 2);
+        assert_eq!(constraints.waypoints, vec![NodeId(9)]);
+        assert_eq!(constraints.min_weight, Some(EdgeWeight::new(-1)));
+
+        assert_eq!(
+            parse("path 5 <- 0"),
+            QueryType::StrongestPathBackward {
+                start: NodeId(5),
+                end: NodeId(0),
+                relation: None,
+            }
+        );
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_token() {
+        assert_eq!(error_at("PATH 42 87").0, 8);
+        assert_eq!(error_at("TRAVERSE 1 DEPTH").0, 16);
+        assert_eq!(error_at("TRAVERSE 1 DEPTH 3 DEPTH 4").0, 19);
+        assert_eq!(error_at("REACHABLE 1 -> 2 MAXHOPS 3").0, 17);
+        assert_eq!(error_at("TRAVERSE 1 MINWEIGHT 2 BACKWARD").0, 23);
+        assert_eq!(error_at("SEARCH \"open").0, 7);
+        assert_eq!(error_at("PATHS 1 -> 2").0, 12);
+
+        let (position, message) = error_at("WALK 1");
+        assert_eq!(position, 0);
+        assert!(message.contains("LOOKUP"));
+
+        let (position, message) = error_at("LOOKUP 99999999999999999999");
+        assert_eq!(position, 7);
+        assert!(message.contains("out of range"));
+    }
+
+    #[test]
+    fn positions_count_characters() {
+        // The quoted label holds a two-byte character.
+        assert_eq!(error_at("TRAVERSE 1 RELATION \"é\" x").0, 24);
+    }
+}
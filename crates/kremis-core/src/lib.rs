@@ -33,19 +33,31 @@
 // MODULES
 // =============================================================================
 
+pub mod centrality;
 pub mod certificate;
+pub mod components;
 pub mod compositor;
 pub mod confidence;
+pub mod cursor;
+pub mod delta;
+pub mod diff;
 pub mod export;
 pub mod formats;
 pub mod graph;
 pub mod grounding;
+pub mod history;
 pub mod ingestor;
+pub mod journal;
+pub mod language;
+pub mod merge;
 pub mod mutation;
+pub mod pattern;
 pub mod primitives;
 pub mod query;
+pub mod search;
 pub mod session;
 pub mod storage;
+pub mod stream;
 pub mod system;
 pub mod types;
 
@@ -54,28 +66,40 @@ pub mod types;
 // =============================================================================
 
 pub use types::{
-    Artifact, Attribute, Buffer, EdgeWeight, EntityId, Facet, KremisError, Node, NodeId, Signal,
-    Value,
+    Artifact, Attribute, Buffer, EdgeWeight, EntityId, Facet, KremisError, LabeledEdge, Node,
+    NodeId, Signal, Value,
 };
 
 // =============================================================================
 // RE-EXPORTS: Graph Engine
 // =============================================================================
 
+pub use centrality::CentralityMetric;
 pub use certificate::{CERT_MAGIC, CERT_VERSION, CertBody, CertHeader, QueryCertificate};
+pub use components::Components;
 pub use compositor::Compositor;
 pub use confidence::ConfidenceScore;
+pub use cursor::Cursor;
+pub use delta::GraphDelta;
+pub use diff::{EdgeReweight, GraphDiff, diff};
 pub use export::{
     CanonicalGraph, CanonicalHeader, canonical_checksum, export_canonical, import_canonical,
     verify_canonical,
 };
-pub use graph::{Graph, GraphStore, LoadDiagnostics, SerializableGraph};
+pub use graph::{
+    Graph, GraphStore, LoadDiagnostics, PathConstraints, RankedPath, Reachability,
+    SerializableGraph, VisitBudget,
+};
 pub use grounding::{GroundedResult, verify_hypothesis};
-pub use ingestor::Ingestor;
+pub use ingestor::{Ingestor, LinkMode};
+pub use language::parse_query;
+pub use merge::{MergeDiagnostics, MergePolicy};
 pub use mutation::MutationEngine;
+pub use pattern::{Pattern, PatternEdge, PatternMatch, PatternVariable};
 pub use query::{Query, QueryType};
 pub use session::{Session, StorageBackend};
-pub use storage::RedbGraph;
+pub use storage::{CheckpointPolicy, RedbExport, RedbGraph};
+pub use stream::{CanonicalItem, CanonicalSource};
 
 // =============================================================================
 // RE-EXPORTS: Formats (from formats module)
@@ -0,0 +1,436 @@
+//! # Merge Module
+//!
+//! Deterministic merge of a [`CanonicalGraph`] into an existing graph.
+//!
+//! - Incoming nodes are reconciled by `EntityId`: a node whose entity already
+//!   exists maps onto that node
+//! - A new node keeps its incoming id when the target never handed it out
+//!   (the id is at or above its next node id), and is otherwise remapped to
+//!   a fresh id above every id either side uses. Ids below the next node id
+//!   are never reused, even when the node that held one was deleted
+//! - An edge present on both sides gets its weight from the [`MergePolicy`];
+//!   property sets are unioned, except that `KeepExisting` leaves an
+//!   attribute a reconciled node already has untouched
+//! - Edges and properties that reference a node missing from the incoming
+//!   graph are dropped and counted, as `LoadDiagnostics` does
+//!
+//! [`plan`] only reads the target store and returns the changes as one
+//! [`MutationRecord`], so both backends apply the same merge in a single step.
+
+use crate::export::CanonicalGraph;
+use crate::graph::GraphStore;
+use crate::history::{Mutation, MutationRecord};
+use crate::ingestor::Ingestor;
+use crate::primitives::MAX_PROPERTIES_PER_NODE;
+use crate::{Attribute, EdgeWeight, EntityId, KremisError, NodeId, Value};
+use serde::{Deserialize, Serialize};
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// How an edge present in both graphs gets its merged weight.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergePolicy {
+    /// Add the two weights, saturating at the bounds of `i64`.
+    #[default]
+    Sum,
+    /// Keep the larger weight.
+    Max,
+    /// Keep the existing weight, and the existing values of any attribute
+    /// a reconciled node already has.
+    KeepExisting,
+}
+
+impl MergePolicy {
+    /// The merged weight of an edge weighted `existing` here and `incoming`
+    /// in the merged graph.
+    #[must_use]
+    pub fn combine(self, existing: EdgeWeight, incoming: EdgeWeight) -> EdgeWeight {
+        match self {
+            Self::Sum => EdgeWeight::new(existing.value().saturating_add(incoming.value())),
+            Self::Max => existing.max(incoming),
+            Self::KeepExisting => existing,
+        }
+    }
+}
+
+/// Counts reported by a merge.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeDiagnostics {
+    /// Incoming nodes added as new nodes, remapped or not.
+    pub nodes_added: usize,
+    /// Incoming nodes mapped onto an existing node with the same entity.
+    pub nodes_reconciled: usize,
+    /// New nodes whose incoming id was already handed out here, and that got
+    /// a fresh id.
+    pub nodes_remapped: usize,
+    /// Incoming edges with no counterpart in the existing graph.
+    pub edges_added: usize,
+    /// Incoming edges combined with an existing edge under the policy.
+    pub edges_combined: usize,
+    /// Property pairs added to a node.
+    pub properties_added: usize,
+    /// Entity key bindings added to the registry.
+    pub keys_bound: usize,
+    /// Entity key bindings skipped because the key or the entity is already
+    /// bound differently, or the key is invalid.
+    pub key_conflicts: usize,
+    /// Edges dropped because one of their endpoints was not in the payload.
+    pub dangling_edges: usize,
+    /// Properties dropped because the node they belong to was not in the payload.
+    pub dangling_properties: usize,
+}
+
+/// The changes a merge makes, ready to be applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergePlan {
+    /// Every change, in the order it must be applied.
+    pub record: MutationRecord,
+    /// What the merge reconciled, added and dropped.
+    pub diagnostics: MergeDiagnostics,
+}
+
+/// Plan the merge of `incoming` into `store`, whose next node id is
+/// `next_node_id`.
+///
+/// Nodes, edges, properties and key bindings are visited in canonical order,
+/// so the same store and payload always produce the same plan. Returns
+/// `KremisError::PropertyLimitExceeded` if a node would end up with more
+/// than `MAX_PROPERTIES_PER_NODE` properties; nothing is planned in that case.
+pub fn plan<S: GraphStore + ?Sized>(
+    store: &S,
+    next_node_id: u64,
+    incoming: &CanonicalGraph,
+    policy: MergePolicy,
+) -> Result<MergePlan, KremisError> {
+    let mut nodes: Vec<_> = incoming.nodes.iter().collect();
+    nodes.sort();
+    let mut edges: Vec<_> = incoming.edges.iter().collect();
+    edges.sort();
+    let mut properties: Vec<_> = incoming.properties.iter().collect();
+    properties.sort();
+    let mut entity_keys: Vec<_> = incoming.entity_keys.iter().collect();
+    entity_keys.sort();
+
+    let mut diagnostics = MergeDiagnostics::default();
+    let mut mutations = Vec::new();
+
+    // Fresh ids start above every id on either side, so they never collide
+    // with an incoming id kept as is.
+    let mut fresh = nodes
+        .iter()
+        .map(|n| n.id.saturating_add(1))
+        .fold(next_node_id, u64::max);
+    let mut next = next_node_id;
+
+    // Incoming node id -> target node, and whether the target already existed.
+    let mut targets: BTreeMap<u64, (NodeId, bool)> = BTreeMap::new();
+    let mut planned_entities: BTreeMap<EntityId, NodeId> = BTreeMap::new();
+    let mut claimed: BTreeSet<NodeId> = BTreeSet::new();
+    for node in nodes {
+        if targets.contains_key(&node.id) {
+            continue;
+        }
+        let entity = EntityId(node.entity);
+        if let Some(existing) = store.get_node_by_entity(entity) {
+            diagnostics.nodes_reconciled += 1;
+            targets.insert(node.id, (existing, true));
+            continue;
+        }
+        if let Some(&planned) = planned_entities.get(&entity) {
+            diagnostics.nodes_reconciled += 1;
+            targets.insert(node.id, (planned, false));
+            continue;
+        }
+        // Every id below `next_node_id` was handed out at some point; one
+        // whose node was deleted may still be named by history or exports.
+        let kept = NodeId(node.id);
+        let target = if node.id >= next_node_id && !claimed.contains(&kept) {
+            kept
+        } else {
+            diagnostics.nodes_remapped += 1;
+            let id = NodeId(fresh);
+            fresh = fresh.saturating_add(1);
+            id
+        };
+        diagnostics.nodes_added += 1;
+        claimed.insert(target);
+        planned_entities.insert(entity, target);
+        targets.insert(node.id, (target, false));
+        next = next.max(target.0.saturating_add(1));
+        mutations.push(Mutation::InsertNode {
+            node: target,
+            entity,
+        });
+    }
+
+    let mut planned_edges: BTreeMap<(NodeId, NodeId, Option<Attribute>), EdgeWeight> =
+        BTreeMap::new();
+    for edge in edges {
+        let (Some(&(from, _)), Some(&(to, _))) = (targets.get(&edge.from), targets.get(&edge.to))
+        else {
+            diagnostics.dangling_edges += 1;
+            continue;
+        };
+        let relation = edge.relation.as_deref().map(Attribute::new);
+        let weight = EdgeWeight::new(edge.weight);
+        let key = (from, to, relation);
+        let current = match planned_edges.get(&key) {
+            Some(&planned) => Some(planned),
+            None => store.get_edge(from, to, key.2.as_ref())?,
+        };
+        let merged = match current {
+            Some(current) => {
+                diagnostics.edges_combined += 1;
+                let merged = policy.combine(current, weight);
+                if merged == current {
+                    continue;
+                }
+                merged
+            }
+            None => {
+                diagnostics.edges_added += 1;
+                weight
+            }
+        };
+        planned_edges.insert(key.clone(), merged);
+        mutations.push(Mutation::SetEdge {
+            from: key.0,
+            to: key.1,
+            relation: key.2,
+            weight: merged,
+        });
+    }
+
+    // Existing properties are read once per target node, with the attributes
+    // they cover before the merge.
+    type NodeProperties = (BTreeSet<Attribute>, BTreeSet<(Attribute, Value)>);
+    let mut node_properties: BTreeMap<NodeId, NodeProperties> = BTreeMap::new();
+    for property in properties {
+        let Some(&(node, existed)) = targets.get(&property.node_id) else {
+            diagnostics.dangling_properties += 1;
+            continue;
+        };
+        let (existing_attributes, set) = match node_properties.entry(node) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let current: BTreeSet<(Attribute, Value)> = if existed {
+                    store.get_properties(node)?.into_iter().collect()
+                } else {
+                    BTreeSet::new()
+                };
+                let attributes = current.iter().map(|(a, _)| a.clone()).collect();
+                entry.insert((attributes, current))
+            }
+        };
+        let attribute = Attribute::new(property.attribute.as_str());
+        if policy == MergePolicy::KeepExisting && existing_attributes.contains(&attribute) {
+            continue;
+        }
+        let value = Value::new(property.value.as_str());
+        if !set.insert((attribute.clone(), value.clone())) {
+            continue;
+        }
+        if set.len() > MAX_PROPERTIES_PER_NODE {
+            return Err(KremisError::PropertyLimitExceeded(
+                node,
+                MAX_PROPERTIES_PER_NODE,
+            ));
+        }
+        diagnostics.properties_added += 1;
+        mutations.push(Mutation::StoreProperty {
+            node,
+            attribute,
+            value,
+        });
+    }
+
+    let mut bound_keys: BTreeSet<&str> = BTreeSet::new();
+    let mut bound_entities: BTreeSet<EntityId> = BTreeSet::new();
+    for binding in entity_keys {
+        let entity = EntityId(binding.entity);
+        if store.entity_for_key(&binding.key)? == Some(entity) {
+            continue;
+        }
+        if Ingestor::validate_entity_key(&binding.key).is_err()
+            || store.entity_for_key(&binding.key)?.is_some()
+            || store.key_for_entity(entity)?.is_some()
+            || bound_keys.contains(binding.key.as_str())
+            || bound_entities.contains(&entity)
+        {
+            diagnostics.key_conflicts += 1;
+            continue;
+        }
+        bound_keys.insert(&binding.key);
+        bound_entities.insert(entity);
+        diagnostics.keys_bound += 1;
+        mutations.push(Mutation::BindEntityKey {
+            key: binding.key.clone(),
+            entity,
+        });
+    }
+
+    Ok(MergePlan {
+        record: MutationRecord {
+            next_node_id: next,
+            mutations,
+        },
+        diagnostics,
+    })
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::graph::Graph;
+
+    fn merged(target: &Graph, incoming: &Graph, policy: MergePolicy) -> (Graph, MergeDiagnostics) {
+        let plan = plan(
+            target,
+            target.next_node_id(),
+            &CanonicalGraph::from_graph(incoming),
+            policy,
+        )
+        .unwrap();
+        let mut graph = target.clone();
+        plan.record.apply(&mut graph).unwrap();
+        (graph, plan.diagnostics)
+    }
+
+    /// Two graphs built separately: both know entity 1, each knows one more.
+    fn teams() -> (Graph, Graph) {
+        let mut ours = Graph::new();
+        let a = ours.insert_node(EntityId(1)).unwrap();
+        let b = ours.insert_node(EntityId(2)).unwrap();
+        ours.insert_edge(a, b, None, EdgeWeight::new(3)).unwrap();
+        ours.store_property(a, Attribute::new("name"), Value::new("Alice"))
+            .unwrap();
+
+        let mut theirs = Graph::new();
+        let c = theirs.insert_node(EntityId(3)).unwrap();
+        let a = theirs.insert_node(EntityId(1)).unwrap();
+        let b = theirs.insert_node(EntityId(2)).unwrap();
+        theirs.insert_edge(a, b, None, EdgeWeight::new(5)).unwrap();
+        theirs.insert_edge(a, c, None, EdgeWeight::new(1)).unwrap();
+        theirs
+            .store_property(a, Attribute::new("name"), Value::new("Alicia"))
+            .unwrap();
+        theirs
+            .store_property(c, Attribute::new("name"), Value::new("Carol"))
+            .unwrap();
+        (ours, theirs)
+    }
+
+    #[test]
+    fn merge_reconciles_entities_and_remaps_taken_ids() {
+        let (ours, theirs) = teams();
+        let (graph, diagnostics) = merged(&ours, &theirs, MergePolicy::Sum);
+
+        assert_eq!(diagnostics.nodes_reconciled, 2);
+        assert_eq!(diagnostics.nodes_added, 1);
+        assert_eq!(diagnostics.nodes_remapped, 1);
+        // Entity 3 had id 0 in its graph, taken here: it gets the first id
+        // above both sides.
+        let carol = graph.get_node_by_entity(EntityId(3)).unwrap();
+        assert_eq!(carol, NodeId(3));
+        assert_eq!(graph.next_node_id(), 4);
+
+        let (a, b) = (NodeId(0), NodeId(1));
+        assert_eq!(
+            graph.get_edge(a, b, None).unwrap(),
+            Some(EdgeWeight::new(8))
+        );
+        assert_eq!(
+            graph.get_edge(a, carol, None).unwrap(),
+            Some(EdgeWeight::new(1))
+        );
+        assert_eq!(graph.get_properties(a).unwrap().len(), 2);
+        assert_eq!(diagnostics.edges_combined, 1);
+        assert_eq!(diagnostics.edges_added, 1);
+        assert_eq!(diagnostics.properties_added, 2);
+    }
+
+    #[test]
+    fn merge_never_reuses_the_id_of_a_deleted_node() {
+        let (mut ours, _) = teams();
+        let gone = ours.insert_node(EntityId(7)).unwrap();
+        ours.remove_node(gone).unwrap();
+        assert_eq!(gone, NodeId(2));
+        assert_eq!(ours.next_node_id(), 3);
+
+        // Ids 2 and 3 are free, but only 3 was never handed out here.
+        let mut theirs = Graph::new();
+        for entity in [10, 11, 12, 13] {
+            theirs.insert_node(EntityId(entity)).unwrap();
+        }
+        let (graph, diagnostics) = merged(&ours, &theirs, MergePolicy::Sum);
+
+        assert_eq!(diagnostics.nodes_added, 4);
+        assert_eq!(diagnostics.nodes_remapped, 3);
+        assert_eq!(graph.get_node_by_entity(EntityId(13)), Some(NodeId(3)));
+        for entity in [10, 11, 12] {
+            let node = graph.get_node_by_entity(EntityId(entity)).unwrap();
+            assert!(node.0 >= 4, "entity {entity} got {node:?}");
+        }
+        assert!(!graph.contains_node(gone).unwrap());
+    }
+
+    #[test]
+    fn policies_choose_the_merged_weight_and_properties() {
+        let (ours, theirs) = teams();
+        let (a, b) = (NodeId(0), NodeId(1));
+
+        let (max, _) = merged(&ours, &theirs, MergePolicy::Max);
+        assert_eq!(max.get_edge(a, b, None).unwrap(), Some(EdgeWeight::new(5)));
+
+        let (kept, diagnostics) = merged(&ours, &theirs, MergePolicy::KeepExisting);
+        assert_eq!(kept.get_edge(a, b, None).unwrap(), Some(EdgeWeight::new(3)));
+        assert_eq!(
+            kept.get_properties(a).unwrap(),
+            vec![(Attribute::new("name"), Value::new("Alice"))]
+        );
+        // Carol is new, so her properties are added under every policy.
+        assert_eq!(diagnostics.properties_added, 1);
+    }
+
+    #[test]
+    fn merge_is_deterministic_and_counts_dangling_items() {
+        let (ours, theirs) = teams();
+        let mut payload = CanonicalGraph::from_graph(&theirs);
+        payload.nodes.reverse();
+        payload.edges.push(crate::export::CanonicalEdge {
+            from: 0,
+            to: 99,
+            relation: None,
+            weight: 1,
+        });
+        payload.properties.push(crate::export::CanonicalProperty {
+            node_id: 99,
+            attribute: "name".to_string(),
+            value: "ghost".to_string(),
+        });
+
+        let first = plan(&ours, ours.next_node_id(), &payload, MergePolicy::Sum).unwrap();
+        let second = plan(&ours, ours.next_node_id(), &payload, MergePolicy::Sum).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(first.diagnostics.dangling_edges, 1);
+        assert_eq!(first.diagnostics.dangling_properties, 1);
+
+        // Merging a graph into itself reconciles everything and, under
+        // `KeepExisting`, changes nothing.
+        let noop = plan(
+            &ours,
+            ours.next_node_id(),
+            &CanonicalGraph::from_graph(&ours),
+            MergePolicy::KeepExisting,
+        )
+        .unwrap();
+        assert!(noop.record.mutations.is_empty());
+        assert_eq!(noop.diagnostics.nodes_reconciled, 2);
+    }
+}
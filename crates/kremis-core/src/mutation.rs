@@ -67,7 +67,7 @@ impl MutationEngine {
     ///
     /// This increments the edge weight between the two nodes by 1.
     pub fn link_signals(graph: &mut Graph, from: NodeId, to: NodeId) -> Result<(), KremisError> {
-        graph.increment_edge(from, to)
+        graph.increment_edge(from, to, None)
     }
 }
 
@@ -105,7 +105,12 @@ mod tests {
         let nodes = MutationEngine::process_sequence(&mut graph, &signals).expect("process");
 
         assert_eq!(nodes.len(), 2);
-        assert!(graph.get_edge(nodes[0], nodes[1]).expect("get").is_some());
+        assert!(
+            graph
+                .get_edge(nodes[0], nodes[1], None)
+                .expect("get")
+                .is_some()
+        );
     }
 
     #[test]
@@ -122,10 +127,16 @@ mod tests {
         let b = graph.insert_node(EntityId(2)).expect("insert");
 
         MutationEngine::link_signals(&mut graph, a, b).expect("link");
-        assert_eq!(graph.get_edge(a, b).expect("get"), Some(EdgeWeight::new(1)));
+        assert_eq!(
+            graph.get_edge(a, b, None).expect("get"),
+            Some(EdgeWeight::new(1))
+        );
 
         MutationEngine::link_signals(&mut graph, a, b).expect("link");
-        assert_eq!(graph.get_edge(a, b).expect("get"), Some(EdgeWeight::new(2)));
+        assert_eq!(
+            graph.get_edge(a, b, None).expect("get"),
+            Some(EdgeWeight::new(2))
+        );
     }
 
     #[test]
@@ -0,0 +1,456 @@
+//! # Pattern Module
+//!
+//! Deterministic subgraph pattern matching over any [`GraphStore`].
+//!
+//! A [`Pattern`] is a list of variables and a list of edges between them.
+//! A variable may be pinned to a node and may require properties; an edge
+//! may require a relation label and a minimum weight. A binding assigns a
+//! distinct node to every variable so that every pattern edge is backed by a
+//! stored edge.
+//!
+//! - Variables are bound in declaration order, each from its candidates in
+//!   ascending node id order, so bindings come out in lexicographic order of
+//!   their node lists and the first `limit` of them are returned
+//! - A variable's candidates are, by preference: its pinned node; the
+//!   neighbours of an earlier variable it shares an edge with; the nodes
+//!   carrying its first required property; every node
+//! - The search examines at most `MAX_PATTERN_CANDIDATES` candidate nodes and
+//!   rejects the pattern beyond that, rather than return an answer that
+//!   depends on where it stopped
+//!
+//! Declaring a selective variable first (pinned, or with a property) keeps
+//! the search small.
+
+use crate::graph::GraphStore;
+use crate::primitives::{
+    MAX_PATTERN_CANDIDATES, MAX_PATTERN_EDGES, MAX_PATTERN_PROPERTIES, MAX_PATTERN_VARIABLES,
+    MAX_PROPERTY_MATCHES,
+};
+use crate::{Attribute, EdgeWeight, KremisError, LabeledEdge, NodeId, Value};
+use std::collections::BTreeSet;
+
+/// A subgraph pattern: variables and the edges required between them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Pattern {
+    /// The variables, each bound to one node; their order is the search
+    /// order and the order of every binding's node list.
+    pub variables: Vec<PatternVariable>,
+    /// Edges that must exist between the bound nodes.
+    pub edges: Vec<PatternEdge>,
+}
+
+/// One variable of a [`Pattern`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PatternVariable {
+    /// Bind only this node.
+    pub node: Option<NodeId>,
+    /// Properties the bound node must carry.
+    pub properties: Vec<(Attribute, Value)>,
+}
+
+/// One edge of a [`Pattern`], between two variables by index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternEdge {
+    /// Index of the variable the edge leaves.
+    pub from: usize,
+    /// Index of the variable the edge enters.
+    pub to: usize,
+    /// Only edges with this relation label qualify (`None`: any label).
+    pub relation: Option<Attribute>,
+    /// Edges weighing less than this do not qualify.
+    pub min_weight: Option<EdgeWeight>,
+}
+
+/// One binding of a [`Pattern`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternMatch {
+    /// The node bound to each variable, in variable order.
+    pub nodes: Vec<NodeId>,
+    /// The stored edge backing each pattern edge, as `(from, to, relation,
+    /// weight)`, in pattern order. With several qualifying labels, the
+    /// heaviest edge is reported.
+    pub edges: Vec<LabeledEdge>,
+}
+
+/// Find the bindings of `pattern`, in lexicographic order of their node
+/// lists, and return the first `limit`.
+///
+/// Returns `KremisError::InvalidSignal` for an empty pattern, one past the
+/// `MAX_PATTERN_*` size limits or with an edge naming a missing variable,
+/// and when the search would examine more than `MAX_PATTERN_CANDIDATES`
+/// candidates.
+pub fn match_pattern<G: GraphStore + ?Sized>(
+    graph: &G,
+    pattern: &Pattern,
+    limit: usize,
+) -> Result<Vec<PatternMatch>, KremisError> {
+    validate(pattern)?;
+    let mut search = Search {
+        graph,
+        pattern,
+        limit,
+        examined: 0,
+        bound: Vec::with_capacity(pattern.variables.len()),
+        weights: vec![None; pattern.edges.len()],
+        matches: Vec::new(),
+    };
+    if limit > 0 {
+        search.extend()?;
+    }
+    Ok(search.matches)
+}
+
+fn validate(pattern: &Pattern) -> Result<(), KremisError> {
+    let variables = pattern.variables.len();
+    if variables == 0
+        || variables > MAX_PATTERN_VARIABLES
+        || pattern.edges.len() > MAX_PATTERN_EDGES
+    {
+        return Err(KremisError::InvalidSignal);
+    }
+    let properties: usize = pattern.variables.iter().map(|v| v.properties.len()).sum();
+    if properties > MAX_PATTERN_PROPERTIES {
+        return Err(KremisError::InvalidSignal);
+    }
+    if pattern
+        .edges
+        .iter()
+        .any(|edge| edge.from >= variables || edge.to >= variables)
+    {
+        return Err(KremisError::InvalidSignal);
+    }
+    Ok(())
+}
+
+/// Backtracking state of one `match_pattern` call.
+struct Search<'a, G: GraphStore + ?Sized> {
+    graph: &'a G,
+    pattern: &'a Pattern,
+    limit: usize,
+    examined: usize,
+    /// Nodes bound so far, one per leading variable.
+    bound: Vec<NodeId>,
+    /// Label and weight of the stored edge backing each pattern edge, once
+    /// both of its variables are bound.
+    weights: Vec<Option<(Option<Attribute>, EdgeWeight)>>,
+    matches: Vec<PatternMatch>,
+}
+
+impl<G: GraphStore + ?Sized> Search<'_, G> {
+    /// Bind the next variable to each of its candidates in turn. Returns
+    /// `Ok(true)` once `limit` bindings have been found.
+    fn extend(&mut self) -> Result<bool, KremisError> {
+        let index = self.bound.len();
+        if index == self.pattern.variables.len() {
+            self.record();
+            return Ok(self.matches.len() >= self.limit);
+        }
+
+        for candidate in self.candidates(index)? {
+            self.examined += 1;
+            if self.examined > MAX_PATTERN_CANDIDATES {
+                return Err(KremisError::InvalidSignal);
+            }
+            if self.bound.contains(&candidate) || !self.accepts(index, candidate)? {
+                continue;
+            }
+            self.bound.push(candidate);
+            let done = self.extend()?;
+            self.bound.pop();
+            if done {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Candidate nodes for variable `index`, ascending.
+    fn candidates(&self, index: usize) -> Result<BTreeSet<NodeId>, KremisError> {
+        let variable = &self.pattern.variables[index];
+        if let Some(node) = variable.node {
+            return Ok(if self.graph.contains_node(node)? {
+                BTreeSet::from([node])
+            } else {
+                BTreeSet::new()
+            });
+        }
+
+        // An edge to an earlier variable: walk it from the bound end.
+        for edge in &self.pattern.edges {
+            if edge.to == index && edge.from < index {
+                let from = self.bound[edge.from];
+                let out = self.graph.neighbors(from, edge.relation.as_ref())?;
+                return Ok(out.into_iter().map(|(to, _)| to).collect());
+            }
+            if edge.from == index && edge.to < index {
+                let to = self.bound[edge.to];
+                let incoming = self.graph.predecessors(to, edge.relation.as_ref())?;
+                return Ok(incoming.into_iter().map(|(from, _)| from).collect());
+            }
+        }
+
+        if let Some((attribute, value)) = variable.properties.first() {
+            let mut nodes = BTreeSet::new();
+            let mut after = None;
+            loop {
+                let page =
+                    self.graph
+                        .find_by_property(attribute, value, after, MAX_PROPERTY_MATCHES)?;
+                let full = page.len() == MAX_PROPERTY_MATCHES;
+                after = page.last().copied();
+                nodes.extend(page);
+                if !full || nodes.len() > MAX_PATTERN_CANDIDATES {
+                    break;
+                }
+            }
+            return Ok(nodes);
+        }
+
+        Ok(self.graph.node_ids()?.into_iter().collect())
+    }
+
+    /// Whether `candidate` satisfies variable `index`'s properties and every
+    /// pattern edge between it and the variables already bound. Records the
+    /// backing weights of those edges.
+    fn accepts(&mut self, index: usize, candidate: NodeId) -> Result<bool, KremisError> {
+        let variable = &self.pattern.variables[index];
+        if !variable.properties.is_empty() {
+            let stored = self.graph.get_properties(candidate)?;
+            if !variable
+                .properties
+                .iter()
+                .all(|property| stored.contains(property))
+            {
+                return Ok(false);
+            }
+        }
+
+        for (position, edge) in self.pattern.edges.iter().enumerate() {
+            let endpoint = |variable: usize| {
+                if variable == index {
+                    Some(candidate)
+                } else {
+                    self.bound.get(variable).copied()
+                }
+            };
+            if edge.from != index && edge.to != index {
+                continue;
+            }
+            let (Some(from), Some(to)) = (endpoint(edge.from), endpoint(edge.to)) else {
+                continue;
+            };
+            let backing = self
+                .graph
+                .strongest_labeled_edge(from, to, edge.relation.as_ref())?;
+            match backing {
+                Some((label, weight)) if edge.min_weight.is_none_or(|min| weight >= min) => {
+                    self.weights[position] = Some((label, weight));
+                }
+                _ => return Ok(false),
+            }
+        }
+        Ok(true)
+    }
+
+    fn record(&mut self) {
+        let edges = self
+            .pattern
+            .edges
+            .iter()
+            .zip(&self.weights)
+            .filter_map(|(edge, backing)| {
+                backing.clone().map(|(label, weight)| {
+                    (self.bound[edge.from], self.bound[edge.to], label, weight)
+                })
+            })
+            .collect();
+        self.matches.push(PatternMatch {
+            nodes: self.bound.clone(),
+            edges,
+        });
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EntityId;
+    use crate::graph::Graph;
+
+    /// Services 0..=3: 0 -> 1 -> 2, 0 -> 2, 3 -> 1 (depends_on), 0 and 3
+    /// typed as gateways.
+    fn services() -> Graph {
+        let mut graph = Graph::new();
+        for entity in 0..4 {
+            graph.insert_node(EntityId(entity)).expect("insert");
+        }
+        let depends_on = Attribute::new("depends_on");
+        for (from, to, weight) in [(0, 1, 5), (1, 2, 3), (0, 2, 1), (3, 1, 4)] {
+            graph
+                .insert_edge(
+                    NodeId(from),
+                    NodeId(to),
+                    Some(&depends_on),
+                    EdgeWeight::new(weight),
+                )
+                .expect("edge");
+        }
+        for node in [0, 3] {
+            graph
+                .store_property(NodeId(node), Attribute::new("type"), Value::new("gateway"))
+                .expect("property");
+        }
+        graph
+    }
+
+    fn variable() -> PatternVariable {
+        PatternVariable::default()
+    }
+
+    fn edge(from: usize, to: usize) -> PatternEdge {
+        PatternEdge {
+            from,
+            to,
+            relation: None,
+            min_weight: None,
+        }
+    }
+
+    fn nodes(matches: &[PatternMatch]) -> Vec<Vec<u64>> {
+        matches
+            .iter()
+            .map(|m| m.nodes.iter().map(|n| n.0).collect())
+            .collect()
+    }
+
+    #[test]
+    fn chain_bindings_come_in_lexicographic_order() {
+        let pattern = Pattern {
+            variables: vec![variable(), variable(), variable()],
+            edges: vec![edge(0, 1), edge(1, 2)],
+        };
+        let matches = match_pattern(&services(), &pattern, 10).expect("match");
+        assert_eq!(nodes(&matches), vec![vec![0, 1, 2], vec![3, 1, 2]]);
+        assert_eq!(
+            matches[1].edges,
+            vec![
+                (
+                    NodeId(3),
+                    NodeId(1),
+                    Some(Attribute::new("depends_on")),
+                    EdgeWeight::new(4)
+                ),
+                (
+                    NodeId(1),
+                    NodeId(2),
+                    Some(Attribute::new("depends_on")),
+                    EdgeWeight::new(3)
+                ),
+            ]
+        );
+
+        let first = match_pattern(&services(), &pattern, 1).expect("match");
+        assert_eq!(nodes(&first), vec![vec![0, 1, 2]]);
+    }
+
+    #[test]
+    fn properties_pins_and_weights_filter_bindings() {
+        let gateway = PatternVariable {
+            node: None,
+            properties: vec![(Attribute::new("type"), Value::new("gateway"))],
+        };
+        // A gateway reaching some node both directly and in two hops.
+        let triangle = Pattern {
+            variables: vec![gateway.clone(), variable(), variable()],
+            edges: vec![edge(0, 1), edge(1, 2), edge(0, 2)],
+        };
+        let matches = match_pattern(&services(), &triangle, 10).expect("match");
+        assert_eq!(nodes(&matches), vec![vec![0, 1, 2]]);
+
+        let heavy = Pattern {
+            variables: vec![gateway, variable()],
+            edges: vec![PatternEdge {
+                min_weight: Some(EdgeWeight::new(5)),
+                ..edge(0, 1)
+            }],
+        };
+        let matches = match_pattern(&services(), &heavy, 10).expect("match");
+        assert_eq!(nodes(&matches), vec![vec![0, 1]]);
+
+        let pinned = Pattern {
+            variables: vec![
+                variable(),
+                PatternVariable {
+                    node: Some(NodeId(2)),
+                    properties: Vec::new(),
+                },
+            ],
+            edges: vec![edge(0, 1)],
+        };
+        let matches = match_pattern(&services(), &pinned, 10).expect("match");
+        assert_eq!(nodes(&matches), vec![vec![0, 2], vec![1, 2]]);
+    }
+
+    #[test]
+    fn bindings_are_injective_and_respect_relations() {
+        // Two variables on the same edge in both directions: no node pair
+        // qualifies, and a variable never binds twice to one node.
+        let cycle = Pattern {
+            variables: vec![variable(), variable()],
+            edges: vec![edge(0, 1), edge(1, 0)],
+        };
+        assert!(
+            match_pattern(&services(), &cycle, 10)
+                .expect("match")
+                .is_empty()
+        );
+
+        let owned_by = Pattern {
+            variables: vec![variable(), variable()],
+            edges: vec![PatternEdge {
+                relation: Some(Attribute::new("owned_by")),
+                ..edge(0, 1)
+            }],
+        };
+        assert!(
+            match_pattern(&services(), &owned_by, 10)
+                .expect("match")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_and_unbounded_patterns() {
+        let graph = services();
+        let dangling = Pattern {
+            variables: vec![variable()],
+            edges: vec![edge(0, 1)],
+        };
+        assert!(matches!(
+            match_pattern(&graph, &dangling, 10),
+            Err(KremisError::InvalidSignal)
+        ));
+        assert!(matches!(
+            match_pattern(&graph, &Pattern::default(), 10),
+            Err(KremisError::InvalidSignal)
+        ));
+
+        // Six unconstrained variables over 60 nodes: far past the budget.
+        let mut big = Graph::new();
+        for entity in 0..60 {
+            big.insert_node(EntityId(entity)).expect("insert");
+        }
+        let loose = Pattern {
+            variables: vec![variable(); 6],
+            edges: Vec::new(),
+        };
+        assert!(matches!(
+            match_pattern(&big, &loose, MAX_PATTERN_CANDIDATES),
+            Err(KremisError::InvalidSignal)
+        ));
+    }
+}
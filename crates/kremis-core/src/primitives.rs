@@ -16,7 +16,8 @@
 /// - `ASSOCIATION_WINDOW = 1`: Links are formed only between strictly adjacent signals.
 /// - Signal A connects to Signal B only if B immediately follows A.
 ///
-/// This is the LINKING_PRIMITIVE constant.
+/// This is the LINKING_PRIMITIVE constant: the window used unless an ingest
+/// request asks for another [`LinkMode`](crate::ingestor::LinkMode).
 pub const ASSOCIATION_WINDOW: usize = 1;
 
 /// Magic bytes for the Kremis binary format header.
@@ -44,12 +45,58 @@ pub const PROMOTION_THRESHOLD: i64 = 10;
 /// - This prevents runaway traversals in large graphs.
 pub const MAX_TRAVERSAL_DEPTH: usize = 100;
 
+/// Maximum number of paths one `simple_paths` or `k_strongest_paths` query
+/// may return.
+pub const MAX_PATH_RESULTS: usize = 100;
+
+/// Maximum number of nodes a path query may avoid, and separately of
+/// waypoints it may name.
+pub const MAX_PATH_CONSTRAINT_NODES: usize = 100;
+
+/// Maximum number of partial paths `simple_paths` may extend.
+///
+/// Ordering the paths needs all of them, so a query that would enumerate
+/// more is rejected instead of answered from a partial set.
+pub const MAX_PATH_ENUMERATION: usize = 100_000;
+
+/// Mutations between two snapshot checkpoints of a persistent graph.
+///
+/// A point-in-time read replays the mutation log from the latest checkpoint
+/// at or before its sequence number, so it replays at most this many
+/// transactions past the checkpoint. Taking a checkpoint costs the write that
+/// reaches it time and memory proportional to the whole graph. This is the
+/// default of [`CheckpointPolicy`](crate::CheckpointPolicy).
+pub const SNAPSHOT_CHECKPOINT_INTERVAL: u64 = 1024;
+
+/// Maximum number of snapshot checkpoints a persistent graph keeps.
+///
+/// Past the limit, the checkpoint whose removal leaves the smallest gap is
+/// dropped, so recent history stays densely covered and older history
+/// sparsely. Each checkpoint holds a serialized copy of the graph. This is
+/// the default of [`CheckpointPolicy`](crate::CheckpointPolicy).
+pub const MAX_SNAPSHOT_CHECKPOINTS: usize = 32;
+
+/// Visit budget the HTTP server gives a query that sets no `timeout_ms`.
+///
+/// Counted like [`VisitBudget`](crate::VisitBudget): one visit per node
+/// expanded and one per edge followed.
+pub const DEFAULT_VISIT_BUDGET: u64 = 1_000_000;
+
+/// Largest visit budget the HTTP server grants; a larger `timeout_ms` is
+/// cut down to it.
+pub const MAX_VISIT_BUDGET: u64 = 10_000_000;
+
 /// Maximum total node visits during strongest-path DFS.
 ///
-/// Bounds the total work done by the DFS, preventing exponential blowup
-/// on dense graphs where depth alone is insufficient.
-/// When exhausted, the algorithm returns the best path found so far.
-pub const MAX_VISIT_COUNT: usize = 50_000;
+/// Strongest-path queries no longer cap their visits with a fixed count;
+/// every traversal is bounded by a [`VisitBudget`](crate::VisitBudget)
+/// instead. Kept as an alias of [`DEFAULT_VISIT_BUDGET`] for callers that
+/// still size their own limits from it.
+#[deprecated(
+    since = "0.22.0",
+    note = "Traversals are bounded by a VisitBudget; use DEFAULT_VISIT_BUDGET"
+)]
+pub const MAX_VISIT_COUNT: usize = DEFAULT_VISIT_BUDGET as usize;
 
 // =============================================================================
 // INPUT VALIDATION LIMITS
@@ -72,6 +119,15 @@ pub const MAX_VALUE_LENGTH: usize = 65536;
 /// Sequences longer than this will be rejected to prevent DoS.
 pub const MAX_SEQUENCE_LENGTH: usize = 10000;
 
+/// Maximum association window an ingest request may ask for.
+///
+/// A window of `N` creates up to `N` edges per signal, so this bounds the
+/// edge writes of one batch at `MAX_SEQUENCE_LENGTH * MAX_ASSOCIATION_WINDOW`.
+pub const MAX_ASSOCIATION_WINDOW: usize = 16;
+
+/// Maximum number of explicit links in one ingest request.
+pub const MAX_EXPLICIT_LINKS: usize = 10000;
+
 /// Maximum number of distinct `(attribute, value)` properties per node.
 ///
 /// Each node accumulates properties across successive ingestions. Length and
@@ -83,6 +139,82 @@ pub const MAX_SEQUENCE_LENGTH: usize = 10000;
 /// of an already-stored pair remain allowed (they do not grow the node).
 pub const MAX_PROPERTIES_PER_NODE: usize = 4096;
 
+/// Maximum number of variables in a pattern query.
+pub const MAX_PATTERN_VARIABLES: usize = 16;
+
+/// Maximum number of edges in a pattern query.
+pub const MAX_PATTERN_EDGES: usize = 32;
+
+/// Maximum number of property constraints in a pattern query, over all of
+/// its variables.
+pub const MAX_PATTERN_PROPERTIES: usize = 32;
+
+/// Maximum number of bindings one pattern query may return.
+pub const MAX_PATTERN_RESULTS: usize = 1000;
+
+/// Maximum number of candidate nodes a pattern search may examine.
+///
+/// A search that would examine more is rejected instead of answered from
+/// the part it explored.
+pub const MAX_PATTERN_CANDIDATES: usize = 100_000;
+
+/// Maximum length of a textual query, in bytes.
+pub const MAX_QUERY_TEXT_LENGTH: usize = 4096;
+
+/// Maximum number of stages in one query pipeline.
+pub const MAX_PIPELINE_STAGES: usize = 8;
+
+/// Maximum number of nodes a pipeline stage may pass to the next one.
+///
+/// Every stage runs once per input node, so a stage whose node set would be
+/// larger is rejected instead of answered from part of it.
+pub const MAX_PIPELINE_NODES: usize = 1000;
+
+/// Maximum number of PageRank power iterations one centrality query may run.
+pub const MAX_PAGERANK_ITERATIONS: usize = 100;
+
+/// Maximum number of nodes in a graph whose betweenness may be computed.
+///
+/// Exact betweenness runs one breadth-first search per node, so its cost
+/// grows with nodes times edges.
+pub const MAX_BETWEENNESS_NODES: usize = 10_000;
+
+/// Maximum number of ranked nodes returned by one centrality query.
+pub const MAX_CENTRALITY_RESULTS: usize = 1000;
+
+/// Maximum number of node ids returned by one `find_by_property` page.
+///
+/// A popular `(attribute, value)` pair can match most of the graph; larger
+/// result sets are read page by page.
+pub const MAX_PROPERTY_MATCHES: usize = 1000;
+
+/// Maximum number of items in one page of a traversal result.
+pub const MAX_PAGE_SIZE: usize = 1000;
+
+/// Maximum number of distinct tokens in a search query.
+///
+/// Each token costs one prefix scan of the token index.
+pub const MAX_SEARCH_TERMS: usize = 16;
+
+/// Maximum number of ranked nodes returned by one search.
+pub const MAX_SEARCH_RESULTS: usize = 1000;
+
+/// Maximum length for entity key strings.
+///
+/// Keys are external names for entities; the registry stores each one twice
+/// (key -> id and id -> key), so they are capped like attributes.
+pub const MAX_ENTITY_KEY_LENGTH: usize = 256;
+
+/// First `EntityId` of the range allocated to string entity keys.
+///
+/// Keyed entities take ids in the upper half of the `u64` space, derived from
+/// the key itself, so the same key names the same id in independently built
+/// graphs. Numeric ids chosen by clients normally stay below this value.
+pub const KEYED_ENTITY_BASE: u64 = 1 << 63;
+
+/// Maximum number of ids probed when a key's derived id is already taken.
+pub const MAX_ENTITY_KEY_PROBES: u64 = 64;
+
 /// Minimum number of nodes in an Intersect query.
 ///
 /// Intersection requires at least two sets to be meaningful.
@@ -107,4 +239,10 @@ mod tests {
     fn magic_bytes_correct() {
         assert_eq!(MAGIC_BYTES, b"KREM");
     }
+
+    #[test]
+    #[allow(deprecated)]
+    fn max_visit_count_aliases_the_default_budget() {
+        assert_eq!(MAX_VISIT_COUNT as u64, DEFAULT_VISIT_BUDGET);
+    }
 }
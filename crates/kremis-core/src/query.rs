@@ -6,7 +6,8 @@
 //! - Deterministic query parsing (no semantic guessing)
 //! - Support for complex queries
 
-use crate::{EdgeWeight, EntityId, NodeId};
+use crate::graph::VisitBudget;
+use crate::{Attribute, EdgeWeight, EntityId, NodeId, PathConstraints, Pattern, Value};
 
 /// Query operation types supported by the CORE.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -20,6 +21,8 @@ pub enum QueryType {
         start: NodeId,
         /// Maximum number of hops to follow away from `start`.
         depth: usize,
+        /// Only follow edges with this relation label (`None`: all edges).
+        relation: Option<Attribute>,
     },
 
     /// Traverse with minimum weight filter.
@@ -30,6 +33,8 @@ pub enum QueryType {
         depth: usize,
         /// Edges weighing less than this are not followed.
         min_weight: EdgeWeight,
+        /// Only follow edges with this relation label (`None`: all edges).
+        relation: Option<Attribute>,
     },
 
     /// Find the strongest path between two nodes.
@@ -38,6 +43,95 @@ pub enum QueryType {
         start: NodeId,
         /// Node the path must reach.
         end: NodeId,
+        /// Only follow edges with this relation label (`None`: all edges).
+        relation: Option<Attribute>,
+        /// Nodes to avoid, waypoints, hop limit and minimum hop weight.
+        constraints: PathConstraints,
+    },
+
+    /// Traverse incoming edges from a starting node up to a depth limit.
+    TraverseBackward {
+        /// Node the traversal starts from.
+        start: NodeId,
+        /// Maximum number of hops to follow back from `start`.
+        depth: usize,
+        /// Only follow edges with this relation label (`None`: all edges).
+        relation: Option<Attribute>,
+    },
+
+    /// Find the strongest path from `start` back to `end` over incoming edges.
+    StrongestPathBackward {
+        /// Node the path starts from.
+        start: NodeId,
+        /// Node the path must reach by walking edges in reverse.
+        end: NodeId,
+        /// Only follow edges with this relation label (`None`: all edges).
+        relation: Option<Attribute>,
+    },
+
+    /// Decide whether `end` is reachable from `start`, exploring the whole
+    /// forward closure of `start` when it is not.
+    Reachable {
+        /// Node the search starts from.
+        start: NodeId,
+        /// Node the search looks for.
+        end: NodeId,
+        /// Only follow edges with this relation label (`None`: all edges).
+        relation: Option<Attribute>,
+    },
+
+    /// List the simple paths from `start` to `end`, heaviest first.
+    Paths {
+        /// Node the paths start from.
+        start: NodeId,
+        /// Node the paths must reach.
+        end: NodeId,
+        /// Maximum number of hops per path.
+        max_hops: usize,
+        /// Maximum number of paths to return.
+        limit: usize,
+        /// Only follow edges with this relation label (`None`: all edges).
+        relation: Option<Attribute>,
+    },
+
+    /// Find the `k` strongest simple paths from `start` to `end`.
+    KStrongestPaths {
+        /// Node the paths start from.
+        start: NodeId,
+        /// Node the paths must reach.
+        end: NodeId,
+        /// Number of paths to return.
+        k: usize,
+        /// Only follow edges with this relation label (`None`: all edges).
+        relation: Option<Attribute>,
+    },
+
+    /// Find the bindings of a subgraph pattern.
+    Pattern {
+        /// Variables and the edges required between them.
+        pattern: Pattern,
+        /// Maximum number of bindings to return.
+        limit: usize,
+    },
+
+    /// Find nodes carrying an exact `(attribute, value)` property.
+    FindByProperty {
+        /// Property attribute to match.
+        attribute: Attribute,
+        /// Property value to match.
+        value: Value,
+        /// Only return node ids greater than this one (pagination cursor).
+        after: Option<NodeId>,
+        /// Maximum number of node ids to return.
+        limit: usize,
+    },
+
+    /// Rank nodes by token matches between `query` and their property values.
+    Search {
+        /// Free-text query, tokenized like stored values.
+        query: String,
+        /// Maximum number of ranked nodes to return.
+        limit: usize,
     },
 
     /// Find nodes connected to ALL input nodes.
@@ -52,17 +146,19 @@ pub enum QueryType {
     },
 }
 
-/// A structured query with optional timeout.
+/// A structured query with an optional work budget.
 #[derive(Debug, Clone)]
 pub struct Query {
     /// The type of query operation.
     pub query_type: QueryType,
-    /// Optional timeout in milliseconds.
+    /// Optional work budget, counted in node and edge visits (see
+    /// [`VisitBudget`]) rather than milliseconds, so a query that runs out
+    /// always stops at the same point.
     pub timeout_ms: Option<u64>,
 }
 
 impl Query {
-    /// Create a new query with no timeout.
+    /// Create a new query with no work budget.
     #[must_use]
     pub fn new(query_type: QueryType) -> Self {
         Self {
@@ -71,7 +167,7 @@ impl Query {
         }
     }
 
-    /// Create a new query with a timeout.
+    /// Create a new query with a work budget of `timeout_ms` visits.
     #[must_use]
     pub fn with_timeout(query_type: QueryType, timeout_ms: u64) -> Self {
         Self {
@@ -80,6 +176,14 @@ impl Query {
         }
     }
 
+    /// The visit budget this query runs under: `timeout_ms` visits, or
+    /// unlimited without one.
+    #[must_use]
+    pub fn budget(&self) -> VisitBudget {
+        self.timeout_ms
+            .map_or_else(VisitBudget::unlimited, VisitBudget::new)
+    }
+
     /// Lookup helper.
     #[must_use]
     pub fn lookup(entity: EntityId) -> Self {
@@ -89,13 +193,22 @@ impl Query {
     /// Traverse helper.
     #[must_use]
     pub fn traverse(start: NodeId, depth: usize) -> Self {
-        Self::new(QueryType::Traverse { start, depth })
+        Self::new(QueryType::Traverse {
+            start,
+            depth,
+            relation: None,
+        })
     }
 
     /// Strongest path helper.
     #[must_use]
     pub fn strongest_path(start: NodeId, end: NodeId) -> Self {
-        Self::new(QueryType::StrongestPath { start, end })
+        Self::new(QueryType::StrongestPath {
+            start,
+            end,
+            relation: None,
+            constraints: PathConstraints::default(),
+        })
     }
 
     /// Intersect helper.
@@ -126,6 +239,7 @@ mod tests {
             QueryType::Traverse {
                 start: NodeId(1),
                 depth: 5,
+                relation: None,
             },
             1000,
         );
@@ -0,0 +1,54 @@
+//! # Search Module
+//!
+//! Deterministic token search over property values.
+//!
+//! - Values are split into tokens at every non-alphanumeric character
+//! - Tokens are case-folded with `str::to_lowercase`
+//! - A query token matches a stored token it equals or is a prefix of
+//! - Scores are integers; ties are broken by ascending `NodeId`
+
+use std::collections::BTreeSet;
+
+/// Score contributed by a query token that equals a stored token.
+pub const EXACT_TOKEN_SCORE: u64 = 2;
+
+/// Score contributed by a query token that is only a prefix of a stored token.
+pub const PREFIX_TOKEN_SCORE: u64 = 1;
+
+/// Split `text` into its distinct case-folded tokens.
+///
+/// A token is a maximal run of alphanumeric characters, so `"Ledger-API v2"`
+/// yields `{"api", "ledger", "v2"}`. The same text always yields the same set.
+#[must_use]
+pub fn tokenize(text: &str) -> BTreeSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenize_splits_and_folds_case() {
+        let tokens: Vec<String> = tokenize("Ledger-API v2, ledger").into_iter().collect();
+        assert_eq!(tokens, vec!["api", "ledger", "v2"]);
+    }
+
+    #[test]
+    fn tokenize_keeps_unicode_letters() {
+        let tokens: Vec<String> = tokenize("Zürich/ÅRHUS").into_iter().collect();
+        assert_eq!(tokens, vec!["zürich", "århus"]);
+    }
+
+    #[test]
+    fn tokenize_ignores_punctuation_only_input() {
+        assert!(tokenize(" -- ,; ").is_empty());
+    }
+}
@@ -13,12 +13,22 @@
 //! - `InMemory`: Uses in-memory `Graph` (fast, volatile unless explicitly saved)
 //! - `Persistent`: Uses `RedbGraph` for disk-backed ACID storage
 
-use crate::graph::{Graph, GraphStore};
-use crate::ingestor::Ingestor;
-use crate::storage::RedbGraph;
+use crate::centrality::{self, CentralityMetric};
+use crate::certificate::QueryCertificate;
+use crate::components::{self, Components};
+use crate::export::{CanonicalGraph, CanonicalHeader};
+use crate::graph::{Graph, GraphStore, PathConstraints, RankedPath, Reachability, VisitBudget};
+use crate::ingestor::{Ingestor, LinkMode};
+use crate::journal::JournalEntry;
+use crate::merge::{self, MergeDiagnostics, MergePolicy};
+use crate::pattern::{self, Pattern, PatternMatch};
+use crate::storage::{CheckpointPolicy, RedbGraph};
+use crate::stream;
 use crate::{
-    Artifact, Attribute, Buffer, EdgeWeight, EntityId, KremisError, NodeId, Signal, Value,
+    Artifact, Attribute, Buffer, EdgeWeight, EntityId, KremisError, Node, NodeId, Signal, Value,
 };
+use std::collections::BTreeMap;
+use std::io::Write;
 use std::path::Path;
 
 /// Storage backend for a Session.
@@ -198,6 +208,15 @@ impl Session {
         }
     }
 
+    /// Set when a persistent backend takes snapshot checkpoints.
+    ///
+    /// The in-memory backend keeps no history, so it ignores the policy.
+    pub fn set_checkpoint_policy(&mut self, policy: CheckpointPolicy) {
+        if let StorageBackend::Persistent(redb) = &mut self.backend {
+            redb.set_checkpoint_policy(policy);
+        }
+    }
+
     /// Get a reference to the buffer.
     #[must_use]
     pub fn buffer(&self) -> &Buffer {
@@ -240,9 +259,23 @@ impl Session {
     /// Creates edges between adjacent signals per ASSOCIATION_WINDOW.
     /// All resulting nodes are added to active context.
     pub fn ingest_sequence(&mut self, signals: &[Signal]) -> Result<Vec<NodeId>, KremisError> {
+        self.ingest_sequence_with(signals, &LinkMode::default())
+    }
+
+    /// Ingest a sequence of signals, creating the edges `links` asks for.
+    ///
+    /// See [`Ingestor::ingest_sequence_with`]. All resulting nodes are added
+    /// to active context.
+    pub fn ingest_sequence_with(
+        &mut self,
+        signals: &[Signal],
+        links: &LinkMode,
+    ) -> Result<Vec<NodeId>, KremisError> {
         let nodes = match &mut self.backend {
-            StorageBackend::InMemory(graph) => Ingestor::ingest_sequence(graph, signals)?,
-            StorageBackend::Persistent(redb) => redb.ingest_batch(signals)?,
+            StorageBackend::InMemory(graph) => {
+                Ingestor::ingest_sequence_with(graph, signals, links)?
+            }
+            StorageBackend::Persistent(redb) => redb.ingest_batch_with(signals, links)?,
         };
         for &node in &nodes {
             self.buffer.activate(node);
@@ -253,13 +286,31 @@ impl Session {
     /// Decrement the weight of an existing edge by 1, floored at 0.
     ///
     /// Returns `KremisError::EdgeNotFound` if the edge does not exist.
-    pub fn decrement_edge(&mut self, from: NodeId, to: NodeId) -> Result<(), KremisError> {
+    pub fn decrement_edge(
+        &mut self,
+        from: NodeId,
+        to: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<(), KremisError> {
         match &mut self.backend {
-            StorageBackend::InMemory(graph) => graph.decrement_edge(from, to),
-            StorageBackend::Persistent(redb) => redb.decrement_edge(from, to),
+            StorageBackend::InMemory(graph) => graph.decrement_edge(from, to, relation),
+            StorageBackend::Persistent(redb) => redb.decrement_edge(from, to, relation),
         }
     }
 
+    /// Remove a node with its incident edges, properties and entity mapping.
+    ///
+    /// The node is also dropped from the active buffer. Returns
+    /// `KremisError::NodeNotFound` if the node does not exist.
+    pub fn remove_node(&mut self, id: NodeId) -> Result<Node, KremisError> {
+        let node = match &mut self.backend {
+            StorageBackend::InMemory(graph) => graph.remove_node(id)?,
+            StorageBackend::Persistent(redb) => redb.remove_node(id)?,
+        };
+        self.buffer.deactivate(&id);
+        Ok(node)
+    }
+
     // =========================================================================
     // COMPOSITION
     // =========================================================================
@@ -267,8 +318,8 @@ impl Session {
     /// Compose an artifact from a starting node.
     pub fn compose(&self, start: NodeId, depth: usize) -> Result<Option<Artifact>, KremisError> {
         match &self.backend {
-            StorageBackend::InMemory(graph) => graph.traverse(start, depth),
-            StorageBackend::Persistent(redb) => redb.traverse(start, depth),
+            StorageBackend::InMemory(graph) => graph.traverse(start, depth, None),
+            StorageBackend::Persistent(redb) => redb.traverse(start, depth, None),
         }
     }
 
@@ -283,16 +334,15 @@ impl Session {
         self.compose(*start, depth)
     }
 
-    /// Extract path between two nodes.
+    /// Extract path between two nodes, following only edges that match
+    /// `relation` (`None`: all edges).
     pub fn extract_path(
         &self,
         start: NodeId,
         end: NodeId,
+        relation: Option<&Attribute>,
     ) -> Result<Option<Artifact>, KremisError> {
-        let path_result = match &self.backend {
-            StorageBackend::InMemory(graph) => graph.strongest_path(start, end),
-            StorageBackend::Persistent(redb) => redb.strongest_path(start, end),
-        };
+        let path_result = self.strongest_path(start, end, relation);
         let Some(path) = path_result? else {
             return Ok(None);
         };
@@ -302,8 +352,16 @@ impl Session {
         for window in path.windows(2) {
             let from = window[0];
             let to = window[1];
-            if let Some(weight) = self.get_edge(from, to)? {
-                subgraph.push((from, to, weight));
+            let edge = match &self.backend {
+                StorageBackend::InMemory(graph) => {
+                    graph.strongest_labeled_edge(from, to, relation)?
+                }
+                StorageBackend::Persistent(redb) => {
+                    redb.strongest_labeled_edge(from, to, relation)?
+                }
+            };
+            if let Some((label, weight)) = edge {
+                subgraph.push((from, to, label, weight));
             }
         }
 
@@ -397,14 +455,19 @@ impl Session {
         }
     }
 
-    /// Get edge weight between two nodes.
+    /// Get the weight of the edge `(from, to, relation)`.
     ///
     /// Returns `Ok(None)` when the edge does not exist (intentional absence).
     /// Returns `Err` only on storage failures.
-    pub fn get_edge(&self, from: NodeId, to: NodeId) -> Result<Option<EdgeWeight>, KremisError> {
+    pub fn get_edge(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<Option<EdgeWeight>, KremisError> {
         match &self.backend {
-            StorageBackend::InMemory(graph) => graph.get_edge(from, to),
-            StorageBackend::Persistent(redb) => redb.get_edge(from, to),
+            StorageBackend::InMemory(graph) => graph.get_edge(from, to, relation),
+            StorageBackend::Persistent(redb) => redb.get_edge(from, to, relation),
         }
     }
 
@@ -428,14 +491,20 @@ impl Session {
         }
     }
 
-    /// Traverse from a starting node.
+    /// Traverse from a starting node, following only edges that match
+    /// `relation` (`None`: all edges).
     ///
     /// Returns `Ok(None)` when the start node does not exist (intentional absence).
     /// Returns `Err` only on storage failures.
-    pub fn traverse(&self, start: NodeId, depth: usize) -> Result<Option<Artifact>, KremisError> {
+    pub fn traverse(
+        &self,
+        start: NodeId,
+        depth: usize,
+        relation: Option<&Attribute>,
+    ) -> Result<Option<Artifact>, KremisError> {
         match &self.backend {
-            StorageBackend::InMemory(graph) => graph.traverse(start, depth),
-            StorageBackend::Persistent(redb) => redb.traverse(start, depth),
+            StorageBackend::InMemory(graph) => graph.traverse(start, depth, relation),
+            StorageBackend::Persistent(redb) => redb.traverse(start, depth, relation),
         }
     }
 
@@ -448,14 +517,49 @@ impl Session {
         start: NodeId,
         depth: usize,
         min_weight: EdgeWeight,
+        relation: Option<&Attribute>,
     ) -> Result<Option<Artifact>, KremisError> {
         match &self.backend {
-            StorageBackend::InMemory(graph) => graph.traverse_filtered(start, depth, min_weight),
-            StorageBackend::Persistent(redb) => redb.traverse_filtered(start, depth, min_weight),
+            StorageBackend::InMemory(graph) => {
+                graph.traverse_filtered(start, depth, min_weight, relation)
+            }
+            StorageBackend::Persistent(redb) => {
+                redb.traverse_filtered(start, depth, min_weight, relation)
+            }
         }
     }
 
-    /// Find strongest path between two nodes.
+    /// Get the incoming edges of a node as `(from, weight)` pairs, following
+    /// only edges that match `relation` (`None`: all edges).
+    pub fn predecessors(
+        &self,
+        node: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<Vec<(NodeId, EdgeWeight)>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => graph.predecessors(node, relation),
+            StorageBackend::Persistent(redb) => redb.predecessors(node, relation),
+        }
+    }
+
+    /// Traverse incoming edges from a starting node up to a depth limit.
+    ///
+    /// Returns `Ok(None)` when the start node does not exist (intentional absence).
+    /// Returns `Err` only on storage failures.
+    pub fn traverse_backward(
+        &self,
+        start: NodeId,
+        depth: usize,
+        relation: Option<&Attribute>,
+    ) -> Result<Option<Artifact>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => graph.traverse_backward(start, depth, relation),
+            StorageBackend::Persistent(redb) => redb.traverse_backward(start, depth, relation),
+        }
+    }
+
+    /// Find strongest path between two nodes, following only edges that match
+    /// `relation` (`None`: all edges).
     ///
     /// Returns `Ok(None)` when no path exists (intentional absence).
     /// Returns `Err` only on storage failures.
@@ -463,10 +567,256 @@ impl Session {
         &self,
         start: NodeId,
         end: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<Option<Vec<NodeId>>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => graph.strongest_path(start, end, relation),
+            StorageBackend::Persistent(redb) => redb.strongest_path(start, end, relation),
+        }
+    }
+
+    /// Find the strongest path between two nodes that satisfies
+    /// `constraints`.
+    ///
+    /// See [`GraphStore::strongest_path_with`]. Returns `Ok(None)` when no
+    /// such path exists.
+    pub fn strongest_path_with(
+        &self,
+        start: NodeId,
+        end: NodeId,
+        relation: Option<&Attribute>,
+        constraints: &PathConstraints,
+    ) -> Result<Option<Vec<NodeId>>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => {
+                graph.strongest_path_with(start, end, relation, constraints)
+            }
+            StorageBackend::Persistent(redb) => {
+                redb.strongest_path_with(start, end, relation, constraints)
+            }
+        }
+    }
+
+    /// Find the strongest path from `start` back to `end` over incoming edges.
+    ///
+    /// Each consecutive pair `(a, b)` of the result is backed by an edge
+    /// `b -> a`. Returns `Ok(None)` when no such path exists.
+    pub fn strongest_path_backward(
+        &self,
+        start: NodeId,
+        end: NodeId,
+        relation: Option<&Attribute>,
     ) -> Result<Option<Vec<NodeId>>, KremisError> {
         match &self.backend {
-            StorageBackend::InMemory(graph) => graph.strongest_path(start, end),
-            StorageBackend::Persistent(redb) => redb.strongest_path(start, end),
+            StorageBackend::InMemory(graph) => graph.strongest_path_backward(start, end, relation),
+            StorageBackend::Persistent(redb) => redb.strongest_path_backward(start, end, relation),
+        }
+    }
+
+    /// Decide exhaustively whether `end` can be reached from `start`.
+    ///
+    /// See [`GraphStore::reachable`]. Returns `Ok(None)` when `start` does
+    /// not exist.
+    pub fn reachable(
+        &self,
+        start: NodeId,
+        end: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<Option<Reachability>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => graph.reachable(start, end, relation),
+            StorageBackend::Persistent(redb) => redb.reachable(start, end, relation),
+        }
+    }
+
+    /// [`reachable`](Self::reachable) under a visit budget; `Ok(None)` when
+    /// `budget` runs out.
+    pub fn reachable_within(
+        &self,
+        start: NodeId,
+        end: NodeId,
+        relation: Option<&Attribute>,
+        budget: &mut VisitBudget,
+    ) -> Result<Option<Reachability>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => graph.reachable_within(start, end, relation, budget),
+            StorageBackend::Persistent(redb) => redb.reachable_within(start, end, relation, budget),
+        }
+    }
+
+    /// Score every node under a centrality metric.
+    ///
+    /// See [`centrality::centrality`].
+    pub fn centrality(
+        &self,
+        metric: CentralityMetric,
+        relation: Option<&Attribute>,
+    ) -> Result<BTreeMap<NodeId, u64>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => centrality::centrality(graph, metric, relation),
+            StorageBackend::Persistent(redb) => centrality::centrality(redb, metric, relation),
+        }
+    }
+
+    /// Find the bindings of a subgraph pattern, in lexicographic order.
+    ///
+    /// See [`pattern::match_pattern`].
+    pub fn match_pattern(
+        &self,
+        pattern: &Pattern,
+        limit: usize,
+    ) -> Result<Vec<PatternMatch>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => pattern::match_pattern(graph, pattern, limit),
+            StorageBackend::Persistent(redb) => pattern::match_pattern(redb, pattern, limit),
+        }
+    }
+
+    /// Split the graph into weakly connected components.
+    ///
+    /// See [`components::weakly_connected_components`].
+    pub fn weakly_connected_components(
+        &self,
+        relation: Option<&Attribute>,
+    ) -> Result<Components, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => {
+                components::weakly_connected_components(graph, relation)
+            }
+            StorageBackend::Persistent(redb) => {
+                components::weakly_connected_components(redb, relation)
+            }
+        }
+    }
+
+    /// [`weakly_connected_components`](Self::weakly_connected_components)
+    /// under a visit budget; `Ok(None)` when `budget` runs out.
+    pub fn weakly_connected_components_within(
+        &self,
+        relation: Option<&Attribute>,
+        budget: &mut VisitBudget,
+    ) -> Result<Option<Components>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => {
+                components::weakly_connected_components_within(graph, relation, budget)
+            }
+            StorageBackend::Persistent(redb) => {
+                components::weakly_connected_components_within(redb, relation, budget)
+            }
+        }
+    }
+
+    /// Split the graph into strongly connected components and their cycles.
+    ///
+    /// See [`components::strongly_connected_components`].
+    pub fn strongly_connected_components(
+        &self,
+        relation: Option<&Attribute>,
+    ) -> Result<Components, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => {
+                components::strongly_connected_components(graph, relation)
+            }
+            StorageBackend::Persistent(redb) => {
+                components::strongly_connected_components(redb, relation)
+            }
+        }
+    }
+
+    /// [`strongly_connected_components`](Self::strongly_connected_components)
+    /// under a visit budget; `Ok(None)` when `budget` runs out.
+    pub fn strongly_connected_components_within(
+        &self,
+        relation: Option<&Attribute>,
+        budget: &mut VisitBudget,
+    ) -> Result<Option<Components>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => {
+                components::strongly_connected_components_within(graph, relation, budget)
+            }
+            StorageBackend::Persistent(redb) => {
+                components::strongly_connected_components_within(redb, relation, budget)
+            }
+        }
+    }
+
+    /// List the simple paths from `start` to `end`, heaviest first.
+    ///
+    /// See [`GraphStore::simple_paths`]. Returns `Ok(None)` when `start` or
+    /// `end` does not exist.
+    pub fn simple_paths(
+        &self,
+        start: NodeId,
+        end: NodeId,
+        max_hops: usize,
+        limit: usize,
+        relation: Option<&Attribute>,
+    ) -> Result<Option<Vec<RankedPath>>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => {
+                graph.simple_paths(start, end, max_hops, limit, relation)
+            }
+            StorageBackend::Persistent(redb) => {
+                redb.simple_paths(start, end, max_hops, limit, relation)
+            }
+        }
+    }
+
+    /// [`simple_paths`](Self::simple_paths) under a visit budget; `Ok(None)`
+    /// when `budget` runs out.
+    pub fn simple_paths_within(
+        &self,
+        start: NodeId,
+        end: NodeId,
+        max_hops: usize,
+        limit: usize,
+        relation: Option<&Attribute>,
+        budget: &mut VisitBudget,
+    ) -> Result<Option<Vec<RankedPath>>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => {
+                graph.simple_paths_within(start, end, max_hops, limit, relation, budget)
+            }
+            StorageBackend::Persistent(redb) => {
+                redb.simple_paths_within(start, end, max_hops, limit, relation, budget)
+            }
+        }
+    }
+
+    /// Find the `k` strongest simple paths from `start` to `end`.
+    ///
+    /// See [`GraphStore::k_strongest_paths`]. Returns `Ok(None)` when `start`
+    /// or `end` does not exist.
+    pub fn k_strongest_paths(
+        &self,
+        start: NodeId,
+        end: NodeId,
+        k: usize,
+        relation: Option<&Attribute>,
+    ) -> Result<Option<Vec<RankedPath>>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => graph.k_strongest_paths(start, end, k, relation),
+            StorageBackend::Persistent(redb) => redb.k_strongest_paths(start, end, k, relation),
+        }
+    }
+
+    /// [`k_strongest_paths`](Self::k_strongest_paths) under a visit budget;
+    /// `Ok(None)` when `budget` runs out.
+    pub fn k_strongest_paths_within(
+        &self,
+        start: NodeId,
+        end: NodeId,
+        k: usize,
+        relation: Option<&Attribute>,
+        budget: &mut VisitBudget,
+    ) -> Result<Option<Vec<RankedPath>>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => {
+                graph.k_strongest_paths_within(start, end, k, relation, budget)
+            }
+            StorageBackend::Persistent(redb) => {
+                redb.k_strongest_paths_within(start, end, k, relation, budget)
+            }
         }
     }
 
@@ -478,10 +828,153 @@ impl Session {
         }
     }
 
+    // =========================================================================
+    // BUDGETED QUERIES
+    // =========================================================================
+
+    /// [`traverse`](Self::traverse) under a visit budget; the artifact is
+    /// partial when `budget` runs out.
+    pub fn traverse_within(
+        &self,
+        start: NodeId,
+        depth: usize,
+        relation: Option<&Attribute>,
+        budget: &mut VisitBudget,
+    ) -> Result<Option<Artifact>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => {
+                graph.traverse_within(start, depth, relation, budget)
+            }
+            StorageBackend::Persistent(redb) => {
+                redb.traverse_within(start, depth, relation, budget)
+            }
+        }
+    }
+
+    /// [`traverse_filtered`](Self::traverse_filtered) under a visit budget;
+    /// the artifact is partial when `budget` runs out.
+    pub fn traverse_filtered_within(
+        &self,
+        start: NodeId,
+        depth: usize,
+        min_weight: EdgeWeight,
+        relation: Option<&Attribute>,
+        budget: &mut VisitBudget,
+    ) -> Result<Option<Artifact>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => {
+                graph.traverse_filtered_within(start, depth, min_weight, relation, budget)
+            }
+            StorageBackend::Persistent(redb) => {
+                redb.traverse_filtered_within(start, depth, min_weight, relation, budget)
+            }
+        }
+    }
+
+    /// The first `max_nodes` nodes of a budgeted traversal, with the edges
+    /// read while expanding them; see [`GraphStore::traverse_prefix_within`].
+    pub fn traverse_prefix_within(
+        &self,
+        start: NodeId,
+        depth: usize,
+        min_weight: Option<EdgeWeight>,
+        relation: Option<&Attribute>,
+        max_nodes: usize,
+        budget: &mut VisitBudget,
+    ) -> Result<Option<Artifact>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => {
+                graph.traverse_prefix_within(start, depth, min_weight, relation, max_nodes, budget)
+            }
+            StorageBackend::Persistent(redb) => {
+                redb.traverse_prefix_within(start, depth, min_weight, relation, max_nodes, budget)
+            }
+        }
+    }
+
+    /// [`strongest_path_with`](Self::strongest_path_with) under a visit
+    /// budget; `Ok(None)` when `budget` runs out.
+    pub fn strongest_path_within(
+        &self,
+        start: NodeId,
+        end: NodeId,
+        relation: Option<&Attribute>,
+        constraints: &PathConstraints,
+        budget: &mut VisitBudget,
+    ) -> Result<Option<Vec<NodeId>>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => {
+                graph.strongest_path_within(start, end, relation, constraints, budget)
+            }
+            StorageBackend::Persistent(redb) => {
+                redb.strongest_path_within(start, end, relation, constraints, budget)
+            }
+        }
+    }
+
+    /// [`intersect`](Self::intersect) under a visit budget; no nodes when
+    /// `budget` runs out.
+    pub fn intersect_within(
+        &self,
+        nodes: &[NodeId],
+        budget: &mut VisitBudget,
+    ) -> Result<Vec<NodeId>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => graph.intersect_within(nodes, budget),
+            StorageBackend::Persistent(redb) => redb.intersect_within(nodes, budget),
+        }
+    }
+
     // =========================================================================
     // PROPERTIES
     // =========================================================================
 
+    /// Rank nodes by token matches against their property values.
+    ///
+    /// See [`GraphStore::search`] for the scoring rules.
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<(NodeId, u64)>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => graph.search(query, limit),
+            StorageBackend::Persistent(redb) => redb.search(query, limit),
+        }
+    }
+
+    /// Find nodes carrying the exact `(attribute, value)` pair.
+    ///
+    /// Returns up to `limit` node ids in ascending order, after `after` if given.
+    pub fn find_by_property(
+        &self,
+        attribute: &Attribute,
+        value: &Value,
+        after: Option<NodeId>,
+        limit: usize,
+    ) -> Result<Vec<NodeId>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => {
+                graph.find_by_property(attribute, value, after, limit)
+            }
+            StorageBackend::Persistent(redb) => {
+                redb.find_by_property(attribute, value, after, limit)
+            }
+        }
+    }
+
+    /// Retract one `(attribute, value)` pair from a node.
+    ///
+    /// Returns `Ok(false)` when the node does not carry that pair and
+    /// `KremisError::NodeNotFound` when the node does not exist.
+    pub fn remove_property(
+        &mut self,
+        node: NodeId,
+        attribute: &Attribute,
+        value: &Value,
+    ) -> Result<bool, KremisError> {
+        match &mut self.backend {
+            StorageBackend::InMemory(graph) => graph.remove_property(node, attribute, value),
+            StorageBackend::Persistent(redb) => redb.remove_property(node, attribute, value),
+        }
+    }
+
     /// Get all properties for a node.
     pub fn get_properties(&self, node: NodeId) -> Result<Vec<(Attribute, Value)>, KremisError> {
         match &self.backend {
@@ -490,10 +983,225 @@ impl Session {
         }
     }
 
+    // =========================================================================
+    // ENTITY KEYS
+    // =========================================================================
+
+    /// Resolve an entity key without registering it.
+    pub fn resolve_entity_key(&self, key: &str) -> Result<Option<EntityId>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => graph.entity_for_key(key),
+            StorageBackend::Persistent(redb) => redb.entity_for_key(key),
+        }
+    }
+
+    /// Resolve an entity key, allocating an entity on first use.
+    ///
+    /// See [`GraphStore::register_entity_key`] for how ids are allocated.
+    pub fn register_entity_key(&mut self, key: &str) -> Result<EntityId, KremisError> {
+        match &mut self.backend {
+            StorageBackend::InMemory(graph) => graph.register_entity_key(key),
+            StorageBackend::Persistent(redb) => redb.register_entity_key(key),
+        }
+    }
+
+    /// Get the key bound to an entity, if any.
+    pub fn entity_key(&self, entity: EntityId) -> Result<Option<String>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => graph.key_for_entity(entity),
+            StorageBackend::Persistent(redb) => redb.key_for_entity(entity),
+        }
+    }
+
+    // =========================================================================
+    // MERGE
+    // =========================================================================
+
+    /// Merge a canonical graph into this one, reconciling nodes by entity.
+    ///
+    /// See [`merge::plan`] for the rules. The whole merge is applied or
+    /// nothing is; merged nodes are not added to the active context.
+    pub fn merge_canonical(
+        &mut self,
+        incoming: &CanonicalGraph,
+        policy: MergePolicy,
+    ) -> Result<MergeDiagnostics, KremisError> {
+        match &mut self.backend {
+            StorageBackend::InMemory(graph) => {
+                let plan = merge::plan(graph, graph.next_node_id(), incoming, policy)?;
+                plan.record.apply(graph)?;
+                Ok(plan.diagnostics)
+            }
+            StorageBackend::Persistent(redb) => redb.merge_canonical(incoming, policy),
+        }
+    }
+
+    // =========================================================================
+    // HISTORY
+    // =========================================================================
+
+    /// Get the sequence number of the last logged mutation.
+    ///
+    /// Only the persistent backend keeps a mutation log; in-memory sessions
+    /// return `None`.
+    pub fn mutation_seq(&self) -> Result<Option<u64>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(_) => Ok(None),
+            StorageBackend::Persistent(redb) => redb.mutation_seq().map(Some),
+        }
+    }
+
+    /// Rebuild the graph as it was right after mutation `seq`.
+    ///
+    /// Returns `KremisError::InvalidSignal` for in-memory sessions, which keep
+    /// no history, and for a `seq` beyond [`mutation_seq`](Self::mutation_seq).
+    pub fn snapshot_at(&self, seq: u64) -> Result<Graph, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(_) => Err(KremisError::InvalidSignal),
+            StorageBackend::Persistent(redb) => redb.snapshot_at(seq),
+        }
+    }
+
+    /// [`snapshot_at`](Self::snapshot_at) under a visit budget, one visit
+    /// per replayed mutation. Returns `KremisError::HistoryReplayLimit` once
+    /// `budget` runs out.
+    pub fn snapshot_at_within(
+        &self,
+        seq: u64,
+        budget: &mut VisitBudget,
+    ) -> Result<Graph, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(_) => Err(KremisError::InvalidSignal),
+            StorageBackend::Persistent(redb) => redb
+                .snapshot_at_within(seq, budget)?
+                .ok_or(KremisError::HistoryReplayLimit(seq)),
+        }
+    }
+
+    /// Find the snapshot checkpoint whose state hashes to `hash`.
+    ///
+    /// Returns `None` for in-memory sessions, which keep no checkpoints.
+    ///
+    /// # Requires
+    ///
+    /// Only available with the `crypto-hash` feature.
+    #[cfg(feature = "crypto-hash")]
+    pub fn checkpoint_with_hash(&self, hash: &[u8; 32]) -> Result<Option<u64>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(_) => Ok(None),
+            StorageBackend::Persistent(redb) => redb.checkpoint_with_hash(hash),
+        }
+    }
+
+    /// Get the input journal, if it covers the whole history.
+    ///
+    /// Returns `None` for in-memory sessions, which keep no journal, and for
+    /// databases that already held data when the journal was introduced.
+    /// Replay the entries with [`crate::journal::replay`].
+    pub fn journal(&self) -> Result<Option<Vec<JournalEntry>>, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(_) => Ok(None),
+            StorageBackend::Persistent(redb) => {
+                if redb.journal_is_complete()? {
+                    redb.journal().map(Some)
+                } else {
+                    Ok(None)
+                }
+            }
+        }
+    }
+
     // =========================================================================
     // EXPORT SUPPORT (M3 FIX)
     // =========================================================================
 
+    /// Write the canonical export of the current state to `out`.
+    ///
+    /// The bytes equal `export_canonical` of
+    /// [`export_graph_snapshot`](Self::export_graph_snapshot), but a
+    /// persistent backend streams them from its tables instead of building
+    /// the snapshot first.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KremisError::IoError` if reading the database or writing to
+    /// `out` fails.
+    pub fn write_canonical<W: Write>(&self, out: W) -> Result<CanonicalHeader, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => stream::write_canonical(graph, out),
+            StorageBackend::Persistent(redb) => {
+                stream::write_canonical(&redb.canonical_export()?, out)
+            }
+        }
+    }
+
+    /// BLAKE3 hash of the canonical export of the current state.
+    ///
+    /// Equal to `certificate::state_hash` of the snapshot, computed without
+    /// buffering the export.
+    ///
+    /// # Requires
+    ///
+    /// Only available with the `crypto-hash` feature.
+    #[cfg(feature = "crypto-hash")]
+    pub fn state_hash(&self) -> Result<[u8; 32], KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => stream::stream_state_hash(graph),
+            StorageBackend::Persistent(redb) => {
+                stream::stream_state_hash(&redb.canonical_export()?)
+            }
+        }
+    }
+
+    /// The delta from the canonical export read by `open_base` to the current
+    /// state, with neither state held in memory.
+    ///
+    /// See [`stream_delta`](crate::delta::stream_delta): `open_base` is
+    /// called once per pass over the export.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KremisError::SerializationError` if the base is not a valid
+    /// canonical export, and `KremisError::IoError` if reading the database
+    /// fails.
+    ///
+    /// # Requires
+    ///
+    /// Only available with the `crypto-hash` feature.
+    #[cfg(feature = "crypto-hash")]
+    pub fn delta_from<R: std::io::Read>(
+        &self,
+        open_base: impl Fn() -> Result<R, KremisError>,
+    ) -> Result<crate::delta::GraphDelta, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => crate::delta::stream_delta(open_base, graph),
+            StorageBackend::Persistent(redb) => {
+                crate::delta::stream_delta(open_base, &redb.canonical_export()?)
+            }
+        }
+    }
+
+    /// Build a [`QueryCertificate`] for `artifact` against the current state.
+    ///
+    /// The evidence is read from the backend directly, so a persistent
+    /// session is not snapshotted; see [`QueryCertificate::from_store`].
+    pub fn certificate(
+        &self,
+        state_hash: [u8; 32],
+        query: impl Into<String>,
+        grounding: impl Into<String>,
+        artifact: &Artifact,
+    ) -> Result<QueryCertificate, KremisError> {
+        match &self.backend {
+            StorageBackend::InMemory(graph) => Ok(QueryCertificate::new(
+                state_hash, query, grounding, graph, artifact,
+            )),
+            StorageBackend::Persistent(redb) => {
+                QueryCertificate::from_store(state_hash, query, grounding, redb, artifact)
+            }
+        }
+    }
+
     /// Build an in-memory Graph snapshot for export purposes.
     ///
     /// This method works with both in-memory and persistent backends:
@@ -522,9 +1230,9 @@ impl Session {
                     graph.import_node(node.clone());
                 }
 
-                // Import all edges
-                for (from, to, weight) in redb.edges()? {
-                    graph.insert_edge(from, to, weight)?;
+                // Import all edges, keeping their relation labels
+                for (from, to, relation, weight) in redb.edges_with_relations()? {
+                    graph.insert_edge(from, to, relation.as_ref(), weight)?;
                 }
 
                 // Import all properties — propagate errors so corrupted
@@ -535,6 +1243,14 @@ impl Session {
                     }
                 }
 
+                for (key, entity) in redb.entity_keys()? {
+                    graph.bind_entity_key(&key, entity)?;
+                }
+
+                // Ids of deleted nodes are never reused, so carry the counter
+                // over instead of deriving it from the surviving nodes.
+                graph.advance_next_node_id(redb.next_node_id());
+
                 Ok(graph)
             }
         }
@@ -640,7 +1356,7 @@ mod tests {
 
         assert!(
             session
-                .get_edge(nodes[0], nodes[1])
+                .get_edge(nodes[0], nodes[1], None)
                 .expect("get_edge")
                 .is_some()
         );
@@ -666,16 +1382,19 @@ mod tests {
         let session = Session::new();
 
         // Missing node → Ok(None), not Err
-        assert!(matches!(session.traverse(NodeId(999), 1), Ok(None)));
+        assert!(matches!(session.traverse(NodeId(999), 1, None), Ok(None)));
+        assert!(matches!(
+            session.traverse_filtered(NodeId(999), 1, EdgeWeight::new(1), None),
+            Ok(None)
+        ));
         assert!(matches!(
-            session.traverse_filtered(NodeId(999), 1, EdgeWeight::new(1)),
+            session.strongest_path(NodeId(1), NodeId(2), None),
             Ok(None)
         ));
         assert!(matches!(
-            session.strongest_path(NodeId(1), NodeId(2)),
+            session.get_edge(NodeId(1), NodeId(2), None),
             Ok(None)
         ));
-        assert!(matches!(session.get_edge(NodeId(1), NodeId(2)), Ok(None)));
         assert!(matches!(session.compose(NodeId(999), 1), Ok(None)));
 
         // Empty graph → Ok(0) / Ok([]), not Err
@@ -707,6 +1426,135 @@ mod tests {
         assert!(props2.contains(&(Attribute::new("role"), Value::new("admin"))));
     }
 
+    #[test]
+    fn redb_certificate_matches_the_certificate_of_its_snapshot() {
+        let dir = tempfile::tempdir().expect("tmpdir");
+        let mut redb = RedbGraph::open(dir.path().join("session.redb")).expect("open");
+        let [a, b, c] = [1, 2, 3].map(|e| redb.insert_node(EntityId(e)).expect("node"));
+        let owns = Attribute::new("owns");
+        redb.insert_edge(a, b, None, EdgeWeight::new(2))
+            .expect("edge");
+        redb.insert_edge(a, b, Some(&owns), EdgeWeight::new(7))
+            .expect("edge");
+        redb.insert_edge(b, c, None, EdgeWeight::new(4))
+            .expect("edge");
+        let session = Session::with_redb_graph(redb);
+        let snapshot = session.export_graph_snapshot().expect("snapshot");
+
+        for artifact in [
+            Artifact::with_path(vec![a, b, c, NodeId(99)]),
+            Artifact::with_subgraph(vec![a], vec![(a, b, None, EdgeWeight::new(7))]),
+            Artifact::with_path(Vec::new()),
+        ] {
+            let from_store = session
+                .certificate([7; 32], "q", "fact", &artifact)
+                .expect("certificate");
+            let from_snapshot = QueryCertificate::new([7; 32], "q", "fact", &snapshot, &artifact);
+            assert_eq!(from_store, from_snapshot);
+        }
+    }
+
+    #[test]
+    fn redb_snapshot_carries_entity_keys() {
+        let dir = tempfile::tempdir().expect("tmpdir");
+        let mut session = Session::with_redb(dir.path().join("session.redb")).expect("open");
+        let entity = session.register_entity_key("svc:ledger").expect("register");
+        assert_eq!(
+            session.resolve_entity_key("svc:ledger").expect("resolve"),
+            Some(entity)
+        );
+        assert_eq!(
+            session.resolve_entity_key("svc:other").expect("resolve"),
+            None
+        );
+
+        let snapshot = session.export_graph_snapshot().expect("snapshot");
+        assert_eq!(
+            snapshot.entity_keys().collect::<Vec<_>>(),
+            vec![("svc:ledger", entity)]
+        );
+    }
+
+    #[test]
+    fn snapshot_at_matches_the_export_of_the_time() {
+        use crate::export::export_canonical;
+
+        let dir = tempfile::tempdir().expect("tmpdir");
+        let mut session = Session::with_redb(dir.path().join("session.redb")).expect("open");
+        session
+            .ingest(&make_signal(1, "name", "Alice"))
+            .expect("ingest");
+        let node = session
+            .ingest(&make_signal(2, "name", "Bob"))
+            .expect("ingest");
+        session.register_entity_key("svc:ledger").expect("register");
+
+        let seq = session.mutation_seq().expect("seq").expect("persistent");
+        let bytes_then =
+            export_canonical(&session.export_graph_snapshot().expect("snapshot")).expect("export");
+
+        session.remove_node(node).expect("remove");
+        let bytes_now =
+            export_canonical(&session.export_graph_snapshot().expect("snapshot")).expect("export");
+        assert_ne!(bytes_then, bytes_now);
+
+        let replayed = session.snapshot_at(seq).expect("replay");
+        assert_eq!(export_canonical(&replayed).expect("export"), bytes_then);
+        let latest = session
+            .snapshot_at(session.mutation_seq().expect("seq").expect("persistent"))
+            .expect("replay");
+        assert_eq!(export_canonical(&latest).expect("export"), bytes_now);
+
+        assert_eq!(Session::new().mutation_seq().expect("seq"), None);
+        assert!(matches!(
+            Session::new().snapshot_at(0),
+            Err(KremisError::InvalidSignal)
+        ));
+    }
+
+    #[test]
+    fn journal_replay_reproduces_the_redb_graph() {
+        use crate::export::export_canonical;
+        use crate::journal::replay;
+
+        let dir = tempfile::tempdir().expect("tmpdir");
+        let mut session = Session::with_redb(dir.path().join("session.redb")).expect("open");
+        let nodes = session
+            .ingest_sequence(&[
+                make_signal(1, "name", "Alice"),
+                make_signal(2, "name", "Bob"),
+                make_signal(3, "name", "Carol"),
+            ])
+            .expect("ingest");
+        session
+            .ingest(&make_signal(1, "role", "admin"))
+            .expect("ingest");
+        session
+            .decrement_edge(nodes[0], nodes[1], None)
+            .expect("decrement");
+        session
+            .remove_property(nodes[0], &Attribute::new("role"), &Value::new("admin"))
+            .expect("retract");
+        session.remove_node(nodes[2]).expect("remove");
+        session.register_entity_key("svc:ledger").expect("register");
+        session
+            .ingest_sequence_with(
+                &[make_signal(4, "name", "Dan")],
+                &LinkMode::Explicit(vec![(EntityId(4), EntityId(1), None)]),
+            )
+            .expect("ingest");
+
+        let journal = session.journal().expect("journal").expect("complete");
+        assert_eq!(journal.len(), 7);
+        let replayed = replay(&journal).expect("replay");
+        assert_eq!(
+            export_canonical(&replayed).expect("export"),
+            export_canonical(&session.export_graph_snapshot().expect("snapshot")).expect("export")
+        );
+
+        assert_eq!(Session::new().journal().expect("journal"), None);
+    }
+
     /// `ingest` on the persistent backend routes through the batch path to spend
     /// one fsync instead of two. This asserts the observable result is unchanged:
     /// node created, property stored, buffer activated, repeats idempotent.
@@ -742,4 +1590,38 @@ mod tests {
         assert_eq!(session.node_count().expect("count"), 1);
         assert_eq!(session.get_properties(node).expect("props").len(), 2);
     }
+    #[test]
+    fn remove_node_clears_graph_and_buffer_on_both_backends() {
+        let dir = tempfile::tempdir().expect("tmpdir");
+        let persistent = Session::with_redb(dir.path().join("session.redb")).expect("open");
+
+        for mut session in [Session::new(), persistent] {
+            let signals = vec![
+                make_signal(1, "name", "Alice"),
+                make_signal(2, "name", "Bob"),
+                make_signal(3, "name", "Carol"),
+            ];
+            session.ingest_sequence(&signals).expect("ingest");
+            let bob = session.lookup_entity(EntityId(2)).expect("bob");
+            session
+                .ingest(&make_signal(2, "role", "admin"))
+                .expect("ingest");
+            assert!(session.buffer().active_nodes.contains(&bob));
+
+            let removed = session.remove_node(bob).expect("remove");
+            assert_eq!(removed.entity, EntityId(2));
+            assert_eq!(session.lookup_entity(EntityId(2)), None);
+            assert!(!session.buffer().active_nodes.contains(&bob));
+            assert_eq!(session.node_count().expect("count"), 2);
+            assert_eq!(session.edge_count().expect("count"), 0);
+
+            let snapshot = session.export_graph_snapshot().expect("snapshot");
+            assert_eq!(snapshot.nodes().count(), 2);
+            assert_eq!(snapshot.edges().count(), 0);
+            assert!(matches!(
+                session.remove_node(bob),
+                Err(KremisError::NodeNotFound(_))
+            ));
+        }
+    }
 }
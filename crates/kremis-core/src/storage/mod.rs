@@ -9,4 +9,5 @@
 
 mod redb_graph;
 
-pub use redb_graph::RedbGraph;
+pub(crate) use redb_graph::stable_attr_hash;
+pub use redb_graph::{CheckpointPolicy, RedbExport, RedbGraph};
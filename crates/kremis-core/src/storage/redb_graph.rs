@@ -15,18 +15,60 @@
 //! storage backend for Kremis sessions. Unlike the in-memory `Graph`,
 //! `RedbGraph` persists data to disk automatically.
 
-use crate::graph::GraphStore;
-use crate::{Attribute, EdgeWeight, EntityId, KremisError, Node, NodeId, Signal, Value};
-use redb::{Database, ReadableDatabase, ReadableTable, ReadableTableMetadata, TableDefinition};
+use crate::export::{
+    CanonicalEdge, CanonicalEntityKey, CanonicalGraph, CanonicalNode, CanonicalProperty,
+};
+use crate::graph::{Graph, GraphStore, LoadDiagnostics, SerializableGraph, VisitBudget};
+use crate::history::{Mutation, MutationRecord};
+use crate::ingestor::{Ingestor, LinkMode};
+use crate::journal::JournalEntry;
+use crate::merge::{self, MergeDiagnostics, MergePolicy};
+use crate::primitives::{MAX_SNAPSHOT_CHECKPOINTS, SNAPSHOT_CHECKPOINT_INTERVAL};
+use crate::stream::{self, CanonicalItem, CanonicalSource};
+use crate::{
+    Attribute, EdgeWeight, EntityId, KremisError, LabeledEdge, Node, NodeId, Signal, Value,
+};
+use redb::{
+    Database, ReadTransaction, ReadableDatabase, ReadableTable, ReadableTableMetadata, Table,
+    TableDefinition, WriteTransaction,
+};
 use std::collections::BTreeMap;
 use std::path::Path;
 
+#[cfg(feature = "crypto-hash")]
+use crate::delta::GraphDelta;
+#[cfg(feature = "crypto-hash")]
+use crate::diff::{EdgeReweight, GraphDiff};
+#[cfg(feature = "crypto-hash")]
+use crate::history::{BaseValues, EdgeKey, LogWindow};
+#[cfg(feature = "crypto-hash")]
+use std::collections::BTreeSet;
+
 /// Table for nodes: NodeId(u64) -> serialized Node bytes
 const NODES: TableDefinition<u64, &[u8]> = TableDefinition::new("nodes");
 
 /// Table for edges: (from_id, to_id) -> weight
 const EDGES: TableDefinition<(u64, u64), i64> = TableDefinition::new("edges");
 
+/// Table for labeled edges: (from_id, to_id, relation) -> weight
+///
+/// Unlabeled association edges stay in `EDGES`, so databases written before
+/// relation labels existed open without migration.
+const LABELED_EDGES: TableDefinition<(u64, u64, &str), i64> = TableDefinition::new("labeled_edges");
+
+/// Table for the reverse adjacency index: (to_id, from_id, relation) -> ()
+///
+/// Unlabeled edges are keyed with the empty relation, which `validate_relation`
+/// never accepts as a label, so it sorts first exactly like `None` does in
+/// `Graph`. Weights are read back from `EDGES` / `LABELED_EDGES`.
+const REVERSE_EDGES: TableDefinition<(u64, u64, &str), ()> = TableDefinition::new("reverse_edges");
+
+/// METADATA key recording that `REVERSE_EDGES` covers every stored edge.
+///
+/// Absent in databases written before the reverse index existed; `open`
+/// rebuilds the index once and then sets it.
+const REVERSE_INDEX_BUILT: &str = "reverse_index_built";
+
 /// Table for entity index: EntityId(u64) -> NodeId(u64)
 const ENTITY_INDEX: TableDefinition<u64, u64> = TableDefinition::new("entity_index");
 
@@ -42,9 +84,82 @@ const PROPERTIES: TableDefinition<(u64, u64), &[u8]> = TableDefinition::new("pro
 /// Maintained incrementally so `store_property` can enforce
 /// `MAX_PROPERTIES_PER_NODE` in O(1) instead of scanning every bucket of the
 /// node on each insert (which would be O(N²) and hold the write lock).
-/// Properties are never removed, so the count is monotonic.
+/// `remove_property` decrements it and `remove_node` deletes the row; a count
+/// that reaches zero is deleted too, so an absent row always means zero.
 const PROPERTY_COUNTS: TableDefinition<u64, u64> = TableDefinition::new("property_counts");
 
+/// Table for the inverted property index: (attribute, value, node_id) -> ()
+///
+/// Written alongside every PROPERTIES change, so a range over one
+/// `(attribute, value)` prefix yields its nodes in ascending id order.
+const PROPERTY_INDEX: TableDefinition<(&str, &str, u64), ()> =
+    TableDefinition::new("property_index");
+
+/// METADATA key recording that `PROPERTY_INDEX` covers every stored property.
+const PROPERTY_INDEX_BUILT: &str = "property_index_built";
+
+/// Table for token posting lists: (token, node_id) -> number of the node's
+/// property values containing the token.
+///
+/// Tokens come from [`crate::search::tokenize`]; sorting by token first makes
+/// a prefix search a single range scan.
+const TOKEN_INDEX: TableDefinition<(&str, u64), u64> = TableDefinition::new("token_index");
+
+/// METADATA key recording that `TOKEN_INDEX` covers every stored value.
+const TOKEN_INDEX_BUILT: &str = "token_index_built";
+
+/// Table for the entity key registry: key -> EntityId(u64)
+const ENTITY_KEYS: TableDefinition<&str, u64> = TableDefinition::new("entity_keys");
+
+/// Table for the inverse registry: EntityId(u64) -> key
+///
+/// Written in the same transaction as `ENTITY_KEYS`, so the two stay a
+/// one-to-one mapping.
+const ENTITY_KEY_NAMES: TableDefinition<u64, &str> = TableDefinition::new("entity_key_names");
+
+/// Table for the mutation log: sequence number -> postcard `MutationRecord`
+///
+/// Every committed write transaction that changed something appends one
+/// record, so replaying records `1..=n` rebuilds the state after mutation `n`.
+const MUTATION_LOG: TableDefinition<u64, &[u8]> = TableDefinition::new("mutation_log");
+
+/// Table for snapshot checkpoints: sequence number -> postcard
+/// `SerializableGraph` of the state right after that mutation
+///
+/// Written every `CheckpointPolicy::interval` mutations, in the transaction
+/// that reaches the sequence number, so point-in-time reads replay the log
+/// from the nearest checkpoint instead of from its start.
+const SNAPSHOT_CHECKPOINTS: TableDefinition<u64, &[u8]> =
+    TableDefinition::new("snapshot_checkpoints");
+
+/// Table for checkpoint state hashes: sequence number -> BLAKE3 state hash
+/// of the checkpoint with that sequence number
+///
+/// Only written with the `crypto-hash` feature; a checkpoint without an
+/// entry is still replayed from, it just cannot be found by its hash.
+const CHECKPOINT_HASHES: TableDefinition<u64, &[u8]> = TableDefinition::new("checkpoint_hashes");
+
+/// Largest number of mutations logged in one record by
+/// [`RedbGraph::import_canonical`], which never buffers the whole import's
+/// mutations.
+const IMPORT_LOG_CHUNK: usize = 4096;
+
+/// METADATA key holding the sequence number of the last logged transaction.
+///
+/// Absent in databases written before the log existed; `open` then logs the
+/// whole current state as a single baseline record.
+const MUTATION_SEQ: &str = "mutation_seq";
+
+/// Table for the input journal: sequence number -> postcard `JournalEntry`
+const JOURNAL: TableDefinition<u64, &[u8]> = TableDefinition::new("journal");
+
+/// METADATA key holding the sequence number of the last journal entry.
+const JOURNAL_SEQ: &str = "journal_seq";
+
+/// METADATA marker set when the journal was started on a non-empty database,
+/// so the state before its first entry was never journaled.
+const JOURNAL_PARTIAL: &str = "journal_partial";
+
 /// Compute a stable, cross-version attribute hash for use as a PROPERTIES table sub-key.
 ///
 /// Uses FNV-1a 64-bit: a fixed, publicly documented algorithm guaranteed to produce
@@ -60,7 +175,7 @@ const PROPERTY_COUNTS: TableDefinition<u64, u64> = TableDefinition::new("propert
 /// ## FNV-1a 64-bit constants
 /// - Offset basis: 0xcbf29ce484222325 (standard FNV-1a 64-bit)
 /// - Prime:        0x100000001b3
-fn stable_attr_hash(s: &str) -> u64 {
+pub(crate) fn stable_attr_hash(s: &str) -> u64 {
     const FNV_OFFSET: u64 = 0xcbf29ce484222325;
     const FNV_PRIME: u64 = 0x100000001b3;
     let mut hash = FNV_OFFSET;
@@ -71,6 +186,24 @@ fn stable_attr_hash(s: &str) -> u64 {
     hash
 }
 
+/// Decode a PROPERTIES bucket, checking that it belongs to `attribute`.
+///
+/// Buckets are keyed by attribute hash, so a bucket holding a different
+/// attribute means two names collided; that is reported as corruption rather
+/// than silently merging their values.
+fn decode_property_bucket(data: &[u8], attribute: &Attribute) -> Result<Vec<Value>, KremisError> {
+    let (stored_attr, values) = postcard::from_bytes::<(Attribute, Vec<Value>)>(data)
+        .map_err(|e| KremisError::DeserializationError(e.to_string()))?;
+    if stored_attr != *attribute {
+        return Err(KremisError::StorageCorruption(format!(
+            "attribute hash collision: bucket contains \"{}\", expected \"{}\"",
+            stored_attr.as_str(),
+            attribute.as_str()
+        )));
+    }
+    Ok(values)
+}
+
 /// A disk-backed graph store using redb.
 ///
 /// Per the architectural decision:
@@ -84,6 +217,360 @@ pub struct RedbGraph {
     entity_cache: BTreeMap<EntityId, NodeId>,
     /// Next available node ID.
     next_node_id: u64,
+    /// When snapshot checkpoints are taken and how many are kept.
+    checkpoints: CheckpointPolicy,
+}
+
+/// When a [`RedbGraph`] takes snapshot checkpoints, and how many it keeps.
+///
+/// A checkpoint rebuilds the whole graph from the previous one and the log
+/// records since, then serializes and hashes it inside the write transaction
+/// that reaches it. That transaction pays time and memory proportional to the
+/// graph, and each kept checkpoint costs about one serialized graph on disk.
+/// A longer interval makes that rarer but lets point-in-time reads replay up
+/// to `interval` records past a checkpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckpointPolicy {
+    /// Log records between two checkpoints. `0` takes no checkpoints, so
+    /// point-in-time reads replay the log from its start.
+    pub interval: u64,
+    /// Checkpoints kept before older ones are thinned out. The newest one is
+    /// always kept.
+    pub max_kept: usize,
+}
+
+impl Default for CheckpointPolicy {
+    fn default() -> Self {
+        Self {
+            interval: SNAPSHOT_CHECKPOINT_INTERVAL,
+            max_kept: MAX_SNAPSHOT_CHECKPOINTS,
+        }
+    }
+}
+
+impl CheckpointPolicy {
+    /// Whether the record with sequence number `seq` gets a checkpoint.
+    fn is_due(&self, seq: u64) -> bool {
+        self.interval != 0 && seq.is_multiple_of(self.interval)
+    }
+}
+
+/// A pinned state of a [`RedbGraph`], read table by table as a
+/// [`CanonicalSource`].
+///
+/// Yields what `Session::export_graph_snapshot` would hold, without building
+/// it: edges whose endpoints are gone are skipped, and only one node's
+/// properties are held at a time.
+pub struct RedbExport {
+    txn: ReadTransaction,
+    next_node_id: u64,
+}
+
+impl std::fmt::Debug for RedbExport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedbExport")
+            .field("next_node_id", &self.next_node_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CanonicalSource for RedbExport {
+    fn visit_canonical(
+        &self,
+        visit: &mut dyn FnMut(CanonicalItem) -> Result<(), KremisError>,
+    ) -> Result<u64, KremisError> {
+        let io = |e: redb::Error| KremisError::IoError(e.to_string());
+        let open = |e: redb::TableError| io(e.into());
+        let read = |e: redb::StorageError| io(e.into());
+        let nodes_table = self.txn.open_table(NODES).map_err(open)?;
+        let edges_table = self.txn.open_table(EDGES).map_err(open)?;
+        let labeled_table = self.txn.open_table(LABELED_EDGES).map_err(open)?;
+        let props_table = self.txn.open_table(PROPERTIES).map_err(open)?;
+        let keys_table = self.txn.open_table(ENTITY_KEYS).map_err(open)?;
+
+        let mut next_node_id = self.next_node_id;
+        for entry in nodes_table.iter().map_err(read)? {
+            let (_, value) = entry.map_err(read)?;
+            let node: Node = postcard::from_bytes(value.value())
+                .map_err(|e| KremisError::SerializationError(e.to_string()))?;
+            next_node_id = next_node_id.max(node.id.0.saturating_add(1));
+            visit(CanonicalItem::Node(CanonicalNode::from(&node)))?;
+        }
+
+        // Both edge tables are ordered by (from, to[, relation]); merging
+        // them with the unlabeled edge first gives canonical edge order.
+        let mut unlabeled = edges_table.iter().map_err(read)?.peekable();
+        let mut labeled = labeled_table.iter().map_err(read)?.peekable();
+        loop {
+            let take_unlabeled = match (unlabeled.peek(), labeled.peek()) {
+                (None, None) => break,
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (Some(Ok((a, _))), Some(Ok((b, _)))) => {
+                    let (from, to) = a.value();
+                    let (b_from, b_to, _) = b.value();
+                    (from, to) <= (b_from, b_to)
+                }
+                // Let the failing entry surface its error below.
+                (Some(Err(_)), _) => true,
+                (_, Some(Err(_))) => false,
+            };
+            let edge = if take_unlabeled {
+                let (key, value) =
+                    unlabeled.next().transpose().map_err(read)?.ok_or_else(|| {
+                        KremisError::IoError("edge table changed during export".to_string())
+                    })?;
+                let (from, to) = key.value();
+                CanonicalEdge::new(NodeId(from), NodeId(to), EdgeWeight::new(value.value()))
+            } else {
+                let (key, value) = labeled.next().transpose().map_err(read)?.ok_or_else(|| {
+                    KremisError::IoError("edge table changed during export".to_string())
+                })?;
+                let (from, to, relation) = key.value();
+                CanonicalEdge {
+                    from,
+                    to,
+                    relation: Some(relation.to_string()),
+                    weight: value.value(),
+                }
+            };
+            if nodes_table.get(edge.from).map_err(read)?.is_none()
+                || nodes_table.get(edge.to).map_err(read)?.is_none()
+            {
+                continue;
+            }
+            visit(CanonicalItem::Edge(edge))?;
+        }
+
+        // Property rows are keyed by attribute hash, so each node's rows are
+        // sorted here; a node holds at most MAX_PROPERTIES_PER_NODE of them.
+        for entry in nodes_table.iter().map_err(read)? {
+            let (key, _) = entry.map_err(read)?;
+            let node_id = key.value();
+            let mut properties = Vec::new();
+            for row in props_table
+                .range((node_id, 0u64)..=(node_id, u64::MAX))
+                .map_err(read)?
+            {
+                let (_, data) = row.map_err(read)?;
+                let (attr, values): (Attribute, Vec<Value>) = postcard::from_bytes(data.value())
+                    .map_err(|e| KremisError::DeserializationError(e.to_string()))?;
+                for value in values {
+                    properties.push(CanonicalProperty {
+                        node_id,
+                        attribute: attr.as_str().to_string(),
+                        value: value.as_str().to_string(),
+                    });
+                }
+            }
+            properties.sort();
+            properties.dedup();
+            for property in properties {
+                visit(CanonicalItem::Property(property))?;
+            }
+        }
+
+        for entry in keys_table.iter().map_err(read)? {
+            let (key, value) = entry.map_err(read)?;
+            visit(CanonicalItem::EntityKey(CanonicalEntityKey {
+                key: key.value().to_string(),
+                entity: value.value(),
+            }))?;
+        }
+
+        Ok(next_node_id)
+    }
+}
+
+#[cfg(feature = "crypto-hash")]
+impl RedbExport {
+    /// The delta from the state right after mutation `seq`, whose state hash
+    /// is `base_hash`, to the pinned state.
+    ///
+    /// Neither state is rebuilt. The log records after `seq` name every node,
+    /// edge, property and entity key that changed; their pinned values come
+    /// from the tables and their earlier values from walking the records up
+    /// to `seq` back until each is found. Memory holds the changes, and time
+    /// is at most one pass over the log plus one over the pinned state to
+    /// hash it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KremisError::InvalidSignal` if `seq` is beyond the pinned
+    /// mutation sequence number, and `KremisError::StorageCorruption` if the
+    /// log has no record for it.
+    ///
+    /// # Requires
+    ///
+    /// Only available with the `crypto-hash` feature.
+    pub fn delta_since(&self, seq: u64, base_hash: [u8; 32]) -> Result<GraphDelta, KremisError> {
+        let io = |e: redb::Error| KremisError::IoError(e.to_string());
+        let open = |e: redb::TableError| io(e.into());
+        let read = |e: redb::StorageError| io(e.into());
+        let decode = |data: &[u8]| {
+            postcard::from_bytes::<MutationRecord>(data)
+                .map_err(|e| KremisError::DeserializationError(e.to_string()))
+        };
+        let current = self
+            .txn
+            .open_table(METADATA)
+            .map_err(open)?
+            .get(MUTATION_SEQ)
+            .map_err(read)?
+            .map(|v| v.value())
+            .unwrap_or(0);
+        if seq > current {
+            return Err(KremisError::InvalidSignal);
+        }
+
+        let log_table = self.txn.open_table(MUTATION_LOG).map_err(open)?;
+        let base_next = if seq == 0 {
+            0
+        } else {
+            let data = log_table.get(seq).map_err(read)?.ok_or_else(|| {
+                KremisError::StorageCorruption(format!("mutation log has no record {seq}"))
+            })?;
+            decode(data.value())?.next_node_id
+        };
+        let mut window = LogWindow::default();
+        for entry in log_table.range(seq.saturating_add(1)..).map_err(read)? {
+            let (_, data) = entry.map_err(read)?;
+            window.add(&decode(data.value())?);
+        }
+        let mut base = BaseValues::new(&window, base_next);
+        if !base.is_resolved() {
+            for entry in log_table.range(..=seq).map_err(read)?.rev() {
+                let (_, data) = entry.map_err(read)?;
+                if base.rewind(&decode(data.value())?) {
+                    break;
+                }
+            }
+        }
+
+        let nodes_table = self.txn.open_table(NODES).map_err(open)?;
+        let edges_table = self.txn.open_table(EDGES).map_err(open)?;
+        let labeled_table = self.txn.open_table(LABELED_EDGES).map_err(open)?;
+        let props_table = self.txn.open_table(PROPERTIES).map_err(open)?;
+        let keys_table = self.txn.open_table(ENTITY_KEYS).map_err(open)?;
+        let pinned_node = |id: u64| -> Result<Option<u64>, KremisError> {
+            match nodes_table.get(id).map_err(read)? {
+                Some(data) => {
+                    let node: Node = postcard::from_bytes(data.value())
+                        .map_err(|e| KremisError::SerializationError(e.to_string()))?;
+                    Ok(Some(node.entity.0))
+                }
+                None => Ok(None),
+            }
+        };
+        let base_has_node = |id: u64| -> Result<bool, KremisError> {
+            if window.nodes.contains(&id) {
+                Ok(base.node(id).is_some())
+            } else {
+                Ok(pinned_node(id)?.is_some())
+            }
+        };
+
+        let mut changes = GraphDiff::default();
+        for &id in &window.nodes {
+            let (before, after) = (base.node(id), pinned_node(id)?);
+            if before != after {
+                if let Some(entity) = before {
+                    changes.nodes_removed.push(CanonicalNode { id, entity });
+                }
+                if let Some(entity) = after {
+                    changes.nodes_added.push(CanonicalNode { id, entity });
+                }
+            }
+        }
+
+        let edge_keys: BTreeSet<&EdgeKey> = window.edges.iter().chain(base.edge_keys()).collect();
+        for key in edge_keys {
+            let (from, to, relation) = key;
+            let before = match base.edge(key) {
+                Some(weight) if base_has_node(*from)? && base_has_node(*to)? => Some(weight),
+                _ => None,
+            };
+            let after = if pinned_node(*from)?.is_some() && pinned_node(*to)?.is_some() {
+                match relation {
+                    None => edges_table.get((*from, *to)).map_err(read)?,
+                    Some(relation) => labeled_table
+                        .get((*from, *to, relation.as_str()))
+                        .map_err(read)?,
+                }
+                .map(|v| v.value())
+            } else {
+                None
+            };
+            let edge = |weight| CanonicalEdge {
+                from: *from,
+                to: *to,
+                relation: relation.clone(),
+                weight,
+            };
+            match (before, after) {
+                (Some(old), Some(new)) if old != new => {
+                    changes.edges_reweighted.push(EdgeReweight {
+                        from: *from,
+                        to: *to,
+                        relation: relation.clone(),
+                        old_weight: EdgeWeight::new(old),
+                        new_weight: EdgeWeight::new(new),
+                    });
+                }
+                (Some(old), None) => changes.edges_removed.push(edge(old)),
+                (None, Some(new)) => changes.edges_added.push(edge(new)),
+                _ => {}
+            }
+        }
+
+        let property_keys: BTreeSet<&CanonicalProperty> = window
+            .properties
+            .iter()
+            .chain(base.property_keys())
+            .collect();
+        for property in property_keys {
+            let before = base.has_property(property) && base_has_node(property.node_id)?;
+            let after = pinned_node(property.node_id)?.is_some() && {
+                let attribute = Attribute::new(&property.attribute);
+                match props_table
+                    .get((property.node_id, stable_attr_hash(&property.attribute)))
+                    .map_err(read)?
+                {
+                    Some(data) => decode_property_bucket(data.value(), &attribute)?
+                        .iter()
+                        .any(|v| v.as_str() == property.value),
+                    None => false,
+                }
+            };
+            if before && !after {
+                changes.properties_removed.push(property.clone());
+            } else if after && !before {
+                changes.properties_added.push(property.clone());
+            }
+        }
+
+        let mut entity_keys_added = Vec::new();
+        for key in &window.entity_keys {
+            if let Some(entity) = keys_table.get(key.as_str()).map_err(read)? {
+                entity_keys_added.push(CanonicalEntityKey {
+                    key: key.clone(),
+                    entity: entity.value(),
+                });
+            }
+        }
+
+        let last_node = nodes_table.last().map_err(read)?.map(|(id, _)| id.value());
+        Ok(GraphDelta {
+            base_hash,
+            target_hash: stream::stream_state_hash(self)?,
+            changes,
+            next_node_id: last_node.map_or(self.next_node_id, |id| {
+                self.next_node_id.max(id.saturating_add(1))
+            }),
+            entity_keys_added,
+            entity_keys_removed: Vec::new(),
+        })
+    }
 }
 
 impl std::fmt::Debug for RedbGraph {
@@ -91,6 +578,7 @@ impl std::fmt::Debug for RedbGraph {
         f.debug_struct("RedbGraph")
             .field("entity_cache_size", &self.entity_cache.len())
             .field("next_node_id", &self.next_node_id)
+            .field("checkpoints", &self.checkpoints)
             .finish_non_exhaustive()
     }
 }
@@ -112,6 +600,12 @@ impl RedbGraph {
             let _ = write_txn
                 .open_table(EDGES)
                 .map_err(|e| KremisError::IoError(e.to_string()))?;
+            let _ = write_txn
+                .open_table(LABELED_EDGES)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            let _ = write_txn
+                .open_table(REVERSE_EDGES)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
             let _ = write_txn
                 .open_table(ENTITY_INDEX)
                 .map_err(|e| KremisError::IoError(e.to_string()))?;
@@ -124,6 +618,35 @@ impl RedbGraph {
             let _ = write_txn
                 .open_table(PROPERTY_COUNTS)
                 .map_err(|e| KremisError::IoError(e.to_string()))?;
+            let _ = write_txn
+                .open_table(PROPERTY_INDEX)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            let _ = write_txn
+                .open_table(TOKEN_INDEX)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            let _ = write_txn
+                .open_table(ENTITY_KEYS)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            let _ = write_txn
+                .open_table(ENTITY_KEY_NAMES)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            let _ = write_txn
+                .open_table(MUTATION_LOG)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            let _ = write_txn
+                .open_table(SNAPSHOT_CHECKPOINTS)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            let _ = write_txn
+                .open_table(CHECKPOINT_HASHES)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            let _ = write_txn
+                .open_table(JOURNAL)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            backfill_reverse_index(&write_txn)?;
+            backfill_property_index(&write_txn)?;
+            backfill_token_index(&write_txn)?;
+            backfill_mutation_log(&write_txn)?;
+            start_journal(&write_txn)?;
             write_txn
                 .commit()
                 .map_err(|e| KremisError::IoError(e.to_string()))?;
@@ -145,29 +668,29 @@ impl RedbGraph {
                 .unwrap_or(0)
         };
 
-        // Load entity cache
-        let entity_cache = {
-            let table = read_txn
-                .open_table(ENTITY_INDEX)
-                .map_err(|e| KremisError::IoError(e.to_string()))?;
-            let mut cache = BTreeMap::new();
-            for entry in table
-                .iter()
-                .map_err(|e| KremisError::IoError(e.to_string()))?
-            {
-                let (key, value) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
-                cache.insert(EntityId(key.value()), NodeId(value.value()));
-            }
-            cache
-        };
+        let entity_cache = load_entity_cache(&read_txn)?;
 
         Ok(Self {
             db,
             entity_cache,
             next_node_id,
+            checkpoints: CheckpointPolicy::default(),
         })
     }
 
+    /// When this handle takes snapshot checkpoints and how many it keeps.
+    pub fn checkpoint_policy(&self) -> CheckpointPolicy {
+        self.checkpoints
+    }
+
+    /// Change when this handle takes snapshot checkpoints.
+    ///
+    /// Only later writes follow the new policy: existing checkpoints stay
+    /// until the next checkpoint thins them to `policy.max_kept`.
+    pub fn set_checkpoint_policy(&mut self, policy: CheckpointPolicy) {
+        self.checkpoints = policy;
+    }
+
     /// Compact the database (optional optimization).
     pub fn compact(&mut self) -> Result<(), KremisError> {
         self.db
@@ -190,8 +713,20 @@ impl RedbGraph {
     /// - The sequence exceeds `MAX_SEQUENCE_LENGTH`
     /// - Any signal is invalid (all signals are validated before the transaction opens)
     pub fn ingest_batch(&mut self, signals: &[Signal]) -> Result<Vec<NodeId>, KremisError> {
-        use crate::ingestor::Ingestor;
-        use crate::primitives::{ASSOCIATION_WINDOW, MAX_PROPERTIES_PER_NODE, MAX_SEQUENCE_LENGTH};
+        self.ingest_batch_with(signals, &LinkMode::default())
+    }
+
+    /// Ingest a sequence of signals in a single transaction, creating the
+    /// edges `links` asks for.
+    ///
+    /// Produces the same graph as [`Ingestor::ingest_sequence_with`], and is
+    /// rejected before the transaction opens for the same reasons.
+    pub fn ingest_batch_with(
+        &mut self,
+        signals: &[Signal],
+        links: &LinkMode,
+    ) -> Result<Vec<NodeId>, KremisError> {
+        use crate::primitives::{MAX_PROPERTIES_PER_NODE, MAX_SEQUENCE_LENGTH};
 
         if signals.is_empty() {
             return Ok(Vec::new());
@@ -204,12 +739,19 @@ impl RedbGraph {
         // If any signal is invalid, the entire batch is rejected atomically.
         for signal in signals {
             Ingestor::validate(signal)?;
+            Ingestor::validate_entity(self, signal.entity)?;
         }
+        links.validate()?;
+        let pairs = links.pairs(signals);
+        Ingestor::check_link_endpoints(&pairs, signals, |entity| {
+            self.entity_cache.contains_key(&entity)
+        })?;
 
         // Track entities newly inserted in this batch (not yet in entity_cache).
         let mut batch_entity_map: BTreeMap<EntityId, NodeId> = BTreeMap::new();
         let mut current_next_id = self.next_node_id;
         let mut node_ids = Vec::with_capacity(signals.len());
+        let mut mutations = Vec::new();
 
         let write_txn = self
             .db
@@ -223,15 +765,18 @@ impl RedbGraph {
             let mut entity_table = write_txn
                 .open_table(ENTITY_INDEX)
                 .map_err(|e| KremisError::IoError(e.to_string()))?;
-            let mut edges_table = write_txn
-                .open_table(EDGES)
-                .map_err(|e| KremisError::IoError(e.to_string()))?;
             let mut props_table = write_txn
                 .open_table(PROPERTIES)
                 .map_err(|e| KremisError::IoError(e.to_string()))?;
             let mut counts_table = write_txn
                 .open_table(PROPERTY_COUNTS)
                 .map_err(|e| KremisError::IoError(e.to_string()))?;
+            let mut index_table = write_txn
+                .open_table(PROPERTY_INDEX)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            let mut token_table = write_txn
+                .open_table(TOKEN_INDEX)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
             let mut meta_table = write_txn
                 .open_table(METADATA)
                 .map_err(|e| KremisError::IoError(e.to_string()))?;
@@ -258,6 +803,10 @@ impl RedbGraph {
                         .map_err(|e| KremisError::IoError(e.to_string()))?;
 
                     batch_entity_map.insert(signal.entity, new_node_id);
+                    mutations.push(Mutation::InsertNode {
+                        node: new_node_id,
+                        entity: signal.entity,
+                    });
                     new_node_id
                 };
 
@@ -267,22 +816,7 @@ impl RedbGraph {
                 let mut values: Vec<Value> = props_table
                     .get((node_id.0, attr_hash))
                     .map_err(|e| KremisError::IoError(e.to_string()))?
-                    .map(|data| {
-                        postcard::from_bytes::<(Attribute, Vec<Value>)>(data.value())
-                            .map_err(|e| KremisError::DeserializationError(e.to_string()))
-                            .and_then(|(stored_attr, values)| {
-                                if stored_attr != signal.attribute {
-                                    Err(KremisError::StorageCorruption(format!(
-                                        "attribute hash collision: bucket contains \"{}\", \
-                                         expected \"{}\"",
-                                        stored_attr.as_str(),
-                                        signal.attribute.as_str()
-                                    )))
-                                } else {
-                                    Ok(values)
-                                }
-                            })
-                    })
+                    .map(|data| decode_property_bucket(data.value(), &signal.attribute))
                     .transpose()?
                     .unwrap_or_default();
 
@@ -306,6 +840,13 @@ impl RedbGraph {
                     }
 
                     values.push(signal.value.clone());
+                    index_table
+                        .insert(
+                            (signal.attribute.as_str(), signal.value.as_str(), node_id.0),
+                            (),
+                        )
+                        .map_err(|e| KremisError::IoError(e.to_string()))?;
+                    index_tokens(&mut token_table, node_id.0, &signal.value)?;
 
                     let prop_bytes = postcard::to_allocvec(&(signal.attribute.clone(), values))
                         .map_err(|e| KremisError::SerializationError(e.to_string()))?;
@@ -315,49 +856,55 @@ impl RedbGraph {
                     counts_table
                         .insert(node_id.0, (count as u64) + 1)
                         .map_err(|e| KremisError::IoError(e.to_string()))?;
+                    mutations.push(Mutation::StoreProperty {
+                        node: node_id,
+                        attribute: signal.attribute.clone(),
+                        value: signal.value.clone(),
+                    });
                 }
 
                 node_ids.push(node_id);
             }
 
-            // Pass 2: create edges between adjacent signals (ASSOCIATION_WINDOW = 1).
-            for window in signals.windows(ASSOCIATION_WINDOW + 1) {
-                let current_signal = &window[window.len() - 1];
-                let current_node = self
-                    .entity_cache
-                    .get(&current_signal.entity)
-                    .copied()
-                    .or_else(|| batch_entity_map.get(&current_signal.entity).copied())
-                    .ok_or(KremisError::InvalidSignal)?;
-
-                for prev_signal in window.iter().take(window.len() - 1) {
-                    let prev_node = self
-                        .entity_cache
-                        .get(&prev_signal.entity)
-                        .copied()
-                        .or_else(|| batch_entity_map.get(&prev_signal.entity).copied())
-                        .ok_or(KremisError::InvalidSignal)?;
-
-                    let current_weight = edges_table
-                        .get((prev_node.0, current_node.0))
-                        .map_err(|e| KremisError::IoError(e.to_string()))?
-                        .map(|v| v.value())
-                        .unwrap_or(0);
-                    edges_table
-                        .insert(
-                            (prev_node.0, current_node.0),
-                            current_weight.saturating_add(1),
-                        )
-                        .map_err(|e| KremisError::IoError(e.to_string()))?;
-                }
-            }
-
             // Update metadata.
             meta_table
                 .insert("next_node_id", current_next_id)
                 .map_err(|e| KremisError::IoError(e.to_string()))?;
         }
 
+        // Pass 2: create the edges the link mode asks for.
+        for (from, to, relation) in &pairs {
+            let node_of = |entity: &EntityId| {
+                self.entity_cache
+                    .get(entity)
+                    .copied()
+                    .or_else(|| batch_entity_map.get(entity).copied())
+                    .ok_or(KremisError::InvalidSignal)
+            };
+            let (from_node, to_node) = (node_of(from)?, node_of(to)?);
+
+            let current_weight =
+                read_edge_weight(&write_txn, from_node, to_node, relation.as_ref())?.unwrap_or(0);
+            let weight = current_weight.saturating_add(1);
+            write_edge_weight(&write_txn, from_node, to_node, relation.as_ref(), weight)?;
+            mutations.push(Mutation::SetEdge {
+                from: from_node,
+                to: to_node,
+                relation: relation.clone(),
+                weight: EdgeWeight::new(weight),
+            });
+        }
+        log_mutations(&write_txn, &self.checkpoints, current_next_id, mutations)?;
+        let entry = if *links == LinkMode::default() {
+            JournalEntry::Ingest(signals.to_vec())
+        } else {
+            JournalEntry::IngestLinked {
+                signals: signals.to_vec(),
+                links: links.clone(),
+            }
+        };
+        append_journal(&write_txn, &entry)?;
+
         write_txn
             .commit()
             .map_err(|e| KremisError::IoError(e.to_string()))?;
@@ -371,53 +918,351 @@ impl RedbGraph {
         Ok(node_ids)
     }
 
-    /// Get all edges in deterministic order.
-    pub fn edges(&self) -> Result<Vec<(NodeId, NodeId, EdgeWeight)>, KremisError> {
-        let read_txn = self
+    /// Merge `incoming` into the database in a single transaction.
+    ///
+    /// See [`merge::plan`] for how nodes are reconciled and weights combined.
+    /// The merge is journaled with its payload, so replay repeats it.
+    pub fn merge_canonical(
+        &mut self,
+        incoming: &CanonicalGraph,
+        policy: MergePolicy,
+    ) -> Result<MergeDiagnostics, KremisError> {
+        let plan = merge::plan(self, self.next_node_id, incoming, policy)?;
+        let MutationRecord {
+            next_node_id,
+            mutations,
+        } = plan.record;
+        let mut added: BTreeMap<EntityId, NodeId> = BTreeMap::new();
+
+        let write_txn = self
             .db
-            .begin_read()
+            .begin_write()
             .map_err(|e| KremisError::IoError(e.to_string()))?;
-        let edges_table = read_txn
-            .open_table(EDGES)
+        for mutation in &mutations {
+            match mutation {
+                Mutation::InsertNode { node, entity } => {
+                    let node_bytes = postcard::to_allocvec(&Node::new(*node, *entity))
+                        .map_err(|e| KremisError::SerializationError(e.to_string()))?;
+                    write_txn
+                        .open_table(NODES)
+                        .map_err(|e| KremisError::IoError(e.to_string()))?
+                        .insert(node.0, node_bytes.as_slice())
+                        .map_err(|e| KremisError::IoError(e.to_string()))?;
+                    write_txn
+                        .open_table(ENTITY_INDEX)
+                        .map_err(|e| KremisError::IoError(e.to_string()))?
+                        .insert(entity.0, node.0)
+                        .map_err(|e| KremisError::IoError(e.to_string()))?;
+                    added.insert(*entity, *node);
+                }
+                Mutation::SetEdge {
+                    from,
+                    to,
+                    relation,
+                    weight,
+                } => write_edge_weight(&write_txn, *from, *to, relation.as_ref(), weight.value())?,
+                Mutation::StoreProperty {
+                    node,
+                    attribute,
+                    value,
+                } => {
+                    write_property(&write_txn, *node, attribute, value)?;
+                }
+                Mutation::BindEntityKey { key, entity } => {
+                    write_txn
+                        .open_table(ENTITY_KEYS)
+                        .map_err(|e| KremisError::IoError(e.to_string()))?
+                        .insert(key.as_str(), entity.0)
+                        .map_err(|e| KremisError::IoError(e.to_string()))?;
+                    write_txn
+                        .open_table(ENTITY_KEY_NAMES)
+                        .map_err(|e| KremisError::IoError(e.to_string()))?
+                        .insert(entity.0, key.as_str())
+                        .map_err(|e| KremisError::IoError(e.to_string()))?;
+                }
+                Mutation::RemoveProperty { .. } | Mutation::RemoveNode { .. } => {
+                    return Err(KremisError::StorageCorruption(
+                        "merge planned a removal".to_string(),
+                    ));
+                }
+            }
+        }
+        write_txn
+            .open_table(METADATA)
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+            .insert("next_node_id", next_node_id)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        log_mutations(&write_txn, &self.checkpoints, next_node_id, mutations)?;
+        append_journal(
+            &write_txn,
+            &JournalEntry::Merge {
+                graph: incoming.clone(),
+                policy,
+            },
+        )?;
+        write_txn
+            .commit()
             .map_err(|e| KremisError::IoError(e.to_string()))?;
 
-        let mut edges = Vec::new();
-        for entry in edges_table
-            .iter()
-            .map_err(|e| KremisError::IoError(e.to_string()))?
-        {
-            let (key, value) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
-            let (from_id, to_id) = key.value();
-            edges.push((
-                NodeId(from_id),
-                NodeId(to_id),
-                EdgeWeight::new(value.value()),
-            ));
-        }
-        Ok(edges)
+        // Update in-memory state only after successful commit.
+        self.next_node_id = next_node_id;
+        self.entity_cache.extend(added);
+
+        Ok(plan.diagnostics)
     }
 
-    /// Get all nodes in deterministic order.
-    pub fn nodes(&self) -> Result<Vec<Node>, KremisError> {
-        let read_txn = self
+    /// Import a canonical export from `input` into this empty database.
+    ///
+    /// The export is streamed straight into the tables in one transaction, so
+    /// the file is never held in memory, and nodes are resolved through the
+    /// tables rather than an in-memory index. Edges and properties whose node
+    /// is not in the export are discarded and counted, as by
+    /// [`import_canonical`](crate::export::import_canonical). The import is
+    /// logged in records of at most `IMPORT_LOG_CHUNK` mutations and is not
+    /// journaled, so [`journal_is_complete`](Self::journal_is_complete)
+    /// turns `false`.
+    ///
+    /// No checkpoint is taken while the records are written. A single one is
+    /// taken for the last record, unless the
+    /// [`CheckpointPolicy`] takes none, which rebuilds the imported graph in
+    /// memory once; the entity cache is then reloaded from the committed
+    /// tables.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KremisError::SerializationError` if the database is not
+    /// empty or the export is malformed; nothing is written in either case.
+    pub fn import_canonical(
+        &mut self,
+        input: impl std::io::Read,
+    ) -> Result<LoadDiagnostics, KremisError> {
+        let write_txn = self
             .db
-            .begin_read()
+            .begin_write()
             .map_err(|e| KremisError::IoError(e.to_string()))?;
-        let nodes_table = read_txn
+        let is_empty = write_txn
             .open_table(NODES)
-            .map_err(|e| KremisError::IoError(e.to_string()))?;
-
-        let mut nodes = Vec::new();
-        for entry in nodes_table
-            .iter()
             .map_err(|e| KremisError::IoError(e.to_string()))?
-        {
-            let (_, value) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
-            let node: Node = postcard::from_bytes(value.value())
-                .map_err(|e| KremisError::SerializationError(e.to_string()))?;
-            nodes.push(node);
-        }
-        Ok(nodes)
+            .is_empty()
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+            && write_txn
+                .open_table(ENTITY_KEYS)
+                .map_err(|e| KremisError::IoError(e.to_string()))?
+                .is_empty()
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+        if !is_empty {
+            return Err(KremisError::SerializationError(
+                "Import needs an empty database; use merge to combine graphs".to_string(),
+            ));
+        }
+
+        let mut diag = LoadDiagnostics::default();
+        let mut next_node_id = 0u64;
+        let mut pending: Vec<Mutation> = Vec::new();
+        let (_, declared_next_id) = stream::read_canonical(input, &mut |item| {
+            // Flush before adding, so the last record is never empty and
+            // carries the final node id counter.
+            if pending.len() >= IMPORT_LOG_CHUNK {
+                append_mutations(&write_txn, next_node_id, std::mem::take(&mut pending))?;
+            }
+            match item {
+                CanonicalItem::Node(node) => {
+                    let (id, entity) = (NodeId(node.id), EntityId(node.entity));
+                    let node_bytes = postcard::to_allocvec(&Node::new(id, entity))
+                        .map_err(|e| KremisError::SerializationError(e.to_string()))?;
+                    write_txn
+                        .open_table(NODES)
+                        .map_err(|e| KremisError::IoError(e.to_string()))?
+                        .insert(id.0, node_bytes.as_slice())
+                        .map_err(|e| KremisError::IoError(e.to_string()))?;
+                    write_txn
+                        .open_table(ENTITY_INDEX)
+                        .map_err(|e| KremisError::IoError(e.to_string()))?
+                        .insert(entity.0, id.0)
+                        .map_err(|e| KremisError::IoError(e.to_string()))?;
+                    next_node_id = next_node_id.max(id.0.saturating_add(1));
+                    pending.push(Mutation::InsertNode { node: id, entity });
+                }
+                CanonicalItem::Edge(edge) => {
+                    let (from, to) = (NodeId(edge.from), NodeId(edge.to));
+                    if !(txn_has_node(&write_txn, from)? && txn_has_node(&write_txn, to)?) {
+                        diag.dangling_edges += 1;
+                        return Ok(());
+                    }
+                    let relation = edge.relation.as_deref().map(Attribute::new);
+                    write_edge_weight(&write_txn, from, to, relation.as_ref(), edge.weight)?;
+                    pending.push(Mutation::SetEdge {
+                        from,
+                        to,
+                        relation,
+                        weight: EdgeWeight::new(edge.weight),
+                    });
+                }
+                CanonicalItem::Property(property) => {
+                    let node = NodeId(property.node_id);
+                    if !txn_has_node(&write_txn, node)? {
+                        diag.dangling_properties += 1;
+                        return Ok(());
+                    }
+                    let attribute = Attribute::new(&property.attribute);
+                    let value = Value::new(&property.value);
+                    // Properties past the per-node cap are skipped, as on load.
+                    match write_property(&write_txn, node, &attribute, &value) {
+                        Ok(true) => pending.push(Mutation::StoreProperty {
+                            node,
+                            attribute,
+                            value,
+                        }),
+                        Ok(false) | Err(KremisError::PropertyLimitExceeded(..)) => {}
+                        Err(e) => return Err(e),
+                    }
+                }
+                CanonicalItem::EntityKey(binding) => {
+                    write_txn
+                        .open_table(ENTITY_KEYS)
+                        .map_err(|e| KremisError::IoError(e.to_string()))?
+                        .insert(binding.key.as_str(), binding.entity)
+                        .map_err(|e| KremisError::IoError(e.to_string()))?;
+                    write_txn
+                        .open_table(ENTITY_KEY_NAMES)
+                        .map_err(|e| KremisError::IoError(e.to_string()))?
+                        .insert(binding.entity, binding.key.as_str())
+                        .map_err(|e| KremisError::IoError(e.to_string()))?;
+                    pending.push(Mutation::BindEntityKey {
+                        key: binding.key,
+                        entity: EntityId(binding.entity),
+                    });
+                }
+            }
+            Ok(())
+        })?;
+
+        // The node id counter never falls back under an imported id.
+        let next_node_id = next_node_id.max(declared_next_id);
+        {
+            let mut meta_table = write_txn
+                .open_table(METADATA)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            meta_table
+                .insert("next_node_id", next_node_id)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            meta_table
+                .insert(JOURNAL_PARTIAL, 1)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+        }
+        if let Some(seq) = append_mutations(&write_txn, next_node_id, pending)?
+            && self.checkpoints.interval != 0
+        {
+            write_checkpoint(&write_txn, &self.checkpoints, seq)?;
+        }
+        write_txn
+            .commit()
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+
+        // Update in-memory state only after successful commit.
+        self.next_node_id = next_node_id;
+        let read_txn = self
+            .db
+            .begin_read()
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        self.entity_cache = load_entity_cache(&read_txn)?;
+
+        Ok(diag)
+    }
+
+    /// Get all edges in deterministic order.
+    ///
+    /// Relation labels are dropped; see [`edges_with_relations`](Self::edges_with_relations).
+    pub fn edges(&self) -> Result<Vec<(NodeId, NodeId, EdgeWeight)>, KremisError> {
+        Ok(self
+            .edges_with_relations()?
+            .into_iter()
+            .map(|(from, to, _relation, weight)| (from, to, weight))
+            .collect())
+    }
+
+    /// Get all edges with their relation labels, ordered by
+    /// `(from, to, relation)` with the unlabeled edge first.
+    #[allow(clippy::type_complexity)]
+    pub fn edges_with_relations(&self) -> Result<Vec<LabeledEdge>, KremisError> {
+        let read_txn = self
+            .db
+            .begin_read()
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        let edges_table = read_txn
+            .open_table(EDGES)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        let labeled_table = read_txn
+            .open_table(LABELED_EDGES)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+
+        let mut edges = Vec::new();
+        for entry in edges_table
+            .iter()
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+        {
+            let (key, value) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+            let (from_id, to_id) = key.value();
+            edges.push((
+                NodeId(from_id),
+                NodeId(to_id),
+                None,
+                EdgeWeight::new(value.value()),
+            ));
+        }
+        for entry in labeled_table
+            .iter()
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+        {
+            let (key, value) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+            let (from_id, to_id, relation) = key.value();
+            edges.push((
+                NodeId(from_id),
+                NodeId(to_id),
+                Some(Attribute::new(relation)),
+                EdgeWeight::new(value.value()),
+            ));
+        }
+        edges.sort_by(|a, b| (a.0, a.1, &a.2).cmp(&(b.0, b.1, &b.2)));
+        Ok(edges)
+    }
+
+    /// Pin the current state for a streaming canonical export.
+    ///
+    /// The returned [`RedbExport`] holds one read transaction, so every pass
+    /// over it sees the same state while later writes commit alongside it.
+    pub fn canonical_export(&self) -> Result<RedbExport, KremisError> {
+        let txn = self
+            .db
+            .begin_read()
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        Ok(RedbExport {
+            txn,
+            next_node_id: self.next_node_id,
+        })
+    }
+
+    /// Get all nodes in deterministic order.
+    pub fn nodes(&self) -> Result<Vec<Node>, KremisError> {
+        let read_txn = self
+            .db
+            .begin_read()
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        let nodes_table = read_txn
+            .open_table(NODES)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+
+        let mut nodes = Vec::new();
+        for entry in nodes_table
+            .iter()
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+        {
+            let (_, value) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+            let node: Node = postcard::from_bytes(value.value())
+                .map_err(|e| KremisError::SerializationError(e.to_string()))?;
+            nodes.push(node);
+        }
+        Ok(nodes)
     }
 
     /// Get every `(entity, node)` pair, ordered by [`EntityId`].
@@ -430,6 +1275,167 @@ impl RedbGraph {
             .map(|(entity, node)| (*entity, *node))
     }
 
+    /// Get the id the next inserted node will receive.
+    pub fn next_node_id(&self) -> u64 {
+        self.next_node_id
+    }
+
+    /// Get the sequence number of the last logged mutation (0 before any).
+    pub fn mutation_seq(&self) -> Result<u64, KremisError> {
+        let read_txn = self
+            .db
+            .begin_read()
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        let meta_table = read_txn
+            .open_table(METADATA)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        Ok(meta_table
+            .get(MUTATION_SEQ)
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+            .map(|v| v.value())
+            .unwrap_or(0))
+    }
+
+    /// Rebuild the graph as it was right after mutation `seq` committed.
+    ///
+    /// Sequence 0 is the empty graph. The log is replayed from the latest
+    /// snapshot checkpoint at or before `seq`, so at most the
+    /// [`CheckpointPolicy`] interval of transactions is replayed past it.
+    /// For a database upgraded from before the log existed, sequence 1 is the
+    /// state it had when first reopened.
+    ///
+    /// Returns `KremisError::InvalidSignal` if `seq` is beyond
+    /// [`mutation_seq`](Self::mutation_seq).
+    pub fn snapshot_at(&self, seq: u64) -> Result<Graph, KremisError> {
+        self.snapshot_at_within(seq, &mut VisitBudget::unlimited())
+            .map(Option::unwrap_or_default)
+    }
+
+    /// [`snapshot_at`](Self::snapshot_at) under a visit budget: one visit per
+    /// replayed mutation. Returns `Ok(None)` once `budget` runs out.
+    pub fn snapshot_at_within(
+        &self,
+        seq: u64,
+        budget: &mut VisitBudget,
+    ) -> Result<Option<Graph>, KremisError> {
+        let read_txn = self
+            .db
+            .begin_read()
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        let current = read_txn
+            .open_table(METADATA)
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+            .get(MUTATION_SEQ)
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+            .map(|v| v.value())
+            .unwrap_or(0);
+        if seq > current {
+            return Err(KremisError::InvalidSignal);
+        }
+
+        let log_table = read_txn
+            .open_table(MUTATION_LOG)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        let checkpoints = read_txn
+            .open_table(SNAPSHOT_CHECKPOINTS)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        replay_to(&checkpoints, &log_table, seq, budget)
+    }
+
+    /// Find the snapshot checkpoint whose state hashes to `hash`.
+    ///
+    /// Returns its sequence number, or `None` if no kept checkpoint has that
+    /// state hash. [`snapshot_at`](Self::snapshot_at) rebuilds it without
+    /// replaying the log.
+    ///
+    /// # Requires
+    ///
+    /// Only available with the `crypto-hash` feature.
+    #[cfg(feature = "crypto-hash")]
+    pub fn checkpoint_with_hash(&self, hash: &[u8; 32]) -> Result<Option<u64>, KremisError> {
+        let read_txn = self
+            .db
+            .begin_read()
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        let hashes = read_txn
+            .open_table(CHECKPOINT_HASHES)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        for entry in hashes
+            .iter()
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+        {
+            let (seq, stored) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+            if stored.value() == hash.as_slice() {
+                return Ok(Some(seq.value()));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Get every journal entry, oldest first.
+    pub fn journal(&self) -> Result<Vec<JournalEntry>, KremisError> {
+        let read_txn = self
+            .db
+            .begin_read()
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        let journal_table = read_txn
+            .open_table(JOURNAL)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        let mut entries = Vec::new();
+        for entry in journal_table
+            .iter()
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+        {
+            let (_, data) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+            entries.push(
+                postcard::from_bytes(data.value())
+                    .map_err(|e| KremisError::DeserializationError(e.to_string()))?,
+            );
+        }
+        Ok(entries)
+    }
+
+    /// Check whether the journal covers the whole history of the database.
+    ///
+    /// `false` for a database that already held data when the journal was
+    /// introduced, or that was filled by
+    /// [`import_canonical`](Self::import_canonical); replaying its journal
+    /// would miss that data.
+    pub fn journal_is_complete(&self) -> Result<bool, KremisError> {
+        let read_txn = self
+            .db
+            .begin_read()
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        let meta_table = read_txn
+            .open_table(METADATA)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        Ok(meta_table
+            .get(JOURNAL_PARTIAL)
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+            .is_none())
+    }
+
+    /// Get every `(key, entity)` binding of the key registry, ordered by key.
+    pub fn entity_keys(&self) -> Result<Vec<(String, EntityId)>, KremisError> {
+        let read_txn = self
+            .db
+            .begin_read()
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        let keys_table = read_txn
+            .open_table(ENTITY_KEYS)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+
+        let mut keys = Vec::new();
+        for entry in keys_table
+            .iter()
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+        {
+            let (key, value) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+            keys.push((key.value().to_string(), EntityId(value.value())));
+        }
+        Ok(keys)
+    }
+
     /// Get stable edge count (edges with weight >= threshold).
     pub fn stable_edge_count(&self, threshold: i64) -> Result<usize, KremisError> {
         let read_txn = self
@@ -440,6 +1446,10 @@ impl RedbGraph {
             .open_table(EDGES)
             .map_err(|e| KremisError::IoError(e.to_string()))?;
 
+        let labeled_table = read_txn
+            .open_table(LABELED_EDGES)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+
         let mut count = 0;
         for entry in edges_table
             .iter()
@@ -450,10 +1460,760 @@ impl RedbGraph {
                 count += 1;
             }
         }
+        for entry in labeled_table
+            .iter()
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+        {
+            let (_, value) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+            if value.value() >= threshold {
+                count += 1;
+            }
+        }
         Ok(count)
     }
 }
 
+/// Read the weight of the edge `(from, to, relation)` inside a write
+/// transaction, picking `EDGES` or `LABELED_EDGES` by label.
+fn read_edge_weight(
+    txn: &WriteTransaction,
+    from: NodeId,
+    to: NodeId,
+    relation: Option<&Attribute>,
+) -> Result<Option<i64>, KremisError> {
+    match relation {
+        None => {
+            let table = txn
+                .open_table(EDGES)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            let weight = table
+                .get((from.0, to.0))
+                .map_err(|e| KremisError::IoError(e.to_string()))?
+                .map(|v| v.value());
+            Ok(weight)
+        }
+        Some(relation) => {
+            let table = txn
+                .open_table(LABELED_EDGES)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            let weight = table
+                .get((from.0, to.0, relation.as_str()))
+                .map_err(|e| KremisError::IoError(e.to_string()))?
+                .map(|v| v.value());
+            Ok(weight)
+        }
+    }
+}
+
+/// Write the weight of the edge `(from, to, relation)` inside a write
+/// transaction, picking `EDGES` or `LABELED_EDGES` by label.
+fn write_edge_weight(
+    txn: &WriteTransaction,
+    from: NodeId,
+    to: NodeId,
+    relation: Option<&Attribute>,
+    weight: i64,
+) -> Result<(), KremisError> {
+    match relation {
+        None => {
+            let mut table = txn
+                .open_table(EDGES)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            table
+                .insert((from.0, to.0), weight)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+        }
+        Some(relation) => {
+            let mut table = txn
+                .open_table(LABELED_EDGES)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            table
+                .insert((from.0, to.0, relation.as_str()), weight)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+        }
+    }
+    let mut reverse_table = txn
+        .open_table(REVERSE_EDGES)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    reverse_table
+        .insert(
+            (
+                to.0,
+                from.0,
+                relation.map_or("", |relation| relation.as_str()),
+            ),
+            (),
+        )
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    Ok(())
+}
+
+/// Add the pair `(attribute, value)` to `node` inside a write transaction,
+/// keeping the property counter and both indexes in step.
+///
+/// Returns `Ok(false)` if the pair was already stored, and
+/// `KremisError::PropertyLimitExceeded` if the node is at its cap.
+fn write_property(
+    txn: &WriteTransaction,
+    node: NodeId,
+    attribute: &Attribute,
+    value: &Value,
+) -> Result<bool, KremisError> {
+    use crate::primitives::MAX_PROPERTIES_PER_NODE;
+
+    // Stable attribute hash: FNV-1a 64-bit (see stable_attr_hash fn for rationale)
+    let attr_hash = stable_attr_hash(attribute.as_str());
+
+    let mut props_table = txn
+        .open_table(PROPERTIES)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    let mut counts_table = txn
+        .open_table(PROPERTY_COUNTS)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+
+    // Read existing values for this (node, attribute) pair
+    let existing: Vec<Value> = props_table
+        .get((node.0, attr_hash))
+        .map_err(|e| KremisError::IoError(e.to_string()))?
+        .map(|data| decode_property_bucket(data.value(), attribute))
+        .transpose()?
+        .unwrap_or_default();
+
+    // Set semantics: an already-stored pair is idempotent and never
+    // grows the node, so it is allowed even when the node is at its cap.
+    if existing.contains(value) {
+        return Ok(false);
+    }
+
+    // Bound the number of distinct properties per node (DoS guard),
+    // using the O(1) maintained counter rather than scanning buckets.
+    let count = counts_table
+        .get(node.0)
+        .map_err(|e| KremisError::IoError(e.to_string()))?
+        .map(|v| v.value())
+        .unwrap_or(0) as usize;
+    if count >= MAX_PROPERTIES_PER_NODE {
+        // Returning drops the tables + txn without commit (abort).
+        return Err(KremisError::PropertyLimitExceeded(
+            node,
+            MAX_PROPERTIES_PER_NODE,
+        ));
+    }
+
+    let mut index_table = txn
+        .open_table(PROPERTY_INDEX)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    index_table
+        .insert((attribute.as_str(), value.as_str(), node.0), ())
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    let mut token_table = txn
+        .open_table(TOKEN_INDEX)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    index_tokens(&mut token_table, node.0, value)?;
+
+    let mut values = existing;
+    values.push(value.clone());
+
+    // Serialize and store the new property, then bump the counter.
+    let prop_bytes = postcard::to_allocvec(&(attribute, values))
+        .map_err(|e| KremisError::SerializationError(e.to_string()))?;
+    props_table
+        .insert((node.0, attr_hash), prop_bytes.as_slice())
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    counts_table
+        .insert(node.0, (count as u64) + 1)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    Ok(true)
+}
+
+/// Append `mutations` to the log under the next sequence number, inside `txn`,
+/// and take a checkpoint when `policy` says one is due.
+///
+/// Transactions that changed nothing are not logged, so the sequence number
+/// only moves when the state does. Callers must have dropped their own
+/// `METADATA` handle first.
+fn log_mutations(
+    txn: &WriteTransaction,
+    policy: &CheckpointPolicy,
+    next_node_id: u64,
+    mutations: Vec<Mutation>,
+) -> Result<(), KremisError> {
+    if let Some(seq) = append_mutations(txn, next_node_id, mutations)?
+        && policy.is_due(seq)
+    {
+        write_checkpoint(txn, policy, seq)?;
+    }
+    Ok(())
+}
+
+/// Append one record of `mutations` to the mutation log, inside `txn`,
+/// without taking a checkpoint.
+///
+/// Returns the record's sequence number, or `None` if `mutations` is empty
+/// and nothing was logged.
+fn append_mutations(
+    txn: &WriteTransaction,
+    next_node_id: u64,
+    mutations: Vec<Mutation>,
+) -> Result<Option<u64>, KremisError> {
+    if mutations.is_empty() {
+        return Ok(None);
+    }
+    let mut meta_table = txn
+        .open_table(METADATA)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    let seq = meta_table
+        .get(MUTATION_SEQ)
+        .map_err(|e| KremisError::IoError(e.to_string()))?
+        .map(|v| v.value())
+        .unwrap_or(0)
+        .saturating_add(1);
+    let record = postcard::to_allocvec(&MutationRecord {
+        next_node_id,
+        mutations,
+    })
+    .map_err(|e| KremisError::SerializationError(e.to_string()))?;
+    let mut log_table = txn
+        .open_table(MUTATION_LOG)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    log_table
+        .insert(seq, record.as_slice())
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    meta_table
+        .insert(MUTATION_SEQ, seq)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    Ok(Some(seq))
+}
+
+/// Read the whole `ENTITY_INDEX` table into an entity -> node map.
+fn load_entity_cache(
+    read_txn: &ReadTransaction,
+) -> Result<BTreeMap<EntityId, NodeId>, KremisError> {
+    let table = read_txn
+        .open_table(ENTITY_INDEX)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    let mut cache = BTreeMap::new();
+    for entry in table
+        .iter()
+        .map_err(|e| KremisError::IoError(e.to_string()))?
+    {
+        let (key, value) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+        cache.insert(EntityId(key.value()), NodeId(value.value()));
+    }
+    Ok(cache)
+}
+
+/// Rebuild the state right after mutation `seq` from the latest checkpoint
+/// at or before it and the log records that follow the checkpoint.
+///
+/// Spends one visit per replayed mutation and returns `Ok(None)` once
+/// `budget` runs out.
+fn replay_to(
+    checkpoints: &impl ReadableTable<u64, &'static [u8]>,
+    log: &impl ReadableTable<u64, &'static [u8]>,
+    seq: u64,
+    budget: &mut VisitBudget,
+) -> Result<Option<Graph>, KremisError> {
+    let latest = checkpoints
+        .range(..=seq)
+        .map_err(|e| KremisError::IoError(e.to_string()))?
+        .next_back()
+        .transpose()
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    let (start, mut graph) = match latest {
+        Some((at, data)) => {
+            let saved: SerializableGraph = postcard::from_bytes(data.value())
+                .map_err(|e| KremisError::DeserializationError(e.to_string()))?;
+            (at.value(), Graph::from(saved))
+        }
+        None => (0, Graph::new()),
+    };
+    if start == seq {
+        return Ok(Some(graph));
+    }
+    for entry in log
+        .range(start.saturating_add(1)..=seq)
+        .map_err(|e| KremisError::IoError(e.to_string()))?
+    {
+        let (_, data) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+        let record: MutationRecord = postcard::from_bytes(data.value())
+            .map_err(|e| KremisError::DeserializationError(e.to_string()))?;
+        if !budget.spend(record.mutations.len() as u64) {
+            return Ok(None);
+        }
+        record.apply(&mut graph)?;
+    }
+    Ok(Some(graph))
+}
+
+/// Save the state right after mutation `seq` as a snapshot checkpoint,
+/// inside `txn`, then drop checkpoints beyond `policy.max_kept`.
+///
+/// The state is rebuilt from the previous checkpoint and the log, the same
+/// way [`RedbGraph::snapshot_at`] rebuilds it, so reading from a checkpoint
+/// gives exactly the graph a full replay would.
+fn write_checkpoint(
+    txn: &WriteTransaction,
+    policy: &CheckpointPolicy,
+    seq: u64,
+) -> Result<(), KremisError> {
+    let log_table = txn
+        .open_table(MUTATION_LOG)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    let mut checkpoints = txn
+        .open_table(SNAPSHOT_CHECKPOINTS)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    let graph = replay_to(&checkpoints, &log_table, seq, &mut VisitBudget::unlimited())?
+        .unwrap_or_default();
+    let data = postcard::to_allocvec(&SerializableGraph::from(&graph))
+        .map_err(|e| KremisError::SerializationError(e.to_string()))?;
+    checkpoints
+        .insert(seq, data.as_slice())
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    let mut hashes = txn
+        .open_table(CHECKPOINT_HASHES)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    #[cfg(feature = "crypto-hash")]
+    hashes
+        .insert(seq, crate::certificate::state_hash(&graph)?.as_slice())
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+
+    let mut kept = Vec::new();
+    for entry in checkpoints
+        .iter()
+        .map_err(|e| KremisError::IoError(e.to_string()))?
+    {
+        let (at, _) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+        kept.push(at.value());
+    }
+    if let Some(thinned) = thinned_checkpoint(&kept, policy.max_kept) {
+        checkpoints
+            .remove(thinned)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        hashes
+            .remove(thinned)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// The checkpoint to drop from `kept` (ascending) once it holds more than
+/// `max_kept`: the one, other than the newest, whose removal
+/// leaves the smallest gap between its neighbours, counting the empty graph
+/// at sequence 0 as the neighbour of the first.
+fn thinned_checkpoint(kept: &[u64], max_kept: usize) -> Option<u64> {
+    if kept.len() <= max_kept {
+        return None;
+    }
+    (0..kept.len() - 1)
+        .min_by_key(|&i| kept[i + 1] - if i == 0 { 0 } else { kept[i - 1] })
+        .map(|i| kept[i])
+}
+
+/// Append `entry` to the input journal, inside `txn`.
+///
+/// Callers must have dropped their own `METADATA` handle first.
+fn append_journal(txn: &WriteTransaction, entry: &JournalEntry) -> Result<(), KremisError> {
+    let mut meta_table = txn
+        .open_table(METADATA)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    let seq = meta_table
+        .get(JOURNAL_SEQ)
+        .map_err(|e| KremisError::IoError(e.to_string()))?
+        .map(|v| v.value())
+        .unwrap_or(0)
+        .saturating_add(1);
+    let bytes =
+        postcard::to_allocvec(entry).map_err(|e| KremisError::SerializationError(e.to_string()))?;
+    let mut journal_table = txn
+        .open_table(JOURNAL)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    journal_table
+        .insert(seq, bytes.as_slice())
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    meta_table
+        .insert(JOURNAL_SEQ, seq)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    Ok(())
+}
+
+/// Check whether node `id` exists, inside `txn`.
+fn txn_has_node(txn: &WriteTransaction, id: NodeId) -> Result<bool, KremisError> {
+    Ok(txn
+        .open_table(NODES)
+        .map_err(|e| KremisError::IoError(e.to_string()))?
+        .get(id.0)
+        .map_err(|e| KremisError::IoError(e.to_string()))?
+        .is_some())
+}
+
+/// Start the input journal, unless METADATA says it already runs.
+///
+/// The inputs that built an existing graph are gone, so a database that
+/// already holds nodes or keys gets `JOURNAL_PARTIAL` and cannot be replayed.
+fn start_journal(txn: &WriteTransaction) -> Result<(), KremisError> {
+    let already_running = txn
+        .open_table(METADATA)
+        .map_err(|e| KremisError::IoError(e.to_string()))?
+        .get(JOURNAL_SEQ)
+        .map_err(|e| KremisError::IoError(e.to_string()))?
+        .is_some();
+    if already_running {
+        return Ok(());
+    }
+
+    let has_state = !txn
+        .open_table(NODES)
+        .map_err(|e| KremisError::IoError(e.to_string()))?
+        .is_empty()
+        .map_err(|e| KremisError::IoError(e.to_string()))?
+        || !txn
+            .open_table(ENTITY_KEYS)
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+            .is_empty()
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+
+    let mut meta_table = txn
+        .open_table(METADATA)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    meta_table
+        .insert(JOURNAL_SEQ, 0)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    if has_state {
+        meta_table
+            .insert(JOURNAL_PARTIAL, 1)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Log the new weight of the edge `(from, to, relation)`.
+fn log_edge(
+    txn: &WriteTransaction,
+    policy: &CheckpointPolicy,
+    next_node_id: u64,
+    from: NodeId,
+    to: NodeId,
+    relation: Option<&Attribute>,
+    weight: EdgeWeight,
+) -> Result<(), KremisError> {
+    log_mutations(
+        txn,
+        policy,
+        next_node_id,
+        vec![Mutation::SetEdge {
+            from,
+            to,
+            relation: relation.cloned(),
+            weight,
+        }],
+    )
+}
+
+/// Start the mutation log of a database written before it existed.
+///
+/// The whole current state becomes one baseline record, so earlier history
+/// is not recoverable but every later sequence number is. A database with
+/// nothing in it just starts at sequence 0.
+fn backfill_mutation_log(txn: &WriteTransaction) -> Result<(), KremisError> {
+    let next_node_id = {
+        let meta_table = txn
+            .open_table(METADATA)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        if meta_table
+            .get(MUTATION_SEQ)
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+            .is_some()
+        {
+            return Ok(());
+        }
+        meta_table
+            .get("next_node_id")
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+            .map(|v| v.value())
+            .unwrap_or(0)
+    };
+
+    let mut mutations = Vec::new();
+    {
+        let nodes_table = txn
+            .open_table(NODES)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        for entry in nodes_table
+            .iter()
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+        {
+            let (_, value) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+            let node: Node = postcard::from_bytes(value.value())
+                .map_err(|e| KremisError::SerializationError(e.to_string()))?;
+            mutations.push(Mutation::InsertNode {
+                node: node.id,
+                entity: node.entity,
+            });
+        }
+
+        let edges_table = txn
+            .open_table(EDGES)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        for entry in edges_table
+            .iter()
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+        {
+            let (key, value) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+            let (from, to) = key.value();
+            mutations.push(Mutation::SetEdge {
+                from: NodeId(from),
+                to: NodeId(to),
+                relation: None,
+                weight: EdgeWeight::new(value.value()),
+            });
+        }
+        let labeled_table = txn
+            .open_table(LABELED_EDGES)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        for entry in labeled_table
+            .iter()
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+        {
+            let (key, value) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+            let (from, to, relation) = key.value();
+            mutations.push(Mutation::SetEdge {
+                from: NodeId(from),
+                to: NodeId(to),
+                relation: Some(Attribute::new(relation)),
+                weight: EdgeWeight::new(value.value()),
+            });
+        }
+
+        let props_table = txn
+            .open_table(PROPERTIES)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        for entry in props_table
+            .iter()
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+        {
+            let (key, data) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+            let (attribute, values): (Attribute, Vec<Value>) =
+                postcard::from_bytes(data.value())
+                    .map_err(|e| KremisError::DeserializationError(e.to_string()))?;
+            for value in values {
+                mutations.push(Mutation::StoreProperty {
+                    node: NodeId(key.value().0),
+                    attribute: attribute.clone(),
+                    value,
+                });
+            }
+        }
+
+        let keys_table = txn
+            .open_table(ENTITY_KEYS)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        for entry in keys_table
+            .iter()
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+        {
+            let (key, value) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+            mutations.push(Mutation::BindEntityKey {
+                key: key.value().to_string(),
+                entity: EntityId(value.value()),
+            });
+        }
+    }
+
+    if mutations.is_empty() {
+        let mut meta_table = txn
+            .open_table(METADATA)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        meta_table
+            .insert(MUTATION_SEQ, 0)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        return Ok(());
+    }
+    log_mutations(txn, &CheckpointPolicy::default(), next_node_id, mutations)
+}
+
+/// Populate `REVERSE_EDGES` from both edge tables, unless METADATA says it
+/// has already been done.
+///
+/// Runs inside the `open` transaction so a database created before the
+/// reverse index existed is upgraded atomically on first open.
+fn backfill_reverse_index(txn: &WriteTransaction) -> Result<(), KremisError> {
+    let mut meta_table = txn
+        .open_table(METADATA)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    if meta_table
+        .get(REVERSE_INDEX_BUILT)
+        .map_err(|e| KremisError::IoError(e.to_string()))?
+        .is_some()
+    {
+        return Ok(());
+    }
+
+    let edges_table = txn
+        .open_table(EDGES)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    let labeled_table = txn
+        .open_table(LABELED_EDGES)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    let mut reverse_table = txn
+        .open_table(REVERSE_EDGES)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+
+    for entry in edges_table
+        .iter()
+        .map_err(|e| KremisError::IoError(e.to_string()))?
+    {
+        let (key, _) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+        let (from_id, to_id) = key.value();
+        reverse_table
+            .insert((to_id, from_id, ""), ())
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+    }
+    for entry in labeled_table
+        .iter()
+        .map_err(|e| KremisError::IoError(e.to_string()))?
+    {
+        let (key, _) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+        let (from_id, to_id, relation) = key.value();
+        reverse_table
+            .insert((to_id, from_id, relation), ())
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+    }
+
+    meta_table
+        .insert(REVERSE_INDEX_BUILT, 1)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    Ok(())
+}
+
+/// Populate `PROPERTY_INDEX` from `PROPERTIES`, unless METADATA says it has
+/// already been done. Runs inside the `open` transaction like
+/// [`backfill_reverse_index`].
+fn backfill_property_index(txn: &WriteTransaction) -> Result<(), KremisError> {
+    let mut meta_table = txn
+        .open_table(METADATA)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    if meta_table
+        .get(PROPERTY_INDEX_BUILT)
+        .map_err(|e| KremisError::IoError(e.to_string()))?
+        .is_some()
+    {
+        return Ok(());
+    }
+
+    let props_table = txn
+        .open_table(PROPERTIES)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    let mut index_table = txn
+        .open_table(PROPERTY_INDEX)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+
+    for entry in props_table
+        .iter()
+        .map_err(|e| KremisError::IoError(e.to_string()))?
+    {
+        let (key, data) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+        let (node_id, _) = key.value();
+        let (attribute, values): (Attribute, Vec<Value>) = postcard::from_bytes(data.value())
+            .map_err(|e| KremisError::DeserializationError(e.to_string()))?;
+        for value in &values {
+            index_table
+                .insert((attribute.as_str(), value.as_str(), node_id), ())
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+        }
+    }
+
+    meta_table
+        .insert(PROPERTY_INDEX_BUILT, 1)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    Ok(())
+}
+
+/// Add `value`'s tokens to the `TOKEN_INDEX` postings of `node_id`.
+fn index_tokens(
+    table: &mut Table<'_, (&'static str, u64), u64>,
+    node_id: u64,
+    value: &Value,
+) -> Result<(), KremisError> {
+    for token in crate::search::tokenize(value.as_str()) {
+        let count = table
+            .get((token.as_str(), node_id))
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+            .map(|v| v.value())
+            .unwrap_or(0);
+        table
+            .insert((token.as_str(), node_id), count.saturating_add(1))
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Undo [`index_tokens`] for one value of `node_id`, deleting postings whose
+/// count reaches zero.
+fn unindex_tokens(
+    table: &mut Table<'_, (&'static str, u64), u64>,
+    node_id: u64,
+    value: &Value,
+) -> Result<(), KremisError> {
+    for token in crate::search::tokenize(value.as_str()) {
+        let count = table
+            .get((token.as_str(), node_id))
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+            .map(|v| v.value())
+            .unwrap_or(0);
+        if count <= 1 {
+            table
+                .remove((token.as_str(), node_id))
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+        } else {
+            table
+                .insert((token.as_str(), node_id), count - 1)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Populate `TOKEN_INDEX` from `PROPERTIES`, unless METADATA says it has
+/// already been done.
+fn backfill_token_index(txn: &WriteTransaction) -> Result<(), KremisError> {
+    let mut meta_table = txn
+        .open_table(METADATA)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    if meta_table
+        .get(TOKEN_INDEX_BUILT)
+        .map_err(|e| KremisError::IoError(e.to_string()))?
+        .is_some()
+    {
+        return Ok(());
+    }
+
+    let props_table = txn
+        .open_table(PROPERTIES)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    let mut token_table = txn
+        .open_table(TOKEN_INDEX)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+
+    for entry in props_table
+        .iter()
+        .map_err(|e| KremisError::IoError(e.to_string()))?
+    {
+        let (key, data) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+        let (node_id, _) = key.value();
+        let (_, values): (Attribute, Vec<Value>) = postcard::from_bytes(data.value())
+            .map_err(|e| KremisError::DeserializationError(e.to_string()))?;
+        for value in &values {
+            index_tokens(&mut token_table, node_id, value)?;
+        }
+    }
+
+    meta_table
+        .insert(TOKEN_INDEX_BUILT, 1)
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    Ok(())
+}
+
 // =============================================================================
 // GRAPHSTORE TRAIT IMPLEMENTATION
 // =============================================================================
@@ -503,6 +2263,16 @@ impl GraphStore for RedbGraph {
                     .insert("next_node_id", self.next_node_id)
                     .map_err(|e| KremisError::IoError(e.to_string()))?;
             }
+            log_mutations(
+                &write_txn,
+                &self.checkpoints,
+                self.next_node_id,
+                vec![Mutation::InsertNode {
+                    node: node_id,
+                    entity,
+                }],
+            )?;
+            append_journal(&write_txn, &JournalEntry::InsertNode(entity))?;
             write_txn
                 .commit()
                 .map_err(|e| KremisError::IoError(e.to_string()))?;
@@ -518,6 +2288,7 @@ impl GraphStore for RedbGraph {
         &mut self,
         from: NodeId,
         to: NodeId,
+        relation: Option<&Attribute>,
         weight: EdgeWeight,
     ) -> Result<(), KremisError> {
         // Check that both nodes exist before inserting (consistent with Graph behavior)
@@ -529,21 +2300,37 @@ impl GraphStore for RedbGraph {
             .db
             .begin_write()
             .map_err(|e| KremisError::IoError(e.to_string()))?;
-        {
-            let mut edges_table = write_txn
-                .open_table(EDGES)
-                .map_err(|e| KremisError::IoError(e.to_string()))?;
-            edges_table
-                .insert((from.0, to.0), weight.value())
-                .map_err(|e| KremisError::IoError(e.to_string()))?;
-        }
+        write_edge_weight(&write_txn, from, to, relation, weight.value())?;
+        log_edge(
+            &write_txn,
+            &self.checkpoints,
+            self.next_node_id,
+            from,
+            to,
+            relation,
+            weight,
+        )?;
+        append_journal(
+            &write_txn,
+            &JournalEntry::InsertEdge {
+                from,
+                to,
+                relation: relation.cloned(),
+                weight,
+            },
+        )?;
         write_txn
             .commit()
             .map_err(|e| KremisError::IoError(e.to_string()))?;
         Ok(())
     }
 
-    fn increment_edge(&mut self, from: NodeId, to: NodeId) -> Result<(), KremisError> {
+    fn increment_edge(
+        &mut self,
+        from: NodeId,
+        to: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<(), KremisError> {
         // Silent no-op when either node is missing — consistent with insert_edge semantics.
         if !self.contains_node(from)? || !self.contains_node(to)? {
             return Ok(());
@@ -552,47 +2339,213 @@ impl GraphStore for RedbGraph {
             .db
             .begin_write()
             .map_err(|e| KremisError::IoError(e.to_string()))?;
-        {
-            let mut edges_table = write_txn
-                .open_table(EDGES)
-                .map_err(|e| KremisError::IoError(e.to_string()))?;
-            let current = edges_table
-                .get((from.0, to.0))
-                .map_err(|e| KremisError::IoError(e.to_string()))?
-                .map(|v| v.value())
-                .unwrap_or(0);
-            edges_table
-                .insert((from.0, to.0), current.saturating_add(1))
-                .map_err(|e| KremisError::IoError(e.to_string()))?;
-        }
+        let current = read_edge_weight(&write_txn, from, to, relation)?.unwrap_or(0);
+        let weight = EdgeWeight::new(current.saturating_add(1));
+        write_edge_weight(&write_txn, from, to, relation, weight.value())?;
+        log_edge(
+            &write_txn,
+            &self.checkpoints,
+            self.next_node_id,
+            from,
+            to,
+            relation,
+            weight,
+        )?;
+        append_journal(
+            &write_txn,
+            &JournalEntry::IncrementEdge {
+                from,
+                to,
+                relation: relation.cloned(),
+            },
+        )?;
         write_txn
             .commit()
             .map_err(|e| KremisError::IoError(e.to_string()))?;
         Ok(())
     }
 
-    fn decrement_edge(&mut self, from: NodeId, to: NodeId) -> Result<(), KremisError> {
-        let current = self
-            .get_edge(from, to)?
+    fn decrement_edge(
+        &mut self,
+        from: NodeId,
+        to: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<(), KremisError> {
+        let write_txn = self
+            .db
+            .begin_write()
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        let current = read_edge_weight(&write_txn, from, to, relation)?
+            .map(EdgeWeight::new)
             .ok_or(KremisError::EdgeNotFound(from, to))?;
-        let new_weight = current.decrement();
+        let weight = current.decrement();
+        write_edge_weight(&write_txn, from, to, relation, weight.value())?;
+        log_edge(
+            &write_txn,
+            &self.checkpoints,
+            self.next_node_id,
+            from,
+            to,
+            relation,
+            weight,
+        )?;
+        append_journal(
+            &write_txn,
+            &JournalEntry::DecrementEdge {
+                from,
+                to,
+                relation: relation.cloned(),
+            },
+        )?;
+        write_txn
+            .commit()
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        Ok(())
+    }
 
+    fn remove_node(&mut self, id: NodeId) -> Result<Node, KremisError> {
         let write_txn = self
             .db
             .begin_write()
             .map_err(|e| KremisError::IoError(e.to_string()))?;
+
+        let node = {
+            let mut nodes_table = write_txn
+                .open_table(NODES)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            let removed = nodes_table
+                .remove(id.0)
+                .map_err(|e| KremisError::IoError(e.to_string()))?
+                .ok_or(KremisError::NodeNotFound(id))?;
+            postcard::from_bytes::<Node>(removed.value())
+                .map_err(|e| KremisError::SerializationError(e.to_string()))?
+        };
+
+        {
+            let mut entity_table = write_txn
+                .open_table(ENTITY_INDEX)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            entity_table
+                .remove(node.entity.0)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+
+            let mut props_table = write_txn
+                .open_table(PROPERTIES)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            let mut index_table = write_txn
+                .open_table(PROPERTY_INDEX)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            let mut buckets: Vec<(Attribute, Vec<Value>)> = Vec::new();
+            for entry in props_table
+                .range((id.0, 0u64)..=(id.0, u64::MAX))
+                .map_err(|e| KremisError::IoError(e.to_string()))?
+            {
+                let (_, data) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+                buckets.push(
+                    postcard::from_bytes(data.value())
+                        .map_err(|e| KremisError::DeserializationError(e.to_string()))?,
+                );
+            }
+            let mut token_table = write_txn
+                .open_table(TOKEN_INDEX)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            for (attribute, values) in &buckets {
+                for value in values {
+                    index_table
+                        .remove((attribute.as_str(), value.as_str(), id.0))
+                        .map_err(|e| KremisError::IoError(e.to_string()))?;
+                    unindex_tokens(&mut token_table, id.0, value)?;
+                }
+            }
+            props_table
+                .retain_in((id.0, 0u64)..=(id.0, u64::MAX), |_, _| false)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+
+            let mut counts_table = write_txn
+                .open_table(PROPERTY_COUNTS)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            counts_table
+                .remove(id.0)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+        }
+
         {
             let mut edges_table = write_txn
                 .open_table(EDGES)
                 .map_err(|e| KremisError::IoError(e.to_string()))?;
-            edges_table
-                .insert((from.0, to.0), new_weight.value())
+            let mut labeled_table = write_txn
+                .open_table(LABELED_EDGES)
                 .map_err(|e| KremisError::IoError(e.to_string()))?;
+            let mut reverse_table = write_txn
+                .open_table(REVERSE_EDGES)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+
+            // Every incident edge as (from, to, relation), "" for unlabeled.
+            // Outgoing edges come from the forward tables, incoming ones from
+            // the reverse index; a self-loop shows up in both, which is harmless.
+            let mut incident: Vec<(u64, u64, String)> = Vec::new();
+            for entry in edges_table
+                .range((id.0, 0u64)..=(id.0, u64::MAX))
+                .map_err(|e| KremisError::IoError(e.to_string()))?
+            {
+                let (key, _) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+                let (from_id, to_id) = key.value();
+                incident.push((from_id, to_id, String::new()));
+            }
+            for entry in labeled_table
+                .range((id.0, 0u64, "")..)
+                .map_err(|e| KremisError::IoError(e.to_string()))?
+            {
+                let (key, _) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+                let (from_id, to_id, label) = key.value();
+                if from_id != id.0 {
+                    break;
+                }
+                incident.push((from_id, to_id, label.to_string()));
+            }
+            for entry in reverse_table
+                .range((id.0, 0u64, "")..)
+                .map_err(|e| KremisError::IoError(e.to_string()))?
+            {
+                let (key, _) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+                let (to_id, from_id, label) = key.value();
+                if to_id != id.0 {
+                    break;
+                }
+                incident.push((from_id, to_id, label.to_string()));
+            }
+
+            for (from_id, to_id, label) in &incident {
+                if label.is_empty() {
+                    edges_table
+                        .remove((*from_id, *to_id))
+                        .map_err(|e| KremisError::IoError(e.to_string()))?;
+                } else {
+                    labeled_table
+                        .remove((*from_id, *to_id, label.as_str()))
+                        .map_err(|e| KremisError::IoError(e.to_string()))?;
+                }
+                reverse_table
+                    .remove((*to_id, *from_id, label.as_str()))
+                    .map_err(|e| KremisError::IoError(e.to_string()))?;
+            }
         }
+        log_mutations(
+            &write_txn,
+            &self.checkpoints,
+            self.next_node_id,
+            vec![Mutation::RemoveNode { node: id }],
+        )?;
+        append_journal(&write_txn, &JournalEntry::RemoveNode(id))?;
+
         write_txn
             .commit()
             .map_err(|e| KremisError::IoError(e.to_string()))?;
-        Ok(())
+
+        // Update cache only after successful commit.
+        self.entity_cache.remove(&node.entity);
+
+        Ok(node)
     }
 
     fn lookup(&self, id: NodeId) -> Result<Option<Node>, KremisError> {
@@ -621,7 +2574,68 @@ impl GraphStore for RedbGraph {
         self.entity_cache.get(&entity).copied()
     }
 
-    fn get_edge(&self, from: NodeId, to: NodeId) -> Result<Option<EdgeWeight>, KremisError> {
+    fn get_edge(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<Option<EdgeWeight>, KremisError> {
+        let read_txn = self
+            .db
+            .begin_read()
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        let result = match relation {
+            None => {
+                let edges_table = read_txn
+                    .open_table(EDGES)
+                    .map_err(|e| KremisError::IoError(e.to_string()))?;
+                edges_table
+                    .get((from.0, to.0))
+                    .map_err(|e| KremisError::IoError(e.to_string()))?
+                    .map(|v| EdgeWeight::new(v.value()))
+            }
+            Some(relation) => {
+                let labeled_table = read_txn
+                    .open_table(LABELED_EDGES)
+                    .map_err(|e| KremisError::IoError(e.to_string()))?;
+                labeled_table
+                    .get((from.0, to.0, relation.as_str()))
+                    .map_err(|e| KremisError::IoError(e.to_string()))?
+                    .map(|v| EdgeWeight::new(v.value()))
+            }
+        };
+        Ok(result)
+    }
+
+    fn neighbors(
+        &self,
+        from: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<Vec<(NodeId, EdgeWeight)>, KremisError> {
+        Ok(self
+            .labeled_neighbors(from, relation)?
+            .into_iter()
+            .map(|(to, _, weight)| (to, weight))
+            .collect())
+    }
+
+    fn predecessors(
+        &self,
+        to: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<Vec<(NodeId, EdgeWeight)>, KremisError> {
+        Ok(self
+            .labeled_predecessors(to, relation)?
+            .into_iter()
+            .map(|(from, _, weight)| (from, weight))
+            .collect())
+    }
+
+    fn labeled_neighbors(
+        &self,
+        from: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<Vec<(NodeId, Option<Attribute>, EdgeWeight)>, KremisError> {
         let read_txn = self
             .db
             .begin_read()
@@ -629,32 +2643,98 @@ impl GraphStore for RedbGraph {
         let edges_table = read_txn
             .open_table(EDGES)
             .map_err(|e| KremisError::IoError(e.to_string()))?;
-        let result = edges_table
-            .get((from.0, to.0))
+        let labeled_table = read_txn
+            .open_table(LABELED_EDGES)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+
+        // (to, relation, weight) — sorted at the end so unlabeled and labeled
+        // edges interleave in the same (to, relation) order as `Graph`.
+        let mut found: Vec<(u64, Option<String>, i64)> = Vec::new();
+        if relation.is_none() {
+            for entry in edges_table
+                .range((from.0, 0u64)..=(from.0, u64::MAX))
+                .map_err(|e| KremisError::IoError(e.to_string()))?
+            {
+                let (key, value) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+                let (_from_id, to_id) = key.value();
+                found.push((to_id, None, value.value()));
+            }
+        }
+        for entry in labeled_table
+            .range((from.0, 0u64, "")..)
             .map_err(|e| KremisError::IoError(e.to_string()))?
-            .map(|v| EdgeWeight::new(v.value()));
-        Ok(result)
+        {
+            let (key, value) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+            let (from_id, to_id, label) = key.value();
+            if from_id != from.0 {
+                break;
+            }
+            if relation.is_none_or(|wanted| wanted.as_str() == label) {
+                found.push((to_id, Some(label.to_string()), value.value()));
+            }
+        }
+        found.sort();
+
+        Ok(found
+            .into_iter()
+            .map(|(to_id, label, weight)| {
+                (
+                    NodeId(to_id),
+                    label.map(Attribute::new),
+                    EdgeWeight::new(weight),
+                )
+            })
+            .collect())
     }
 
-    fn neighbors(&self, from: NodeId) -> Result<Vec<(NodeId, EdgeWeight)>, KremisError> {
+    fn labeled_predecessors(
+        &self,
+        to: NodeId,
+        relation: Option<&Attribute>,
+    ) -> Result<Vec<(NodeId, Option<Attribute>, EdgeWeight)>, KremisError> {
         let read_txn = self
             .db
             .begin_read()
             .map_err(|e| KremisError::IoError(e.to_string()))?;
+        let reverse_table = read_txn
+            .open_table(REVERSE_EDGES)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
         let edges_table = read_txn
             .open_table(EDGES)
             .map_err(|e| KremisError::IoError(e.to_string()))?;
+        let labeled_table = read_txn
+            .open_table(LABELED_EDGES)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
 
-        let mut neighbors = Vec::new();
-        for entry in edges_table
-            .range((from.0, 0u64)..=(from.0, u64::MAX))
+        // Keys are (to, from, relation) with "" for unlabeled, so the scan
+        // already yields the (from, relation) order `Graph` uses.
+        let mut found = Vec::new();
+        for entry in reverse_table
+            .range((to.0, 0u64, "")..)
             .map_err(|e| KremisError::IoError(e.to_string()))?
         {
-            let (key, value) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
-            let (_from_id, to_id) = key.value();
-            neighbors.push((NodeId(to_id), EdgeWeight::new(value.value())));
+            let (key, _) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+            let (to_id, from_id, label) = key.value();
+            if to_id != to.0 {
+                break;
+            }
+            let weight = match relation {
+                None if label.is_empty() => edges_table
+                    .get((from_id, to_id))
+                    .map_err(|e| KremisError::IoError(e.to_string()))?
+                    .map(|v| v.value()),
+                Some(wanted) if wanted.as_str() != label => continue,
+                _ => labeled_table
+                    .get((from_id, to_id, label))
+                    .map_err(|e| KremisError::IoError(e.to_string()))?
+                    .map(|v| v.value()),
+            };
+            if let Some(weight) = weight {
+                let label = (!label.is_empty()).then(|| Attribute::new(label));
+                found.push((NodeId(from_id), label, EdgeWeight::new(weight)));
+            }
         }
-        Ok(neighbors)
+        Ok(found)
     }
 
     fn contains_node(&self, id: NodeId) -> Result<bool, KremisError> {
@@ -686,6 +2766,26 @@ impl GraphStore for RedbGraph {
         Ok(count as usize)
     }
 
+    fn node_ids(&self) -> Result<Vec<NodeId>, KremisError> {
+        let read_txn = self
+            .db
+            .begin_read()
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        let nodes_table = read_txn
+            .open_table(NODES)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+
+        let mut ids = Vec::new();
+        for entry in nodes_table
+            .iter()
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+        {
+            let (key, _) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+            ids.push(NodeId(key.value()));
+        }
+        Ok(ids)
+    }
+
     fn edge_count(&self) -> Result<usize, KremisError> {
         let read_txn = self
             .db
@@ -694,9 +2794,17 @@ impl GraphStore for RedbGraph {
         let edges_table = read_txn
             .open_table(EDGES)
             .map_err(|e| KremisError::IoError(e.to_string()))?;
+        let labeled_table = read_txn
+            .open_table(LABELED_EDGES)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
         let count = edges_table
             .len()
-            .map_err(|e| KremisError::IoError(e.to_string()))?;
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+            .saturating_add(
+                labeled_table
+                    .len()
+                    .map_err(|e| KremisError::IoError(e.to_string()))?,
+            );
         Ok(count as usize)
     }
 
@@ -706,14 +2814,53 @@ impl GraphStore for RedbGraph {
         attribute: Attribute,
         value: Value,
     ) -> Result<(), KremisError> {
-        use crate::primitives::MAX_PROPERTIES_PER_NODE;
-
         // Verify node exists
         if !self.contains_node(node)? {
             return Err(KremisError::NodeNotFound(node));
         }
 
-        // Stable attribute hash: FNV-1a 64-bit (see stable_attr_hash fn for rationale)
+        let write_txn = self
+            .db
+            .begin_write()
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        let stored = write_property(&write_txn, node, &attribute, &value)?;
+        if stored {
+            append_journal(
+                &write_txn,
+                &JournalEntry::StoreProperty {
+                    node,
+                    attribute: attribute.clone(),
+                    value: value.clone(),
+                },
+            )?;
+            log_mutations(
+                &write_txn,
+                &self.checkpoints,
+                self.next_node_id,
+                vec![Mutation::StoreProperty {
+                    node,
+                    attribute,
+                    value,
+                }],
+            )?;
+        }
+        write_txn
+            .commit()
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    fn remove_property(
+        &mut self,
+        node: NodeId,
+        attribute: &Attribute,
+        value: &Value,
+    ) -> Result<bool, KremisError> {
+        if !self.contains_node(node)? {
+            return Err(KremisError::NodeNotFound(node));
+        }
+
         let attr_hash = stable_attr_hash(attribute.as_str());
 
         let write_txn = self
@@ -728,66 +2875,80 @@ impl GraphStore for RedbGraph {
                 .open_table(PROPERTY_COUNTS)
                 .map_err(|e| KremisError::IoError(e.to_string()))?;
 
-            // Read existing values for this (node, attribute) pair
-            let existing: Vec<Value> = props_table
+            let mut values: Vec<Value> = props_table
                 .get((node.0, attr_hash))
                 .map_err(|e| KremisError::IoError(e.to_string()))?
-                .map(|data| {
-                    postcard::from_bytes::<(Attribute, Vec<Value>)>(data.value())
-                        .map_err(|e| KremisError::DeserializationError(e.to_string()))
-                        .and_then(|(stored_attr, values)| {
-                            if stored_attr != attribute {
-                                Err(KremisError::StorageCorruption(format!(
-                                    "attribute hash collision: bucket contains \"{}\", \
-                                     expected \"{}\"",
-                                    stored_attr.as_str(),
-                                    attribute.as_str()
-                                )))
-                            } else {
-                                Ok(values)
-                            }
-                        })
-                })
+                .map(|data| decode_property_bucket(data.value(), attribute))
                 .transpose()?
                 .unwrap_or_default();
 
-            // Set semantics: an already-stored pair is idempotent and never
-            // grows the node, so it is allowed even when the node is at its cap.
-            if !existing.contains(&value) {
-                // Bound the number of distinct properties per node (DoS guard),
-                // using the O(1) maintained counter rather than scanning buckets.
-                let count = counts_table
-                    .get(node.0)
-                    .map_err(|e| KremisError::IoError(e.to_string()))?
-                    .map(|v| v.value())
-                    .unwrap_or(0) as usize;
-                if count >= MAX_PROPERTIES_PER_NODE {
-                    // Returning drops the tables + txn without commit (abort).
-                    return Err(KremisError::PropertyLimitExceeded(
-                        node,
-                        MAX_PROPERTIES_PER_NODE,
-                    ));
-                }
+            let Some(position) = values.iter().position(|v| v == value) else {
+                // Nothing to retract: dropping the txn without commit aborts it.
+                return Ok(false);
+            };
+            values.remove(position);
 
-                let mut values = existing;
-                values.push(value);
+            let mut index_table = write_txn
+                .open_table(PROPERTY_INDEX)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            index_table
+                .remove((attribute.as_str(), value.as_str(), node.0))
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            let mut token_table = write_txn
+                .open_table(TOKEN_INDEX)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            unindex_tokens(&mut token_table, node.0, value)?;
 
-                // Serialize and store the new property, then bump the counter.
+            if values.is_empty() {
+                props_table
+                    .remove((node.0, attr_hash))
+                    .map_err(|e| KremisError::IoError(e.to_string()))?;
+            } else {
                 let prop_bytes = postcard::to_allocvec(&(attribute, values))
                     .map_err(|e| KremisError::SerializationError(e.to_string()))?;
                 props_table
                     .insert((node.0, attr_hash), prop_bytes.as_slice())
                     .map_err(|e| KremisError::IoError(e.to_string()))?;
+            }
+
+            let count = counts_table
+                .get(node.0)
+                .map_err(|e| KremisError::IoError(e.to_string()))?
+                .map(|v| v.value())
+                .unwrap_or(0);
+            if count <= 1 {
+                counts_table
+                    .remove(node.0)
+                    .map_err(|e| KremisError::IoError(e.to_string()))?;
+            } else {
                 counts_table
-                    .insert(node.0, (count as u64) + 1)
+                    .insert(node.0, count - 1)
                     .map_err(|e| KremisError::IoError(e.to_string()))?;
             }
         }
+        log_mutations(
+            &write_txn,
+            &self.checkpoints,
+            self.next_node_id,
+            vec![Mutation::RemoveProperty {
+                node,
+                attribute: attribute.clone(),
+                value: value.clone(),
+            }],
+        )?;
+        append_journal(
+            &write_txn,
+            &JournalEntry::RemoveProperty {
+                node,
+                attribute: attribute.clone(),
+                value: value.clone(),
+            },
+        )?;
         write_txn
             .commit()
             .map_err(|e| KremisError::IoError(e.to_string()))?;
 
-        Ok(())
+        Ok(true)
     }
 
     fn get_properties(&self, node: NodeId) -> Result<Vec<(Attribute, Value)>, KremisError> {
@@ -821,6 +2982,137 @@ impl GraphStore for RedbGraph {
 
         Ok(result)
     }
+
+    fn find_by_property(
+        &self,
+        attribute: &Attribute,
+        value: &Value,
+        after: Option<NodeId>,
+        limit: usize,
+    ) -> Result<Vec<NodeId>, KremisError> {
+        let first = match after {
+            None => 0,
+            Some(NodeId(id)) => match id.checked_add(1) {
+                Some(next) => next,
+                None => return Ok(Vec::new()),
+            },
+        };
+
+        let read_txn = self
+            .db
+            .begin_read()
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        let index_table = read_txn
+            .open_table(PROPERTY_INDEX)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+
+        let (attr, val) = (attribute.as_str(), value.as_str());
+        let mut result = Vec::new();
+        for entry in index_table
+            .range((attr, val, first)..=(attr, val, u64::MAX))
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+            .take(limit)
+        {
+            let (key, _) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+            result.push(NodeId(key.value().2));
+        }
+
+        Ok(result)
+    }
+
+    fn token_prefix_matches(&self, prefix: &str) -> Result<BTreeMap<NodeId, bool>, KremisError> {
+        let read_txn = self
+            .db
+            .begin_read()
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        let token_table = read_txn
+            .open_table(TOKEN_INDEX)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+
+        let mut matches = BTreeMap::new();
+        for entry in token_table
+            .range((prefix, 0u64)..)
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+        {
+            let (key, _) = entry.map_err(|e| KremisError::IoError(e.to_string()))?;
+            let (token, node_id) = key.value();
+            if !token.starts_with(prefix) {
+                break;
+            }
+            *matches.entry(NodeId(node_id)).or_insert(false) |= token == prefix;
+        }
+
+        Ok(matches)
+    }
+
+    fn entity_for_key(&self, key: &str) -> Result<Option<EntityId>, KremisError> {
+        let read_txn = self
+            .db
+            .begin_read()
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        let keys_table = read_txn
+            .open_table(ENTITY_KEYS)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        Ok(keys_table
+            .get(key)
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+            .map(|v| EntityId(v.value())))
+    }
+
+    fn key_for_entity(&self, entity: EntityId) -> Result<Option<String>, KremisError> {
+        let read_txn = self
+            .db
+            .begin_read()
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        let names_table = read_txn
+            .open_table(ENTITY_KEY_NAMES)
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        Ok(names_table
+            .get(entity.0)
+            .map_err(|e| KremisError::IoError(e.to_string()))?
+            .map(|v| v.value().to_string()))
+    }
+
+    fn bind_entity_key(&mut self, key: &str, entity: EntityId) -> Result<(), KremisError> {
+        let write_txn = self
+            .db
+            .begin_write()
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        {
+            let mut keys_table = write_txn
+                .open_table(ENTITY_KEYS)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            let mut names_table = write_txn
+                .open_table(ENTITY_KEY_NAMES)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            keys_table
+                .insert(key, entity.0)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+            names_table
+                .insert(entity.0, key)
+                .map_err(|e| KremisError::IoError(e.to_string()))?;
+        }
+        log_mutations(
+            &write_txn,
+            &self.checkpoints,
+            self.next_node_id,
+            vec![Mutation::BindEntityKey {
+                key: key.to_string(),
+                entity,
+            }],
+        )?;
+        append_journal(
+            &write_txn,
+            &JournalEntry::RegisterEntityKey {
+                key: key.to_string(),
+                entity,
+            },
+        )?;
+        write_txn
+            .commit()
+            .map_err(|e| KremisError::IoError(e.to_string()))?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -842,7 +3134,7 @@ mod tests {
         assert_eq!(graph.node_count().expect("count"), 2);
 
         graph
-            .insert_edge(node1, node2, EdgeWeight::new(5))
+            .insert_edge(node1, node2, None, EdgeWeight::new(5))
             .expect("insert edge");
         assert_eq!(graph.edge_count().expect("count"), 1);
     }
@@ -861,69 +3153,329 @@ mod tests {
     }
 
     #[test]
-    fn entities_survive_a_reopen() {
+    fn entities_survive_a_reopen() {
+        let temp = tempdir().expect("temp dir");
+        let db_path = temp.path().join("test.redb");
+
+        let node = {
+            let mut graph = RedbGraph::open(&db_path).expect("open db");
+            graph.insert_node(EntityId(7)).expect("insert node");
+            graph.insert_node(EntityId(3)).expect("insert node")
+        };
+
+        // Reopened by a process that never saw the ingest: the reverse mapping
+        // must come back from ENTITY_INDEX, not from memory.
+        let graph = RedbGraph::open(&db_path).expect("reopen db");
+
+        assert_eq!(
+            graph.entities().map(|(e, _)| e).collect::<Vec<_>>(),
+            vec![EntityId(3), EntityId(7)]
+        );
+        assert_eq!(
+            graph.lookup(node).expect("lookup").map(|n| n.entity),
+            Some(EntityId(3))
+        );
+    }
+
+    #[test]
+    fn persistence() {
+        let temp = tempdir().expect("temp dir");
+        let db_path = temp.path().join("test.redb");
+
+        // Create and populate
+        {
+            let mut graph = RedbGraph::open(&db_path).expect("open db");
+            graph.insert_node(EntityId(1)).expect("insert node");
+            graph.insert_node(EntityId(2)).expect("insert node");
+        }
+
+        // Reopen and verify
+        {
+            let graph = RedbGraph::open(&db_path).expect("open db");
+            assert_eq!(graph.node_count().expect("count"), 2);
+            assert!(graph.get_node_by_entity(EntityId(1)).is_some());
+        }
+    }
+
+    #[test]
+    fn edge_operations() {
+        let temp = tempdir().expect("temp dir");
+        let db_path = temp.path().join("test.redb");
+        let mut graph = RedbGraph::open(&db_path).expect("open db");
+
+        let node1 = graph.insert_node(EntityId(1)).expect("insert node");
+        let node2 = graph.insert_node(EntityId(2)).expect("insert node");
+
+        graph
+            .insert_edge(node1, node2, None, EdgeWeight::new(3))
+            .expect("insert edge");
+
+        let weight = graph.get_edge(node1, node2, None).expect("get edge");
+        assert_eq!(weight, Some(EdgeWeight::new(3)));
+
+        graph
+            .increment_edge(node1, node2, None)
+            .expect("increment edge");
+        let weight = graph.get_edge(node1, node2, None).expect("get edge");
+        assert_eq!(weight, Some(EdgeWeight::new(4)));
+    }
+
+    #[test]
+    fn labeled_edges_are_distinct_and_persist() {
+        let temp = tempdir().expect("temp dir");
+        let db_path = temp.path().join("test.redb");
+        let owns = Attribute::new("owns");
+
+        {
+            let mut graph = RedbGraph::open(&db_path).expect("open db");
+            let n1 = graph.insert_node(EntityId(1)).expect("insert");
+            let n2 = graph.insert_node(EntityId(2)).expect("insert");
+            let n3 = graph.insert_node(EntityId(3)).expect("insert");
+
+            graph
+                .insert_edge(n1, n3, None, EdgeWeight::new(1))
+                .expect("edge");
+            graph
+                .insert_edge(n1, n2, Some(&owns), EdgeWeight::new(5))
+                .expect("edge");
+            graph.increment_edge(n1, n2, None).expect("increment");
+            graph
+                .decrement_edge(n1, n2, Some(&owns))
+                .expect("decrement");
+        }
+
+        let graph = RedbGraph::open(&db_path).expect("reopen db");
+        let n1 = graph.get_node_by_entity(EntityId(1)).unwrap();
+        let n2 = graph.get_node_by_entity(EntityId(2)).unwrap();
+        let n3 = graph.get_node_by_entity(EntityId(3)).unwrap();
+
+        assert_eq!(graph.edge_count().expect("count"), 3);
+        assert_eq!(
+            graph.get_edge(n1, n2, Some(&owns)).expect("get"),
+            Some(EdgeWeight::new(4))
+        );
+        assert_eq!(
+            graph.get_edge(n1, n2, None).expect("get"),
+            Some(EdgeWeight::new(1))
+        );
+
+        // Unlabeled and labeled edges merge in (to, relation) order.
+        assert_eq!(
+            graph.neighbors(n1, None).expect("neighbors"),
+            vec![
+                (n2, EdgeWeight::new(1)),
+                (n2, EdgeWeight::new(4)),
+                (n3, EdgeWeight::new(1))
+            ]
+        );
+        assert_eq!(
+            graph.neighbors(n1, Some(&owns)).expect("neighbors"),
+            vec![(n2, EdgeWeight::new(4))]
+        );
+        assert_eq!(
+            graph.edges_with_relations().expect("edges")[1],
+            (n1, n2, Some(owns.clone()), EdgeWeight::new(4))
+        );
+        assert_eq!(
+            graph.labeled_neighbors(n1, None).expect("neighbors"),
+            vec![
+                (n2, None, EdgeWeight::new(1)),
+                (n2, Some(owns.clone()), EdgeWeight::new(4)),
+                (n3, None, EdgeWeight::new(1))
+            ]
+        );
+        assert_eq!(
+            graph.labeled_predecessors(n2, None).expect("predecessors"),
+            vec![
+                (n1, None, EdgeWeight::new(1)),
+                (n1, Some(owns.clone()), EdgeWeight::new(4))
+            ]
+        );
+        assert_eq!(
+            graph
+                .strongest_labeled_edge(n1, n2, None)
+                .expect("strongest"),
+            Some((Some(owns.clone()), EdgeWeight::new(4)))
+        );
+    }
+
+    #[test]
+    fn predecessors_follow_incoming_edges() {
+        let temp = tempdir().expect("temp dir");
+        let db_path = temp.path().join("test.redb");
+        let mut graph = RedbGraph::open(&db_path).expect("open db");
+        let owns = Attribute::new("owns");
+
+        let n1 = graph.insert_node(EntityId(1)).expect("insert");
+        let n2 = graph.insert_node(EntityId(2)).expect("insert");
+        let n3 = graph.insert_node(EntityId(3)).expect("insert");
+
+        graph
+            .insert_edge(n2, n3, None, EdgeWeight::new(2))
+            .expect("edge");
+        graph
+            .insert_edge(n1, n3, Some(&owns), EdgeWeight::new(7))
+            .expect("edge");
+        graph.increment_edge(n1, n3, None).expect("increment");
+        graph
+            .ingest_batch(&[make_signal(3, "a", "x"), make_signal(2, "a", "y")])
+            .expect("batch");
+
+        assert_eq!(
+            graph.predecessors(n3, None).expect("predecessors"),
+            vec![
+                (n1, EdgeWeight::new(1)),
+                (n1, EdgeWeight::new(7)),
+                (n2, EdgeWeight::new(2))
+            ]
+        );
+        assert_eq!(
+            graph.predecessors(n3, Some(&owns)).expect("predecessors"),
+            vec![(n1, EdgeWeight::new(7))]
+        );
+        // The batch linked 3 -> 2, so 3 is now a predecessor of 2.
+        assert_eq!(
+            graph.predecessors(n2, None).expect("predecessors"),
+            vec![(n3, EdgeWeight::new(1))]
+        );
+        assert!(
+            graph
+                .predecessors(n1, None)
+                .expect("predecessors")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn remove_node_cascades_and_persists() {
         let temp = tempdir().expect("temp dir");
         let db_path = temp.path().join("test.redb");
+        let owns = Attribute::new("owns");
 
-        let node = {
+        {
             let mut graph = RedbGraph::open(&db_path).expect("open db");
-            graph.insert_node(EntityId(7)).expect("insert node");
-            graph.insert_node(EntityId(3)).expect("insert node")
-        };
+            let n1 = graph.insert_node(EntityId(1)).expect("insert");
+            let n2 = graph.insert_node(EntityId(2)).expect("insert");
+            let n3 = graph.insert_node(EntityId(3)).expect("insert");
 
-        // Reopened by a process that never saw the ingest: the reverse mapping
-        // must come back from ENTITY_INDEX, not from memory.
-        let graph = RedbGraph::open(&db_path).expect("reopen db");
+            graph.increment_edge(n1, n2, None).expect("edge");
+            graph
+                .insert_edge(n2, n3, Some(&owns), EdgeWeight::new(2))
+                .expect("edge");
+            graph
+                .insert_edge(n3, n2, Some(&owns), EdgeWeight::new(4))
+                .expect("edge");
+            graph.increment_edge(n2, n2, None).expect("edge");
+            graph.increment_edge(n1, n3, None).expect("edge");
+            graph
+                .store_property(n2, Attribute::new("name"), Value::new("Bob"))
+                .expect("prop");
+
+            let removed = graph.remove_node(n2).expect("remove");
+            assert_eq!(removed.entity, EntityId(2));
+            assert_eq!(graph.get_node_by_entity(EntityId(2)), None);
+            assert!(matches!(
+                graph.remove_node(n2),
+                Err(KremisError::NodeNotFound(_))
+            ));
+        }
 
+        let mut graph = RedbGraph::open(&db_path).expect("reopen db");
+        let n1 = graph.get_node_by_entity(EntityId(1)).unwrap();
+        let n3 = graph.get_node_by_entity(EntityId(3)).unwrap();
+        assert_eq!(graph.get_node_by_entity(EntityId(2)), None);
+        assert_eq!(graph.node_count().expect("count"), 2);
         assert_eq!(
-            graph.entities().map(|(e, _)| e).collect::<Vec<_>>(),
-            vec![EntityId(3), EntityId(7)]
+            graph.edges_with_relations().expect("edges"),
+            vec![(n1, n3, None, EdgeWeight::new(1))]
         );
         assert_eq!(
-            graph.lookup(node).expect("lookup").map(|n| n.entity),
-            Some(EntityId(3))
+            graph.predecessors(n3, None).expect("predecessors"),
+            vec![(n1, EdgeWeight::new(1))]
         );
+        assert!(graph.neighbors(n3, None).expect("neighbors").is_empty());
+
+        // A re-ingested entity gets a fresh id and an empty property set.
+        let again = graph.insert_node(EntityId(2)).expect("insert");
+        assert_ne!(again, NodeId(1));
+        assert!(graph.get_properties(again).expect("props").is_empty());
     }
 
     #[test]
-    fn persistence() {
+    fn reverse_index_is_rebuilt_for_older_databases() {
         let temp = tempdir().expect("temp dir");
         let db_path = temp.path().join("test.redb");
+        let owns = Attribute::new("owns");
 
-        // Create and populate
         {
             let mut graph = RedbGraph::open(&db_path).expect("open db");
-            graph.insert_node(EntityId(1)).expect("insert node");
-            graph.insert_node(EntityId(2)).expect("insert node");
-        }
+            let n1 = graph.insert_node(EntityId(1)).expect("insert");
+            let n2 = graph.insert_node(EntityId(2)).expect("insert");
+            graph
+                .insert_edge(n1, n2, None, EdgeWeight::new(3))
+                .expect("edge");
+            graph
+                .insert_edge(n1, n2, Some(&owns), EdgeWeight::new(4))
+                .expect("edge");
 
-        // Reopen and verify
-        {
-            let graph = RedbGraph::open(&db_path).expect("open db");
-            assert_eq!(graph.node_count().expect("count"), 2);
-            assert!(graph.get_node_by_entity(EntityId(1)).is_some());
+            // Simulate a database written before the reverse index existed.
+            let write_txn = graph.db.begin_write().expect("begin write");
+            write_txn
+                .delete_table(REVERSE_EDGES)
+                .expect("drop reverse table");
+            write_txn
+                .open_table(METADATA)
+                .expect("metadata")
+                .remove(REVERSE_INDEX_BUILT)
+                .expect("remove marker");
+            write_txn.commit().expect("commit");
         }
+
+        let graph = RedbGraph::open(&db_path).expect("reopen db");
+        let n1 = graph.get_node_by_entity(EntityId(1)).unwrap();
+        let n2 = graph.get_node_by_entity(EntityId(2)).unwrap();
+        assert_eq!(
+            graph.predecessors(n2, None).expect("predecessors"),
+            vec![(n1, EdgeWeight::new(3)), (n1, EdgeWeight::new(4))]
+        );
     }
 
     #[test]
-    fn edge_operations() {
+    fn property_index_is_rebuilt_for_older_databases() {
         let temp = tempdir().expect("temp dir");
         let db_path = temp.path().join("test.redb");
-        let mut graph = RedbGraph::open(&db_path).expect("open db");
-
-        let node1 = graph.insert_node(EntityId(1)).expect("insert node");
-        let node2 = graph.insert_node(EntityId(2)).expect("insert node");
+        let role = Attribute::new("role");
+        let engineer = Value::new("engineer");
 
-        graph
-            .insert_edge(node1, node2, EdgeWeight::new(3))
-            .expect("insert edge");
+        {
+            let mut graph = RedbGraph::open(&db_path).expect("open db");
+            for entity in [1, 2] {
+                let node = graph.insert_node(EntityId(entity)).expect("insert");
+                graph
+                    .store_property(node, role.clone(), engineer.clone())
+                    .expect("store");
+            }
 
-        let weight = graph.get_edge(node1, node2).expect("get edge");
-        assert_eq!(weight, Some(EdgeWeight::new(3)));
+            // Simulate a database written before the property index existed.
+            let write_txn = graph.db.begin_write().expect("begin write");
+            write_txn
+                .delete_table(PROPERTY_INDEX)
+                .expect("drop index table");
+            write_txn
+                .open_table(METADATA)
+                .expect("metadata")
+                .remove(PROPERTY_INDEX_BUILT)
+                .expect("remove marker");
+            write_txn.commit().expect("commit");
+        }
 
-        graph.increment_edge(node1, node2).expect("increment edge");
-        let weight = graph.get_edge(node1, node2).expect("get edge");
-        assert_eq!(weight, Some(EdgeWeight::new(4)));
+        let graph = RedbGraph::open(&db_path).expect("reopen db");
+        assert_eq!(
+            graph
+                .find_by_property(&role, &engineer, None, 10)
+                .expect("find"),
+            vec![NodeId(0), NodeId(1)]
+        );
     }
 
     #[test]
@@ -937,13 +3489,13 @@ mod tests {
         let node3 = graph.insert_node(EntityId(3)).expect("insert node");
 
         graph
-            .insert_edge(node1, node2, EdgeWeight::new(5))
+            .insert_edge(node1, node2, None, EdgeWeight::new(5))
             .expect("insert edge");
         graph
-            .insert_edge(node1, node3, EdgeWeight::new(3))
+            .insert_edge(node1, node3, None, EdgeWeight::new(3))
             .expect("insert edge");
 
-        let neighbors = graph.neighbors(node1).expect("neighbors");
+        let neighbors = graph.neighbors(node1, None).expect("neighbors");
         assert_eq!(neighbors.len(), 2);
     }
 
@@ -971,13 +3523,13 @@ mod tests {
         let node3 = graph.insert_node(EntityId(3)).expect("insert node");
 
         graph
-            .insert_edge(node1, node2, EdgeWeight::new(5))
+            .insert_edge(node1, node2, None, EdgeWeight::new(5))
             .expect("edge");
         graph
-            .insert_edge(node2, node3, EdgeWeight::new(3))
+            .insert_edge(node2, node3, None, EdgeWeight::new(3))
             .expect("edge");
 
-        let artifact = graph.traverse(node1, 2).expect("traverse");
+        let artifact = graph.traverse(node1, 2, None).expect("traverse");
         assert!(artifact.is_some());
 
         let path = &artifact.as_ref().expect("artifact").path;
@@ -996,10 +3548,14 @@ mod tests {
         let b = graph.insert_node(EntityId(2)).expect("insert node");
         let c = graph.insert_node(EntityId(3)).expect("insert node");
 
-        graph.insert_edge(a, b, EdgeWeight::new(10)).expect("edge");
-        graph.insert_edge(b, c, EdgeWeight::new(10)).expect("edge");
+        graph
+            .insert_edge(a, b, None, EdgeWeight::new(10))
+            .expect("edge");
+        graph
+            .insert_edge(b, c, None, EdgeWeight::new(10))
+            .expect("edge");
 
-        let path = graph.strongest_path(a, c).expect("path");
+        let path = graph.strongest_path(a, c, None).expect("path");
         assert_eq!(path, Some(vec![a, b, c]));
     }
 
@@ -1014,15 +3570,17 @@ mod tests {
         let n4 = graph.insert_node(EntityId(4)).expect("insert node");
         let n5 = graph.insert_node(EntityId(5)).expect("insert node");
 
-        graph.insert_edge(n3, n4, EdgeWeight::new(1)).expect("edge");
         graph
-            .insert_edge(n3, n5, EdgeWeight::new(50))
+            .insert_edge(n3, n4, None, EdgeWeight::new(1))
+            .expect("edge");
+        graph
+            .insert_edge(n3, n5, None, EdgeWeight::new(50))
             .expect("edge");
         graph
-            .insert_edge(n5, n4, EdgeWeight::new(50))
+            .insert_edge(n5, n4, None, EdgeWeight::new(50))
             .expect("edge");
 
-        let path = graph.strongest_path(n3, n4).expect("path");
+        let path = graph.strongest_path(n3, n4, None).expect("path");
         assert_eq!(path, Some(vec![n3, n5, n4]));
     }
 
@@ -1038,14 +3596,20 @@ mod tests {
         let n4 = graph.insert_node(EntityId(4)).expect("insert node");
         let n5 = graph.insert_node(EntityId(5)).expect("insert node");
 
-        graph.insert_edge(n2, n3, EdgeWeight::new(2)).expect("edge");
-        graph.insert_edge(n2, n4, EdgeWeight::new(1)).expect("edge");
-        graph.insert_edge(n3, n5, EdgeWeight::new(1)).expect("edge");
         graph
-            .insert_edge(n4, n5, EdgeWeight::new(10))
+            .insert_edge(n2, n3, None, EdgeWeight::new(2))
+            .expect("edge");
+        graph
+            .insert_edge(n2, n4, None, EdgeWeight::new(1))
+            .expect("edge");
+        graph
+            .insert_edge(n3, n5, None, EdgeWeight::new(1))
+            .expect("edge");
+        graph
+            .insert_edge(n4, n5, None, EdgeWeight::new(10))
             .expect("edge");
 
-        let path = graph.strongest_path(n2, n5).expect("path");
+        let path = graph.strongest_path(n2, n5, None).expect("path");
         assert_eq!(path, Some(vec![n2, n4, n5]));
     }
 
@@ -1061,12 +3625,16 @@ mod tests {
         }
         for (i, &a) in nodes.iter().enumerate() {
             for &b in &nodes[i + 1..] {
-                graph.insert_edge(a, b, EdgeWeight::new(1)).expect("edge");
+                graph
+                    .insert_edge(a, b, None, EdgeWeight::new(1))
+                    .expect("edge");
             }
         }
         let start = *nodes.first().expect("non-empty");
         let end = *nodes.last().expect("non-empty");
-        let path = graph.strongest_path(start, end).expect("should not error");
+        let path = graph
+            .strongest_path(start, end, None)
+            .expect("should not error");
         let p = path.expect("should find a path");
         assert_eq!(*p.first().expect("non-empty"), start);
         assert_eq!(*p.last().expect("non-empty"), end);
@@ -1083,10 +3651,10 @@ mod tests {
         let common = graph.insert_node(EntityId(100)).expect("insert node");
 
         graph
-            .insert_edge(a, common, EdgeWeight::new(1))
+            .insert_edge(a, common, None, EdgeWeight::new(1))
             .expect("edge");
         graph
-            .insert_edge(b, common, EdgeWeight::new(1))
+            .insert_edge(b, common, None, EdgeWeight::new(1))
             .expect("edge");
 
         let result = graph.intersect(&[a, b]).expect("intersect");
@@ -1103,8 +3671,12 @@ mod tests {
         let b = graph.insert_node(EntityId(2)).expect("insert node");
         let c = graph.insert_node(EntityId(3)).expect("insert node");
 
-        graph.insert_edge(a, b, EdgeWeight::new(5)).expect("edge");
-        graph.insert_edge(b, c, EdgeWeight::new(15)).expect("edge");
+        graph
+            .insert_edge(a, b, None, EdgeWeight::new(5))
+            .expect("edge");
+        graph
+            .insert_edge(b, c, None, EdgeWeight::new(15))
+            .expect("edge");
 
         // Threshold 10: only edge b->c qualifies
         let stable = graph.stable_edge_count(10).expect("stable count");
@@ -1127,13 +3699,13 @@ mod tests {
         let n3 = graph.insert_node(EntityId(3)).expect("insert node 3");
 
         graph
-            .insert_edge(n1, n2, EdgeWeight::new(10))
+            .insert_edge(n1, n2, None, EdgeWeight::new(10))
             .expect("edge 1");
         graph
-            .insert_edge(n2, n3, EdgeWeight::new(20))
+            .insert_edge(n2, n3, None, EdgeWeight::new(20))
             .expect("edge 2");
         graph
-            .insert_edge(n1, n3, EdgeWeight::new(30))
+            .insert_edge(n1, n3, None, EdgeWeight::new(30))
             .expect("edge 3");
 
         // Verify all operations persisted
@@ -1142,15 +3714,24 @@ mod tests {
 
         // Verify edge weights
         assert_eq!(
-            graph.get_edge(n1, n2).expect("get").map(|e| e.value()),
+            graph
+                .get_edge(n1, n2, None)
+                .expect("get")
+                .map(|e| e.value()),
             Some(10)
         );
         assert_eq!(
-            graph.get_edge(n2, n3).expect("get").map(|e| e.value()),
+            graph
+                .get_edge(n2, n3, None)
+                .expect("get")
+                .map(|e| e.value()),
             Some(20)
         );
         assert_eq!(
-            graph.get_edge(n1, n3).expect("get").map(|e| e.value()),
+            graph
+                .get_edge(n1, n3, None)
+                .expect("get")
+                .map(|e| e.value()),
             Some(30)
         );
     }
@@ -1165,18 +3746,26 @@ mod tests {
         let n2 = graph.insert_node(EntityId(2)).expect("insert node");
 
         // Insert edge with initial weight
-        graph.insert_edge(n1, n2, EdgeWeight::new(5)).expect("edge");
+        graph
+            .insert_edge(n1, n2, None, EdgeWeight::new(5))
+            .expect("edge");
         assert_eq!(
-            graph.get_edge(n1, n2).expect("get").map(|e| e.value()),
+            graph
+                .get_edge(n1, n2, None)
+                .expect("get")
+                .map(|e| e.value()),
             Some(5)
         );
 
         // Overwrite with new weight
         graph
-            .insert_edge(n1, n2, EdgeWeight::new(100))
+            .insert_edge(n1, n2, None, EdgeWeight::new(100))
             .expect("edge update");
         assert_eq!(
-            graph.get_edge(n1, n2).expect("get").map(|e| e.value()),
+            graph
+                .get_edge(n1, n2, None)
+                .expect("get")
+                .map(|e| e.value()),
             Some(100)
         );
 
@@ -1194,16 +3783,22 @@ mod tests {
         let n2 = graph.insert_node(EntityId(2)).expect("insert node");
 
         // Increment edge that doesn't exist (should create with weight 1)
-        graph.increment_edge(n1, n2).expect("increment");
+        graph.increment_edge(n1, n2, None).expect("increment");
         assert_eq!(
-            graph.get_edge(n1, n2).expect("get").map(|e| e.value()),
+            graph
+                .get_edge(n1, n2, None)
+                .expect("get")
+                .map(|e| e.value()),
             Some(1)
         );
 
         // Increment again
-        graph.increment_edge(n1, n2).expect("increment 2");
+        graph.increment_edge(n1, n2, None).expect("increment 2");
         assert_eq!(
-            graph.get_edge(n1, n2).expect("get").map(|e| e.value()),
+            graph
+                .get_edge(n1, n2, None)
+                .expect("get")
+                .map(|e| e.value()),
             Some(2)
         );
     }
@@ -1222,10 +3817,10 @@ mod tests {
         let n2 = graph.insert_node(large_id2).expect("insert node");
 
         graph
-            .insert_edge(n1, n2, EdgeWeight::new(i64::MAX))
+            .insert_edge(n1, n2, None, EdgeWeight::new(i64::MAX))
             .expect("edge");
 
-        let weight = graph.get_edge(n1, n2).expect("get");
+        let weight = graph.get_edge(n1, n2, None).expect("get");
         assert_eq!(weight.map(|w| w.value()), Some(i64::MAX));
     }
 
@@ -1240,10 +3835,10 @@ mod tests {
 
         // Negative weights should be handled correctly
         graph
-            .insert_edge(n1, n2, EdgeWeight::new(-100))
+            .insert_edge(n1, n2, None, EdgeWeight::new(-100))
             .expect("edge");
 
-        let weight = graph.get_edge(n1, n2).expect("get");
+        let weight = graph.get_edge(n1, n2, None).expect("get");
         assert_eq!(weight.map(|w| w.value()), Some(-100));
     }
 
@@ -1261,14 +3856,14 @@ mod tests {
         let n1 = graph.insert_node(EntityId(1)).expect("insert node");
         let n2 = graph.insert_node(EntityId(2)).expect("insert node");
         graph
-            .insert_edge(n1, n2, EdgeWeight::new(42))
+            .insert_edge(n1, n2, None, EdgeWeight::new(42))
             .expect("edge");
 
         // Multiple reads should work
         for _ in 0..10 {
             assert_eq!(graph.node_count().expect("count"), 2);
             assert_eq!(graph.edge_count().expect("count"), 1);
-            let _ = graph.neighbors(n1).expect("neighbors");
+            let _ = graph.neighbors(n1, None).expect("neighbors");
             let _ = graph.lookup(n1).expect("lookup");
         }
     }
@@ -1289,7 +3884,7 @@ mod tests {
         // Add edges between consecutive nodes
         for i in 0..99 {
             graph
-                .insert_edge(NodeId(i), NodeId(i + 1), EdgeWeight::new(i as i64))
+                .insert_edge(NodeId(i), NodeId(i + 1), None, EdgeWeight::new(i as i64))
                 .expect("edge");
         }
 
@@ -1333,10 +3928,10 @@ mod tests {
             let n3 = graph.insert_node(EntityId(300)).expect("insert node");
 
             graph
-                .insert_edge(n1, n2, EdgeWeight::new(10))
+                .insert_edge(n1, n2, None, EdgeWeight::new(10))
                 .expect("edge");
             graph
-                .insert_edge(n2, n3, EdgeWeight::new(20))
+                .insert_edge(n2, n3, None, EdgeWeight::new(20))
                 .expect("edge");
         }
         // Graph dropped here, simulating process exit
@@ -1359,11 +3954,17 @@ mod tests {
             let n3 = graph.get_node_by_entity(EntityId(300)).unwrap();
 
             assert_eq!(
-                graph.get_edge(n1, n2).expect("get").map(|e| e.value()),
+                graph
+                    .get_edge(n1, n2, None)
+                    .expect("get")
+                    .map(|e| e.value()),
                 Some(10)
             );
             assert_eq!(
-                graph.get_edge(n2, n3).expect("get").map(|e| e.value()),
+                graph
+                    .get_edge(n2, n3, None)
+                    .expect("get")
+                    .map(|e| e.value()),
                 Some(20)
             );
         }
@@ -1442,9 +4043,11 @@ mod tests {
             let n1 = graph.insert_node(EntityId(1)).expect("insert");
             let n2 = graph.insert_node(EntityId(2)).expect("insert");
 
-            graph.insert_edge(n1, n2, EdgeWeight::new(0)).expect("edge");
+            graph
+                .insert_edge(n1, n2, None, EdgeWeight::new(0))
+                .expect("edge");
             for _ in 0..10 {
-                graph.increment_edge(n1, n2).expect("increment");
+                graph.increment_edge(n1, n2, None).expect("increment");
             }
         }
 
@@ -1454,7 +4057,10 @@ mod tests {
             let n1 = graph.get_node_by_entity(EntityId(1)).unwrap();
             let n2 = graph.get_node_by_entity(EntityId(2)).unwrap();
 
-            let weight = graph.get_edge(n1, n2).expect("get").map(|w| w.value());
+            let weight = graph
+                .get_edge(n1, n2, None)
+                .expect("get")
+                .map(|w| w.value());
             assert_eq!(weight, Some(10));
         }
     }
@@ -1495,7 +4101,7 @@ mod tests {
         let graph = RedbGraph::open(&db_path).expect("open db");
 
         // Traverse from non-existent node should return None
-        let result = graph.traverse(NodeId(999), 5).expect("traverse");
+        let result = graph.traverse(NodeId(999), 5, None).expect("traverse");
         assert!(result.is_none());
     }
 
@@ -1511,14 +4117,16 @@ mod tests {
 
         // High weight path: n1 -> n2
         graph
-            .insert_edge(n1, n2, EdgeWeight::new(100))
+            .insert_edge(n1, n2, None, EdgeWeight::new(100))
             .expect("edge");
         // Low weight path: n1 -> n3
-        graph.insert_edge(n1, n3, EdgeWeight::new(1)).expect("edge");
+        graph
+            .insert_edge(n1, n3, None, EdgeWeight::new(1))
+            .expect("edge");
 
         // Filter with min weight 50 should only include n1 -> n2
         let artifact = graph
-            .traverse_filtered(n1, 2, EdgeWeight::new(50))
+            .traverse_filtered(n1, 2, EdgeWeight::new(50), None)
             .expect("traverse");
 
         assert!(artifact.is_some());
@@ -1540,7 +4148,7 @@ mod tests {
         let n2 = graph.insert_node(EntityId(2)).expect("insert");
 
         // No edge between n1 and n2
-        let path = graph.strongest_path(n1, n2).expect("path");
+        let path = graph.strongest_path(n1, n2, None).expect("path");
         assert!(path.is_none());
     }
 
@@ -1553,10 +4161,129 @@ mod tests {
         let n1 = graph.insert_node(EntityId(1)).expect("insert");
 
         // Path from node to itself
-        let path = graph.strongest_path(n1, n1).expect("path");
+        let path = graph.strongest_path(n1, n1, None).expect("path");
         assert_eq!(path, Some(vec![n1]));
     }
 
+    #[test]
+    fn reachable_reports_path_or_closure() {
+        let temp = tempdir().expect("temp dir");
+        let db_path = temp.path().join("test.redb");
+        let mut graph = RedbGraph::open(&db_path).expect("open db");
+
+        let n1 = graph.insert_node(EntityId(1)).expect("insert");
+        let n2 = graph.insert_node(EntityId(2)).expect("insert");
+        let n3 = graph.insert_node(EntityId(3)).expect("insert");
+        graph.increment_edge(n1, n2, None).expect("inc");
+        graph.increment_edge(n3, n1, None).expect("inc");
+
+        assert_eq!(
+            graph.reachable(n3, n2, None).expect("reachable"),
+            Some(crate::graph::Reachability::Reachable(vec![n3, n1, n2]))
+        );
+        assert_eq!(
+            graph.reachable(n1, n3, None).expect("reachable"),
+            Some(crate::graph::Reachability::Unreachable(
+                crate::Artifact::with_subgraph(
+                    vec![n1, n2],
+                    vec![(n1, n2, None, EdgeWeight::new(1))]
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn k_strongest_paths_rank_alternatives() {
+        let temp = tempdir().expect("temp dir");
+        let db_path = temp.path().join("test.redb");
+        let mut graph = RedbGraph::open(&db_path).expect("open db");
+
+        let n1 = graph.insert_node(EntityId(1)).expect("insert");
+        let n2 = graph.insert_node(EntityId(2)).expect("insert");
+        let n3 = graph.insert_node(EntityId(3)).expect("insert");
+        graph
+            .insert_edge(n1, n2, None, EdgeWeight::new(4))
+            .expect("edge");
+        graph
+            .insert_edge(n2, n3, None, EdgeWeight::new(4))
+            .expect("edge");
+        graph
+            .insert_edge(n1, n3, None, EdgeWeight::new(2))
+            .expect("edge");
+
+        let paths = graph
+            .k_strongest_paths(n1, n3, 5, None)
+            .expect("paths")
+            .expect("nodes exist");
+        let nodes: Vec<Vec<NodeId>> = paths.into_iter().map(|path| path.nodes).collect();
+        assert_eq!(nodes, vec![vec![n1, n2, n3], vec![n1, n3]]);
+    }
+
+    #[test]
+    fn strong_components_match_in_memory_graph() {
+        let temp = tempdir().expect("temp dir");
+        let db_path = temp.path().join("test.redb");
+        let mut graph = RedbGraph::open(&db_path).expect("open db");
+        let mut memory = Graph::new();
+
+        for entity in 1..=4 {
+            graph.insert_node(EntityId(entity)).expect("insert");
+            memory.insert_node(EntityId(entity)).expect("insert");
+        }
+        for (from, to) in [(0, 1), (1, 0), (1, 2), (3, 2)] {
+            graph
+                .insert_edge(NodeId(from), NodeId(to), None, EdgeWeight::new(1))
+                .expect("edge");
+            memory
+                .insert_edge(NodeId(from), NodeId(to), None, EdgeWeight::new(1))
+                .expect("edge");
+        }
+
+        assert_eq!(
+            graph.node_ids().expect("ids"),
+            vec![NodeId(0), NodeId(1), NodeId(2), NodeId(3)]
+        );
+        let stored = crate::components::strongly_connected_components(&graph, None).expect("scc");
+        let expected =
+            crate::components::strongly_connected_components(&memory, None).expect("scc");
+        assert_eq!(stored, expected);
+        assert_eq!(stored.cycles, vec![vec![NodeId(0), NodeId(1)]]);
+    }
+
+    #[test]
+    fn budgeted_traversal_stops_like_the_in_memory_graph() {
+        let temp = tempdir().expect("temp dir");
+        let db_path = temp.path().join("test.redb");
+        let mut redb = RedbGraph::open(&db_path).expect("open db");
+        let mut memory = Graph::new();
+
+        fn chain(store: &mut impl GraphStore) {
+            let nodes: Vec<NodeId> = (1..=5)
+                .map(|e| store.insert_node(EntityId(e)).expect("insert node"))
+                .collect();
+            for pair in nodes.windows(2) {
+                store
+                    .insert_edge(pair[0], pair[1], None, EdgeWeight::new(2))
+                    .expect("edge");
+            }
+        }
+        chain(&mut redb);
+        chain(&mut memory);
+
+        for visits in 0..10 {
+            let mut redb_budget = crate::graph::VisitBudget::new(visits);
+            let mut memory_budget = crate::graph::VisitBudget::new(visits);
+            assert_eq!(
+                redb.traverse_within(NodeId(0), 10, None, &mut redb_budget)
+                    .expect("traverse"),
+                memory
+                    .traverse_within(NodeId(0), 10, None, &mut memory_budget)
+                    .expect("traverse")
+            );
+            assert_eq!(redb_budget, memory_budget);
+        }
+    }
+
     #[test]
     fn intersect_empty_input() {
         let temp = tempdir().expect("temp dir");
@@ -1579,8 +4306,12 @@ mod tests {
         let n4 = graph.insert_node(EntityId(4)).expect("insert");
 
         // n1 -> n3, n2 -> n4 (no common neighbors)
-        graph.insert_edge(n1, n3, EdgeWeight::new(1)).expect("edge");
-        graph.insert_edge(n2, n4, EdgeWeight::new(1)).expect("edge");
+        graph
+            .insert_edge(n1, n3, None, EdgeWeight::new(1))
+            .expect("edge");
+        graph
+            .insert_edge(n2, n4, None, EdgeWeight::new(1))
+            .expect("edge");
 
         let result = graph.intersect(&[n1, n2]).expect("intersect");
         assert!(result.is_empty());
@@ -1677,9 +4408,207 @@ mod tests {
             .expect("idempotent re-insert at cap");
 
         assert_eq!(
-            graph.get_properties(node).expect("get").len(),
-            MAX_PROPERTIES_PER_NODE
+            graph.get_properties(node).expect("get").len(),
+            MAX_PROPERTIES_PER_NODE
+        );
+    }
+
+    #[test]
+    fn remove_property_frees_a_slot_and_persists() {
+        use crate::primitives::MAX_PROPERTIES_PER_NODE;
+
+        let temp = tempdir().expect("temp dir");
+        let db_path = temp.path().join("test.redb");
+
+        {
+            let mut graph = RedbGraph::open(&db_path).expect("open db");
+            let fill: Vec<Signal> = (0..MAX_PROPERTIES_PER_NODE)
+                .map(|i| {
+                    Signal::new(
+                        EntityId(1),
+                        Attribute::new(format!("p{i}")),
+                        Value::new("v"),
+                    )
+                })
+                .collect();
+            let node = graph.ingest_batch(&fill).expect("batch fill")[0];
+
+            let p0 = Attribute::new("p0");
+            assert!(
+                !graph
+                    .remove_property(node, &p0, &Value::new("other"))
+                    .expect("remove")
+            );
+            assert!(
+                graph
+                    .remove_property(node, &p0, &Value::new("v"))
+                    .expect("remove")
+            );
+
+            // The retracted pair gave back one slot under the cap.
+            graph
+                .store_property(node, Attribute::new("replacement"), Value::new("v"))
+                .expect("store into freed slot");
+            assert!(matches!(
+                graph.store_property(node, Attribute::new("overflow"), Value::new("v")),
+                Err(KremisError::PropertyLimitExceeded(_, _))
+            ));
+        }
+
+        let graph = RedbGraph::open(&db_path).expect("reopen db");
+        let node = graph.get_node_by_entity(EntityId(1)).unwrap();
+        let props = graph.get_properties(node).expect("get");
+        assert_eq!(props.len(), MAX_PROPERTIES_PER_NODE);
+        assert!(!props.iter().any(|(attr, _)| attr.as_str() == "p0"));
+        assert!(props.iter().any(|(attr, _)| attr.as_str() == "replacement"));
+    }
+
+    #[test]
+    fn find_by_property_follows_writes_and_persists() {
+        let temp = tempdir().expect("temp dir");
+        let db_path = temp.path().join("test.redb");
+        let role = Attribute::new("role");
+        let engineer = Value::new("engineer");
+
+        {
+            let mut graph = RedbGraph::open(&db_path).expect("open db");
+            graph
+                .ingest_batch(&[
+                    make_signal(1, "role", "engineer"),
+                    make_signal(2, "role", "engineer"),
+                    make_signal(3, "role", "manager"),
+                ])
+                .expect("batch");
+            let n4 = graph.insert_node(EntityId(4)).expect("insert");
+            graph
+                .store_property(n4, role.clone(), engineer.clone())
+                .expect("store");
+
+            let first = graph
+                .find_by_property(&role, &engineer, None, 2)
+                .expect("find");
+            assert_eq!(first, vec![NodeId(0), NodeId(1)]);
+            let rest = graph
+                .find_by_property(&role, &engineer, first.last().copied(), 10)
+                .expect("find");
+            assert_eq!(rest, vec![n4]);
+
+            graph
+                .remove_property(NodeId(0), &role, &engineer)
+                .expect("remove");
+            graph.remove_node(NodeId(1)).expect("remove");
+        }
+
+        let graph = RedbGraph::open(&db_path).expect("reopen db");
+        assert_eq!(
+            graph
+                .find_by_property(&role, &engineer, None, 10)
+                .expect("find"),
+            vec![NodeId(3)]
+        );
+        assert_eq!(
+            graph
+                .find_by_property(&role, &Value::new("manager"), None, 10)
+                .expect("find"),
+            vec![NodeId(2)]
+        );
+        assert!(
+            graph
+                .find_by_property(&role, &engineer, Some(NodeId(u64::MAX)), 10)
+                .expect("find")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn search_postings_follow_writes_and_rebuild() {
+        let temp = tempdir().expect("temp dir");
+        let db_path = temp.path().join("test.redb");
+        let name = Attribute::new("name");
+
+        {
+            let mut graph = RedbGraph::open(&db_path).expect("open db");
+            graph
+                .ingest_batch(&[
+                    make_signal(1, "name", "Payment Ledger"),
+                    make_signal(2, "name", "ledgers archive"),
+                    make_signal(3, "name", "Ledger API"),
+                ])
+                .expect("batch");
+            assert_eq!(
+                graph.search("ledger api", 10).expect("search"),
+                vec![(NodeId(2), 4), (NodeId(0), 2), (NodeId(1), 1)]
+            );
+
+            graph
+                .remove_property(NodeId(0), &name, &Value::new("Payment Ledger"))
+                .expect("remove");
+            graph.remove_node(NodeId(2)).expect("remove");
+            let n3 = graph.insert_node(EntityId(4)).expect("insert");
+            graph
+                .store_property(n3, name.clone(), Value::new("LEDGER"))
+                .expect("store");
+
+            // Simulate a database written before the token index existed.
+            let write_txn = graph.db.begin_write().expect("begin write");
+            write_txn
+                .delete_table(TOKEN_INDEX)
+                .expect("drop token table");
+            write_txn
+                .open_table(METADATA)
+                .expect("metadata")
+                .remove(TOKEN_INDEX_BUILT)
+                .expect("remove marker");
+            write_txn.commit().expect("commit");
+        }
+
+        let graph = RedbGraph::open(&db_path).expect("reopen db");
+        assert_eq!(
+            graph.search("ledger", 10).expect("search"),
+            vec![(NodeId(3), 2), (NodeId(1), 1)]
+        );
+        assert!(graph.search("payment", 10).expect("search").is_empty());
+    }
+
+    #[test]
+    fn entity_keys_persist_and_match_in_memory_allocation() {
+        let temp = tempdir().expect("temp dir");
+        let db_path = temp.path().join("test.redb");
+        let mut memory = crate::graph::Graph::new();
+        let expected = memory.register_entity_key("svc:ledger").expect("register");
+
+        {
+            let mut graph = RedbGraph::open(&db_path).expect("open db");
+            let entity = graph.register_entity_key("svc:ledger").expect("register");
+            assert_eq!(entity, expected);
+            let node = graph.insert_node(entity).expect("insert");
+            graph.remove_node(node).expect("remove");
+        }
+
+        let mut graph = RedbGraph::open(&db_path).expect("reopen db");
+        assert_eq!(
+            graph.entity_for_key("svc:ledger").expect("resolve"),
+            Some(expected)
+        );
+        assert_eq!(
+            graph.key_for_entity(expected).expect("reverse"),
+            Some("svc:ledger".to_string())
+        );
+        assert_eq!(
+            graph.register_entity_key("svc:ledger").expect("again"),
+            expected
+        );
+        assert_eq!(
+            graph.entity_keys().expect("keys"),
+            vec![("svc:ledger".to_string(), expected)]
         );
+
+        // Only a bound id in the keyed range can be ingested.
+        let signal = |entity| Signal::new(entity, Attribute::new("name"), Value::new("x"));
+        let unbound = EntityId(expected.0 ^ 1);
+        assert!(graph.ingest_batch(&[signal(unbound)]).is_err());
+        assert!(graph.ingest_batch(&[signal(expected)]).is_ok());
+        assert_eq!(graph.get_node_by_entity(unbound), None);
     }
 
     #[test]
@@ -1744,19 +4673,19 @@ mod tests {
 
         // Edge from existing to non-existing: silently ignored
         graph
-            .insert_edge(node1, dangling, EdgeWeight::new(5))
+            .insert_edge(node1, dangling, None, EdgeWeight::new(5))
             .expect("insert edge");
         assert_eq!(graph.edge_count().expect("count"), 0);
 
         // Edge from non-existing to existing: silently ignored
         graph
-            .insert_edge(dangling, node1, EdgeWeight::new(5))
+            .insert_edge(dangling, node1, None, EdgeWeight::new(5))
             .expect("insert edge");
         assert_eq!(graph.edge_count().expect("count"), 0);
 
         // Edge between two non-existing nodes: silently ignored
         graph
-            .insert_edge(NodeId(888), dangling, EdgeWeight::new(5))
+            .insert_edge(NodeId(888), dangling, None, EdgeWeight::new(5))
             .expect("insert edge");
         assert_eq!(graph.edge_count().expect("count"), 0);
     }
@@ -1772,19 +4701,19 @@ mod tests {
 
         // Increment from existing to non-existing: silently ignored, no phantom edge
         graph
-            .increment_edge(node1, dangling)
+            .increment_edge(node1, dangling, None)
             .expect("increment edge");
         assert_eq!(graph.edge_count().expect("count"), 0);
 
         // Increment from non-existing to existing: silently ignored, no phantom edge
         graph
-            .increment_edge(dangling, node1)
+            .increment_edge(dangling, node1, None)
             .expect("increment edge");
         assert_eq!(graph.edge_count().expect("count"), 0);
 
         // Increment between two non-existing nodes: silently ignored
         graph
-            .increment_edge(NodeId(998), dangling)
+            .increment_edge(NodeId(998), dangling, None)
             .expect("increment edge");
         assert_eq!(graph.edge_count().expect("count"), 0);
     }
@@ -1836,18 +4765,71 @@ mod tests {
         let nodes = graph.ingest_batch(&signals).expect("ingest batch");
 
         // Edge from node 0 to node 1
-        let w01 = graph.get_edge(nodes[0], nodes[1]).expect("get edge");
+        let w01 = graph.get_edge(nodes[0], nodes[1], None).expect("get edge");
         assert_eq!(w01, Some(EdgeWeight::new(1)));
 
         // Edge from node 1 to node 2
-        let w12 = graph.get_edge(nodes[1], nodes[2]).expect("get edge");
+        let w12 = graph.get_edge(nodes[1], nodes[2], None).expect("get edge");
         assert_eq!(w12, Some(EdgeWeight::new(1)));
 
         // No edge from node 0 to node 2 (ASSOCIATION_WINDOW = 1)
-        let w02 = graph.get_edge(nodes[0], nodes[2]).expect("get edge");
+        let w02 = graph.get_edge(nodes[0], nodes[2], None).expect("get edge");
         assert!(w02.is_none());
     }
 
+    #[test]
+    fn ingest_batch_with_matches_the_ingestor() {
+        use crate::ingestor::Ingestor;
+
+        let signals: Vec<Signal> = (1..=4)
+            .map(|i| Signal::new(EntityId(i), Attribute::new("n"), Value::new("x")))
+            .collect();
+        for links in [
+            LinkMode::Window(3, None),
+            LinkMode::Window(2, Some(Attribute::new("next"))),
+            LinkMode::Explicit(vec![
+                (EntityId(4), EntityId(1), None),
+                (EntityId(4), EntityId(1), None),
+                (EntityId(4), EntityId(1), Some(Attribute::new("owns"))),
+            ]),
+        ] {
+            let temp = tempdir().expect("temp dir");
+            let mut redb = RedbGraph::open(temp.path().join("test.redb")).expect("open db");
+            let mut graph = Graph::new();
+
+            redb.ingest_batch_with(&signals, &links).expect("batch");
+            Ingestor::ingest_sequence_with(&mut graph, &signals, &links).expect("ingest");
+
+            assert_eq!(
+                redb.edges_with_relations().expect("edges"),
+                graph
+                    .edges_with_relations()
+                    .map(|(from, to, relation, weight)| (from, to, relation.cloned(), weight))
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(redb.journal().expect("journal").len(), 1);
+        }
+    }
+
+    #[test]
+    fn ingest_batch_with_rejects_unknown_link_before_writing() {
+        let temp = tempdir().expect("temp dir");
+        let mut graph = RedbGraph::open(temp.path().join("test.redb")).expect("open db");
+        let signals = [Signal::new(
+            EntityId(1),
+            Attribute::new("n"),
+            Value::new("x"),
+        )];
+        let links = LinkMode::Explicit(vec![(EntityId(1), EntityId(2), None)]);
+
+        assert!(matches!(
+            graph.ingest_batch_with(&signals, &links),
+            Err(KremisError::InvalidSignal)
+        ));
+        assert_eq!(graph.node_count().expect("count"), 0);
+        assert_eq!(graph.mutation_seq().expect("seq"), 0);
+    }
+
     #[test]
     fn ingest_batch_deduplication() {
         let temp = tempdir().expect("temp dir");
@@ -2040,4 +5022,624 @@ mod tests {
             "expected StorageCorruption, got: {result:?}"
         );
     }
+
+    #[test]
+    fn mutation_seq_advances_only_on_changes() {
+        let temp = tempdir().expect("temp dir");
+        let db_path = temp.path().join("test.redb");
+        let mut graph = RedbGraph::open(&db_path).expect("open db");
+        assert_eq!(graph.mutation_seq().expect("seq"), 0);
+
+        let node = graph.insert_node(EntityId(1)).expect("insert");
+        assert_eq!(graph.mutation_seq().expect("seq"), 1);
+
+        // Re-inserting a known entity and retracting an absent pair are no-ops.
+        graph.insert_node(EntityId(1)).expect("insert");
+        assert!(
+            !graph
+                .remove_property(node, &Attribute::new("a"), &Value::new("b"))
+                .expect("remove")
+        );
+        assert_eq!(graph.mutation_seq().expect("seq"), 1);
+
+        graph
+            .store_property(node, Attribute::new("a"), Value::new("b"))
+            .expect("store");
+        assert_eq!(graph.mutation_seq().expect("seq"), 2);
+    }
+
+    #[test]
+    fn snapshots_read_from_checkpoints_match_a_full_replay() {
+        let temp = tempdir().expect("temp dir");
+        let db_path = temp.path().join("test.redb");
+        let mut graph = RedbGraph::open(&db_path).expect("open db");
+
+        let mut prev = graph.insert_node(EntityId(0)).expect("insert");
+        for entity in 1..SNAPSHOT_CHECKPOINT_INTERVAL + 10 {
+            let node = graph.insert_node(EntityId(entity)).expect("insert");
+            graph
+                .insert_edge(prev, node, None, EdgeWeight::new(1))
+                .expect("edge");
+            prev = node;
+        }
+        let seq = graph.mutation_seq().expect("seq");
+        assert!(seq > SNAPSHOT_CHECKPOINT_INTERVAL);
+
+        let read_txn = graph.db.begin_read().expect("begin read");
+        let checkpoints = read_txn
+            .open_table(SNAPSHOT_CHECKPOINTS)
+            .expect("open table");
+        let log_table = read_txn.open_table(MUTATION_LOG).expect("open table");
+        assert_eq!(checkpoints.len().expect("len"), 2);
+
+        // Replaying from the start, as before checkpoints, gives the same graphs.
+        for at in [
+            SNAPSHOT_CHECKPOINT_INTERVAL - 1,
+            SNAPSHOT_CHECKPOINT_INTERVAL,
+            SNAPSHOT_CHECKPOINT_INTERVAL + 1,
+            seq,
+        ] {
+            let mut full = Graph::new();
+            for entry in log_table.range(1..=at).expect("range") {
+                let (_, data) = entry.expect("entry");
+                let record: MutationRecord = postcard::from_bytes(data.value()).expect("record");
+                record.apply(&mut full).expect("apply");
+            }
+            let snapshot = graph.snapshot_at(at).expect("snapshot");
+            assert_eq!(
+                CanonicalGraph::from_graph(&snapshot),
+                CanonicalGraph::from_graph(&full),
+                "state after {at}"
+            );
+        }
+        // Past the checkpoint only the later records are replayed.
+        let mut budget = VisitBudget::new(3);
+        assert!(
+            graph
+                .snapshot_at_within(SNAPSHOT_CHECKPOINT_INTERVAL + 1, &mut budget)
+                .expect("snapshot")
+                .is_some()
+        );
+        let mut budget = VisitBudget::new(3);
+        assert!(
+            graph
+                .snapshot_at_within(SNAPSHOT_CHECKPOINT_INTERVAL - 1, &mut budget)
+                .expect("snapshot")
+                .is_none()
+        );
+        assert!(budget.is_exhausted());
+
+        // Every checkpoint is found by the state hash of its snapshot.
+        #[cfg(feature = "crypto-hash")]
+        {
+            let snapshot = graph
+                .snapshot_at(SNAPSHOT_CHECKPOINT_INTERVAL)
+                .expect("snapshot");
+            let hash = crate::certificate::state_hash(&snapshot).expect("hash");
+            assert_eq!(
+                graph.checkpoint_with_hash(&hash).expect("lookup"),
+                Some(SNAPSHOT_CHECKPOINT_INTERVAL)
+            );
+            assert_eq!(graph.checkpoint_with_hash(&[0; 32]).expect("lookup"), None);
+        }
+    }
+
+    #[test]
+    fn import_canonical_streams_an_export_into_an_empty_database() {
+        let mut source = Graph::new();
+        let a = source.insert_node(EntityId(1)).expect("insert");
+        let b = source.insert_node(EntityId(2)).expect("insert");
+        let gone = source.insert_node(EntityId(3)).expect("insert");
+        source
+            .insert_edge(a, b, Some(&Attribute::new("owns")), EdgeWeight::new(4))
+            .expect("edge");
+        source
+            .insert_edge(b, a, None, EdgeWeight::new(2))
+            .expect("edge");
+        source
+            .store_property(a, Attribute::new("name"), Value::new("alice"))
+            .expect("property");
+        source.register_entity_key("svc:alice").expect("key");
+        source.remove_node(gone).expect("remove");
+
+        let mut canonical = CanonicalGraph::from_graph(&source);
+        canonical.edges.push(CanonicalEdge {
+            from: a.0,
+            to: 42,
+            relation: None,
+            weight: 1,
+        });
+        let mut data = Vec::new();
+        stream::write_canonical(&canonical, &mut data).expect("export");
+
+        let temp = tempdir().expect("temp dir");
+        let mut graph = RedbGraph::open(temp.path().join("test.redb")).expect("open db");
+        let diag = graph.import_canonical(data.as_slice()).expect("import");
+        assert_eq!(diag.dangling_edges, 1);
+        assert_eq!(diag.dangling_properties, 0);
+        assert_eq!(
+            CanonicalGraph::from_graph(
+                &graph
+                    .snapshot_at(graph.mutation_seq().expect("seq"))
+                    .expect("replay")
+            ),
+            CanonicalGraph::from_graph(&source)
+        );
+        assert_eq!(graph.next_node_id(), source.next_node_id());
+        assert_eq!(graph.get_node_by_entity(EntityId(2)), Some(b));
+        assert!(!graph.journal_is_complete().expect("journal"));
+
+        // A second import would overwrite the state, so it is refused.
+        assert!(graph.import_canonical(data.as_slice()).is_err());
+
+        // A corrupt export writes nothing.
+        let temp = tempdir().expect("temp dir");
+        let mut graph = RedbGraph::open(temp.path().join("test.redb")).expect("open db");
+        let last = data.len() - 1;
+        data[last] ^= 0xff;
+        assert!(graph.import_canonical(data.as_slice()).is_err());
+        assert!(graph.nodes().expect("nodes").is_empty());
+        assert_eq!(graph.mutation_seq().expect("seq"), 0);
+    }
+
+    #[test]
+    fn import_canonical_checkpoints_only_its_last_record() {
+        let temp = tempdir().expect("temp dir");
+        let mut graph = RedbGraph::open(temp.path().join("test.redb")).expect("open db");
+        // Leave the database empty two records short of a checkpoint.
+        while graph.mutation_seq().expect("seq") < SNAPSHOT_CHECKPOINT_INTERVAL - 2 {
+            let node = graph.insert_node(EntityId(1)).expect("insert");
+            graph.remove_node(node).expect("remove");
+        }
+
+        // Three log records, the second of them on the checkpoint interval.
+        let mut source = Graph::new();
+        let mut prev = source.insert_node(EntityId(0)).expect("insert");
+        for entity in 1..=IMPORT_LOG_CHUNK as u64 {
+            let node = source.insert_node(EntityId(entity)).expect("insert");
+            source
+                .insert_edge(prev, node, None, EdgeWeight::new(1))
+                .expect("edge");
+            prev = node;
+        }
+        let mut data = Vec::new();
+        stream::write_canonical(&source, &mut data).expect("export");
+        graph.import_canonical(data.as_slice()).expect("import");
+        let seq = graph.mutation_seq().expect("seq");
+        assert_eq!(seq, SNAPSHOT_CHECKPOINT_INTERVAL + 1);
+
+        let read_txn = graph.db.begin_read().expect("begin read");
+        let checkpoints = read_txn
+            .open_table(SNAPSHOT_CHECKPOINTS)
+            .expect("open table");
+        let kept: Vec<u64> = checkpoints
+            .iter()
+            .expect("iter")
+            .map(|entry| entry.expect("entry").0.value())
+            .collect();
+        assert_eq!(kept, vec![seq]);
+        assert_eq!(
+            CanonicalGraph::from_graph(&graph.snapshot_at(seq).expect("snapshot")),
+            CanonicalGraph::from_graph(&source)
+        );
+
+        // The entity cache is reloaded from the committed tables.
+        assert_eq!(graph.entities().count(), IMPORT_LOG_CHUNK + 1);
+        assert_eq!(graph.get_node_by_entity(EntityId(0)), Some(NodeId(0)));
+        assert_eq!(
+            graph.get_node_by_entity(EntityId(1)),
+            source.get_node_by_entity(EntityId(1))
+        );
+    }
+
+    #[test]
+    fn checkpoint_policy_bounds_checkpoint_work() {
+        fn kept(graph: &RedbGraph) -> Vec<u64> {
+            let read_txn = graph.db.begin_read().expect("begin read");
+            let checkpoints = read_txn
+                .open_table(SNAPSHOT_CHECKPOINTS)
+                .expect("open table");
+            checkpoints
+                .iter()
+                .expect("iter")
+                .map(|entry| entry.expect("entry").0.value())
+                .collect()
+        }
+
+        let temp = tempdir().expect("temp dir");
+        let mut graph = RedbGraph::open(temp.path().join("test.redb")).expect("open db");
+        assert_eq!(graph.checkpoint_policy(), CheckpointPolicy::default());
+        let policy = CheckpointPolicy {
+            interval: 8,
+            max_kept: 3,
+        };
+        graph.set_checkpoint_policy(policy);
+        for entity in 0..40 {
+            graph.insert_node(EntityId(entity)).expect("insert");
+        }
+        // One checkpoint per interval, thinned to `max_kept`.
+        let at = kept(&graph);
+        assert_eq!(at.len(), policy.max_kept);
+        assert_eq!(at.last(), Some(&40));
+        assert!(at.iter().all(|seq| seq % policy.interval == 0));
+
+        // A checkpoint only reads the records since the previous one.
+        {
+            let write_txn = graph.db.begin_write().expect("begin write");
+            {
+                let mut log = write_txn.open_table(MUTATION_LOG).expect("open table");
+                for seq in 1..=40 {
+                    log.remove(seq).expect("remove");
+                }
+            }
+            write_txn.commit().expect("commit");
+        }
+        for entity in 40..48 {
+            graph.insert_node(EntityId(entity)).expect("insert");
+        }
+        assert_eq!(kept(&graph).last(), Some(&48));
+        assert_eq!(
+            graph
+                .snapshot_at(48)
+                .expect("snapshot")
+                .node_count()
+                .expect("count"),
+            48
+        );
+
+        // Interval 0 takes none.
+        let temp = tempdir().expect("temp dir");
+        let mut graph = RedbGraph::open(temp.path().join("test.redb")).expect("open db");
+        graph.set_checkpoint_policy(CheckpointPolicy {
+            interval: 0,
+            max_kept: 3,
+        });
+        for entity in 0..40 {
+            graph.insert_node(EntityId(entity)).expect("insert");
+        }
+        assert!(kept(&graph).is_empty());
+        assert_eq!(
+            graph
+                .snapshot_at(40)
+                .expect("snapshot")
+                .node_count()
+                .expect("count"),
+            40
+        );
+    }
+
+    #[test]
+    fn checkpoint_thinning_keeps_the_newest_and_spreads_the_rest() {
+        let full: Vec<u64> = (1..=MAX_SNAPSHOT_CHECKPOINTS as u64)
+            .map(|i| i * 10)
+            .collect();
+        assert_eq!(thinned_checkpoint(&full, MAX_SNAPSHOT_CHECKPOINTS), None);
+
+        let mut kept = full.clone();
+        kept.push(kept[kept.len() - 1] + 10);
+        // Evenly spaced: the first checkpoint leaves the smallest gap.
+        assert_eq!(
+            thinned_checkpoint(&kept, MAX_SNAPSHOT_CHECKPOINTS),
+            Some(10)
+        );
+
+        // 400 sits in a cluster; dropping it leaves a gap of 310.
+        let mut kept: Vec<u64> = vec![100, 400, 410];
+        kept.extend((1..=MAX_SNAPSHOT_CHECKPOINTS as u64).map(|i| 1000 + i * 1000));
+        assert_eq!(
+            thinned_checkpoint(&kept, MAX_SNAPSHOT_CHECKPOINTS),
+            Some(400)
+        );
+        let newest = kept[kept.len() - 1];
+        assert_ne!(
+            thinned_checkpoint(&kept, MAX_SNAPSHOT_CHECKPOINTS),
+            Some(newest)
+        );
+    }
+
+    #[cfg(feature = "crypto-hash")]
+    #[test]
+    fn delta_since_matches_the_delta_between_replayed_states() {
+        use crate::delta::delta_between;
+
+        let temp = tempdir().expect("temp dir");
+        let db_path = temp.path().join("test.redb");
+        let owns = Attribute::new("owns");
+        let name = Attribute::new("name");
+        let mut graph = RedbGraph::open(&db_path).expect("open db");
+
+        let nodes = graph
+            .ingest_batch(&[
+                Signal::new(EntityId(1), name.clone(), Value::new("alice")),
+                Signal::new(EntityId(2), name.clone(), Value::new("bob")),
+                Signal::new(EntityId(3), name.clone(), Value::new("carol")),
+            ])
+            .expect("batch");
+        let (a, b, c) = (nodes[0], nodes[1], nodes[2]);
+        graph
+            .insert_edge(a, b, Some(&owns), EdgeWeight::new(2))
+            .expect("edge");
+        graph
+            .insert_edge(b, c, None, EdgeWeight::new(1))
+            .expect("edge");
+        graph.increment_edge(a, b, None).expect("increment");
+        graph
+            .store_property(b, Attribute::new("role"), Value::new("admin"))
+            .expect("store");
+        graph.register_entity_key("svc:ledger").expect("key");
+        graph.increment_edge(a, b, Some(&owns)).expect("increment");
+        graph
+            .remove_property(a, &name, &Value::new("alice"))
+            .expect("retract");
+        graph.remove_node(b).expect("remove");
+        let d = graph.insert_node(EntityId(4)).expect("insert");
+        graph
+            .insert_edge(d, a, Some(&owns), EdgeWeight::new(5))
+            .expect("edge");
+        graph
+            .store_property(a, name.clone(), Value::new("alice"))
+            .expect("store");
+        graph.register_entity_key("svc:billing").expect("key");
+
+        let head = graph.mutation_seq().expect("seq");
+        let target = graph.snapshot_at(head).expect("head");
+        let export = graph.canonical_export().expect("export");
+        for seq in 0..=head {
+            let base = graph.snapshot_at(seq).expect("snapshot");
+            let expected = delta_between(&base, &target).expect("delta");
+            assert_eq!(
+                export
+                    .delta_since(seq, expected.base_hash)
+                    .expect("delta since"),
+                expected,
+                "delta since {seq}"
+            );
+        }
+        assert!(matches!(
+            export.delta_since(head + 1, [0; 32]),
+            Err(KremisError::InvalidSignal)
+        ));
+    }
+
+    #[test]
+    fn snapshot_at_replays_the_log() {
+        let temp = tempdir().expect("temp dir");
+        let db_path = temp.path().join("test.redb");
+        let owns = Attribute::new("owns");
+        let mut graph = RedbGraph::open(&db_path).expect("open db");
+
+        let nodes = graph
+            .ingest_batch(&[
+                Signal::new(EntityId(1), Attribute::new("name"), Value::new("alice")),
+                Signal::new(EntityId(2), Attribute::new("name"), Value::new("bob")),
+            ])
+            .expect("batch");
+        graph
+            .insert_edge(nodes[0], nodes[1], Some(&owns), EdgeWeight::new(2))
+            .expect("edge");
+        let before_delete = graph.mutation_seq().expect("seq");
+        graph.remove_node(nodes[1]).expect("remove");
+
+        let past = graph.snapshot_at(before_delete).expect("snapshot");
+        assert_eq!(past.node_count().expect("count"), 2);
+        assert_eq!(
+            past.get_edge(nodes[0], nodes[1], Some(&owns))
+                .expect("edge"),
+            Some(EdgeWeight::new(2))
+        );
+        assert_eq!(
+            past.get_properties(nodes[1]).expect("props"),
+            vec![(Attribute::new("name"), Value::new("bob"))]
+        );
+
+        let now = graph
+            .snapshot_at(graph.mutation_seq().expect("seq"))
+            .expect("snapshot");
+        assert_eq!(now.node_count().expect("count"), 1);
+        assert_eq!(now.next_node_id(), graph.next_node_id());
+
+        assert_eq!(
+            graph
+                .snapshot_at(0)
+                .expect("empty")
+                .node_count()
+                .expect("count"),
+            0
+        );
+        assert!(matches!(
+            graph.snapshot_at(before_delete + 10),
+            Err(KremisError::InvalidSignal)
+        ));
+    }
+
+    #[test]
+    fn mutation_log_starts_from_a_baseline_for_older_databases() {
+        let temp = tempdir().expect("temp dir");
+        let db_path = temp.path().join("test.redb");
+
+        {
+            let mut graph = RedbGraph::open(&db_path).expect("open db");
+            let n1 = graph.insert_node(EntityId(1)).expect("insert");
+            let n2 = graph.insert_node(EntityId(2)).expect("insert");
+            graph
+                .insert_edge(n1, n2, None, EdgeWeight::new(3))
+                .expect("edge");
+            graph
+                .store_property(n2, Attribute::new("name"), Value::new("bob"))
+                .expect("store");
+
+            // Simulate a database written before the mutation log existed.
+            let write_txn = graph.db.begin_write().expect("begin write");
+            write_txn
+                .delete_table(MUTATION_LOG)
+                .expect("drop log table");
+            write_txn
+                .open_table(METADATA)
+                .expect("metadata")
+                .remove(MUTATION_SEQ)
+                .expect("remove seq");
+            write_txn.commit().expect("commit");
+        }
+
+        let graph = RedbGraph::open(&db_path).expect("reopen db");
+        assert_eq!(graph.mutation_seq().expect("seq"), 1);
+        let baseline = graph.snapshot_at(1).expect("snapshot");
+        let n1 = graph.get_node_by_entity(EntityId(1)).unwrap();
+        let n2 = graph.get_node_by_entity(EntityId(2)).unwrap();
+        assert_eq!(baseline.node_count().expect("count"), 2);
+        assert_eq!(
+            baseline.get_edge(n1, n2, None).expect("edge"),
+            Some(EdgeWeight::new(3))
+        );
+        assert_eq!(
+            baseline.get_properties(n2).expect("props"),
+            vec![(Attribute::new("name"), Value::new("bob"))]
+        );
+    }
+
+    #[test]
+    fn journal_records_each_accepted_call() {
+        let temp = tempdir().expect("temp dir");
+        let db_path = temp.path().join("test.redb");
+        let mut graph = RedbGraph::open(&db_path).expect("open db");
+        assert!(graph.journal_is_complete().expect("complete"));
+
+        let batch = vec![
+            Signal::new(EntityId(1), Attribute::new("name"), Value::new("a")),
+            Signal::new(EntityId(2), Attribute::new("name"), Value::new("b")),
+        ];
+        let nodes = graph.ingest_batch(&batch).expect("batch");
+        graph
+            .decrement_edge(nodes[0], nodes[1], None)
+            .expect("decrement");
+        // A rejected call leaves no entry.
+        assert!(graph.decrement_edge(nodes[1], nodes[0], None).is_err());
+
+        assert_eq!(
+            graph.journal().expect("journal"),
+            vec![
+                JournalEntry::Ingest(batch),
+                JournalEntry::DecrementEdge {
+                    from: nodes[0],
+                    to: nodes[1],
+                    relation: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn direct_store_calls_are_journaled_and_replay() {
+        use crate::export::CanonicalGraph;
+
+        let temp = tempdir().expect("temp dir");
+        let db_path = temp.path().join("test.redb");
+        let mut graph = RedbGraph::open(&db_path).expect("open db");
+        let a = graph.insert_node(EntityId(1)).expect("insert");
+        let b = graph.insert_node(EntityId(2)).expect("insert");
+        // Re-inserting an entity changes nothing and leaves no entry.
+        graph.insert_node(EntityId(1)).expect("insert again");
+        let owns = Attribute::new("owns");
+        graph
+            .insert_edge(a, b, Some(&owns), EdgeWeight::new(3))
+            .expect("edge");
+        graph.increment_edge(a, b, None).expect("increment");
+        graph
+            .store_property(a, Attribute::new("name"), Value::new("a"))
+            .expect("property");
+        // A duplicate property stores nothing and leaves no entry.
+        graph
+            .store_property(a, Attribute::new("name"), Value::new("a"))
+            .expect("property again");
+
+        let journal = graph.journal().expect("journal");
+        assert_eq!(journal.len(), 5);
+        assert_eq!(
+            journal[2],
+            JournalEntry::InsertEdge {
+                from: a,
+                to: b,
+                relation: Some(owns),
+                weight: EdgeWeight::new(3),
+            }
+        );
+        let replayed = crate::journal::replay(&journal).expect("replay");
+        assert_eq!(
+            CanonicalGraph::from_graph(&replayed),
+            CanonicalGraph::from_graph(
+                &graph
+                    .snapshot_at(graph.mutation_seq().expect("seq"))
+                    .expect("snapshot")
+            )
+        );
+    }
+
+    #[test]
+    fn merge_commits_once_and_replays_from_the_journal() {
+        use crate::export::CanonicalGraph;
+        use crate::merge::MergePolicy;
+
+        let temp = tempdir().expect("temp dir");
+        let db_path = temp.path().join("test.redb");
+        let mut graph = RedbGraph::open(&db_path).expect("open db");
+        let batch = vec![
+            Signal::new(EntityId(1), Attribute::new("name"), Value::new("a")),
+            Signal::new(EntityId(2), Attribute::new("name"), Value::new("b")),
+        ];
+        graph.ingest_batch(&batch).expect("batch");
+        let seq = graph.mutation_seq().expect("seq");
+
+        let mut theirs = Graph::new();
+        let c = theirs.insert_node(EntityId(3)).expect("insert");
+        let b = theirs.insert_node(EntityId(2)).expect("insert");
+        theirs
+            .insert_edge(b, c, None, EdgeWeight::new(4))
+            .expect("edge");
+        let incoming = CanonicalGraph::from_graph(&theirs);
+        let diagnostics = graph
+            .merge_canonical(&incoming, MergePolicy::Sum)
+            .expect("merge");
+        assert_eq!(diagnostics.nodes_reconciled, 1);
+        assert_eq!(diagnostics.nodes_remapped, 1);
+        assert_eq!(graph.mutation_seq().expect("seq"), seq + 1);
+
+        let carol = graph.get_node_by_entity(EntityId(3)).expect("merged");
+        assert_eq!(
+            graph.get_edge(NodeId(1), carol, None).expect("edge"),
+            Some(EdgeWeight::new(4))
+        );
+        let state = CanonicalGraph::from_graph(&graph.snapshot_at(seq + 1).expect("snapshot"));
+        let replayed = crate::journal::replay(&graph.journal().expect("journal")).expect("replay");
+        assert_eq!(CanonicalGraph::from_graph(&replayed), state);
+
+        drop(graph);
+        let graph = RedbGraph::open(&db_path).expect("reopen");
+        assert_eq!(graph.get_node_by_entity(EntityId(3)), Some(carol));
+        assert_eq!(graph.next_node_id(), carol.0 + 1);
+    }
+
+    #[test]
+    fn journal_started_on_existing_data_is_partial() {
+        let temp = tempdir().expect("temp dir");
+        let db_path = temp.path().join("test.redb");
+
+        {
+            let mut graph = RedbGraph::open(&db_path).expect("open db");
+            graph.insert_node(EntityId(1)).expect("insert");
+
+            // Simulate a database written before the journal existed.
+            let write_txn = graph.db.begin_write().expect("begin write");
+            write_txn.delete_table(JOURNAL).expect("drop journal table");
+            write_txn
+                .open_table(METADATA)
+                .expect("metadata")
+                .remove(JOURNAL_SEQ)
+                .expect("remove seq");
+            write_txn.commit().expect("commit");
+        }
+
+        let graph = RedbGraph::open(&db_path).expect("reopen db");
+        assert!(!graph.journal_is_complete().expect("complete"));
+        assert!(graph.journal().expect("journal").is_empty());
+    }
 }
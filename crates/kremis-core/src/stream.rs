@@ -0,0 +1,745 @@
+//! # Stream Module
+//!
+//! Canonical (KREX) export and import without holding the graph in memory.
+//!
+//! - A [`CanonicalSource`] feeds canonical items in canonical order; a
+//!   `RedbGraph` does it straight from its tables, one node's properties at a
+//!   time
+//! - [`write_canonical`] reads the source twice: once for the header counts,
+//!   checksum and format version, once to encode the items to any
+//!   [`io::Write`]. The bytes are identical to [`export_canonical`]
+//! - [`HashingWriter`] hashes what it writes with an incremental BLAKE3
+//!   hasher, so a state hash needs no buffer at all
+//! - [`read_canonical`] decodes an export from any [`io::Read`] item by item
+//!
+//! [`export_canonical`]: crate::export::export_canonical
+
+use crate::KremisError;
+use crate::export::{
+    CANONICAL_VERSION, CanonicalEdge, CanonicalEntityKey, CanonicalGraph, CanonicalHeader,
+    CanonicalNode, CanonicalProperty, MAX_IMPORT_BYTES, MAX_IMPORT_EDGE_COUNT,
+    MAX_IMPORT_NODE_COUNT, entity_key_checksum, relation_checksum,
+};
+use crate::graph::Graph;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read, Write};
+
+/// Largest header a reader accepts; real headers are a few dozen bytes.
+const MAX_HEADER_LEN: usize = 1024;
+
+/// Bytes a reader pulls from its input at a time.
+const READ_CHUNK: usize = 8 * 1024;
+
+/// One element of a canonical export.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CanonicalItem {
+    /// A node.
+    Node(CanonicalNode),
+    /// An edge.
+    Edge(CanonicalEdge),
+    /// A property pair.
+    Property(CanonicalProperty),
+    /// An entity key binding.
+    EntityKey(CanonicalEntityKey),
+}
+
+/// A graph state that can be exported item by item.
+pub trait CanonicalSource {
+    /// Feed every item to `visit` in canonical order and return the next
+    /// node id.
+    ///
+    /// Nodes come first, sorted by id, then edges by `(from, to, relation)`,
+    /// properties by `(node_id, attribute, value)` and entity keys by key.
+    /// Every call must feed the same items.
+    fn visit_canonical(
+        &self,
+        visit: &mut dyn FnMut(CanonicalItem) -> Result<(), KremisError>,
+    ) -> Result<u64, KremisError>;
+}
+
+impl CanonicalSource for CanonicalGraph {
+    fn visit_canonical(
+        &self,
+        visit: &mut dyn FnMut(CanonicalItem) -> Result<(), KremisError>,
+    ) -> Result<u64, KremisError> {
+        for node in &self.nodes {
+            visit(CanonicalItem::Node(node.clone()))?;
+        }
+        for edge in &self.edges {
+            visit(CanonicalItem::Edge(edge.clone()))?;
+        }
+        for property in &self.properties {
+            visit(CanonicalItem::Property(property.clone()))?;
+        }
+        for key in &self.entity_keys {
+            visit(CanonicalItem::EntityKey(key.clone()))?;
+        }
+        Ok(self.next_node_id)
+    }
+}
+
+impl CanonicalSource for Graph {
+    fn visit_canonical(
+        &self,
+        visit: &mut dyn FnMut(CanonicalItem) -> Result<(), KremisError>,
+    ) -> Result<u64, KremisError> {
+        CanonicalGraph::from_graph(self).visit_canonical(visit)
+    }
+}
+
+/// Edge layout of format versions 1 and 2, which predate relation labels.
+#[derive(Serialize, Deserialize)]
+struct EdgeV2 {
+    from: u64,
+    to: u64,
+    weight: i64,
+}
+
+/// What `CanonicalGraph::checksum` XORs in for one item.
+fn checksum_term(item: &CanonicalItem) -> u64 {
+    let mut hash = 0u64;
+    match item {
+        CanonicalItem::Node(node) => {
+            hash ^= node.id.rotate_left(13);
+            hash ^= node.entity.rotate_left(7);
+        }
+        CanonicalItem::Edge(edge) => {
+            hash ^= edge.from.rotate_left(17);
+            hash ^= edge.to.rotate_left(11);
+            hash ^= (edge.weight as u64).rotate_left(5);
+            if let Some(relation) = &edge.relation {
+                hash ^= relation_checksum(relation);
+            }
+        }
+        CanonicalItem::Property(prop) => {
+            hash ^= prop.node_id.rotate_left(19);
+            for byte in prop.attribute.as_bytes() {
+                hash ^= (*byte as u64).rotate_left(23);
+            }
+            for byte in prop.value.as_bytes() {
+                hash ^= (*byte as u64).rotate_left(29);
+            }
+        }
+        CanonicalItem::EntityKey(entry) => {
+            hash ^= entity_key_checksum(&entry.key);
+            hash ^= entry.entity.rotate_left(41);
+        }
+    }
+    hash
+}
+
+/// Section counts and header of a source, from its first pass.
+struct Summary {
+    header: CanonicalHeader,
+    /// Items per section: nodes, edges, properties, entity keys.
+    counts: [u64; 4],
+    next_node_id: u64,
+}
+
+/// Payload section an item belongs to.
+fn section(item: &CanonicalItem) -> usize {
+    match item {
+        CanonicalItem::Node(_) => 0,
+        CanonicalItem::Edge(_) => 1,
+        CanonicalItem::Property(_) => 2,
+        CanonicalItem::EntityKey(_) => 3,
+    }
+}
+
+/// First pass over a source: counts, checksum and format version.
+fn scan(source: &(impl CanonicalSource + ?Sized)) -> Result<Summary, KremisError> {
+    let mut counts = [0u64; 4];
+    let mut labeled = false;
+    let mut checksum = 0u64;
+    let next_node_id = source.visit_canonical(&mut |item| {
+        if let CanonicalItem::Edge(edge) = &item {
+            labeled |= edge.relation.is_some();
+        }
+        counts[section(&item)] += 1;
+        checksum ^= checksum_term(&item);
+        Ok(())
+    })?;
+    checksum ^= next_node_id.rotate_left(3);
+
+    // Same rule as `CanonicalGraph::format_version`.
+    let version = if counts[3] > 0 {
+        CANONICAL_VERSION
+    } else if labeled {
+        3
+    } else {
+        2
+    };
+    Ok(Summary {
+        header: CanonicalHeader {
+            version,
+            ..CanonicalHeader::new(counts[0], counts[1], checksum)
+        },
+        counts,
+        next_node_id,
+    })
+}
+
+/// Encode `value` to `out` as postcard.
+fn put<T: Serialize + ?Sized>(out: &mut impl Write, value: &T) -> Result<(), KremisError> {
+    postcard::to_io(value, out)
+        .map(|_| ())
+        .map_err(|e| KremisError::SerializationError(format!("Data: {}", e)))
+}
+
+/// Write the prefix of payload section `section`: its length, preceded by
+/// the next node id for the properties section.
+fn open_section(
+    out: &mut impl Write,
+    summary: &Summary,
+    section: usize,
+) -> Result<(), KremisError> {
+    if section == 2 {
+        put(out, &summary.next_node_id)?;
+    }
+    put(out, &(summary.counts[section] as usize))
+}
+
+/// Write the canonical export of `source` to `out`.
+///
+/// Produces the same bytes as `export_canonical` on the same state, while
+/// holding one item at a time. Returns the header that was written.
+///
+/// # Errors
+///
+/// Returns an error if reading the source or writing to `out` fails, and
+/// `KremisError::SerializationError` if the source feeds different items on
+/// its two passes.
+pub fn write_canonical<W: Write>(
+    source: &(impl CanonicalSource + ?Sized),
+    mut out: W,
+) -> Result<CanonicalHeader, KremisError> {
+    let summary = scan(source)?;
+    let changed = || KremisError::SerializationError("Source changed during export".to_string());
+
+    let header_bytes = postcard::to_allocvec(&summary.header)
+        .map_err(|e| KremisError::SerializationError(format!("Header: {}", e)))?;
+    out.write_all(&(header_bytes.len() as u32).to_le_bytes())
+        .and_then(|()| out.write_all(&header_bytes))
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+
+    // Version 4 is the only layout with an entity key section.
+    let sections = if summary.header.version == CANONICAL_VERSION {
+        4
+    } else {
+        3
+    };
+    let mut opened = 0;
+    let mut seen = [0u64; 4];
+    let mut checksum = 0u64;
+    let next_node_id = source.visit_canonical(&mut |item| {
+        let section = section(&item);
+        if section + 1 < opened || section >= sections {
+            return Err(changed());
+        }
+        // Empty sections before this one still get their prefix.
+        while opened <= section {
+            open_section(&mut out, &summary, opened)?;
+            opened += 1;
+        }
+        seen[section] += 1;
+        checksum ^= checksum_term(&item);
+        match item {
+            CanonicalItem::Node(node) => put(&mut out, &node),
+            CanonicalItem::Edge(edge) if summary.header.version == 2 => put(
+                &mut out,
+                &EdgeV2 {
+                    from: edge.from,
+                    to: edge.to,
+                    weight: edge.weight,
+                },
+            ),
+            CanonicalItem::Edge(edge) => put(&mut out, &edge),
+            CanonicalItem::Property(prop) => put(&mut out, &prop),
+            CanonicalItem::EntityKey(key) => put(&mut out, &key),
+        }
+    })?;
+    while opened < sections {
+        open_section(&mut out, &summary, opened)?;
+        opened += 1;
+    }
+
+    checksum ^= next_node_id.rotate_left(3);
+    if seen != summary.counts
+        || next_node_id != summary.next_node_id
+        || checksum != summary.header.checksum
+    {
+        return Err(changed());
+    }
+    out.flush()
+        .map_err(|e| KremisError::IoError(e.to_string()))?;
+    Ok(summary.header)
+}
+
+/// A writer that hashes everything written through it with BLAKE3.
+///
+/// # Requires
+///
+/// Only available with the `crypto-hash` feature.
+#[cfg(feature = "crypto-hash")]
+pub struct HashingWriter<W> {
+    inner: W,
+    hasher: blake3::Hasher,
+}
+
+#[cfg(feature = "crypto-hash")]
+impl<W: Write> HashingWriter<W> {
+    /// Wrap `inner`.
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: blake3::Hasher::new(),
+        }
+    }
+
+    /// The hash of everything written so far, and the inner writer.
+    pub fn finalize(self) -> ([u8; 32], W) {
+        (*self.hasher.finalize().as_bytes(), self.inner)
+    }
+}
+
+#[cfg(feature = "crypto-hash")]
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// The BLAKE3 state hash of `source`, computed without buffering the export.
+///
+/// Equal to `certificate::state_hash` of the same state.
+///
+/// # Requires
+///
+/// Only available with the `crypto-hash` feature.
+#[cfg(feature = "crypto-hash")]
+pub fn stream_state_hash(
+    source: &(impl CanonicalSource + ?Sized),
+) -> Result<[u8; 32], KremisError> {
+    let mut writer = HashingWriter::new(io::sink());
+    write_canonical(source, &mut writer)?;
+    Ok(writer.finalize().0)
+}
+
+/// Pulls postcard values off an `io::Read` through a small buffer.
+struct ItemReader<R> {
+    input: R,
+    buf: Vec<u8>,
+    pos: usize,
+    consumed: usize,
+    /// Largest payload accepted, or `None` to only bound single items.
+    limit: Option<usize>,
+}
+
+impl<R: Read> ItemReader<R> {
+    fn take<T: DeserializeOwned>(&mut self) -> Result<T, KremisError> {
+        loop {
+            match postcard::take_from_bytes::<T>(&self.buf[self.pos..]) {
+                Ok((value, rest)) => {
+                    let used = self.buf.len() - self.pos - rest.len();
+                    self.pos += used;
+                    self.consumed = self.consumed.saturating_add(used);
+                    if self.limit.is_some_and(|limit| self.consumed > limit) {
+                        return Err(KremisError::SerializationError(format!(
+                            "Payload size exceeds maximum allowed {}",
+                            MAX_IMPORT_BYTES
+                        )));
+                    }
+                    return Ok(value);
+                }
+                Err(postcard::Error::DeserializeUnexpectedEnd) => self.fill()?,
+                Err(e) => return Err(KremisError::SerializationError(format!("Data: {}", e))),
+            }
+        }
+    }
+
+    /// Read another chunk, dropping the bytes already decoded.
+    fn fill(&mut self) -> Result<(), KremisError> {
+        self.buf.drain(..self.pos);
+        self.pos = 0;
+        // A single item cannot be larger than the whole payload may be.
+        let pending = match self.limit {
+            Some(_) => self.consumed.saturating_add(self.buf.len()),
+            None => self.buf.len(),
+        };
+        if pending > self.limit.unwrap_or(MAX_IMPORT_BYTES) {
+            return Err(KremisError::SerializationError(format!(
+                "Payload size exceeds maximum allowed {}",
+                MAX_IMPORT_BYTES
+            )));
+        }
+        let start = self.buf.len();
+        self.buf.resize(start + READ_CHUNK, 0);
+        let read = loop {
+            match self.input.read(&mut self.buf[start..]) {
+                Ok(read) => break read,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(KremisError::IoError(e.to_string())),
+            }
+        };
+        self.buf.truncate(start + read);
+        if read == 0 {
+            return Err(KremisError::SerializationError(
+                "Data: unexpected end of input".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// How much of its input a [`CanonicalReader`] accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ReadLimits {
+    /// The limits of `import_canonical`, for a caller that keeps the items.
+    Import,
+    /// Only single items are bounded, for a caller that keeps none of them.
+    #[cfg(feature = "crypto-hash")]
+    Streaming,
+}
+
+/// Where a [`CanonicalReader`] is in the payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Start,
+    Nodes,
+    Edges,
+    Properties,
+    EntityKeys,
+    Done,
+}
+
+/// Pulls the items of a canonical export off an `io::Read` one at a time,
+/// in file order.
+pub(crate) struct CanonicalReader<R> {
+    reader: ItemReader<R>,
+    header: CanonicalHeader,
+    section: Section,
+    /// Items left in the current section.
+    left: usize,
+    nodes: usize,
+    edges: usize,
+    next_node_id: u64,
+    checksum: u64,
+}
+
+impl<R: Read> CanonicalReader<R> {
+    /// Read and validate the header of the export in `input`.
+    pub(crate) fn new(mut input: R, limits: ReadLimits) -> Result<Self, KremisError> {
+        let mut len = [0u8; 4];
+        input
+            .read_exact(&mut len)
+            .map_err(|_| KremisError::SerializationError("Data too short".to_string()))?;
+        let header_len = u32::from_le_bytes(len) as usize;
+        if header_len > MAX_HEADER_LEN {
+            return Err(KremisError::SerializationError(
+                "Invalid file format".to_string(),
+            ));
+        }
+        let mut header_bytes = vec![0u8; header_len];
+        input.read_exact(&mut header_bytes).map_err(|_| {
+            KremisError::SerializationError("Data too short for header".to_string())
+        })?;
+        let header: CanonicalHeader = postcard::from_bytes(&header_bytes)
+            .map_err(|e| KremisError::SerializationError(format!("Header: {}", e)))?;
+        header.validate()?;
+        if limits == ReadLimits::Import {
+            if header.node_count > MAX_IMPORT_NODE_COUNT {
+                return Err(KremisError::SerializationError(format!(
+                    "Node count {} exceeds maximum allowed {}",
+                    header.node_count, MAX_IMPORT_NODE_COUNT
+                )));
+            }
+            if header.edge_count > MAX_IMPORT_EDGE_COUNT {
+                return Err(KremisError::SerializationError(format!(
+                    "Edge count {} exceeds maximum allowed {}",
+                    header.edge_count, MAX_IMPORT_EDGE_COUNT
+                )));
+            }
+        }
+
+        Ok(Self {
+            reader: ItemReader {
+                input,
+                buf: Vec::new(),
+                pos: 0,
+                consumed: 0,
+                limit: (limits == ReadLimits::Import).then_some(MAX_IMPORT_BYTES),
+            },
+            header,
+            section: Section::Start,
+            left: 0,
+            nodes: 0,
+            edges: 0,
+            next_node_id: 0,
+            checksum: 0,
+        })
+    }
+
+    /// The next item, or `None` once the payload is read.
+    pub(crate) fn next_item(&mut self) -> Result<Option<CanonicalItem>, KremisError> {
+        while self.left == 0 {
+            self.section = match self.section {
+                Section::Start => {
+                    self.nodes = self.reader.take()?;
+                    self.left = self.nodes;
+                    Section::Nodes
+                }
+                Section::Nodes => {
+                    self.edges = self.reader.take()?;
+                    self.left = self.edges;
+                    Section::Edges
+                }
+                Section::Edges => {
+                    self.next_node_id = self.reader.take()?;
+                    if self.header.version >= 2 {
+                        self.left = self.reader.take()?;
+                    }
+                    Section::Properties
+                }
+                Section::Properties if self.header.version >= CANONICAL_VERSION => {
+                    self.left = self.reader.take()?;
+                    Section::EntityKeys
+                }
+                Section::Properties | Section::EntityKeys | Section::Done => {
+                    self.section = Section::Done;
+                    return Ok(None);
+                }
+            };
+        }
+
+        self.left -= 1;
+        let item = match self.section {
+            Section::Nodes => CanonicalItem::Node(self.reader.take()?),
+            Section::Edges if self.header.version <= 2 => {
+                let edge: EdgeV2 = self.reader.take()?;
+                CanonicalItem::Edge(CanonicalEdge {
+                    from: edge.from,
+                    to: edge.to,
+                    relation: None,
+                    weight: edge.weight,
+                })
+            }
+            Section::Edges => CanonicalItem::Edge(self.reader.take()?),
+            Section::Properties => CanonicalItem::Property(self.reader.take()?),
+            Section::EntityKeys => CanonicalItem::EntityKey(self.reader.take()?),
+            Section::Start | Section::Done => return Ok(None),
+        };
+        self.checksum ^= checksum_term(&item);
+        Ok(Some(item))
+    }
+
+    /// Check the checksum and counts once every item was read. Returns the
+    /// header and the next node id.
+    pub(crate) fn finish(self) -> Result<(CanonicalHeader, u64), KremisError> {
+        if self.section != Section::Done {
+            return Err(KremisError::SerializationError(
+                "Data: export was not read to its end".to_string(),
+            ));
+        }
+        let checksum = self.checksum ^ self.next_node_id.rotate_left(3);
+        if checksum != self.header.checksum {
+            return Err(KremisError::SerializationError(format!(
+                "Checksum mismatch: expected {}, got {}",
+                self.header.checksum, checksum
+            )));
+        }
+        if self.nodes as u64 != self.header.node_count {
+            return Err(KremisError::SerializationError(
+                "Node count mismatch".to_string(),
+            ));
+        }
+        if self.edges as u64 != self.header.edge_count {
+            return Err(KremisError::SerializationError(
+                "Edge count mismatch".to_string(),
+            ));
+        }
+        Ok((self.header, self.next_node_id))
+    }
+}
+
+/// Read a canonical export from `input`, feeding its items to `visit` in
+/// file order.
+///
+/// Accepts every version `import_canonical` does and applies the same size
+/// limits, checksum and count checks. Returns the header and the next node
+/// id.
+///
+/// # Errors
+///
+/// Returns `KremisError::SerializationError` if the export is malformed. The
+/// checksum is only known once every item was read, so on error the caller
+/// must discard what `visit` already received.
+pub fn read_canonical<R: Read>(
+    input: R,
+    visit: &mut dyn FnMut(CanonicalItem) -> Result<(), KremisError>,
+) -> Result<(CanonicalHeader, u64), KremisError> {
+    let mut reader = CanonicalReader::new(input, ReadLimits::Import)?;
+    while let Some(item) = reader.next_item()? {
+        visit(item)?;
+    }
+    reader.finish()
+}
+
+/// A canonical export read from the start on every pass, as a
+/// [`CanonicalSource`].
+///
+/// Only single items are bounded, so an export of any size is fed through
+/// without being held in memory. The closure reopens it for each pass.
+#[cfg(feature = "crypto-hash")]
+pub(crate) struct ExportReader<F>(pub(crate) F);
+
+#[cfg(feature = "crypto-hash")]
+impl<R: Read, F: Fn() -> Result<R, KremisError>> CanonicalSource for ExportReader<F> {
+    fn visit_canonical(
+        &self,
+        visit: &mut dyn FnMut(CanonicalItem) -> Result<(), KremisError>,
+    ) -> Result<u64, KremisError> {
+        let mut reader = CanonicalReader::new((self.0)()?, ReadLimits::Streaming)?;
+        while let Some(item) = reader.next_item()? {
+            visit(item)?;
+        }
+        reader.finish().map(|(_, next_node_id)| next_node_id)
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::export::export_canonical;
+    use crate::graph::GraphStore;
+    use crate::storage::RedbGraph;
+    use crate::{Attribute, EdgeWeight, EntityId, NodeId, Session, Value};
+
+    /// A redb session at each format version: unlabeled, labeled, keyed.
+    fn sessions(dir: &std::path::Path) -> Vec<Session> {
+        (2..=4)
+            .map(|version| {
+                let mut redb = RedbGraph::open(dir.join(format!("v{version}.redb"))).unwrap();
+                let a = redb.insert_node(EntityId(1)).unwrap();
+                let b = redb.insert_node(EntityId(2)).unwrap();
+                let c = redb.insert_node(EntityId(3)).unwrap();
+                redb.insert_edge(a, b, None, EdgeWeight::new(3)).unwrap();
+                redb.insert_edge(b, c, None, EdgeWeight::new(-1)).unwrap();
+                redb.insert_edge(c, a, None, EdgeWeight::new(1)).unwrap();
+                for (attr, value) in [("name", "Bob"), ("role", "admin"), ("name", "Al")] {
+                    redb.store_property(b, Attribute::new(attr), Value::new(value))
+                        .unwrap();
+                }
+                redb.store_property(a, Attribute::new("zone"), Value::new("eu"))
+                    .unwrap();
+                redb.remove_node(c).unwrap();
+                if version >= 3 {
+                    let rel = Attribute::new("owns");
+                    redb.insert_edge(a, b, Some(&rel), EdgeWeight::new(2))
+                        .unwrap();
+                    redb.insert_edge(b, a, Some(&rel), EdgeWeight::new(5))
+                        .unwrap();
+                }
+                if version == 4 {
+                    redb.register_entity_key("svc:ledger").unwrap();
+                }
+                Session::with_redb_graph(redb)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn streamed_export_matches_the_snapshot_export_at_every_version() {
+        let dir = tempfile::tempdir().unwrap();
+        for (session, version) in sessions(dir.path()).iter().zip(2..) {
+            let expected = export_canonical(&session.export_graph_snapshot().unwrap()).unwrap();
+            let mut streamed = Vec::new();
+            let header = session.write_canonical(&mut streamed).unwrap();
+            assert_eq!(header.version, version);
+            assert_eq!(streamed, expected);
+        }
+
+        let empty = Session::with_redb(dir.path().join("empty.redb")).unwrap();
+        let mut streamed = Vec::new();
+        empty.write_canonical(&mut streamed).unwrap();
+        assert_eq!(
+            streamed,
+            export_canonical(&empty.export_graph_snapshot().unwrap()).unwrap()
+        );
+    }
+
+    #[test]
+    fn read_canonical_returns_the_exported_items() {
+        let dir = tempfile::tempdir().unwrap();
+        for session in sessions(dir.path()) {
+            let snapshot = CanonicalGraph::from_graph(&session.export_graph_snapshot().unwrap());
+            let mut bytes = Vec::new();
+            session.write_canonical(&mut bytes).unwrap();
+
+            let mut items = Vec::new();
+            let (_, next_node_id) = read_canonical(bytes.as_slice(), &mut |item| {
+                items.push(item);
+                Ok(())
+            })
+            .unwrap();
+
+            let mut expected = Vec::new();
+            snapshot
+                .visit_canonical(&mut |item| {
+                    expected.push(item);
+                    Ok(())
+                })
+                .unwrap();
+            assert_eq!(items, expected);
+            assert_eq!(next_node_id, snapshot.next_node_id);
+        }
+    }
+
+    #[test]
+    fn read_canonical_rejects_truncated_or_corrupt_input() {
+        let mut graph = Graph::new();
+        let a = graph.insert_node(EntityId(1)).unwrap();
+        graph
+            .store_property(a, Attribute::new("name"), Value::new("Alice"))
+            .unwrap();
+        graph
+            .insert_edge(a, NodeId(0), None, EdgeWeight::new(1))
+            .unwrap();
+        let bytes = export_canonical(&graph).unwrap();
+
+        let mut ignore = |_| Ok(());
+        assert!(read_canonical(&bytes[..bytes.len() - 1], &mut ignore).is_err());
+        let mut corrupt = bytes.clone();
+        let last = corrupt.len() - 1;
+        corrupt[last] ^= 0x01;
+        assert!(matches!(
+            read_canonical(corrupt.as_slice(), &mut ignore),
+            Err(KremisError::SerializationError(_))
+        ));
+        assert!(read_canonical(bytes.as_slice(), &mut ignore).is_ok());
+    }
+
+    #[cfg(feature = "crypto-hash")]
+    #[test]
+    fn streamed_state_hash_matches_the_certificate_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        for session in sessions(dir.path()) {
+            let snapshot = session.export_graph_snapshot().unwrap();
+            assert_eq!(
+                session.state_hash().unwrap(),
+                crate::certificate::state_hash(&snapshot).unwrap()
+            );
+        }
+    }
+}
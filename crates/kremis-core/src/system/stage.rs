@@ -486,7 +486,7 @@ mod tests {
                 .insert_node(EntityId(i as u64 * 2 + 1))
                 .expect("insert");
             graph
-                .insert_edge(from, to, EdgeWeight::new(STABLE_THRESHOLD))
+                .insert_edge(from, to, None, EdgeWeight::new(STABLE_THRESHOLD))
                 .expect("insert");
         }
 
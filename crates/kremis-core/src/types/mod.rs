@@ -178,6 +178,9 @@ impl Signal {
 // ARTIFACT
 // =============================================================================
 
+/// A directed edge as `(from, to, relation, weight)`; `None` is the unlabeled edge.
+pub type LabeledEdge = (NodeId, NodeId, Option<Attribute>, EdgeWeight);
+
 /// An Artifact is the output of a graph traversal operation.
 ///
 /// The Compositor outputs raw symbolic structures only.
@@ -186,8 +189,8 @@ impl Signal {
 pub struct Artifact {
     /// The path of nodes traversed.
     pub path: Vec<NodeId>,
-    /// Optional subgraph extracted (edges with weights).
-    pub subgraph: Option<Vec<(NodeId, NodeId, EdgeWeight)>>,
+    /// Optional subgraph extracted, as `(from, to, relation, weight)` edges.
+    pub subgraph: Option<Vec<LabeledEdge>>,
 }
 
 impl Artifact {
@@ -208,7 +211,7 @@ impl Artifact {
 
     /// Create an artifact with both path and subgraph.
     #[must_use]
-    pub fn with_subgraph(path: Vec<NodeId>, subgraph: Vec<(NodeId, NodeId, EdgeWeight)>) -> Self {
+    pub fn with_subgraph(path: Vec<NodeId>, subgraph: Vec<LabeledEdge>) -> Self {
         Self {
             path,
             subgraph: Some(subgraph),
@@ -347,6 +350,39 @@ pub enum KremisError {
     /// A node has reached its maximum number of distinct properties.
     #[error("Property limit exceeded for node {0:?}: maximum {1} properties per node")]
     PropertyLimitExceeded(NodeId, usize),
+
+    /// A textual query could not be parsed.
+    #[error("Query parse error at position {position}: {message}")]
+    QueryParse {
+        /// Character offset of the offending token, counted from 0.
+        position: usize,
+        /// What the parser expected there.
+        message: String,
+    },
+
+    /// A page cursor was issued for a graph state that no longer holds.
+    #[error("Stale cursor: the graph has changed since the cursor was issued")]
+    StaleCursor,
+
+    /// A path enumeration would extend more partial paths than allowed.
+    #[error(
+        "Path enumeration limit exceeded: more than {0} partial paths; lower max_hops or filter by relation"
+    )]
+    PathEnumerationLimit(usize),
+
+    /// Rebuilding a past state would replay more mutations than the visit
+    /// budget allows.
+    #[error(
+        "History replay limit exceeded: rebuilding the state at mutation {0} needs more visits than the budget allows; raise timeout_ms or query a later state"
+    )]
+    HistoryReplayLimit(u64),
+
+    /// Exact betweenness would count more shortest paths than fit in 128
+    /// bits.
+    #[error(
+        "Betweenness overflow: the graph has more shortest paths between two nodes than can be counted exactly; filter by relation or use another metric"
+    )]
+    BetweennessOverflow,
 }
 
 // =============================================================================
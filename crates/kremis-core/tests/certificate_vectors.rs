@@ -12,7 +12,7 @@
 #![allow(clippy::expect_used)]
 
 use kremis_core::graph::GraphStore;
-use kremis_core::{Artifact, EdgeWeight, EntityId, Graph, QueryCertificate};
+use kremis_core::{Artifact, Attribute, EdgeWeight, EntityId, Graph, NodeId, QueryCertificate};
 
 /// Deterministic fixture graph: 1 -> 2 -> 3.
 fn fixture_graph() -> Graph {
@@ -20,8 +20,10 @@ fn fixture_graph() -> Graph {
     let a = g.insert_node(EntityId(1)).expect("node 1");
     let b = g.insert_node(EntityId(2)).expect("node 2");
     let c = g.insert_node(EntityId(3)).expect("node 3");
-    g.insert_edge(a, b, EdgeWeight::new(10)).expect("edge a-b");
-    g.insert_edge(b, c, EdgeWeight::new(20)).expect("edge b-c");
+    g.insert_edge(a, b, None, EdgeWeight::new(10))
+        .expect("edge a-b");
+    g.insert_edge(b, c, None, EdgeWeight::new(20))
+        .expect("edge b-c");
     g
 }
 
@@ -30,7 +32,7 @@ const FIXED_HASH: [u8; 32] = [7u8; 32];
 #[test]
 fn vector_fact_is_reproducible() {
     let g = fixture_graph();
-    let artifact = Artifact::with_subgraph(vec![], g.edges().collect());
+    let artifact = Artifact::with_subgraph(vec![], unlabeled(&g));
 
     let c1 = QueryCertificate::new(FIXED_HASH, "traverse:1:2", "fact", &g, &artifact);
     let c2 = QueryCertificate::new(FIXED_HASH, "traverse:1:2", "fact", &g, &artifact);
@@ -56,7 +58,7 @@ fn vector_bytes_are_frozen() {
     let ids: Vec<_> = g.nodes().map(|n| n.id).collect();
 
     // subgraph = Some (traverse): evidence edges come from the artifact.
-    let traverse = Artifact::with_subgraph(ids.clone(), g.edges().collect());
+    let traverse = Artifact::with_subgraph(ids.clone(), unlabeled(&g));
     let bytes = QueryCertificate::new(FIXED_HASH, "traverse:1:2", "fact", &g, &traverse)
         .to_canonical_bytes()
         .expect("encode traverse");
@@ -76,6 +78,32 @@ fn vector_bytes_are_frozen() {
     );
 }
 
+/// Labeled evidence is encoded unlabeled, so labels leave the frozen bytes
+/// untouched.
+#[test]
+fn vector_bytes_ignore_relation_labels() {
+    let g = fixture_graph();
+    let ids: Vec<_> = g.nodes().map(|n| n.id).collect();
+    let labeled = g
+        .edges()
+        .map(|(from, to, w)| (from, to, Some(Attribute::new("depends_on")), w))
+        .collect();
+
+    let traverse = Artifact::with_subgraph(ids.clone(), labeled);
+    let bytes = QueryCertificate::new(FIXED_HASH, "traverse:1:2", "fact", &g, &traverse)
+        .to_canonical_bytes()
+        .expect("encode traverse");
+    assert_eq!(
+        hex(&bytes),
+        "250000004b5651430107070707070707070707070707070707070707070707070707070707070707070c74726176657273653a313a320300010102020302000114010228030001020466616374"
+    );
+}
+
+/// The fixture's edges as unlabeled subgraph edges.
+fn unlabeled(g: &Graph) -> Vec<(NodeId, NodeId, Option<Attribute>, EdgeWeight)> {
+    g.edges().map(|(from, to, w)| (from, to, None, w)).collect()
+}
+
 fn hex(bytes: &[u8]) -> String {
     bytes.iter().map(|b| format!("{b:02x}")).collect()
 }
@@ -76,14 +76,14 @@ proptest! {
         for entity in &entities {
             let node = graph.insert_node(*entity).expect("insert");
             if let Some(prev) = previous_node {
-                graph.increment_edge(prev, node).expect("inc");
+                graph.increment_edge(prev, node, None).expect("inc");
             }
             previous_node = Some(node);
         }
 
         let start = graph.insert_node(entities[0]).expect("insert");
-        let result1 = graph.traverse(start, depth).expect("traverse");
-        let result2 = graph.traverse(start, depth).expect("traverse");
+        let result1 = graph.traverse(start, depth, None).expect("traverse");
+        let result2 = graph.traverse(start, depth, None).expect("traverse");
 
         // Same traversal twice should produce identical results
         prop_assert_eq!(result1.is_some(), result2.is_some());
@@ -121,9 +121,9 @@ proptest! {
         let n1 = graph.insert_node(e1).expect("insert");
         let n2 = graph.insert_node(e2).expect("insert");
 
-        graph.insert_edge(n1, n2, weight).expect("edge");
+        graph.insert_edge(n1, n2, None, weight).expect("edge");
 
-        prop_assert!(graph.contains_edge(n1, n2));
-        prop_assert_eq!(graph.get_edge(n1, n2).expect("get"), Some(weight));
+        prop_assert!(graph.contains_edge(n1, n2, None));
+        prop_assert_eq!(graph.get_edge(n1, n2, None).expect("get"), Some(weight));
     }
 }
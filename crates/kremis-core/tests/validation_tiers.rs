@@ -87,8 +87,12 @@ mod t1_deterministic_edges {
             let a = graph.insert_node(EntityId(1)).expect("insert");
             let b = graph.insert_node(EntityId(2)).expect("insert");
             let c = graph.insert_node(EntityId(3)).expect("insert");
-            graph.insert_edge(a, b, EdgeWeight::new(5)).expect("edge");
-            graph.insert_edge(b, c, EdgeWeight::new(3)).expect("edge");
+            graph
+                .insert_edge(a, b, None, EdgeWeight::new(5))
+                .expect("edge");
+            graph
+                .insert_edge(b, c, None, EdgeWeight::new(3))
+                .expect("edge");
             graph
         };
 
@@ -112,8 +116,8 @@ mod t1_deterministic_edges {
         let b2 = graph2.get_node_by_entity(EntityId(2)).expect("b2");
 
         assert_eq!(
-            graph1.get_edge(a1, b1).expect("edge"),
-            graph2.get_edge(a2, b2).expect("edge")
+            graph1.get_edge(a1, b1, None).expect("edge"),
+            graph2.get_edge(a2, b2, None).expect("edge")
         );
     }
 
@@ -125,19 +129,27 @@ mod t1_deterministic_edges {
         let b = graph.insert_node(EntityId(2)).expect("insert");
 
         // Initial weight
-        graph.insert_edge(a, b, EdgeWeight::new(1)).expect("edge");
-        assert_eq!(graph.get_edge(a, b).expect("get"), Some(EdgeWeight::new(1)));
+        graph
+            .insert_edge(a, b, None, EdgeWeight::new(1))
+            .expect("edge");
+        assert_eq!(
+            graph.get_edge(a, b, None).expect("get"),
+            Some(EdgeWeight::new(1))
+        );
 
         // Increment
-        graph.increment_edge(a, b).expect("inc");
-        assert_eq!(graph.get_edge(a, b).expect("get"), Some(EdgeWeight::new(2)));
+        graph.increment_edge(a, b, None).expect("inc");
+        assert_eq!(
+            graph.get_edge(a, b, None).expect("get"),
+            Some(EdgeWeight::new(2))
+        );
 
         // More increments
         for _ in 0..8 {
-            graph.increment_edge(a, b).expect("inc");
+            graph.increment_edge(a, b, None).expect("inc");
         }
         assert_eq!(
-            graph.get_edge(a, b).expect("get"),
+            graph.get_edge(a, b, None).expect("get"),
             Some(EdgeWeight::new(10))
         );
     }
@@ -153,7 +165,9 @@ mod t1_deterministic_edges {
                 .collect();
 
             for i in 0..9 {
-                graph.increment_edge(nodes[i], nodes[i + 1]).expect("inc");
+                graph
+                    .increment_edge(nodes[i], nodes[i + 1], None)
+                    .expect("inc");
             }
 
             assert_eq!(graph.edge_count().expect("count"), 9);
@@ -186,10 +200,14 @@ mod t2_single_hop {
         let b = graph.insert_node(EntityId(2)).expect("insert");
         let c = graph.insert_node(EntityId(3)).expect("insert");
 
-        graph.insert_edge(a, b, EdgeWeight::new(1)).expect("edge");
-        graph.insert_edge(a, c, EdgeWeight::new(1)).expect("edge");
+        graph
+            .insert_edge(a, b, None, EdgeWeight::new(1))
+            .expect("edge");
+        graph
+            .insert_edge(a, c, None, EdgeWeight::new(1))
+            .expect("edge");
 
-        let neighbors = graph.neighbors(a).expect("neighbors");
+        let neighbors = graph.neighbors(a, None).expect("neighbors");
         assert_eq!(neighbors.len(), 2);
         assert!(neighbors.iter().any(|(n, _)| *n == b));
         assert!(neighbors.iter().any(|(n, _)| *n == c));
@@ -201,7 +219,7 @@ mod t2_single_hop {
         let graph = Graph::new();
         let fake_node = NodeId(999);
 
-        let neighbors = graph.neighbors(fake_node).expect("neighbors");
+        let neighbors = graph.neighbors(fake_node, None).expect("neighbors");
         assert!(neighbors.is_empty());
     }
 
@@ -215,12 +233,18 @@ mod t2_single_hop {
         let d = graph.insert_node(EntityId(4)).expect("insert");
 
         // Insert in random order
-        graph.insert_edge(a, d, EdgeWeight::new(1)).expect("edge");
-        graph.insert_edge(a, b, EdgeWeight::new(1)).expect("edge");
-        graph.insert_edge(a, c, EdgeWeight::new(1)).expect("edge");
+        graph
+            .insert_edge(a, d, None, EdgeWeight::new(1))
+            .expect("edge");
+        graph
+            .insert_edge(a, b, None, EdgeWeight::new(1))
+            .expect("edge");
+        graph
+            .insert_edge(a, c, None, EdgeWeight::new(1))
+            .expect("edge");
 
         let neighbors: Vec<_> = graph
-            .neighbors(a)
+            .neighbors(a, None)
             .expect("neighbors")
             .into_iter()
             .map(|(n, _)| n)
@@ -265,11 +289,15 @@ mod t3_multi_hop {
         let b = graph.insert_node(EntityId(2)).expect("insert");
         let c = graph.insert_node(EntityId(3)).expect("insert");
 
-        graph.insert_edge(a, b, EdgeWeight::new(1)).expect("edge");
-        graph.insert_edge(b, c, EdgeWeight::new(1)).expect("edge");
+        graph
+            .insert_edge(a, b, None, EdgeWeight::new(1))
+            .expect("edge");
+        graph
+            .insert_edge(b, c, None, EdgeWeight::new(1))
+            .expect("edge");
 
         let artifact = graph
-            .traverse(a, 3)
+            .traverse(a, 3, None)
             .expect("traverse")
             .expect("should traverse");
 
@@ -287,8 +315,12 @@ mod t3_multi_hop {
         let b = graph.insert_node(EntityId(2)).expect("insert");
         let c = graph.insert_node(EntityId(3)).expect("insert");
 
-        graph.insert_edge(a, b, EdgeWeight::new(1)).expect("edge");
-        graph.insert_edge(b, c, EdgeWeight::new(1)).expect("edge");
+        graph
+            .insert_edge(a, b, None, EdgeWeight::new(1))
+            .expect("edge");
+        graph
+            .insert_edge(b, c, None, EdgeWeight::new(1))
+            .expect("edge");
 
         let artifact = graph.traverse_dfs(a, 3).expect("should traverse");
 
@@ -307,13 +339,13 @@ mod t3_multi_hop {
 
         for i in 0..9 {
             graph
-                .insert_edge(nodes[i], nodes[i + 1], EdgeWeight::new(1))
+                .insert_edge(nodes[i], nodes[i + 1], None, EdgeWeight::new(1))
                 .expect("edge");
         }
 
         // Depth 2 should only reach first 3 nodes
         let artifact = graph
-            .traverse(nodes[0], 2)
+            .traverse(nodes[0], 2, None)
             .expect("traverse")
             .expect("artifact");
         assert!(artifact.path.len() <= 3);
@@ -326,9 +358,14 @@ mod t3_multi_hop {
         let a = graph.insert_node(EntityId(1)).expect("insert");
         let b = graph.insert_node(EntityId(2)).expect("insert");
 
-        graph.insert_edge(a, b, EdgeWeight::new(5)).expect("edge");
+        graph
+            .insert_edge(a, b, None, EdgeWeight::new(5))
+            .expect("edge");
 
-        let artifact = graph.traverse(a, 2).expect("traverse").expect("artifact");
+        let artifact = graph
+            .traverse(a, 2, None)
+            .expect("traverse")
+            .expect("artifact");
 
         assert!(!artifact.path.is_empty());
         assert!(artifact.subgraph.is_some());
@@ -346,12 +383,18 @@ mod t3_multi_hop {
         let c = graph.insert_node(EntityId(3)).expect("insert");
 
         // Create cycle: a -> b -> c -> a
-        graph.insert_edge(a, b, EdgeWeight::new(1)).expect("edge");
-        graph.insert_edge(b, c, EdgeWeight::new(1)).expect("edge");
-        graph.insert_edge(c, a, EdgeWeight::new(1)).expect("edge");
+        graph
+            .insert_edge(a, b, None, EdgeWeight::new(1))
+            .expect("edge");
+        graph
+            .insert_edge(b, c, None, EdgeWeight::new(1))
+            .expect("edge");
+        graph
+            .insert_edge(c, a, None, EdgeWeight::new(1))
+            .expect("edge");
 
         // This should complete without hanging
-        let artifact = graph.traverse(a, 10).expect("traverse");
+        let artifact = graph.traverse(a, 10, None).expect("traverse");
         assert!(artifact.is_some());
 
         // DFS should also handle cycles
@@ -367,10 +410,14 @@ mod t3_multi_hop {
         let b = graph.insert_node(EntityId(2)).expect("insert");
         let c = graph.insert_node(EntityId(3)).expect("insert");
 
-        graph.insert_edge(a, b, EdgeWeight::new(10)).expect("edge");
-        graph.insert_edge(b, c, EdgeWeight::new(10)).expect("edge");
+        graph
+            .insert_edge(a, b, None, EdgeWeight::new(10))
+            .expect("edge");
+        graph
+            .insert_edge(b, c, None, EdgeWeight::new(10))
+            .expect("edge");
 
-        let path = graph.strongest_path(a, c).expect("path");
+        let path = graph.strongest_path(a, c, None).expect("path");
         assert!(path.is_some());
         let path = path.expect("path");
         assert_eq!(path.first(), Some(&a));
@@ -385,7 +432,7 @@ mod t3_multi_hop {
         let b = graph.insert_node(EntityId(2)).expect("insert");
         // No edge between a and b
 
-        let path = graph.strongest_path(a, b).expect("path");
+        let path = graph.strongest_path(a, b, None).expect("path");
         assert!(path.is_none());
     }
 }